@@ -130,6 +130,7 @@ where
             shallow: &Default::default(),
             tags: Default::default(),
             reject_shallow_remote: true,
+            filter: None,
         },
     )
     .await?;
@@ -185,6 +185,12 @@ pub fn file(
         &repo.objects,
     )?;
 
+    let function_names = resource_cache
+        .resource(gix::diff::blob::ResourceKind::OldOrSource)
+        .and_then(|resource| resource.driver_index)
+        .map(|idx| resource_cache.filter.drivers()[idx].function_name_matcher())
+        .transpose()?
+        .unwrap_or_default();
     let outcome = resource_cache.prepare_diff()?;
 
     use gix::diff::blob::platform::prepare_diff::Operation;
@@ -209,7 +215,8 @@ pub fn file(
         String::new(),
         NewlineSeparator::AfterHeaderAndLine("\n"),
         ContextSize::symmetrical(3),
-    );
+    )
+    .with_function_names(&function_names);
 
     let unified_diff = gix::diff::blob::diff(algorithm, &interner, unified_diff)?;
 
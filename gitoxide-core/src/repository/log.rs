@@ -1,5 +1,4 @@
-use anyhow::bail;
-use gix::bstr::{BString, ByteSlice};
+use gix::bstr::{BStr, BString, ByteSlice};
 
 pub fn log(mut repo: gix::Repository, out: &mut dyn std::io::Write, path: Option<BString>) -> anyhow::Result<()> {
     repo.object_cache_size_if_unset(repo.compute_object_cache_size_for_tree_diffs(&**repo.index_or_empty()?));
@@ -25,8 +24,46 @@ fn log_all(repo: gix::Repository, out: &mut dyn std::io::Write) -> Result<(), an
     Ok(())
 }
 
-fn log_file(_repo: gix::Repository, _out: &mut dyn std::io::Write, _path: BString) -> anyhow::Result<()> {
-    bail!("File-based lookup isn't yet implemented in a way that is competitively fast");
+/// Show all commits whose version of `path` differs from the one in all of its parents, using changed-path
+/// Bloom filters of the commit-graph to skip commits that definitely didn't change `path`.
+fn log_file(repo: gix::Repository, out: &mut dyn std::io::Write, path: BString) -> anyhow::Result<()> {
+    let head = repo.head()?.peel_to_commit_in_place()?;
+    let cache = repo.commit_graph_if_enabled()?;
+    let topo = gix::traverse::commit::topo::Builder::from_iters(&repo.objects, [head.id], None::<Vec<gix::ObjectId>>)
+        .with_commit_graph(repo.commit_graph_if_enabled()?)
+        .build()?;
+
+    let path_keys = gix::commitgraph::bloom::PathKeys::new(path.as_ref());
+    let mut buf = Vec::new();
+    for info in topo {
+        let info = info?;
+        let commit = gix::traverse::commit::find(cache.as_ref(), &repo.objects, &info.id, &mut buf)?;
+        if !commit.may_have_changed_path(&path_keys) {
+            continue;
+        }
+
+        let entry = entry_id_at(&repo, info.id, path.as_ref())?;
+        let mut changed = entry.is_some() || !info.parent_ids.is_empty();
+        for parent_id in &info.parent_ids {
+            if entry_id_at(&repo, *parent_id, path.as_ref())? == entry {
+                changed = false;
+                break;
+            }
+        }
+        if changed {
+            write_info(&repo, &mut *out, &info)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn entry_id_at(repo: &gix::Repository, commit_id: gix::ObjectId, path: &BStr) -> anyhow::Result<Option<gix::ObjectId>> {
+    Ok(repo
+        .find_commit(commit_id)?
+        .tree()?
+        .lookup_entry(path.split_str("/"))?
+        .map(|entry| entry.object_id()))
 }
 
 fn write_info(
@@ -123,6 +123,7 @@ pub fn show(
                     gix::diff::index::Change::Addition { .. } => "A",
                     gix::diff::index::Change::Deletion { .. } => "D",
                     gix::diff::index::Change::Modification { .. } => "M",
+                    gix::diff::index::Change::Unmerged { .. } => "U",
                     gix::diff::index::Change::Rewrite {
                         ref source_location, ..
                     } => {
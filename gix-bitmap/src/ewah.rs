@@ -49,8 +49,190 @@ pub fn decode(data: &[u8]) -> Result<(Vec, &[u8]), decode::Error> {
     ))
 }
 
+///
+pub mod write {
+    use super::{Vec, RLW_LARGEST_LITERAL_COUNT, RLW_LARGEST_RUNNING_COUNT, RLW_RUNNING_BITS};
+
+    impl Default for Vec {
+        fn default() -> Self {
+            Vec::new()
+        }
+    }
+
+    impl Vec {
+        /// Create a new bitmap without any bits.
+        pub fn new() -> Self {
+            Vec {
+                num_bits: 0,
+                bits: vec![0],
+                rlw: 0,
+            }
+        }
+
+        /// Create a new bitmap with the bits at each of the ascending `indices` set.
+        ///
+        /// # Panics
+        ///
+        /// If `indices` aren't strictly ascending.
+        pub fn from_set_bits(indices: impl IntoIterator<Item = usize>) -> Self {
+            let mut out = Vec::new();
+            for index in indices {
+                out.set(index);
+            }
+            out
+        }
+
+        /// Set the bit at `index`, which must be larger than the index of any previously set bit, and grow
+        /// the bitmap to contain it. This is the only way to add bits, just like in `git`.
+        ///
+        /// # Panics
+        ///
+        /// If `index` isn't larger than the index of the last bit.
+        pub fn set(&mut self, index: usize) {
+            assert!(
+                index >= self.num_bits(),
+                "BUG: bits must be set in ascending order, but {index} is not larger than {}",
+                self.num_bits
+            );
+            let num_words = |num_bits: usize| (num_bits + 63) / 64;
+            let distance = num_words(index + 1) - num_words(self.num_bits());
+            self.num_bits = u32::try_from(index + 1).expect("no more than 4 billion bits");
+            let bit = 1u64 << (index % 64);
+
+            if distance > 0 {
+                if distance > 1 {
+                    self.add_empty_words(false, distance as u64 - 1);
+                }
+                self.add_literal(bit);
+                return;
+            }
+            if rlw_literal_words(self.rlw_word()) == 0 {
+                let running_len = rlw_running_len(self.rlw_word());
+                self.set_rlw_running_len(running_len - 1);
+                self.add_literal(bit);
+                return;
+            }
+            let last = self.bits.last_mut().expect("literal words exist");
+            *last |= bit;
+            if *last == u64::MAX {
+                self.bits.pop();
+                let literal_words = rlw_literal_words(self.rlw_word());
+                self.set_rlw_literal_words(literal_words - 1);
+                self.add_empty_word(true);
+            }
+        }
+
+        /// Serialize this bitmap to `out` in the format understood by [`decode()`](super::decode()).
+        pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+            out.write_all(&self.num_bits.to_be_bytes())?;
+            let num_words = u32::try_from(self.bits.len()).expect("no more than 4 billion words");
+            out.write_all(&num_words.to_be_bytes())?;
+            for word in &self.bits {
+                out.write_all(&word.to_be_bytes())?;
+            }
+            let rlw = u32::try_from(self.rlw).expect("rlw is a word index");
+            out.write_all(&rlw.to_be_bytes())
+        }
+
+        fn rlw_word(&self) -> u64 {
+            self.bits[self.rlw as usize]
+        }
+
+        fn rlw_word_mut(&mut self) -> &mut u64 {
+            &mut self.bits[self.rlw as usize]
+        }
+
+        fn set_rlw_run_bit(&mut self, run_bit: bool) {
+            let word = self.rlw_word_mut();
+            *word = (*word & !1) | u64::from(run_bit);
+        }
+
+        fn set_rlw_running_len(&mut self, len: u64) {
+            let word = self.rlw_word_mut();
+            *word = (*word & !(RLW_LARGEST_RUNNING_COUNT << 1)) | (len << 1);
+        }
+
+        fn set_rlw_literal_words(&mut self, num_words: u64) {
+            let word = self.rlw_word_mut();
+            *word = (*word & ((1 << (RLW_RUNNING_BITS + 1)) - 1)) | (num_words << (RLW_RUNNING_BITS + 1));
+        }
+
+        fn push_rlw(&mut self) {
+            self.bits.push(0);
+            self.rlw = (self.bits.len() - 1) as u64;
+        }
+
+        fn add_literal(&mut self, word: u64) {
+            let literal_words = rlw_literal_words(self.rlw_word());
+            if literal_words >= RLW_LARGEST_LITERAL_COUNT {
+                self.push_rlw();
+                self.set_rlw_literal_words(1);
+            } else {
+                self.set_rlw_literal_words(literal_words + 1);
+            }
+            self.bits.push(word);
+        }
+
+        fn add_empty_word(&mut self, run_bit: bool) {
+            let rlw = self.rlw_word();
+            let no_literal = rlw_literal_words(rlw) == 0;
+            let running_len = rlw_running_len(rlw);
+            if no_literal && running_len == 0 {
+                self.set_rlw_run_bit(run_bit);
+            }
+            if no_literal && rlw_run_bit(self.rlw_word()) == run_bit && running_len < RLW_LARGEST_RUNNING_COUNT {
+                self.set_rlw_running_len(running_len + 1);
+            } else {
+                self.push_rlw();
+                self.set_rlw_run_bit(run_bit);
+                self.set_rlw_running_len(1);
+            }
+        }
+
+        fn add_empty_words(&mut self, run_bit: bool, mut num_words: u64) {
+            let rlw = self.rlw_word();
+            if rlw_run_bit(rlw) != run_bit && rlw_running_len(rlw) + rlw_literal_words(rlw) == 0 {
+                self.set_rlw_run_bit(run_bit);
+            } else if rlw_literal_words(rlw) != 0 || rlw_run_bit(rlw) != run_bit {
+                self.push_rlw();
+                self.set_rlw_run_bit(run_bit);
+            }
+            let running_len = rlw_running_len(self.rlw_word());
+            let can_add = num_words.min(RLW_LARGEST_RUNNING_COUNT - running_len);
+            self.set_rlw_running_len(running_len + can_add);
+            num_words -= can_add;
+            while num_words >= RLW_LARGEST_RUNNING_COUNT {
+                self.push_rlw();
+                self.set_rlw_run_bit(run_bit);
+                self.set_rlw_running_len(RLW_LARGEST_RUNNING_COUNT);
+                num_words -= RLW_LARGEST_RUNNING_COUNT;
+            }
+            if num_words > 0 {
+                self.push_rlw();
+                self.set_rlw_run_bit(run_bit);
+                self.set_rlw_running_len(num_words);
+            }
+        }
+    }
+
+    #[inline]
+    fn rlw_run_bit(w: u64) -> bool {
+        w & 1 == 1
+    }
+
+    #[inline]
+    fn rlw_running_len(w: u64) -> u64 {
+        (w >> 1) & RLW_LARGEST_RUNNING_COUNT
+    }
+
+    #[inline]
+    fn rlw_literal_words(w: u64) -> u64 {
+        w >> (1 + RLW_RUNNING_BITS)
+    }
+}
+
 mod access {
-    use super::Vec;
+    use super::{Vec, RLW_LARGEST_RUNNING_COUNT, RLW_RUNNING_BITS};
 
     impl Vec {
         /// Call `f(index)` for each bit that is true, given the index of the bit that identifies it uniquely within the bit array.
@@ -111,17 +293,49 @@ mod access {
     fn rlw_runbit_is_set(w: &u64) -> bool {
         w & 1 == 1
     }
-
-    const RLW_RUNNING_BITS: u64 = 4 * 8;
-    const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
 }
 
+const RLW_RUNNING_BITS: u64 = 4 * 8;
+const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+const RLW_LARGEST_LITERAL_COUNT: u64 = (1 << (64 - RLW_RUNNING_BITS - 1)) - 1;
+
 /// A growable collection of u64 that are seen as stream of individual bits.
-#[allow(dead_code)]
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Vec {
     num_bits: u32,
     bits: std::vec::Vec<u64>,
     /// RLW is an offset into the `bits` buffer, so `1` translates into &bits\[1] essentially.
     rlw: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, Vec};
+
+    fn roundtrip(indices: &[usize]) {
+        let bitmap = Vec::from_set_bits(indices.iter().copied());
+        let mut buf = std::vec::Vec::new();
+        bitmap.write_to(&mut buf).unwrap();
+        let (decoded, rest) = decode(&buf).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(decoded, bitmap);
+        assert_eq!(decoded.num_bits(), indices.last().map_or(0, |i| i + 1));
+
+        let mut actual = std::vec::Vec::new();
+        decoded.for_each_set_bit(|index| {
+            actual.push(index);
+            Some(())
+        });
+        assert_eq!(actual, indices);
+    }
+
+    #[test]
+    fn set_bits_roundtrip() {
+        roundtrip(&[]);
+        roundtrip(&[0]);
+        roundtrip(&[1, 5, 63, 64, 200]);
+        roundtrip(&(0..64).collect::<std::vec::Vec<_>>());
+        roundtrip(&(3..500).chain([1000, 100_000]).collect::<std::vec::Vec<_>>());
+        roundtrip(&(0..1000).filter(|i| i % 3 == 0).collect::<std::vec::Vec<_>>());
+    }
+}
@@ -114,6 +114,7 @@ pub fn file(
     let mut out = Vec::new();
     let mut diff_state = gix_diff::tree::State::default();
     let mut previous_entry: Option<(ObjectId, ObjectId)> = None;
+    let path_keys = gix_commitgraph::bloom::PathKeys::new(file_path);
     'outer: while let Some(suspect) = queue.pop_value() {
         stats.commits_traversed += 1;
         if hunks_to_blame.is_empty() {
@@ -129,6 +130,7 @@ pub fn file(
 
         let commit = find_commit(cache.as_ref(), &odb, &suspect, &mut buf)?;
         let commit_time = commit.commit_time()?;
+        let may_have_changed_file_path = commit.may_have_changed_path(&path_keys);
 
         if let Some(since) = options.since {
             if commit_time < since.seconds {
@@ -157,6 +159,20 @@ pub fn file(
             continue;
         }
 
+        if !may_have_changed_file_path {
+            // The file is the same as in the first parent, so it takes all the blame. The entry we might know
+            // is also the entry of the parent.
+            stats.commits_skipped_by_bloom_filter += 1;
+            let (parent_id, parent_commit_time) = parent_ids[0];
+            previous_entry = previous_entry
+                .take()
+                .filter(|(id, _)| *id == suspect)
+                .map(|(_, entry)| (parent_id, entry));
+            pass_blame_from_to(suspect, parent_id, &mut hunks_to_blame);
+            queue.insert(parent_commit_time, parent_id);
+            continue;
+        }
+
         let mut entry = previous_entry
             .take()
             .filter(|(id, _)| *id == suspect)
@@ -175,6 +175,9 @@ pub struct Statistics {
     /// The amount of blobs there were compared to each other to learn what changed between commits.
     /// Note that in order to diff a blob, one needs to load both versions from the database.
     pub blobs_diffed: usize,
+    /// The amount of commits whose changed-path Bloom filter showed that the blamed file wasn't changed compared to the
+    /// first parent, which made looking up the file in their trees unnecessary.
+    pub commits_skipped_by_bloom_filter: usize,
 }
 
 impl Outcome {
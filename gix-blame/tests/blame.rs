@@ -281,6 +281,55 @@ fn diff_disparity() {
     }
 }
 
+#[test]
+fn bloom_filters_of_the_commit_graph_are_used_to_skip_commits() -> gix_testtools::Result {
+    let worktree = gix_testtools::scripted_fixture_writable("make_blame_repo.sh")?;
+    let status = std::process::Command::new("git")
+        .arg("-C")
+        .arg(worktree.path())
+        .args([
+            "commit-graph",
+            "write",
+            "--no-progress",
+            "--reachable",
+            "--changed-paths",
+        ])
+        .env_remove("GIT_DIR")
+        .status()?;
+    assert!(status.success());
+
+    let mut commits_skipped = 0;
+    for case in [
+        "simple",
+        "multiline-hunks",
+        "resolved-conflict",
+        "file-changed-in-two-branches",
+    ] {
+        let Fixture {
+            odb,
+            mut resource_cache,
+            suspect,
+        } = Fixture::for_worktree_path(worktree.path().to_owned())?;
+        let outcome = gix_blame::file(
+            &odb,
+            suspect,
+            Some(gix_commitgraph::at(worktree.path().join(".git/objects/info"))?),
+            &mut resource_cache,
+            format!("{case}.txt").as_str().into(),
+            gix_blame::Options {
+                diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                range: BlameRanges::default(),
+                since: None,
+            },
+        )?;
+        let baseline = Baseline::collect(worktree.path().join(".git").join(format!("{case}.baseline")))?;
+        assert_eq!(outcome.entries, baseline, "{case}");
+        commits_skipped += outcome.statistics.commits_skipped_by_bloom_filter;
+    }
+    assert_ne!(commits_skipped, 0, "commits that don't touch the file are skipped");
+    Ok(())
+}
+
 #[test]
 fn since() {
     let Fixture {
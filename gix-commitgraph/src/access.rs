@@ -2,6 +2,12 @@ use crate::{file, file::Commit, File, Graph, Position};
 
 /// Access
 impl Graph {
+    /// Returns the settings of the changed-path Bloom filters of the top-most file that has them, or `None` if there are no
+    /// such filters.
+    pub fn bloom_filter_settings(&self) -> Option<crate::bloom::Settings> {
+        self.files.iter().rev().find_map(File::bloom_filter_settings)
+    }
+
     /// Returns the commit at the given position `pos`.
     ///
     /// # Panics
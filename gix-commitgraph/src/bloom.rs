@@ -0,0 +1,252 @@
+//! Changed-path Bloom filters as stored in the `BIDX` and `BDAT` chunks of commit-graph files.
+//!
+//! Each commit may have a filter that knows all paths that changed compared to its first parent, including all leading
+//! directories of these paths. Querying a filter can tell with certainty that a path did *not* change, which allows
+//! to skip tree-diffs in path-limited history traversals.
+use bstr::{BStr, ByteSlice};
+
+/// The default amount of hashes used per path, as used by git.
+pub const DEFAULT_NUM_HASHES: u32 = 7;
+/// The default amount of bits used per path in a filter, as used by git.
+pub const DEFAULT_BITS_PER_ENTRY: u32 = 10;
+/// The default maximum amount of changed paths for which a filter is computed, as used by git.
+///
+/// Commits with more changed paths receive a filter that matches all paths.
+pub const DEFAULT_MAX_CHANGED_PATHS: usize = 512;
+
+const SEED0: u32 = 0x293a_e76f;
+const SEED1: u32 = 0x7e64_6e2c;
+
+/// The version of the murmur3 hash used to compute [keys](Key).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum HashVersion {
+    /// The original implementation of git, which sign-extends bytes with the high bit set and thus differs from the
+    /// reference implementation for paths that aren't ASCII.
+    #[default]
+    V1 = 1,
+    /// The reference implementation of murmur3.
+    V2 = 2,
+}
+
+impl TryFrom<u32> for HashVersion {
+    type Error = u32;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Ok(match value {
+            1 => HashVersion::V1,
+            2 => HashVersion::V2,
+            unknown => return Err(unknown),
+        })
+    }
+}
+
+/// The settings that all Bloom filters within a commit-graph file were computed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Settings {
+    /// The version of the hash function.
+    pub hash_version: HashVersion,
+    /// The amount of hashes, and thus bits, to set for each path.
+    pub num_hashes: u32,
+    /// The amount of bits to reserve in a filter for each path.
+    pub bits_per_entry: u32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            hash_version: HashVersion::default(),
+            num_hashes: DEFAULT_NUM_HASHES,
+            bits_per_entry: DEFAULT_BITS_PER_ENTRY,
+        }
+    }
+}
+
+/// Compute the 32 bit murmur3 hash of `data` with the given `seed` and hash `version`.
+pub fn murmur3_seeded(mut seed: u32, data: &[u8], version: HashVersion) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    const R1: u32 = 15;
+    const R2: u32 = 13;
+    const M: u32 = 5;
+    const N: u32 = 0xe654_6b64;
+
+    let byte = |b: u8| match version {
+        HashVersion::V1 => b as i8 as u32,
+        HashVersion::V2 => u32::from(b),
+    };
+    let mut chunks = data.chunks_exact(4);
+    for chunk in chunks.by_ref() {
+        let mut k = byte(chunk[0]) | byte(chunk[1]) << 8 | byte(chunk[2]) << 16 | byte(chunk[3]) << 24;
+        k = k.wrapping_mul(C1).rotate_left(R1).wrapping_mul(C2);
+        seed ^= k;
+        seed = seed.rotate_left(R2).wrapping_mul(M).wrapping_add(N);
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0;
+        for (shift, b) in tail.iter().enumerate().rev() {
+            k ^= byte(*b) << (shift * 8);
+        }
+        k = k.wrapping_mul(C1).rotate_left(R1).wrapping_mul(C2);
+        seed ^= k;
+    }
+
+    seed ^= data.len() as u32;
+    seed ^= seed >> 16;
+    seed = seed.wrapping_mul(0x85eb_ca6b);
+    seed ^= seed >> 13;
+    seed = seed.wrapping_mul(0xc2b2_ae35);
+    seed ^= seed >> 16;
+    seed
+}
+
+/// The key of a single path, used to add it to or to look it up in a [`Filter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    hash0: u32,
+    hash1: u32,
+    version: HashVersion,
+}
+
+impl Key {
+    /// Compute the key for `path`, which is relative to the repository root and uses slashes as separators,
+    /// using the hash of the given `version`.
+    pub fn new(path: &BStr, version: HashVersion) -> Self {
+        Key {
+            hash0: murmur3_seeded(SEED0, path, version),
+            hash1: murmur3_seeded(SEED1, path, version),
+            version,
+        }
+    }
+
+    /// The version of the hash this key was computed with.
+    pub fn hash_version(&self) -> HashVersion {
+        self.version
+    }
+
+    fn bit_positions(&self, num_hashes: u32, num_bits: u64) -> impl Iterator<Item = u64> + '_ {
+        (0..num_hashes).map(move |i| u64::from(self.hash0.wrapping_add(i.wrapping_mul(self.hash1))) % num_bits)
+    }
+}
+
+/// The keys for a path and all of its leading directories, in each hash version, to find out if a [`Filter`] may contain
+/// changes to that path, similar to how git queries filters for pathspecs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathKeys {
+    v1: Vec<Key>,
+    v2: Vec<Key>,
+}
+
+impl PathKeys {
+    /// Compute the keys for `path` and each of its leading directories, so `a/b/c` leads to keys for `a/b/c`, `a/b` and `a`.
+    ///
+    /// `path` is relative to the repository root and uses slashes as separators. Trailing slashes are ignored.
+    pub fn new(path: &BStr) -> Self {
+        let path = path.trim_end_with(|c| c == '/');
+        let mut paths = vec![path.as_bstr()];
+        let mut remaining = path;
+        while let Some(pos) = remaining.rfind_byte(b'/') {
+            remaining = &remaining[..pos];
+            paths.push(remaining.as_bstr());
+        }
+        let keys = |version| {
+            paths
+                .iter()
+                .filter(|path| !path.is_empty())
+                .map(|path| Key::new(path, version))
+                .collect()
+        };
+        PathKeys {
+            v1: keys(HashVersion::V1),
+            v2: keys(HashVersion::V2),
+        }
+    }
+
+    /// Return the keys that were computed with the given hash `version`.
+    pub fn keys(&self, version: HashVersion) -> &[Key] {
+        match version {
+            HashVersion::V1 => &self.v1,
+            HashVersion::V2 => &self.v2,
+        }
+    }
+}
+
+/// A changed-path Bloom filter of a single commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Filter<'a> {
+    data: &'a [u8],
+    settings: Settings,
+}
+
+impl<'a> Filter<'a> {
+    /// Create a new instance from its raw `data`, which was computed with `settings`.
+    pub fn new(data: &'a [u8], settings: Settings) -> Self {
+        Filter { data, settings }
+    }
+
+    /// Return the raw bytes of the filter.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Return the settings the filter was computed with.
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+
+    /// Return `false` if the path of `key` definitely isn't contained in this filter, or `true` if it may be contained.
+    ///
+    /// Note that keys that were computed with a different hash version than this filter are always considered contained.
+    pub fn contains(&self, key: &Key) -> bool {
+        if key.version != self.settings.hash_version || self.data.is_empty() {
+            return true;
+        }
+        let num_bits = self.data.len() as u64 * 8;
+        key.bit_positions(self.settings.num_hashes, num_bits)
+            .all(|bit| self.data[(bit / 8) as usize] & (1 << (bit % 8)) != 0)
+    }
+
+    /// Return `false` if the path that `keys` were computed for definitely didn't change in the commit that owns this filter,
+    /// or `true` if it may have changed.
+    pub fn may_contain_path(&self, keys: &PathKeys) -> bool {
+        keys.keys(self.settings.hash_version)
+            .iter()
+            .all(|key| self.contains(key))
+    }
+}
+
+/// Compute the data of a filter for a commit with `changed_paths` compared to its first parent, or compared to the empty
+/// tree if it has no parent, using `settings`.
+///
+/// `changed_paths` should contain the paths of all changed entries, as obtained by a recursive tree-diff without rename
+/// tracking. The leading directories of each path are added automatically.
+/// If there are more than `max_changed_paths` paths including their leading directories, a filter that contains all
+/// paths is returned. [`DEFAULT_MAX_CHANGED_PATHS`] is the value git uses.
+pub fn compute_filter<'a>(
+    changed_paths: impl IntoIterator<Item = &'a BStr>,
+    settings: &Settings,
+    max_changed_paths: usize,
+) -> Vec<u8> {
+    let mut paths = std::collections::HashSet::new();
+    for path in changed_paths {
+        let mut path = path.as_bytes();
+        while !path.is_empty() {
+            if paths.insert(path) && paths.len() > max_changed_paths {
+                return vec![0xff];
+            }
+            path = &path[..path.rfind_byte(b'/').unwrap_or(0)];
+        }
+    }
+
+    let num_bytes = ((paths.len() as u64 * u64::from(settings.bits_per_entry) + 7) / 8).max(1);
+    let mut data = vec![0u8; num_bytes as usize];
+    let num_bits = num_bytes * 8;
+    for path in paths {
+        let key = Key::new(path.as_bstr(), settings.hash_version);
+        for bit in key.bit_positions(settings.num_hashes, num_bits) {
+            data[(bit / 8) as usize] |= 1 << (bit % 8);
+        }
+    }
+    data
+}
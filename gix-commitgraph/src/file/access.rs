@@ -4,7 +4,11 @@ use std::{
 };
 
 use crate::{
-    file::{self, commit::Commit, COMMIT_DATA_ENTRY_SIZE_SANS_HASH},
+    bloom,
+    file::{
+        self, commit::Commit, BLOOM_FILTER_DATA_HEADER_LEN, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        GENERATION_DATA_OVERFLOW_MASK,
+    },
     File,
 };
 
@@ -15,6 +19,40 @@ impl File {
         self.base_graph_count
     }
 
+    /// Returns the settings of the changed-path Bloom filters in this file, or `None` if this file has no such filters or
+    /// if they use an unsupported hash version.
+    pub fn bloom_filter_settings(&self) -> Option<bloom::Settings> {
+        self.bloom_filter_settings
+    }
+
+    /// Returns the changed-path Bloom filter of the commit at the given lexicographical position, or `None` if this file
+    /// has no filters or if no filter was computed for the commit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of bounds.
+    pub fn bloom_filter_at(&self, pos: file::Position) -> Option<bloom::Filter<'_>> {
+        assert!(
+            pos.0 < self.num_commits(),
+            "expected lexicographical position less than {}, got {}",
+            self.num_commits(),
+            pos.0
+        );
+        let settings = self.bloom_filter_settings?;
+        let index_offset = self.bloom_filter_index_offset?;
+        let data = &self.data[self.bloom_filter_data_range.clone()?][BLOOM_FILTER_DATA_HEADER_LEN..];
+        let end_at =
+            |pos: usize| u32::from_be_bytes(self.data[index_offset + pos * 4..][..4].try_into().unwrap()) as usize;
+        let pos = pos.0 as usize;
+        let start = if pos == 0 { 0 } else { end_at(pos - 1) };
+        let end = end_at(pos);
+        // Empty filters weren't computed, and invalid ranges are ignored like git does.
+        if start >= end || end > data.len() {
+            return None;
+        }
+        Some(bloom::Filter::new(&data[start..end], settings))
+    }
+
     /// Returns the commit data for the commit located at the given lexicographical position.
     ///
     /// `pos` must range from 0 to `self.num_commits()`.
@@ -26,6 +64,13 @@ impl File {
         Commit::new(self, pos)
     }
 
+    /// Returns `true` if corrected commit dates, i.e. generation number v2, are available for the commits in this file.
+    ///
+    /// Note that they are unavailable if the file is part of a [chain](crate::Graph) in which other files don't have them.
+    pub fn has_corrected_commit_dates(&self) -> bool {
+        self.generation_data_offset.is_some()
+    }
+
     /// The kind of hash used in this File.
     ///
     /// Note that it is always conforming to the hash used in the owning repository.
@@ -127,6 +172,22 @@ impl File {
         &self.data[start..][..entry_size]
     }
 
+    /// Returns the offset of the corrected commit date of the commit at `pos` to its committer timestamp,
+    /// as stored in the Generation Data (GDA2) and Generation Data Overflow (GDO2) chunks.
+    ///
+    /// It's `None` if there is no such data. Overflowing offsets were validated when opening the file.
+    pub(crate) fn corrected_commit_date_offset(&self, pos: file::Position) -> Option<u32> {
+        let offset = self.generation_data_offset?;
+        let value = u32::from_be_bytes(self.data[offset + pos.0 as usize * 4..][..4].try_into().unwrap());
+        if value & GENERATION_DATA_OVERFLOW_MASK == 0 {
+            return Some(value);
+        }
+        let overflow = &self.data[self.generation_data_overflow_range.clone()?];
+        let start = (value & !GENERATION_DATA_OVERFLOW_MASK) as usize * 8;
+        let offset = u64::from_be_bytes(overflow[start..][..8].try_into().unwrap());
+        Some(offset.try_into().expect("validated to fit when opening the file"))
+    }
+
     /// Returns the byte slice for this file's entire Extra Edge List (EDGE) chunk.
     pub(crate) fn extra_edges_data(&self) -> Option<&[u8]> {
         Some(&self.data[self.extra_edges_list_range.clone()?])
@@ -31,6 +31,7 @@ pub struct Commit<'a> {
     // We can parse the below fields lazily if needed.
     commit_timestamp: u64,
     generation: u32,
+    corrected_commit_date_offset: Option<u32>,
     parent1: ParentEdge,
     parent2: ParentEdge,
     root_tree_id: &'a gix_hash::oid,
@@ -44,18 +45,17 @@ fn read_u32(b: &[u8]) -> u32 {
 impl<'a> Commit<'a> {
     pub(crate) fn new(file: &'a File, pos: file::Position) -> Self {
         let bytes = file.commit_data_bytes(pos);
+        let commit_timestamp =
+            u64::from_be_bytes(bytes[file.hash_len + 8..][..8].try_into().unwrap()) & 0x0003_ffff_ffff;
         Commit {
             file,
             pos,
             root_tree_id: gix_hash::oid::from_bytes_unchecked(&bytes[..file.hash_len]),
             parent1: ParentEdge::from_raw(read_u32(&bytes[file.hash_len..][..4])),
             parent2: ParentEdge::from_raw(read_u32(&bytes[file.hash_len + 4..][..4])),
-            // TODO: Add support for corrected commit date offset overflow.
-            //      See https://github.com/git/git/commit/e8b63005c48696a26f976f5f9b0ccaf1983e439d and
-            //          https://github.com/git/git/commit/f90fca638e99a031dce8e3aca72427b2f9b4bb38 for more details and hints at a test.
             generation: read_u32(&bytes[file.hash_len + 8..][..4]) >> 2,
-            commit_timestamp: u64::from_be_bytes(bytes[file.hash_len + 8..][..8].try_into().unwrap())
-                & 0x0003_ffff_ffff,
+            corrected_commit_date_offset: file.corrected_commit_date_offset(pos),
+            commit_timestamp,
         }
     }
 
@@ -66,6 +66,13 @@ impl<'a> Commit<'a> {
         self.commit_timestamp
     }
 
+    /// Returns the changed-path Bloom filter of this commit, or `None` if there is none.
+    ///
+    /// The filter knows the paths that changed compared to the first parent of this commit.
+    pub fn bloom_filter(&self) -> Option<crate::bloom::Filter<'a>> {
+        self.file.bloom_filter_at(self.pos)
+    }
+
     /// Returns the generation number of this commit.
     ///
     /// Commits without parents have generation number 1. Commits with parents have a generation
@@ -74,6 +81,30 @@ impl<'a> Commit<'a> {
         self.generation
     }
 
+    /// Returns the corrected commit date of this commit, or `None` if the commit-graph doesn't store them.
+    ///
+    /// It's the max of the committer timestamp and the corrected commit dates of all parents + 1, which makes it a
+    /// generation number that is also close to the commit time. This is generation number v2 in git.
+    pub fn corrected_commit_date(&self) -> Option<u64> {
+        self.corrected_commit_date_offset
+            .map(|offset| self.commit_timestamp + u64::from(offset))
+    }
+
+    /// Returns the offset of the [corrected commit date](Self::corrected_commit_date()) to the
+    /// [committer timestamp](Self::committer_timestamp()), or `None` if the commit-graph doesn't store them.
+    pub fn corrected_commit_date_offset(&self) -> Option<u32> {
+        self.corrected_commit_date_offset
+    }
+
+    /// Returns the generation number that should be used when comparing commits, which is the
+    /// [corrected commit date](Self::corrected_commit_date()) if available, or the [topological level](Self::generation())
+    /// otherwise.
+    ///
+    /// As all commits of a [Graph][crate::Graph] use the same kind of generation number, they can be compared with each other.
+    pub fn effective_generation(&self) -> u64 {
+        self.corrected_commit_date().unwrap_or(u64::from(self.generation))
+    }
+
     /// Returns an iterator over the parent positions for lookup in the owning [Graph][crate::Graph].
     pub fn iter_parents(self) -> Parents<'a> {
         // I didn't find a combinator approach that a) was as strict as ParentIterator, b) supported
@@ -110,10 +141,11 @@ impl Debug for Commit<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Commit {{ id: {}, lex_pos: {}, generation: {}, root_tree_id: {}, parent1: {:?}, parent2: {:?} }}",
+            "Commit {{ id: {}, lex_pos: {}, generation: {}, corrected_commit_date: {:?}, root_tree_id: {}, parent1: {:?}, parent2: {:?} }}",
             self.id(),
             self.pos,
             self.generation(),
+            self.corrected_commit_date(),
             self.root_tree_id(),
             self.parent1,
             self.parent2,
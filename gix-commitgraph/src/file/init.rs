@@ -1,11 +1,17 @@
-use std::path::{Path, PathBuf};
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
 
 use bstr::ByteSlice;
 
 use crate::{
+    bloom,
     file::{
-        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
-        EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, GENERATION_DATA_CHUNK_ID, GENERATION_DATA_OVERFLOW_CHUNK_ID,
+        GENERATION_DATA_OVERFLOW_MASK, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
     },
     File,
 };
@@ -184,6 +190,87 @@ impl File {
 
         let extra_edges_list_range = chunks.usize_offset_by_id(EXTENDED_EDGES_LIST_CHUNK_ID).ok();
 
+        let generation_data_offset = chunks
+            .validated_usize_offset_by_id(GENERATION_DATA_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                let expected_size = oid_lookup_count as usize * 4;
+                if chunk_size != expected_size {
+                    return Err(Error::InvalidChunkSize {
+                        id: GENERATION_DATA_CHUNK_ID,
+                        msg: format!("expected chunk length {expected_size}, got {chunk_size}"),
+                    });
+                }
+                Ok(chunk_range.start)
+            })
+            .ok()
+            .transpose()?;
+        let generation_data_overflow_range = chunks
+            .validated_usize_offset_by_id(GENERATION_DATA_OVERFLOW_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                if chunk_size % 8 != 0 {
+                    return Err(Error::InvalidChunkSize {
+                        id: GENERATION_DATA_OVERFLOW_CHUNK_ID,
+                        msg: format!("chunk size {chunk_size} is not a multiple of 8"),
+                    });
+                }
+                Ok(chunk_range)
+            })
+            .ok()
+            .transpose()?;
+
+        let bloom_filter_index_offset = chunks
+            .validated_usize_offset_by_id(BLOOM_FILTER_INDEX_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                let expected_size = oid_lookup_count as usize * 4;
+                if chunk_size != expected_size {
+                    return Err(Error::InvalidChunkSize {
+                        id: BLOOM_FILTER_INDEX_CHUNK_ID,
+                        msg: format!("expected chunk length {expected_size}, got {chunk_size}"),
+                    });
+                }
+                Ok(chunk_range.start)
+            })
+            .ok()
+            .transpose()?;
+        let bloom_filter_data_range = chunks
+            .validated_usize_offset_by_id(BLOOM_FILTER_DATA_CHUNK_ID, |chunk_range| {
+                let chunk_size = chunk_range.len();
+                if chunk_size < BLOOM_FILTER_DATA_HEADER_LEN {
+                    return Err(Error::InvalidChunkSize {
+                        id: BLOOM_FILTER_DATA_CHUNK_ID,
+                        msg: format!("expected at least {BLOOM_FILTER_DATA_HEADER_LEN} bytes, got {chunk_size}"),
+                    });
+                }
+                Ok(chunk_range)
+            })
+            .ok()
+            .transpose()?;
+        // Filters of unknown hash versions are ignored, just like git does.
+        let bloom_filter_settings = bloom_filter_index_offset
+            .and(bloom_filter_data_range.as_ref())
+            .and_then(|range| {
+                let header = &data[range.start..][..BLOOM_FILTER_DATA_HEADER_LEN];
+                Some(bloom::Settings {
+                    hash_version: read_u32(&header[..4]).try_into().ok()?,
+                    num_hashes: read_u32(&header[4..8]),
+                    bits_per_entry: read_u32(&header[8..]),
+                })
+            });
+
+        // Corrected commit dates are ignored if some of them can't be resolved, so all commits in the graph
+        // fall back to topological levels instead of mixing both kinds of generation numbers.
+        let (generation_data_offset, generation_data_overflow_range) = match generation_data_offset.filter(|offset| {
+            generation_data_is_valid(
+                &data,
+                *offset,
+                oid_lookup_count,
+                generation_data_overflow_range.as_ref(),
+            )
+        }) {
+            Some(offset) => (Some(offset), generation_data_overflow_range),
+            None => (None, None),
+        };
+
         let trailer = &data[chunks.highest_offset() as usize..];
         if trailer.len() != object_hash.len_in_bytes() {
             return Err(Error::Trailer(format!(
@@ -220,9 +307,14 @@ impl File {
         Ok(File {
             base_graph_count,
             base_graphs_list_offset,
+            bloom_filter_index_offset,
+            bloom_filter_data_range,
+            bloom_filter_settings,
             commit_data_offset,
             data,
             extra_edges_list_range,
+            generation_data_offset,
+            generation_data_overflow_range,
             fan,
             oid_lookup_offset,
             path,
@@ -252,6 +344,25 @@ impl TryFrom<&Path> for File {
     }
 }
 
+/// Return `true` if all overflowing corrected commit date offsets in the generation data chunk at `offset` can be found
+/// in the `overflow` chunk and fit into 32 bits.
+fn generation_data_is_valid(data: &[u8], offset: usize, num_commits: u32, overflow: Option<&Range<usize>>) -> bool {
+    data[offset..][..num_commits as usize * 4]
+        .chunks_exact(4)
+        .map(read_u32)
+        .filter(|value| value & GENERATION_DATA_OVERFLOW_MASK != 0)
+        .all(|value| {
+            let start = (value & !GENERATION_DATA_OVERFLOW_MASK) as usize * 8;
+            overflow
+                .and_then(|range| data[range.clone()].get(start..start + 8))
+                .is_some_and(|bytes| u32::try_from(u64::from_be_bytes(bytes.try_into().unwrap())).is_ok())
+        })
+}
+
+fn read_u32(b: &[u8]) -> u32 {
+    u32::from_be_bytes(b.try_into().expect("4 bytes"))
+}
+
 // Copied from gix-odb/pack/index/init.rs
 fn read_fan(d: &[u8]) -> ([u32; FAN_LEN], usize) {
     assert!(d.len() >= FAN_LEN * 4);
@@ -81,7 +81,9 @@ impl Graph {
     }
 
     /// Create a new commit graph from a list of `files`.
-    pub fn new(files: Vec<File>) -> Result<Self, Error> {
+    ///
+    /// Note that corrected commit dates are only used if all `files` have them, just like git does.
+    pub fn new(mut files: Vec<File>) -> Result<Self, Error> {
         let num_commits: u64 = files.iter().map(|f| u64::from(f.num_commits())).sum();
         if num_commits > u64::from(MAX_COMMITS) {
             return Err(Error::TooManyCommits(num_commits));
@@ -100,6 +102,13 @@ impl Graph {
             }
         }
 
+        if files.iter().any(|f| f.generation_data_offset.is_none()) {
+            for file in &mut files {
+                file.generation_data_offset = None;
+                file.generation_data_overflow_range = None;
+            }
+        }
+
         Ok(Self { files })
     }
 }
@@ -1,4 +1,4 @@
-//! Read, verify, and traverse git commit graphs.
+//! Read, verify, traverse, and write git commit graphs.
 //!
 //! A [commit graph][Graph] is an index of commits in the git commit history.
 //! The [Graph] stores commit data in a way that accelerates lookups considerably compared to
@@ -24,9 +24,14 @@ use std::path::Path;
 pub struct File {
     base_graph_count: u8,
     base_graphs_list_offset: Option<usize>,
+    bloom_filter_index_offset: Option<usize>,
+    bloom_filter_data_range: Option<std::ops::Range<usize>>,
+    bloom_filter_settings: Option<bloom::Settings>,
     commit_data_offset: usize,
     data: memmap2::Mmap,
     extra_edges_list_range: Option<std::ops::Range<usize>>,
+    generation_data_offset: Option<usize>,
+    generation_data_overflow_range: Option<std::ops::Range<usize>>,
     fan: [u32; file::FAN_LEN],
     oid_lookup_offset: usize,
     path: std::path::PathBuf,
@@ -49,10 +54,12 @@ pub fn at(path: impl AsRef<Path>) -> Result<Graph, init::Error> {
 }
 
 mod access;
+pub mod bloom;
 pub mod file;
 ///
 pub mod init;
 pub mod verify;
+pub mod write;
 
 /// The number of generations that are considered 'infinite' commit history.
 pub const GENERATION_NUMBER_INFINITY: u32 = 0xffff_ffff;
@@ -63,6 +70,10 @@ pub const GENERATION_NUMBER_INFINITY: u32 = 0xffff_ffff;
 /// The largest distinct generation number is `GENERATION_NUMBER_MAX - 1`.
 pub const GENERATION_NUMBER_MAX: u32 = 0x3fff_ffff;
 
+/// The generation that is considered 'infinite' when comparing [effective generations](file::Commit::effective_generation()),
+/// which is useful for commits that aren't part of the commit graph.
+pub const GENERATION_NUMBER_V2_INFINITY: u64 = u64::MAX;
+
 /// The maximum number of commits that can be stored in a commit graph.
 pub const MAX_COMMITS: u32 = (1 << 30) + (1 << 29) + (1 << 28) - 1;
 
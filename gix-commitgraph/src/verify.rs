@@ -40,6 +40,12 @@ pub enum Error<E: std::error::Error + 'static> {
         expected: u32,
         id: gix_hash::ObjectId,
     },
+    #[error("Commit {id}'s corrected commit date should be at least {min_expected} but is {actual}")]
+    CorrectedCommitDate {
+        actual: u64,
+        min_expected: u64,
+        id: gix_hash::ObjectId,
+    },
     #[error(
         "Commit {id} has parent position {parent_pos} that is out of range (should be in range 0-{max_valid_pos})"
     )]
@@ -130,6 +136,7 @@ impl Graph {
             let file_stats = file
                 .traverse(|commit| {
                     let mut max_parent_generation = 0u32;
+                    let mut max_parent_corrected_commit_date = 0u64;
                     for parent_pos in commit.iter_parents() {
                         let parent_pos = parent_pos.map_err(Error::Commit)?;
                         if parent_pos >= next_file_start_pos {
@@ -141,6 +148,10 @@ impl Graph {
                         }
                         let parent = self.commit_at(parent_pos);
                         max_parent_generation = max(max_parent_generation, parent.generation());
+                        max_parent_corrected_commit_date = max(
+                            max_parent_corrected_commit_date,
+                            parent.corrected_commit_date().unwrap_or_default(),
+                        );
                     }
 
                     // If the max parent generation is GENERATION_NUMBER_MAX, then this commit's
@@ -153,6 +164,19 @@ impl Graph {
                             id: commit.id().into(),
                         });
                     }
+                    if let Some(corrected_commit_date) = commit.corrected_commit_date() {
+                        let min_expected = max(
+                            max_parent_corrected_commit_date.saturating_add(1),
+                            commit.committer_timestamp(),
+                        );
+                        if corrected_commit_date < min_expected {
+                            return Err(Error::CorrectedCommitDate {
+                                actual: corrected_commit_date,
+                                min_expected,
+                                id: commit.id().into(),
+                            });
+                        }
+                    }
 
                     processor(commit).map_err(Error::Processor)?;
 
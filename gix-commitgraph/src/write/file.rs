@@ -0,0 +1,362 @@
+use crate::{
+    file::{
+        BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        EXTENDED_EDGES_LIST_CHUNK_ID, EXTENDED_EDGES_MASK, FAN_LEN, GENERATION_DATA_CHUNK_ID,
+        GENERATION_DATA_OFFSET_MAX, GENERATION_DATA_OVERFLOW_CHUNK_ID, GENERATION_DATA_OVERFLOW_MASK, HEADER_LEN,
+        LAST_EXTENDED_EDGE_MASK, NO_PARENT, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
+    write, File, Graph, Position, GENERATION_NUMBER_MAX, MAX_COMMITS,
+};
+
+/// Writing
+impl File {
+    /// Write all `commits` as new commit-graph file into `out` according to `options`, using `base` as the graph the new file
+    /// is layered on top of.
+    ///
+    /// Commits are sorted and deduplicated, and those that are already contained in `base` are skipped.
+    /// The parents of all commits must either be part of `commits` or be contained in `base`.
+    /// Generation numbers are always written as topological levels, i.e. generation version 1, and corrected commit dates,
+    /// i.e. generation version 2, are written in addition if [enabled](write::Options::corrected_commit_dates).
+    ///
+    /// Note that if `base` is set, the written file can only be used as part of a commit-graph chain that also contains
+    /// the files of `base`, in order.
+    pub fn write_from_commits(
+        commits: impl IntoIterator<Item = write::Commit>,
+        base: Option<&Graph>,
+        out: &mut dyn std::io::Write,
+        options: write::Options,
+    ) -> Result<write::Outcome, write::Error> {
+        let write::Options {
+            object_hash,
+            corrected_commit_dates,
+            bloom_filter_settings,
+        } = options;
+        let base_files = base.map_or(&[][..], |graph| graph.files.as_slice());
+        let base_graph_count =
+            u8::try_from(base_files.len()).map_err(|_| write::Error::TooManyBaseGraphs(base_files.len()))?;
+        if let Some(base_file) = base_files.first().filter(|f| f.object_hash() != object_hash) {
+            return Err(write::Error::HashKindMismatch {
+                base: base_file.object_hash(),
+                actual: object_hash,
+            });
+        }
+        let base_num_commits = base.map_or(0, Graph::num_commits);
+        let mut commits: Vec<_> = commits
+            .into_iter()
+            .filter(|c| base.map_or(true, |graph| graph.lookup(c.id).is_none()))
+            .collect();
+        commits.sort_by_key(|c| c.id);
+        commits.dedup_by(|a, b| a.id == b.id);
+        let total_commits = commits.len() as u64 + u64::from(base_num_commits);
+        if total_commits > u64::from(MAX_COMMITS) {
+            return Err(write::Error::TooManyCommits(total_commits));
+        }
+        let num_commits = commits.len() as u32;
+
+        let parents = commits
+            .iter()
+            .map(|commit| {
+                commit
+                    .parents
+                    .iter()
+                    .map(|parent| {
+                        commits
+                            .binary_search_by(|c| c.id.cmp(parent))
+                            .ok()
+                            .map(|lex_pos| Position(base_num_commits + lex_pos as u32))
+                            .or_else(|| base.and_then(|graph| graph.lookup(parent)))
+                            .ok_or(write::Error::MissingParent {
+                                id: commit.id,
+                                parent: *parent,
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let generations = compute_generations(
+            &parents,
+            base,
+            base_num_commits,
+            |commit| u64::from(commit.generation()),
+            |_, max_parent_generation| (max_parent_generation + 1).min(u64::from(GENERATION_NUMBER_MAX)),
+        );
+        // A layer with corrected commit dates on top of layers without them wouldn't be used, so we don't write them.
+        let corrected_commit_dates = (corrected_commit_dates
+            && base.map_or(true, |graph| graph.files.iter().all(File::has_corrected_commit_dates)))
+        .then(|| {
+            compute_generations(
+                &parents,
+                base,
+                base_num_commits,
+                |commit| {
+                    commit
+                        .corrected_commit_date()
+                        .unwrap_or_else(|| commit.committer_timestamp())
+                },
+                |lex_pos, max_parent_corrected_commit_date| {
+                    commits[lex_pos]
+                        .committer_timestamp
+                        .max(max_parent_corrected_commit_date + 1)
+                },
+            )
+        });
+        let num_generation_overflows = corrected_commit_dates.as_ref().map_or(0, |dates| {
+            dates
+                .iter()
+                .zip(&commits)
+                .filter(|(date, commit)| date.saturating_sub(commit.committer_timestamp) > GENERATION_DATA_OFFSET_MAX)
+                .count()
+        });
+        let num_extra_edges: usize = parents.iter().filter(|p| p.len() > 2).map(|p| p.len() - 1).sum();
+
+        let hash_len = object_hash.len_in_bytes();
+        let mut cf = gix_chunk::file::Index::for_writing();
+        cf.plan_chunk(OID_FAN_CHUNK_ID, (FAN_LEN * 4) as u64);
+        cf.plan_chunk(OID_LOOKUP_CHUNK_ID, (commits.len() * hash_len) as u64);
+        cf.plan_chunk(
+            COMMIT_DATA_CHUNK_ID,
+            (commits.len() * (hash_len + COMMIT_DATA_ENTRY_SIZE_SANS_HASH)) as u64,
+        );
+        if corrected_commit_dates.is_some() {
+            cf.plan_chunk(GENERATION_DATA_CHUNK_ID, (commits.len() * 4) as u64);
+            if num_generation_overflows != 0 {
+                cf.plan_chunk(GENERATION_DATA_OVERFLOW_CHUNK_ID, (num_generation_overflows * 8) as u64);
+            }
+        }
+        if num_extra_edges != 0 {
+            cf.plan_chunk(EXTENDED_EDGES_LIST_CHUNK_ID, (num_extra_edges * 4) as u64);
+        }
+        if bloom_filter_settings.is_some() {
+            let num_filter_bytes: usize = commits
+                .iter()
+                .filter_map(|c| c.bloom_filter.as_ref())
+                .map(Vec::len)
+                .sum();
+            if num_filter_bytes > u32::MAX as usize {
+                return Err(write::Error::BloomFiltersTooLarge(num_filter_bytes));
+            }
+            cf.plan_chunk(BLOOM_FILTER_INDEX_CHUNK_ID, (commits.len() * 4) as u64);
+            cf.plan_chunk(
+                BLOOM_FILTER_DATA_CHUNK_ID,
+                (BLOOM_FILTER_DATA_HEADER_LEN + num_filter_bytes) as u64,
+            );
+        }
+        if base_graph_count != 0 {
+            cf.plan_chunk(BASE_GRAPHS_LIST_CHUNK_ID, (base_files.len() * hash_len) as u64);
+        }
+
+        let mut out = gix_hash::io::Write::new(out, object_hash);
+        write_header(
+            &mut out,
+            cf.num_chunks().try_into().expect("BUG: wrote more than 256 chunks"),
+            base_graph_count,
+            object_hash,
+        )
+        .map_err(gix_hash::io::Error::from)?;
+        let mut chunk_write = cf.into_write(&mut out, HEADER_LEN).map_err(gix_hash::io::Error::from)?;
+        while let Some(chunk_to_write) = chunk_write.next_chunk() {
+            write_chunk(
+                chunk_to_write,
+                &mut chunk_write,
+                Data {
+                    commits: &commits,
+                    parents: &parents,
+                    generations: &generations,
+                    corrected_commit_dates: corrected_commit_dates.as_deref(),
+                    bloom_filter_settings,
+                    base_files,
+                },
+            )
+            .map_err(gix_hash::io::Error::from)?;
+        }
+
+        let checksum = out.hash.try_finalize().map_err(gix_hash::io::Error::from)?;
+        out.inner
+            .write_all(checksum.as_slice())
+            .map_err(gix_hash::io::Error::from)?;
+        Ok(write::Outcome { checksum, num_commits })
+    }
+}
+
+/// Everything that is needed to write the chunks of a graph file, with one entry per commit in lexicographical order.
+#[derive(Clone, Copy)]
+struct Data<'a> {
+    commits: &'a [write::Commit],
+    parents: &'a [Vec<Position>],
+    generations: &'a [u64],
+    corrected_commit_dates: Option<&'a [u64]>,
+    bloom_filter_settings: Option<crate::bloom::Settings>,
+    base_files: &'a [File],
+}
+
+fn write_chunk(
+    chunk_to_write: gix_chunk::Id,
+    chunk_write: &mut dyn std::io::Write,
+    Data {
+        commits,
+        parents,
+        generations,
+        corrected_commit_dates,
+        bloom_filter_settings,
+        base_files,
+    }: Data<'_>,
+) -> std::io::Result<()> {
+    match chunk_to_write {
+        OID_FAN_CHUNK_ID => {
+            let mut fan = [0u32; FAN_LEN];
+            for commit in commits {
+                fan[usize::from(commit.id.first_byte())] += 1;
+            }
+            let mut num_commits_so_far = 0;
+            for count in fan {
+                num_commits_so_far += count;
+                chunk_write.write_all(&num_commits_so_far.to_be_bytes())?;
+            }
+        }
+        OID_LOOKUP_CHUNK_ID => {
+            for commit in commits {
+                chunk_write.write_all(commit.id.as_slice())?;
+            }
+        }
+        COMMIT_DATA_CHUNK_ID => {
+            let mut extra_edge_index = 0;
+            for ((commit, parents), generation) in commits.iter().zip(parents).zip(generations) {
+                let parent1 = parents.first().map_or(NO_PARENT, |p| p.0);
+                let parent2 = match parents.len() {
+                    0 | 1 => NO_PARENT,
+                    2 => parents[1].0,
+                    num_parents => {
+                        let edge = EXTENDED_EDGES_MASK | extra_edge_index;
+                        extra_edge_index += num_parents as u32 - 1;
+                        edge
+                    }
+                };
+                chunk_write.write_all(commit.tree.as_slice())?;
+                chunk_write.write_all(&parent1.to_be_bytes())?;
+                chunk_write.write_all(&parent2.to_be_bytes())?;
+                let generation_and_time_high =
+                    ((*generation as u32) << 2) | ((commit.committer_timestamp >> 32) & 0x3) as u32;
+                chunk_write.write_all(&generation_and_time_high.to_be_bytes())?;
+                chunk_write.write_all(&(commit.committer_timestamp as u32).to_be_bytes())?;
+            }
+        }
+        GENERATION_DATA_CHUNK_ID => {
+            let dates = corrected_commit_dates.expect("chunk is only planned with corrected commit dates");
+            let mut overflow_index = 0;
+            for (commit, date) in commits.iter().zip(dates) {
+                let offset = date - commit.committer_timestamp;
+                let value = if offset > GENERATION_DATA_OFFSET_MAX {
+                    let value = GENERATION_DATA_OVERFLOW_MASK | overflow_index;
+                    overflow_index += 1;
+                    value
+                } else {
+                    offset as u32
+                };
+                chunk_write.write_all(&value.to_be_bytes())?;
+            }
+        }
+        GENERATION_DATA_OVERFLOW_CHUNK_ID => {
+            let dates = corrected_commit_dates.expect("chunk is only planned with corrected commit dates");
+            for (commit, date) in commits.iter().zip(dates) {
+                let offset = date - commit.committer_timestamp;
+                if offset > GENERATION_DATA_OFFSET_MAX {
+                    chunk_write.write_all(&offset.to_be_bytes())?;
+                }
+            }
+        }
+        EXTENDED_EDGES_LIST_CHUNK_ID => {
+            for parents in parents.iter().filter(|p| p.len() > 2) {
+                let (last, extra_parents) = parents[1..].split_last().expect("more than two parents");
+                for parent in extra_parents {
+                    chunk_write.write_all(&parent.0.to_be_bytes())?;
+                }
+                chunk_write.write_all(&(last.0 | LAST_EXTENDED_EDGE_MASK).to_be_bytes())?;
+            }
+        }
+        BLOOM_FILTER_INDEX_CHUNK_ID => {
+            let mut end = 0;
+            for commit in commits {
+                end += commit.bloom_filter.as_ref().map_or(0, Vec::len) as u32;
+                chunk_write.write_all(&end.to_be_bytes())?;
+            }
+        }
+        BLOOM_FILTER_DATA_CHUNK_ID => {
+            let settings = bloom_filter_settings.expect("chunk is only planned with settings");
+            chunk_write.write_all(&(settings.hash_version as u32).to_be_bytes())?;
+            chunk_write.write_all(&settings.num_hashes.to_be_bytes())?;
+            chunk_write.write_all(&settings.bits_per_entry.to_be_bytes())?;
+            for filter in commits.iter().filter_map(|c| c.bloom_filter.as_ref()) {
+                chunk_write.write_all(filter)?;
+            }
+        }
+        BASE_GRAPHS_LIST_CHUNK_ID => {
+            for base_file in base_files {
+                chunk_write.write_all(base_file.checksum().as_bytes())?;
+            }
+        }
+        unknown => unreachable!("BUG: forgot to implement chunk {:?}", std::str::from_utf8(&unknown)),
+    }
+    Ok(())
+}
+
+fn write_header(
+    out: &mut dyn std::io::Write,
+    num_chunks: u8,
+    base_graph_count: u8,
+    object_hash: gix_hash::Kind,
+) -> std::io::Result<()> {
+    out.write_all(SIGNATURE)?;
+    out.write_all(&[1 /* version */, object_hash as u8, num_chunks, base_graph_count])
+}
+
+/// Compute a generation number for each commit, whose `parents` are given by their graph position in the new file,
+/// or in `base` if the position is lower than `base_num_commits`.
+///
+/// The generation of commits in `base` is obtained with `base_generation()`, and the generation of new commits is
+/// computed by `generation(lex_pos, max_parent_generation)`, which must never be 0.
+fn compute_generations(
+    parents: &[Vec<Position>],
+    base: Option<&Graph>,
+    base_num_commits: u32,
+    base_generation: impl Fn(crate::file::Commit<'_>) -> u64,
+    generation: impl Fn(usize, u64) -> u64,
+) -> Vec<u64> {
+    let mut generations = vec![0u64; parents.len()];
+    let mut stack = Vec::new();
+    for lex_pos in 0..parents.len() {
+        if generations[lex_pos] != 0 {
+            continue;
+        }
+        stack.push(lex_pos);
+        while let Some(&current) = stack.last() {
+            let mut max_parent_generation = 0;
+            let mut unknown_parent = None;
+            for parent in &parents[current] {
+                let generation = match parent.0.checked_sub(base_num_commits) {
+                    Some(parent_lex_pos) => {
+                        let parent_lex_pos = parent_lex_pos as usize;
+                        if generations[parent_lex_pos] == 0 {
+                            unknown_parent = Some(parent_lex_pos);
+                            break;
+                        }
+                        generations[parent_lex_pos]
+                    }
+                    None => base_generation(
+                        base.expect("positions lower than the amount of base commits are in the base graph")
+                            .commit_at(*parent),
+                    ),
+                };
+                max_parent_generation = max_parent_generation.max(generation);
+            }
+            match unknown_parent {
+                Some(parent_lex_pos) => stack.push(parent_lex_pos),
+                None => {
+                    generations[current] = generation(current, max_parent_generation);
+                    stack.pop();
+                }
+            }
+        }
+    }
+    generations
+}
@@ -0,0 +1,366 @@
+use std::path::PathBuf;
+
+/// The way commit-graph files are laid out in the `objects/info` directory, similar to `git commit-graph write --split`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Split {
+    /// Write the new commits into a new layer of the commit-graph chain, and merge the layers below it into it as long as
+    /// these aren't larger than [`Options::size_multiple`] times the commits in the new layer, or as long as the new layer
+    /// has more than [`Options::max_commits`].
+    ///
+    /// This is like `--split`.
+    #[default]
+    MergeLayers,
+    /// Write the new commits into a new layer of the commit-graph chain, without ever merging layers.
+    ///
+    /// This is like `--split=no-merge`.
+    NoMerge,
+    /// Merge all layers and the new commits into a single layer that replaces the existing chain.
+    ///
+    /// This is like `--split=replace`.
+    Replace,
+}
+
+/// Options for use in [`to_info_dir()`](crate::write::to_info_dir()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The kind of hash to use for checksums, which must match the hash used by commits and the existing graph files.
+    pub object_hash: gix_hash::Kind,
+    /// If `None`, write a single `commit-graph` file containing all commits of the existing graph along with the new ones,
+    /// removing the commit-graph chain, similar to `git commit-graph write`.
+    /// Otherwise, maintain a commit-graph chain in the `commit-graphs` directory according to the given strategy, similar to
+    /// `git commit-graph write --split[=<strategy>]`.
+    pub split: Option<Split>,
+    /// When merging layers, merge the top-most existing layer if it has no more than this multiple of the commits that are
+    /// about to be written, like `--size-multiple`.
+    pub size_multiple: u32,
+    /// When merging layers, keep merging them while the amount of commits to write is larger than this number,
+    /// like `--max-commits`.
+    pub max_commits: Option<u32>,
+    /// Graph files in the `commit-graphs` directory that are not part of the chain anymore and that were last modified
+    /// at or before this time are deleted, like `--expire-time`. If `None`, the current time is used.
+    pub expire_time: Option<std::time::SystemTime>,
+    /// If `true`, write corrected commit dates, i.e. generation version 2, like `commitGraph.generationVersion=2` does.
+    ///
+    /// Note that they are only written if all layers below the new one have them as well.
+    pub corrected_commit_dates: bool,
+    /// If set, write changed-path Bloom filters with these settings, like `--changed-paths`.
+    ///
+    /// New commits must bring their own filters, while the filters of commits in merged layers are retained if they were
+    /// computed with the same settings.
+    pub bloom_filter_settings: Option<crate::bloom::Settings>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            object_hash: gix_hash::Kind::default(),
+            split: None,
+            size_multiple: 2,
+            max_commits: None,
+            expire_time: None,
+            corrected_commit_dates: true,
+            bloom_filter_settings: None,
+        }
+    }
+}
+
+/// The outcome of [`to_info_dir()`](crate::write::to_info_dir()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The path to the newly written graph file, or `None` if there was nothing to write.
+    pub path: Option<PathBuf>,
+    /// The amount of commits in the newly written graph file.
+    pub num_commits: u32,
+    /// The amount of existing graph files whose commits were merged into the newly written file.
+    pub layers_merged: usize,
+    /// The amount of layers in the commit-graph chain, or 0 if a single `commit-graph` file was written.
+    pub num_layers: usize,
+    /// The amount of graph files that were deleted as they were not part of the chain anymore.
+    pub files_expired: usize,
+}
+
+/// The error returned by [`to_info_dir()`](crate::write::to_info_dir()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Open(#[from] crate::init::Error),
+    #[error(transparent)]
+    Commit(#[from] crate::file::commit::Error),
+    #[error(transparent)]
+    Write(#[from] crate::write::Error),
+    #[error("Could not acquire lock to write commit-graph files")]
+    AcquireLock(#[from] gix_lock::acquire::Error),
+    #[error("Could not commit lock to write commit-graph files")]
+    CommitLock(#[from] gix_lock::commit::Error<gix_lock::File>),
+    #[error("An IO error occurred when handling commit-graph file at '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+}
+
+pub(crate) mod function {
+    use std::{
+        io::Write,
+        path::{Path, PathBuf},
+        time::SystemTime,
+    };
+
+    use gix_tempfile::{AutoRemove, ContainingDirectory};
+
+    use super::{Error, Options, Outcome, Split};
+    use crate::{write, File, Graph, Position};
+
+    impl Options {
+        fn to_write_options(self) -> write::Options {
+            write::Options {
+                object_hash: self.object_hash,
+                corrected_commit_dates: self.corrected_commit_dates,
+                bloom_filter_settings: self.bloom_filter_settings,
+            }
+        }
+    }
+
+    /// Write `commits` into the commit-graph files in `info_dir`, i.e. `.git/objects/info`, according to `options`,
+    /// similar to what `git commit-graph write` does.
+    ///
+    /// Commits that are already contained in the existing commit-graph are skipped, and the parents of all commits
+    /// must either be part of `commits` or of the existing commit-graph.
+    /// If a commit-graph chain is maintained, layers are merged according to [`Options::split`], and graph files that are
+    /// not part of the chain anymore are deleted if they are old enough.
+    /// Otherwise, a single `commit-graph` file is written that contains all commits, and the chain is removed.
+    ///
+    /// Note that nothing is written if there are no new commits, unless the layout of the files has to change.
+    pub fn to_info_dir(
+        info_dir: &Path,
+        commits: impl IntoIterator<Item = write::Commit>,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let monolithic_path = info_dir.join("commit-graph");
+        let graphs_dir = info_dir.join("commit-graphs");
+        let chain_path = graphs_dir.join("commit-graph-chain");
+        if options.split.is_some() {
+            std::fs::create_dir_all(&graphs_dir).map_err(|err| Error::Io {
+                path: graphs_dir.clone(),
+                source: err,
+            })?;
+        }
+        let lock = gix_lock::File::acquire_to_update_resource(
+            if options.split.is_some() {
+                &chain_path
+            } else {
+                &monolithic_path
+            },
+            gix_lock::acquire::Fail::Immediately,
+            None,
+        )?;
+
+        let graph = (monolithic_path.is_file() || chain_path.is_file())
+            .then(|| Graph::from_info_dir(info_dir))
+            .transpose()?;
+        let is_monolithic = graph
+            .as_ref()
+            .and_then(|graph| graph.files.first())
+            .is_some_and(|file| file.path() == monolithic_path);
+        let mut commits: Vec<_> = commits
+            .into_iter()
+            .filter(|c| graph.as_ref().map_or(true, |graph| graph.lookup(c.id).is_none()))
+            .collect();
+        commits.sort_by_key(|c| c.id);
+        commits.dedup_by(|a, b| a.id == b.id);
+
+        let num_layers_before = graph.as_ref().map_or(0, |graph| graph.files.len());
+        let has_desired_layout = match options.split {
+            None => is_monolithic || graph.is_none(),
+            Some(Split::Replace) => num_layers_before <= 1 && !is_monolithic,
+            Some(Split::MergeLayers | Split::NoMerge) => true,
+        };
+        if commits.is_empty() && has_desired_layout {
+            return Ok(Outcome {
+                path: None,
+                num_commits: 0,
+                layers_merged: 0,
+                num_layers: if options.split.is_some() { num_layers_before } else { 0 },
+                files_expired: 0,
+            });
+        }
+
+        let layers_merged = match (options.split, graph.as_ref()) {
+            (_, None) | (Some(Split::NoMerge), _) => 0,
+            (None | Some(Split::Replace), Some(_)) => num_layers_before,
+            (Some(Split::MergeLayers), Some(graph)) => {
+                let mut num_commits = commits.len() as u64;
+                let mut layers_merged = 0;
+                for file in graph.files.iter().rev() {
+                    let file_num_commits = u64::from(file.num_commits());
+                    if file_num_commits <= u64::from(options.size_multiple) * num_commits
+                        || options.max_commits.is_some_and(|max| num_commits > u64::from(max))
+                    {
+                        num_commits += file_num_commits;
+                        layers_merged += 1;
+                    } else {
+                        break;
+                    }
+                }
+                layers_merged
+            }
+        };
+        let num_layers_kept = num_layers_before - layers_merged;
+        let base = match graph {
+            Some(graph) => {
+                let num_base_commits: u32 = graph.files[..num_layers_kept].iter().map(File::num_commits).sum();
+                for pos in num_base_commits..graph.num_commits() {
+                    let commit = graph.commit_at(Position(pos));
+                    commits.push(write::Commit {
+                        id: commit.id().to_owned(),
+                        tree: commit.root_tree_id().to_owned(),
+                        parents: commit
+                            .iter_parents()
+                            .map(|parent| parent.map(|parent| graph.id_at(parent).to_owned()))
+                            .collect::<Result<_, _>>()?,
+                        committer_timestamp: commit.committer_timestamp(),
+                        bloom_filter: commit
+                            .bloom_filter()
+                            .filter(|filter| Some(filter.settings()) == options.bloom_filter_settings)
+                            .map(|filter| filter.data().to_vec()),
+                    });
+                }
+                let mut files = graph.files;
+                files.truncate(num_layers_kept);
+                (!files.is_empty()).then_some(Graph { files })
+            }
+            None => None,
+        };
+
+        if options.split.is_none() {
+            let mut out = std::io::BufWriter::new(lock);
+            let outcome = File::write_from_commits(commits, None, &mut out, options.to_write_options())?;
+            out.into_inner()
+                .map_err(|err| Error::Io {
+                    path: monolithic_path.clone(),
+                    source: err.into_error(),
+                })?
+                .commit()?;
+            remove_file_if_present(&chain_path)?;
+            let files_expired = expire_graph_files(&graphs_dir, &[], options.expire_time)?;
+            return Ok(Outcome {
+                path: Some(monolithic_path),
+                num_commits: outcome.num_commits,
+                layers_merged,
+                num_layers: 0,
+                files_expired,
+            });
+        }
+
+        let tempfile =
+            gix_tempfile::new(&graphs_dir, ContainingDirectory::Exists, AutoRemove::Tempfile).map_err(|err| {
+                Error::Io {
+                    path: graphs_dir.clone(),
+                    source: err,
+                }
+            })?;
+        let mut out = std::io::BufWriter::new(tempfile);
+        let outcome = File::write_from_commits(commits, base.as_ref(), &mut out, options.to_write_options())?;
+        let graph_path = graph_file_path(&graphs_dir, &outcome.checksum);
+        out.into_inner()
+            .map_err(std::io::IntoInnerError::into_error)
+            .and_then(|tempfile| tempfile.persist(&graph_path).map_err(|err| err.error))
+            .map_err(|err| Error::Io {
+                path: graph_path.clone(),
+                source: err,
+            })?;
+
+        let mut chain: Vec<_> = base
+            .iter()
+            .flat_map(|graph| graph.files.iter().map(|file| file.checksum().to_owned()))
+            .collect();
+        drop(base);
+        if is_monolithic {
+            if num_layers_kept == 0 {
+                remove_file_if_present(&monolithic_path)?;
+            } else {
+                let base_path = graph_file_path(&graphs_dir, &chain[0]);
+                std::fs::rename(&monolithic_path, &base_path).map_err(|err| Error::Io {
+                    path: base_path,
+                    source: err,
+                })?;
+            }
+        }
+        chain.push(outcome.checksum);
+
+        let mut out = std::io::BufWriter::new(lock);
+        for checksum in &chain {
+            writeln!(out, "{checksum}").map_err(|err| Error::Io {
+                path: chain_path.clone(),
+                source: err,
+            })?;
+        }
+        out.into_inner()
+            .map_err(|err| Error::Io {
+                path: chain_path.clone(),
+                source: err.into_error(),
+            })?
+            .commit()?;
+        let files_expired = expire_graph_files(&graphs_dir, &chain, options.expire_time)?;
+
+        Ok(Outcome {
+            path: Some(graph_path),
+            num_commits: outcome.num_commits,
+            layers_merged,
+            num_layers: chain.len(),
+            files_expired,
+        })
+    }
+
+    fn graph_file_path(graphs_dir: &Path, checksum: &gix_hash::oid) -> PathBuf {
+        graphs_dir.join(format!("graph-{checksum}.graph"))
+    }
+
+    fn remove_file_if_present(path: &Path) -> Result<(), Error> {
+        match std::fs::remove_file(path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(Error::Io {
+                path: path.to_owned(),
+                source: err,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Delete all graph files in `graphs_dir` that aren't in `chain` and that were last modified at or before `expire_time`.
+    fn expire_graph_files(
+        graphs_dir: &Path,
+        chain: &[gix_hash::ObjectId],
+        expire_time: Option<SystemTime>,
+    ) -> Result<usize, Error> {
+        let io_err = |err| Error::Io {
+            path: graphs_dir.to_owned(),
+            source: err,
+        };
+        let entries = match std::fs::read_dir(graphs_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(io_err(err)),
+        };
+        let expire_time = expire_time.unwrap_or_else(SystemTime::now);
+        let mut files_expired = 0;
+        for entry in entries {
+            let entry = entry.map_err(io_err)?;
+            let file_name = entry.file_name();
+            let Some(hex) = file_name
+                .to_str()
+                .and_then(|name| name.strip_prefix("graph-"))
+                .and_then(|name| name.strip_suffix(".graph"))
+            else {
+                continue;
+            };
+            if chain.iter().any(|checksum| checksum.to_hex().to_string() == hex) {
+                continue;
+            }
+            let modified = entry.metadata().and_then(|m| m.modified()).map_err(io_err)?;
+            if modified > expire_time {
+                continue;
+            }
+            remove_file_if_present(&entry.path())?;
+            files_expired += 1;
+        }
+        Ok(files_expired)
+    }
+}
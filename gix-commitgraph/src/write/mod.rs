@@ -0,0 +1,88 @@
+//! Write commit-graph files, or maintain them in an `objects/info` directory like `git commit-graph write` would.
+
+use crate::MAX_COMMITS;
+
+mod file;
+
+///
+pub mod info_dir;
+pub use info_dir::function::to_info_dir;
+
+/// A commit as it is provided to be written into a commit-graph file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Commit {
+    /// The id of the commit.
+    pub id: gix_hash::ObjectId,
+    /// The id of the tree the commit points to.
+    pub tree: gix_hash::ObjectId,
+    /// The ids of all parents of the commit, in order.
+    ///
+    /// All of them must be present either in the same graph file or in its base graph.
+    pub parents: Vec<gix_hash::ObjectId>,
+    /// The committer time in seconds since the unix epoch.
+    ///
+    /// Note that only the lower 34 bits of it can be stored.
+    pub committer_timestamp: u64,
+    /// The data of the changed-path Bloom filter of the commit, as computed by [`compute_filter()`](crate::bloom::compute_filter())
+    /// with the settings the graph file is written with.
+    ///
+    /// It's only written if the graph file is written with Bloom filters, and if `None`, the commit has no filter.
+    pub bloom_filter: Option<Vec<u8>>,
+}
+
+/// Options for use in [`File::write_from_commits()`](crate::File::write_from_commits()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The kind of hash to use for the trailing checksum, which must match the hash used by commits and the base graph.
+    pub object_hash: gix_hash::Kind,
+    /// If `true`, write corrected commit dates in addition to topological levels, which is generation version 2
+    /// and what git does by default.
+    pub corrected_commit_dates: bool,
+    /// If set, write changed-path Bloom filters, which must have been computed with these settings.
+    pub bloom_filter_settings: Option<crate::bloom::Settings>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            object_hash: gix_hash::Kind::default(),
+            corrected_commit_dates: true,
+            bloom_filter_settings: None,
+        }
+    }
+}
+
+/// The error returned by [`File::write_from_commits()`](crate::File::write_from_commits()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] gix_hash::io::Error),
+    #[error("Parent {parent} of commit {id} is neither part of the commits to write nor of the base graph")]
+    MissingParent {
+        id: gix_hash::ObjectId,
+        parent: gix_hash::ObjectId,
+    },
+    #[error("Commit-graph files would contain {0} commits altogether, but only {MAX_COMMITS} commits are allowed")]
+    TooManyCommits(u64),
+    #[error("The changed-path Bloom filters of all commits have {0} bytes, but at most 4GB are supported")]
+    BloomFiltersTooLarge(usize),
+    #[error("A commit-graph file can be based on at most 255 other graph files, got {0}")]
+    TooManyBaseGraphs(usize),
+    #[error("The base graph uses hash {base:?}, but {actual:?} was requested to be written")]
+    HashKindMismatch {
+        base: gix_hash::Kind,
+        actual: gix_hash::Kind,
+    },
+}
+
+/// The outcome of [`File::write_from_commits()`](crate::File::write_from_commits()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The checksum over the written file, which is also the trailer of the file.
+    ///
+    /// It's also what identifies the file within a commit-graph chain.
+    pub checksum: gix_hash::ObjectId,
+    /// The amount of commits that were written.
+    pub num_commits: u32,
+}
@@ -1,3 +1,5 @@
+use gix_commitgraph::Graph;
+
 use crate::{check_common, graph_and_expected, graph_and_expected_named};
 
 #[test]
@@ -56,17 +58,79 @@ fn single_commit_future_64bit_dates_work() {
 fn generation_numbers_overflow_is_handled_in_chained_graph() {
     let names = ["extra", "old-2", "future-2", "old-1", "future-1"];
     let (cg, mut refs) = graph_and_expected("generation_number_overflow.sh", &names);
-    for (r, expected) in names
+    for ((r, expected), expected_corrected_commit_date) in names
         .iter()
         .map(|n| refs.remove(n.to_owned()).expect("present"))
         .zip((1..=5).rev())
+        .zip((4147483646..=4147483650).rev())
     {
+        let commit = cg.commit_by_id(r.id).expect("present");
         assert_eq!(
-            cg.commit_by_id(r.id).expect("present").generation(),
+            commit.generation(),
             expected,
             "actually, this test seems to have valid generation numbers from the get-go. How to repro the actual issue?"
         );
+        assert_eq!(
+            commit.corrected_commit_date(),
+            Some(expected_corrected_commit_date),
+            "offsets that don't fit into 31 bits are read from the overflow chunk"
+        );
+        assert_eq!(commit.effective_generation(), expected_corrected_commit_date);
     }
+    cg.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))
+        .expect("corrected commit dates are valid");
+}
+
+#[test]
+fn corrected_commit_dates_with_unresolvable_overflow_fall_back_to_topological_levels() -> gix_testtools::Result {
+    let repo_dir = gix_testtools::scripted_fixture_read_only("corrected_commit_dates.sh")?;
+    let mut data = std::fs::read(repo_dir.join(".git/objects/info/commit-graph"))?;
+    let generation_data = chunk_range(&data, *b"GDA2");
+    let overflowing_entry = data[generation_data]
+        .chunks_exact(4)
+        .position(|entry| entry[0] & 0x80 != 0)
+        .expect("the fixture has an offset in the overflow chunk");
+    let pos = chunk_range(&data, *b"GDA2").start + overflowing_entry * 4;
+    data[pos..][..4].copy_from_slice(&0x8000_0010_u32.to_be_bytes());
+
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let path = tmp.path().join("commit-graph");
+    std::fs::write(&path, data)?;
+    let cg = Graph::from_file(&path)?;
+    assert!(
+        cg.iter_commits().all(|c| c.corrected_commit_date().is_none()),
+        "a single bad overflow index invalidates all corrected commit dates"
+    );
+    assert!(
+        cg.iter_commits()
+            .all(|c| c.effective_generation() == u64::from(c.generation())),
+        "so that all commits consistently use topological levels"
+    );
+    Ok(())
+}
+
+/// Return the range of the chunk with `id` in the commit-graph file `data`.
+fn chunk_range(data: &[u8], id: [u8; 4]) -> std::ops::Range<usize> {
+    let num_chunks = data[6] as usize;
+    let offset_at = |entry: usize| {
+        let start = 8 + entry * 12;
+        (
+            &data[start..][..4],
+            u64::from_be_bytes(data[start + 4..][..8].try_into().unwrap()) as usize,
+        )
+    };
+    let entry = (0..num_chunks)
+        .find(|entry| offset_at(*entry).0 == id)
+        .expect("chunk present");
+    offset_at(entry).1..offset_at(entry + 1).1
+}
+
+#[test]
+fn corrected_commit_dates_are_unavailable_without_generation_data() {
+    let (cg, refs) = graph_and_expected("write.sh", &["parent1"]);
+    let commit = cg.commit_by_id(refs["parent1"].id).expect("present");
+    assert_eq!(commit.corrected_commit_date(), None);
+    assert_eq!(commit.effective_generation(), 2, "topological levels are used instead");
 }
 
 #[test]
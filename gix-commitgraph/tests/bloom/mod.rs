@@ -0,0 +1,108 @@
+use std::{path::Path, process::Command};
+
+use bstr::ByteSlice;
+use gix_commitgraph::{
+    bloom::{self, HashVersion, PathKeys},
+    Graph,
+};
+use gix_testtools::{scripted_fixture_read_only, Result};
+
+#[test]
+fn murmur3_is_the_same_as_the_one_of_git() {
+    for version in [HashVersion::V1, HashVersion::V2] {
+        assert_eq!(bloom::murmur3_seeded(0, b"", version), 0);
+        assert_eq!(bloom::murmur3_seeded(0, b"Hello world!", version), 0x627b_0c2c);
+        assert_eq!(
+            bloom::murmur3_seeded(0, b"The quick brown fox jumps over the lazy dog", version),
+            0x2e4f_f723
+        );
+    }
+    let high_bits = b"\x99\xaa\xbb\xcc\xdd\xee\xff";
+    assert_eq!(bloom::murmur3_seeded(0, high_bits, HashVersion::V2), 0xa183_ccfd);
+    assert_ne!(
+        bloom::murmur3_seeded(0, high_bits, HashVersion::V1),
+        0xa183_ccfd,
+        "the first version sign-extends bytes with the high bit set"
+    );
+}
+
+#[test]
+fn filters_of_empty_and_too_many_changes() {
+    let settings = bloom::Settings::default();
+    assert_eq!(
+        bloom::compute_filter(None, &settings, bloom::DEFAULT_MAX_CHANGED_PATHS),
+        [0]
+    );
+    assert_eq!(
+        bloom::compute_filter(Some("a/b".into()), &settings, 1),
+        [0xff],
+        "leading directories count as changed paths"
+    );
+    assert_eq!(
+        bloom::compute_filter(Some("a/b".into()), &settings, 2).len(),
+        3,
+        "10 bits per path, rounded up to the next byte"
+    );
+}
+
+fn rev_parse(repo_dir: &Path, rev: &str) -> gix_hash::ObjectId {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_dir)
+        .args(["rev-parse", rev])
+        .env_remove("GIT_DIR")
+        .output()
+        .expect("failed to execute `git rev-parse`");
+    assert!(output.status.success(), "`git rev-parse` succeeds");
+    gix_hash::ObjectId::from_hex(output.stdout.trim()).expect("valid hex")
+}
+
+#[test]
+fn filters_written_by_git() -> Result {
+    let repo_dir = scripted_fixture_read_only("bloom_filters.sh")?;
+    let graph = Graph::from_info_dir(&repo_dir.join(".git").join("objects").join("info"))?;
+    assert_eq!(graph.bloom_filter_settings(), Some(bloom::Settings::default()));
+
+    let may_have_changed = |rev: &str, path: &str| {
+        let commit = graph.commit_by_id(rev_parse(&repo_dir, rev)).expect("commit in graph");
+        let filter = commit.bloom_filter().expect("all commits have filters");
+        filter.may_contain_path(&PathKeys::new(path.into()))
+    };
+
+    assert!(may_have_changed("main", "a"));
+    assert!(!may_have_changed("main", "dir/b"));
+    assert!(!may_have_changed("main", "dir"));
+
+    assert!(may_have_changed("main~1", "many/1"));
+    assert!(
+        may_have_changed("main~1", "a"),
+        "filters with too many paths match everything"
+    );
+    assert!(!may_have_changed("main~2", "a"), "empty filters match nothing");
+    assert!(!may_have_changed("main~2", "many"));
+
+    assert!(may_have_changed("main~3", "dir/ümlaut"));
+    assert!(may_have_changed("main~3", "dir/"));
+    assert!(!may_have_changed("main~3", "a"));
+
+    assert!(
+        may_have_changed("main~4", "dir/b"),
+        "merges are compared to their first parent"
+    );
+    assert!(!may_have_changed("main~4", "dir/subdir/c"));
+    assert!(!may_have_changed("main~4", "a"));
+
+    assert!(may_have_changed("main~5", "dir/subdir/c"));
+    assert!(may_have_changed("main~5", "dir/subdir/"));
+    assert!(!may_have_changed("main~5", "dir/b"));
+    assert!(!may_have_changed("main~5", "a"));
+
+    for path in ["a", "dir/b", "dir/subdir/c"] {
+        assert!(
+            may_have_changed("main~7", path),
+            "all paths were added in the root commit"
+        );
+    }
+    assert!(!may_have_changed("main~7", "dir/ümlaut"));
+    Ok(())
+}
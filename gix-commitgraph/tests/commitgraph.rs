@@ -10,6 +10,8 @@ use gix_commitgraph::{Graph, Position as GraphPosition};
 use gix_testtools::scripted_fixture_read_only;
 
 mod access;
+mod bloom;
+mod write;
 
 pub fn check_common(cg: &Graph, expected: &HashMap<String, RefInfo, impl BuildHasher>) {
     cg.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))
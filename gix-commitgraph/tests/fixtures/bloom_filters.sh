@@ -0,0 +1,40 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config commitGraph.generationVersion 1
+git checkout -q -b main
+
+mkdir -p dir/subdir
+echo a > a
+echo b > dir/b
+echo c > dir/subdir/c
+git add . && git commit -q -m root
+
+echo a2 > a
+git commit -q -am "change a"
+
+echo c2 > dir/subdir/c
+git commit -q -am "change dir/subdir/c"
+
+git checkout -q -b side HEAD~1
+echo b2 > dir/b
+git commit -q -am "change dir/b"
+
+git checkout -q main
+git merge -q -m merge --no-ff side >/dev/null
+
+echo u > "dir/ümlaut"
+git add . && git commit -q -m "add non-ascii path"
+
+git commit -q --allow-empty -m "no changes"
+
+mkdir many
+for idx in $(seq 600); do
+  echo $idx > many/$idx
+done
+git add . && git commit -q -m "too many changes"
+
+git rm -q a && git commit -q -m "delete a"
+
+git commit-graph write --no-progress --reachable --changed-paths
@@ -0,0 +1,27 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config commitGraph.generationVersion 2
+
+function commit_at() {
+  local message=${1:?first argument is the commit message}
+  local date=${2:?second argument is the commit date}
+  GIT_COMMITTER_DATE="$date" GIT_AUTHOR_DATE="$date" git commit -q --allow-empty -m "$message"
+}
+
+git checkout -q -b main
+commit_at root "@1000000000 +0000"
+# the clock of the committer was skewed, so the corrected commit date must be larger than the commit date.
+commit_at skewed "@900000000 +0000"
+commit_at regular "@1000000100 +0000"
+
+git checkout -q -b side main~1
+commit_at future "@4147483646 +0000"
+# the offset to the corrected commit date doesn't fit into 31 bits anymore.
+commit_at epoch "@0 +0000"
+
+git checkout -q main
+GIT_COMMITTER_DATE="@1000000200 +0000" git merge -q -m merge --no-ff side
+
+git commit-graph write --no-progress --reachable
@@ -0,0 +1,27 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config commitGraph.generationVersion 1
+
+git checkout -q --orphan root
+git commit -q --allow-empty -m root
+
+for branch in parent1 parent2 parent3; do
+  git checkout -q -b $branch root
+  git commit -q --allow-empty -m $branch
+done
+
+git checkout -q -b merge parent1
+git merge -q -m merge --no-ff parent2 >/dev/null
+
+git checkout -q -b octopus merge
+git merge -q -m octopus --no-ff parent1 parent2 parent3 >/dev/null
+
+git commit-graph write --no-progress --reachable
+
+git checkout -q -b main octopus
+for idx in $(seq 5); do
+  git commit -q --allow-empty -m "commit $idx"
+done
+git repack -adq
@@ -0,0 +1,433 @@
+use std::{io::BufRead, path::Path, process::Command};
+
+use bstr::BString;
+use gix_commitgraph::{
+    bloom,
+    write::{self, info_dir::Split},
+    File, Graph,
+};
+use gix_testtools::{scripted_fixture_read_only, scripted_fixture_writable, Result};
+
+/// Return all commits reachable from `rev` in the repository at `repo_dir`, parents first.
+fn commits(repo_dir: &Path, rev: &str) -> Vec<write::Commit> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_dir)
+        .args(["log", "--reverse", "--topo-order", "--format=%H %T %ct %P", rev, "--"])
+        .env_remove("GIT_DIR")
+        .output()
+        .expect("failed to execute `git log`");
+    assert!(output.status.success(), "`git log` succeeds");
+    output
+        .stdout
+        .lines()
+        .map(|line| {
+            let line = line.expect("valid output");
+            let mut tokens = line.split_whitespace();
+            let mut next_id =
+                || gix_hash::ObjectId::from_hex(tokens.next().expect("id").as_bytes()).expect("valid hex");
+            let id = next_id();
+            let tree = next_id();
+            write::Commit {
+                id,
+                tree,
+                committer_timestamp: tokens.next().expect("time").parse().expect("valid time"),
+                parents: tokens
+                    .map(|hex| gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("valid hex"))
+                    .collect(),
+                bloom_filter: None,
+            }
+        })
+        .collect()
+}
+
+/// Return the paths that changed in `commit` compared to its first parent, or to the empty tree if it has no parent.
+fn changed_paths(repo_dir: &Path, commit: &write::Commit) -> Vec<BString> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(repo_dir).args([
+        "diff-tree",
+        "-r",
+        "-z",
+        "--no-renames",
+        "--name-only",
+        "--no-commit-id",
+        "--root",
+    ]);
+    if let Some(parent) = commit.parents.first() {
+        cmd.arg(parent.to_string());
+    }
+    let output = cmd
+        .arg(commit.id.to_string())
+        .env_remove("GIT_DIR")
+        .output()
+        .expect("failed to execute `git diff-tree`");
+    assert!(output.status.success(), "`git diff-tree` succeeds");
+    output
+        .stdout
+        .split(|b| *b == 0)
+        .filter(|path| !path.is_empty())
+        .map(Into::into)
+        .collect()
+}
+
+fn git_verify(repo_dir: &Path) {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_dir)
+        .args(["commit-graph", "verify", "--no-progress"])
+        .env_remove("GIT_DIR")
+        .output()
+        .expect("failed to execute `git commit-graph verify`");
+    assert!(
+        output.status.success(),
+        "git considers the commit-graph valid: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn info_dir(repo_dir: &Path) -> std::path::PathBuf {
+    repo_dir.join(".git").join("objects").join("info")
+}
+
+fn chain(info_dir: &Path) -> Result<Vec<String>> {
+    Ok(
+        std::fs::read_to_string(info_dir.join("commit-graphs").join("commit-graph-chain"))?
+            .lines()
+            .map(ToOwned::to_owned)
+            .collect(),
+    )
+}
+
+/// The fixtures are written with generation version 1, which we match to be able to compare the written files.
+fn options(split: impl Into<Option<Split>>) -> write::info_dir::Options {
+    write::info_dir::Options {
+        split: split.into(),
+        corrected_commit_dates: false,
+        ..Default::default()
+    }
+}
+
+fn file_options(bloom_filter_settings: Option<bloom::Settings>) -> write::Options {
+    write::Options {
+        object_hash: gix_hash::Kind::Sha1,
+        corrected_commit_dates: false,
+        bloom_filter_settings,
+    }
+}
+
+#[test]
+fn written_file_is_identical_to_the_one_written_by_git() -> Result {
+    let repo_dir = scripted_fixture_read_only("write.sh")?;
+    let expected_path = info_dir(&repo_dir).join("commit-graph");
+    let mut commits = commits(&repo_dir, "octopus");
+    commits.reverse();
+    commits.push(commits[0].clone());
+
+    let mut buf = Vec::new();
+    let outcome = File::write_from_commits(commits, None, &mut buf, file_options(None))?;
+    assert_eq!(outcome.num_commits, 6, "commits are deduplicated");
+    assert_eq!(
+        buf,
+        std::fs::read(&expected_path)?,
+        "the octopus merge needs extra edges"
+    );
+
+    let file = File::at(&expected_path)?;
+    assert_eq!(outcome.checksum, file.checksum());
+    Ok(())
+}
+
+#[test]
+fn written_file_with_bloom_filters_is_identical_to_the_one_written_by_git() -> Result {
+    let repo_dir = scripted_fixture_read_only("bloom_filters.sh")?;
+    let settings = bloom::Settings::default();
+    let commits: Vec<_> = commits(&repo_dir, "main")
+        .into_iter()
+        .map(|mut commit| {
+            let paths = changed_paths(&repo_dir, &commit);
+            commit.bloom_filter = Some(bloom::compute_filter(
+                paths.iter().map(AsRef::as_ref),
+                &settings,
+                bloom::DEFAULT_MAX_CHANGED_PATHS,
+            ));
+            commit
+        })
+        .collect();
+
+    let mut buf = Vec::new();
+    let outcome = File::write_from_commits(commits, None, &mut buf, file_options(Some(settings)))?;
+    assert_eq!(outcome.num_commits, 9);
+    assert_eq!(
+        buf,
+        std::fs::read(info_dir(&repo_dir).join("commit-graph"))?,
+        "filters and their chunks are the same"
+    );
+    Ok(())
+}
+
+#[test]
+fn written_file_with_corrected_commit_dates_is_identical_to_the_one_written_by_git() -> Result {
+    let repo_dir = scripted_fixture_read_only("corrected_commit_dates.sh")?;
+    let expected_path = info_dir(&repo_dir).join("commit-graph");
+    let commits = commits(&repo_dir, "main");
+
+    let mut buf = Vec::new();
+    let outcome = File::write_from_commits(
+        commits,
+        None,
+        &mut buf,
+        write::Options {
+            object_hash: gix_hash::Kind::Sha1,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.num_commits, 6);
+    assert_eq!(
+        buf,
+        std::fs::read(&expected_path)?,
+        "skewed commit dates are corrected, and large offsets go into the overflow chunk"
+    );
+
+    let graph = Graph::from_file(&expected_path)?;
+    graph.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+    let max_corrected_commit_date = graph.iter_commits().filter_map(|c| c.corrected_commit_date()).max();
+    assert_eq!(
+        max_corrected_commit_date,
+        Some(4147483648),
+        "the merge is one more than its parent that is one more than the commit in the future"
+    );
+    Ok(())
+}
+
+#[test]
+fn corrected_commit_dates_are_not_written_on_top_of_layers_without_them() -> Result {
+    let repo = scripted_fixture_writable("write.sh")?;
+    let info_dir = info_dir(repo.path());
+
+    let outcome = write::to_info_dir(
+        &info_dir,
+        commits(repo.path(), "main"),
+        write::info_dir::Options {
+            corrected_commit_dates: true,
+            ..options(Split::NoMerge)
+        },
+    )?;
+    let path = outcome.path.expect("a layer was written");
+    assert!(
+        !File::at(&path)?.has_corrected_commit_dates(),
+        "git wouldn't use them in a mixed chain"
+    );
+    git_verify(repo.path());
+
+    let outcome = write::to_info_dir(
+        &info_dir,
+        None,
+        write::info_dir::Options {
+            corrected_commit_dates: true,
+            ..options(Split::Replace)
+        },
+    )?;
+    assert_eq!(outcome.layers_merged, 2);
+    let graph = Graph::from_info_dir(&info_dir)?;
+    assert!(
+        graph.iter_commits().all(|c| c.corrected_commit_date().is_some()),
+        "once all layers are merged, they can be written"
+    );
+    git_verify(repo.path());
+    Ok(())
+}
+
+#[test]
+fn bloom_filters_of_merged_layers_are_retained_if_settings_match() -> Result {
+    let repo = scripted_fixture_writable("bloom_filters.sh")?;
+    let info_dir = info_dir(repo.path());
+    let expected = std::fs::read(info_dir.join("commit-graph"))?;
+    let settings = bloom::Settings::default();
+
+    let outcome = write::to_info_dir(
+        &info_dir,
+        None,
+        write::info_dir::Options {
+            bloom_filter_settings: Some(settings),
+            ..options(Split::Replace)
+        },
+    )?;
+    assert_eq!(outcome.layers_merged, 1);
+    git_verify(repo.path());
+    let outcome = write::to_info_dir(
+        &info_dir,
+        None,
+        write::info_dir::Options {
+            bloom_filter_settings: Some(settings),
+            ..options(None)
+        },
+    )?;
+    assert_eq!(outcome.layers_merged, 1);
+    assert_eq!(
+        std::fs::read(info_dir.join("commit-graph"))?,
+        expected,
+        "all filters were copied from the previous layers"
+    );
+
+    write::to_info_dir(&info_dir, None, options(Split::Replace))?;
+    let graph = Graph::from_info_dir(&info_dir)?;
+    assert_eq!(
+        graph.bloom_filter_settings(),
+        None,
+        "filters are dropped without settings"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_parents_are_an_error() -> Result {
+    let repo_dir = scripted_fixture_read_only("write.sh")?;
+    let commits = commits(&repo_dir, "main");
+    let err = File::write_from_commits(commits[1..].to_vec(), None, &mut Vec::new(), file_options(None)).unwrap_err();
+    assert!(matches!(err, write::Error::MissingParent { parent, .. } if parent == commits[0].id));
+    Ok(())
+}
+
+#[test]
+fn layers_are_written_on_top_of_existing_graph_files() -> Result {
+    let repo_dir = scripted_fixture_read_only("write.sh")?;
+    let graph = Graph::from_info_dir(&info_dir(&repo_dir))?;
+    let commits = commits(&repo_dir, "main");
+
+    let mut buf = Vec::new();
+    let outcome = File::write_from_commits(commits, Some(&graph), &mut buf, file_options(None))?;
+    assert_eq!(outcome.num_commits, 5, "commits in the base graph are skipped");
+
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = tmp.path().join(format!("graph-{}.graph", outcome.checksum));
+    std::fs::write(&path, buf)?;
+    let file = File::at(&path)?;
+    assert_eq!(file.base_graph_count(), 1);
+    assert_eq!(
+        file.iter_base_graph_ids().collect::<Vec<_>>(),
+        [File::at(info_dir(&repo_dir).join("commit-graph"))?.checksum()]
+    );
+    Ok(())
+}
+
+#[test]
+fn monolithic_graph_becomes_base_of_chain_and_chains_can_become_monolithic() -> Result {
+    let repo = scripted_fixture_writable("write.sh")?;
+    let info_dir = info_dir(repo.path());
+    let monolithic_path = info_dir.join("commit-graph");
+    let monolithic_checksum = File::at(&monolithic_path)?.checksum().to_owned();
+
+    let outcome = write::to_info_dir(&info_dir, commits(repo.path(), "main"), options(Split::NoMerge))?;
+    assert_eq!(outcome.num_commits, 5);
+    assert_eq!(outcome.layers_merged, 0);
+    assert_eq!(outcome.num_layers, 2);
+    assert!(
+        !monolithic_path.exists(),
+        "the monolithic file was moved into the chain"
+    );
+    assert_eq!(chain(&info_dir)?[0], monolithic_checksum.to_string());
+    git_verify(repo.path());
+
+    let outcome = write::to_info_dir(&info_dir, commits(repo.path(), "main"), options(Split::NoMerge))?;
+    assert_eq!(outcome.path, None, "without new commits there is nothing to do");
+    assert_eq!(outcome.num_layers, 2);
+
+    let outcome = write::to_info_dir(&info_dir, commits(repo.path(), "main"), options(None))?;
+    assert_eq!(outcome.path.as_deref(), Some(monolithic_path.as_path()));
+    assert_eq!(outcome.num_commits, 11);
+    assert_eq!(outcome.layers_merged, 2);
+    assert_eq!(outcome.num_layers, 0);
+    assert_eq!(outcome.files_expired, 2);
+    assert!(!info_dir.join("commit-graphs").join("commit-graph-chain").exists());
+    git_verify(repo.path());
+
+    let expected = std::fs::read(&monolithic_path)?;
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(repo.path())
+        .args(["commit-graph", "write", "--no-progress", "--reachable"])
+        .env_remove("GIT_DIR")
+        .status()?;
+    assert!(status.success());
+    assert_eq!(
+        std::fs::read(&monolithic_path)?,
+        expected,
+        "git writes the same file for the same commits"
+    );
+    Ok(())
+}
+
+#[test]
+fn layers_are_merged_according_to_their_size() -> Result {
+    let repo = scripted_fixture_writable("write.sh")?;
+    let info_dir = info_dir(repo.path());
+    std::fs::remove_file(info_dir.join("commit-graph"))?;
+    let commits = commits(repo.path(), "main");
+
+    let outcome = write::to_info_dir(&info_dir, commits[..6].to_vec(), options(Split::NoMerge))?;
+    assert_eq!(outcome.num_layers, 1);
+    git_verify(repo.path());
+
+    let mut opts = options(Split::MergeLayers);
+    opts.expire_time = Some(std::time::SystemTime::UNIX_EPOCH);
+    let outcome = write::to_info_dir(&info_dir, commits[6..7].to_vec(), opts)?;
+    assert_eq!(
+        outcome.layers_merged, 0,
+        "the layer below has 6 commits, more than twice as many as the 1 to be written"
+    );
+    assert_eq!(outcome.num_layers, 2);
+    git_verify(repo.path());
+
+    let outcome = write::to_info_dir(&info_dir, commits[7..].to_vec(), opts)?;
+    assert_eq!(
+        outcome.layers_merged, 2,
+        "4 new commits allow the merge of the layer with 1 commit, and then the one with 6 commits"
+    );
+    assert_eq!(outcome.num_commits, 11);
+    assert_eq!(outcome.num_layers, 1);
+    assert_eq!(outcome.files_expired, 0, "the expiry time is too far in the past");
+    assert_eq!(chain(&info_dir)?.len(), 1);
+    git_verify(repo.path());
+
+    let outcome = write::to_info_dir(&info_dir, None, options(Split::MergeLayers))?;
+    assert_eq!(outcome.path, None);
+    assert_eq!(outcome.files_expired, 0, "nothing is done if there is nothing to write");
+    Ok(())
+}
+
+#[test]
+fn max_commits_and_replace_merge_layers_regardless_of_their_size() -> Result {
+    let repo = scripted_fixture_writable("write.sh")?;
+    let info_dir = info_dir(repo.path());
+    std::fs::remove_file(info_dir.join("commit-graph"))?;
+    let commits = commits(repo.path(), "main");
+
+    write::to_info_dir(&info_dir, commits[..8].to_vec(), options(Split::NoMerge))?;
+    let outcome = write::to_info_dir(
+        &info_dir,
+        commits[8..9].to_vec(),
+        write::info_dir::Options {
+            max_commits: Some(0),
+            ..options(Split::MergeLayers)
+        },
+    )?;
+    assert_eq!(
+        outcome.layers_merged, 1,
+        "there are more commits than allowed in a layer"
+    );
+    assert_eq!(outcome.num_layers, 1);
+    assert_eq!(outcome.files_expired, 1);
+
+    write::to_info_dir(&info_dir, commits[9..].to_vec(), options(Split::NoMerge))?;
+    assert_eq!(chain(&info_dir)?.len(), 2);
+    let outcome = write::to_info_dir(&info_dir, None, options(Split::Replace))?;
+    assert_eq!(outcome.layers_merged, 2);
+    assert_eq!(outcome.num_commits, 11);
+    assert_eq!(outcome.num_layers, 1);
+    git_verify(repo.path());
+
+    let graph = Graph::from_info_dir(&info_dir)?;
+    assert_eq!(graph.num_commits(), 11);
+    graph.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use bstr::{BStr, ByteSlice};
+
+/// The error returned by [`Matcher::from_xfuncname()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not compile function-name pattern {pattern:?}")]
+    Compile {
+        pattern: bstr::BString,
+        source: Box<regex::Error>,
+    },
+}
+
+/// A single pattern of a [`Matcher`].
+#[derive(Debug, Clone)]
+struct Pattern {
+    regex: regex::bytes::Regex,
+    /// If `true`, a match of this pattern means the line is *not* a function line.
+    negate: bool,
+}
+
+/// A way to find lines that start a function (or another interesting section), like `fn main() {`.
+///
+/// It's used to find the function name in hunk headers, like `@@ -1,3 +1,3 @@ fn main() {`, and to determine
+/// how far context should reach if [function context](crate::blob::unified_diff::ContextSize::with_function_context())
+/// is enabled.
+///
+/// The [default](Matcher::default()) matches Git's behaviour without any driver, which considers each line
+/// starting with an alphabetic character, `_` or `$` a function line.
+#[derive(Debug, Clone, Default)]
+pub struct Matcher {
+    /// The patterns to try in order, or empty for the default behaviour.
+    patterns: Vec<Pattern>,
+}
+
+/// The names of all drivers Git knows out of the box, for which [`Matcher::builtin()`] returns `Some(…)`.
+pub const BUILTIN_DRIVER_NAMES: &[&str] = &[
+    "bash", "cpp", "css", "golang", "html", "java", "kotlin", "markdown", "perl", "php", "python", "ruby", "rust",
+    "tex",
+];
+
+/// Lifecycle
+impl Matcher {
+    /// The matcher that behaves like Git without any configured driver.
+    pub(crate) const DEFAULT: Matcher = Matcher { patterns: Vec::new() };
+
+    /// Create a new instance from the value of `diff.<driver>.xfuncname`, a list of extended regular expressions separated by newlines.
+    ///
+    /// Patterns prefixed with `!` are negated, so lines they match are never function lines. The first pattern that matches
+    /// decides, and if it isn't negated its first capture group is used as function name, or the whole match if there is none.
+    ///
+    /// Note that this is also the right choice for the legacy `diff.<driver>.funcname` key, even though Git treats those as
+    /// basic regular expressions.
+    pub fn from_xfuncname(patterns: &BStr) -> Result<Self, Error> {
+        Self::from_patterns(patterns, false)
+    }
+
+    /// Return the matcher of the driver named `name` that Git has built-in, like `rust` or `python`,
+    /// or `None` if there is no such driver.
+    pub fn builtin(name: &str) -> Option<Self> {
+        let (patterns, ignore_case) = match name {
+            "bash" => (
+                concat!(
+                    r"^[ \t]*((([a-zA-Z_][a-zA-Z0-9_]*[ \t]*\([ \t]*\))|(function[ \t]+[a-zA-Z_][a-zA-Z0-9_]*(([ \t]*\([ \t]*\))|([ \t]+))))",
+                    r"[ \t]*(\{|\(\(?|\[\[))"
+                ),
+                false,
+            ),
+            "cpp" => (
+                concat!(
+                    r"!^[ \t]*[A-Za-z_][A-Za-z_0-9]*:[[:space:]]*($|/[/*])",
+                    "\n",
+                    r"^((::[[:space:]]*)?[A-Za-z_].*)$"
+                ),
+                false,
+            ),
+            "css" => (concat!(r"![:;][[:space:]]*$", "\n", r"^[:\[@.#]?[_a-z0-9].*$"), true),
+            "golang" => (
+                concat!(
+                    r"^[ \t]*(func[ \t]*.*(\{[ \t]*)?)",
+                    "\n",
+                    r"^[ \t]*(type[ \t].*(struct|interface)[ \t]*(\{[ \t]*)?)"
+                ),
+                false,
+            ),
+            "html" => (r"^[ \t]*(<[Hh][1-6]([ \t].*)?>.*)$", false),
+            "java" => (
+                concat!(
+                    r"!^[ \t]*(catch|do|for|if|instanceof|new|return|switch|throw|while)",
+                    "\n",
+                    r"^[ \t]*(([a-z-]+[ \t]+)*(class|enum|interface|record)[ \t]+.*)$",
+                    "\n",
+                    r"^[ \t]*(([A-Za-z_<>&][\]\[?&<>.,A-Za-z_0-9]*[ \t]+)+[A-Za-z_][A-Za-z_0-9]*[ \t]*\([^;]*)$"
+                ),
+                false,
+            ),
+            "kotlin" => (r"^[ \t]*(([a-z]+[ \t]+)*(fun|class|interface)[ \t]+.*)$", false),
+            "markdown" => (r"^ {0,3}#{1,6}[ \t].*", false),
+            "perl" => (
+                concat!(
+                    r"^package .*",
+                    "\n",
+                    r"^sub [[:alnum:]_':]+[ \t]*(\([^)]*\)[ \t]*)?(:[^;#]*)?(\{[ \t]*)?(#.*)?$",
+                    "\n",
+                    r"^(BEGIN|END|INIT|CHECK|UNITCHECK|AUTOLOAD|DESTROY)[ \t]*(\{[ \t]*)?(#.*)?$",
+                    "\n",
+                    r"^=head[0-9] .*"
+                ),
+                false,
+            ),
+            "php" => (
+                concat!(
+                    r"^[\t ]*(((public|protected|private|static|abstract|final)[\t ]+)*function.*)$",
+                    "\n",
+                    r"^[\t ]*((((final|abstract)[\t ]+)?class|enum|interface|trait).*)$"
+                ),
+                false,
+            ),
+            "python" => (r"^[ \t]*((class|(async[ \t]+)?def)[ \t].*)$", false),
+            "ruby" => (r"^[ \t]*((class|module|def)[ \t].*)$", false),
+            "rust" => (
+                r#"^[\t ]*((pub(\([^\)]+\))?[\t ]+)?((async|const|unsafe|extern([\t ]+"[^"]+"))[\t ]+)?(struct|enum|union|mod|trait|fn|impl|macro_rules!)[< \t]+[^;]*)$"#,
+                false,
+            ),
+            "tex" => (r"^(\\((sub)*section|chapter|part)\*{0,1}\{.*)$", false),
+            _ => return None,
+        };
+        Some(Self::from_patterns(patterns.into(), ignore_case).expect("built-in patterns are valid"))
+    }
+
+    fn from_patterns(patterns: &BStr, ignore_case: bool) -> Result<Self, Error> {
+        let patterns = patterns
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let (negate, pattern) = match line.strip_prefix(b"!") {
+                    Some(pattern) => (true, pattern),
+                    None => (false, line),
+                };
+                let compile_error = |err: regex::Error| Error::Compile {
+                    pattern: pattern.into(),
+                    source: Box::new(err),
+                };
+                let pattern_str = pattern
+                    .to_str()
+                    .map_err(|_| compile_error(regex::Error::Syntax("pattern is not valid UTF-8".into())))?;
+                regex::bytes::RegexBuilder::new(pattern_str)
+                    .unicode(false)
+                    .case_insensitive(ignore_case)
+                    .build()
+                    .map(|regex| Pattern { regex, negate })
+                    .map_err(compile_error)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Matcher { patterns })
+    }
+}
+
+/// Matching
+impl Matcher {
+    /// Return the name of the function started by `line`, or `None` if it doesn't start a function.
+    ///
+    /// `line` may end in a newline which is ignored, and trailing whitespace is removed from the returned name.
+    pub fn function_name<'a>(&self, line: &'a [u8]) -> Option<&'a [u8]> {
+        let line = line
+            .strip_suffix(b"\n")
+            .map_or(line, |line| line.strip_suffix(b"\r").unwrap_or(line));
+        let name = if self.patterns.is_empty() {
+            line.first()
+                .filter(|b| b.is_ascii_alphabetic() || **b == b'_' || **b == b'$')
+                .map(|_| line)?
+        } else {
+            let (pattern, captures) = self
+                .patterns
+                .iter()
+                .find_map(|p| p.regex.captures(line).map(|c| (p, c)))?;
+            if pattern.negate {
+                return None;
+            }
+            let m = captures.get(1).or_else(|| captures.get(0)).expect("0 is always set");
+            &line[m.range()]
+        };
+        Some(name.trim_end_with(|c| c.is_ascii_whitespace()))
+    }
+
+    /// Return `true` if `line` starts a function.
+    pub fn is_function_line(&self, line: &[u8]) -> bool {
+        self.function_name(line).is_some()
+    }
+}
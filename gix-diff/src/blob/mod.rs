@@ -2,7 +2,7 @@
 //! maintained by [Pascal Kuthe](https://github.com/pascalkuthe).
 use std::{collections::HashMap, path::PathBuf};
 
-use bstr::BString;
+use bstr::{BString, ByteSlice};
 pub use imara_diff::*;
 
 ///
@@ -14,6 +14,9 @@ pub mod platform;
 pub mod unified_diff;
 pub use unified_diff::_impl::UnifiedDiff;
 
+/// Find lines that start a function, for use in hunk headers and for function context.
+pub mod funcname;
+
 /// Information about the diff performed to detect similarity.
 #[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
 pub struct DiffLineStats {
@@ -65,6 +68,28 @@ pub struct Driver {
     /// If `Some(false)`, it won't be considered binary, and the its data will not be sampled for the null-byte either.
     /// Leaving it to `None` means binary detection is automatic, and is based on the presence of the `0` byte in the first 8kB of the buffer.
     pub is_binary: Option<bool>,
+    /// The patterns to find lines that start a function, separated by newlines, as configured by `diff.<driver>.xfuncname`.
+    ///
+    /// If unset, the patterns of a built-in driver of the same name will be used, if there is one.
+    /// See [`funcname::Matcher::from_xfuncname()`] for details.
+    pub xfuncname: Option<BString>,
+}
+
+/// Access
+impl Driver {
+    /// Produce a matcher for function lines as configured by [`xfuncname`](Self::xfuncname), or fall back to the built-in
+    /// driver of the same name, or Git's default behaviour if there is none.
+    pub fn function_name_matcher(&self) -> Result<funcname::Matcher, funcname::Error> {
+        match &self.xfuncname {
+            Some(patterns) => funcname::Matcher::from_xfuncname(patterns.as_ref()),
+            None => Ok(self
+                .name
+                .to_str()
+                .ok()
+                .and_then(funcname::Matcher::builtin)
+                .unwrap_or_default()),
+        }
+    }
 }
 
 /// A conversion pipeline to take an object or path from what's stored in `git` to what can be diffed, while
@@ -10,6 +10,10 @@
 pub struct ContextSize {
     /// Defines the size of the context printed before and after each change.
     symmetrical: u32,
+    /// The amount of lines between two hunks that are allowed in addition to their context to merge them.
+    inter_hunk: u32,
+    /// If `true`, the context extends to the whole function that contains a change.
+    function: bool,
 }
 
 impl Default for ContextSize {
@@ -22,7 +26,28 @@ impl Default for ContextSize {
 impl ContextSize {
     /// Create a symmetrical context with `n` lines before and after a changed hunk.
     pub fn symmetrical(n: u32) -> Self {
-        ContextSize { symmetrical: n }
+        ContextSize {
+            symmetrical: n,
+            inter_hunk: 0,
+            function: false,
+        }
+    }
+
+    /// Merge hunks if they are up to `n` lines apart in addition to their context, similar to `--inter-hunk-context`
+    /// in git diff.
+    pub fn with_inter_hunk_context(mut self, n: u32) -> Self {
+        self.inter_hunk = n;
+        self
+    }
+
+    /// If `toggle` is `true`, show the whole function that contains a change as context, similar to `--function-context`
+    /// in git diff.
+    ///
+    /// Functions are found with the matcher passed to [`UnifiedDiff::with_function_names()`](super::UnifiedDiff::with_function_names()),
+    /// or with [the default matcher](crate::blob::funcname::Matcher::default()) if none was set.
+    pub fn with_function_context(mut self, toggle: bool) -> Self {
+        self.function = toggle;
+        self
     }
 }
 
@@ -76,6 +101,10 @@ pub(super) mod _impl {
     use intern::{InternedInput, Interner, Token};
 
     use super::{ConsumeHunk, ContextSize, NewlineSeparator};
+    use crate::blob::funcname;
+
+    /// The maximum amount of bytes of a function name to show in a hunk header, just like Git.
+    const MAX_FUNCTION_NAME_LEN: usize = 80;
 
     /// A [`Sink`] that creates a textual diff in the format typically output by git or `gnu-diff` if the `-u` option is used,
     /// and passes it in full to a consumer.
@@ -93,8 +122,14 @@ pub(super) mod _impl {
         after_hunk_start: u32,
         before_hunk_len: u32,
         after_hunk_len: u32,
-        /// Symmetrical context before and after the changed hunk.
-        ctx_size: u32,
+        /// The context before and after the changed hunk, and how to merge hunks.
+        ctx_size: ContextSize,
+        /// If set, find function names to show in hunk headers.
+        function_names: Option<&'a funcname::Matcher>,
+        /// The line in `before` from which we last searched for a function name backwards, along with the line we found.
+        last_function_line: Option<(u32, Option<u32>)>,
+        /// If `true`, lines without a trailing newline are followed by `\ No newline at end of file`.
+        missing_newline_marker: bool,
 
         buffer: Vec<u8>,
         header_buf: String,
@@ -133,13 +168,129 @@ pub(super) mod _impl {
                 before: &input.before,
                 after: &input.after,
                 pos: 0,
-                ctx_size: context_size.symmetrical,
+                ctx_size: context_size,
+                function_names: None,
+                last_function_line: None,
+                missing_newline_marker: false,
                 newline: newline_separator,
 
                 err: None,
             }
         }
 
+        /// Use `matcher` to find the function that precedes each hunk and show its name in the hunk header,
+        /// like `@@ -1,3 +1,3 @@ fn main() {`.
+        ///
+        /// It's also used to find functions if [function context](ContextSize::with_function_context()) is enabled.
+        pub fn with_function_names(mut self, matcher: &'a funcname::Matcher) -> Self {
+            self.function_names = Some(matcher);
+            self
+        }
+
+        /// If `toggle` is `true`, follow lines that don't end in a newline with `\ No newline at end of file`, just like Git does.
+        ///
+        /// This only has an effect with [`NewlineSeparator::AfterHeaderAndWhenNeeded`] as only then tokens are expected to
+        /// include their newline, and it's required to produce patches that can be applied without losing this information.
+        pub fn with_missing_newline_marker(mut self, toggle: bool) -> Self {
+            self.missing_newline_marker = toggle;
+            self
+        }
+
+        fn function_matcher(&self) -> &'a funcname::Matcher {
+            static DEFAULT: funcname::Matcher = funcname::Matcher::DEFAULT;
+            self.function_names.unwrap_or(&DEFAULT)
+        }
+
+        fn is_function_line(&self, tokens: &[Token], idx: u32) -> bool {
+            self.function_matcher()
+                .is_function_line(self.interner[tokens[idx as usize]].as_ref())
+        }
+
+        fn is_empty_line(&self, idx: u32) -> bool {
+            self.interner[self.before[idx as usize]]
+                .as_ref()
+                .iter()
+                .all(u8::is_ascii_whitespace)
+        }
+
+        /// Search `lines` in `before` for the first function line, in the order they are provided.
+        fn find_function_line(&self, mut lines: impl Iterator<Item = u32>) -> Option<u32> {
+            lines.find(|idx| self.is_function_line(self.before, *idx))
+        }
+
+        /// Return the first line of the context in `before` for a change at `before` and `after`.
+        fn context_start(&self, before: &Range<u32>, after: &Range<u32>) -> u32 {
+            let start = before.start.saturating_sub(self.ctx_size.symmetrical);
+            if !self.ctx_size.function {
+                return start;
+            }
+            let len = self.before.len() as u32;
+            let mut change_start = before.start;
+            if change_start >= len {
+                // Lines were appended - no additional context is needed if that added a whole function.
+                if (after.start..self.after.len() as u32).any(|idx| self.is_function_line(self.after, idx)) {
+                    return start;
+                }
+                change_start = len.saturating_sub(1);
+            }
+            let function_start = self.find_function_line((0..=change_start).rev()).map_or(0, |mut line| {
+                // Include comments and other non-empty lines directly preceding the function.
+                while line > 0 && !self.is_empty_line(line - 1) && !self.is_function_line(self.before, line - 1) {
+                    line -= 1;
+                }
+                line
+            });
+            start.min(function_start)
+        }
+
+        /// Return the exclusive end of the context in `before` for the current hunk, whose last change ends at `pos`.
+        fn context_end(&self) -> u32 {
+            let len = self.before.len() as u32;
+            let end = (self.pos + self.ctx_size.symmetrical).min(len);
+            if !self.ctx_size.function {
+                return end;
+            }
+            let function_end = self.find_function_line(self.pos..len).map_or(len, |mut line| {
+                while line > 0 && self.is_empty_line(line - 1) {
+                    line -= 1;
+                }
+                line
+            });
+            end.max(function_end)
+        }
+
+        /// Return `true` if a change starting at `change_start` in `before`, with its context starting at `context_start`,
+        /// should be part of the current hunk.
+        fn is_part_of_current_hunk(&self, context_start: u32, change_start: u32) -> bool {
+            let end = self.context_end();
+            if context_start <= end.saturating_add(self.ctx_size.inter_hunk) {
+                return true;
+            }
+            if !self.ctx_size.function {
+                return false;
+            }
+            // Merge changes that are in the same function as the current hunk ends in.
+            let last_line = change_start.min((self.before.len() as u32).saturating_sub(1));
+            last_line.saturating_sub(self.ctx_size.symmetrical) <= end
+                || self.find_function_line((end + 1..=last_line).rev()).is_none()
+        }
+
+        /// Find the name of the function that the hunk starting at `before_hunk_start` is in.
+        fn function_name(&mut self) -> Option<&'a [u8]> {
+            let matcher = self.function_names?;
+            let search_start = self.before_hunk_start.checked_sub(1)?;
+            // Only search the lines we didn't see yet, and otherwise use what we found previously.
+            let line = match self.last_function_line {
+                Some((prev_search_start, prev_line)) if prev_search_start <= search_start => self
+                    .find_function_line((prev_search_start + 1..=search_start).rev())
+                    .or(prev_line),
+                _ => self.find_function_line((0..=search_start).rev()),
+            };
+            self.last_function_line = Some((search_start, line));
+            let name = matcher.function_name(self.interner[self.before[line? as usize]].as_ref())?;
+            Some(&name[..name.len().min(MAX_FUNCTION_NAME_LEN)])
+        }
+
         fn print_tokens(&mut self, tokens: &[Token], prefix: char) {
             for &token in tokens {
                 self.buffer.push_char(prefix);
@@ -152,30 +303,41 @@ pub(super) mod _impl {
                     NewlineSeparator::AfterHeaderAndWhenNeeded(nl) => {
                         if !line.as_ref().ends_with_str(nl) {
                             self.buffer.push_str(nl);
+                            if self.missing_newline_marker {
+                                self.buffer.push_str("\\ No newline at end of file");
+                                self.buffer.push_str(nl);
+                            }
                         }
                     }
                 }
             }
         }
 
+        fn has_hunk(&self) -> bool {
+            self.before_hunk_len != 0 || self.after_hunk_len != 0
+        }
+
         fn flush(&mut self) -> std::io::Result<()> {
-            if self.before_hunk_len == 0 && self.after_hunk_len == 0 {
+            if !self.has_hunk() {
                 return Ok(());
             }
 
-            let end = (self.pos + self.ctx_size).min(self.before.len() as u32);
+            let end = self.context_end();
             self.update_pos(end, end);
 
             self.header_buf.clear();
 
+            let function_name = self.function_name();
             std::fmt::Write::write_fmt(
                 &mut self.header_buf,
                 format_args!(
-                    "@@ -{},{} +{},{} @@{nl}",
+                    "@@ -{},{} +{},{} @@{space}{name}{nl}",
                     self.before_hunk_start + 1,
                     self.before_hunk_len,
                     self.after_hunk_start + 1,
                     self.after_hunk_len,
+                    space = if function_name.is_some() { " " } else { "" },
+                    name = function_name.map(|name| name.to_str_lossy()).unwrap_or_default(),
                     nl = match self.newline {
                         NewlineSeparator::AfterHeaderAndLine(nl) | NewlineSeparator::AfterHeaderAndWhenNeeded(nl) => {
                             nl
@@ -218,16 +380,15 @@ pub(super) mod _impl {
             if self.err.is_some() {
                 return;
             }
-            if ((self.pos == 0) && (before.start - self.pos > self.ctx_size))
-                || (before.start - self.pos > 2 * self.ctx_size)
-            {
+            let context_start = self.context_start(&before, &after);
+            if !self.has_hunk() || !self.is_part_of_current_hunk(context_start, before.start) {
                 if let Err(err) = self.flush() {
                     self.err = Some(err);
                     return;
                 }
-                self.pos = before.start - self.ctx_size;
-                self.before_hunk_start = self.pos;
-                self.after_hunk_start = after.start - self.ctx_size;
+                self.pos = context_start;
+                self.before_hunk_start = context_start;
+                self.after_hunk_start = after.start - (before.start - context_start);
             }
             self.update_pos(before.start, before.end);
             self.before_hunk_len += before.end - before.start;
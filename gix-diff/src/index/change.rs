@@ -74,6 +74,19 @@ impl ChangeRef<'_, '_> {
                 id: Cow::Owned(id.into_owned()),
                 copy,
             },
+            ChangeRef::Unmerged {
+                location,
+                previous_index,
+                index,
+                entry_mode,
+                id,
+            } => ChangeRef::Unmerged {
+                location: Cow::Owned(location.into_owned()),
+                previous_index,
+                index,
+                entry_mode,
+                id: Cow::Owned(id.into_owned()),
+            },
         }
     }
 }
@@ -81,7 +94,8 @@ impl ChangeRef<'_, '_> {
 impl ChangeRef<'_, '_> {
     /// Return all shared fields among all variants: `(location, index, entry_mode, id)`
     ///
-    /// In case of rewrites, the fields return to the current change.
+    /// In case of rewrites, the fields return to the current change, and in case of unmerged entries, the fields
+    /// refer to the first conflicting entry in `rhs`.
     pub fn fields(&self) -> (&BStr, usize, gix_index::entry::Mode, &gix_hash::oid) {
         match self {
             ChangeRef::Addition {
@@ -111,6 +125,13 @@ impl ChangeRef<'_, '_> {
                 entry_mode,
                 id,
                 ..
+            }
+            | ChangeRef::Unmerged {
+                location,
+                index,
+                entry_mode,
+                id,
+                ..
             } => (location.as_ref(), *index, *entry_mode, id),
         }
     }
@@ -122,7 +143,7 @@ impl rewrites::tracker::Change for ChangeRef<'_, '_> {
             ChangeRef::Addition { id, .. } | ChangeRef::Deletion { id, .. } | ChangeRef::Modification { id, .. } => {
                 id.as_ref()
             }
-            ChangeRef::Rewrite { .. } => {
+            ChangeRef::Rewrite { .. } | ChangeRef::Unmerged { .. } => {
                 unreachable!("BUG")
             }
         }
@@ -140,6 +161,9 @@ impl rewrites::tracker::Change for ChangeRef<'_, '_> {
             ChangeRef::Rewrite { .. } => {
                 unreachable!("BUG: rewrites can't be determined ahead of time")
             }
+            ChangeRef::Unmerged { .. } => {
+                unreachable!("BUG: unmerged entries don't participate in rewrite tracking")
+            }
         }
     }
 
@@ -148,7 +172,8 @@ impl rewrites::tracker::Change for ChangeRef<'_, '_> {
             ChangeRef::Addition { entry_mode, .. }
             | ChangeRef::Deletion { entry_mode, .. }
             | ChangeRef::Modification { entry_mode, .. }
-            | ChangeRef::Rewrite { entry_mode, .. } => {
+            | ChangeRef::Rewrite { entry_mode, .. }
+            | ChangeRef::Unmerged { entry_mode, .. } => {
                 entry_mode
                     .to_tree_entry_mode()
                     // Default is for the impossible case - just don't let it participate in rename tracking.
@@ -162,7 +187,8 @@ impl rewrites::tracker::Change for ChangeRef<'_, '_> {
             ChangeRef::Addition { id, entry_mode, .. }
             | ChangeRef::Deletion { id, entry_mode, .. }
             | ChangeRef::Modification { id, entry_mode, .. }
-            | ChangeRef::Rewrite { id, entry_mode, .. } => {
+            | ChangeRef::Rewrite { id, entry_mode, .. }
+            | ChangeRef::Unmerged { id, entry_mode, .. } => {
                 (
                     id,
                     entry_mode
@@ -20,7 +20,7 @@ use crate::rewrites;
 /// Return the outcome of the rewrite tracker if it was enabled.
 ///
 /// Note that only `rhs` may contain unmerged entries, as `rhs` is expected to be the index read from `.git/index`.
-/// Unmerged entries are skipped entirely.
+/// Unmerged entries are emitted once per path as [`ChangeRef::Unmerged`], and entries marked as *intent-to-add* are skipped.
 ///
 /// Conceptually, `rhs` is *ours*, and `lhs` is *theirs*.
 /// The entries in `lhs` and `rhs` are both expected to be sorted like index entries are typically sorted.
@@ -101,7 +101,16 @@ where
                         Action::Cancel => return Ok(None),
                     },
                     Ordering::Equal => {
-                        if ignore_unmerged_and_intent_to_add(rhs) {
+                        if is_unmerged(rhs_entry) {
+                            let action = emit_unmerged(Some(lhs_idx), rhs, &mut cb)?;
+                            lhs_storage = lhs_iter.next();
+                            rhs_storage = next_path(rhs_path, &mut rhs_iter);
+                            match action {
+                                Action::Continue => continue,
+                                Action::Cancel => return Ok(None),
+                            }
+                        }
+                        if is_intent_to_add(rhs_entry) {
                             rhs_storage = rhs_iter.next();
                             lhs_storage = lhs_iter.next();
                             continue;
@@ -131,6 +140,12 @@ where
                         lhs_storage = lhs_iter.next();
                         rhs_storage = rhs_iter.next();
                     }
+                    Ordering::Greater if is_unmerged(rhs_entry) => match emit_unmerged(None, rhs, &mut cb)? {
+                        Action::Continue => {
+                            rhs_storage = next_path(rhs_path, &mut rhs_iter);
+                        }
+                        Action::Cancel => return Ok(None),
+                    },
                     Ordering::Greater => match emit_addition(rhs, &mut cb, tracker.as_mut())? {
                         Action::Continue => {
                             rhs_storage = rhs_iter.next();
@@ -145,6 +160,12 @@ where
                     lhs_storage = lhs_iter.next();
                 }
             },
+            (None, Some(rhs)) if is_unmerged(rhs.2) => match emit_unmerged(None, rhs, &mut cb)? {
+                Action::Cancel => return Ok(None),
+                Action::Continue => {
+                    rhs_storage = next_path(rhs.1, &mut rhs_iter);
+                }
+            },
             (None, Some(rhs)) => match emit_addition(rhs, &mut cb, tracker.as_mut())? {
                 Action::Cancel => return Ok(None),
                 Action::Continue => {
@@ -254,7 +275,7 @@ fn emit_addition<'rhs, 'lhs: 'rhs, E>(
 where
     E: Into<Box<dyn std::error::Error + Send + Sync>>,
 {
-    if ignore_unmerged_and_intent_to_add((idx, path, entry)) {
+    if is_intent_to_add(entry) {
         return Ok(Action::Continue);
     }
 
@@ -276,9 +297,36 @@ where
     cb(change).map_err(|err| Error::Callback(err.into()))
 }
 
-fn ignore_unmerged_and_intent_to_add<'rhs, 'lhs: 'rhs>(
-    (_idx, _path, entry): (usize, &'rhs BStr, &'rhs gix_index::Entry),
-) -> bool {
-    let stage = entry.stage();
-    entry.flags.contains(gix_index::entry::Flags::INTENT_TO_ADD) || stage != gix_index::entry::Stage::Unconflicted
+fn emit_unmerged<'rhs, 'lhs: 'rhs, E>(
+    previous_index: Option<usize>,
+    (idx, path, entry): (usize, &'rhs BStr, &'rhs gix_index::Entry),
+    mut cb: impl FnMut(ChangeRef<'lhs, 'rhs>) -> Result<Action, E>,
+) -> Result<Action, Error>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    cb(ChangeRef::Unmerged {
+        location: Cow::Borrowed(path),
+        previous_index,
+        index: idx,
+        entry_mode: entry.mode,
+        id: Cow::Borrowed(entry.id.as_ref()),
+    })
+    .map_err(|err| Error::Callback(err.into()))
+}
+
+/// Return the first item of `iter` that isn't another stage of the unmerged entry at `path`.
+fn next_path<'rhs>(
+    path: &BStr,
+    iter: &mut impl Iterator<Item = (usize, &'rhs BStr, &'rhs gix_index::Entry)>,
+) -> Option<(usize, &'rhs BStr, &'rhs gix_index::Entry)> {
+    iter.find(|(_, next_path, entry)| *next_path != path || !is_unmerged(entry))
+}
+
+fn is_unmerged(entry: &gix_index::Entry) -> bool {
+    entry.stage() != gix_index::entry::Stage::Unconflicted
+}
+
+fn is_intent_to_add(entry: &gix_index::Entry) -> bool {
+    entry.flags.contains(gix_index::entry::Flags::INTENT_TO_ADD)
 }
@@ -115,6 +115,22 @@ pub enum ChangeRef<'lhs, 'rhs> {
         /// or similar content.
         copy: bool,
     },
+    /// An entry is unmerged in `rhs`, i.e. it's represented by up to three entries with a non-zero stage which are
+    /// consecutive in the entries array of `rhs`.
+    ///
+    /// Unmerged entries never participate in rename tracking.
+    Unmerged {
+        /// The location of the unmerged entry in `rhs`, and in `lhs` if it exists there.
+        location: Cow<'rhs, BStr>,
+        /// The index into the entries array of `lhs` of the entry at the same location, if there is one.
+        previous_index: Option<usize>,
+        /// The index of the first conflicting entry, into the entries array of `rhs` for full access to all of its stages.
+        index: usize,
+        /// The mode of the first conflicting entry in `rhs`.
+        entry_mode: gix_index::entry::Mode,
+        /// The object id of the first conflicting entry in `rhs`.
+        id: Cow<'rhs, gix_hash::oid>,
+    },
 }
 
 /// The fully-owned version of [`ChangeRef`].
@@ -67,3 +67,13 @@ pub use index::function::diff as index;
 ///
 #[cfg(feature = "blob")]
 pub mod blob;
+
+/// Parse patches in unified diff format and apply them.
+pub mod patch;
+
+/// Compute ids of patches to identify changes regardless of where they were applied, similar to `git patch-id`.
+pub mod patch_id;
+
+/// Pair commits of two ranges and show how they differ, similar to `git range-diff`.
+#[cfg(feature = "blob")]
+pub mod range_diff;
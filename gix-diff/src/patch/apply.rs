@@ -0,0 +1,98 @@
+use bstr::ByteSlice;
+
+use crate::patch::{File, Line};
+
+/// The error returned by [`File::apply()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Binary patches can't be applied")]
+    Binary,
+    #[error("Hunk {hunk} expected at line {line} did not match")]
+    HunkMismatch { hunk: usize, line: u32 },
+}
+
+impl File {
+    /// Apply all hunks of this file to `old`, the content of the file before the change, and return the content after the change.
+    ///
+    /// Like `git apply`, hunks must match exactly, but may be found before or after the line they are expected at.
+    /// Hunks without leading or trailing context must match at the beginning or the end of `old` respectively.
+    ///
+    /// Note that the caller is responsible for handling additions and deletions, for which `old` is expected to be empty
+    /// or the result is empty respectively.
+    pub fn apply(&self, old: &[u8]) -> Result<Vec<u8>, Error> {
+        if self.is_binary {
+            return Err(Error::Binary);
+        }
+        let lines: Vec<_> = old.lines_with_terminator().collect();
+        let mut out = Vec::with_capacity(old.len());
+        let mut consumed = 0;
+        let mut offset = 0_i64;
+        for (hunk_idx, hunk) in self.hunks.iter().enumerate() {
+            let preimage: Vec<&[u8]> = hunk
+                .lines
+                .iter()
+                .filter_map(|line| match line {
+                    Line::Context(line) | Line::Removed(line) => Some(line.as_slice()),
+                    Line::Added(_) => None,
+                })
+                .collect();
+            let leading_context = hunk.lines.iter().take_while(|l| matches!(l, Line::Context(_))).count();
+            let trailing_context = hunk
+                .lines
+                .iter()
+                .rev()
+                .take_while(|l| matches!(l, Line::Context(_)))
+                .count();
+            let match_beginning = leading_context == 0 && hunk.before_hunk_start <= 1;
+            let match_end = trailing_context == 0 && !preimage.is_empty();
+
+            let expected_line = if hunk.before_hunk_len == 0 {
+                hunk.before_hunk_start
+            } else {
+                hunk.before_hunk_start.saturating_sub(1)
+            };
+            let Some(max_pos) = lines.len().checked_sub(preimage.len()) else {
+                return Err(Error::HunkMismatch {
+                    hunk: hunk_idx,
+                    line: hunk.before_hunk_start,
+                });
+            };
+            let expected = (i64::from(expected_line) + offset).clamp(consumed as i64, max_pos.max(consumed) as i64) as usize;
+            let matches_at =
+                |pos: usize| -> bool { pos >= consumed && pos <= max_pos && lines[pos..][..preimage.len()] == preimage[..] };
+            let pos = if match_beginning {
+                (matches_at(0) && consumed == 0).then_some(0)
+            } else if match_end {
+                matches_at(max_pos).then_some(max_pos)
+            } else {
+                (0..=lines.len()).find_map(|distance| {
+                    [expected.checked_add(distance), expected.checked_sub(distance)]
+                        .into_iter()
+                        .flatten()
+                        .find(|pos| matches_at(*pos))
+                })
+            }
+            .ok_or(Error::HunkMismatch {
+                hunk: hunk_idx,
+                line: hunk.before_hunk_start,
+            })?;
+
+            for line in &lines[consumed..pos] {
+                out.extend_from_slice(line);
+            }
+            for line in &hunk.lines {
+                match line {
+                    Line::Context(line) | Line::Added(line) => out.extend_from_slice(line),
+                    Line::Removed(_) => {}
+                }
+            }
+            consumed = pos + preimage.len();
+            offset = pos as i64 - i64::from(expected_line);
+        }
+        for line in &lines[consumed..] {
+            out.extend_from_slice(line);
+        }
+        Ok(out)
+    }
+}
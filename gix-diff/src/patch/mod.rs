@@ -0,0 +1,83 @@
+use bstr::BString;
+use gix_object::tree::EntryMode;
+
+/// A single line of a [`Hunk`], including its newline unless it was marked with `\ No newline at end of file`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Line {
+    /// A line that is the same before and after the change.
+    Context(BString),
+    /// A line that only exists before the change.
+    Removed(BString),
+    /// A line that only exists after the change.
+    Added(BString),
+}
+
+impl Line {
+    /// Return the content of the line, without its prefix.
+    pub fn content(&self) -> &BString {
+        match self {
+            Line::Context(line) | Line::Removed(line) | Line::Added(line) => line,
+        }
+    }
+}
+
+/// A hunk like `@@ -1,3 +1,4 @@`, along with all of its lines.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hunk {
+    /// The 1-based first line of this hunk in the old file, or the line after which to insert if `before_hunk_len` is 0.
+    pub before_hunk_start: u32,
+    /// The amount of lines of this hunk in the old file.
+    pub before_hunk_len: u32,
+    /// The 1-based first line of this hunk in the new file, or the line after which lines were removed if `after_hunk_len` is 0.
+    pub after_hunk_start: u32,
+    /// The amount of lines of this hunk in the new file.
+    pub after_hunk_len: u32,
+    /// All lines in the order they appear in the patch.
+    pub lines: Vec<Line>,
+}
+
+/// The changes to a single file as parsed from a patch, typically starting with `diff --git a/file b/file`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct File {
+    /// The path of the file before the change, or `None` if it was added.
+    pub old_path: Option<BString>,
+    /// The path of the file after the change, or `None` if it was deleted.
+    pub new_path: Option<BString>,
+    /// The mode before the change, if it was mentioned in the patch.
+    pub old_mode: Option<EntryMode>,
+    /// The mode after the change, if it was mentioned in the patch.
+    pub new_mode: Option<EntryMode>,
+    /// The possibly abbreviated id of the blob before the change, as seen in the `index` line, or `None` if it was added
+    /// or if there was no `index` line.
+    pub old_id: Option<gix_hash::Prefix>,
+    /// The possibly abbreviated id of the blob after the change, as seen in the `index` line, or `None` if it was deleted
+    /// or if there was no `index` line.
+    pub new_id: Option<gix_hash::Prefix>,
+    /// If `true`, the new file was copied from the old one. Otherwise, differing paths indicate a rename.
+    pub is_copy: bool,
+    /// The similarity of a rename or copy in percent, if known.
+    pub similarity: Option<u8>,
+    /// If `true`, the patch only says that binary files differ, or contains a binary patch that isn't supported.
+    pub is_binary: bool,
+    /// All hunks in the order they appear in the patch.
+    pub hunks: Vec<Hunk>,
+}
+
+impl File {
+    /// Return `true` if this file was added.
+    pub fn is_addition(&self) -> bool {
+        self.old_path.is_none()
+    }
+
+    /// Return `true` if this file was deleted.
+    pub fn is_deletion(&self) -> bool {
+        self.new_path.is_none()
+    }
+}
+
+///
+pub mod parse;
+pub use parse::function::parse;
+
+///
+pub mod apply;
@@ -0,0 +1,270 @@
+/// The error returned by [`parse()`](crate::patch::parse()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not parse hunk header {line:?}")]
+    HunkHeader { line: bstr::BString },
+    #[error("Hunk starting with {header:?} ended prematurely")]
+    TruncatedHunk { header: bstr::BString },
+    #[error("Unexpected line {line:?} in hunk starting with {header:?}")]
+    UnexpectedHunkLine { header: bstr::BString, line: bstr::BString },
+    #[error("Could not parse file mode in line {line:?}")]
+    Mode { line: bstr::BString },
+    #[error("Could not parse object ids in line {line:?}")]
+    Index { line: bstr::BString },
+}
+
+pub(super) mod function {
+    use bstr::{BStr, BString, ByteSlice};
+    use gix_object::tree::EntryMode;
+
+    use super::Error;
+    use crate::patch::{File, Hunk, Line};
+
+    /// Parse `input` as a patch in unified diff format, as produced by `git diff` or `git format-patch`,
+    /// and return all files it changes in order.
+    ///
+    /// Everything that isn't part of a patch, like a commit message or diff statistics before it, is ignored.
+    /// Besides patches with extended Git headers that start with `diff --git`, traditional unified diffs starting
+    /// with `--- ` and `+++ ` lines are supported as well.
+    ///
+    /// Note that quoted paths and binary patches aren't supported, the latter are only indicated by [`File::is_binary`].
+    pub fn parse(input: &[u8]) -> Result<Vec<File>, Error> {
+        let mut lines = input.lines_with_terminator().peekable();
+        let mut files = Vec::new();
+        while let Some(line) = lines.next() {
+            let mut file = if let Some(paths) = line.strip_prefix(b"diff --git ") {
+                let mut file = File::default();
+                if let Some((old, new)) = split_git_header_paths(trim_newline(paths).as_bstr()) {
+                    file.old_path = Some(old);
+                    file.new_path = Some(new);
+                }
+                while let Some(line) = lines.next_if(|line| !line.starts_with(b"diff --git ") && !line.starts_with(b"@@ ")) {
+                    parse_extended_header(trim_newline(line), &mut file, &mut lines)?;
+                }
+                file
+            } else if line.starts_with(b"--- ") && lines.peek().is_some_and(|next| next.starts_with(b"+++ ")) {
+                let mut file = File::default();
+                parse_extended_header(trim_newline(line), &mut file, &mut lines)?;
+                let next = lines.next().expect("peeked");
+                parse_extended_header(trim_newline(next), &mut file, &mut lines)?;
+                file
+            } else {
+                continue;
+            };
+
+            while let Some(header) = lines.next_if(|line| line.starts_with(b"@@ ")) {
+                file.hunks.push(parse_hunk(header, &mut lines)?);
+            }
+            files.push(file);
+        }
+        Ok(files)
+    }
+
+    fn is_file_start(line: &[u8]) -> bool {
+        line.starts_with(b"diff --git ") || line.starts_with(b"--- ")
+    }
+
+    fn trim_newline(line: &[u8]) -> &[u8] {
+        line.strip_suffix(b"\n")
+            .map_or(line, |line| line.strip_suffix(b"\r").unwrap_or(line))
+    }
+
+    /// Split `a/old b/new` into `old` and `new`, preferring splits that yield equal paths as the header is ambiguous
+    /// if paths contain spaces.
+    fn split_git_header_paths(paths: &BStr) -> Option<(BString, BString)> {
+        let paths = paths.strip_prefix(b"a/")?;
+        let mut candidates = paths.find_iter(b" b/").map(|pos| (&paths[..pos], &paths[pos + 3..]));
+        let first = candidates.next()?;
+        Some(
+            std::iter::once(first)
+                .chain(candidates)
+                .find(|(old, new)| old == new)
+                .unwrap_or(first),
+        )
+        .map(|(old, new)| (old.into(), new.into()))
+    }
+
+    /// Parse a path as it appears in `---` and `+++` lines or in `rename from` lines,
+    /// returning `None` for `/dev/null`.
+    fn parse_path(path: &[u8], strip_prefix: bool) -> Option<BString> {
+        // Traditional diffs may have a timestamp after a tab.
+        let path = path.find_byte(b'\t').map_or(path, |pos| &path[..pos]);
+        if path == b"/dev/null" {
+            return None;
+        }
+        let path = if strip_prefix {
+            path.find_byte(b'/').map_or(path, |pos| &path[pos + 1..])
+        } else {
+            path
+        };
+        Some(path.into())
+    }
+
+    fn parse_mode(mode: &[u8], line: &[u8]) -> Result<EntryMode, Error> {
+        EntryMode::from_bytes(mode.trim()).ok_or_else(|| Error::Mode { line: line.into() })
+    }
+
+    fn parse_id(hex: &[u8], line: &[u8]) -> Result<Option<gix_hash::Prefix>, Error> {
+        if hex.iter().all(|b| *b == b'0') {
+            return Ok(None);
+        }
+        hex.to_str()
+            .ok()
+            .and_then(|hex| gix_hash::Prefix::from_hex(hex).ok())
+            .map(Some)
+            .ok_or_else(|| Error::Index { line: line.into() })
+    }
+
+    fn parse_extended_header<'a>(
+        line: &[u8],
+        file: &mut File,
+        lines: &mut std::iter::Peekable<impl Iterator<Item = &'a [u8]>>,
+    ) -> Result<(), Error> {
+        if let Some(path) = line.strip_prefix(b"--- ") {
+            file.old_path = parse_path(path, true);
+        } else if let Some(path) = line.strip_prefix(b"+++ ") {
+            file.new_path = parse_path(path, true);
+        } else if let Some(mode) = line.strip_prefix(b"old mode ") {
+            file.old_mode = Some(parse_mode(mode, line)?);
+        } else if let Some(mode) = line.strip_prefix(b"new mode ") {
+            file.new_mode = Some(parse_mode(mode, line)?);
+        } else if let Some(mode) = line.strip_prefix(b"deleted file mode ") {
+            file.old_mode = Some(parse_mode(mode, line)?);
+            file.new_path = None;
+        } else if let Some(mode) = line.strip_prefix(b"new file mode ") {
+            file.new_mode = Some(parse_mode(mode, line)?);
+            file.old_path = None;
+        } else if let Some(path) = line
+            .strip_prefix(b"rename from ")
+            .or_else(|| line.strip_prefix(b"copy from "))
+        {
+            file.old_path = parse_path(path, false);
+        } else if let Some(path) = line.strip_prefix(b"rename to ") {
+            file.new_path = parse_path(path, false);
+        } else if let Some(path) = line.strip_prefix(b"copy to ") {
+            file.new_path = parse_path(path, false);
+            file.is_copy = true;
+        } else if let Some(percentage) = line.strip_prefix(b"similarity index ") {
+            file.similarity = percentage
+                .strip_suffix(b"%")
+                .and_then(|p| p.to_str().ok())
+                .and_then(|p| p.parse().ok());
+        } else if let Some(ids) = line.strip_prefix(b"index ") {
+            let (ids, mode) = ids.split_once_str(" ").unwrap_or((ids, &[]));
+            let (old, new) = ids.split_once_str("..").ok_or_else(|| Error::Index { line: line.into() })?;
+            file.old_id = parse_id(old, line)?;
+            file.new_id = parse_id(new, line)?;
+            if !mode.is_empty() {
+                let mode = parse_mode(mode, line)?;
+                file.old_mode = Some(mode);
+                file.new_mode = Some(mode);
+            }
+        } else if line.starts_with(b"Binary files ") {
+            file.is_binary = true;
+        } else if line == b"GIT binary patch" {
+            file.is_binary = true;
+            while lines.next_if(|line| !is_file_start(line)).is_some() {}
+        }
+        Ok(())
+    }
+
+    /// Parse `-start[,len]` or `+start[,len]`.
+    fn parse_range(range: &[u8], prefix: u8) -> Option<(u32, u32)> {
+        let range = range.strip_prefix(&[prefix])?.to_str().ok()?;
+        Some(match range.split_once(',') {
+            Some((start, len)) => (start.parse().ok()?, len.parse().ok()?),
+            None => (range.parse().ok()?, 1),
+        })
+    }
+
+    fn parse_hunk<'a>(
+        header: &[u8],
+        lines: &mut std::iter::Peekable<impl Iterator<Item = &'a [u8]>>,
+    ) -> Result<Hunk, Error> {
+        let header = trim_newline(header);
+        let header_error = || Error::HunkHeader { line: header.into() };
+        let mut tokens = header[3..].splitn_str(3, " ");
+        let (before_hunk_start, before_hunk_len) = tokens
+            .next()
+            .and_then(|range| parse_range(range, b'-'))
+            .ok_or_else(header_error)?;
+        let (after_hunk_start, after_hunk_len) = tokens
+            .next()
+            .and_then(|range| parse_range(range, b'+'))
+            .ok_or_else(header_error)?;
+        if !tokens.next().is_some_and(|rest| rest.starts_with(b"@@")) {
+            return Err(header_error());
+        }
+
+        let mut hunk = Hunk {
+            before_hunk_start,
+            before_hunk_len,
+            after_hunk_start,
+            after_hunk_len,
+            lines: Vec::new(),
+        };
+        let (mut before_remaining, mut after_remaining) = (before_hunk_len, after_hunk_len);
+        while before_remaining > 0 || after_remaining > 0 {
+            let line = lines.next().ok_or_else(|| Error::TruncatedHunk { header: header.into() })?;
+            let (prefix, content) = match line.split_first() {
+                Some((prefix, content)) => (*prefix, content),
+                None => return Err(Error::TruncatedHunk { header: header.into() }),
+            };
+            let counter_for = |remaining: &mut u32| {
+                *remaining = remaining.checked_sub(1).ok_or_else(|| Error::UnexpectedHunkLine {
+                    header: header.into(),
+                    line: line.into(),
+                })?;
+                Ok::<_, Error>(())
+            };
+            let line = match prefix {
+                b' ' => {
+                    counter_for(&mut before_remaining)?;
+                    counter_for(&mut after_remaining)?;
+                    Line::Context(content.into())
+                }
+                // Some editors strip the space of empty context lines.
+                b'\n' | b'\r' => {
+                    counter_for(&mut before_remaining)?;
+                    counter_for(&mut after_remaining)?;
+                    Line::Context(line.into())
+                }
+                b'-' => {
+                    counter_for(&mut before_remaining)?;
+                    Line::Removed(content.into())
+                }
+                b'+' => {
+                    counter_for(&mut after_remaining)?;
+                    Line::Added(content.into())
+                }
+                b'\\' => {
+                    strip_newline_of_last_line(&mut hunk);
+                    continue;
+                }
+                _ => {
+                    return Err(Error::UnexpectedHunkLine {
+                        header: header.into(),
+                        line: line.into(),
+                    })
+                }
+            };
+            hunk.lines.push(line);
+        }
+        if lines.next_if(|line| line.starts_with(b"\\")).is_some() {
+            strip_newline_of_last_line(&mut hunk);
+        }
+        Ok(hunk)
+    }
+
+    /// Handle `\ No newline at end of file`.
+    fn strip_newline_of_last_line(hunk: &mut Hunk) {
+        if let Some(line) = hunk.lines.last_mut() {
+            let content = match line {
+                Line::Context(line) | Line::Removed(line) | Line::Added(line) => line,
+            };
+            let len = trim_newline(content).len();
+            content.truncate(len);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use bstr::ByteSlice;
+use gix_hash::ObjectId;
+
+/// Determine how a patch id is computed, see [`compute()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Hash each file separately and sum up the hashes, so the id doesn't change if files are diffed in a different order.
+    ///
+    /// Whitespace is ignored. This is what `git patch-id --stable` does, and what Git uses for `git cherry`.
+    Stable,
+    /// Hash all files in the order they appear in the patch, which is what `git patch-id --unstable` does.
+    ///
+    /// Whitespace is ignored.
+    Unstable,
+    /// Like [`Stable`](Mode::Stable), but whitespace is hashed as well, just like `git patch-id --verbatim`.
+    Verbatim,
+}
+
+/// Compute the patch id of `patch`, a diff in the format produced by `git diff` or `git show`, in the given `mode`
+/// with a hash of `kind`. Return `None` if there is no diff, as for commits without changes.
+///
+/// Everything before the first line starting with `diff ` is ignored, which allows the input to contain a commit
+/// header and message. Lines with `index` and hunk headers are ignored, so line numbers and abbreviations don't matter,
+/// and so are `\ No newline at end of file` markers unless the `mode` is [`Verbatim`](Mode::Verbatim).
+/// Binary files are identified by the object ids on their `index` line, which should thus not be abbreviated.
+pub fn compute(patch: &[u8], mode: Mode, kind: gix_hash::Kind) -> Result<Option<ObjectId>, gix_hash::hasher::Error> {
+    let (stable, verbatim) = match mode {
+        Mode::Stable => (true, false),
+        Mode::Unstable => (false, false),
+        Mode::Verbatim => (true, true),
+    };
+    let mut result = ObjectId::null(kind);
+    let mut hasher = gix_hash::hasher(kind);
+    let mut patch_len = 0;
+    // The amount of lines left in the current hunk before and after the change, or `None` while parsing a file header.
+    let mut remaining: Option<(i64, i64)> = None;
+    let mut is_binary = false;
+    let (mut old_hex, mut new_hex) = (&b""[..], &b""[..]);
+    let mut buf = Vec::new();
+    for line in patch.lines_with_terminator() {
+        if line.starts_with(b"\\ ") && line.len() > 12 {
+            if verbatim {
+                hasher.update(line);
+            }
+            continue;
+        }
+        if patch_len == 0 && !line.starts_with(b"diff ") {
+            continue;
+        }
+
+        if remaining.is_none() {
+            if line.starts_with(b"GIT binary patch") || line.starts_with(b"Binary files") {
+                is_binary = true;
+                remaining = Some((0, 0));
+                hasher.update(old_hex);
+                hasher.update(new_hex);
+                if stable {
+                    flush_one_hunk(&mut result, &mut hasher, kind)?;
+                }
+                continue;
+            } else if let Some(ids) = line.strip_prefix(b"index ") {
+                if let Some((old, new)) = ids.trim_end().split_once_str("..") {
+                    old_hex = old;
+                    new_hex = new.split_str(" ").next().unwrap_or(new);
+                }
+                continue;
+            } else if line.starts_with(b"--- ") {
+                remaining = Some((1, 1));
+            } else if !line.first().is_some_and(u8::is_ascii_alphabetic) {
+                break;
+            }
+        }
+
+        if is_binary {
+            if line.starts_with(b"diff ") {
+                is_binary = false;
+                remaining = None;
+            }
+            continue;
+        }
+
+        if remaining == Some((0, 0)) {
+            if line.starts_with(b"@@ -") {
+                remaining = Some(scan_hunk_header(&line[4..]));
+                continue;
+            }
+            if !line.starts_with(b"diff ") {
+                break;
+            }
+            if stable {
+                flush_one_hunk(&mut result, &mut hasher, kind)?;
+            }
+            remaining = None;
+        }
+
+        if let Some((before, after)) = remaining.as_mut() {
+            if matches!(line.first(), Some(b'-' | b' ')) {
+                *before -= 1;
+            }
+            if matches!(line.first(), Some(b'+' | b' ')) {
+                *after -= 1;
+            }
+        }
+
+        let line = if verbatim {
+            line
+        } else {
+            buf.clear();
+            buf.extend(line.iter().filter(|b| !is_space(**b)));
+            &buf
+        };
+        patch_len += line.len();
+        hasher.update(line);
+    }
+
+    if patch_len == 0 {
+        return Ok(None);
+    }
+    flush_one_hunk(&mut result, &mut hasher, kind)?;
+    Ok(Some(result))
+}
+
+/// Like `isspace()` in C, which includes vertical tabs and form feeds.
+fn is_space(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c)
+}
+
+/// Add the hash of everything seen so far to `result` and restart `hasher`.
+fn flush_one_hunk(
+    result: &mut ObjectId,
+    hasher: &mut gix_hash::Hasher,
+    kind: gix_hash::Kind,
+) -> Result<(), gix_hash::hasher::Error> {
+    let hash = std::mem::replace(hasher, gix_hash::hasher(kind)).try_finalize()?;
+    let mut carry = 0_u16;
+    for (sum, byte) in result.as_mut_slice().iter_mut().zip(hash.as_slice()) {
+        carry += u16::from(*sum) + u16::from(*byte);
+        *sum = carry as u8;
+        carry >>= 8;
+    }
+    Ok(())
+}
+
+/// Parse the amount of lines before and after from a hunk header like `-1,2 +3,4 @@` that was stripped of its leading `@@ `.
+fn scan_hunk_header(header: &[u8]) -> (i64, i64) {
+    fn count(range: &[u8]) -> i64 {
+        match range.split_once_str(",") {
+            Some((_, len)) => {
+                let digits = len.iter().take_while(|b| b.is_ascii_digit()).count();
+                len[..digits].to_str().ok().and_then(|n| n.parse().ok()).unwrap_or(0)
+            }
+            None => 1,
+        }
+    }
+    let mut ranges = header.splitn_str(2, " +");
+    let before = ranges.next().map_or(1, count);
+    let after = ranges
+        .next()
+        .and_then(|after| after.split_str(" ").next())
+        .map_or(1, count);
+    (before, after)
+}
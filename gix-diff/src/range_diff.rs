@@ -0,0 +1,324 @@
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::blob::{
+    funcname,
+    intern::InternedInput,
+    sources::byte_lines_with_terminator,
+    unified_diff::{ConsumeHunk, ContextSize, NewlineSeparator},
+    Algorithm, UnifiedDiff,
+};
+
+/// The creation factor Git uses by default, see [`pairs()`].
+pub const DEFAULT_CREATION_FACTOR: u32 = 60;
+
+/// A commit rendered as text, so it can be compared to commits of another range.
+///
+/// The text is expected to be in the format Git uses for `git range-diff`, starting with a ` ## Metadata ##` section with
+/// the author, followed by ` ## Commit message ##` with the indented message and a ` ## path ##` section with the changes
+/// to each file.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Patch {
+    /// The complete text of the patch, which is compared to decide if two commits are the same.
+    pub text: BString,
+    /// The offset into `text` at which the changes to the first file start, so `text[diff_start..]` doesn't contain
+    /// metadata or the commit message.
+    pub diff_start: usize,
+}
+
+impl AsRef<Patch> for Patch {
+    fn as_ref(&self) -> &Patch {
+        self
+    }
+}
+
+impl Patch {
+    /// Return the part of the patch that contains the changes to all files.
+    pub fn diff(&self) -> &BStr {
+        self.text[self.diff_start.min(self.text.len())..].as_bstr()
+    }
+
+    /// The size of the changes, as the amount of lines in the [diff](Self::diff()) without empty lines that separate files.
+    fn diff_size(&self) -> usize {
+        self.diff().lines().filter(|line| !line.is_empty()).count()
+    }
+}
+
+/// A pair of commits as produced by [`pairs()`], with indices into the old and the new range respectively.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Pair {
+    /// The commit at `old` has no counterpart in the new range.
+    Removed {
+        /// The index of the commit in the old range.
+        old: usize,
+    },
+    /// The commit at `new` has no counterpart in the old range.
+    Added {
+        /// The index of the commit in the new range.
+        new: usize,
+    },
+    /// The commits match, and their patches are the same, including their metadata and message.
+    Unchanged {
+        /// The index of the commit in the old range.
+        old: usize,
+        /// The index of the commit in the new range.
+        new: usize,
+    },
+    /// The commits match, but their patches differ.
+    Changed {
+        /// The index of the commit in the old range.
+        old: usize,
+        /// The index of the commit in the new range.
+        new: usize,
+    },
+}
+
+/// Find the commits in `new` that correspond to the commits in `old`, and return all of them as pairs in the order Git would
+/// show them in, which follows the order of `new` while showing removed commits as early as possible.
+///
+/// Commits whose changes are the same are paired first. All others are paired so that the total size of the differences
+/// between their changes is minimal, while a commit that isn't paired costs `creation_factor` percent of the size of its changes.
+/// This means that higher values of `creation_factor` allow more different commits to still be paired, and
+/// [`DEFAULT_CREATION_FACTOR`] is what Git uses.
+pub fn pairs<P: AsRef<Patch>>(old: &[P], new: &[P], creation_factor: u32) -> Vec<Pair> {
+    let old: Vec<_> = old.iter().map(AsRef::as_ref).collect();
+    let new: Vec<_> = new.iter().map(AsRef::as_ref).collect();
+    let (old_to_new, new_to_old) = correspondences(&old, &new, creation_factor);
+
+    let mut out = Vec::with_capacity(old.len().max(new.len()));
+    let mut shown = vec![false; old.len()];
+    let (mut old_idx, mut new_idx) = (0, 0);
+    while old_idx < old.len() || new_idx < new.len() {
+        while old_idx < old.len() && shown[old_idx] {
+            old_idx += 1;
+        }
+        if old_idx < old.len() && old_to_new[old_idx].is_none() {
+            out.push(Pair::Removed { old: old_idx });
+            old_idx += 1;
+            continue;
+        }
+        while new_idx < new.len() && new_to_old[new_idx].is_none() {
+            out.push(Pair::Added { new: new_idx });
+            new_idx += 1;
+        }
+        if new_idx < new.len() {
+            let old_idx = new_to_old[new_idx].expect("unmatched ones were skipped");
+            out.push(if old[old_idx].text == new[new_idx].text {
+                Pair::Unchanged {
+                    old: old_idx,
+                    new: new_idx,
+                }
+            } else {
+                Pair::Changed {
+                    old: old_idx,
+                    new: new_idx,
+                }
+            });
+            shown[old_idx] = true;
+            new_idx += 1;
+        }
+    }
+    out
+}
+
+/// Return the mapping from old to new indices and from new to old indices.
+fn correspondences(old: &[&Patch], new: &[&Patch], creation_factor: u32) -> (Vec<Option<usize>>, Vec<Option<usize>>) {
+    const COST_MAX: i64 = 1 << 16;
+    let mut old_to_new = vec![None; old.len()];
+    let mut new_to_old = vec![None; new.len()];
+    let mut by_diff = std::collections::HashMap::<&BStr, Vec<usize>>::new();
+    for (idx, patch) in old.iter().enumerate() {
+        by_diff.entry(patch.diff()).or_default().push(idx);
+    }
+    for (new_idx, patch) in new.iter().enumerate() {
+        if let Some(old_idx) = by_diff
+            .get(patch.diff())
+            .and_then(|candidates| candidates.iter().copied().find(|idx| old_to_new[*idx].is_none()))
+        {
+            old_to_new[old_idx] = Some(new_idx);
+            new_to_old[new_idx] = Some(old_idx);
+        }
+    }
+
+    let creation_cost = |patch: &Patch| (patch.diff_size() as i64 * i64::from(creation_factor) / 100).min(COST_MAX);
+    let n = old.len() + new.len();
+    let mut cost = vec![0_i64; n * n];
+    for (old_idx, old_patch) in old.iter().enumerate() {
+        for (new_idx, new_patch) in new.iter().enumerate() {
+            cost[old_idx * n + new_idx] = if old_to_new[old_idx] == Some(new_idx) {
+                0
+            } else if old_to_new[old_idx].is_none() && new_to_old[new_idx].is_none() {
+                diff_size(old_patch.diff(), new_patch.diff()).min(COST_MAX)
+            } else {
+                COST_MAX
+            };
+        }
+        let unpaired = if old_to_new[old_idx].is_none() {
+            creation_cost(old_patch)
+        } else {
+            COST_MAX
+        };
+        cost[old_idx * n + new.len()..(old_idx + 1) * n].fill(unpaired);
+    }
+    for (new_idx, new_patch) in new.iter().enumerate() {
+        let unpaired = if new_to_old[new_idx].is_none() {
+            creation_cost(new_patch)
+        } else {
+            COST_MAX
+        };
+        for old_idx in old.len()..n {
+            cost[old_idx * n + new_idx] = unpaired;
+        }
+    }
+
+    for (old_idx, new_idx) in assignment(n, &cost).into_iter().enumerate().take(old.len()) {
+        if new_idx < new.len() {
+            old_to_new[old_idx] = Some(new_idx);
+            new_to_old[new_idx] = Some(old_idx);
+        }
+    }
+    (old_to_new, new_to_old)
+}
+
+/// Return the amount of lines a unified diff between `old` and `new` would have, including hunk headers and context lines.
+fn diff_size(old: &BStr, new: &BStr) -> i64 {
+    let input = InternedInput::new(byte_lines_with_terminator(old), byte_lines_with_terminator(new));
+    crate::blob::diff(
+        Algorithm::Myers,
+        &input,
+        UnifiedDiff::new(
+            &input,
+            CountLines(0),
+            NewlineSeparator::AfterHeaderAndWhenNeeded("\n"),
+            ContextSize::symmetrical(3),
+        ),
+    )
+    .expect("counting can't fail")
+}
+
+/// Count the lines of all hunks, including their header.
+struct CountLines(i64);
+
+impl ConsumeHunk for CountLines {
+    type Out = i64;
+
+    fn consume_hunk(&mut self, _: u32, _: u32, _: u32, _: u32, _: &str, hunk: &[u8]) -> std::io::Result<()> {
+        self.0 += 1 + hunk.lines().count() as i64;
+        Ok(())
+    }
+
+    fn finish(self) -> Self::Out {
+        self.0
+    }
+}
+
+/// Solve the assignment problem for the square `n`×`n` `cost` matrix in row-major order with the Hungarian method,
+/// and return the column assigned to each row such that the sum of all costs is minimal.
+fn assignment(n: usize, cost: &[i64]) -> Vec<usize> {
+    let mut row_potential = vec![0_i64; n + 1];
+    let mut column_potential = vec![0_i64; n + 1];
+    // The row assigned to each column, 1-based, with 0 meaning unassigned, and column 0 being a virtual starting point.
+    let mut row_of_column = vec![0_usize; n + 1];
+    let mut previous_column = vec![0_usize; n + 1];
+    for row in 1..=n {
+        row_of_column[0] = row;
+        let mut column = 0;
+        let mut min_slack = vec![i64::MAX; n + 1];
+        let mut used = vec![false; n + 1];
+        loop {
+            used[column] = true;
+            let current_row = row_of_column[column];
+            let mut delta = i64::MAX;
+            let mut next_column = 0;
+            for candidate in 1..=n {
+                if used[candidate] {
+                    continue;
+                }
+                let slack = cost[(current_row - 1) * n + candidate - 1]
+                    - row_potential[current_row]
+                    - column_potential[candidate];
+                if slack < min_slack[candidate] {
+                    min_slack[candidate] = slack;
+                    previous_column[candidate] = column;
+                }
+                if min_slack[candidate] < delta {
+                    delta = min_slack[candidate];
+                    next_column = candidate;
+                }
+            }
+            for candidate in 0..=n {
+                if used[candidate] {
+                    row_potential[row_of_column[candidate]] += delta;
+                    column_potential[candidate] -= delta;
+                } else {
+                    min_slack[candidate] -= delta;
+                }
+            }
+            column = next_column;
+            if row_of_column[column] == 0 {
+                break;
+            }
+        }
+        while column != 0 {
+            let previous = previous_column[column];
+            row_of_column[column] = row_of_column[previous];
+            column = previous;
+        }
+    }
+
+    let mut column_of_row = vec![0; n];
+    for (column, row) in row_of_column.into_iter().enumerate().skip(1) {
+        if row != 0 {
+            column_of_row[row - 1] = column - 1;
+        }
+    }
+    column_of_row
+}
+
+/// Write the differences between the `old` and the `new` patch to `out`, with each line indented by four spaces, just like
+/// `git range-diff` shows them for [changed](Pair::Changed) pairs.
+///
+/// Hunk headers don't show line numbers, but the name of the section they are in, like `@@ Commit message` or
+/// `@@ path/to/file: fn main() {`.
+pub fn write_diff(old: &Patch, new: &Patch, out: &mut dyn std::io::Write, context: ContextSize) -> std::io::Result<()> {
+    let sections = funcname::Matcher::from_xfuncname("^ ## (.*) ##$\n^.?@@ (.*)$".into()).expect("valid patterns");
+    let input = InternedInput::new(
+        byte_lines_with_terminator(old.text.as_ref()),
+        byte_lines_with_terminator(new.text.as_ref()),
+    );
+    crate::blob::diff(
+        Algorithm::Myers,
+        &input,
+        UnifiedDiff::new(
+            &input,
+            IndentedHunks { out },
+            NewlineSeparator::AfterHeaderAndWhenNeeded("\n"),
+            context,
+        )
+        .with_function_names(&sections),
+    )
+}
+
+/// Write hunks with section names instead of line numbers in their headers, with each line indented.
+struct IndentedHunks<'a> {
+    out: &'a mut dyn std::io::Write,
+}
+
+impl ConsumeHunk for IndentedHunks<'_> {
+    type Out = ();
+
+    fn consume_hunk(&mut self, _: u32, _: u32, _: u32, _: u32, header: &str, hunk: &[u8]) -> std::io::Result<()> {
+        let section = header
+            .trim_end_matches('\n')
+            .get(2..)
+            .and_then(|header| header.split_once("@@"))
+            .map_or("", |(_, name)| name);
+        writeln!(self.out, "    @@{section}")?;
+        for line in hunk.lines_with_terminator() {
+            self.out.write_all(b"    ")?;
+            self.out.write_all(line)?;
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Self::Out {}
+}
@@ -0,0 +1,52 @@
+use gix_diff::blob::funcname::{Matcher, BUILTIN_DRIVER_NAMES};
+
+#[test]
+fn default_matches_lines_starting_with_identifiers() {
+    let m = Matcher::default();
+    assert_eq!(m.function_name(b"fn main() {  \n"), Some(&b"fn main() {"[..]));
+    assert_eq!(m.function_name(b"$var\r\n"), Some(&b"$var"[..]));
+    assert_eq!(m.function_name(b"_private"), Some(&b"_private"[..]));
+    assert_eq!(m.function_name(b"    indented()"), None);
+    assert_eq!(m.function_name(b"// comment"), None);
+    assert_eq!(m.function_name(b""), None);
+}
+
+#[test]
+fn all_builtins_compile() {
+    for name in BUILTIN_DRIVER_NAMES {
+        assert!(Matcher::builtin(name).is_some(), "{name}");
+    }
+    assert!(Matcher::builtin("unknown").is_none());
+}
+
+#[test]
+fn builtin_capture_groups_are_used_as_name() {
+    let rust = Matcher::builtin("rust").expect("exists");
+    assert_eq!(
+        rust.function_name(b"    pub(crate) async fn run(&self) -> Result<()> {\n"),
+        Some(&b"pub(crate) async fn run(&self) -> Result<()> {"[..])
+    );
+    assert_eq!(rust.function_name(b"struct S;"), None);
+
+    let python = Matcher::builtin("python").expect("exists");
+    assert_eq!(python.function_name(b"  async def f(x):"), Some(&b"async def f(x):"[..]));
+
+    let cpp = Matcher::builtin("cpp").expect("exists");
+    assert_eq!(cpp.function_name(b"public:"), None, "negated patterns win");
+    assert_eq!(cpp.function_name(b"int main(void)"), Some(&b"int main(void)"[..]));
+
+    let css = Matcher::builtin("css").expect("exists");
+    assert_eq!(css.function_name(b"BODY {"), Some(&b"BODY {"[..]), "case-insensitive");
+}
+
+#[test]
+fn xfuncname_with_negation() -> crate::Result {
+    let m = Matcher::from_xfuncname("!^skip\n^(s[a-z]+)\n^=.*".into())?;
+    assert_eq!(m.function_name(b"skip this"), None, "first matching pattern decides");
+    assert_eq!(m.function_name(b"section 1"), Some(&b"section"[..]), "first group is the name");
+    assert_eq!(m.function_name(b"=head"), Some(&b"=head"[..]), "whole match without group");
+    assert_eq!(m.function_name(b"other"), None);
+
+    assert!(Matcher::from_xfuncname("(unclosed".into()).is_err());
+    Ok(())
+}
@@ -1,3 +1,4 @@
+mod funcname;
 pub(crate) mod pipeline;
 mod platform;
 mod unified_diff;
@@ -1,4 +1,5 @@
 use gix_diff::blob::{
+    funcname,
     unified_diff::{ConsumeHunk, ContextSize, NewlineSeparator},
     Algorithm, UnifiedDiff,
 };
@@ -278,6 +279,37 @@ fn all_added_or_removed() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn missing_newline_marker() -> crate::Result {
+    let a = "1\n2\n3";
+    let b = "1\n2\nthree";
+
+    let interner = gix_diff::blob::intern::InternedInput::new(
+        gix_diff::blob::sources::byte_lines_with_terminator(a.as_bytes()),
+        gix_diff::blob::sources::byte_lines_with_terminator(b.as_bytes()),
+    );
+    let actual = gix_diff::blob::diff(
+        Algorithm::Myers,
+        &interner,
+        UnifiedDiff::new(
+            &interner,
+            String::new(),
+            NewlineSeparator::AfterHeaderAndWhenNeeded("\n"),
+            ContextSize::symmetrical(1),
+        )
+        .with_missing_newline_marker(true),
+    )?;
+    insta::assert_snapshot!(actual, @r"
+    @@ -2,2 +2,2 @@
+     2
+    -3
+    \ No newline at end of file
+    +three
+    \ No newline at end of file
+    ");
+    Ok(())
+}
+
 #[test]
 fn empty() -> crate::Result {
     let interner = gix_diff::blob::intern::InternedInput::new(&b""[..], &b""[..]);
@@ -296,6 +328,221 @@ fn empty() -> crate::Result {
     Ok(())
 }
 
+const RUST_BEFORE: &str = r#"use std::io;
+
+// The entry point.
+fn main() {
+    let a = 1;
+    let b = 2;
+    let c = 3;
+    println!("{a}");
+}
+
+/// Helper.
+pub fn helper(x: u32) -> u32 {
+    let y = x + 1;
+    let z = y * 2;
+    z
+}
+
+struct S;
+"#;
+
+const RUST_AFTER: &str = r#"use std::io;
+
+// The entry point.
+fn main() {
+    let a = 10;
+    let b = 2;
+    let c = 30;
+    println!("{a}");
+}
+
+/// Helper.
+pub fn helper(x: u32) -> u32 {
+    let y = x + 1;
+    let z = y * 3;
+    z
+}
+
+struct S;
+"#;
+
+fn rust_diff(context_size: ContextSize, matcher: Option<&funcname::Matcher>) -> crate::Result<String> {
+    let interner = gix_diff::blob::intern::InternedInput::new(
+        gix_diff::blob::sources::lines(RUST_BEFORE),
+        gix_diff::blob::sources::lines(RUST_AFTER),
+    );
+    let mut sink = UnifiedDiff::new(
+        &interner,
+        String::new(),
+        NewlineSeparator::AfterHeaderAndLine("\n"),
+        context_size,
+    );
+    if let Some(matcher) = matcher {
+        sink = sink.with_function_names(matcher);
+    }
+    Ok(gix_diff::blob::diff(Algorithm::Myers, &interner, sink)?)
+}
+
+#[test]
+fn function_names_in_hunk_headers() -> crate::Result {
+    let rust = funcname::Matcher::builtin("rust").expect("built-in");
+    let actual = rust_diff(ContextSize::symmetrical(0), Some(&rust))?;
+    insta::assert_snapshot!(actual, @r"
+    @@ -5,1 +5,1 @@ fn main() {
+    -    let a = 1;
+    +    let a = 10;
+    @@ -7,1 +7,1 @@ fn main() {
+    -    let c = 3;
+    +    let c = 30;
+    @@ -14,1 +14,1 @@ pub fn helper(x: u32) -> u32 {
+    -    let z = y * 2;
+    +    let z = y * 3;
+    ");
+
+    let actual = rust_diff(ContextSize::symmetrical(1), Some(&rust))?;
+    insta::assert_snapshot!(actual, @r#"
+    @@ -4,5 +4,5 @@
+     fn main() {
+    -    let a = 1;
+    +    let a = 10;
+         let b = 2;
+    -    let c = 3;
+    +    let c = 30;
+         println!("{a}");
+    @@ -13,3 +13,3 @@ pub fn helper(x: u32) -> u32 {
+         let y = x + 1;
+    -    let z = y * 2;
+    +    let z = y * 3;
+         z
+    "#);
+
+    let actual = rust_diff(ContextSize::symmetrical(1), Some(&funcname::Matcher::default()))?;
+    insta::assert_snapshot!(actual, @r#"
+    @@ -4,5 +4,5 @@ use std::io;
+     fn main() {
+    -    let a = 1;
+    +    let a = 10;
+         let b = 2;
+    -    let c = 3;
+    +    let c = 30;
+         println!("{a}");
+    @@ -13,3 +13,3 @@ pub fn helper(x: u32) -> u32 {
+         let y = x + 1;
+    -    let z = y * 2;
+    +    let z = y * 3;
+         z
+    "#);
+    Ok(())
+}
+
+#[test]
+fn inter_hunk_context_merges_close_hunks() -> crate::Result {
+    let rust = funcname::Matcher::builtin("rust").expect("built-in");
+    let actual = rust_diff(ContextSize::symmetrical(0).with_inter_hunk_context(1), Some(&rust))?;
+    insta::assert_snapshot!(actual, @r"
+    @@ -5,3 +5,3 @@ fn main() {
+    -    let a = 1;
+    +    let a = 10;
+         let b = 2;
+    -    let c = 3;
+    +    let c = 30;
+    @@ -14,1 +14,1 @@ pub fn helper(x: u32) -> u32 {
+    -    let z = y * 2;
+    +    let z = y * 3;
+    ");
+
+    let actual = rust_diff(ContextSize::symmetrical(1).with_inter_hunk_context(5), None)?;
+    insta::assert_snapshot!(actual, @r#"
+    @@ -4,12 +4,12 @@
+     fn main() {
+    -    let a = 1;
+    +    let a = 10;
+         let b = 2;
+    -    let c = 3;
+    +    let c = 30;
+         println!("{a}");
+     }
+     
+     /// Helper.
+     pub fn helper(x: u32) -> u32 {
+         let y = x + 1;
+    -    let z = y * 2;
+    +    let z = y * 3;
+         z
+    "#);
+    Ok(())
+}
+
+#[test]
+fn function_context() -> crate::Result {
+    let rust = funcname::Matcher::builtin("rust").expect("built-in");
+    let actual = rust_diff(ContextSize::symmetrical(1).with_function_context(true), Some(&rust))?;
+    insta::assert_snapshot!(actual, @r#"
+    @@ -3,16 +3,16 @@
+     // The entry point.
+     fn main() {
+    -    let a = 1;
+    +    let a = 10;
+         let b = 2;
+    -    let c = 3;
+    +    let c = 30;
+         println!("{a}");
+     }
+     
+     /// Helper.
+     pub fn helper(x: u32) -> u32 {
+         let y = x + 1;
+    -    let z = y * 2;
+    +    let z = y * 3;
+         z
+     }
+     
+     struct S;
+    "#);
+
+    let interner = gix_diff::blob::intern::InternedInput::new("fn a() {\n  1\n}\n", "fn a() {\n  1\n}\nmore\n");
+    let actual = gix_diff::blob::diff(
+        Algorithm::Myers,
+        &interner,
+        UnifiedDiff::new(
+            &interner,
+            String::new(),
+            NewlineSeparator::AfterHeaderAndLine("\n"),
+            ContextSize::symmetrical(0).with_function_context(true),
+        )
+        .with_function_names(&rust),
+    )?;
+    insta::assert_snapshot!(actual, @r"
+    @@ -1,3 +1,4 @@
+     fn a() {
+       1
+     }
+    +more
+    ");
+
+    let interner = gix_diff::blob::intern::InternedInput::new("a\nb\nc\n", "a\nb\nc\nfn x() {\n}\n");
+    let actual = gix_diff::blob::diff(
+        Algorithm::Myers,
+        &interner,
+        UnifiedDiff::new(
+            &interner,
+            String::new(),
+            NewlineSeparator::AfterHeaderAndLine("\n"),
+            ContextSize::symmetrical(1).with_function_context(true),
+        )
+        .with_function_names(&rust),
+    )?;
+    insta::assert_snapshot!(actual, @r"
+    @@ -3,1 +3,3 @@
+     c
+    +fn x() {
+    +}
+    ");
+    Ok(())
+}
+
 #[derive(Default)]
 struct Recorder {
     #[allow(clippy::type_complexity)]
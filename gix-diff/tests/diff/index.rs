@@ -1193,16 +1193,55 @@ fn unmerged_entries_and_intent_to_add() -> crate::Result {
         }),
     )?;
 
-    // Intent-to-add is transparent. And unmerged entries aren't emitted either, along with
-    // their sibling paths.
+    // Intent-to-add is transparent. And unmerged entries are emitted only once, without participating
+    // in rename tracking, and their sibling paths aren't emitted either.
     // All that with rename tracking…
-    insta::assert_debug_snapshot!(changes.into_iter().collect::<Vec<_>>(), @"[]");
+    insta::assert_debug_snapshot!(changes.into_iter().collect::<Vec<_>>(), @r#"
+    [
+        Unmerged {
+            location: "src/plumbing-renamed/main.rs",
+            previous_index: Some(
+                2,
+            ),
+            index: 2,
+            entry_mode: Mode(
+                FILE,
+            ),
+            id: Sha1(d00491fd7e5bb6fa28c517a0bb32b8b506539d4d),
+        },
+    ]
+    "#);
 
     let changes = collect_changes_no_renames("r4-dir-rename-non-identity", ".git/index")?;
     // …or without
-    insta::assert_debug_snapshot!(changes.into_iter().collect::<Vec<_>>(), @"[]");
+    insta::assert_debug_snapshot!(changes.into_iter().collect::<Vec<_>>(), @r#"
+    [
+        Unmerged {
+            location: "src/plumbing-renamed/main.rs",
+            previous_index: Some(
+                2,
+            ),
+            index: 2,
+            entry_mode: Mode(
+                FILE,
+            ),
+            id: Sha1(d00491fd7e5bb6fa28c517a0bb32b8b506539d4d),
+        },
+    ]
+    "#);
 
     let (index, _, _, _, _) = repo_with_indices(".git/index", ".git/index", None)?;
+    assert_eq!(
+        index.entries()[2..4]
+            .iter()
+            .map(|e| (e.path(&index).to_owned(), e.stage_raw()))
+            .collect::<Vec<_>>(),
+        [
+            ("src/plumbing-renamed/main.rs".into(), 1),
+            ("src/plumbing-renamed/main.rs".into(), 2)
+        ],
+        "the index of the unmerged change points to the first of its stages"
+    );
     assert_eq!(
         index.entry_by_path("will-add".into()).map(|e| e.id),
         Some(hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391")),
@@ -1212,6 +1251,30 @@ fn unmerged_entries_and_intent_to_add() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn unmerged_entries_without_counterpart_in_lhs() -> crate::Result {
+    let changes = collect_changes_no_renames(None, ".git/index")?;
+    let unmerged: Vec<_> = changes
+        .iter()
+        .filter(|change| matches!(change, Change::Unmerged { .. }))
+        .map(|change| (change.fields().0, change.fields().1))
+        .collect();
+    assert_eq!(
+        unmerged,
+        [("src/plumbing-renamed/main.rs".into(), 2)],
+        "each unmerged path is emitted once, no matter how many stages it has"
+    );
+    assert!(
+        changes.iter().all(|change| match change {
+            Change::Addition { location, .. } => location.as_ref() != "src/plumbing-renamed/main.rs",
+            Change::Unmerged { previous_index, .. } => previous_index.is_none(),
+            _ => false,
+        }),
+        "stages aren't additions, and there is nothing to compare to in lhs"
+    );
+    Ok(())
+}
+
 mod util {
     use std::{
         convert::Infallible,
@@ -6,6 +6,9 @@ fn hex_to_id(hex: &str) -> gix_hash::ObjectId {
 
 mod blob;
 mod index;
+mod patch;
+mod patch_id;
+mod range_diff;
 mod rewrites;
 mod tree;
 mod tree_with_rewrites;
@@ -0,0 +1,207 @@
+use gix_diff::patch::{self, Line};
+use gix_object::tree::EntryKind;
+
+const GIT_PATCH: &str = r"From 4175a6d0a52ab77a50bb805e0795804ea5f9399d Mon Sep 17 00:00:00 2001
+Subject: [PATCH] modify files
+---
+ a | 2 ++
+ 1 file changed, 2 insertions(+)
+
+diff --git a/a b/a
+old mode 100644
+new mode 100755
+index f00c965..08fe19c
+--- a/a
++++ b/a
+@@ -8,3 +8,5 @@ fn main() {
+ 8
+ 9
+ 10
++11
++12
+diff --git a/to-be-renamed b/renamed
+similarity index 94%
+rename from to-be-renamed
+rename to renamed
+index 0ff3bbb..d4de868 100644
+--- a/to-be-renamed
++++ b/renamed
+@@ -2 +2 @@
+-2
++two
+diff --git a/b b/b
+index 20cbb4d..0f92a3f 100644
+--- a/b
++++ b/b
+@@ -1 +1 @@
+-no newline
+\ No newline at end of file
++no newline, still
+\ No newline at end of file
+diff --git a/new b/new
+new file mode 100644
+index 0000000..3e75765
+--- /dev/null
++++ b/new
+@@ -0,0 +1 @@
++new
+diff --git a/gone b/gone
+deleted file mode 100644
+index 3e75765..0000000
+--- a/gone
++++ /dev/null
+@@ -1 +0,0 @@
+-new
+diff --git a/image.png b/image.png
+index 8b13789..f2ad6c7 100644
+Binary files a/image.png and b/image.png differ
+-- 
+2.49.0
+";
+
+mod parse {
+    use super::*;
+
+    #[test]
+    fn git_patch() -> crate::Result {
+        let files = patch::parse(GIT_PATCH.as_bytes())?;
+        assert_eq!(files.len(), 6);
+
+        let a = &files[0];
+        assert_eq!(a.old_path.as_ref().expect("set"), "a");
+        assert_eq!(a.new_path.as_ref().expect("set"), "a");
+        assert_eq!(a.old_mode.map(|m| m.kind()), Some(EntryKind::Blob));
+        assert_eq!(a.new_mode.map(|m| m.kind()), Some(EntryKind::BlobExecutable));
+        assert_eq!(a.old_id.expect("set").to_string(), "f00c965");
+        assert_eq!(a.hunks.len(), 1);
+        let hunk = &a.hunks[0];
+        assert_eq!(
+            (
+                hunk.before_hunk_start,
+                hunk.before_hunk_len,
+                hunk.after_hunk_start,
+                hunk.after_hunk_len
+            ),
+            (8, 3, 8, 5)
+        );
+        assert_eq!(
+            hunk.lines,
+            [
+                Line::Context("8\n".into()),
+                Line::Context("9\n".into()),
+                Line::Context("10\n".into()),
+                Line::Added("11\n".into()),
+                Line::Added("12\n".into()),
+            ]
+        );
+
+        let renamed = &files[1];
+        assert_eq!(renamed.old_path.as_ref().expect("set"), "to-be-renamed");
+        assert_eq!(renamed.new_path.as_ref().expect("set"), "renamed");
+        assert_eq!(renamed.similarity, Some(94));
+        assert!(!renamed.is_copy);
+        assert_eq!(
+            (renamed.hunks[0].before_hunk_len, renamed.hunks[0].after_hunk_len),
+            (1, 1),
+            "omitted lengths are 1"
+        );
+
+        assert_eq!(
+            files[2].hunks[0].lines,
+            [
+                Line::Removed("no newline".into()),
+                Line::Added("no newline, still".into())
+            ],
+            "missing newlines are preserved"
+        );
+
+        let new = &files[3];
+        assert!(new.is_addition());
+        assert_eq!(new.old_id, None, "null ids are ignored");
+        assert_eq!(new.new_mode.map(|m| m.kind()), Some(EntryKind::Blob));
+
+        let gone = &files[4];
+        assert!(gone.is_deletion());
+        assert_eq!(gone.new_id, None);
+
+        assert!(files[5].is_binary);
+        assert!(files[5].hunks.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn traditional_patch() -> crate::Result {
+        let files = patch::parse(b"--- a/file\n+++ b/file\n@@ -1,2 +1,2 @@\n-1\n+one\n 2\n")?;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].old_path.as_ref().expect("set"), "file");
+        assert_eq!(files[0].new_path.as_ref().expect("set"), "file");
+        assert_eq!(files[0].hunks[0].lines.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_hunk() {
+        let err = patch::parse(b"--- a/file\n+++ b/file\n@@ -1,2 +1,2 @@\n-1\n").unwrap_err();
+        assert!(matches!(err, patch::parse::Error::TruncatedHunk { .. }), "{err:?}");
+    }
+
+    #[test]
+    fn invalid_hunk_header() {
+        let err = patch::parse(b"--- a/file\n+++ b/file\n@@ -x +1 @@\n+1\n").unwrap_err();
+        assert!(matches!(err, patch::parse::Error::HunkHeader { .. }), "{err:?}");
+    }
+}
+
+mod apply {
+    use gix_object::bstr::ByteSlice;
+
+    use super::*;
+
+    fn files() -> Vec<patch::File> {
+        patch::parse(GIT_PATCH.as_bytes()).expect("valid")
+    }
+
+    #[test]
+    fn exact_and_with_offset() -> crate::Result {
+        let a = &files()[0];
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n";
+        assert_eq!(a.apply(old.as_bytes())?.as_bstr(), format!("{old}11\n12\n"));
+
+        let old = format!("0\n{old}");
+        assert_eq!(
+            a.apply(old.as_bytes())?.as_bstr(),
+            format!("{old}11\n12\n"),
+            "the hunk is found even if lines were added before it"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn missing_newlines() -> crate::Result {
+        assert_eq!(files()[2].apply(b"no newline")?.as_bstr(), "no newline, still");
+        Ok(())
+    }
+
+    #[test]
+    fn additions_and_deletions() -> crate::Result {
+        let files = files();
+        assert_eq!(files[3].apply(b"")?.as_bstr(), "new\n");
+        assert_eq!(files[4].apply(b"new\n")?.as_bstr(), "");
+        Ok(())
+    }
+
+    #[test]
+    fn mismatch() {
+        let err = files()[0].apply(b"1\n2\n3\n4\n5\n6\n7\n8\nnine\n10\n").unwrap_err();
+        assert!(
+            matches!(err, patch::apply::Error::HunkMismatch { hunk: 0, .. }),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn binary() {
+        let err = files()[5].apply(b"").unwrap_err();
+        assert!(matches!(err, patch::apply::Error::Binary), "{err:?}");
+    }
+}
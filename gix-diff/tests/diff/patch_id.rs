@@ -0,0 +1,90 @@
+use gix_diff::patch_id::{compute, Mode};
+use gix_hash::{Kind, ObjectId};
+
+const PATCH: &str = r"commit 0000000000000000000000000000000000000000
+Author: a <a@example.com>
+
+    message with diff --git in it
+
+diff --git a/a b/a
+index 3b18e51..8ad3f5b 100644
+--- a/a
++++ b/a
+@@ -1,3 +1,3 @@ fn main() {
+ one
+-two
++t w o
+ three
+@@ -10 +10 @@
+-last
+\ No newline at end of file
++last
+diff --git a/bin b/bin
+index 1111111..2222222 100644
+Binary files a/bin and b/bin differ
+diff --git a/x b/x
+new file mode 100755
+index 0000000..3b18e51
+--- /dev/null
++++ b/x
+@@ -0,0 +1 @@
++hello world
+";
+
+fn id(hex: &str) -> ObjectId {
+    ObjectId::from_hex(hex.as_bytes()).expect("valid hex")
+}
+
+#[test]
+fn matches_git() -> crate::Result {
+    for (mode, expected) in [
+        (Mode::Stable, "0b922f75e8a9aaa4941874fd89a89ca7f634d648"),
+        (Mode::Unstable, "116a4abc657b5e2bc34f2e4a121f9718c281db7b"),
+        (Mode::Verbatim, "652dce919e1d5c80948167fad50b931f7521875d"),
+    ] {
+        assert_eq!(
+            compute(PATCH.as_bytes(), mode, Kind::Sha1)?,
+            Some(id(expected)),
+            "{mode:?}: computed with `git patch-id --<mode>`"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn stable_ids_do_not_depend_on_the_order_of_files() -> crate::Result {
+    let section = |name: &str| {
+        let start = PATCH.find(&format!("diff --git a/{name}")).expect("present");
+        let end = PATCH[start + 1..]
+            .find("diff --git")
+            .map_or(PATCH.len(), |end| start + 1 + end);
+        &PATCH[start..end]
+    };
+    let (a, x) = (section("a"), section("x"));
+    let (patch, reordered) = (format!("{a}{x}"), format!("{x}{a}"));
+    for mode in [Mode::Stable, Mode::Verbatim] {
+        assert_eq!(
+            compute(reordered.as_bytes(), mode, Kind::Sha1)?,
+            compute(patch.as_bytes(), mode, Kind::Sha1)?
+        );
+    }
+    assert_ne!(
+        compute(reordered.as_bytes(), Mode::Unstable, Kind::Sha1)?,
+        compute(patch.as_bytes(), Mode::Unstable, Kind::Sha1)?
+    );
+    Ok(())
+}
+
+#[test]
+fn no_diff_means_no_id() -> crate::Result {
+    assert_eq!(compute(b"", Mode::Stable, Kind::Sha1)?, None);
+    assert_eq!(
+        compute(
+            b"commit message only\n\n    diff in the message\n",
+            Mode::Unstable,
+            Kind::Sha1
+        )?,
+        None
+    );
+    Ok(())
+}
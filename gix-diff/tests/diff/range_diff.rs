@@ -0,0 +1,83 @@
+use gix_diff::{
+    blob::unified_diff::ContextSize,
+    range_diff::{pairs, write_diff, Pair, Patch, DEFAULT_CREATION_FACTOR},
+};
+
+fn patch(message: &str, diff: &str) -> Patch {
+    let mut text = format!(" ## Metadata ##\nAuthor: a <a@example.com>\n\n ## Commit message ##\n    {message}\n\n");
+    let diff_start = text.len();
+    text.push_str(diff);
+    Patch {
+        text: text.into(),
+        diff_start,
+    }
+}
+
+const FILE_A: &str = " ## a ##\n@@\n 1\n 2\n-3\n+three\n 4\n 5\n";
+const FILE_A_V2: &str = " ## a ##\n@@\n 1\n 2\n-3\n+THREE\n 4\n 5\n";
+const FILE_B: &str = " ## b (new) ##\n@@\n+1\n+2\n+3\n+4\n+5\n+6\n";
+const FILE_C: &str = " ## c (deleted) ##\n@@\n-x\n-y\n-z\n";
+
+#[test]
+fn exact_matches_and_removals() {
+    let old = [patch("a", FILE_A), patch("b", FILE_B), patch("c", FILE_C)];
+    let new = [patch("b reworded", FILE_B), patch("a", FILE_A)];
+    assert_eq!(
+        pairs(&old, &new, DEFAULT_CREATION_FACTOR),
+        [
+            Pair::Changed { old: 1, new: 0 },
+            Pair::Unchanged { old: 0, new: 1 },
+            Pair::Removed { old: 2 },
+        ],
+        "the same diff pairs commits even if the message changed, and removed commits are shown once their predecessors are"
+    );
+}
+
+#[test]
+fn creation_factor_decides_if_similar_commits_are_paired() {
+    let old = [patch("a", FILE_A)];
+    let new = [patch("a", FILE_A_V2), patch("b", FILE_B)];
+    assert_eq!(
+        pairs(&old, &new, DEFAULT_CREATION_FACTOR),
+        [Pair::Removed { old: 0 }, Pair::Added { new: 0 }, Pair::Added { new: 1 }],
+        "the changes to 'a' differ too much in relation to its size"
+    );
+    assert_eq!(
+        pairs(&old, &new, 300),
+        [Pair::Changed { old: 0, new: 0 }, Pair::Added { new: 1 }]
+    );
+}
+
+#[test]
+fn empty_ranges() {
+    let none: [Patch; 0] = [];
+    assert_eq!(pairs(&none, &none, DEFAULT_CREATION_FACTOR), []);
+    assert_eq!(
+        pairs(&[patch("a", FILE_A)], &none, DEFAULT_CREATION_FACTOR),
+        [Pair::Removed { old: 0 }]
+    );
+}
+
+#[test]
+fn diff_of_diffs() -> crate::Result {
+    let mut out = Vec::new();
+    write_diff(
+        &patch("a", FILE_A),
+        &patch("a, but louder", FILE_A_V2),
+        &mut out,
+        ContextSize::symmetrical(1),
+    )?;
+    insta::assert_snapshot!(String::from_utf8(out)?, @r"
+    @@ Metadata
+      ## Commit message ##
+    -    a
+    +    a, but louder
+     
+    @@ a
+     -3
+    -+three
+    ++THREE
+      4
+    ");
+    Ok(())
+}
@@ -7,7 +7,7 @@ use bstr::{BStr, BString, ByteSlice};
 
 use crate::{
     entry,
-    walk::{classify, readdir, Action, Context, Delegate, Error, ForDeletionMode, Options, Outcome},
+    walk::{classify, readdir, untracked_cache, Action, Context, Delegate, Error, ForDeletionMode, Options, Outcome},
     EntryRef,
 };
 
@@ -62,6 +62,16 @@ pub fn walk(
             .unwrap_or_else(|| worktree_root.join(ctx.pathspec.prefix_directory())),
     };
     let _span = gix_trace::coarse!("walk", root = ?root, worktree_root = ?worktree_root, options = ?options);
+    if ctx.untracked_cache.as_ref().is_some_and(|cache| {
+        !untracked_cache::is_usable(
+            cache,
+            &options,
+            ctx.excludes.is_some(),
+            ctx.pathspec.patterns().len() != 0,
+        )
+    }) {
+        ctx.untracked_cache = None;
+    }
     let (mut current, worktree_root_relative) = assure_no_symlink_in_root(worktree_root, &root)?;
     let mut out = Outcome::default();
     let mut buf = BString::default();
@@ -262,6 +262,37 @@ pub struct Context<'a> {
     ///
     /// If the `traversal_root` is not in the `worktree_root` passed to [walk()](crate::walk()).
     pub explicit_traversal_root: Option<&'a std::path::Path>,
+    /// If not `None`, the untracked cache to consult to avoid reading directories that didn't change since the cache
+    /// was filled, and to update with the contents of the directories that had to be read.
+    ///
+    /// Note that it will only be used if the [`Options`] are compatible with the way the cache was filled, see
+    /// [`UntrackedCacheContext`] for details.
+    pub untracked_cache: Option<UntrackedCacheContext<'a>>,
+}
+
+/// Everything needed to use the untracked cache during a [`walk()`](function::walk()).
+///
+/// The cache is only used if all of the following is true, and ignored otherwise:
+///
+/// * the [`dir_flags()`](gix_index::extension::UntrackedCache::dir_flags()) of the cache match the way
+///   untracked files are emitted, i.e. [`DIR_SHOW_OTHER_DIRECTORIES`](gix_index::extension::untracked_cache::DIR_SHOW_OTHER_DIRECTORIES)
+///   along with [`DIR_HIDE_EMPTY_DIRECTORIES`](gix_index::extension::untracked_cache::DIR_HIDE_EMPTY_DIRECTORIES)
+///   if [`Options::emit_untracked`] is [`EmissionMode::CollapseDirectory`], and no flag otherwise.
+/// * only untracked files are emitted, i.e. ignored, tracked, pruned, collapsed and empty entries are not.
+/// * the walk isn't [for deletion](Options::for_deletion) and doesn't know about [nested worktrees](Options::worktree_relative_worktree_dirs).
+/// * [excludes](Context::excludes) are available and the [pathspec](Context::pathspec) has no patterns.
+///
+/// ### Deviation
+///
+/// Like `git`, the id of the per-directory exclude file is checked for each directory to see if the cache
+/// is still valid, but unlike `git`, changes to the global excludes, i.e. `$GIT_DIR/info/exclude` and `core.excludesFile`,
+/// are not detected here. The caller has to use [`set_global_excludes()`](gix_index::extension::UntrackedCache::set_global_excludes())
+/// before the walk to invalidate the cache if they changed.
+pub struct UntrackedCacheContext<'a> {
+    /// The cache to use and update.
+    pub cache: &'a mut gix_index::extension::UntrackedCache,
+    /// The options to use when comparing the stat information of directories with the one stored in the cache.
+    pub stat: gix_index::entry::stat::Options,
 }
 
 /// Additional information collected as outcome of [`walk()`](function::walk()).
@@ -273,6 +304,11 @@ pub struct Outcome {
     pub returned_entries: usize,
     /// The amount of entries, prior to pathspecs filtering them out or otherwise excluding them.
     pub seen_entries: u32,
+    /// The amount of directories whose contents were obtained from the [untracked cache](Context::untracked_cache)
+    /// instead of reading them.
+    ///
+    /// Note that the cache doesn't know about ignored entries, so these aren't part of the `seen_entries` in that case.
+    pub untracked_cache_hits: u32,
 }
 
 /// The error returned by [`walk()`](function::walk()).
@@ -310,3 +346,4 @@ pub enum Error {
 mod classify;
 pub(crate) mod function;
 mod readdir;
+mod untracked_cache;
@@ -1,5 +1,6 @@
 use std::{
     borrow::Cow,
+    ffi::OsStr,
     path::{Path, PathBuf},
     sync::atomic::Ordering,
 };
@@ -13,7 +14,7 @@ use crate::{
     walk::{
         classify,
         function::{can_recurse, emit_entry},
-        Action, CollapsedEntriesEmissionMode, Context, Delegate,
+        untracked_cache, Action, CollapsedEntriesEmissionMode, Context, Delegate,
         EmissionMode::CollapseDirectory,
         Error, ForDeletionMode, Options, Outcome,
     },
@@ -38,92 +39,85 @@ pub(super) fn recursive(
     if ctx.should_interrupt.is_some_and(|flag| flag.load(Ordering::Relaxed)) {
         return Err(Error::Interrupted);
     }
-    out.read_dir_calls += 1;
-    let entries = gix_fs::read_dir(current, opts.precompose_unicode).map_err(|err| Error::ReadDir {
-        path: current.to_owned(),
-        source: err,
-    })?;
 
     let mut num_entries = 0;
     let mark = state.mark(may_collapse);
     let mut prevent_collapse = false;
-    for entry in entries {
-        let entry = entry.map_err(|err| Error::DirEntry {
-            parent_directory: current.to_owned(),
-            source: err,
-        })?;
-        // Important to count right away, otherwise the directory could be seen as empty even though it's not.
-        // That is, this should be independent of the kind.
-        num_entries += 1;
-
-        let prev_len = current_bstr.len();
-        if prev_len != 0 {
-            current_bstr.push(b'/');
+    match ctx
+        .untracked_cache
+        .as_mut()
+        .map(|cache| untracked_cache::lookup(cache, current, current_bstr.as_bstr(), ctx.index))
+    {
+        Some(untracked_cache::Lookup::Unchanged(entries)) => {
+            out.untracked_cache_hits += 1;
+            let dir = current.clone();
+            for (file_name, disk_kind) in entries {
+                num_entries += 1;
+                let action = visit_entry(
+                    gix_path::from_bstr(file_name.as_bstr()).as_os_str(),
+                    disk_kind,
+                    || {
+                        dir.join(gix_path::from_bstr(file_name.as_bstr()))
+                            .symlink_metadata()
+                            .ok()
+                            .map(|m| m.file_type().into())
+                    },
+                    current,
+                    current_bstr,
+                    ctx,
+                    opts,
+                    delegate,
+                    out,
+                    state,
+                    &mut prevent_collapse,
+                    None,
+                )?;
+                if action != Action::Continue {
+                    return Ok((action, prevent_collapse));
+                }
+            }
         }
-        let file_name = entry.file_name();
-        current_bstr.extend_from_slice(
-            gix_path::try_os_str_into_bstr(Cow::Borrowed(file_name.as_ref()))
-                .expect("no illformed UTF-8")
-                .as_ref(),
-        );
-        current.push(file_name);
-
-        let mut info = classify::path(
-            current,
-            current_bstr,
-            if prev_len == 0 { 0 } else { prev_len + 1 },
-            None,
-            || entry.file_type().ok().map(Into::into),
-            opts,
-            ctx,
-        )?;
+        lookup => {
+            let mut recorder = match lookup {
+                Some(untracked_cache::Lookup::Changed(recorder)) => Some(recorder),
+                _ => None,
+            };
+            out.read_dir_calls += 1;
+            let entries = gix_fs::read_dir(current, opts.precompose_unicode).map_err(|err| Error::ReadDir {
+                path: current.to_owned(),
+                source: err,
+            })?;
+            for entry in entries {
+                let entry = entry.map_err(|err| Error::DirEntry {
+                    parent_directory: current.to_owned(),
+                    source: err,
+                })?;
+                // Important to count right away, otherwise the directory could be seen as empty even though it's not.
+                // That is, this should be independent of the kind.
+                num_entries += 1;
 
-        if can_recurse(
-            current_bstr.as_bstr(),
-            info,
-            opts.for_deletion,
-            false, /* is root */
-            delegate,
-        ) {
-            let subdir_may_collapse = state.may_collapse(current);
-            let (action, subdir_prevent_collapse) = recursive(
-                subdir_may_collapse,
-                current,
-                current_bstr,
-                info,
-                ctx,
-                opts,
-                delegate,
-                out,
-                state,
-            )?;
-            prevent_collapse |= subdir_prevent_collapse;
-            if action != Action::Continue {
-                return Ok((action, prevent_collapse));
-            }
-        } else {
-            if opts.for_deletion == Some(ForDeletionMode::IgnoredDirectoriesCanHideNestedRepositories)
-                && info.disk_kind == Some(entry::Kind::Directory)
-                && matches!(info.status, Status::Ignored(_))
-            {
-                info.disk_kind = classify::maybe_upgrade_to_repository(
-                    info.disk_kind,
-                    true,
-                    false,
+                let action = visit_entry(
+                    entry.file_name().as_ref(),
+                    None,
+                    || entry.file_type().ok().map(Into::into),
                     current,
-                    ctx.current_dir,
-                    ctx.git_dir_realpath,
-                );
-            }
-            if !state.held_for_directory_collapse(current_bstr.as_bstr(), info, &opts) {
-                let action = emit_entry(Cow::Borrowed(current_bstr.as_bstr()), info, None, opts, out, delegate);
+                    current_bstr,
+                    ctx,
+                    opts,
+                    delegate,
+                    out,
+                    state,
+                    &mut prevent_collapse,
+                    recorder.as_mut(),
+                )?;
                 if action != Action::Continue {
                     return Ok((action, prevent_collapse));
                 }
             }
+            if let Some((recorder, cache)) = recorder.zip(ctx.untracked_cache.as_mut()) {
+                recorder.finish(cache.cache);
+            }
         }
-        current_bstr.truncate(prev_len);
-        current.pop();
     }
 
     let res = mark.reduce_held_entries(
@@ -141,6 +135,106 @@ pub(super) fn recursive(
     Ok((res, prevent_collapse))
 }
 
+/// Classify the entry with `file_name` in the `current` directory and recurse into it, or emit it.
+/// If `recorder` is set, the outcome is recorded for use in the untracked cache.
+#[allow(clippy::too_many_arguments)]
+fn visit_entry(
+    file_name: &OsStr,
+    disk_kind: Option<entry::Kind>,
+    on_demand_disk_kind: impl FnOnce() -> Option<entry::Kind>,
+    current: &mut PathBuf,
+    current_bstr: &mut BString,
+    ctx: &mut Context<'_>,
+    opts: Options<'_>,
+    delegate: &mut dyn Delegate,
+    out: &mut Outcome,
+    state: &mut State,
+    prevent_collapse: &mut bool,
+    recorder: Option<&mut untracked_cache::Recorder>,
+) -> Result<Action, Error> {
+    let prev_len = current_bstr.len();
+    if prev_len != 0 {
+        current_bstr.push(b'/');
+    }
+    let filename_start = if prev_len == 0 { 0 } else { prev_len + 1 };
+    current_bstr.extend_from_slice(
+        gix_path::try_os_str_into_bstr(Cow::Borrowed(file_name))
+            .expect("no illformed UTF-8")
+            .as_ref(),
+    );
+    current.push(file_name);
+
+    let mut info = classify::path(
+        current,
+        current_bstr,
+        filename_start,
+        disk_kind,
+        on_demand_disk_kind,
+        opts,
+        ctx,
+    )?;
+    if can_recurse(
+        current_bstr.as_bstr(),
+        info,
+        opts.for_deletion,
+        false, /* is root */
+        delegate,
+    ) {
+        let subdir_may_collapse = state.may_collapse(current);
+        let (action, subdir_prevent_collapse) = recursive(
+            subdir_may_collapse,
+            current,
+            current_bstr,
+            info,
+            ctx,
+            opts,
+            delegate,
+            out,
+            state,
+        )?;
+        *prevent_collapse |= subdir_prevent_collapse;
+        if action != Action::Continue {
+            return Ok(action);
+        }
+        if let Some(recorder) = recorder {
+            let is_collapsed_untracked_directory = state
+                .on_hold
+                .last()
+                .is_some_and(|e| e.status == Status::Untracked && e.property.is_none() && e.rela_path == *current_bstr);
+            recorder.record_sub_directory(
+                current_bstr[filename_start..].as_bstr(),
+                is_collapsed_untracked_directory,
+            );
+        }
+    } else {
+        if let Some(recorder) = recorder {
+            recorder.record(current_bstr[filename_start..].as_bstr(), &info);
+        }
+        if opts.for_deletion == Some(ForDeletionMode::IgnoredDirectoriesCanHideNestedRepositories)
+            && info.disk_kind == Some(entry::Kind::Directory)
+            && matches!(info.status, Status::Ignored(_))
+        {
+            info.disk_kind = classify::maybe_upgrade_to_repository(
+                info.disk_kind,
+                true,
+                false,
+                current,
+                ctx.current_dir,
+                ctx.git_dir_realpath,
+            );
+        }
+        if !state.held_for_directory_collapse(current_bstr.as_bstr(), info, &opts) {
+            let action = emit_entry(Cow::Borrowed(current_bstr.as_bstr()), info, None, opts, out, delegate);
+            if action != Action::Continue {
+                return Ok(action);
+            }
+        }
+    }
+    current_bstr.truncate(prev_len);
+    current.pop();
+    Ok(Action::Continue)
+}
+
 pub(super) struct State {
     /// The entries to hold back until it's clear what to do with them.
     pub on_hold: Vec<Entry>,
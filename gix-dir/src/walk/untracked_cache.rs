@@ -0,0 +1,222 @@
+use std::path::Path;
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_index::{
+    entry::{Flags, Stat},
+    extension::untracked_cache::{DIR_HIDE_EMPTY_DIRECTORIES, DIR_SHOW_OTHER_DIRECTORIES},
+    hash::ObjectId,
+};
+
+use crate::{
+    entry,
+    walk::{classify, EmissionMode, Options, UntrackedCacheContext},
+};
+
+/// Return `true` if the untracked cache in `ctx` can be used with the given `opts`.
+pub(super) fn is_usable(
+    ctx: &UntrackedCacheContext<'_>,
+    opts: &Options<'_>,
+    has_excludes: bool,
+    has_patterns: bool,
+) -> bool {
+    let expected_dir_flags = if opts.emit_untracked == EmissionMode::CollapseDirectory {
+        DIR_SHOW_OTHER_DIRECTORIES | DIR_HIDE_EMPTY_DIRECTORIES
+    } else {
+        0
+    };
+    ctx.cache.dir_flags() == expected_dir_flags
+        && opts.emit_ignored.is_none()
+        && opts.emit_collapsed.is_none()
+        && !opts.emit_pruned
+        && !opts.emit_tracked
+        && !opts.emit_empty_directories
+        && opts.for_deletion.is_none()
+        && opts
+            .worktree_relative_worktree_dirs
+            .map_or(true, std::collections::BTreeSet::is_empty)
+        && has_excludes
+        && !has_patterns
+}
+
+/// The result of looking up a directory in the untracked cache.
+pub(super) enum Lookup {
+    /// The directory didn't change, and these are the names of its entries along with their kind, if known.
+    Unchanged(Vec<(BString, Option<entry::Kind>)>),
+    /// The directory has to be read, and what's found has to be recorded to update the cache.
+    Changed(Recorder),
+}
+
+/// Collects the untracked entries of a directory that is read to update the cache with them once done.
+pub(super) struct Recorder {
+    dir_index: usize,
+    show_other_directories: bool,
+    stat: Option<Stat>,
+    untracked_entries: Vec<BString>,
+    sub_directories: Vec<BString>,
+}
+
+impl Recorder {
+    /// Keep track of the directory entry with `file_name` and the given classification `info`, which wasn't recursed into.
+    pub(super) fn record(&mut self, file_name: &BStr, info: &classify::Outcome) {
+        if info.status != entry::Status::Untracked {
+            return;
+        }
+        let mut name = BString::from(file_name);
+        if info.disk_kind.is_some_and(|kind| kind.is_dir()) {
+            name.push(b'/');
+        }
+        self.untracked_entries.push(name);
+    }
+
+    /// Keep track of the directory with `file_name` which was recursed into, and which `is_untracked` as a whole.
+    pub(super) fn record_sub_directory(&mut self, file_name: &BStr, is_untracked: bool) {
+        self.sub_directories.push(file_name.into());
+        // Like `git`, untracked directories are only listed if they are shown as a whole.
+        if is_untracked && self.show_other_directories {
+            let mut name = BString::from(file_name);
+            name.push(b'/');
+            self.untracked_entries.push(name);
+        }
+    }
+
+    /// Write everything we recorded into the cache, after the directory was read completely.
+    pub(super) fn finish(mut self, cache: &mut gix_index::extension::UntrackedCache) {
+        self.sub_directories.sort();
+        self.untracked_entries.sort();
+        let directories = cache.directories();
+        // Forget about sub-directories that we didn't see anymore.
+        let sub_directories: Vec<_> = directories[self.dir_index]
+            .sub_directories
+            .iter()
+            .copied()
+            .filter(|idx| self.sub_directories.binary_search(&directories[*idx].name).is_ok())
+            .collect();
+
+        let dir = &mut cache.directories_mut()[self.dir_index];
+        dir.sub_directories = sub_directories;
+        dir.untracked_entries = self.untracked_entries;
+        dir.stat = self.stat;
+        dir.check_only = false;
+    }
+}
+
+/// Find the directory at `dir` with `dir_rela_path` in the cache of `ctx` and see if its stat information and
+/// exclude file are unchanged.
+///
+/// If so, return the names of all of its entries based on the cached untracked entries and sub-directories, along
+/// with the tracked entries in the `index`.
+/// Otherwise, invalidate the cached directory so it can be filled again.
+pub(super) fn lookup(
+    ctx: &mut UntrackedCacheContext<'_>,
+    dir: &Path,
+    dir_rela_path: &BStr,
+    index: &gix_index::State,
+) -> Lookup {
+    let cache = &mut *ctx.cache;
+    let stat = gix_index::fs::Metadata::from_path_no_follow(dir)
+        .ok()
+        .and_then(|meta| Stat::from_fs(&meta).ok());
+    let exclude_file_oid = exclude_file_oid(dir, cache.exclude_filename_per_dir(), index.object_hash());
+    let dir_index = cache.directory_index_or_insert(dir_rela_path);
+
+    let cached = &cache.directories()[dir_index];
+    if cached.exclude_file_oid != exclude_file_oid || exclude_file_oid.is_none() {
+        // Like `git`, assume that all sub-directories are affected by the changed exclude file.
+        cache.invalidate_directory_tree(dir_index);
+    } else if let Some((cached_stat, stat)) = cached.stat.zip(stat) {
+        if !cached.check_only
+            && cached_stat.matches(&stat, ctx.stat)
+            && !cached_stat.is_racy(index.timestamp(), ctx.stat)
+        {
+            let entries = cached_entries(cache, dir_index, dir_rela_path, index);
+            if !entries.is_empty() {
+                return Lookup::Unchanged(entries);
+            }
+        }
+    }
+
+    let dir = &mut cache.directories_mut()[dir_index];
+    dir.stat = None;
+    dir.untracked_entries.clear();
+    dir.exclude_file_oid = exclude_file_oid;
+    Lookup::Changed(Recorder {
+        dir_index,
+        show_other_directories: cache.dir_flags() & DIR_SHOW_OTHER_DIRECTORIES != 0,
+        stat,
+        untracked_entries: Vec::new(),
+        sub_directories: Vec::new(),
+    })
+}
+
+/// Return the id of the exclude file with `filename` in `dir` as blob, the id of the empty blob if it doesn't exist
+/// like `git` does, or `None` if it couldn't be read.
+fn exclude_file_oid(dir: &Path, filename: &BStr, object_hash: gix_index::hash::Kind) -> Option<ObjectId> {
+    match std::fs::read(dir.join(gix_path::from_bstr(filename))) {
+        Ok(data) => gix_object::compute_hash(object_hash, gix_object::Kind::Blob, &data).ok(),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Some(ObjectId::empty_blob(object_hash)),
+        Err(_) => None,
+    }
+}
+
+fn cached_entries(
+    cache: &gix_index::extension::UntrackedCache,
+    dir_index: usize,
+    dir_rela_path: &BStr,
+    index: &gix_index::State,
+) -> Vec<(BString, Option<entry::Kind>)> {
+    let directories = cache.directories();
+    let dir = &directories[dir_index];
+    let mut out: Vec<_> = dir
+        .untracked_entries
+        .iter()
+        .map(|name| match name.strip_suffix(b"/") {
+            Some(dir_name) => (dir_name.into(), Some(entry::Kind::Directory)),
+            None => (name.clone(), None),
+        })
+        .chain(
+            dir.sub_directories
+                .iter()
+                .map(|idx| (directories[*idx].name.clone(), Some(entry::Kind::Directory))),
+        )
+        .collect();
+    tracked_entries(index, dir_rela_path, &mut out);
+
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    out.dedup_by(|cur, prev| {
+        let is_same = cur.0 == prev.0;
+        if is_same && prev.1.is_none() {
+            prev.1 = cur.1;
+        }
+        is_same
+    });
+    out
+}
+
+/// Add the names of all tracked entries directly within the directory at `dir_rela_path` to `out`, which is needed as
+/// they affect whether or not the directory can be collapsed.
+fn tracked_entries(index: &gix_index::State, dir_rela_path: &BStr, out: &mut Vec<(BString, Option<entry::Kind>)>) {
+    let mut prefix = BString::from(dir_rela_path);
+    if !prefix.is_empty() {
+        prefix.push(b'/');
+    }
+    let Some(entries) = index.prefixed_entries(prefix.as_ref()) else {
+        return;
+    };
+    let mut idx = 0;
+    while let Some(entry) = entries.get(idx) {
+        idx += 1;
+        if entry.flags.contains(Flags::SKIP_WORKTREE) {
+            continue;
+        }
+        let rest = &entry.path(index)[prefix.len()..];
+        match rest.find_byte(b'/') {
+            Some(pos) => {
+                let name = &rest[..pos];
+                let sub_directory_prefix = &entry.path(index)[..prefix.len() + pos + 1];
+                idx += entries[idx..].partition_point(|e| e.path(index).starts_with(sub_directory_prefix));
+                out.push((name.into(), Some(entry::Kind::Directory)));
+            }
+            None => out.push((rest.into(), None)),
+        }
+    }
+}
@@ -65,6 +65,7 @@ fn one_top_level_fifo() {
             read_dir_calls: 1,
             returned_entries: entries.len(),
             seen_entries: 2,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -97,6 +98,7 @@ fn fifo_in_traversal() {
             read_dir_calls: 3,
             returned_entries: entries.len(),
             seen_entries: 5,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -133,6 +135,7 @@ fn symlink_to_dir_can_be_excluded() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 9,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -168,6 +171,7 @@ fn symlink_to_dir_can_be_excluded() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 9,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -273,6 +277,7 @@ fn empty_root() -> crate::Result {
             read_dir_calls: 1,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -298,6 +303,7 @@ fn empty_root() -> crate::Result {
             read_dir_calls: 1,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -318,6 +324,7 @@ fn complex_empty() -> crate::Result {
             read_dir_calls: 9,
             returned_entries: entries.len(),
             seen_entries: 5,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -349,6 +356,7 @@ fn complex_empty() -> crate::Result {
             read_dir_calls: 9,
             returned_entries: entries.len(),
             seen_entries: 5,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -375,6 +383,7 @@ fn complex_empty() -> crate::Result {
             read_dir_calls: 9,
             returned_entries: entries.len(),
             seen_entries: 9,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -417,6 +426,7 @@ fn ignored_with_prefix_pathspec_collapses_just_like_untracked() -> crate::Result
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 6,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -450,6 +460,7 @@ fn ignored_with_prefix_pathspec_collapses_just_like_untracked() -> crate::Result
             read_dir_calls: 4,
             returned_entries: entries.len(),
             seen_entries: 8,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -491,6 +502,7 @@ fn ignored_dir_with_cwd_handling() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 3,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -525,6 +537,7 @@ fn ignored_dir_with_cwd_handling() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 2,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -561,7 +574,8 @@ fn ignored_dir_with_cwd_handling() -> crate::Result {
         walk::Outcome {
             read_dir_calls: 8,
             returned_entries: entries.len(),
-            seen_entries: 26
+            seen_entries: 26,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -606,6 +620,7 @@ fn ignored_with_cwd_handling() -> crate::Result {
             read_dir_calls: 1,
             returned_entries: entries.len(),
             seen_entries: 3,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -641,6 +656,7 @@ fn ignored_with_cwd_handling() -> crate::Result {
             read_dir_calls: 5,
             returned_entries: entries.len(),
             seen_entries: 7,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -685,6 +701,7 @@ fn only_untracked_with_cwd_handling() -> crate::Result {
             read_dir_calls: 3,
             returned_entries: entries.len(),
             seen_entries: 9,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -723,6 +740,7 @@ fn only_untracked_with_cwd_handling() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 5,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -757,6 +775,7 @@ fn only_untracked_with_cwd_handling() -> crate::Result {
             read_dir_calls: 3,
             returned_entries: entries.len(),
             seen_entries: 8,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -797,6 +816,7 @@ fn only_untracked_with_cwd_handling() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 4,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -839,6 +859,7 @@ fn only_untracked_with_pathspec() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 5,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -871,6 +892,7 @@ fn only_untracked_with_pathspec() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 5,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -903,6 +925,7 @@ fn only_untracked_with_prefix_deletion() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 5,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -929,6 +952,7 @@ fn only_untracked_with_prefix_deletion() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 5,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -949,6 +973,7 @@ fn only_untracked() -> crate::Result {
             read_dir_calls: 3,
             returned_entries: entries.len(),
             seen_entries: 7,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -971,6 +996,7 @@ fn only_untracked() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 3,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -999,6 +1025,7 @@ fn only_untracked() -> crate::Result {
             read_dir_calls: 3,
             returned_entries: entries.len(),
             seen_entries: 7 + 2,
+            untracked_cache_hits: 0,
         },
         "There are 2 extra directories that we fold into, but ultimately discard"
     );
@@ -1039,6 +1066,7 @@ fn only_untracked_explicit_pathspec_selection() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 3,
+            untracked_cache_hits: 0,
         },
     );
     assert_eq!(
@@ -1073,6 +1101,7 @@ fn only_untracked_explicit_pathspec_selection() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 3,
+            untracked_cache_hits: 0,
         },
         "no collapsing happens"
     );
@@ -1108,6 +1137,7 @@ fn only_untracked_explicit_pathspec_selection() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 2 + 3,
+            untracked_cache_hits: 0,
         },
         "collapsing happens just like Git"
     );
@@ -1129,6 +1159,7 @@ fn expendable_and_precious() {
             read_dir_calls: 6,
             returned_entries: entries.len(),
             seen_entries: 18,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -1174,6 +1205,7 @@ fn expendable_and_precious() {
             read_dir_calls: 6,
             returned_entries: entries.len(),
             seen_entries: 18 + 2,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -1219,6 +1251,7 @@ fn expendable_and_precious() {
             read_dir_calls: 6,
             returned_entries: entries.len(),
             seen_entries: 16 + 2,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -1242,6 +1275,7 @@ fn subdir_untracked() -> crate::Result {
             read_dir_calls: 3,
             returned_entries: entries.len(),
             seen_entries: 7,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(entries, [entry("d/d/a", Untracked, File)]);
@@ -1261,6 +1295,7 @@ fn subdir_untracked() -> crate::Result {
             read_dir_calls: 3,
             returned_entries: entries.len(),
             seen_entries: 7,
+            untracked_cache_hits: 0,
         },
         "pruning has no actual effect here as there is no extra directories that could be avoided"
     );
@@ -1283,6 +1318,7 @@ fn subdir_untracked() -> crate::Result {
             read_dir_calls: 3,
             returned_entries: entries.len(),
             seen_entries: 7 + 1,
+            untracked_cache_hits: 0,
         },
         "there is a folded directory we added"
     );
@@ -1301,6 +1337,7 @@ fn only_untracked_from_subdir() -> crate::Result {
             read_dir_calls: 1,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -1338,6 +1375,7 @@ fn untracked_and_ignored_pathspec_guidance() -> crate::Result {
                 read_dir_calls: 1,
                 returned_entries: entries.len(),
                 seen_entries: 1,
+                untracked_cache_hits: 0,
             },
             "we have to read the parent directory, just like git, as we can't assume a directory"
         );
@@ -1378,6 +1416,7 @@ fn untracked_and_ignored_for_deletion_negative_wildcard_spec() -> crate::Result
             read_dir_calls: 5,
             returned_entries: entries.len(),
             seen_entries: 23,
+            untracked_cache_hits: 0,
         },
     );
     assert_eq!(
@@ -1433,6 +1472,7 @@ fn untracked_and_ignored_for_deletion_positive_wildcard_spec() -> crate::Result
             read_dir_calls: 8,
             returned_entries: entries.len(),
             seen_entries: 27,
+            untracked_cache_hits: 0,
         },
     );
     assert_eq!(
@@ -1486,6 +1526,7 @@ fn untracked_and_ignored_for_deletion_nonmatching_wildcard_spec() -> crate::Resu
             read_dir_calls: 8,
             returned_entries: entries.len(),
             seen_entries: 28,
+            untracked_cache_hits: 0,
         },
     );
     assert_eq!(
@@ -1632,6 +1673,7 @@ fn expendable_and_precious_in_ignored_dir_with_pathspec() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 5,
+            untracked_cache_hits: 0,
         },
     );
 
@@ -1674,6 +1716,7 @@ fn expendable_and_precious_in_ignored_dir_with_pathspec() -> crate::Result {
             read_dir_calls: 9,
             returned_entries: entries.len(),
             seen_entries: 19,
+            untracked_cache_hits: 0,
         },
     );
 
@@ -1728,6 +1771,7 @@ fn expendable_and_precious_in_ignored_dir_with_pathspec() -> crate::Result {
             read_dir_calls: 9,
             returned_entries: entries.len(),
             seen_entries: 19,
+            untracked_cache_hits: 0,
         },
     );
 
@@ -1776,6 +1820,7 @@ fn untracked_and_ignored() -> crate::Result {
             read_dir_calls: 5,
             returned_entries: entries.len(),
             seen_entries: 21,
+            untracked_cache_hits: 0,
         },
         "some untracked ones are hidden by default"
     );
@@ -1822,6 +1867,7 @@ fn untracked_and_ignored() -> crate::Result {
             read_dir_calls: 5,
             returned_entries: entries.len(),
             seen_entries: 21,
+            untracked_cache_hits: 0,
         },
         "basically the same result…"
     );
@@ -1854,6 +1900,7 @@ fn untracked_and_ignored() -> crate::Result {
             read_dir_calls: 5,
             returned_entries: entries.len(),
             seen_entries: 21 + 1,
+            untracked_cache_hits: 0,
         },
         "we still encounter the same amount of entries, and 1 folded directory"
     );
@@ -1880,6 +1927,7 @@ fn untracked_and_ignored() -> crate::Result {
             read_dir_calls: 5,
             returned_entries: entries.len(),
             seen_entries: 21 + 2,
+            untracked_cache_hits: 0,
         },
         "some untracked ones are hidden by default, folded directories"
     );
@@ -1922,6 +1970,7 @@ fn untracked_and_ignored() -> crate::Result {
             read_dir_calls: 5,
             returned_entries: entries.len(),
             seen_entries: 21 + 3,
+            untracked_cache_hits: 0,
         },
         "some untracked ones are hidden by default, and folded directories"
     );
@@ -1977,6 +2026,7 @@ fn untracked_and_ignored_collapse_handling_mixed() -> crate::Result {
             read_dir_calls: 1,
             returned_entries: entries.len(),
             seen_entries: 4,
+            untracked_cache_hits: 0,
         },
         "it has to read 'd/d' as 'd/d/b.o' isn't a directory candidate"
     );
@@ -2015,6 +2065,7 @@ fn untracked_and_ignored_collapse_handling_mixed() -> crate::Result {
                 read_dir_calls: 4,
                 returned_entries: entries.len(),
                 seen_entries: 21,
+                untracked_cache_hits: 0,
             },
         );
 
@@ -2065,7 +2116,8 @@ fn untracked_and_ignored_collapse_handling_mixed_with_prefix() -> crate::Result
         walk::Outcome {
             read_dir_calls: 3,
             returned_entries: entries.len(),
-            seen_entries: 11
+            seen_entries: 11,
+            untracked_cache_hits: 0,
         },
         "this is not a directory, so the prefix is only 'd', not 'd/d'"
     );
@@ -2107,6 +2159,7 @@ fn untracked_and_ignored_collapse_handling_mixed_with_prefix() -> crate::Result
                 read_dir_calls: 2,
                 returned_entries: entries.len(),
                 seen_entries: 6,
+                untracked_cache_hits: 0,
             },
         );
 
@@ -2157,7 +2210,8 @@ fn untracked_and_ignored_collapse_handling_for_deletion_with_wildcards() -> crat
         walk::Outcome {
             read_dir_calls: 8,
             returned_entries: entries.len(),
-            seen_entries: 26
+            seen_entries: 26,
+            untracked_cache_hits: 0,
         },
     );
     assert_eq!(
@@ -2203,7 +2257,8 @@ fn untracked_and_ignored_collapse_handling_for_deletion_with_wildcards() -> crat
         walk::Outcome {
             read_dir_calls: 8,
             returned_entries: entries.len(),
-            seen_entries: 28
+            seen_entries: 28,
+            untracked_cache_hits: 0,
         },
     );
     assert_eq!(
@@ -2261,6 +2316,7 @@ fn untracked_and_ignored_collapse_handling_for_deletion_with_prefix_wildcards()
             read_dir_calls: 1,
             returned_entries: entries.len(),
             seen_entries: 2,
+            untracked_cache_hits: 0,
         },
     );
     assert_eq!(
@@ -2293,6 +2349,7 @@ fn untracked_and_ignored_collapse_handling_for_deletion_mixed() -> crate::Result
             read_dir_calls: 5,
             returned_entries: entries.len(),
             seen_entries: 21,
+            untracked_cache_hits: 0,
         },
     );
 
@@ -2322,6 +2379,7 @@ fn untracked_and_ignored_collapse_handling_for_deletion_mixed() -> crate::Result
             read_dir_calls: 5,
             returned_entries: entries.len(),
             seen_entries: 24,
+            untracked_cache_hits: 0,
         },
     );
 
@@ -2370,6 +2428,7 @@ fn untracked_and_ignored_collapse_handling_for_deletion_mixed() -> crate::Result
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 6,
+            untracked_cache_hits: 0,
         },
     );
 
@@ -2412,6 +2471,7 @@ fn untracked_and_ignored_collapse_handling_for_deletion_mixed() -> crate::Result
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 5,
+            untracked_cache_hits: 0,
         },
     );
 
@@ -2451,6 +2511,7 @@ fn untracked_and_ignored_collapse_handling_for_deletion_mixed() -> crate::Result
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 5,
+            untracked_cache_hits: 0,
         },
     );
 
@@ -2488,6 +2549,7 @@ fn untracked_and_ignored_collapse_handling_for_deletion_mixed() -> crate::Result
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 6,
+            untracked_cache_hits: 0,
         },
     );
 
@@ -2527,6 +2589,7 @@ fn untracked_and_ignored_collapse_handling_for_deletion_mixed() -> crate::Result
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 4,
+            untracked_cache_hits: 0,
         },
     );
 
@@ -2587,6 +2650,7 @@ fn precious_are_not_expendable() {
             read_dir_calls: 3,
             returned_entries: entries.len(),
             seen_entries: 10,
+            untracked_cache_hits: 0,
         },
     );
 
@@ -2629,6 +2693,7 @@ fn precious_are_not_expendable() {
             read_dir_calls: 3,
             returned_entries: entries.len(),
             seen_entries: 10,
+            untracked_cache_hits: 0,
         },
         "'d' is assumed to be a file, hence it's stripped to its base '', yielding one more call."
     );
@@ -2668,6 +2733,7 @@ fn precious_are_not_expendable() {
                 read_dir_calls: 2,
                 returned_entries: entries.len(),
                 seen_entries: 7,
+                untracked_cache_hits: 0,
             },
             "{equivalent_pathspec}: should yield same result, they also see the 'd' prefix directory"
         );
@@ -2703,6 +2769,7 @@ fn precious_are_not_expendable() {
             read_dir_calls: 3,
             returned_entries: entries.len(),
             seen_entries: 9,
+            untracked_cache_hits: 0,
         },
     );
 
@@ -2751,6 +2818,7 @@ fn decomposed_unicode_in_directory_is_returned_precomposed() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -2777,6 +2845,7 @@ fn decomposed_unicode_in_directory_is_returned_precomposed() -> crate::Result {
             read_dir_calls: 1,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         },
         "note how it starts directly in the right repository"
     );
@@ -2811,6 +2880,7 @@ fn worktree_root_can_be_symlink() -> crate::Result {
             read_dir_calls: 0,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -2835,6 +2905,7 @@ fn root_may_not_go_through_dot_git() -> crate::Result {
                 read_dir_calls: 0,
                 returned_entries: entries.len(),
                 seen_entries: 1,
+                untracked_cache_hits: 0,
             }
         );
         assert_eq!(
@@ -2880,6 +2951,7 @@ fn root_at_submodule_repository_allows_walk() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 3,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -2921,6 +2993,7 @@ fn root_in_submodule_repository_allows_walk() -> crate::Result {
             read_dir_calls: 1,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -2962,6 +3035,7 @@ fn root_in_submodule_from_superproject_repository_allows_walk() -> crate::Result
             read_dir_calls: 1,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -3003,6 +3077,7 @@ fn root_enters_directory_with_dot_git_in_reconfigured_worktree_tracked() -> crat
             read_dir_calls: 0,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -3037,6 +3112,7 @@ fn root_enters_directory_with_dot_git_in_reconfigured_worktree_tracked() -> crat
             read_dir_calls: 0,
             returned_entries: 0,
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -3107,6 +3183,7 @@ fn root_may_not_go_through_nested_repository_unless_enabled() -> crate::Result {
             read_dir_calls: 0,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -3135,6 +3212,7 @@ fn root_may_not_go_through_submodule() -> crate::Result {
             read_dir_calls: 0,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         },
     );
     assert_eq!(
@@ -3156,6 +3234,7 @@ fn walk_with_submodule() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 4,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -3189,6 +3268,7 @@ fn root_that_is_tracked_file_is_returned() -> crate::Result {
             read_dir_calls: 0,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -3218,6 +3298,7 @@ fn root_that_is_untracked_file_is_returned() -> crate::Result {
             read_dir_calls: 0,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -3254,6 +3335,7 @@ fn root_can_be_pruned_early_with_pathspec() -> crate::Result {
             read_dir_calls: 0,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -3275,6 +3357,7 @@ fn submodules() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 5,
+            untracked_cache_hits: 0,
         }
     );
     let expected_content = [
@@ -3412,6 +3495,7 @@ fn file_root_is_shown_if_pathspec_matches_exactly() -> crate::Result {
             read_dir_calls: 0,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         },
     );
 
@@ -3443,6 +3527,7 @@ fn root_that_is_tracked_and_ignored_is_considered_tracked() -> crate::Result {
             read_dir_calls: 0,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -3477,6 +3562,7 @@ fn root_with_dir_that_is_tracked_and_ignored() -> crate::Result {
                 read_dir_calls: 2,
                 returned_entries: entries.len(),
                 seen_entries: 3,
+                untracked_cache_hits: 0,
             }
         );
 
@@ -3518,6 +3604,7 @@ fn empty_and_nested_untracked() -> crate::Result {
                 read_dir_calls: 3,
                 returned_entries: entries.len(),
                 seen_entries: 2,
+                untracked_cache_hits: 0,
             }
         );
 
@@ -3548,6 +3635,7 @@ fn empty_and_nested_untracked() -> crate::Result {
                 read_dir_calls: 3,
                 returned_entries: entries.len(),
                 seen_entries: 3,
+                untracked_cache_hits: 0,
             }
         );
 
@@ -3594,6 +3682,7 @@ fn root_that_is_ignored_is_listed_for_files_and_directories() -> crate::Result {
                     read_dir_calls: 0,
                     returned_entries: entries.len(),
                     seen_entries: 1,
+                    untracked_cache_hits: 0,
                 }
             );
 
@@ -3756,6 +3845,7 @@ fn nested_repos_in_ignored_directories() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 4,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -3787,6 +3877,7 @@ fn nested_repos_in_ignored_directories() -> crate::Result {
             read_dir_calls: 4,
             returned_entries: entries.len(),
             seen_entries: 6,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -3820,6 +3911,7 @@ fn nested_repos_in_ignored_directories() -> crate::Result {
             read_dir_calls: 4,
             returned_entries: entries.len(),
             seen_entries: 7,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -3875,6 +3967,7 @@ fn decomposed_unicode_in_root_is_returned_precomposed() -> crate::Result {
             read_dir_calls: 0,
             returned_entries: entries.len(),
             seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -3932,6 +4025,7 @@ fn untracked_and_ignored_collapse_mix() {
             read_dir_calls: 4,
             returned_entries: entries.len(),
             seen_entries: 7,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -3966,6 +4060,7 @@ fn untracked_and_ignored_collapse_mix() {
             read_dir_calls: 4,
             returned_entries: entries.len(),
             seen_entries: 8,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -4000,6 +4095,7 @@ fn untracked_and_ignored_collapse_mix() {
             read_dir_calls: 4,
             returned_entries: entries.len(),
             seen_entries: 8,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -4040,6 +4136,7 @@ fn root_cannot_pass_through_case_altered_capital_dot_git_if_case_insensitive() -
                 read_dir_calls: 0,
                 returned_entries: entries.len(),
                 seen_entries: 1,
+                untracked_cache_hits: 0,
             }
         );
         assert_eq!(
@@ -4102,6 +4199,7 @@ fn partial_checkout_cone_and_non_one() -> crate::Result {
                 read_dir_calls: 0,
                 returned_entries: entries.len(),
                 seen_entries: 1,
+                untracked_cache_hits: 0,
             }
         );
         assert_eq!(
@@ -4147,6 +4245,7 @@ fn type_mismatch() {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 3,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -4189,6 +4288,7 @@ fn type_mismatch() {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 3 + 1,
+            untracked_cache_hits: 0,
         }
     );
 
@@ -4234,6 +4334,7 @@ fn type_mismatch_ignore_case() {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 3,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -4274,6 +4375,7 @@ fn type_mismatch_ignore_case() {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 3 + 1,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -4318,6 +4420,7 @@ fn type_mismatch_ignore_case_clash_dir_is_file() {
             read_dir_calls: 1,
             returned_entries: entries.len(),
             seen_entries: 2,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -4359,6 +4462,7 @@ fn type_mismatch_ignore_case_clash_file_is_dir() {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 2,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -4380,6 +4484,7 @@ fn top_level_slash_with_negations() -> crate::Result {
                 read_dir_calls: 2,
                 returned_entries: entries.len(),
                 seen_entries: 5,
+                untracked_cache_hits: 0,
             }
         );
         assert_eq!(
@@ -4412,6 +4517,7 @@ fn top_level_slash_with_negations() -> crate::Result {
                 read_dir_calls: 2,
                 returned_entries: entries.len(),
                 seen_entries: 5,
+                untracked_cache_hits: 0,
             }
         );
         assert_eq!(
@@ -4437,6 +4543,7 @@ fn subdir_slash_with_negations() -> crate::Result {
                 read_dir_calls: 3,
                 returned_entries: entries.len(),
                 seen_entries: 5,
+                untracked_cache_hits: 0,
             }
         );
         assert_eq!(
@@ -4469,6 +4576,7 @@ fn subdir_slash_with_negations() -> crate::Result {
                 read_dir_calls: 3,
                 returned_entries: entries.len(),
                 seen_entries: 5,
+                untracked_cache_hits: 0,
             }
         );
         assert_eq!(
@@ -4493,6 +4601,7 @@ fn one_ignored_submodule() -> crate::Result {
             read_dir_calls: 1,
             returned_entries: entries.len(),
             seen_entries: 5,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -4516,7 +4625,8 @@ fn one_ignored_submodule() -> crate::Result {
         walk::Outcome {
             read_dir_calls: 0,
             returned_entries: entries.len(),
-            seen_entries: 1
+            seen_entries: 1,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -4537,6 +4647,7 @@ fn ignored_sub_repo() -> crate::Result {
             read_dir_calls: 1,
             returned_entries: entries.len(),
             seen_entries: 3,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -4571,6 +4682,7 @@ fn ignored_sub_repo() -> crate::Result {
                     read_dir_calls: 1,
                     returned_entries: entries.len(),
                     seen_entries: 3,
+                    untracked_cache_hits: 0,
                 }
             );
             assert_eq!(
@@ -4596,6 +4708,7 @@ fn in_repo_worktree() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 4,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -4626,6 +4739,7 @@ fn in_repo_worktree() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 4,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -4652,6 +4766,7 @@ fn in_repo_hidden_worktree() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 4,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -4683,6 +4798,7 @@ fn in_repo_hidden_worktree() -> crate::Result {
             read_dir_calls: 2,
             returned_entries: entries.len(),
             seen_entries: 4,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(
@@ -4721,6 +4837,7 @@ fn in_repo_hidden_worktree() -> crate::Result {
                     read_dir_calls: 4,
                     returned_entries: entries.len(),
                     seen_entries: 5,
+                    untracked_cache_hits: 0,
                 }
             );
             assert_eq!(
@@ -4738,3 +4855,186 @@ fn in_repo_hidden_worktree() -> crate::Result {
     }
     Ok(())
 }
+
+mod untracked_cache {
+    use gix_dir::{
+        entry::{Kind::*, Status::*},
+        walk,
+        walk::EmissionMode::*,
+    };
+    use gix_index::extension::{
+        untracked_cache::{DIR_HIDE_EMPTY_DIRECTORIES, DIR_SHOW_OTHER_DIRECTORIES},
+        UntrackedCache,
+    };
+    use pretty_assertions::assert_eq;
+
+    use crate::walk_utils::{entry, options, try_collect_filtered_opts_collect, Entries, Options};
+
+    fn collect_with_cache(
+        root: &std::path::Path,
+        cache: &mut UntrackedCache,
+        options: walk::Options<'_>,
+    ) -> crate::Result<(walk::Outcome, Entries)> {
+        let ((out, _root), entries) = try_collect_filtered_opts_collect(
+            root,
+            None,
+            |keep, ctx| gix_dir::walk(root, ctx, options, keep),
+            None::<&str>,
+            Options {
+                untracked_cache: Some(cache),
+                ..Default::default()
+            },
+        )?;
+        Ok((out, entries))
+    }
+
+    #[test]
+    fn unchanged_directories_are_not_read() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("many.sh")?;
+        let root = tmp.path().join("subdir-untracked-and-ignored");
+        let mut cache = UntrackedCache::new("", ".gitignore", 0);
+
+        let (out, entries) = collect_with_cache(&root, &mut cache, options())?;
+        let expected_entries = [entry(".gitignore", Untracked, File), entry("d/d/a", Untracked, File)];
+        assert_eq!(entries, expected_entries);
+        assert_eq!(out.untracked_cache_hits, 0, "the cache starts out empty");
+        let read_dir_calls_without_cache = out.read_dir_calls;
+
+        let (out, entries) = collect_with_cache(&root, &mut cache, options())?;
+        assert_eq!(entries, expected_entries, "the result is the same when using the cache");
+        assert_eq!(
+            out,
+            walk::Outcome {
+                read_dir_calls: 1,
+                returned_entries: entries.len(),
+                seen_entries: 8,
+                untracked_cache_hits: read_dir_calls_without_cache - 1,
+            },
+            "only `objs/sub` with nothing but ignored files is read again, as there is nothing cached for it, \
+             and ignored files aren't seen anymore"
+        );
+
+        std::fs::write(root.join("d").join("new"), b"")?;
+        let (out, entries) = collect_with_cache(&root, &mut cache, options())?;
+        assert_eq!(
+            entries,
+            [
+                entry(".gitignore", Untracked, File),
+                entry("d/d/a", Untracked, File),
+                entry("d/new", Untracked, File)
+            ],
+            "the changed directory is read again"
+        );
+        assert_eq!(
+            out.read_dir_calls, 2,
+            "`d` is read, along with `objs/sub` which is never cached"
+        );
+
+        let (out, entries) = collect_with_cache(&root, &mut cache, options())?;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(out.read_dir_calls, 1, "now the new file is cached as well");
+
+        std::fs::write(root.join(".gitignore"), b"*.o\ngenerated/\nnew\n")?;
+        let (out, entries) = collect_with_cache(&root, &mut cache, options())?;
+        assert_eq!(
+            entries, expected_entries,
+            "a changed exclude file invalidates all directories below it"
+        );
+        assert_eq!(out.read_dir_calls, read_dir_calls_without_cache);
+        assert_eq!(out.untracked_cache_hits, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn collapsed_directories_are_cached() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("many.sh")?;
+        let root = tmp.path().join("untracked-and-ignored-for-collapse");
+        let mut cache = UntrackedCache::new(
+            "",
+            ".gitignore",
+            DIR_SHOW_OTHER_DIRECTORIES | DIR_HIDE_EMPTY_DIRECTORIES,
+        );
+        let opts = walk::Options {
+            emit_untracked: CollapseDirectory,
+            ..options()
+        };
+
+        let (out, entries) = collect_with_cache(&root, &mut cache, opts)?;
+        let expected_entries = [
+            entry(".gitignore", Untracked, File),
+            entry("mixed", Untracked, Directory),
+            entry("untracked", Untracked, Directory),
+        ];
+        assert_eq!(entries, expected_entries);
+        assert_eq!(out.untracked_cache_hits, 0);
+        assert_eq!(
+            cache.directories()[0].untracked_entries,
+            [".gitignore", "mixed/", "untracked/"],
+            "untracked directories are listed as a whole"
+        );
+
+        let (out, entries) = collect_with_cache(&root, &mut cache, opts)?;
+        assert_eq!(entries, expected_entries);
+        assert_eq!(
+            out.read_dir_calls, 1,
+            "only `ignored-inside` is read as it has no untracked files"
+        );
+        assert_eq!(out.untracked_cache_hits, 3);
+
+        let root = tmp.path().join("subdir-untracked");
+        let mut cache = UntrackedCache::new(
+            "",
+            ".gitignore",
+            DIR_SHOW_OTHER_DIRECTORIES | DIR_HIDE_EMPTY_DIRECTORIES,
+        );
+        let (out, entries) = collect_with_cache(&root, &mut cache, opts)?;
+        let expected_entries = [entry("d/d", Untracked, Directory)];
+        assert_eq!(entries, expected_entries);
+        assert_eq!(out.untracked_cache_hits, 0);
+
+        let (out, entries) = collect_with_cache(&root, &mut cache, opts)?;
+        assert_eq!(
+            entries, expected_entries,
+            "tracked files are taken from the index, and prevent directories from being collapsed"
+        );
+        assert_eq!(out.read_dir_calls, 0);
+        assert_eq!(out.untracked_cache_hits, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn incompatible_options_ignore_the_cache() -> crate::Result {
+        let tmp = gix_testtools::scripted_fixture_writable("many.sh")?;
+        let root = tmp.path().join("untracked-and-ignored-for-collapse");
+        let mut cache = UntrackedCache::new("", ".gitignore", 0);
+
+        let (out, _entries) = collect_with_cache(
+            &root,
+            &mut cache,
+            walk::Options {
+                emit_untracked: CollapseDirectory,
+                ..options()
+            },
+        )?;
+        assert_eq!(out.untracked_cache_hits, 0);
+        assert!(
+            cache.directories().is_empty(),
+            "the flags of the cache don't match the way untracked files are emitted"
+        );
+
+        let (out, _entries) = collect_with_cache(
+            &root,
+            &mut cache,
+            walk::Options {
+                emit_ignored: Some(Matching),
+                ..options()
+            },
+        )?;
+        assert_eq!(out.untracked_cache_hits, 0);
+        assert!(
+            cache.directories().is_empty(),
+            "ignored files aren't cached, so the cache can't be used"
+        );
+        Ok(())
+    }
+}
@@ -283,15 +283,26 @@ pub fn try_collect_filtered_opts(
         fresh_index,
         git_dir,
         should_interrupt,
+        untracked_cache,
     }: Options<'_>,
 ) -> Result<(walk::Outcome, PathBuf), walk::Error> {
     let git_dir = worktree_root.join(git_dir.unwrap_or(".git"));
+    // Pretend the index was written just now so directories that were changed before aren't racy.
+    let index_timestamp = if untracked_cache.is_some() {
+        std::time::SystemTime::now()
+    } else {
+        std::time::UNIX_EPOCH
+    };
     let mut index = std::fs::read(git_dir.join("index")).ok().map_or_else(
-        || gix_index::State::new(gix_index::hash::Kind::Sha1),
+        || {
+            let mut state = gix_index::State::new(gix_index::hash::Kind::Sha1);
+            state.set_timestamp(index_timestamp.into());
+            state
+        },
         |bytes| {
             gix_index::State::from_bytes(
                 &bytes,
-                std::time::UNIX_EPOCH.into(),
+                index_timestamp.into(),
                 gix_index::hash::Kind::Sha1,
                 Default::default(),
             )
@@ -358,6 +369,13 @@ pub fn try_collect_filtered_opts(
             objects: &gix_object::find::Never,
             explicit_traversal_root,
             should_interrupt,
+            untracked_cache: untracked_cache.map(|cache| walk::UntrackedCacheContext {
+                cache,
+                stat: gix_index::entry::stat::Options {
+                    use_nsec: true,
+                    ..Default::default()
+                },
+            }),
         },
     )
 }
@@ -366,6 +384,7 @@ pub struct Options<'a> {
     pub fresh_index: bool,
     pub git_dir: Option<&'a str>,
     pub should_interrupt: Option<&'a AtomicBool>,
+    pub untracked_cache: Option<&'a mut gix_index::extension::UntrackedCache>,
 }
 
 impl<'a> Options<'a> {
@@ -383,8 +402,9 @@ impl Default for Options<'_> {
             fresh_index: true,
             git_dir: None,
             should_interrupt: None,
+            untracked_cache: None,
         }
     }
 }
 
-type Entries = Vec<(Entry, Option<entry::Status>)>;
+pub type Entries = Vec<(Entry, Option<entry::Status>)>;
@@ -127,6 +127,24 @@ pub fn num_threads(thread_limit: Option<usize>) -> usize {
     thread_limit.map_or(logical_cores, |l| if l == 0 { logical_cores } else { l })
 }
 
+/// Always returns 1, available when the `parallel` feature toggle is unset.
+#[cfg(not(feature = "parallel"))]
+pub fn num_threads_for_cost(_num_items: usize, _thread_cost: usize, _thread_limit: Option<usize>) -> usize {
+    1
+}
+
+/// Return the amount of threads to use for processing `num_items` if each thread should get at least `thread_cost` items,
+/// which is the amount of items for which it's worth spawning a thread, while using no more than [`num_threads(thread_limit)`](num_threads()) threads.
+///
+/// The returned amount of threads is at least 1, indicating that work should be performed on the current thread.
+/// It can be passed as `thread_limit` to [`in_parallel()`] or [`optimize_chunk_size_and_thread_limit()`].
+///
+/// Only available with the `parallel` feature toggle set.
+#[cfg(feature = "parallel")]
+pub fn num_threads_for_cost(num_items: usize, thread_cost: usize, thread_limit: Option<usize>) -> usize {
+    (num_items / thread_cost.max(1)).clamp(1, num_threads(thread_limit))
+}
+
 /// Run [`in_parallel()`] only if the given `condition()` returns true when eagerly evaluated.
 ///
 /// For parameters, see the documentation of [`in_parallel()`]
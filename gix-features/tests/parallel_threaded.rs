@@ -120,3 +120,20 @@ mod optimize_chunk_size_and_thread_limit {
         }
     }
 }
+
+mod num_threads_for_cost {
+    use gix_features::parallel::num_threads_for_cost;
+
+    #[test]
+    fn too_few_items_for_more_than_one_thread() {
+        assert_eq!(num_threads_for_cost(0, 500, Some(4)), 1);
+        assert_eq!(num_threads_for_cost(999, 500, Some(4)), 1);
+        assert_eq!(num_threads_for_cost(10, 0, Some(1)), 1, "a cost of 0 is treated like 1");
+    }
+
+    #[test]
+    fn thread_limit_is_respected() {
+        assert_eq!(num_threads_for_cost(1000, 500, Some(4)), 2);
+        assert_eq!(num_threads_for_cost(100_000, 500, Some(4)), 4);
+    }
+}
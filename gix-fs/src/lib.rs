@@ -93,6 +93,16 @@ pub mod io_err {
         // TODO: use variant once MSRV is 1.83
         err == ErrorKind::NotFound || raw_err == Some(20)
     }
+
+    /// Return `true` if `raw_err` indicates that a rename failed as source and destination are on different filesystems.
+    pub fn is_cross_device(raw_err: Option<i32>) -> bool {
+        // TODO: use `ErrorKind::CrossesDevices` once MSRV is 1.85
+        if cfg!(windows) {
+            raw_err == Some(17) // ERROR_NOT_SAME_DEVICE
+        } else {
+            raw_err == Some(18) // EXDEV
+        }
+    }
 }
 
 #[cfg(not(unix))]
@@ -9,10 +9,6 @@ use crate::{
     extension, AccelerateLookup, Entry, PathStorage, PathStorageRef, State, Version,
 };
 
-// TODO: integrate this somehow, somewhere, depending on later usage.
-#[allow(dead_code)]
-mod sparse;
-
 /// General information and entries
 impl State {
     /// Return the version used to store this state's information on disk.
@@ -20,6 +16,14 @@ impl State {
         self.version
     }
 
+    /// Set the `version` to store this state's information with, which is used when writing it with
+    /// [`write::Options::version`](crate::write::Options::version) set to [`version()`](Self::version()).
+    ///
+    /// This is useful to let new indices use the version configured by `index.version`.
+    pub fn set_version(&mut self, version: Version) {
+        self.version = version;
+    }
+
     /// Returns time at which the state was created, indicating its freshness compared to other files on disk.
     pub fn timestamp(&self) -> FileTime {
         self.timestamp
@@ -423,6 +427,9 @@ impl State {
     }
 
     /// Return mutable entries in a slice.
+    ///
+    /// Note that the [tree extension](Self::tree()) isn't invalidated, which should be done with
+    /// [`tree_mut()`](Self::tree_mut()) if ids or modes of entries are changed.
     pub fn entries_mut(&mut self) -> &mut [Entry] {
         &mut self.entries
     }
@@ -461,9 +468,13 @@ impl State {
 
     /// Like [`entry_index_by_path_and_stage()`][State::entry_index_by_path_and_stage()],
     /// but returns the mutable entry instead of the index.
+    ///
+    /// The [tree extension](Self::tree()) is invalidated along `path` as the entry is expected to change, and
+    /// the entry is considered changed by the [filesystem monitor extension](Self::fs_monitor()).
     pub fn entry_mut_by_path_and_stage(&mut self, path: &BStr, stage: entry::Stage) -> Option<&mut Entry> {
-        self.entry_index_by_path_and_stage(path, stage)
-            .map(|idx| &mut self.entries[idx])
+        let idx = self.entry_index_by_path_and_stage(path, stage)?;
+        self.entry_changed(idx);
+        Some(&mut self.entries[idx])
     }
 
     /// Push a new entry containing `stat`, `id`, `flags` and `mode` and `path` to the end of our storage, without performing
@@ -476,6 +487,8 @@ impl State {
     ///
     /// Alternatively, make sure to call [`sort_entries()`][State::sort_entries()] before entry lookup by path to restore
     /// the invariant.
+    ///
+    /// Extensions are updated as if the entry was [inserted](Self::upsert_entry()).
     pub fn dangerously_push_entry(
         &mut self,
         stat: entry::Stat,
@@ -497,15 +510,96 @@ impl State {
             mode,
             path,
         });
+        self.entry_added(self.entries.len() - 1);
+    }
+
+    /// Insert a new entry containing `stat`, `id`, `flags` and `mode` at `path` so that all entries remain sorted, or replace
+    /// the entry at `path` if it already exists in the stage indicated by `flags`. Return the index of the entry.
+    ///
+    /// The [tree extension](Self::tree()) is invalidated along `path`, so trees are recomputed where needed.
+    /// Similarly, the [untracked cache](Self::untracked()) is invalidated for the directory of new entries, and the
+    /// [filesystem monitor extension](Self::fs_monitor()) considers the entry changed.
+    ///
+    /// Note that entries of `path` in other stages are kept, and that entries conflicting with `path` aren't removed,
+    /// like `path/file` if `path` is a file now. Use [`remove_entries_at_path()`](Self::remove_entries_at_path()) to remove
+    /// them as needed.
+    pub fn upsert_entry(
+        &mut self,
+        stat: entry::Stat,
+        id: gix_hash::ObjectId,
+        flags: entry::Flags,
+        mode: entry::Mode,
+        path: &BStr,
+    ) -> usize {
+        let stage = flags.stage();
+        let idx = match self.entry_index_by_path_and_stage(path, stage) {
+            Some(idx) => {
+                let entry = &mut self.entries[idx];
+                entry.stat = stat;
+                entry.id = id;
+                entry.flags = flags;
+                entry.mode = mode;
+                self.entry_changed(idx);
+                idx
+            }
+            None => {
+                let paths = &self.path_backing;
+                let idx = self.entries.partition_point(|e| {
+                    Entry::cmp_filepaths(e.path_in(paths), path)
+                        .then_with(|| e.stage().cmp(&stage))
+                        .is_lt()
+                });
+                let path = {
+                    let path_start = self.path_backing.len();
+                    self.path_backing.push_str(path);
+                    path_start..self.path_backing.len()
+                };
+                self.entries.insert(
+                    idx,
+                    Entry {
+                        stat,
+                        id,
+                        flags,
+                        mode,
+                        path,
+                    },
+                );
+                self.entry_added(idx);
+                idx
+            }
+        };
+        idx
+    }
+
+    /// Physically remove all entries at `path` in all stages, and return the amount of removed entries.
+    ///
+    /// Extensions are updated just like in [`remove_entry_at_index()`](Self::remove_entry_at_index()).
+    /// Note that the memory used for the removed entries paths is not freed, as it's append-only.
+    pub fn remove_entries_at_path(&mut self, path: &BStr) -> usize {
+        let Some(range) = self.entry_range(path) else {
+            return 0;
+        };
+        let num_removed = range.len();
+        for idx in range.rev() {
+            let entry = self.entries.remove(idx);
+            self.entry_removed(idx, &entry);
+        }
+        num_removed
     }
 
     /// Unconditionally sort entries as needed to perform lookups quickly.
+    ///
+    /// As the position of entries may change, all of them are considered changed by the
+    /// [filesystem monitor extension](Self::fs_monitor()).
     pub fn sort_entries(&mut self) {
         let path_backing = &self.path_backing;
         self.entries.sort_by(|a, b| {
             Entry::cmp_filepaths(a.path_in(path_backing), b.path_in(path_backing))
                 .then_with(|| a.stage().cmp(&b.stage()))
         });
+        if let Some(fs_monitor) = self.fs_monitor.as_mut() {
+            fs_monitor.mark_all_dirty(self.entries.len());
+        }
     }
 
     /// Similar to [`sort_entries()`][State::sort_entries()], but applies `compare` after comparing
@@ -517,12 +611,16 @@ impl State {
                 .then_with(|| a.stage().cmp(&b.stage()))
                 .then_with(|| compare(a, b))
         });
+        if let Some(fs_monitor) = self.fs_monitor.as_mut() {
+            fs_monitor.mark_all_dirty(self.entries.len());
+        }
     }
 
     /// Physically remove all entries for which `should_remove(idx, path, entry)` returns `true`, traversing them from first to last.
     ///
     /// Note that the memory used for the removed entries paths is not freed, as it's append-only, and
     /// that some extensions might refer to paths which are now deleted.
+    /// Extensions are updated for all removed entries just like in [`remove_entry_at_index()`](Self::remove_entry_at_index()).
     ///
     /// ### Performance
     ///
@@ -530,10 +628,17 @@ impl State {
     /// them when [writing the index](Self::write_to()).
     pub fn remove_entries(&mut self, mut should_remove: impl FnMut(usize, &BStr, &mut Entry) -> bool) {
         let mut index = 0;
+        let mut num_removed = 0;
         let paths = &self.path_backing;
+        let (tree, untracked) = (&mut self.tree, &mut self.untracked);
+        let (fs_monitor, resolve_undo) = (&mut self.fs_monitor, &mut self.resolve_undo);
         self.entries.retain_mut(|e| {
             let path = e.path_in(paths);
             let res = !should_remove(index, path, e);
+            if !res {
+                update_extensions_on_removal(tree, untracked, fs_monitor, resolve_undo, index - num_removed, e, path);
+                num_removed += 1;
+            }
             index += 1;
             res
         });
@@ -545,25 +650,101 @@ impl State {
     ///
     /// Note that the memory used for the removed entries paths is not freed, as it's append-only, and
     /// that some extensions might refer to paths which are now deleted.
+    /// The [tree extension](Self::tree()) and the [untracked cache](Self::untracked()) are invalidated along the path
+    /// of the removed entry though, and if it was conflicted, it is recorded in the [resolve-undo extension](Self::resolve_undo()).
     pub fn remove_entry_at_index(&mut self, index: usize) -> Entry {
-        self.entries.remove(index)
+        let entry = self.entries.remove(index);
+        self.entry_removed(index, &entry);
+        entry
     }
 }
 
+/// Keeping extensions in sync with changes to entries.
+impl State {
+    /// Update extensions after the entry at `idx` was changed in place.
+    fn entry_changed(&mut self, idx: usize) {
+        if let Some(tree) = self.tree.as_mut() {
+            tree.invalidate_path(self.entries[idx].path_in(&self.path_backing));
+        }
+        if let Some(fs_monitor) = self.fs_monitor.as_mut() {
+            fs_monitor.mark_dirty(idx);
+            self.entries[idx].flags.remove(entry::Flags::FSMONITOR_VALID);
+        }
+    }
+
+    /// Update extensions after an entry was inserted at `idx`.
+    fn entry_added(&mut self, idx: usize) {
+        let path = self.entries[idx].path_in(&self.path_backing);
+        if let Some(tree) = self.tree.as_mut() {
+            tree.invalidate_path(path);
+        }
+        if let Some(untracked) = self.untracked.as_mut() {
+            untracked.invalidate_path(path);
+        }
+        if let Some(fs_monitor) = self.fs_monitor.as_mut() {
+            fs_monitor.entry_inserted(idx);
+        }
+    }
+
+    /// Update extensions after `entry` was removed from `idx`.
+    fn entry_removed(&mut self, idx: usize, entry: &Entry) {
+        update_extensions_on_removal(
+            &mut self.tree,
+            &mut self.untracked,
+            &mut self.fs_monitor,
+            &mut self.resolve_undo,
+            idx,
+            entry,
+            entry.path_in(&self.path_backing),
+        );
+    }
+}
+
+fn update_extensions_on_removal(
+    tree: &mut Option<extension::Tree>,
+    untracked: &mut Option<extension::UntrackedCache>,
+    fs_monitor: &mut Option<extension::FsMonitor>,
+    resolve_undo: &mut Option<extension::resolve_undo::Paths>,
+    idx: usize,
+    entry: &Entry,
+    path: &BStr,
+) {
+    if let Some(tree) = tree.as_mut() {
+        tree.invalidate_path(path);
+    }
+    if let Some(untracked) = untracked.as_mut() {
+        untracked.invalidate_path(path);
+    }
+    if let Some(fs_monitor) = fs_monitor.as_mut() {
+        fs_monitor.entry_removed(idx);
+    }
+    extension::resolve_undo::record(resolve_undo, entry, path);
+}
+
 /// Extensions
 impl State {
     /// Access the `tree` extension.
     pub fn tree(&self) -> Option<&extension::Tree> {
         self.tree.as_ref()
     }
+    /// Access the `tree` extension mutably, for example to [invalidate](extension::Tree::invalidate_path()) paths of entries
+    /// that were changed through [`entries_mut()`](Self::entries_mut()).
+    pub fn tree_mut(&mut self) -> Option<&mut extension::Tree> {
+        self.tree.as_mut()
+    }
     /// Remove the `tree` extension.
     pub fn remove_tree(&mut self) -> Option<extension::Tree> {
         self.tree.take()
     }
-    /// Access the `link` extension.
+    /// Access the `link` extension, which is present if this index is a split index.
     pub fn link(&self) -> Option<&extension::Link> {
         self.link.as_ref()
     }
+    /// Remove the `link` extension, which turns a split index into a regular index that contains all entries
+    /// when [written](Self::write_to()).
+    pub fn remove_link(&mut self) -> Option<extension::Link> {
+        self.link.take()
+    }
     /// Obtain the resolve-undo extension.
     pub fn resolve_undo(&self) -> Option<&extension::resolve_undo::Paths> {
         self.resolve_undo.as_ref()
@@ -576,10 +757,28 @@ impl State {
     pub fn untracked(&self) -> Option<&extension::UntrackedCache> {
         self.untracked.as_ref()
     }
+    /// Obtain the untracked extension mutably, for example to fill it during a directory walk.
+    pub fn untracked_mut(&mut self) -> Option<&mut extension::UntrackedCache> {
+        self.untracked.as_mut()
+    }
+    /// Set the untracked extension to `cache`, returning the previous one.
+    pub fn set_untracked(&mut self, cache: Option<extension::UntrackedCache>) -> Option<extension::UntrackedCache> {
+        std::mem::replace(&mut self.untracked, cache)
+    }
     /// Obtain the fsmonitor extension.
     pub fn fs_monitor(&self) -> Option<&extension::FsMonitor> {
         self.fs_monitor.as_ref()
     }
+    /// Obtain the fsmonitor extension mutably, for example to mark entries as valid after checking them.
+    pub fn fs_monitor_mut(&mut self) -> Option<&mut extension::FsMonitor> {
+        self.fs_monitor.as_mut()
+    }
+    /// Set the fsmonitor extension to `fs_monitor`, returning the previous one.
+    ///
+    /// Note that the [`FSMONITOR_VALID`](entry::Flags::FSMONITOR_VALID) flag of entries isn't adjusted.
+    pub fn set_fs_monitor(&mut self, fs_monitor: Option<extension::FsMonitor>) -> Option<extension::FsMonitor> {
+        std::mem::replace(&mut self.fs_monitor, fs_monitor)
+    }
     /// Return `true` if the end-of-index extension was present when decoding this index.
     pub fn had_end_of_index_marker(&self) -> bool {
         self.end_of_index_at_decode_time
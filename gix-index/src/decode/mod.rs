@@ -305,13 +305,13 @@ pub(crate) fn stat(data: &[u8]) -> Option<(entry::Stat, &[u8])> {
     Some((
         entry::Stat {
             mtime: entry::stat::Time {
-                secs: ctime_secs,
-                nsecs: ctime_nsecs,
-            },
-            ctime: entry::stat::Time {
                 secs: mtime_secs,
                 nsecs: mtime_nsecs,
             },
+            ctime: entry::stat::Time {
+                secs: ctime_secs,
+                nsecs: ctime_nsecs,
+            },
             dev,
             ino,
             uid,
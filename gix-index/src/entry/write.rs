@@ -1,8 +1,44 @@
-use crate::{entry, Entry, State};
+use crate::{entry, util::leb64_encode, Entry, State};
 
 impl Entry {
     /// Serialize ourselves to `out` with path access via `state`, without padding.
-    pub fn write_to(&self, mut out: impl std::io::Write, state: &State) -> std::io::Result<()> {
+    pub fn write_to(&self, out: impl std::io::Write, state: &State) -> std::io::Result<()> {
+        self.write_with_path_to(out, self.path(state))
+    }
+
+    /// Like [`write_to()`](Self::write_to()), but write `path` instead of our own path.
+    pub(crate) fn write_with_path_to(&self, mut out: impl std::io::Write, path: &[u8]) -> std::io::Result<()> {
+        self.write_stat_id_and_flags(&mut out, path.len())?;
+        out.write_all(path)?;
+        out.write_all(b"\0")
+    }
+
+    /// Serialize ourselves to `out` with path access via `state` in the format of index V4, which stores our path
+    /// relative to the `previous_path` of the entry that was written before, or the empty path if this is the first entry.
+    pub fn write_v4_to(&self, out: impl std::io::Write, state: &State, previous_path: &[u8]) -> std::io::Result<()> {
+        self.write_v4_with_path_to(out, self.path(state), previous_path)
+    }
+
+    /// Like [`write_v4_to()`](Self::write_v4_to()), but write `path` instead of our own path.
+    pub(crate) fn write_v4_with_path_to(
+        &self,
+        mut out: impl std::io::Write,
+        path: &[u8],
+        previous_path: &[u8],
+    ) -> std::io::Result<()> {
+        self.write_stat_id_and_flags(&mut out, path.len())?;
+        let common_prefix_len = previous_path
+            .iter()
+            .zip(path.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let mut buf = [0u8; 10];
+        out.write_all(leb64_encode((previous_path.len() - common_prefix_len) as u64, &mut buf))?;
+        out.write_all(&path[common_prefix_len..])?;
+        out.write_all(b"\0")
+    }
+
+    fn write_stat_id_and_flags(&self, mut out: impl std::io::Write, path_len: usize) -> std::io::Result<()> {
         let stat = self.stat;
         out.write_all(&stat.ctime.secs.to_be_bytes())?;
         out.write_all(&stat.ctime.nsecs.to_be_bytes())?;
@@ -15,11 +51,10 @@ impl Entry {
         out.write_all(&stat.gid.to_be_bytes())?;
         out.write_all(&stat.size.to_be_bytes())?;
         out.write_all(self.id.as_bytes())?;
-        let path = self.path(state);
-        let path_len: u16 = if path.len() >= entry::Flags::PATH_LEN.bits() as usize {
+        let path_len: u16 = if path_len >= entry::Flags::PATH_LEN.bits() as usize {
             entry::Flags::PATH_LEN.bits() as u16
         } else {
-            path.len()
+            path_len
                 .try_into()
                 .expect("we just checked that the length is smaller than 0xfff")
         };
@@ -31,7 +66,6 @@ impl Entry {
                     .to_be_bytes(),
             )?;
         }
-        out.write_all(path)?;
-        out.write_all(b"\0")
+        Ok(())
     }
 }
@@ -5,15 +5,25 @@ use crate::{
     util::{read_u32, read_u64, split_at_byte_exclusive},
 };
 
-#[derive(Clone)]
-#[allow(dead_code)]
+/// The token identifying the point in time at which the filesystem monitor was last queried.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Token {
-    V1 { nanos_since_1970: u64 },
-    V2 { token: BString },
+    /// The token of the first version of the protocol, a timestamp.
+    V1 {
+        /// The time of the last query in nanoseconds since the UNIX epoch.
+        nanos_since_1970: u64,
+    },
+    /// The token of the second version of the protocol, an opaque string handed out by the filesystem monitor.
+    V2 {
+        /// The opaque token as returned by the filesystem monitor.
+        token: BString,
+    },
 }
 
+/// The signature of the filesystem monitor extension.
 pub const SIGNATURE: Signature = *b"FSMN";
 
+/// Decode the filesystem monitor extension from `data`.
 pub fn decode(data: &[u8]) -> Option<FsMonitor> {
     let (version, data) = read_u32(data)?;
     let (token, data) = match version {
@@ -29,11 +39,164 @@ pub fn decode(data: &[u8]) -> Option<FsMonitor> {
     };
 
     let (ewah_size, data) = read_u32(data)?;
-    let (entry_dirty, data) = gix_bitmap::ewah::decode(&data[..ewah_size as usize]).ok()?;
+    let (entry_dirty, data) = gix_bitmap::ewah::decode(data.get(..ewah_size as usize)?).ok()?;
 
     if !data.is_empty() {
         return None;
     }
 
-    FsMonitor { token, entry_dirty }.into()
+    let mut dirty_entries = Vec::new();
+    entry_dirty.for_each_set_bit(|index| {
+        dirty_entries.push(index);
+        Some(())
+    })?;
+    FsMonitor { token, dirty_entries }.into()
+}
+
+/// Write `fs_monitor` to `out`, with the bitmap of dirty entries adjusted to skip all `removed` entries.
+pub fn write_to(
+    mut out: impl std::io::Write,
+    fs_monitor: &FsMonitor,
+    removed: impl Fn(usize) -> bool,
+) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    match &fs_monitor.token {
+        Token::V1 { nanos_since_1970 } => {
+            buf.extend_from_slice(&1_u32.to_be_bytes());
+            buf.extend_from_slice(&nanos_since_1970.to_be_bytes());
+        }
+        Token::V2 { token } => {
+            buf.extend_from_slice(&2_u32.to_be_bytes());
+            buf.extend_from_slice(token);
+            buf.push(0);
+        }
+    }
+
+    let mut num_removed_so_far = 0;
+    let mut dirty = fs_monitor.dirty_entries.iter().peekable();
+    let mut bitmap = gix_bitmap::ewah::Vec::new();
+    let last = fs_monitor.dirty_entries.last().copied().unwrap_or(0);
+    for index in 0..=last {
+        let is_removed = removed(index);
+        if dirty.next_if_eq(&&index).is_some() && !is_removed {
+            bitmap.set(index - num_removed_so_far);
+        }
+        num_removed_so_far += usize::from(is_removed);
+    }
+    let mut ewah = Vec::new();
+    bitmap.write_to(&mut ewah)?;
+    buf.extend_from_slice(&(u32::try_from(ewah.len()).expect("less than 4GB bitmap")).to_be_bytes());
+    buf.extend_from_slice(&ewah);
+
+    out.write_all(&SIGNATURE)?;
+    out.write_all(&(u32::try_from(buf.len()).expect("less than 4GB extension")).to_be_bytes())?;
+    out.write_all(&buf)
+}
+
+impl FsMonitor {
+    /// Create a new instance from the `token` of the last query and the indices of `dirty_entries`, which
+    /// are entries that may have changed since the last time they were known to be unchanged.
+    pub fn new(token: Token, mut dirty_entries: Vec<usize>) -> Self {
+        dirty_entries.sort_unstable();
+        dirty_entries.dedup();
+        FsMonitor { token, dirty_entries }
+    }
+
+    /// Return the token of the last query to the filesystem monitor.
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// Return the ascending indices of the entries that may have changed, and thus aren't known to be up-to-date
+    /// as per the filesystem monitor.
+    pub fn dirty_entries(&self) -> &[usize] {
+        &self.dirty_entries
+    }
+
+    /// Keep only those dirty entries for which `f` returns `true`, which makes all others valid.
+    ///
+    /// This is useful after checking dirty entries for changes, to only keep those that actually changed.
+    pub fn retain_dirty_entries(&mut self, f: impl FnMut(&usize) -> bool) {
+        self.dirty_entries.retain(f);
+    }
+
+    /// Return `true` if the entry at `index` may have changed according to the filesystem monitor.
+    pub fn is_dirty(&self, index: usize) -> bool {
+        self.dirty_entries.binary_search(&index).is_ok()
+    }
+
+    /// Mark the entry at `index` as dirty, as it changed in memory.
+    pub(crate) fn mark_dirty(&mut self, index: usize) {
+        if let Err(pos) = self.dirty_entries.binary_search(&index) {
+            self.dirty_entries.insert(pos, index);
+        }
+    }
+
+    /// Mark all of `num_entries` as dirty, for instance because they were reordered.
+    pub(crate) fn mark_all_dirty(&mut self, num_entries: usize) {
+        self.dirty_entries.clear();
+        self.dirty_entries.extend(0..num_entries);
+    }
+
+    /// Account for a new and dirty entry that was inserted at `index`.
+    pub(crate) fn entry_inserted(&mut self, index: usize) {
+        let pos = self.dirty_entries.partition_point(|idx| *idx < index);
+        for idx in &mut self.dirty_entries[pos..] {
+            *idx += 1;
+        }
+        self.dirty_entries.insert(pos, index);
+    }
+
+    /// Account for the entry at `index` being removed.
+    pub(crate) fn entry_removed(&mut self, index: usize) {
+        let pos = self.dirty_entries.partition_point(|idx| *idx < index);
+        if self.dirty_entries.get(pos) == Some(&index) {
+            self.dirty_entries.remove(pos);
+        }
+        for idx in &mut self.dirty_entries[pos..] {
+            *idx -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, write_to, Token};
+    use crate::extension::FsMonitor;
+
+    fn dirty(fs_monitor: &FsMonitor) -> Vec<usize> {
+        fs_monitor.dirty_entries.clone()
+    }
+
+    #[test]
+    fn dirty_entries_follow_insertions_and_removals() {
+        let mut fs_monitor = FsMonitor {
+            token: Token::V2 { token: "token".into() },
+            dirty_entries: vec![1, 4],
+        };
+        fs_monitor.entry_inserted(2);
+        assert_eq!(dirty(&fs_monitor), [1, 2, 5]);
+        fs_monitor.entry_removed(1);
+        assert_eq!(dirty(&fs_monitor), [1, 4]);
+        fs_monitor.entry_removed(0);
+        assert_eq!(dirty(&fs_monitor), [0, 3]);
+        fs_monitor.mark_dirty(2);
+        fs_monitor.mark_dirty(3);
+        assert_eq!(dirty(&fs_monitor), [0, 2, 3]);
+        fs_monitor.mark_all_dirty(2);
+        assert_eq!(dirty(&fs_monitor), [0, 1]);
+    }
+
+    #[test]
+    fn removed_entries_are_skipped_when_writing() {
+        let fs_monitor = FsMonitor {
+            token: Token::V1 { nanos_since_1970: 42 },
+            dirty_entries: vec![0, 2, 3, 5],
+        };
+        let mut buf = Vec::new();
+        write_to(&mut buf, &fs_monitor, |idx| idx == 2 || idx == 4).unwrap();
+        let actual = decode(&buf[8..]).expect("valid");
+        assert_eq!(dirty(&actual), [0, 2, 3]);
+        assert!(matches!(actual.token, Token::V1 { nanos_since_1970: 42 }));
+    }
 }
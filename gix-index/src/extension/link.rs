@@ -1,11 +1,22 @@
+use bstr::BStr;
+
 use crate::{
+    entry,
     extension::{Link, Signature},
     util::split_at_pos,
+    Entry, PathStorage, State,
 };
 
 /// The signature of the link extension.
 pub const SIGNATURE: Signature = *b"link";
 
+/// A copy of the entries of the shared index as they were read, to be able to tell which entries to write into the split index.
+#[derive(Clone)]
+pub(crate) struct Shared {
+    entries: Vec<Entry>,
+    path_backing: PathStorage,
+}
+
 /// Bitmaps to know which entries to delete or replace, even though details are still unknown.
 #[derive(Clone)]
 pub struct Bitmaps {
@@ -49,6 +60,7 @@ pub(crate) fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Result<Link, d
         return Ok(Link {
             shared_index_checksum: id,
             bitmaps: None,
+            shared: None,
         });
     }
 
@@ -64,12 +76,13 @@ pub(crate) fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Result<Link, d
     Ok(Link {
         shared_index_checksum: id,
         bitmaps: Some(Bitmaps { delete, replace }),
+        shared: None,
     })
 }
 
 impl Link {
     pub(crate) fn dissolve_into(
-        self,
+        &mut self,
         split_index: &mut crate::File,
         object_hash: gix_hash::Kind,
         skip_hash: bool,
@@ -89,11 +102,14 @@ impl Link {
                 ..options
             },
         )?;
+        self.shared = Some(Shared {
+            entries: shared_index.entries.clone(),
+            path_backing: shared_index.path_backing.clone(),
+        });
 
-        if let Some(bitmaps) = self.bitmaps {
-            let mut split_entry_index = 0;
-
-            let mut err = None;
+        let mut split_entry_index = 0;
+        let mut err = None;
+        if let Some(bitmaps) = &self.bitmaps {
             bitmaps.replace.for_each_set_bit(|replace_index| {
                 let shared_entry = match shared_index.entries.get_mut(replace_index) {
                     Some(e) => e,
@@ -131,23 +147,25 @@ impl Link {
                 split_entry_index += 1;
                 Some(())
             });
-            if let Some(err) = err {
+            if let Some(err) = err.take() {
                 return Err(err.into());
             }
+        }
 
-            let split_index_path_backing = std::mem::take(&mut split_index.path_backing);
-            for mut split_entry in split_index.entries.drain(split_entry_index..) {
-                let start = shared_index.path_backing.len();
-                let split_index_path = split_entry.path.clone();
+        let split_index_path_backing = std::mem::take(&mut split_index.path_backing);
+        for mut split_entry in split_index.entries.drain(split_entry_index..) {
+            let start = shared_index.path_backing.len();
+            let split_index_path = split_entry.path.clone();
 
-                split_entry.path = start..start + split_entry.path.len();
-                shared_index.entries.push(split_entry);
+            split_entry.path = start..start + split_entry.path.len();
+            shared_index.entries.push(split_entry);
 
-                shared_index
-                    .path_backing
-                    .extend_from_slice(&split_index_path_backing[split_index_path]);
-            }
+            shared_index
+                .path_backing
+                .extend_from_slice(&split_index_path_backing[split_index_path]);
+        }
 
+        if let Some(bitmaps) = &self.bitmaps {
             bitmaps.delete.for_each_set_bit(|delete_index| {
                 let shared_entry = match shared_index.entries.get_mut(delete_index) {
                     Some(e) => e,
@@ -162,18 +180,83 @@ impl Link {
             if let Some(err) = err {
                 return Err(err.into());
             }
+        }
 
-            shared_index
-                .entries
-                .retain(|e| !e.flags.contains(crate::entry::Flags::REMOVE));
+        shared_index
+            .entries
+            .retain(|e| !e.flags.contains(crate::entry::Flags::REMOVE));
 
-            let mut shared_entries = std::mem::take(&mut shared_index.entries);
-            shared_entries.sort_by(|a, b| a.cmp(b, &shared_index.state));
+        let mut shared_entries = std::mem::take(&mut shared_index.entries);
+        shared_entries.sort_by(|a, b| a.cmp(b, &shared_index.state));
 
-            split_index.entries = shared_entries;
-            split_index.path_backing = std::mem::take(&mut shared_index.path_backing);
-        }
+        split_index.entries = shared_entries;
+        split_index.path_backing = std::mem::take(&mut shared_index.path_backing);
 
         Ok(())
     }
+
+    /// Return the entries to write into the split index along with the bitmaps telling which entries of the shared index
+    /// they replace and which shared entries are deleted, or `None` if the shared index isn't known and all entries of `state`
+    /// are to be written as they are, along with our unchanged bitmaps.
+    pub(crate) fn split_entries<'a>(&self, state: &'a State) -> Option<(Vec<(&'a Entry, &'a BStr)>, Bitmaps)> {
+        let shared = self.shared.as_ref()?;
+        let mut delete = gix_bitmap::ewah::Vec::new();
+        let mut replace = gix_bitmap::ewah::Vec::new();
+        let mut replaced = Vec::new();
+        let mut is_shared = vec![false; state.entries.len()];
+        for (shared_index, shared_entry) in shared.entries.iter().enumerate() {
+            let path = shared_entry.path_in(&shared.path_backing);
+            let current = state
+                .entry_index_by_path_and_stage(path, shared_entry.stage())
+                .filter(|idx| !state.entries[*idx].flags.contains(entry::Flags::REMOVE));
+            let Some(idx) = current else {
+                delete.set(shared_index);
+                continue;
+            };
+            is_shared[idx] = true;
+            let entry = &state.entries[idx];
+            if !is_same(entry, shared_entry) {
+                replace.set(shared_index);
+                replaced.push((entry, BStr::new("")));
+            }
+        }
+
+        let added = state
+            .entries
+            .iter()
+            .zip(is_shared)
+            .filter(|(entry, is_shared)| !is_shared && !entry.flags.contains(entry::Flags::REMOVE))
+            .map(|(entry, _)| (entry, entry.path(state)));
+        replaced.extend(added);
+        Some((replaced, Bitmaps { delete, replace }))
+    }
+}
+
+/// Return `true` if `a` and `b` would be written the same, ignoring their path.
+fn is_same(a: &Entry, b: &Entry) -> bool {
+    let on_disk = |flags: entry::Flags| {
+        flags
+            & (entry::Flags::STAGE_MASK
+                | entry::Flags::EXTENDED
+                | entry::Flags::ASSUME_VALID
+                | entry::Flags::INTENT_TO_ADD
+                | entry::Flags::SKIP_WORKTREE)
+    };
+    a.stat == b.stat && a.id == b.id && a.mode == b.mode && on_disk(a.flags) == on_disk(b.flags)
+}
+
+/// Write the link extension with `checksum` and `bitmaps` to `out`.
+pub(crate) fn write_to(
+    mut out: impl std::io::Write,
+    checksum: &gix_hash::oid,
+    bitmaps: Option<&Bitmaps>,
+) -> std::io::Result<()> {
+    let mut buf = checksum.as_bytes().to_vec();
+    if let Some(bitmaps) = bitmaps {
+        bitmaps.delete.write_to(&mut buf)?;
+        bitmaps.replace.write_to(&mut buf)?;
+    }
+    out.write_all(&SIGNATURE)?;
+    out.write_all(&(u32::try_from(buf.len()).expect("less than 4GB link extension")).to_be_bytes())?;
+    out.write_all(&buf)
 }
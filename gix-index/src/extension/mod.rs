@@ -39,11 +39,12 @@ pub struct Link {
     pub shared_index_checksum: gix_hash::ObjectId,
     /// Bitmaps to tell us which entries to delete or replace.
     pub bitmaps: Option<link::Bitmaps>,
+    /// The entries of the shared index as they were read, if it was merged into the split index.
+    shared: Option<link::Shared>,
 }
 
 /// The extension for untracked files.
-#[allow(dead_code)]
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UntrackedCache {
     /// Something identifying the location and machine that this cache is for.
     /// Should the repository be copied to a different machine, the entire cache can immediately be invalidated.
@@ -61,17 +62,17 @@ pub struct UntrackedCache {
 }
 
 /// The extension for keeping state on recent information provided by the filesystem monitor.
-#[allow(dead_code)]
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FsMonitor {
     token: fs_monitor::Token,
-    /// if a bit is true, the respective entry is NOT valid as per the fs monitor.
-    entry_dirty: gix_bitmap::ewah::Vec,
+    /// The ascending indices of entries that are NOT valid as per the fs monitor.
+    dirty_entries: Vec<usize>,
 }
 
 mod iter;
 
-pub(crate) mod fs_monitor;
+///
+pub mod fs_monitor;
 
 ///
 pub mod decode;
@@ -87,7 +88,8 @@ pub(crate) mod index_entry_offset_table;
 ///
 pub mod link;
 
-pub(crate) mod resolve_undo;
+///
+pub mod resolve_undo;
 
 ///
 pub mod untracked_cache;
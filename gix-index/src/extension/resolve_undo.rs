@@ -1,33 +1,39 @@
-use bstr::BString;
+use bstr::{BStr, BString, ByteSlice};
 use gix_hash::ObjectId;
 
 use crate::{
+    entry,
     extension::Signature,
     util::{split_at_byte_exclusive, split_at_pos},
+    Entry,
 };
 
+/// All paths with conflicts that were resolved, sorted by name.
 pub type Paths = Vec<ResolvePath>;
 
-#[allow(dead_code)]
-#[derive(Clone)]
+/// A path that had a conflict, along with all of its conflicting stages.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ResolvePath {
     /// relative to the root of the repository, or what would be stored in the index
-    name: BString,
+    pub name: BString,
 
     /// 0 = ancestor/common, 1 = ours, 2 = theirs
-    stages: [Option<Stage>; 3],
+    pub stages: [Option<Stage>; 3],
 }
 
-#[allow(dead_code)]
-#[derive(Clone, Copy)]
+/// A conflicting stage of a [path](ResolvePath).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Stage {
-    mode: u32,
-    id: ObjectId,
+    /// The mode of the entry in this stage.
+    pub mode: u32,
+    /// The id of the entry in this stage.
+    pub id: ObjectId,
 }
 
+/// The signature of the resolve-undo extension.
 pub const SIGNATURE: Signature = *b"REUC";
 
-pub fn decode(mut data: &[u8], object_hash: gix_hash::Kind) -> Option<Paths> {
+pub(crate) fn decode(mut data: &[u8], object_hash: gix_hash::Kind) -> Option<Paths> {
     let hash_len = object_hash.len_in_bytes();
     let mut out = Vec::new();
 
@@ -62,3 +68,50 @@ pub fn decode(mut data: &[u8], object_hash: gix_hash::Kind) -> Option<Paths> {
     }
     out.into()
 }
+
+/// Write `paths` as resolve-undo extension to `out`.
+pub(crate) fn write_to(mut out: impl std::io::Write, paths: &Paths) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    for path in paths {
+        buf.extend_from_slice(&path.name);
+        buf.push(0);
+        for stage in &path.stages {
+            let mode = stage.map_or(0, |stage| stage.mode);
+            buf.extend_from_slice(format!("{mode:o}").as_bytes());
+            buf.push(0);
+        }
+        for stage in path.stages.iter().flatten() {
+            buf.extend_from_slice(stage.id.as_bytes());
+        }
+    }
+    out.write_all(&SIGNATURE)?;
+    out.write_all(&(u32::try_from(buf.len()).expect("less than 4GB resolve-undo extension")).to_be_bytes())?;
+    out.write_all(&buf)
+}
+
+/// Remember the conflicted `entry` at `path` which is about to be removed, so the conflict can be restored later, similar to
+/// what `git` does when resolving conflicts. Entries without conflict are ignored.
+pub(crate) fn record(paths: &mut Option<Paths>, entry: &Entry, path: &BStr) {
+    let stage = entry.stage_raw();
+    if stage == entry::Stage::Unconflicted as u32 {
+        return;
+    }
+    let paths = paths.get_or_insert_with(Vec::new);
+    let idx = match paths.binary_search_by(|p| p.name.as_bstr().cmp(path)) {
+        Ok(idx) => idx,
+        Err(idx) => {
+            paths.insert(
+                idx,
+                ResolvePath {
+                    name: path.to_owned(),
+                    stages: [None; 3],
+                },
+            );
+            idx
+        }
+    };
+    paths[idx].stages[stage as usize - 1] = Some(Stage {
+        mode: entry.mode.bits(),
+        id: entry.id,
+    });
+}
@@ -11,6 +11,31 @@ pub use decode::decode;
 
 mod write;
 
+/// Mutation
+impl crate::extension::Tree {
+    /// Mark this tree and all trees leading to the directory that contains `path` as invalid, so they
+    /// are recomputed when needed. `path` is the path of an entry relative to this tree, like `dir/file`.
+    pub fn invalidate_path(&mut self, path: &bstr::BStr) {
+        self.num_entries = None;
+        let mut tree = self;
+        let mut components = path.split(|b| *b == b'/').peekable();
+        while let Some(component) = components.next() {
+            if components.peek().is_none() {
+                break;
+            }
+            let Some(child) = tree
+                .children
+                .iter_mut()
+                .find(|child| child.name.as_slice() == component)
+            else {
+                break;
+            };
+            child.num_entries = None;
+            tree = child;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use gix_testtools::size_ok;
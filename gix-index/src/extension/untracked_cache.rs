@@ -1,14 +1,14 @@
-use bstr::BString;
+use bstr::{BStr, BString, ByteSlice};
 use gix_hash::ObjectId;
 
 use crate::{
     entry,
     extension::{Signature, UntrackedCache},
-    util::{read_u32, split_at_byte_exclusive, split_at_pos, var_int},
+    util::{leb64_encode, read_u32, split_at_byte_exclusive, split_at_pos, var_int},
 };
 
 /// A structure to track filesystem stat information along with an object id, linking a worktree file with what's in our ODB.
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OidStat {
     /// The file system stat information
     pub stat: entry::Stat,
@@ -17,23 +17,36 @@ pub struct OidStat {
 }
 
 /// A directory with information about its untracked files, and its sub-directories
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Directory {
     /// The directories name, or an empty string if this is the root directory.
     pub name: BString,
-    /// Untracked files and directory names
+    /// Untracked files and directory names, with directory names having a trailing slash.
     pub untracked_entries: Vec<BString>,
     /// indices for sub-directories similar to this one.
     pub sub_directories: Vec<usize>,
 
-    /// The directories stat data, if available or valid // TODO: or is it the exclude file?
+    /// The directories stat data, or `None` if the directory is invalid and its untracked entries have to be recomputed.
     pub stat: Option<entry::Stat>,
-    /// The oid of a .gitignore file, if it exists
+    /// The oid of the per-directory exclude file, typically `.gitignore`, or the id of an empty blob if it doesn't exist.
     pub exclude_file_oid: Option<ObjectId>,
-    /// TODO: figure out what this really does
+    /// If `true`, the directory was only checked for containing untracked files, so `untracked_entries` may not be complete.
     pub check_only: bool,
 }
 
+impl Directory {
+    fn new(name: BString) -> Self {
+        Directory {
+            name,
+            untracked_entries: Vec::new(),
+            sub_directories: Vec::new(),
+            stat: None,
+            exclude_file_oid: None,
+            check_only: false,
+        }
+    }
+}
+
 /// Only used as an indicator
 pub const SIGNATURE: Signature = *b"UNTR";
 
@@ -47,9 +60,11 @@ pub fn decode(data: &[u8], object_hash: gix_hash::Kind) -> Option<UntrackedCache
     let (identifier, data) = split_at_pos(data, identifier_len.try_into().ok()?)?;
 
     let hash_len = object_hash.len_in_bytes();
-    let (info_exclude, data) = decode_oid_stat(data, hash_len)?;
-    let (excludes_file, data) = decode_oid_stat(data, hash_len)?;
+    let (info_exclude_stat, data) = crate::decode::stat(data)?;
+    let (excludes_file_stat, data) = crate::decode::stat(data)?;
     let (dir_flags, data) = read_u32(data)?;
+    let (info_exclude, data) = decode_oid_stat(info_exclude_stat, data, hash_len)?;
+    let (excludes_file, data) = decode_oid_stat(excludes_file_stat, data, hash_len)?;
     let (exclude_filename_per_dir, data) = split_at_byte_exclusive(data, 0)?;
 
     let (num_directory_blocks, data) = var_int(data)?;
@@ -141,8 +156,7 @@ fn decode_directory_block<'a>(data: &'a [u8], directories: &mut Vec<Directory>)
     data.into()
 }
 
-fn decode_oid_stat(data: &[u8], hash_len: usize) -> Option<(OidStat, &[u8])> {
-    let (stat, data) = crate::decode::stat(data)?;
+fn decode_oid_stat(stat: entry::Stat, data: &[u8], hash_len: usize) -> Option<(OidStat, &[u8])> {
     let (hash, data) = split_at_pos(data, hash_len)?;
     Some((
         OidStat {
@@ -152,3 +166,280 @@ fn decode_oid_stat(data: &[u8], hash_len: usize) -> Option<(OidStat, &[u8])> {
         data,
     ))
 }
+
+/// Write `cache` to `out`, with hashes of the kind `object_hash`.
+pub fn write_to(
+    mut out: impl std::io::Write,
+    cache: &UntrackedCache,
+    object_hash: gix_hash::Kind,
+) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    let mut varint = [0u8; 10];
+    buf.extend_from_slice(leb64_encode(cache.identifier.len() as u64, &mut varint));
+    buf.extend_from_slice(&cache.identifier);
+
+    let null = ObjectId::null(object_hash);
+    for oid_stat in [&cache.info_exclude, &cache.excludes_file] {
+        write_stat(&mut buf, oid_stat.as_ref().map(|s| s.stat).unwrap_or_default());
+    }
+    buf.extend_from_slice(&cache.dir_flags.to_be_bytes());
+    for oid_stat in [&cache.info_exclude, &cache.excludes_file] {
+        buf.extend_from_slice(oid_stat.as_ref().map_or(&null, |s| &s.id).as_bytes());
+    }
+    buf.extend_from_slice(&cache.exclude_filename_per_dir);
+    buf.push(0);
+
+    if cache.directories.is_empty() {
+        buf.extend_from_slice(leb64_encode(0, &mut varint));
+    } else {
+        let mut state = WriteState::default();
+        write_directory(&cache.directories, 0, &mut state);
+        buf.extend_from_slice(leb64_encode(state.num_directories as u64, &mut varint));
+        buf.extend_from_slice(&state.blocks);
+        state.valid.write_to(&mut buf)?;
+        state.check_only.write_to(&mut buf)?;
+        state.hash_valid.write_to(&mut buf)?;
+        buf.extend_from_slice(&state.stats);
+        buf.extend_from_slice(&state.hashes);
+    }
+    buf.push(0);
+
+    out.write_all(&SIGNATURE)?;
+    out.write_all(&(u32::try_from(buf.len()).expect("less than 4GB untracked cache")).to_be_bytes())?;
+    out.write_all(&buf)
+}
+
+#[derive(Default)]
+struct WriteState {
+    num_directories: usize,
+    blocks: Vec<u8>,
+    valid: gix_bitmap::ewah::Vec,
+    check_only: gix_bitmap::ewah::Vec,
+    hash_valid: gix_bitmap::ewah::Vec,
+    stats: Vec<u8>,
+    hashes: Vec<u8>,
+}
+
+fn write_directory(directories: &[Directory], index: usize, state: &mut WriteState) {
+    let dir = &directories[index];
+    let bit = state.num_directories;
+    state.num_directories += 1;
+
+    // Like `git`, don't write untracked entries of invalid directories.
+    let is_valid = dir.stat.is_some();
+    if dir.check_only && is_valid {
+        state.check_only.set(bit);
+    }
+    if let Some(stat) = dir.stat {
+        state.valid.set(bit);
+        write_stat(&mut state.stats, stat);
+    }
+    if let Some(id) = dir.exclude_file_oid.filter(|id| !id.is_null()) {
+        state.hash_valid.set(bit);
+        state.hashes.extend_from_slice(id.as_bytes());
+    }
+
+    let mut varint = [0u8; 10];
+    let untracked_entries = if is_valid {
+        dir.untracked_entries.as_slice()
+    } else {
+        &[]
+    };
+    state
+        .blocks
+        .extend_from_slice(leb64_encode(untracked_entries.len() as u64, &mut varint));
+    state
+        .blocks
+        .extend_from_slice(leb64_encode(dir.sub_directories.len() as u64, &mut varint));
+    state.blocks.extend_from_slice(&dir.name);
+    state.blocks.push(0);
+    for name in untracked_entries {
+        state.blocks.extend_from_slice(name);
+        state.blocks.push(0);
+    }
+    for sub_directory in &dir.sub_directories {
+        write_directory(directories, *sub_directory, state);
+    }
+}
+
+fn write_stat(out: &mut Vec<u8>, stat: entry::Stat) {
+    for field in [
+        stat.ctime.secs,
+        stat.ctime.nsecs,
+        stat.mtime.secs,
+        stat.mtime.nsecs,
+        stat.dev,
+        stat.ino,
+        stat.uid,
+        stat.gid,
+        stat.size,
+    ] {
+        out.extend_from_slice(&field.to_be_bytes());
+    }
+}
+
+/// A [directory flag](UntrackedCache::dir_flags()) to indicate that untracked directories are listed as a whole,
+/// instead of listing all untracked files within them.
+pub const DIR_SHOW_OTHER_DIRECTORIES: u32 = 1 << 1;
+/// A [directory flag](UntrackedCache::dir_flags()) to indicate that empty directories are not listed.
+pub const DIR_HIDE_EMPTY_DIRECTORIES: u32 = 1 << 2;
+
+impl UntrackedCache {
+    /// Create a new cache without any directories for the worktree described by `identifier`, where
+    /// `exclude_filename_per_dir` is the name of the exclude file in each directory, typically `.gitignore`,
+    /// and `dir_flags` describe how untracked files are listed.
+    ///
+    /// The flags `git` uses by default are [`DIR_SHOW_OTHER_DIRECTORIES`] and [`DIR_HIDE_EMPTY_DIRECTORIES`].
+    pub fn new(identifier: impl Into<BString>, exclude_filename_per_dir: impl Into<BString>, dir_flags: u32) -> Self {
+        UntrackedCache {
+            identifier: identifier.into(),
+            info_exclude: None,
+            excludes_file: None,
+            exclude_filename_per_dir: exclude_filename_per_dir.into(),
+            dir_flags,
+            directories: Vec::new(),
+        }
+    }
+
+    /// Return the string identifying the location and machine this cache was created for.
+    pub fn identifier(&self) -> &BStr {
+        self.identifier.as_ref()
+    }
+
+    /// Return the name of the exclude file that is expected in each directory, typically `.gitignore`.
+    pub fn exclude_filename_per_dir(&self) -> &BStr {
+        self.exclude_filename_per_dir.as_ref()
+    }
+
+    /// Return the flags that describe how untracked files were listed when filling the cache,
+    /// like [`DIR_SHOW_OTHER_DIRECTORIES`].
+    pub fn dir_flags(&self) -> u32 {
+        self.dir_flags
+    }
+
+    /// Return the stat and id of the `$GIT_DIR/info/exclude` file at the time the cache was filled.
+    pub fn info_exclude(&self) -> Option<&OidStat> {
+        self.info_exclude.as_ref()
+    }
+
+    /// Return the stat and id of the file configured in `core.excludesFile` at the time the cache was filled.
+    pub fn excludes_file(&self) -> Option<&OidStat> {
+        self.excludes_file.as_ref()
+    }
+
+    /// Set the stat and id of the `$GIT_DIR/info/exclude` file and the `core.excludesFile` as they are now,
+    /// and invalidate all directories if one of their ids differs from the ones the cache was filled with.
+    ///
+    /// Return `true` if the cache was invalidated.
+    pub fn set_global_excludes(&mut self, info_exclude: Option<OidStat>, excludes_file: Option<OidStat>) -> bool {
+        let id = |stat: &Option<OidStat>| stat.as_ref().map(|stat| stat.id);
+        let changed = id(&self.info_exclude) != id(&info_exclude) || id(&self.excludes_file) != id(&excludes_file);
+        if changed && !self.directories.is_empty() {
+            self.invalidate_directory_tree(0);
+        }
+        self.info_exclude = info_exclude;
+        self.excludes_file = excludes_file;
+        changed
+    }
+
+    /// Return the list of all directories, with the root directory at index 0 if the list isn't empty.
+    pub fn directories(&self) -> &[Directory] {
+        &self.directories
+    }
+
+    /// Return the list of all directories for modification, with the root directory at index 0 if the list isn't empty.
+    pub fn directories_mut(&mut self) -> &mut [Directory] {
+        &mut self.directories
+    }
+
+    /// Return the index into [`directories()`](Self::directories()) for the directory at the slash-separated
+    /// worktree-relative `path`, with the empty path being the root directory, or `None` if it isn't known.
+    pub fn directory_index(&self, path: &BStr) -> Option<usize> {
+        if self.directories.is_empty() {
+            return None;
+        }
+        let mut index = 0;
+        for component in path.split(|b| *b == b'/').filter(|c| !c.is_empty()) {
+            let dir = &self.directories[index];
+            let pos = dir
+                .sub_directories
+                .binary_search_by(|idx| self.directories[*idx].name.as_slice().cmp(component))
+                .ok()?;
+            index = dir.sub_directories[pos];
+        }
+        Some(index)
+    }
+
+    /// Like [`directory_index()`](Self::directory_index()), but insert all directories leading up to and including
+    /// the one at `path` if they don't exist yet.
+    ///
+    /// Newly inserted directories are invalid, i.e. they have no stat information.
+    pub fn directory_index_or_insert(&mut self, path: &BStr) -> usize {
+        if self.directories.is_empty() {
+            self.directories.push(Directory::new(BString::default()));
+        }
+        let mut index = 0;
+        for component in path.split(|b| *b == b'/').filter(|c| !c.is_empty()) {
+            let directories = &self.directories;
+            index = match directories[index]
+                .sub_directories
+                .binary_search_by(|idx| directories[*idx].name.as_slice().cmp(component))
+            {
+                Ok(pos) => directories[index].sub_directories[pos],
+                Err(pos) => {
+                    let new_index = self.directories.len();
+                    self.directories.push(Directory::new(component.into()));
+                    self.directories[index].sub_directories.insert(pos, new_index);
+                    new_index
+                }
+            };
+        }
+        index
+    }
+
+    /// Invalidate the directory at `index` along with all of its sub-directories, which is needed if an exclude file
+    /// that affects all of them changed.
+    pub fn invalidate_directory_tree(&mut self, index: usize) {
+        let mut stack = vec![index];
+        while let Some(index) = stack.pop() {
+            let dir = &mut self.directories[index];
+            dir.stat = None;
+            dir.untracked_entries.clear();
+            stack.extend_from_slice(&dir.sub_directories);
+        }
+    }
+
+    /// Invalidate the directory containing the file at `path` as its status changed in the index, so its untracked
+    /// files have to be recomputed, similar to what `git` does when entries are added or removed.
+    ///
+    /// Parent directories are invalidated as well if untracked directories are shown as a whole, as their content
+    /// could change from the perspective of the parent.
+    pub fn invalidate_path(&mut self, path: &BStr) {
+        if !self.directories.is_empty() {
+            self.invalidate_component(0, path);
+        }
+    }
+
+    /// Return `true` if the parent of the directory at `dir_index` must be invalidated as well.
+    fn invalidate_component(&mut self, dir_index: usize, path: &BStr) -> bool {
+        let invalidate_parent = self.dir_flags & DIR_SHOW_OTHER_DIRECTORIES != 0;
+        if let Some((component, rest)) = path.split_once_str(b"/") {
+            let child = self.directories[dir_index]
+                .sub_directories
+                .iter()
+                .copied()
+                .find(|idx| self.directories[*idx].name == component);
+            let child_was_invalidated = match child {
+                Some(child) => self.invalidate_component(child, rest.as_bstr()),
+                None => invalidate_parent,
+            };
+            if !child_was_invalidated {
+                return false;
+            }
+        }
+        let dir = &mut self.directories[dir_index];
+        dir.stat = None;
+        dir.untracked_entries.clear();
+        invalidate_parent
+    }
+}
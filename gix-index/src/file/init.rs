@@ -98,6 +98,7 @@ impl File {
         let mut file = File { state, path, checksum };
         if let Some(mut link) = file.link.take() {
             link.dissolve_into(&mut file, object_hash, skip_hash, options)?;
+            file.link = Some(link);
         }
 
         Ok(file)
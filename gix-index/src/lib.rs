@@ -38,6 +38,15 @@ pub mod verify;
 ///
 pub mod write;
 
+///
+pub mod write_tree;
+
+///
+pub mod sparse;
+
+///
+pub mod refresh;
+
 pub mod fs;
 
 /// All known versions of a git index file.
@@ -189,6 +198,25 @@ mod impls {
 }
 
 pub(crate) mod util {
+    /// Encode `n` into the end of `buf` as the variable-length integer understood by [`var_int()`]
+    /// and return the encoded bytes.
+    #[inline]
+    pub fn leb64_encode(mut n: u64, buf: &mut [u8; 10]) -> &[u8] {
+        let mut bytes_written = 1;
+        buf[buf.len() - 1] = n as u8 & 0b0111_1111;
+        for out in buf.iter_mut().rev().skip(1) {
+            n >>= 7;
+            if n == 0 {
+                break;
+            }
+            n -= 1;
+            *out = 0b1000_0000 | (n as u8 & 0b0111_1111);
+            bytes_written += 1;
+        }
+        debug_assert_eq!(n, 0, "BUG: buffer must be large enough to hold a 64 bit integer");
+        &buf[buf.len() - bytes_written..]
+    }
+
     #[inline]
     pub fn var_int(data: &[u8]) -> Option<(u64, &[u8])> {
         let (num, consumed) = gix_features::decode::leb64_from_read(data).ok()?;
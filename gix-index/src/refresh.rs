@@ -0,0 +1,291 @@
+use std::{
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use bstr::{BStr, BString};
+use filetime::FileTime;
+use gix_features::parallel::{in_parallel_if, optimize_chunk_size_and_thread_limit, Reduce};
+
+use crate::{entry, Entry, PathStorageRef, State};
+
+/// The amount of entries it takes for a thread to be worth spawning, just like in git.
+pub const THREAD_COST: usize = 500;
+
+/// The error returned by [`State::refresh()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not obtain metadata of worktree file for '{rela_path}'")]
+    Io { rela_path: BString, source: std::io::Error },
+    #[error("Could not compare the worktree file of '{rela_path}' to its entry")]
+    ContentMatches {
+        rela_path: BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error(transparent)]
+    Time(#[from] std::time::SystemTimeError),
+    #[error("The operation was interrupted")]
+    Interrupted,
+}
+
+/// Options for use in [`State::refresh()`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// Capabilities of the file system which affect how the mode of entries is compared to what's on disk.
+    pub fs: gix_fs::Capabilities,
+    /// Options to configure how stat information of entries is compared to what's on disk.
+    pub stat: entry::stat::Options,
+    /// The amount of threads to use at most, or `None` to use as many as there are logical cores.
+    ///
+    /// Note that a thread is only used for each [`THREAD_COST`] entries, so small indices are always refreshed on the current thread.
+    /// Set it to `Some(1)` if `core.preloadIndex` is `false`.
+    pub thread_limit: Option<usize>,
+}
+
+/// The outcome of [`State::refresh()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The total amount of entries in the index.
+    pub entries_to_process: usize,
+    /// The amount of entries that were skipped due to their flags, because they are conflicting, or because they are
+    /// submodules or sparse directories.
+    pub entries_skipped: usize,
+    /// The amount of times we queried symlink-metadata for a file on disk.
+    pub symlink_metadata_calls: usize,
+    /// The amount of entries whose stat information matched the one on disk.
+    pub entries_up_to_date: usize,
+    /// The amount of entries whose content matched the one on disk despite their stat information not matching,
+    /// or them being *racily clean*, which now carry updated stat information.
+    pub entries_updated: usize,
+    /// The amount of entries whose stat information matched, but that were modified at or after the index was last written.
+    pub racy_clean: usize,
+    /// The amount of entries that are modified, removed or changed their type on disk.
+    pub entries_changed: usize,
+}
+
+/// Refreshing
+impl State {
+    /// Compare the stat information of all entries with the files in the `worktree` directory and mark those that match as
+    /// [up-to-date](entry::Flags::UPTODATE) in preparation for a status, similar to what `git update-index --refresh` does.
+    ///
+    /// For entries whose stat information doesn't match, or that are *racily clean*, `content_matches(entry, rela_path, worktree_path)`
+    /// is called to learn if the file at `worktree_path` still has the content of `entry`, typically by hashing it after applying filters.
+    /// If so, the entry receives the new stat information and is marked as up-to-date. Otherwise, if the entry was racily clean,
+    /// its size is set to 0 to assure it keeps showing up as modified.
+    ///
+    /// Entries are checked in parallel chunks according to `options`, but only if there are enough of them to be worth it.
+    /// Once done, the [timestamp](Self::timestamp()) is set to the time right before the refresh started, and the index
+    /// should be written back to persist the refreshed stat information.
+    ///
+    /// Note that `worktree` must be the root of the worktree, and that entries already marked up-to-date are not checked again.
+    pub fn refresh<E>(
+        &mut self,
+        worktree: &Path,
+        content_matches: impl FnMut(&Entry, &BStr, &Path) -> Result<bool, E> + Send + Clone,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let start = FileTime::now();
+        let timestamp = self.timestamp;
+        let num_entries = self.entries.len();
+        let num_threads = gix_features::parallel::num_threads_for_cost(num_entries, THREAD_COST, options.thread_limit);
+        let (chunk_size, thread_limit, _) =
+            optimize_chunk_size_and_thread_limit(THREAD_COST, Some(num_entries), Some(num_threads), None);
+        let _span = gix_features::trace::coarse!(
+            "gix_index::State::refresh()",
+            num_entries = num_entries,
+            chunk_size = chunk_size,
+            thread_limit = ?thread_limit
+        );
+
+        let (entries, path_backing) = (&mut self.entries, &self.path_backing);
+        let mut out = in_parallel_if(
+            || num_threads > 1,
+            entries.chunks_mut(chunk_size),
+            thread_limit,
+            {
+                let content_matches = content_matches.clone();
+                move |_| (gix_fs::Stack::new(worktree.to_owned()), content_matches)
+            },
+            |chunk, (stack, content_matches)| {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                let mut out = Outcome::default();
+                for entry in chunk {
+                    refresh_entry(
+                        entry,
+                        path_backing,
+                        stack,
+                        content_matches,
+                        timestamp,
+                        options,
+                        &mut out,
+                    )?;
+                }
+                Ok(out)
+            },
+            Aggregate::default(),
+        )?;
+        out.entries_to_process = num_entries;
+        self.timestamp = start;
+        Ok(out)
+    }
+}
+
+fn refresh_entry<E>(
+    entry: &mut Entry,
+    path_backing: &PathStorageRef,
+    stack: &mut gix_fs::Stack,
+    content_matches: &mut impl FnMut(&Entry, &BStr, &Path) -> Result<bool, E>,
+    timestamp: FileTime,
+    options: Options,
+    out: &mut Outcome,
+) -> Result<(), Error>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    if entry.flags.intersects(
+        entry::Flags::UPTODATE
+            | entry::Flags::SKIP_WORKTREE
+            | entry::Flags::ASSUME_VALID
+            | entry::Flags::FSMONITOR_VALID
+            | entry::Flags::INTENT_TO_ADD,
+    ) || entry.stage_raw() != 0
+        || matches!(entry.mode, entry::Mode::COMMIT | entry::Mode::DIR)
+    {
+        out.entries_skipped += 1;
+        return Ok(());
+    }
+
+    let rela_path = entry.path_in(path_backing);
+    let metadata = match stack
+        .make_relative_path_current(rela_path, &mut SymlinkCheck)
+        .and_then(|()| {
+            out.symlink_metadata_calls += 1;
+            crate::fs::Metadata::from_path_no_follow(stack.current())
+        }) {
+        Ok(metadata) if !metadata.is_dir() => metadata,
+        Ok(_dir) => {
+            out.entries_changed += 1;
+            return Ok(());
+        }
+        Err(err) if gix_fs::io_err::is_not_found(err.kind(), err.raw_os_error()) => {
+            out.entries_changed += 1;
+            return Ok(());
+        }
+        Err(err) => {
+            return Err(Error::Io {
+                rela_path: rela_path.to_owned(),
+                source: err,
+            })
+        }
+    };
+    if entry
+        .mode
+        .change_to_match_fs(&metadata, options.fs.symlink, options.fs.executable_bit)
+        .is_some()
+    {
+        out.entries_changed += 1;
+        return Ok(());
+    }
+
+    let new_stat = entry::Stat::from_fs(&metadata)?;
+    // A size of 0 means the entry was found to be racily clean, and its size is unknown. Otherwise, a changed size
+    // means changed content, as it's the size of the worktree file that is recorded. Symlinks may not have a size on all platforms.
+    if entry.mode != entry::Mode::SYMLINK && entry.stat.size != 0 && entry.stat.size != new_stat.size {
+        out.entries_changed += 1;
+        return Ok(());
+    }
+    let stat_matches =
+        new_stat.matches(&entry.stat, options.stat) && (!entry.id.is_empty_blob() || entry.stat.size == 0);
+    let racy_clean = stat_matches && new_stat.is_racy(timestamp, options.stat);
+    if stat_matches && !racy_clean {
+        entry.flags.insert(entry::Flags::UPTODATE);
+        out.entries_up_to_date += 1;
+        return Ok(());
+    }
+    if racy_clean {
+        out.racy_clean += 1;
+    }
+
+    let is_unchanged = content_matches(entry, rela_path, stack.current()).map_err(|err| Error::ContentMatches {
+        rela_path: rela_path.to_owned(),
+        source: Box::new(err),
+    })?;
+    if is_unchanged {
+        entry.stat = new_stat;
+        entry.flags.insert(entry::Flags::UPTODATE);
+        out.entries_updated += 1;
+    } else {
+        if racy_clean {
+            entry.stat.size = 0;
+        }
+        out.entries_changed += 1;
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Aggregate {
+    out: Outcome,
+}
+
+impl Reduce for Aggregate {
+    type Input = Result<Outcome, Error>;
+    type FeedProduce = ();
+    type Output = Outcome;
+    type Error = Error;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        let item = item?;
+        let out = &mut self.out;
+        out.entries_skipped += item.entries_skipped;
+        out.symlink_metadata_calls += item.symlink_metadata_calls;
+        out.entries_up_to_date += item.entries_up_to_date;
+        out.entries_updated += item.entries_updated;
+        out.racy_clean += item.racy_clean;
+        out.entries_changed += item.entries_changed;
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.out)
+    }
+}
+
+/// Assure we don't step through symlinks when obtaining the path to a worktree file.
+struct SymlinkCheck;
+
+impl gix_fs::stack::Delegate for SymlinkCheck {
+    fn push_directory(&mut self, _stack: &gix_fs::Stack) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    #[cfg_attr(windows, allow(unused_variables))]
+    fn push(&mut self, is_last_component: bool, stack: &gix_fs::Stack) -> std::io::Result<()> {
+        #[cfg(windows)]
+        {
+            Ok(())
+        }
+        #[cfg(not(windows))]
+        {
+            if is_last_component {
+                return Ok(());
+            }
+            if stack.current().symlink_metadata()?.is_symlink() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "Cannot step through symlink to perform an lstat",
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    fn pop_directory(&mut self) {}
+}
@@ -0,0 +1,259 @@
+use std::ops::Range;
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{
+    entry,
+    entry::{Flags, Stat},
+    extension::Tree,
+    Entry, State,
+};
+
+/// Configuration related to sparse indexes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// If true, certain entries in the index will be excluded / skipped for certain operations,
+    /// based on the ignore patterns in the `.git/info/sparse-checkout` file. These entries will
+    /// carry the [`SKIP_WORKTREE`][crate::entry::Flags::SKIP_WORKTREE] flag.
+    ///
+    /// This typically is the value of `core.sparseCheckout` in the git configuration.
+    pub sparse_checkout: bool,
+
+    /// Interpret the `.git/info/sparse-checkout` file using _cone mode_.
+    ///
+    /// If true, _cone mode_ is active and entire directories will be included in the checkout, as well as files in the root
+    /// of the repository.
+    /// If false, non-cone mode is active and entries to _include_ will be matched with patterns like those found in `.gitignore` files.
+    ///
+    /// This typically is the value of `core.sparseCheckoutCone` in the git configuration.
+    pub directory_patterns_only: bool,
+
+    /// If true, will attempt to write a sparse index file which only works in cone mode.
+    ///
+    /// A sparse index has [`DIR` entries][crate::entry::Mode::DIR] that represent entire directories to be skipped
+    /// during checkout and other operations due to the added presence of
+    /// the [`SKIP_WORKTREE`][crate::entry::Flags::SKIP_WORKTREE] flag.
+    ///
+    /// This is typically the value of `index.sparse` in the git configuration.
+    pub write_sparse_index: bool,
+}
+
+impl Options {
+    /// Derive a valid mode from all parameters that affect the 'sparseness' of the index.
+    ///
+    /// Some combinations of them degenerate to one particular mode.
+    pub fn sparse_mode(&self) -> Mode {
+        match (
+            self.sparse_checkout,
+            self.directory_patterns_only,
+            self.write_sparse_index,
+        ) {
+            (true, true, true) => Mode::IncludeDirectoriesStoreIncludedEntriesAndExcludedDirs,
+            (true, true, false) => Mode::IncludeDirectoriesStoreAllEntriesSkipUnmatched,
+            (true, false, _) => Mode::IncludeByIgnorePatternStoreAllEntriesSkipUnmatched,
+            (false, _, _) => Mode::Disabled,
+        }
+    }
+}
+
+/// Describes the configuration how a sparse index should be written, or if one should be written at all.
+#[derive(Debug)]
+pub enum Mode {
+    /// index with DIR entries for exclusion and included entries, directory-only include patterns in `.git/info/sparse-checkout` file.
+    IncludeDirectoriesStoreIncludedEntriesAndExcludedDirs,
+    /// index with all file entries and skip worktree flags for exclusion, directory-only include patterns in `.git/info/sparse-checkout` file.
+    IncludeDirectoriesStoreAllEntriesSkipUnmatched,
+    /// index with all file entries and skip-worktree flags for exclusion, `ignore` patterns to include entries in `.git/info/sparse-checkout` file.
+    IncludeByIgnorePatternStoreAllEntriesSkipUnmatched,
+    /// index with all entries, non is excluded, `.git/info/sparse-checkout` file is not considered, a regular index.
+    Disabled,
+}
+
+///
+pub mod expand {
+    use bstr::BString;
+
+    /// The error returned by [State::expand_sparse_directories()](crate::State::expand_sparse_directories()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the tree of the sparse directory at '{path}'")]
+        FromTree {
+            path: BString,
+            source: crate::init::from_tree::Error,
+        },
+    }
+}
+
+/// Sparse directories
+impl State {
+    /// Replace all [sparse directory entries](entry::Mode::DIR) with the entries of the trees they point to, which are
+    /// obtained from `objects`, and return the amount of sparse directories that were expanded.
+    /// `validate` is used to determine which validations to perform on every path component we see.
+    ///
+    /// All new entries are marked with [`SKIP_WORKTREE`](entry::Flags::SKIP_WORKTREE) just like the directory they replace,
+    /// and afterwards the index isn't [sparse](Self::is_sparse()) anymore, similar to what `git` does when it needs a full index.
+    pub fn expand_sparse_directories(
+        &mut self,
+        objects: impl gix_object::Find,
+        validate: gix_validate::path::component::Options,
+    ) -> Result<usize, expand::Error> {
+        let _span = gix_features::trace::coarse!("gix_index::State::expand_sparse_directories()");
+        let mut num_expanded = 0;
+        if self.entries.iter().any(|e| e.mode.is_sparse()) {
+            let mut entries = Vec::with_capacity(self.entries.len());
+            for idx in 0..self.entries.len() {
+                let entry = &self.entries[idx];
+                if !entry.mode.is_sparse() {
+                    entries.push(entry.clone());
+                    continue;
+                }
+                let directory = entry.path(self).to_owned();
+                let tree = State::from_tree(&entry.id, &objects, validate).map_err(|err| expand::Error::FromTree {
+                    path: directory.clone(),
+                    source: err,
+                })?;
+                for sub_entry in tree.entries() {
+                    let path_start = self.path_backing.len();
+                    self.path_backing.extend_from_slice(&directory);
+                    self.path_backing.extend_from_slice(sub_entry.path(&tree));
+                    entries.push(Entry {
+                        stat: Stat::default(),
+                        id: sub_entry.id,
+                        flags: Flags::SKIP_WORKTREE | Flags::EXTENDED,
+                        mode: sub_entry.mode,
+                        path: path_start..self.path_backing.len(),
+                    });
+                }
+                num_expanded += 1;
+            }
+            self.entries = entries;
+            self.entries_changed_in_place();
+        }
+        self.is_sparse = false;
+        Ok(num_expanded)
+    }
+
+    /// Replace all entries in directories for which `is_sparse_directory(directory)` returns `true` with a single
+    /// [sparse directory entry](entry::Mode::DIR) that points to the tree of the directory, and return the amount of
+    /// sparse directories that were created. `directory` is a worktree-relative path without trailing slash.
+    ///
+    /// Directories are only collapsed if all entries within them are unconflicted and marked with
+    /// [`SKIP_WORKTREE`](entry::Flags::SKIP_WORKTREE), and if their tree is known and valid in the [tree extension](Self::tree()),
+    /// so [`write_tree()`](Self::write_tree()) should be called beforehand to have it available for all directories.
+    /// Directories that are not sparse are traversed to find sparse directories within them.
+    ///
+    /// Afterwards the index is [sparse](Self::is_sparse()), unless it contains conflicts in which case nothing happens,
+    /// just like in `git`.
+    pub fn collapse_to_sparse_directories(&mut self, mut is_sparse_directory: impl FnMut(&BStr) -> bool) -> usize {
+        let _span = gix_features::trace::coarse!("gix_index::State::collapse_to_sparse_directories()");
+        if self.entries.iter().any(|e| e.stage_raw() != 0) {
+            return 0;
+        }
+        let Some(mut tree) = self.tree.take() else {
+            self.is_sparse = true;
+            return 0;
+        };
+        let mut directories = Vec::new();
+        collect_sparse_directories(
+            &mut tree,
+            &mut BString::default(),
+            self,
+            &mut is_sparse_directory,
+            &mut directories,
+        );
+        let num_collapsed = directories.len();
+        if num_collapsed != 0 {
+            let mut entries = Vec::with_capacity(self.entries.len());
+            let mut idx = 0;
+            for (range, id, directory_len) in directories {
+                entries.extend_from_slice(&self.entries[idx..range.start]);
+                let path_start = self.entries[range.start].path.start;
+                entries.push(Entry {
+                    stat: Stat::default(),
+                    id,
+                    flags: Flags::SKIP_WORKTREE | Flags::EXTENDED,
+                    mode: entry::Mode::DIR,
+                    // All entries share the directory with trailing slash as prefix, which is the path of the sparse directory.
+                    path: path_start..path_start + directory_len,
+                });
+                idx = range.end;
+            }
+            entries.extend_from_slice(&self.entries[idx..]);
+            self.entries = entries;
+            self.entries_changed_in_place();
+        }
+        recount_entries(&mut tree, &mut BString::default(), self);
+        self.tree = Some(tree);
+        self.is_sparse = true;
+        num_collapsed
+    }
+
+    /// Update all extensions after entries were replaced without being moved.
+    fn entries_changed_in_place(&mut self) {
+        if let Some(fs_monitor) = self.fs_monitor.as_mut() {
+            fs_monitor.mark_all_dirty(self.entries.len());
+        }
+        if let Some(mut tree) = self.tree.take() {
+            recount_entries(&mut tree, &mut BString::default(), self);
+            self.tree = Some(tree);
+        }
+    }
+}
+
+/// Find all directories in `tree` with the path `prefix` (empty or with trailing slash) that `is_sparse_directory()` and that can be collapsed
+/// in `state`, and put the range of their entries, their tree id and the length of their path with trailing slash into `out`.
+/// The nodes of collapsed directories are updated to contain only the sparse directory entry.
+fn collect_sparse_directories(
+    tree: &mut Tree,
+    prefix: &mut BString,
+    state: &State,
+    is_sparse_directory: &mut dyn FnMut(&BStr) -> bool,
+    out: &mut Vec<(Range<usize>, gix_hash::ObjectId, usize)>,
+) {
+    let prefix_len = prefix.len();
+    for child in &mut tree.children {
+        prefix.truncate(prefix_len);
+        prefix.extend_from_slice(&child.name);
+        let is_sparse = is_sparse_directory(prefix.as_bstr());
+        prefix.push(b'/');
+        if is_sparse {
+            let Some(range) = state.prefixed_entries_range(prefix.as_bstr()) else {
+                continue;
+            };
+            let entries = &state.entries[range.clone()];
+            if child.num_entries.is_some_and(|n| n as usize == entries.len())
+                && entries.iter().all(|e| {
+                    e.flags.contains(Flags::SKIP_WORKTREE)
+                        && !e.flags.contains(Flags::INTENT_TO_ADD)
+                        && !(e.mode.is_sparse() && e.path(state).len() == prefix.len())
+                })
+            {
+                child.num_entries = Some(1);
+                child.children.clear();
+                out.push((range, child.id, prefix.len()));
+                continue;
+            }
+        }
+        collect_sparse_directories(child, prefix, state, is_sparse_directory, out);
+    }
+    prefix.truncate(prefix_len);
+}
+
+/// Set the amount of entries of all valid nodes in `tree` with the path `prefix` to what's actually in `state`.
+fn recount_entries(tree: &mut Tree, prefix: &mut BString, state: &State) {
+    if tree.num_entries.is_some() {
+        let num_entries = state
+            .prefixed_entries_range(prefix.as_bstr())
+            .map_or(0, |range| range.len());
+        tree.num_entries = Some(num_entries as u32);
+    }
+    let prefix_len = prefix.len();
+    for child in &mut tree.children {
+        prefix.truncate(prefix_len);
+        prefix.extend_from_slice(&child.name);
+        prefix.push(b'/');
+        recount_entries(child, prefix, state);
+    }
+    prefix.truncate(prefix_len);
+}
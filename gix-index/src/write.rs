@@ -17,6 +17,12 @@ pub enum Extensions {
     Given {
         /// Write the tree-cache extension, if present.
         tree_cache: bool,
+        /// Write the resolve-undo extension, if present.
+        resolve_undo: bool,
+        /// Write the untracked-cache extension, if present.
+        untracked_cache: bool,
+        /// Write the filesystem-monitor extension, if present.
+        fs_monitor: bool,
         /// Write the end-of-index-entry extension.
         end_of_index_entry: bool,
     },
@@ -32,9 +38,15 @@ impl Extensions {
             Extensions::All => Some(signature),
             Extensions::Given {
                 tree_cache,
+                resolve_undo,
+                untracked_cache,
+                fs_monitor,
                 end_of_index_entry,
             } => match signature {
                 extension::tree::SIGNATURE => tree_cache,
+                extension::resolve_undo::SIGNATURE => resolve_undo,
+                extension::untracked_cache::SIGNATURE => untracked_cache,
+                extension::fs_monitor::SIGNATURE => fs_monitor,
                 extension::end_of_index_entry::SIGNATURE => end_of_index_entry,
                 _ => &false,
             }
@@ -45,11 +57,18 @@ impl Extensions {
 
 /// The options for use when [writing an index][State::write_to()].
 ///
-/// Note that default options write either index V2 or V3 depending on the content of the entries.
+/// Note that default options keep V4 indices as V4, and write either index V2 or V3 depending on the content of the entries otherwise.
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Options {
     /// Configures which extensions to write.
     pub extensions: Extensions,
+    /// The version of the index to write, typically controlled by `index.version` or `feature.manyFiles`.
+    ///
+    /// If `None`, the [version of the index](State::version()) is kept if it is [`V4`](Version::V4), and otherwise
+    /// [`V2`](Version::V2) or [`V3`](Version::V3) are written depending on the content of the entries.
+    /// Like in `git`, the same is done if either of these is requested, while [`V4`](Version::V4) is always written as requested
+    /// with prefix-compressed paths for considerably smaller indices.
+    pub version: Option<Version>,
     /// Set the trailing hash of the produced index to all zeroes to save some time.
     ///
     /// This value is typically controlled by `index.skipHash` and is respected when the index is written
@@ -66,28 +85,49 @@ impl State {
         Options {
             extensions,
             skip_hash: _,
+            version,
         }: Options,
     ) -> Result<Version, gix_hash::io::Error> {
         let _span = gix_features::trace::detail!("gix_index::State::write()");
-        let version = self.detect_required_version();
+        let version = match version.unwrap_or(self.version) {
+            Version::V4 => Version::V4,
+            Version::V2 | Version::V3 => self.detect_required_version(),
+        };
 
         let mut write = CountBytes::new(out);
-        let num_entries: u32 = self
-            .entries()
-            .len()
-            .try_into()
-            .expect("definitely not 4billion entries");
-        let removed_entries: u32 = self
-            .entries()
-            .iter()
-            .filter(|e| e.flags.contains(entry::Flags::REMOVE))
-            .count()
-            .try_into()
-            .expect("definitely not too many entries");
-
-        let offset_to_entries = header(&mut write, version, num_entries - removed_entries)?;
-        let offset_to_extensions = entries(&mut write, self, offset_to_entries)?;
-        let (extension_toc, out) = self.write_extensions(write, offset_to_extensions, extensions)?;
+        // With a shared index, only write what changed compared to it, similar to `git`.
+        let split = self.link().and_then(|link| link.split_entries(self));
+        let (num_entries, offset_to_extensions) = match &split {
+            Some((split_entries, _)) => {
+                let num_entries = split_entries.len().try_into().expect("definitely not 4billion entries");
+                let offset_to_entries = header(&mut write, version, num_entries)?;
+                let to_write = split_entries.iter().map(|(entry, path)| (*entry, *path));
+                let offset_to_extensions = entries(&mut write, to_write, version, offset_to_entries)?;
+                (num_entries, offset_to_extensions)
+            }
+            None => {
+                let num_entries: u32 = self
+                    .entries()
+                    .iter()
+                    .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+                    .count()
+                    .try_into()
+                    .expect("definitely not 4billion entries");
+                let offset_to_entries = header(&mut write, version, num_entries)?;
+                let to_write = self
+                    .entries()
+                    .iter()
+                    .filter(|e| !e.flags.contains(entry::Flags::REMOVE))
+                    .map(|e| (e, e.path(self)));
+                let offset_to_extensions = entries(&mut write, to_write, version, offset_to_entries)?;
+                (num_entries, offset_to_extensions)
+            }
+        };
+        let link_bitmaps = match &split {
+            Some((_, bitmaps)) => Some(bitmaps),
+            None => self.link().and_then(|link| link.bitmaps.as_ref()),
+        };
+        let (extension_toc, out) = self.write_extensions(write, offset_to_extensions, extensions, link_bitmaps)?;
 
         if num_entries > 0
             && extensions
@@ -106,17 +146,55 @@ impl State {
         mut write: CountBytes<T>,
         offset_to_extensions: u32,
         extensions: Extensions,
+        link_bitmaps: Option<&extension::link::Bitmaps>,
     ) -> std::io::Result<(Vec<(extension::Signature, u32)>, T)>
     where
         T: std::io::Write,
     {
         type WriteExtFn<'a> = &'a dyn Fn(&mut dyn std::io::Write) -> Option<std::io::Result<extension::Signature>>;
         let extensions: &[WriteExtFn<'_>] = &[
+            &|write| {
+                self.link().map(|link| {
+                    extension::link::write_to(write, &link.shared_index_checksum, link_bitmaps)
+                        .map(|_| extension::link::SIGNATURE)
+                })
+            },
             &|write| {
                 extensions
                     .should_write(extension::tree::SIGNATURE)
                     .and_then(|signature| self.tree().map(|tree| tree.write_to(write).map(|_| signature)))
             },
+            &|write| {
+                extensions
+                    .should_write(extension::resolve_undo::SIGNATURE)
+                    .and_then(|signature| {
+                        self.resolve_undo()
+                            .map(|paths| extension::resolve_undo::write_to(write, paths).map(|_| signature))
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::untracked_cache::SIGNATURE)
+                    .and_then(|signature| {
+                        self.untracked().map(|cache| {
+                            extension::untracked_cache::write_to(write, cache, self.object_hash).map(|_| signature)
+                        })
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::fs_monitor::SIGNATURE)
+                    .and_then(|signature| {
+                        self.fs_monitor().map(|fs_monitor| {
+                            extension::fs_monitor::write_to(write, fs_monitor, |idx| {
+                                self.entries
+                                    .get(idx)
+                                    .is_some_and(|e| e.flags.contains(entry::Flags::REMOVE))
+                            })
+                            .map(|_| signature)
+                        })
+                    })
+            },
             &|write| {
                 self.is_sparse()
                     .then(|| extension::sparse::write_to(write).map(|_| extension::sparse::SIGNATURE))
@@ -164,12 +242,20 @@ fn header<T: std::io::Write>(
     Ok(out.count)
 }
 
-fn entries<T: std::io::Write>(out: &mut CountBytes<T>, state: &State, header_size: u32) -> Result<u32, std::io::Error> {
-    for entry in state.entries() {
-        if entry.flags.contains(entry::Flags::REMOVE) {
+fn entries<'a, T: std::io::Write>(
+    out: &mut CountBytes<T>,
+    entries: impl Iterator<Item = (&'a crate::Entry, &'a bstr::BStr)>,
+    version: Version,
+    header_size: u32,
+) -> Result<u32, std::io::Error> {
+    let mut previous_path: &[u8] = &[];
+    for (entry, path) in entries {
+        if version == Version::V4 {
+            entry.write_v4_with_path_to(&mut *out, path, previous_path)?;
+            previous_path = path;
             continue;
         }
-        entry.write_to(&mut *out, state)?;
+        entry.write_with_path_to(&mut *out, path)?;
         match (out.count - header_size) % 8 {
             0 => {}
             n => {
@@ -0,0 +1,145 @@
+use bstr::{BStr, BString, ByteSlice};
+use gix_object::tree::EntryKind;
+
+use crate::{entry, extension::Tree, Entry, PathStorageRef, State};
+
+/// The error returned by [`State::write_tree()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot write a tree while '{path}' is conflicted")]
+    Conflict { path: BString },
+    #[error("The entry at '{path}' has an invalid mode that can't be represented in a tree")]
+    InvalidMode { path: BString },
+    #[error(transparent)]
+    Write(#[from] gix_object::write::Error),
+}
+
+/// Trees
+impl State {
+    /// Write the tree objects needed to represent all entries into `objects` and return the id of the root tree, similar
+    /// to `git write-tree`.
+    ///
+    /// Trees whose nodes in the [tree extension](Self::tree()) are still valid are reused without being written again,
+    /// and the tree extension is created or refreshed with the ids of all trees that were written, so subsequent calls
+    /// only write trees whose entries have changed.
+    ///
+    /// Entries that are marked as `intent-to-add` are skipped, and the trees containing them remain invalid in the tree extension.
+    /// Entries of unresolved conflicts cause an error.
+    pub fn write_tree(&mut self, objects: &dyn gix_object::Write) -> Result<gix_hash::ObjectId, Error> {
+        let _span = gix_features::trace::coarse!("gix_index::State::write_tree()", num_entries = self.entries.len());
+        if let Some(entry) = self.entries.iter().find(|e| e.stage_raw() != 0) {
+            return Err(Error::Conflict {
+                path: entry.path(self).to_owned(),
+            });
+        }
+        let root = update(
+            BStr::new(""),
+            &self.entries,
+            &self.path_backing,
+            0,
+            self.tree.take(),
+            objects,
+        )?;
+        let id = root.id;
+        self.tree = Some(root);
+        Ok(id)
+    }
+}
+
+/// Update and return the tree node named `name` which contains all `entries` when stripping `prefix_len` bytes off their path,
+/// using the `previous` version of the node to reuse valid trees.
+fn update(
+    name: &BStr,
+    entries: &[Entry],
+    path_backing: &PathStorageRef,
+    prefix_len: usize,
+    previous: Option<Tree>,
+    objects: &dyn gix_object::Write,
+) -> Result<Tree, Error> {
+    let mut previous_children = match previous {
+        Some(previous) if previous.num_entries.is_some_and(|n| n as usize == entries.len()) => return Ok(previous),
+        Some(previous) => previous.children,
+        None => Vec::new(),
+    };
+
+    let mut tree = gix_object::Tree::empty();
+    let mut children = Vec::new();
+    let mut is_valid = true;
+    let mut idx = 0;
+    while let Some(entry) = entries.get(idx) {
+        let path = entry.path_in(path_backing);
+        let relative_path = &path[prefix_len..];
+        match relative_path.find_byte(b'/') {
+            Some(slash) if entry.mode.is_sparse() && slash + 1 == relative_path.len() => {
+                idx += 1;
+                tree.entries.push(gix_object::tree::Entry {
+                    mode: EntryKind::Tree.into(),
+                    filename: relative_path[..slash].into(),
+                    oid: entry.id,
+                });
+            }
+            Some(slash) => {
+                let dir_name = &relative_path[..slash];
+                let end = idx
+                    + entries[idx..]
+                        .iter()
+                        .take_while(|e| {
+                            let path = e.path_in(path_backing);
+                            path.len() > prefix_len + slash
+                                && path[prefix_len..].starts_with(dir_name)
+                                && path[prefix_len + slash] == b'/'
+                        })
+                        .count();
+                let previous_child = previous_children
+                    .iter()
+                    .position(|child| child.name.as_slice() == dir_name)
+                    .map(|pos| previous_children.swap_remove(pos));
+                let child = update(
+                    dir_name.as_bstr(),
+                    &entries[idx..end],
+                    path_backing,
+                    prefix_len + slash + 1,
+                    previous_child,
+                    objects,
+                )?;
+                is_valid &= child.num_entries.is_some();
+                if child.id != gix_hash::ObjectId::empty_tree(child.id.kind()) {
+                    tree.entries.push(gix_object::tree::Entry {
+                        mode: EntryKind::Tree.into(),
+                        filename: dir_name.into(),
+                        oid: child.id,
+                    });
+                    children.push(child);
+                }
+                idx = end;
+            }
+            None => {
+                idx += 1;
+                if entry
+                    .flags
+                    .intersects(entry::Flags::INTENT_TO_ADD | entry::Flags::REMOVE)
+                {
+                    is_valid = false;
+                    continue;
+                }
+                tree.entries.push(gix_object::tree::Entry {
+                    mode: entry
+                        .mode
+                        .to_tree_entry_mode()
+                        .ok_or_else(|| Error::InvalidMode { path: path.to_owned() })?,
+                    filename: relative_path.into(),
+                    oid: entry.id,
+                });
+            }
+        }
+    }
+
+    children.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Tree {
+        name: name.as_bytes().into(),
+        id: objects.write(&tree)?,
+        num_entries: is_valid.then_some(entries.len() as u32),
+        children,
+    })
+}
@@ -227,6 +227,65 @@ fn remove_entry_at_index() {
     assert_eq!(file.entries().len(), 0);
 }
 
+#[test]
+fn upsert_entry() {
+    let mut file = Fixture::Generated("v4_more_files_IEOT").open();
+    let num_entries = file.entries().len();
+    let entry = file.entry(0).clone();
+    let tree = file.tree().expect("present").clone();
+    assert!(tree.num_entries.is_some(), "the tree is valid initially");
+
+    let mut new_entry_idx = 0;
+    for path in ["d/new", "0-first", "zz-last"] {
+        let idx = file.upsert_entry(entry.stat, entry.id, entry.flags, entry.mode, path.into());
+        assert_eq!(file.entry(idx).path(&file), path);
+        if path == "d/new" {
+            new_entry_idx = idx + 1;
+        }
+    }
+    assert_eq!(file.entry(0).path(&file), "0-first");
+    assert_eq!(file.entry(num_entries + 2).path(&file), "zz-last");
+    assert_eq!(file.entries().len(), num_entries + 3);
+    assert!(file.verify_entries().is_ok(), "entries are still sorted");
+
+    let tree = file.tree().expect("still present");
+    assert_eq!(tree.num_entries, None, "the root is invalidated");
+    for child in &tree.children {
+        assert_eq!(
+            child.num_entries.is_none(),
+            child.name.as_slice() == b"d",
+            "only trees along the path are invalidated"
+        );
+    }
+
+    let id = gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1);
+    let idx = file.upsert_entry(entry.stat, id, entry.flags, entry.mode, "d/new".into());
+    assert_eq!(idx, new_entry_idx, "existing entries are updated in place");
+    assert_eq!(file.entry(idx).id, id);
+    assert_eq!(file.entries().len(), num_entries + 3);
+}
+
+#[test]
+fn upsert_entry_in_other_stage() {
+    let mut file = Fixture::Loose("conflicting-file").open();
+    let entry = file.entry(0).clone();
+    let path = entry.path(&file).to_owned();
+    let idx = file.upsert_entry(
+        entry.stat,
+        entry.id,
+        gix_index::entry::Flags::empty(),
+        entry.mode,
+        path.as_ref(),
+    );
+    assert_eq!(idx, 0, "unconflicted entries sort first");
+    assert_eq!(file.entries().len(), 4, "entries in other stages are kept");
+    assert!(file.verify_entries().is_ok());
+
+    assert_eq!(file.remove_entries_at_path(path.as_ref()), 4, "all stages are removed");
+    assert_eq!(file.entries().len(), 0);
+    assert_eq!(file.remove_entries_at_path(path.as_ref()), 0);
+}
+
 #[test]
 fn sort_entries() {
     let mut file = Fixture::Generated("v4_more_files_IEOT").open();
@@ -324,3 +383,78 @@ fn check_prefix(index: &gix_index::State, prefix: &str, expected: &[&str]) {
         "{prefix:?}"
     );
 }
+
+#[test]
+fn removing_conflicted_entries_records_them_for_resolve_undo() {
+    let mut file = Fixture::Loose("conflicting-file").open();
+    assert!(file.resolve_undo().is_none());
+    let expected_stages: Vec<_> = file
+        .entries()
+        .iter()
+        .map(|e| {
+            Some(gix_index::extension::resolve_undo::Stage {
+                mode: e.mode.bits(),
+                id: e.id,
+            })
+        })
+        .collect();
+
+    assert_eq!(file.remove_entries_at_path("file".into()), 3);
+    let paths = file.resolve_undo().expect("created on demand");
+    assert_eq!(paths.len(), 1);
+    assert_eq!(paths[0].name, "file");
+    assert_eq!(paths[0].stages.as_slice(), expected_stages);
+
+    let mut file = Fixture::Loose("REUC").open();
+    let expected = file.resolve_undo().cloned();
+    file.remove_entries(|_, path, _| path == "binary");
+    assert_eq!(
+        file.resolve_undo().cloned(),
+        expected,
+        "entries without conflict don't affect the extension"
+    );
+}
+
+#[test]
+fn untracked_cache_is_invalidated_along_added_and_removed_paths() {
+    let mut file = Fixture::Loose("UNTR").open();
+    let is_valid = |file: &gix_index::File, name: &str| {
+        let dir = file
+            .untracked()
+            .expect("present")
+            .directories()
+            .iter()
+            .find(|d| d.name == name)
+            .expect("present");
+        if dir.stat.is_none() {
+            assert!(
+                dir.untracked_entries.is_empty(),
+                "invalid directories forget their untracked files"
+            );
+        }
+        dir.stat.is_some()
+    };
+    for name in ["", "done", "dtwo", "dthree"] {
+        assert!(is_valid(&file, name), "{name:?} is valid initially");
+    }
+
+    let entry = file.entry(0).clone();
+    file.upsert_entry(entry.stat, entry.id, entry.flags, entry.mode, "dtwo/two".into());
+    assert!(
+        !is_valid(&file, "dtwo"),
+        "the directory of the new entry is invalidated"
+    );
+    assert!(
+        !is_valid(&file, ""),
+        "the parent is invalidated as well as untracked directories are listed"
+    );
+    assert!(is_valid(&file, "dthree"));
+    assert!(is_valid(&file, "done"));
+
+    file.remove_entries_at_path("done/one".into());
+    assert!(
+        !is_valid(&file, "done"),
+        "the directory of removed entries is invalidated"
+    );
+    assert!(is_valid(&file, "dthree"));
+}
@@ -205,7 +205,15 @@ fn fsmn_v1() {
     let file = loose_file("FSMN");
     assert_eq!(file.version(), Version::V2);
 
-    assert!(file.fs_monitor().is_some());
+    let fs_monitor = file.fs_monitor().expect("present");
+    assert!(
+        matches!(fs_monitor.token(), gix_index::extension::fs_monitor::Token::V2 { .. }),
+        "the extension was written with an opaque token as used by the second version of the hook protocol"
+    );
+    assert!(
+        fs_monitor.dirty_entries().iter().all(|idx| *idx < file.entries().len()),
+        "dirty entries refer to existing entries"
+    );
 }
 
 #[test]
@@ -350,8 +358,8 @@ fn split_index_and_regular_index_of_same_content_are_indeed_the_same() {
     );
 
     assert!(
-        split.link().is_none(),
-        "link extension is dissolved, merging the shared index permanently into the split one (for now)"
+        split.link().is_some(),
+        "the link extension is kept after merging the shared index so the split index can be written again"
     );
 
     let regular = verify(
@@ -10,16 +10,30 @@ fn roundtrips() -> crate::Result {
         (Loose("extended-flags"), only_tree_ext()),
         (Loose("conflicting-file"), only_tree_ext()),
         (Loose("very-long-path"), only_tree_ext()),
+        (Loose("REUC"), all_ext_but_eoie()),
+        (Loose("UNTR"), all_ext_but_eoie()),
+        (Loose("UNTR-with-oids"), all_ext_but_eoie()),
+        (Loose("FSMN"), all_ext_but_eoie()),
         (
             Generated("v2"),
             options_with(write::Extensions::Given {
                 tree_cache: true,
+                resolve_undo: false,
+                untracked_cache: false,
+                fs_monitor: false,
                 end_of_index_entry: true,
             }),
         ),
         (Generated("V2_empty"), only_tree_ext()),
         (Generated("v2_more_files"), only_tree_ext()),
         (Generated("v2_all_file_kinds"), only_tree_ext()),
+        (
+            Generated("v4_more_files"),
+            Options {
+                version: Some(Version::V4),
+                ..only_tree_ext()
+            },
+        ),
     ];
 
     for (fixture, options) in input {
@@ -46,8 +60,8 @@ fn skip_hash() -> crate::Result {
 
     expected.set_path(&path);
     expected.write(Options {
-        extensions: Default::default(),
         skip_hash: false,
+        ..Default::default()
     })?;
 
     let actual = gix_index::File::at(
@@ -63,8 +77,8 @@ fn skip_hash() -> crate::Result {
     );
 
     expected.write(Options {
-        extensions: Default::default(),
         skip_hash: true,
+        ..Default::default()
     })?;
 
     let actual = gix_index::File::at(
@@ -118,6 +132,101 @@ fn roundtrips_sparse_index() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn split_index_roundtrips() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = copy_split_index_fixture_to(tmp.path())?;
+    let expected = gix_index::File::at(&path, gix_hash::Kind::Sha1, false, Default::default())?;
+    let shared_index_checksum = expected.link().expect("split index").shared_index_checksum;
+
+    let mut index = expected.clone();
+    index.write(Default::default())?;
+    let (split, _) = read_state(&path)?;
+    assert_eq!(
+        split.link().expect("still present").shared_index_checksum,
+        shared_index_checksum,
+        "the shared index stays the same"
+    );
+    assert_eq!(
+        entry_paths(&split),
+        ["", "d", "e"],
+        "only 'b' replaces a shared entry and is written without path, while 'd' and 'e' aren't in the shared index. \
+         Git also writes unchanged 'y' and 'z', which isn't necessary."
+    );
+
+    let actual = gix_index::File::at(&path, gix_hash::Kind::Sha1, false, Default::default())?;
+    assert_same_entries(&actual, &expected);
+    Ok(())
+}
+
+#[test]
+fn split_index_only_stores_changes_to_its_shared_index() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let path = copy_split_index_fixture_to(tmp.path())?;
+    let mut index = gix_index::File::at(&path, gix_hash::Kind::Sha1, false, Default::default())?;
+
+    let entry = index.entry_by_path("b".into()).expect("present").clone();
+    let new_id = gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1);
+    index.upsert_entry(entry.stat, new_id, entry.flags, entry.mode, "b".into());
+    index.upsert_entry(entry.stat, entry.id, entry.flags, entry.mode, "f".into());
+    assert_eq!(index.remove_entries_at_path("y".into()), 1);
+    index.write(Default::default())?;
+
+    let (split, _) = read_state(&path)?;
+    assert_eq!(entry_paths(&split), ["", "d", "e", "f"]);
+    let actual = gix_index::File::at(&path, gix_hash::Kind::Sha1, false, Default::default())?;
+    actual.verify_entries()?;
+    assert_same_entries(&actual, &index);
+    assert_eq!(actual.entry_by_path("b".into()).expect("present").id, new_id);
+    assert!(actual.entry_by_path("y".into()).is_none());
+
+    index.remove_link();
+    index.write(Default::default())?;
+    let (regular, _) = read_state(&path)?;
+    assert!(regular.link().is_none());
+    assert_eq!(
+        entry_paths(&regular),
+        ["b", "d", "e", "f", "z"],
+        "without link, all entries are written"
+    );
+    Ok(())
+}
+
+fn copy_split_index_fixture_to(dir: &std::path::Path) -> gix_testtools::Result<std::path::PathBuf> {
+    let base = gix_testtools::scripted_fixture_read_only_standalone(
+        std::path::Path::new("make_index").join("v2_split_vs_regular_index.sh"),
+    )?;
+    for entry in std::fs::read_dir(base.join("split").join(".git"))? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if name == "index" || name.to_string_lossy().starts_with("sharedindex.") {
+            std::fs::copy(entry.path(), dir.join(name))?;
+        }
+    }
+    Ok(dir.join("index"))
+}
+
+fn read_state(path: &std::path::Path) -> gix_testtools::Result<(State, Option<gix_hash::ObjectId>)> {
+    Ok(State::from_bytes(
+        &std::fs::read(path)?,
+        FileTime::now(),
+        gix_hash::Kind::Sha1,
+        Default::default(),
+    )?)
+}
+
+fn entry_paths(state: &State) -> Vec<&bstr::BStr> {
+    state.entries().iter().map(|e| e.path(state)).collect()
+}
+
+fn assert_same_entries(actual: &State, expected: &State) {
+    assert_eq!(actual.entries().len(), expected.entries().len());
+    for (a, e) in actual.entries().iter().zip(expected.entries()) {
+        assert_eq!(a.path(actual), e.path(expected));
+        assert_eq!((a.id, a.stat, a.flags, a.mode), (e.id, e.stat, e.flags, e.mode));
+    }
+}
+
 #[test]
 fn state_comparisons_with_various_extension_configurations() {
     for fixture in [
@@ -133,9 +242,8 @@ fn state_comparisons_with_various_extension_configurations() {
         Generated("v2_more_files"),
         Generated("v2_all_file_kinds"),
         Generated("v2_split_index"),
-        // TODO: this fails because git allows to configure the index version while gitoxide doesn't
-        //       the fixture artificially sets the version to V4 and gitoxide writes it back out as the lowest required version, V2
-        // Generated("v4_more_files_IEOT"),
+        Generated("v4_more_files_IEOT"),
+        Generated("v4_more_files"),
         Generated("v3_skip_worktree"),
         Generated("v3_added_files"),
         Generated("v3_sparse_index_non_cone"),
@@ -148,14 +256,22 @@ fn state_comparisons_with_various_extension_configurations() {
             options_with(write::Extensions::All),
             options_with(write::Extensions::Given {
                 tree_cache: true,
+                resolve_undo: true,
+                untracked_cache: false,
+                fs_monitor: true,
                 end_of_index_entry: false,
             }),
             options_with(write::Extensions::Given {
                 tree_cache: false,
+                resolve_undo: false,
+                untracked_cache: true,
+                fs_monitor: false,
                 end_of_index_entry: true,
             }),
         ] {
-            let expected = fixture.open();
+            let mut expected = fixture.open();
+            // Split indices only contain what changed compared to their shared index, which isn't read here.
+            expected.remove_link();
             let fixture = fixture.to_name();
 
             let mut out = Vec::<u8>::new();
@@ -182,31 +298,101 @@ fn extended_flags_automatically_upgrade_the_version_to_avoid_data_loss() -> crat
 }
 
 #[test]
-fn remove_flag_is_respected() -> crate::Result {
-    let mut index = Generated("v4_more_files_IEOT").open();
-    let total_entries = 10;
-    assert_eq!(index.entries().len(), total_entries);
-    let entries_to_remove = 4;
-    for entry in &mut index.entries_mut()[..entries_to_remove] {
-        entry.flags.toggle(entry::Flags::REMOVE);
+fn v4_is_written_only_if_requested() -> crate::Result {
+    let index = Generated("v2_more_files").open();
+    let mut v2 = Vec::new();
+    index.write_to(&mut v2, Default::default())?;
+
+    for (requested, expected) in [
+        (Some(Version::V2), Version::V2),
+        (Some(Version::V3), Version::V2),
+        (Some(Version::V4), Version::V4),
+    ] {
+        let mut buf = Vec::new();
+        let (actual_version, _digest) = index.write_to(
+            &mut buf,
+            Options {
+                version: requested,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(actual_version, expected, "V3 is only written if extended flags need it");
+
+        let (actual, _) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+        assert_eq!(actual.version(), expected);
+        assert_eq!(actual.entries(), index.entries());
+        assert_eq!(actual.path_backing(), index.path_backing());
+        if expected == Version::V4 {
+            assert!(
+                buf.len() < v2.len(),
+                "prefix-compressed paths without padding save space"
+            );
+        }
     }
-    let mut buf = Vec::<u8>::new();
-    index.write_to(&mut buf, Default::default())?;
+    Ok(())
+}
 
-    let (state, _checksum) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
-    assert_eq!(
-        state.entries().len(),
-        total_entries - entries_to_remove,
-        "entries are removed when writing"
-    );
-    assert_eq!(
-        state.entries().iter().map(|e| e.path(&state)).collect::<Vec<_>>(),
-        index.entries()[entries_to_remove..]
-            .iter()
-            .map(|e| e.path(&index))
-            .collect::<Vec<_>>(),
-        "the correct entries are removed"
-    );
+#[test]
+fn v4_is_kept_unless_another_version_is_requested() -> crate::Result {
+    let index = Generated("v4_more_files").open();
+    assert_eq!(index.version(), Version::V4);
+
+    for (requested, expected) in [
+        (None, Version::V4),
+        (Some(Version::V4), Version::V4),
+        (Some(Version::V2), Version::V2),
+    ] {
+        let mut buf = Vec::new();
+        let (actual_version, _digest) = index.write_to(
+            &mut buf,
+            Options {
+                version: requested,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(actual_version, expected, "the default keeps V4 so it doesn't have to be passed");
+
+        let (actual, _) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+        assert_eq!(actual.version(), expected);
+        assert_eq!(actual.entries(), index.entries());
+    }
+    Ok(())
+}
+
+#[test]
+fn remove_flag_is_respected() -> crate::Result {
+    for version in [None, Some(Version::V4)] {
+        let mut index = Generated("v4_more_files_IEOT").open();
+        let total_entries = 10;
+        assert_eq!(index.entries().len(), total_entries);
+        let entries_to_remove = 4;
+        for entry in &mut index.entries_mut()[..entries_to_remove] {
+            entry.flags.toggle(entry::Flags::REMOVE);
+        }
+        let mut buf = Vec::<u8>::new();
+        index.write_to(
+            &mut buf,
+            Options {
+                version,
+                ..Default::default()
+            },
+        )?;
+
+        let (state, _checksum) = State::from_bytes(&buf, FileTime::now(), gix_hash::Kind::Sha1, Default::default())?;
+        assert_eq!(
+            state.entries().len(),
+            total_entries - entries_to_remove,
+            "entries are removed when writing"
+        );
+        assert_eq!(
+            state.entries().iter().map(|e| e.path(&state)).collect::<Vec<_>>(),
+            index.entries()[entries_to_remove..]
+                .iter()
+                .map(|e| e.path(&index))
+                .collect::<Vec<_>>(),
+            "the correct entries are removed, and paths are compressed relative to the previously written one"
+        );
+    }
     Ok(())
 }
 
@@ -293,14 +479,30 @@ fn only_tree_ext() -> Options {
         extensions: write::Extensions::Given {
             end_of_index_entry: false,
             tree_cache: true,
+            resolve_undo: false,
+            untracked_cache: false,
+            fs_monitor: false,
         },
-        skip_hash: false,
+        ..Default::default()
+    }
+}
+
+fn all_ext_but_eoie() -> Options {
+    Options {
+        extensions: write::Extensions::Given {
+            end_of_index_entry: false,
+            tree_cache: true,
+            resolve_undo: true,
+            untracked_cache: true,
+            fs_monitor: true,
+        },
+        ..Default::default()
     }
 }
 
 fn options_with(extensions: write::Extensions) -> Options {
     Options {
         extensions,
-        skip_hash: false,
+        ..Default::default()
     }
 }
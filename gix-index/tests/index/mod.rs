@@ -3,6 +3,9 @@ mod entry;
 mod file;
 mod fs;
 mod init;
+mod refresh;
+mod sparse;
+mod write_tree;
 
 use std::path::{Path, PathBuf};
 
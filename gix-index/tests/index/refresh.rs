@@ -0,0 +1,209 @@
+use std::{
+    path::Path,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use bstr::BStr;
+use filetime::FileTime;
+use gix_index::{entry, refresh, Entry};
+
+fn worktree_and_index() -> crate::Result<(gix_testtools::tempfile::TempDir, gix_index::File)> {
+    let tmp = gix_testtools::scripted_fixture_writable_standalone("make_index/v2_more_files.sh")?;
+    let index = gix_index::File::at(
+        tmp.path().join(".git").join("index"),
+        gix_hash::Kind::Sha1,
+        false,
+        Default::default(),
+    )?;
+    Ok((tmp, index))
+}
+
+fn set_mtime_to_the_past(worktree: &Path, index: &gix_index::State) -> crate::Result {
+    for entry in index.entries() {
+        let path = worktree.join(entry.path(index).to_string());
+        if path.exists() {
+            filetime::set_file_mtime(path, FileTime::from_unix_time(1_000_000, 0))?;
+        }
+    }
+    Ok(())
+}
+
+fn hash_matches(calls: &AtomicUsize) -> impl FnMut(&Entry, &BStr, &Path) -> std::io::Result<bool> + Send + Clone + '_ {
+    move |entry, _rela_path, path| {
+        calls.fetch_add(1, Ordering::Relaxed);
+        let id = gix_object::compute_hash(gix_hash::Kind::Sha1, gix_object::Kind::Blob, &std::fs::read(path)?)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        Ok(id == entry.id)
+    }
+}
+
+fn clear_up_to_date_flags(index: &mut gix_index::State) {
+    for entry in index.entries_mut() {
+        entry.flags.remove(entry::Flags::UPTODATE);
+    }
+}
+
+#[test]
+fn content_checks_update_stat_and_matching_stats_are_up_to_date() -> crate::Result {
+    let (tmp, mut index) = worktree_and_index()?;
+    std::fs::write(tmp.path().join("a"), "changed")?;
+    std::fs::remove_file(tmp.path().join("d").join("b"))?;
+    set_mtime_to_the_past(tmp.path(), &index)?;
+
+    let calls = AtomicUsize::default();
+    let should_interrupt = AtomicBool::default();
+    let out = index.refresh(
+        tmp.path(),
+        hash_matches(&calls),
+        &should_interrupt,
+        refresh::Options::default(),
+    )?;
+    assert_eq!(
+        out,
+        refresh::Outcome {
+            entries_to_process: 6,
+            entries_skipped: 0,
+            symlink_metadata_calls: 6,
+            entries_up_to_date: 0,
+            entries_updated: 4,
+            racy_clean: 0,
+            entries_changed: 2,
+        },
+        "the stat information of files never matches after checkout, so content is compared"
+    );
+    assert_eq!(
+        calls.load(Ordering::Relaxed),
+        5,
+        "the size of 'a' changed, but as its recorded size is 0 the content has to be compared"
+    );
+    assert_eq!(
+        index
+            .entries()
+            .iter()
+            .filter(|e| e.flags.contains(entry::Flags::UPTODATE))
+            .count(),
+        4
+    );
+
+    let out = index.refresh(
+        tmp.path(),
+        hash_matches(&calls),
+        &should_interrupt,
+        refresh::Options::default(),
+    )?;
+    assert_eq!(out.entries_skipped, 4, "up-to-date entries aren't checked again");
+    assert_eq!(out.entries_changed, 2);
+
+    clear_up_to_date_flags(&mut index);
+    let out = index.refresh(
+        tmp.path(),
+        hash_matches(&calls),
+        &should_interrupt,
+        refresh::Options::default(),
+    )?;
+    assert_eq!(out.entries_up_to_date, 4, "now the stat information matches");
+    assert_eq!(out.entries_updated, 0);
+    assert_eq!(
+        calls.load(Ordering::Relaxed),
+        7,
+        "only the content of 'a' was compared in each of the following refreshes"
+    );
+    Ok(())
+}
+
+#[test]
+fn racily_clean_entries_are_checked_by_content() -> crate::Result {
+    let (tmp, mut index) = worktree_and_index()?;
+    set_mtime_to_the_past(tmp.path(), &index)?;
+    let calls = AtomicUsize::default();
+    let should_interrupt = AtomicBool::default();
+    index.refresh(
+        tmp.path(),
+        hash_matches(&calls),
+        &should_interrupt,
+        refresh::Options::default(),
+    )?;
+    assert!(
+        index.timestamp() > FileTime::from_unix_time(1_000_000, 0),
+        "the timestamp is updated to when the refresh started"
+    );
+
+    clear_up_to_date_flags(&mut index);
+    index.set_timestamp(FileTime::from_unix_time(1_000_000, 0));
+    let out = index.refresh(
+        tmp.path(),
+        |_entry: &Entry, rela_path: &BStr, _path: &Path| Ok::<_, std::io::Error>(rela_path != "a"),
+        &should_interrupt,
+        refresh::Options::default(),
+    )?;
+    assert_eq!(out.racy_clean, 6, "all entries were written at the index timestamp");
+    assert_eq!(out.entries_updated, 5);
+    assert_eq!(
+        out.entries_changed, 1,
+        "the content of 'a' was changed while retaining its stat"
+    );
+    let a = index.entry_by_path("a".into()).expect("present");
+    assert_eq!(
+        a.stat.size, 0,
+        "the size is reset to assure it keeps showing as changed"
+    );
+    assert!(!a.flags.contains(entry::Flags::UPTODATE));
+    Ok(())
+}
+
+#[test]
+fn many_entries_are_checked_in_parallel() -> crate::Result {
+    let mut state = gix_index::State::new(gix_hash::Kind::Sha1);
+    let num_entries = refresh::THREAD_COST * 4;
+    for idx in 0..num_entries {
+        state.dangerously_push_entry(
+            Default::default(),
+            gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1),
+            entry::Flags::empty(),
+            entry::Mode::FILE,
+            format!("dir/{idx:05}").as_str().into(),
+        );
+    }
+    state.dangerously_push_entry(
+        Default::default(),
+        gix_hash::ObjectId::empty_blob(gix_hash::Kind::Sha1),
+        entry::Flags::SKIP_WORKTREE,
+        entry::Mode::FILE,
+        "skipped".into(),
+    );
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let out = state.refresh(
+        tmp.path(),
+        |_: &Entry, _: &BStr, _: &Path| -> std::io::Result<bool> { unreachable!("no file exists") },
+        &AtomicBool::default(),
+        refresh::Options {
+            thread_limit: Some(4),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(out.entries_to_process, num_entries + 1);
+    assert_eq!(out.entries_skipped, 1);
+    assert_eq!(out.entries_changed, num_entries, "none of the files exist");
+    Ok(())
+}
+
+#[test]
+fn interrupts_are_respected() -> crate::Result {
+    let (tmp, mut index) = worktree_and_index()?;
+    let timestamp = index.timestamp();
+    let err = index
+        .refresh(
+            tmp.path(),
+            |_: &Entry, _: &BStr, _: &Path| Ok::<_, std::io::Error>(true),
+            &AtomicBool::new(true),
+            refresh::Options::default(),
+        )
+        .unwrap_err();
+    assert!(matches!(err, refresh::Error::Interrupted));
+    assert_eq!(
+        index.timestamp(),
+        timestamp,
+        "the timestamp is unchanged if the refresh didn't finish"
+    );
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use bstr::ByteSlice;
+use gix_index::entry::{Flags, Mode};
+
+use crate::{fixture_index_path, index::Fixture};
+
+fn odb(fixture: &str) -> gix_odb::Handle {
+    let git_dir = fixture_index_path(fixture).parent().expect("index in .git").to_owned();
+    gix_odb::at(git_dir.join("objects")).expect("valid object database")
+}
+
+fn paths_and_flags(state: &gix_index::State) -> Vec<(String, Mode, Flags)> {
+    state
+        .entries()
+        .iter()
+        .map(|e| (e.path(state).to_str_lossy().into_owned(), e.mode, e.flags))
+        .collect()
+}
+
+#[test]
+fn expand_sparse_directories_yields_the_non_sparse_index() -> crate::Result {
+    let mut sparse = Fixture::Generated("v3_sparse_index").open();
+    let expected = Fixture::Generated("v3_skip_worktree").open();
+    assert!(sparse.is_sparse());
+
+    let num_expanded = sparse.expand_sparse_directories(odb("v3_sparse_index"), Default::default())?;
+    assert_eq!(num_expanded, 2, "c1/c3 and d");
+    assert!(!sparse.is_sparse());
+    assert_eq!(paths_and_flags(&sparse), paths_and_flags(&expected));
+    assert_eq!(
+        sparse.entries().iter().map(|e| e.id).collect::<Vec<_>>(),
+        expected.entries().iter().map(|e| e.id).collect::<Vec<_>>()
+    );
+    let tree = sparse.tree().expect("tree extension is kept");
+    assert_eq!(tree.num_entries, Some(sparse.entries().len() as u32));
+    Ok(())
+}
+
+#[test]
+fn collapse_to_sparse_directories_yields_the_sparse_index() -> crate::Result {
+    let mut index = Fixture::Generated("v3_skip_worktree").open();
+    let expected = Fixture::Generated("v3_sparse_index").open();
+    assert!(!index.is_sparse());
+    index.write_tree(&gix_odb::sink(gix_hash::Kind::Sha1))?;
+
+    let mut seen = Vec::new();
+    let num_collapsed = index.collapse_to_sparse_directories(|dir| {
+        seen.push(dir.to_owned());
+        dir != "c1" && dir != "c1/c2"
+    });
+    assert_eq!(num_collapsed, 2, "c1/c3 and d");
+    assert_eq!(seen, ["c1", "c1/c2", "c1/c3", "d"], "sparse directories aren't entered");
+    assert!(index.is_sparse());
+    assert_eq!(paths_and_flags(&index), paths_and_flags(&expected));
+    assert_eq!(
+        index.entries().iter().map(|e| e.id).collect::<Vec<_>>(),
+        expected.entries().iter().map(|e| e.id).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        index.tree(),
+        expected.tree(),
+        "the tree extension matches the one of git"
+    );
+
+    index.expand_sparse_directories(odb("v3_skip_worktree"), Default::default())?;
+    let expected = Fixture::Generated("v3_skip_worktree").open();
+    assert_eq!(
+        paths_and_flags(&index),
+        paths_and_flags(&expected),
+        "expansion reverses it"
+    );
+    Ok(())
+}
+
+#[test]
+fn collapse_to_sparse_directories_requires_skipped_entries_and_valid_trees() -> crate::Result {
+    let mut index = Fixture::Generated("v3_skip_worktree").open();
+    index.remove_tree();
+    assert_eq!(
+        index.collapse_to_sparse_directories(|_| true),
+        0,
+        "without tree extension, nothing can be collapsed"
+    );
+    assert!(index.is_sparse(), "but the index is still considered sparse");
+
+    index.write_tree(&gix_odb::sink(gix_hash::Kind::Sha1))?;
+    assert_eq!(
+        index.collapse_to_sparse_directories(|_| true),
+        2,
+        "c1 contains entries without skip-worktree flag, so only c1/c3 and d are sparse"
+    );
+    Ok(())
+}
@@ -0,0 +1,182 @@
+use std::cell::RefCell;
+
+use gix_hash::ObjectId;
+use gix_index::entry::{Flags, Stage};
+
+use crate::index::Fixture;
+
+/// A sink that remembers the ids of all objects written to it.
+struct Recorder {
+    sink: gix_odb::Sink,
+    written: RefCell<Vec<ObjectId>>,
+}
+
+impl Recorder {
+    fn new() -> Self {
+        Recorder {
+            sink: gix_odb::sink(gix_hash::Kind::Sha1),
+            written: Default::default(),
+        }
+    }
+
+    fn take_written(&self) -> Vec<ObjectId> {
+        std::mem::take(&mut self.written.borrow_mut())
+    }
+}
+
+impl gix_object::Write for Recorder {
+    fn write_stream(
+        &self,
+        kind: gix_object::Kind,
+        size: u64,
+        from: &mut dyn std::io::Read,
+    ) -> Result<ObjectId, gix_object::write::Error> {
+        let id = self.sink.write_stream(kind, size, from)?;
+        self.written.borrow_mut().push(id);
+        Ok(id)
+    }
+}
+
+#[test]
+fn matches_git_including_the_tree_extension() -> crate::Result {
+    for fixture in [
+        "v2",
+        "v2_more_files",
+        "v2_all_file_kinds",
+        "v2_deeper_tree",
+        "v4_more_files_IEOT",
+    ] {
+        let mut index = Fixture::Generated(fixture).open();
+        let expected = index
+            .remove_tree()
+            .expect("git writes the tree extension when committing");
+
+        let recorder = Recorder::new();
+        let id = index.write_tree(&recorder)?;
+        assert_eq!(id, expected.id, "{fixture}: the root tree is the same");
+        assert_eq!(
+            index.tree(),
+            Some(&expected),
+            "{fixture}: the tree extension is recreated exactly"
+        );
+        index.verify_extensions(false, gix_object::find::Never)?;
+        assert_eq!(
+            recorder.take_written().len(),
+            count_trees(&expected),
+            "{fixture}: each tree is written once"
+        );
+
+        assert_eq!(index.write_tree(&recorder)?, id);
+        assert!(
+            recorder.take_written().is_empty(),
+            "{fixture}: nothing is written if all trees are still valid"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn only_changed_trees_are_written() -> crate::Result {
+    let mut index = Fixture::Generated("v2_deeper_tree").open();
+    let recorder = Recorder::new();
+    let before = index.write_tree(&recorder)?;
+    recorder.take_written();
+
+    let entry = index.entry_by_path("d/nested/1".into()).expect("present").clone();
+    index.upsert_entry(
+        entry.stat,
+        gix_object::compute_hash(gix_hash::Kind::Sha1, gix_object::Kind::Blob, b"changed")?,
+        entry.flags,
+        entry.mode,
+        "d/nested/1".into(),
+    );
+    let tree = index.tree().expect("present");
+    assert_eq!(tree.num_entries, None);
+    let d = tree
+        .children
+        .iter()
+        .find(|c| c.name.as_slice() == b"d")
+        .expect("present");
+    assert_eq!(d.num_entries, None);
+    assert_eq!(d.children[0].num_entries, None, "d/nested is invalidated");
+    assert!(
+        tree.children
+            .iter()
+            .find(|c| c.name.as_slice() == b"sub")
+            .expect("present")
+            .num_entries
+            .is_some(),
+        "unrelated trees stay valid"
+    );
+
+    let after = index.write_tree(&recorder)?;
+    assert_ne!(after, before);
+    assert_eq!(
+        recorder.take_written().len(),
+        3,
+        "only the root, d and d/nested are written"
+    );
+    assert_eq!(
+        index.tree().expect("present").num_entries,
+        Some(index.entries().len() as u32)
+    );
+    Ok(())
+}
+
+#[test]
+fn removals_invalidate_trees() -> crate::Result {
+    let mut index = Fixture::Generated("v2_deeper_tree").open();
+    let recorder = Recorder::new();
+    index.write_tree(&recorder)?;
+    recorder.take_written();
+
+    index.remove_entries(|_, path, _| path.starts_with(b"sub/c/"));
+    let id = index.write_tree(&recorder)?;
+    assert_eq!(
+        recorder.take_written().len(),
+        2,
+        "the root and sub are written, while the now empty sub/c isn't"
+    );
+
+    let tree = index.tree().expect("present");
+    assert_eq!(tree.id, id);
+    let sub = tree
+        .children
+        .iter()
+        .find(|c| c.name.as_slice() == b"sub")
+        .expect("present");
+    assert_eq!(
+        sub.children.iter().map(|c| c.name.as_slice()).collect::<Vec<_>>(),
+        [b"a".as_slice(), b"b"],
+        "the now empty tree is gone"
+    );
+    index.verify_extensions(false, gix_object::find::Never)?;
+    Ok(())
+}
+
+#[test]
+fn intent_to_add_entries_are_skipped() -> crate::Result {
+    let mut index = Fixture::Generated("v3_added_files").open();
+    assert!(index.entry(0).flags.contains(Flags::INTENT_TO_ADD));
+
+    let id = index.write_tree(&Recorder::new())?;
+    assert_eq!(id, ObjectId::empty_tree(gix_hash::Kind::Sha1));
+    assert_eq!(
+        index.tree().expect("created").num_entries,
+        None,
+        "trees with intent-to-add entries remain invalid, as they are incomplete"
+    );
+    Ok(())
+}
+
+#[test]
+fn conflicts_are_an_error() {
+    let mut index = Fixture::Loose("conflicting-file").open();
+    assert!(index.entry_by_path_and_stage("file".into(), Stage::Base).is_some());
+    let err = index.write_tree(&Recorder::new()).unwrap_err();
+    assert_eq!(err.to_string(), "Cannot write a tree while 'file' is conflicted");
+}
+
+fn count_trees(tree: &gix_index::extension::Tree) -> usize {
+    1 + tree.children.iter().map(count_trees).sum::<usize>()
+}
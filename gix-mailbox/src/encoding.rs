@@ -0,0 +1,195 @@
+//! Just enough of RFC 2045 and RFC 2047 to read and write patch emails.
+use bstr::{BString, ByteSlice, ByteVec};
+
+/// The kind of header value to encode, as it affects which characters need encoding.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Kind {
+    Subject,
+    Address,
+}
+
+/// Return `true` if `text` can't be written as is into a header, just like Git determines it.
+pub(crate) fn needs_rfc2047_encoding(text: &[u8]) -> bool {
+    !text.is_ascii() || text.contains_str("=?")
+}
+
+fn is_special(byte: u8, kind: Kind) -> bool {
+    // Like Git, encode spaces as `=20` even though `_` would be allowed, as not all programs understand the latter.
+    if !byte.is_ascii() || matches!(byte, b' ' | b'=' | b'?' | b'_') {
+        return true;
+    }
+    kind == Kind::Address && !(byte.is_ascii_alphanumeric() || b"!*+-/".contains(&byte))
+}
+
+/// Append `text` to `out` as encoded words with `q` encoding, assuming the current line is already `line_len` bytes long.
+/// Lines are folded so no encoded word exceeds 76 characters, and multi-byte characters are never split across words.
+pub(crate) fn encode_rfc2047(out: &mut Vec<u8>, text: &[u8], kind: Kind, mut line_len: usize) {
+    const MAX_ENCODED_LEN: usize = 76;
+    const START: &str = "=?UTF-8?q?";
+    out.push_str(START);
+    line_len += START.len();
+    for (start, end, _) in text.char_indices() {
+        let char_bytes = &text[start..end];
+        let special = char_bytes.len() > 1 || is_special(char_bytes[0], kind);
+        let encoded_len = if special { 3 * char_bytes.len() } else { 1 };
+        if line_len + encoded_len + 2 > MAX_ENCODED_LEN {
+            out.push_str("?=\n ");
+            out.push_str(START);
+            line_len = START.len() + 1;
+        }
+        if special {
+            for byte in char_bytes {
+                out.push_str(format!("={byte:02X}"));
+            }
+            line_len += encoded_len;
+        } else {
+            out.extend_from_slice(char_bytes);
+            line_len += 1;
+        }
+    }
+    out.push_str("?=");
+}
+
+/// Decode all encoded words in the header `value`, dropping whitespace between adjacent encoded words.
+pub(crate) fn decode_rfc2047(value: &[u8]) -> BString {
+    let mut out = BString::default();
+    let mut rest = value;
+    let mut pending_whitespace: &[u8] = &[];
+    let mut last_was_encoded = false;
+    while !rest.is_empty() {
+        if let Some((decoded, consumed)) = decode_encoded_word(rest) {
+            if !last_was_encoded {
+                out.extend_from_slice(pending_whitespace);
+            }
+            pending_whitespace = &[];
+            out.extend_from_slice(&decoded);
+            rest = &rest[consumed..];
+            last_was_encoded = true;
+            continue;
+        }
+        let whitespace_len = rest.iter().take_while(|b| matches!(b, b' ' | b'\t')).count();
+        if whitespace_len > 0 {
+            out.extend_from_slice(pending_whitespace);
+            pending_whitespace = &rest[..whitespace_len];
+            rest = &rest[whitespace_len..];
+            continue;
+        }
+        out.extend_from_slice(pending_whitespace);
+        pending_whitespace = &[];
+        let word_len = rest
+            .iter()
+            .position(|b| matches!(b, b' ' | b'\t'))
+            .unwrap_or(rest.len());
+        out.extend_from_slice(&rest[..word_len]);
+        rest = &rest[word_len..];
+        last_was_encoded = false;
+    }
+    out.extend_from_slice(pending_whitespace);
+    out
+}
+
+/// Decode `=?charset?encoding?text?=` at the beginning of `input`, and return the decoded bytes as UTF-8
+/// along with the amount of bytes consumed.
+fn decode_encoded_word(input: &[u8]) -> Option<(Vec<u8>, usize)> {
+    let rest = input.strip_prefix(b"=?")?;
+    let (charset, rest) = rest.split_once_str("?")?;
+    let (encoding, rest) = rest.split_once_str("?")?;
+    let text_len = rest.find("?=")?;
+    let text = &rest[..text_len];
+    if text.contains(&b' ') {
+        return None;
+    }
+    let decoded = match encoding {
+        b"q" | b"Q" => decode_quoted_printable(text, true),
+        b"b" | b"B" => decode_base64(text)?,
+        _ => return None,
+    };
+    let consumed = input.len() - rest.len() + text_len + 2;
+    Some((to_utf8(charset, decoded), consumed))
+}
+
+/// Convert `data` in `charset` to UTF-8 as far as we know how to.
+pub(crate) fn to_utf8(charset: &[u8], data: Vec<u8>) -> Vec<u8> {
+    // Language tags as in `UTF-8*en` aren't relevant to us.
+    let charset = charset.split_str("*").next().unwrap_or_default();
+    if charset.eq_ignore_ascii_case(b"iso-8859-1") || charset.eq_ignore_ascii_case(b"latin1") {
+        data.iter().map(|b| char::from(*b)).collect::<String>().into_bytes()
+    } else {
+        data
+    }
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    Some(match byte {
+        b'0'..=b'9' => byte - b'0',
+        b'a'..=b'f' => byte - b'a' + 10,
+        b'A'..=b'F' => byte - b'A' + 10,
+        _ => return None,
+    })
+}
+
+/// Decode quoted-printable `data`, which is `q` encoding in headers if `is_header` is `true`.
+pub(crate) fn decode_quoted_printable(data: &[u8], is_header: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut bytes = data.iter().copied().peekable();
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'_' if is_header => out.push(b' '),
+            b'=' => match bytes.peek().copied() {
+                // A soft line break.
+                Some(b'\n') => {
+                    bytes.next();
+                }
+                Some(b'\r') => {
+                    bytes.next();
+                    bytes.next_if_eq(&b'\n');
+                }
+                Some(high) => {
+                    bytes.next();
+                    match bytes.peek().copied().and_then(hex_value).zip(hex_value(high)) {
+                        Some((low, high)) => {
+                            bytes.next();
+                            out.push(high << 4 | low);
+                        }
+                        None => {
+                            out.push(b'=');
+                            out.push(high);
+                        }
+                    }
+                }
+                None => out.push(b'='),
+            },
+            _ => out.push(byte),
+        }
+    }
+    out
+}
+
+/// Decode base64 `data`, ignoring whitespace, or return `None` if it's invalid.
+pub(crate) fn decode_base64(data: &[u8]) -> Option<Vec<u8>> {
+    fn value(byte: u8) -> Option<u32> {
+        Some(u32::from(match byte {
+            b'A'..=b'Z' => byte - b'A',
+            b'a'..=b'z' => byte - b'a' + 26,
+            b'0'..=b'9' => byte - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        }))
+    }
+    let mut out = Vec::with_capacity(data.len() / 4 * 3);
+    let (mut acc, mut bits) = (0_u32, 0);
+    for byte in data.iter().copied().filter(|b| !b.is_ascii_whitespace()) {
+        if byte == b'=' {
+            break;
+        }
+        acc = acc << 6 | value(byte)?;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Some(out)
+}
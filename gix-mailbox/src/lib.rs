@@ -0,0 +1,53 @@
+//! [Split][split] mailboxes into messages, [parse][Message::from_bytes()] patches sent by email and [write][write::Patch] them
+//! just like `git format-patch` would, to implement a mail-based patch workflow similar to `git am`.
+//! ## Feature Flags
+#![cfg_attr(
+    all(doc, feature = "document-features"),
+    doc = ::document_features::document_features!()
+)]
+#![cfg_attr(all(doc, feature = "document-features"), feature(doc_cfg, doc_auto_cfg))]
+#![deny(missing_docs, rust_2018_idioms)]
+#![forbid(unsafe_code)]
+
+use bstr::BString;
+
+/// A patch as parsed from an email, with everything needed to turn it into a commit.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Message {
+    /// The author of the patch along with the time it was authored, as obtained from the `From` and `Date` headers,
+    /// or from the same headers at the beginning of the body.
+    pub author: gix_actor::Signature,
+    /// The subject without prefixes like `[PATCH 1/2]` or `Re:`.
+    pub subject: BString,
+    /// The body of the commit message, without leading and trailing empty lines, which may be empty.
+    pub body: BString,
+    /// The patch itself, starting with the `---` line that separates it from the message, or with the first line of the diff.
+    /// It's empty if the message doesn't contain a patch.
+    pub patch: BString,
+}
+
+impl Message {
+    /// Return the full commit message, consisting of subject and body, separated by an empty line.
+    pub fn commit_message(&self) -> BString {
+        let mut message = self.subject.clone();
+        message.push(b'\n');
+        if !self.body.is_empty() {
+            message.push(b'\n');
+            message.extend_from_slice(&self.body);
+            message.push(b'\n');
+        }
+        message
+    }
+}
+
+///
+pub mod split;
+
+///
+pub mod parse;
+
+///
+pub mod write;
+
+mod encoding;
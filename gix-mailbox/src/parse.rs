@@ -0,0 +1,238 @@
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::{encoding, Message};
+
+/// The error returned by [`Message::from_bytes()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The message has no 'From' header to obtain the author from")]
+    MissingAuthor,
+    #[error("The message has no 'Date' header to obtain the author time from")]
+    MissingDate,
+    #[error("Could not parse date {date:?}")]
+    Date {
+        date: BString,
+        source: gix_date::parse::Error,
+    },
+}
+
+/// The headers we care about, all decoded.
+#[derive(Default)]
+struct Headers {
+    from: Option<BString>,
+    subject: Option<BString>,
+    date: Option<BString>,
+}
+
+impl Headers {
+    const NAMES: [&'static [u8]; 3] = [b"from", b"subject", b"date"];
+
+    fn is_relevant(name: &[u8]) -> bool {
+        Self::NAMES.iter().any(|known| name.eq_ignore_ascii_case(known))
+    }
+
+    /// Remember `value` if `name` is a header we care about, and return `true` in that case.
+    fn set(&mut self, name: &[u8], value: &[u8]) -> bool {
+        let field = if name.eq_ignore_ascii_case(b"from") {
+            &mut self.from
+        } else if name.eq_ignore_ascii_case(b"subject") {
+            &mut self.subject
+        } else if name.eq_ignore_ascii_case(b"date") {
+            &mut self.date
+        } else {
+            return false;
+        };
+        *field = Some(encoding::decode_rfc2047(value.trim()));
+        true
+    }
+}
+
+impl Message {
+    /// Parse a single email `data` as split from a mailbox with [`split::mbox()`](crate::split::mbox()) or as read from a maildir,
+    /// similar to what `git mailinfo` does.
+    ///
+    /// `From`, `Subject` and `Date` headers at the beginning of the body take precedence over the ones in the email header,
+    /// which allows to send patches on behalf of others. The body is decoded if it's `quoted-printable` or `base64`,
+    /// and everything from the first line that is `---`, or that starts with `diff -` or `Index: ` is considered the patch.
+    ///
+    /// Note that multipart messages aren't supported, and that bodies in other character sets than UTF-8 or ISO-8859-1
+    /// are passed through unchanged.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        let mut data = data;
+        if data.starts_with(b"From ") {
+            data = data.find_byte(b'\n').map_or(&[], |pos| &data[pos + 1..]);
+        }
+
+        let mut headers = Headers::default();
+        let mut transfer_encoding = BString::default();
+        let mut charset = BString::default();
+        let mut lines = data.lines_with_terminator().peekable();
+        let mut body_start = 0;
+        while let Some(line) = lines.next() {
+            body_start += line.len();
+            let mut line = trim_newline(line).to_owned();
+            if line.is_empty() {
+                break;
+            }
+            while let Some(continuation) = lines.next_if(|line| line.starts_with(b" ") || line.starts_with(b"\t")) {
+                body_start += continuation.len();
+                line.extend_from_slice(trim_newline(continuation));
+            }
+            let Some((name, value)) = line.split_once_str(":") else {
+                continue;
+            };
+            if !headers.set(name, value) {
+                if name.eq_ignore_ascii_case(b"content-transfer-encoding") {
+                    transfer_encoding = value.trim().to_ascii_lowercase().into();
+                } else if name.eq_ignore_ascii_case(b"content-type") {
+                    charset = parameter(value.as_bstr(), "charset").unwrap_or_default();
+                }
+            }
+        }
+
+        let body = &data[body_start.min(data.len())..];
+        let body = match transfer_encoding.as_slice() {
+            b"quoted-printable" => encoding::decode_quoted_printable(body, false),
+            b"base64" => encoding::decode_base64(body).unwrap_or_else(|| body.to_owned()),
+            _ => body.to_owned(),
+        };
+        let body = encoding::to_utf8(&charset, body);
+
+        let mut lines = body.lines_with_terminator().peekable();
+        while lines.next_if(|line| trim_newline(line).trim().is_empty()).is_some() {}
+        let mut has_in_body_headers = false;
+        while let Some(line) = lines.next_if(|line| {
+            line.split_once_str(":")
+                .is_some_and(|(name, _)| Headers::is_relevant(name))
+        }) {
+            let (name, value) = trim_newline(line).split_once_str(":").expect("checked");
+            headers.set(name, value);
+            has_in_body_headers = true;
+        }
+        if has_in_body_headers {
+            lines.next_if(|line| trim_newline(line).is_empty());
+        }
+
+        let mut message = Vec::new();
+        let mut patch = BString::default();
+        for line in lines.by_ref() {
+            if is_patch_break(line) {
+                patch.extend_from_slice(line);
+                break;
+            }
+            message.push(line);
+        }
+        for line in lines {
+            patch.extend_from_slice(line);
+        }
+        let body = trim_empty_lines(&message);
+
+        let (name, email) = headers
+            .from
+            .as_ref()
+            .map(|from| parse_address(from.as_bstr()))
+            .ok_or(Error::MissingAuthor)?;
+        let date = headers.date.ok_or(Error::MissingDate)?;
+        let time = gix_date::parse(date.to_str_lossy().as_ref(), None).map_err(|source| Error::Date {
+            date: date.clone(),
+            source,
+        })?;
+
+        Ok(Message {
+            author: gix_actor::Signature { name, email, time },
+            subject: headers
+                .subject
+                .as_ref()
+                .map(|subject| clean_subject(subject.as_bstr()))
+                .unwrap_or_default(),
+            body,
+            patch,
+        })
+    }
+}
+
+fn trim_newline(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\n")
+        .map_or(line, |line| line.strip_suffix(b"\r").unwrap_or(line))
+}
+
+/// Return `true` if `line` ends the commit message and starts the patch, just like Git determines it.
+fn is_patch_break(line: &[u8]) -> bool {
+    if let Some(rest) = line.strip_prefix(b"---") {
+        return rest.first().map_or(true, u8::is_ascii_whitespace);
+    }
+    line.starts_with(b"diff -") || line.starts_with(b"Index: ")
+}
+
+/// Join `lines`, skipping leading and trailing lines that are empty or consist of whitespace only, and strip the last newline.
+fn trim_empty_lines(lines: &[&[u8]]) -> BString {
+    let is_empty = |line: &&&[u8]| line.trim().is_empty();
+    let start = lines.iter().position(|line| !is_empty(&line)).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !is_empty(&line)).map_or(start, |pos| pos + 1);
+    let mut out: BString = lines[start..end].concat().into();
+    let len = trim_newline(&out).len();
+    out.truncate(len);
+    out
+}
+
+/// Obtain the value of `name` in a header value like `text/plain; charset=UTF-8`.
+fn parameter(value: &BStr, name: &str) -> Option<BString> {
+    value.split_str(";").skip(1).find_map(|param| {
+        let (key, value) = param.split_once_str("=")?;
+        key.trim()
+            .eq_ignore_ascii_case(name.as_bytes())
+            .then(|| value.trim().trim_with(|c| c == '"').into())
+    })
+}
+
+/// Split an address like `Name <email>`, `"Name" <email>` or `email (Name)` into name and email.
+/// If there is no name, the email is used instead, just like Git does.
+fn parse_address(from: &BStr) -> (BString, BString) {
+    if let Some((name, rest)) = from.split_once_str("<") {
+        let email = rest.split_once_str(">").map_or(rest, |(email, _)| email).trim();
+        let name = unquote(name.trim());
+        let name = if name.is_empty() { email.into() } else { name };
+        return (name, email.into());
+    }
+    if let Some((email, rest)) = from.split_once_str("(") {
+        let name = rest.split_once_str(")").map_or(rest, |(name, _)| name).trim();
+        return (name.into(), email.trim().into());
+    }
+    let email = from.trim();
+    (email.into(), email.into())
+}
+
+/// Remove surrounding double-quotes and backslash-escapes from `name`, if it is quoted.
+fn unquote(name: &[u8]) -> BString {
+    let Some(quoted) = name.strip_prefix(b"\"").and_then(|name| name.strip_suffix(b"\"")) else {
+        return name.into();
+    };
+    let mut out = BString::default();
+    let mut bytes = quoted.iter();
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'\\' => out.extend(bytes.next()),
+            _ => out.push(*byte),
+        }
+    }
+    out
+}
+
+/// Remove `Re:` and bracketed prefixes like `[PATCH v2 1/3]` from `subject`, just like `git mailinfo` does by default.
+fn clean_subject(mut subject: &BStr) -> BString {
+    loop {
+        subject = subject.trim_start().as_bstr();
+        if subject.len() >= 3 && subject[..3].eq_ignore_ascii_case(b"re:") {
+            subject = subject[3..].as_bstr();
+        } else if subject.starts_with(b"[") {
+            match subject.find_byte(b']') {
+                Some(end) => subject = subject[end + 1..].as_bstr(),
+                None => break,
+            }
+        } else {
+            break;
+        }
+    }
+    subject.trim_end().into()
+}
@@ -0,0 +1,65 @@
+use std::path::{Path, PathBuf};
+
+use bstr::ByteSlice;
+
+/// An iterator over the messages in a mailbox, as returned by [`mbox()`].
+pub struct Mbox<'a> {
+    data: &'a [u8],
+}
+
+/// Return an iterator over all messages in the mailbox `data`, with each message starting after its `From ` separator line.
+///
+/// A `From ` line is only considered a separator if it's the first line or if it follows an empty line.
+/// Anything before the first separator is considered a message as well, which allows to pass single messages without separator.
+pub fn mbox(data: &[u8]) -> Mbox<'_> {
+    Mbox { data }
+}
+
+impl<'a> Iterator for Mbox<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.starts_with(b"From ") {
+            self.data = self.data.find_byte(b'\n').map_or(&[], |pos| &self.data[pos + 1..]);
+        }
+        if self.data.is_empty() {
+            return None;
+        }
+        let mut end = self.data.len();
+        let mut previous_line_is_empty = false;
+        let mut pos = 0;
+        for line in self.data.lines_with_terminator() {
+            if previous_line_is_empty && line.starts_with(b"From ") {
+                end = pos;
+                break;
+            }
+            previous_line_is_empty = matches!(line, b"\n" | b"\r\n");
+            pos += line.len();
+        }
+        let (message, rest) = self.data.split_at(end);
+        self.data = rest;
+        Some(message)
+    }
+}
+
+/// Return the paths to all messages in the maildir at `path`, which are the files in its `cur` and `new` subdirectories,
+/// sorted by their name just like `git mailsplit` does.
+pub fn maildir(path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for subdir in ["cur", "new"] {
+        let dir = path.join(subdir);
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_file() && !entry.file_name().to_string_lossy().starts_with('.') {
+                files.push(entry.path());
+            }
+        }
+    }
+    files.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    Ok(files)
+}
@@ -0,0 +1,156 @@
+use std::io::Write;
+
+use bstr::{BStr, ByteSlice};
+
+use crate::encoding;
+
+/// The date Git uses in the `From <id> <date>` line that separates messages, to make them recognizable as output of `format-patch`.
+pub const MAGIC_DATE: &str = "Mon Sep 17 00:00:00 2001";
+
+/// The maximum width of a subject line before it's folded, just like Git.
+const MAX_SUBJECT_LEN: usize = 78;
+
+/// All information needed to write a single patch email in the format produced by `git format-patch`.
+#[derive(Debug, Clone, Copy)]
+pub struct Patch<'a> {
+    /// The id of the commit the patch was created from, or the id of the last commit in the series for cover letters.
+    pub id: &'a gix_hash::oid,
+    /// The author of the patch.
+    pub author: gix_actor::IdentityRef<'a>,
+    /// The time at which the patch was authored.
+    pub time: gix_date::Time,
+    /// The prefix to put in brackets before the subject, like `PATCH` or `RFC PATCH v2`.
+    ///
+    /// If empty and there is no `number`, no brackets are written.
+    pub subject_prefix: &'a str,
+    /// The number of the patch and the total amount of patches in the series, like `Some((1, 3))` for `[PATCH 1/3]`.
+    pub number: Option<(usize, usize)>,
+    /// The commit message, whose first paragraph is the subject.
+    pub message: &'a BStr,
+    /// The patch along with its statistics, to be written after the `---` separator, or `None` if there is no patch,
+    /// as for cover letters.
+    pub diff: Option<&'a [u8]>,
+    /// The signature to write at the end of the email, typically the version of the program that created it.
+    pub signature: Option<&'a str>,
+}
+
+impl Patch<'_> {
+    /// Write this instance to `out`, including the `From <id> Mon Sep 17 00:00:00 2001` line that allows to
+    /// concatenate multiple patches into a mailbox.
+    pub fn write_to(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        let (subject, body) = split_message(self.message);
+        writeln!(out, "From {} {MAGIC_DATE}", self.id)?;
+
+        let mut line = b"From: ".to_vec();
+        write_address(&mut line, self.author);
+        line.push(b'\n');
+        out.write_all(&line)?;
+        writeln!(
+            out,
+            "Date: {}",
+            self.time.format(gix_date::time::format::GIT_RFC2822)
+        )?;
+
+        let mut line = b"Subject: ".to_vec();
+        match (self.subject_prefix.is_empty(), self.number) {
+            (true, None) => {}
+            (_, Some((number, total))) => {
+                let space = if self.subject_prefix.is_empty() { "" } else { " " };
+                write!(line, "[{}{space}{number}/{total}] ", self.subject_prefix)?;
+            }
+            (false, None) => write!(line, "[{}] ", self.subject_prefix)?,
+        }
+        write_subject(&mut line, subject.as_bytes());
+        line.push(b'\n');
+        out.write_all(&line)?;
+
+        if !self.author.name.is_ascii() || !self.message.is_ascii() {
+            out.write_all(b"MIME-Version: 1.0\nContent-Type: text/plain; charset=UTF-8\nContent-Transfer-Encoding: 8bit\n")?;
+        }
+        out.write_all(b"\n")?;
+        if !body.is_empty() {
+            out.write_all(body)?;
+            out.write_all(b"\n")?;
+        }
+        match self.diff {
+            Some(diff) => {
+                out.write_all(b"---\n")?;
+                out.write_all(diff)?;
+            }
+            // Cover letters separate their content from the signature.
+            None => out.write_all(b"\n")?,
+        }
+        if let Some(signature) = self.signature {
+            writeln!(out, "-- \n{signature}\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// Split `message` into its subject, with all lines of the first paragraph joined with spaces, and the body without
+/// leading and trailing whitespace.
+fn split_message(message: &BStr) -> (bstr::BString, &[u8]) {
+    let mut subject = bstr::BString::default();
+    let mut rest: &[u8] = message.as_bytes();
+    for line in message.lines_with_terminator() {
+        rest = &rest[line.len()..];
+        let line = line.trim_end();
+        if line.is_empty() {
+            if subject.is_empty() {
+                continue;
+            }
+            break;
+        }
+        if !subject.is_empty() {
+            subject.push(b' ');
+        }
+        subject.extend_from_slice(line.trim_start());
+    }
+    (subject, rest.trim())
+}
+
+/// Write `subject` to `line`, which already contains the `Subject: ` header and prefix, and fold it if necessary.
+fn write_subject(line: &mut Vec<u8>, subject: &[u8]) {
+    if encoding::needs_rfc2047_encoding(subject) {
+        let line_len = line.len();
+        encoding::encode_rfc2047(line, subject, encoding::Kind::Subject, line_len);
+        return;
+    }
+    let mut line_len = line.len();
+    for (idx, word) in subject.split_str(" ").enumerate() {
+        if idx > 0 {
+            if line_len + 1 + word.len() > MAX_SUBJECT_LEN {
+                line.extend_from_slice(b"\n ");
+                line_len = 1;
+            } else {
+                line.push(b' ');
+                line_len += 1;
+            }
+        }
+        line.extend_from_slice(word);
+        line_len += word.len();
+    }
+}
+
+/// Write `Name <email>`, quoting or encoding the name as needed.
+fn write_address(line: &mut Vec<u8>, identity: gix_actor::IdentityRef<'_>) {
+    let name = identity.name.as_bytes();
+    if encoding::needs_rfc2047_encoding(name) {
+        let line_len = line.len();
+        encoding::encode_rfc2047(line, name, encoding::Kind::Address, line_len);
+    } else if name.iter().any(|b| b"()<>[]:;@\\,.\"".contains(b)) {
+        line.push(b'"');
+        for byte in name {
+            if matches!(byte, b'"' | b'\\') {
+                line.push(b'\\');
+            }
+            line.push(*byte);
+        }
+        line.push(b'"');
+    } else {
+        line.extend_from_slice(name);
+    }
+    line.extend_from_slice(b" <");
+    line.extend_from_slice(identity.email);
+    line.push(b'>');
+}
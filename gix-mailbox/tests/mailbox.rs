@@ -0,0 +1,201 @@
+use gix_mailbox::Message;
+use gix_testtools::fixture_bytes;
+
+type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn series() -> Vec<Vec<u8>> {
+    let data = fixture_bytes("series.mbox");
+    gix_mailbox::split::mbox(&data).map(ToOwned::to_owned).collect()
+}
+
+mod split {
+    #[test]
+    fn mbox() {
+        let messages = super::series();
+        assert_eq!(messages.len(), 2, "'From ' in the body isn't a separator unless it follows an empty line");
+        assert!(messages[0].starts_with(b"From: =?UTF-8?q?"), "the separator line is removed");
+        assert!(messages[0].ends_with(b"2.49.0\n\n"));
+        assert!(messages[1].starts_with(b"From: Sender"));
+    }
+
+    #[test]
+    fn single_message_without_separator() {
+        let messages: Vec<_> = gix_mailbox::split::mbox(b"Subject: hi\n\nbody\n").collect();
+        assert_eq!(messages, [&b"Subject: hi\n\nbody\n"[..]]);
+        assert_eq!(gix_mailbox::split::mbox(b"").count(), 0);
+    }
+
+    #[test]
+    fn maildir() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        for (dir, name) in [("new", "2"), ("cur", "1"), ("cur", ".hidden"), ("tmp", "0")] {
+            std::fs::create_dir_all(tmp.path().join(dir))?;
+            std::fs::write(tmp.path().join(dir).join(name), name)?;
+        }
+        let files = gix_mailbox::split::maildir(tmp.path())?;
+        assert_eq!(
+            files,
+            [tmp.path().join("cur").join("1"), tmp.path().join("new").join("2")],
+            "hidden files and files in 'tmp' are ignored, and files are sorted by name"
+        );
+        Ok(())
+    }
+}
+
+mod parse {
+    use super::*;
+
+    #[test]
+    fn encoded_headers_and_quoted_printable_body() -> crate::Result {
+        let message = Message::from_bytes(&series()[0])?;
+        assert_eq!(message.author.name, "Jörg Müller");
+        assert_eq!(message.author.email, "joerg@example.com");
+        assert_eq!(message.author.time, gix_date::parse("2000-01-01 00:00:00 +0100", None)?);
+        assert_eq!(message.subject, "ändere a and more", "folded lines are joined");
+        assert_eq!(
+            message.body,
+            "The body with a very long line that was soft-wrapped by quoted-printable encoding, and ümlauts.\nFrom an unescaped line in the body."
+        );
+        assert!(message.patch.starts_with(b"---\n a | 1 +\n"));
+        assert!(message.patch.ends_with(b"+2\n-- \n2.49.0\n\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn in_body_headers_take_precedence() -> crate::Result {
+        let message = Message::from_bytes(&series()[1])?;
+        assert_eq!(message.author.name, "Original, Author");
+        assert_eq!(message.author.email, "original@example.com");
+        assert_eq!(message.author.time, gix_date::parse("2000-01-03 12:00:00 -0500", None)?);
+        assert_eq!(message.subject, "subject from the body");
+        assert_eq!(message.body, "");
+        assert!(message.patch.starts_with(b"diff --git a/b b/b\n"));
+        assert_eq!(message.commit_message(), "subject from the body\n");
+        Ok(())
+    }
+
+    #[test]
+    fn address_forms() -> crate::Result {
+        for (from, name, email) in [
+            ("Name <e@example.com>", "Name", "e@example.com"),
+            ("e@example.com (Name)", "Name", "e@example.com"),
+            ("<e@example.com>", "e@example.com", "e@example.com"),
+            ("e@example.com", "e@example.com", "e@example.com"),
+        ] {
+            let message = Message::from_bytes(
+                format!("From: {from}\nDate: Sat, 1 Jan 2000 00:00:00 +0000\nSubject: s\n\nbody\n").as_bytes(),
+            )?;
+            assert_eq!(message.author.name, name, "{from}");
+            assert_eq!(message.author.email, email, "{from}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn missing_headers() {
+        assert!(matches!(
+            Message::from_bytes(b"Subject: s\n\nbody\n"),
+            Err(gix_mailbox::parse::Error::MissingAuthor)
+        ));
+        assert!(matches!(
+            Message::from_bytes(b"From: a <a@b>\n\nbody\n"),
+            Err(gix_mailbox::parse::Error::MissingDate)
+        ));
+        assert!(matches!(
+            Message::from_bytes(b"From: a <a@b>\nDate: yesterday-ish\n\nbody\n"),
+            Err(gix_mailbox::parse::Error::Date { .. })
+        ));
+    }
+}
+
+mod write {
+    use bstr::ByteSlice;
+    use gix_mailbox::write::Patch;
+
+    use super::*;
+
+    fn patch<'a>(name: &'a str, message: &'a str, number: Option<(usize, usize)>) -> Patch<'a> {
+        Patch {
+            id: gix_hash::Kind::Sha1.null_ref(),
+            author: gix_actor::IdentityRef {
+                name: name.into(),
+                email: "author@example.com".into(),
+            },
+            time: gix_date::parse("2000-01-01 00:00:00 +0100", None).expect("valid"),
+            subject_prefix: "PATCH",
+            number,
+            message: message.into(),
+            diff: Some(b" a | 1 +\n"),
+            signature: Some("gitoxide"),
+        }
+    }
+
+    fn write(patch: Patch<'_>) -> String {
+        let mut buf = Vec::new();
+        patch.write_to(&mut buf).expect("no IO error");
+        buf.to_str().expect("valid UTF-8").to_owned()
+    }
+
+    #[test]
+    fn ascii() {
+        insta::assert_snapshot!(write(patch("Name, with specials", "subject\n\nbody\n", Some((1, 2)))), @r#"
+        From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+        From: "Name, with specials" <author@example.com>
+        Date: Sat, 1 Jan 2000 00:00:00 +0100
+        Subject: [PATCH 1/2] subject
+
+        body
+        ---
+         a | 1 +
+        -- 
+        gitoxide
+
+        "#);
+    }
+
+    #[test]
+    fn non_ascii_and_long_subjects() {
+        insta::assert_snapshot!(write(patch("Jörg Müller", "ändere a\n", None)), @r"
+        From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+        From: =?UTF-8?q?J=C3=B6rg=20M=C3=BCller?= <author@example.com>
+        Date: Sat, 1 Jan 2000 00:00:00 +0100
+        Subject: [PATCH] =?UTF-8?q?=C3=A4ndere=20a?=
+        MIME-Version: 1.0
+        Content-Type: text/plain; charset=UTF-8
+        Content-Transfer-Encoding: 8bit
+
+        ---
+         a | 1 +
+        -- 
+        gitoxide
+
+        ");
+
+        let subject = "a subject which is long enough to need folding as it exceeds the 78 columns of a line";
+        insta::assert_snapshot!(write(patch("Name", subject, None)), @r"
+        From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+        From: Name <author@example.com>
+        Date: Sat, 1 Jan 2000 00:00:00 +0100
+        Subject: [PATCH] a subject which is long enough to need folding as it exceeds
+         the 78 columns of a line
+
+        ---
+         a | 1 +
+        -- 
+        gitoxide
+
+        ");
+    }
+
+    #[test]
+    fn round_trip() -> crate::Result {
+        let message = "ändere a\n\nThe body.\n";
+        let data = write(patch("Jörg Müller", message, Some((2, 3))));
+        let parsed = Message::from_bytes(data.as_bytes())?;
+        assert_eq!(parsed.author.name, "Jörg Müller");
+        assert_eq!(parsed.author.time, gix_date::parse("2000-01-01 00:00:00 +0100", None)?);
+        assert_eq!(parsed.commit_message(), message);
+        assert_eq!(parsed.patch, "---\n a | 1 +\n-- \ngitoxide\n\n");
+        Ok(())
+    }
+}
@@ -125,12 +125,13 @@ impl Outcome<'_> {
     /// It's important that `index` is at the state of [`Self::tree`].
     ///
     /// Note that in practice, whenever there is a single [conflict](Conflict), this function will return `true`.
+    /// If a conflict lies inside a sparse directory of `index`, an error is returned and nothing is applied.
     pub fn index_changed_after_applying_conflicts(
         &self,
         index: &mut gix_index::State,
         how: TreatAsUnresolved,
         removal_mode: apply_index_entries::RemovalMode,
-    ) -> bool {
+    ) -> Result<bool, apply_index_entries::Error> {
         apply_index_entries(&self.conflicts, how, index, removal_mode)
     }
 }
@@ -458,6 +459,15 @@ pub(super) mod function;
 mod utils;
 ///
 pub mod apply_index_entries {
+    use bstr::BString;
+
+    /// The error returned by [`apply_index_entries()`](crate::tree::apply_index_entries()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The conflict at '{path}' lies inside of a sparse directory, the index must be expanded first")]
+        ConflictInSparseDirectory { path: BString },
+    }
 
     /// Determines how we deal with the removal of unconflicted entries if these are superseded by their conflicted counterparts,
     /// i.e. stage 1, 2 and 3.
@@ -481,8 +491,8 @@ pub mod apply_index_entries {
         use bstr::{BStr, ByteSlice};
 
         use crate::tree::{
-            apply_index_entries::RemovalMode, Conflict, ConflictIndexEntryPathHint, Resolution, ResolutionFailure,
-            TreatAsUnresolved,
+            apply_index_entries::{Error, RemovalMode},
+            Conflict, ConflictIndexEntry, ConflictIndexEntryPathHint, Resolution, ResolutionFailure, TreatAsUnresolved,
         };
 
         /// Returns `true` if `index` changed as we applied conflicting stages to it, using `how` to determine if a
@@ -498,79 +508,31 @@ pub mod apply_index_entries {
         ///
         /// Note that in practice, whenever there is a single [conflict](Conflict), this function will return `true`.
         /// Errors can only occour if `index` isn't the one created from the merged tree that produced the `conflicts`.
+        ///
+        /// If `index` [is sparse](gix_index::State::is_sparse()) and any conflict lies inside of a sparse directory, nothing is applied
+        /// and an error is returned, so the index should be [expanded](gix_index::State::expand_sparse_directories()) beforehand.
         pub fn apply_index_entries(
             conflicts: &[Conflict],
             how: TreatAsUnresolved,
             index: &mut gix_index::State,
             removal_mode: RemovalMode,
-        ) -> bool {
-            if index.is_sparse() {
-                gix_trace::error!("Refusing to apply index entries to sparse index - it's not tested yet");
-                return false;
-            }
+        ) -> Result<bool, Error> {
             let len = index.entries().len();
-            let mut idx_by_path_stage = HashMap::<(gix_index::entry::Stage, &BStr), usize>::default();
-            for conflict in conflicts.iter().filter(|c| c.is_unresolved(how)) {
-                let (renamed_path, current_path): (Option<&BStr>, &BStr) = match &conflict.resolution {
-                    Ok(success) => match success {
-                        Resolution::Forced(_) => continue,
-                        Resolution::SourceLocationAffectedByRename { final_location } => {
-                            (Some(final_location.as_bstr()), final_location.as_bstr())
-                        }
-                        Resolution::OursModifiedTheirsRenamedAndChangedThenRename { final_location, .. } => (
-                            final_location.as_ref().map(|p| p.as_bstr()),
-                            conflict.changes_in_resolution().1.location(),
-                        ),
-                        Resolution::OursModifiedTheirsModifiedThenBlobContentMerge { .. } => {
-                            (None, conflict.ours.location())
-                        }
-                    },
-                    Err(failure) => match failure {
-                        ResolutionFailure::OursDirectoryTheirsNonDirectoryTheirsRenamed {
-                            renamed_unique_path_of_theirs,
-                        } => (Some(renamed_unique_path_of_theirs.as_bstr()), conflict.ours.location()),
-                        ResolutionFailure::OursRenamedTheirsRenamedDifferently { .. } => {
-                            (Some(conflict.theirs.location()), conflict.ours.location())
-                        }
-                        ResolutionFailure::OursModifiedTheirsRenamedTypeMismatch
-                        | ResolutionFailure::OursDeletedTheirsRenamed
-                        | ResolutionFailure::OursModifiedTheirsDeleted
-                        | ResolutionFailure::Unknown => (None, conflict.ours.location()),
-                        ResolutionFailure::OursModifiedTheirsDirectoryThenOursRenamed {
-                            renamed_unique_path_to_modified_blob,
-                        } => (
-                            Some(renamed_unique_path_to_modified_blob.as_bstr()),
-                            conflict.ours.location(),
-                        ),
-                        ResolutionFailure::OursAddedTheirsAddedTypeMismatch { their_unique_location } => {
-                            (Some(their_unique_location.as_bstr()), conflict.ours.location())
-                        }
-                    },
-                };
-                let source_path = conflict.ours.source_location();
-
-                let entries_with_stage = conflict.entries().into_iter().enumerate().filter_map(|(idx, entry)| {
-                    entry.filter(|e| e.mode.is_no_tree()).map(|e| {
-                        (
-                            match idx {
-                                0 => gix_index::entry::Stage::Base,
-                                1 => gix_index::entry::Stage::Ours,
-                                2 => gix_index::entry::Stage::Theirs,
-                                _ => unreachable!("fixed size array with three items"),
-                            },
-                            match e.path_hint {
-                                None => renamed_path.unwrap_or(current_path),
-                                Some(ConflictIndexEntryPathHint::Source) => source_path,
-                                Some(ConflictIndexEntryPathHint::Current) => current_path,
-                                Some(ConflictIndexEntryPathHint::RenamedOrTheirs) => {
-                                    renamed_path.unwrap_or_else(|| conflict.changes_in_resolution().1.location())
-                                }
-                            },
-                            e,
-                        )
-                    })
-                });
+            let conflicts: Vec<_> = conflicts
+                .iter()
+                .filter(|c| c.is_unresolved(how))
+                .filter_map(conflict_index_entries)
+                .collect();
+            if let Some((_, path, _)) = conflicts
+                .iter()
+                .flat_map(Clone::clone)
+                .find(|(_, path, _)| is_in_sparse_directory(index, path))
+            {
+                return Err(Error::ConflictInSparseDirectory { path: path.to_owned() });
+            }
 
+            let mut idx_by_path_stage = HashMap::<(gix_index::entry::Stage, &BStr), usize>::default();
+            for entries_with_stage in conflicts {
                 if !entries_with_stage.clone().any(|(_, path, _)| {
                     index
                         .entry_index_by_path_and_stage_bounded(path, gix_index::entry::Stage::Unconflicted, len)
@@ -619,7 +581,89 @@ pub mod apply_index_entries {
                 }
             }
             index.sort_entries();
-            res
+            Ok(res)
+        }
+
+        /// Return the index entries of `conflict` along with their stage and the path they should be placed at,
+        /// or `None` if the conflict was forcefully resolved.
+        fn conflict_index_entries(
+            conflict: &Conflict,
+        ) -> Option<impl Iterator<Item = (gix_index::entry::Stage, &BStr, ConflictIndexEntry)> + Clone + '_> {
+            let (renamed_path, current_path): (Option<&BStr>, &BStr) = match &conflict.resolution {
+                Ok(success) => match success {
+                    Resolution::Forced(_) => return None,
+                    Resolution::SourceLocationAffectedByRename { final_location } => {
+                        (Some(final_location.as_bstr()), final_location.as_bstr())
+                    }
+                    Resolution::OursModifiedTheirsRenamedAndChangedThenRename { final_location, .. } => (
+                        final_location.as_ref().map(|p| p.as_bstr()),
+                        conflict.changes_in_resolution().1.location(),
+                    ),
+                    Resolution::OursModifiedTheirsModifiedThenBlobContentMerge { .. } => {
+                        (None, conflict.ours.location())
+                    }
+                },
+                Err(failure) => match failure {
+                    ResolutionFailure::OursDirectoryTheirsNonDirectoryTheirsRenamed {
+                        renamed_unique_path_of_theirs,
+                    } => (Some(renamed_unique_path_of_theirs.as_bstr()), conflict.ours.location()),
+                    ResolutionFailure::OursRenamedTheirsRenamedDifferently { .. } => {
+                        (Some(conflict.theirs.location()), conflict.ours.location())
+                    }
+                    ResolutionFailure::OursModifiedTheirsRenamedTypeMismatch
+                    | ResolutionFailure::OursDeletedTheirsRenamed
+                    | ResolutionFailure::OursModifiedTheirsDeleted
+                    | ResolutionFailure::Unknown => (None, conflict.ours.location()),
+                    ResolutionFailure::OursModifiedTheirsDirectoryThenOursRenamed {
+                        renamed_unique_path_to_modified_blob,
+                    } => (
+                        Some(renamed_unique_path_to_modified_blob.as_bstr()),
+                        conflict.ours.location(),
+                    ),
+                    ResolutionFailure::OursAddedTheirsAddedTypeMismatch { their_unique_location } => {
+                        (Some(their_unique_location.as_bstr()), conflict.ours.location())
+                    }
+                },
+            };
+            let source_path = conflict.ours.source_location();
+
+            let entries_with_stage = conflict
+                .entries()
+                .into_iter()
+                .enumerate()
+                .filter_map(move |(idx, entry)| {
+                    entry.filter(|e| e.mode.is_no_tree()).map(|e| {
+                        (
+                            match idx {
+                                0 => gix_index::entry::Stage::Base,
+                                1 => gix_index::entry::Stage::Ours,
+                                2 => gix_index::entry::Stage::Theirs,
+                                _ => unreachable!("fixed size array with three items"),
+                            },
+                            match e.path_hint {
+                                None => renamed_path.unwrap_or(current_path),
+                                Some(ConflictIndexEntryPathHint::Source) => source_path,
+                                Some(ConflictIndexEntryPathHint::Current) => current_path,
+                                Some(ConflictIndexEntryPathHint::RenamedOrTheirs) => {
+                                    renamed_path.unwrap_or_else(|| conflict.changes_in_resolution().1.location())
+                                }
+                            },
+                            e,
+                        )
+                    })
+                });
+            Some(entries_with_stage)
+        }
+
+        /// Return `true` if `path` is contained in a sparse directory entry of `index`.
+        fn is_in_sparse_directory(index: &gix_index::State, path: &BStr) -> bool {
+            if !index.is_sparse() {
+                return false;
+            }
+            let pos = index.entry_index_by_path(path).unwrap_or_else(|pos| pos);
+            pos.checked_sub(1)
+                .map(|idx| index.entry(idx))
+                .is_some_and(|entry| entry.mode.is_sparse() && path.starts_with(entry.path(index)))
         }
     }
 }
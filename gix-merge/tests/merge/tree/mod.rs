@@ -3,9 +3,9 @@ use std::path::Path;
 use gix_diff::Rewrites;
 use gix_merge::{
     commit::Options,
-    tree::{apply_index_entries::RemovalMode, treat_as_unresolved, TreatAsUnresolved},
+    tree::{apply_index_entries, apply_index_entries::RemovalMode, treat_as_unresolved, TreatAsUnresolved},
 };
-use gix_object::Write;
+use gix_object::{bstr::ByteSlice, Write};
 use gix_worktree::stack::state::attributes;
 
 use crate::tree::baseline::Deviation;
@@ -118,8 +118,11 @@ fn run_baseline() -> crate::Result {
             }
         };
         let conflicts_like_in_git = TreatAsUnresolved::git();
-        let did_change =
-            actual.index_changed_after_applying_conflicts(&mut actual_index, conflicts_like_in_git, RemovalMode::Prune);
+        let did_change = actual.index_changed_after_applying_conflicts(
+            &mut actual_index,
+            conflicts_like_in_git,
+            RemovalMode::Prune,
+        )?;
 
         pretty_assertions::assert_eq!(
             baseline::clear_entries(&actual_index),
@@ -215,6 +218,93 @@ fn run_baseline() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn conflicts_in_sparse_directories_are_not_applied() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("tree-baseline.sh")?;
+    let cases = std::fs::read_to_string(root.join("baseline.cases"))?;
+    for baseline::Expectation {
+        root,
+        odb,
+        our_commit_id,
+        their_commit_id,
+        case_name,
+        deviation,
+        ..
+    } in baseline::Expectations::new(&root, &cases)
+    {
+        if deviation.is_some() {
+            continue;
+        }
+        let mut graph = gix_revwalk::Graph::new(&odb, None);
+        let mut actual = gix_merge::commit(
+            our_commit_id,
+            their_commit_id,
+            Default::default(),
+            &mut graph,
+            &mut new_diff_resource_cache(&root),
+            &mut new_blob_merge_platform(&root, 100),
+            &odb,
+            &mut |id| id.to_hex_with_len(7).to_string(),
+            basic_merge_options(),
+        )?
+        .tree_merge;
+        let how = TreatAsUnresolved::git();
+        let Some(sparse_dir) = actual
+            .conflicts
+            .iter()
+            .filter(|c| c.is_unresolved(how))
+            .find_map(|c| {
+                c.ours
+                    .location()
+                    .split_str("/")
+                    .next()
+                    .filter(|_| c.ours.location().contains(&b'/'))
+            })
+            .map(|dir| dir.as_bstr().to_owned())
+        else {
+            continue;
+        };
+
+        let tree_id = actual.tree.write(|tree| odb.write(tree))?;
+        let mut index = gix_index::State::from_tree(&tree_id, &odb, Default::default())?;
+        for entry in index.entries_mut() {
+            entry
+                .flags
+                .insert(gix_index::entry::Flags::SKIP_WORKTREE | gix_index::entry::Flags::EXTENDED);
+        }
+        index.write_tree(&odb)?;
+        if index.collapse_to_sparse_directories(|dir| dir == sparse_dir) == 0 {
+            continue;
+        }
+        let collapsed = index.clone();
+
+        let err = actual
+            .index_changed_after_applying_conflicts(&mut index, how, RemovalMode::Prune)
+            .unwrap_err();
+        assert!(
+            matches!(&err, apply_index_entries::Error::ConflictInSparseDirectory { path } if path.starts_with(&sparse_dir)),
+            "{case_name}: the conflict in '{sparse_dir}' can't be represented in a collapsed sparse directory, got {err}"
+        );
+        assert_eq!(
+            baseline::clear_entries(&index),
+            baseline::clear_entries(&collapsed),
+            "{case_name}: nothing is applied"
+        );
+
+        index.expand_sparse_directories(&odb, Default::default())?;
+        assert!(
+            actual.index_changed_after_applying_conflicts(&mut index, how, RemovalMode::Prune)?,
+            "{case_name}: after expansion, the conflict can be applied"
+        );
+        assert!(
+            index.entries().iter().any(|e| e.stage_raw() != 0),
+            "{case_name}: conflicting stages are present"
+        );
+        return Ok(());
+    }
+    unreachable!("there is at least one case with a conflict inside of a directory")
+}
+
 fn basic_merge_options() -> Options {
     gix_merge::commit::Options {
         allow_missing_merge_base: true,
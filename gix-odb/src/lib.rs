@@ -134,6 +134,8 @@ pub struct Store {
     use_multi_pack_index: bool,
     /// The hash kind to use for some operations
     object_hash: gix_hash::Kind,
+    /// How to access pack data and index files.
+    pack_backing: gix_pack::backing::Mode,
 }
 
 /// Create a new cached handle to the object store with support for additional options.
@@ -35,6 +35,12 @@ pub(crate) mod error {
             /// The original object to lookup
             id: gix_hash::ObjectId,
         },
+        #[error("Could not fetch missing object {id} from the promisor remote")]
+        PromisorFetch {
+            source: crate::store::promisor::Error,
+            /// The object that was missing
+            id: gix_hash::ObjectId,
+        },
         #[error("An error occurred when looking up a ref delta base object {} to decode {}", .base_id, .id)]
         DeltaBaseLookup {
             #[source]
@@ -351,10 +357,32 @@ where
         buffer: &'a mut Vec<u8>,
         pack_cache: &mut dyn DecodeEntry,
     ) -> Result<Option<(gix_object::Data<'a>, Option<gix_pack::data::entry::Location>)>, gix_object::find::Error> {
-        let mut snapshot = self.snapshot.borrow_mut();
-        let mut inflate = self.inflate.borrow_mut();
-        self.try_find_cached_inner(id, buffer, &mut inflate, pack_cache, &mut snapshot, None)
-            .map_err(|err| Box::new(err) as _)
+        let mut lookup = |buffer: &mut Vec<u8>| {
+            let mut snapshot = self.snapshot.borrow_mut();
+            let mut inflate = self.inflate.borrow_mut();
+            // Objects are always decoded into all of `buffer`, so only keep what's needed to rebuild the result.
+            // This allows to look up the object once more after fetching it from the promisor.
+            self.try_find_cached_inner(id, buffer, &mut inflate, pack_cache, &mut snapshot, None)
+                .map(|found| found.map(|(data, location)| (data.kind, location)))
+                .map_err(|err| Box::new(err) as gix_object::find::Error)
+        };
+        let mut found = lookup(buffer)?;
+        if found.is_none()
+            && self
+                .fetch_missing_from_promisor(id)
+                .map_err(|err| Box::new(err) as gix_object::find::Error)?
+        {
+            found = lookup(buffer)?;
+        }
+        Ok(found.map(|(kind, location)| {
+            (
+                gix_object::Data {
+                    kind,
+                    data: buffer.as_slice(),
+                },
+                location,
+            )
+        }))
     }
 
     fn location_by_oid(&self, id: &gix_hash::oid, buf: &mut Vec<u8>) -> Option<gix_pack::data::entry::Location> {
@@ -485,7 +513,7 @@ where
                         return pack
                             .entry_slice(location.entry_range(location.pack_offset))
                             .map(|data| gix_pack::find::Entry {
-                                data: data.to_owned(),
+                                data: data.into_owned(),
                                 version: pack.version(),
                             });
                     }
@@ -521,16 +549,12 @@ where
     S: Deref<Target = super::Store> + Clone,
 {
     fn try_header(&self, id: &gix_hash::oid) -> Result<Option<gix_object::Header>, gix_object::find::Error> {
-        let mut snapshot = self.snapshot.borrow_mut();
-        let mut inflate = self.inflate.borrow_mut();
-        self.try_header_inner(id, &mut inflate, &mut snapshot, None)
-            .map(|maybe_header| {
-                maybe_header.map(|hdr| gix_object::Header {
-                    kind: hdr.kind(),
-                    size: hdr.size(),
-                })
+        crate::Header::try_header(self, id).map(|maybe_header| {
+            maybe_header.map(|hdr| gix_object::Header {
+                kind: hdr.kind(),
+                size: hdr.size(),
             })
-            .map_err(|err| Box::new(err) as _)
+        })
     }
 }
 
@@ -163,9 +163,8 @@ where
             }
 
             for lodb in snapshot.loose_dbs.iter() {
-                // TODO: remove this double-lookup once the borrow checker allows it.
-                if lodb.contains(id) {
-                    return lodb.try_header(id).map(|opt| opt.map(Into::into)).map_err(Into::into);
+                if let Some(header) = lodb.try_header_with_inflate(id, inflate)? {
+                    return Ok(Some(header.into()));
                 }
             }
 
@@ -185,9 +184,20 @@ where
     S: Deref<Target = super::Store> + Clone,
 {
     fn try_header(&self, id: &oid) -> Result<Option<Header>, gix_object::find::Error> {
-        let mut snapshot = self.snapshot.borrow_mut();
-        let mut inflate = self.inflate.borrow_mut();
-        self.try_header_inner(id, &mut inflate, &mut snapshot, None)
-            .map_err(|err| Box::new(err) as _)
+        let lookup = || {
+            let mut snapshot = self.snapshot.borrow_mut();
+            let mut inflate = self.inflate.borrow_mut();
+            self.try_header_inner(id, &mut inflate, &mut snapshot, None)
+                .map_err(|err| Box::new(err) as gix_object::find::Error)
+        };
+        match lookup()? {
+            None if self
+                .fetch_missing_from_promisor(id)
+                .map_err(|err| Box::new(err) as gix_object::find::Error)? =>
+            {
+                lookup()
+            }
+            header => Ok(header),
+        }
     }
 }
@@ -19,6 +19,8 @@ pub struct Options {
     /// The current directory of the process at the time of instantiation.
     /// If unset, it will be retrieved using `gix_fs::current_dir(false)`.
     pub current_dir: Option<std::path::PathBuf>,
+    /// Control how pack data and index files are accessed.
+    pub pack_backing: gix_pack::backing::Mode,
 }
 
 impl Default for Options {
@@ -28,6 +30,7 @@ impl Default for Options {
             object_hash: Default::default(),
             use_multi_pack_index: true,
             current_dir: None,
+            pack_backing: Default::default(),
         }
     }
 }
@@ -77,6 +80,7 @@ impl Store {
             object_hash,
             use_multi_pack_index,
             current_dir,
+            pack_backing,
         }: Options,
     ) -> std::io::Result<Self> {
         let _span = gix_features::trace::detail!("gix_odb::Store::at()");
@@ -124,6 +128,7 @@ impl Store {
             index: ArcSwap::new(Arc::new(SlotMapIndex::default())),
             use_multi_pack_index,
             object_hash,
+            pack_backing,
             num_handles_stable: Default::default(),
             num_handles_unstable: Default::default(),
             num_disk_state_consolidation: Default::default(),
@@ -129,7 +129,7 @@ impl super::Store {
                         if let Some(files) = bundle_mut.as_mut() {
                             // these are always expected to be set, unless somebody raced us. We handle this later by retrying.
                             let res = {
-                                let res = files.load_index(self.object_hash);
+                                let res = files.load_index(self.object_hash, self.pack_backing.clone());
                                 slot.files.store(bundle);
                                 index.loaded_indices.fetch_add(1, Ordering::SeqCst);
                                 res
@@ -24,6 +24,10 @@ where
     /// If true, replacements will not be performed even if these are available.
     pub ignore_replacements: bool,
 
+    /// If set, objects that are missing locally will be fetched from the promisor when looked up, unless the
+    /// [refresh mode](RefreshMode::Never) indicates that objects are expected to be missing.
+    pub promisor: Option<std::sync::Arc<dyn promisor::Fetch>>,
+
     pub(crate) token: Option<handle::Mode>,
     snapshot: RefCell<load_index::Snapshot>,
     inflate: RefCell<zlib::Inflate>,
@@ -58,6 +62,9 @@ pub mod prefix;
 
 mod header;
 
+///
+pub mod promisor;
+
 ///
 pub mod iter;
 
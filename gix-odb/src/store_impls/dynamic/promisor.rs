@@ -0,0 +1,99 @@
+use std::ops::Deref;
+
+use gix_hash::{oid, ObjectId};
+
+use crate::store::{find, handle, RefreshMode};
+
+/// The error returned by [`Fetch::fetch()`].
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A way to obtain objects that are missing locally from a promisor remote, typically after a partial clone.
+///
+/// Implementations are expected to write the fetched objects into the object database, usually as a pack that is
+/// marked with a `.promisor` file, so they can be found once the object database picks up the changes on disk.
+pub trait Fetch: Send + Sync {
+    /// Fetch all objects identified by `ids` from the promisor remote and write them into the object database.
+    fn fetch(&self, ids: &[ObjectId]) -> Result<(), Error>;
+}
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Fetch all objects in `ids` which aren't present locally from the [promisor](Self::promisor) in a single batch,
+    /// and return the amount of objects that were requested.
+    ///
+    /// This is useful to avoid fetching objects one by one when it's known which objects will be needed,
+    /// like before a checkout. Nothing happens if there is no promisor.
+    pub fn prefetch(&self, ids: impl IntoIterator<Item = ObjectId>) -> Result<usize, Error> {
+        let Some(promisor) = self.promisor.as_ref() else {
+            return Ok(0);
+        };
+        let mut missing: Vec<_> = ids
+            .into_iter()
+            .filter(|id| !gix_pack::Find::contains(self, id))
+            .collect();
+        missing.sort();
+        missing.dedup();
+        if !missing.is_empty() {
+            promisor.fetch(&missing)?;
+        }
+        Ok(missing.len())
+    }
+
+    /// Return `true` if `id` is stored in a promisor pack, i.e. a pack that was received from a promisor remote
+    /// and is marked as such by a `.promisor` file next to it.
+    pub fn is_in_promisor_pack(&self, id: &oid) -> bool {
+        let mut snapshot = self.snapshot.borrow_mut();
+        loop {
+            for index in snapshot.indices.iter() {
+                let pack_path = match &index.file {
+                    handle::SingleOrMultiIndex::Single { index, .. } => {
+                        if index.lookup(id).is_none() {
+                            continue;
+                        }
+                        index.path().to_owned()
+                    }
+                    handle::SingleOrMultiIndex::Multi { index, .. } => {
+                        let Some(entry_index) = index.lookup(id) else {
+                            continue;
+                        };
+                        let (pack_index, _) = index.pack_id_and_pack_offset_at_index(entry_index);
+                        index
+                            .path()
+                            .parent()
+                            .expect("multi-index is in a directory")
+                            .join(&index.index_names()[pack_index as usize])
+                    }
+                };
+                return pack_path.with_extension("promisor").is_file();
+            }
+
+            match self.store.load_one_index(self.refresh, snapshot.marker) {
+                Ok(Some(new_snapshot)) => {
+                    *snapshot = new_snapshot;
+                    self.clear_cache();
+                }
+                Ok(None) | Err(_) => return false,
+            }
+        }
+    }
+
+    /// Fetch `id`, which wasn't found locally, from the promisor and return `true` if the lookup should be tried again.
+    ///
+    /// Handles that never refresh expect objects to be missing, which is why these won't ever fetch.
+    pub(crate) fn fetch_missing_from_promisor(&self, id: &oid) -> Result<bool, find::Error> {
+        match (self.promisor.as_ref(), self.refresh) {
+            (Some(promisor), RefreshMode::AfterAllIndicesLoaded) => {
+                promisor
+                    .fetch(&[id.to_owned()])
+                    .map_err(|source| find::Error::PromisorFetch {
+                        source,
+                        id: id.to_owned(),
+                    })?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
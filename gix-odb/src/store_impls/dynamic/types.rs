@@ -311,10 +311,14 @@ impl IndexAndPacks {
         }
     }
 
-    pub(crate) fn load_index(&mut self, object_hash: gix_hash::Kind) -> std::io::Result<()> {
+    pub(crate) fn load_index(
+        &mut self,
+        object_hash: gix_hash::Kind,
+        backing: gix_pack::backing::Mode,
+    ) -> std::io::Result<()> {
         match self {
             IndexAndPacks::Index(bundle) => bundle.index.load_strict(|path| {
-                gix_pack::index::File::at(path, object_hash)
+                gix_pack::index::File::at_opts(path, object_hash, backing)
                     .map(Arc::new)
                     .map_err(|err| match err {
                         gix_pack::index::init::Error::Io { source, .. } => source,
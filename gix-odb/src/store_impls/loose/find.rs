@@ -136,12 +136,21 @@ impl Store {
 
     /// Return only the decompressed size of the object and its kind without fully reading it into memory as tuple of `(size, kind)`.
     /// Returns `None` if `id` does not exist in the database.
+    ///
+    /// Only as many bytes are read and decompressed as needed to decode the object header.
     pub fn try_header(&self, id: &gix_hash::oid) -> Result<Option<(u64, gix_object::Kind)>, Error> {
-        const BUF_SIZE: usize = 256;
-        let mut buf = [0_u8; BUF_SIZE];
-        let path = hash_path(id, self.path.clone());
+        self.try_header_with_inflate(id, &mut zlib::Inflate::default())
+    }
 
-        let mut inflate = zlib::Inflate::default();
+    /// Like [`try_header()`](Self::try_header()), but uses `inflate` for decompression to avoid allocating decompressor state
+    /// for each object. It will be reset before use.
+    pub fn try_header_with_inflate(
+        &self,
+        id: &gix_hash::oid,
+        inflate: &mut zlib::Inflate,
+    ) -> Result<Option<(u64, gix_object::Kind)>, Error> {
+        debug_assert_eq!(self.object_hash, id.kind());
+        let path = hash_path(id, self.path.clone());
         let mut istream = match fs::File::open(&path) {
             Ok(f) => f,
             Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
@@ -154,29 +163,43 @@ impl Store {
             }
         };
 
-        let (compressed_buf, _) = buf.split_at_mut(BUF_SIZE - HEADER_MAX_SIZE);
-        let bytes_read = istream.read(compressed_buf).map_err(|e| Error::Io {
-            source: e,
-            action: "read",
-            path: path.to_owned(),
-        })?;
-        let (compressed_buf, header_buf) = buf.split_at_mut(bytes_read);
-        let (status, _consumed_in, consumed_out) =
-            inflate
-                .once(compressed_buf, header_buf)
-                .map_err(|e| Error::DecompressFile {
-                    source: e,
-                    path: path.to_owned(),
-                })?;
-
-        if status == zlib::Status::BufError {
-            return Err(Error::DecompressFile {
-                source: zlib::inflate::Error::Status(status),
-                path,
-            });
+        inflate.reset();
+        let mut compressed_buf = [0_u8; HEADER_MAX_SIZE];
+        let mut header_buf = [0_u8; HEADER_MAX_SIZE];
+        let mut header_len = 0;
+        loop {
+            let bytes_read = istream.read(&mut compressed_buf).map_err(|e| Error::Io {
+                source: e,
+                action: "read",
+                path: path.to_owned(),
+            })?;
+            let mut input = &compressed_buf[..bytes_read];
+            loop {
+                let (status, consumed_in, consumed_out) =
+                    inflate
+                        .once(input, &mut header_buf[header_len..])
+                        .map_err(|e| Error::DecompressFile {
+                            source: e,
+                            path: path.to_owned(),
+                        })?;
+                input = &input[consumed_in..];
+                header_len += consumed_out;
+                let header = &header_buf[..header_len];
+                if header.contains(&0)
+                    || header_len == header_buf.len()
+                    || status == zlib::Status::StreamEnd
+                    || bytes_read == 0
+                    || (consumed_in == 0 && consumed_out == 0)
+                {
+                    // Decoding fails if the header is incomplete.
+                    let (kind, size, _header_size) = gix_object::decode::loose_header(header)?;
+                    return Ok(Some((size, kind)));
+                }
+                if input.is_empty() {
+                    break;
+                }
+            }
         }
-        let (kind, size, _header_size) = gix_object::decode::loose_header(&header_buf[..consumed_out])?;
-        Ok(Some((size, kind)))
     }
 
     fn find_inner<'a>(&self, id: &gix_hash::oid, buf: &'a mut Vec<u8>) -> Result<gix_object::Data<'a>, Error> {
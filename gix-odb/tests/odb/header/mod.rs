@@ -1,3 +1,6 @@
+use gix_object::FindExt;
+use gix_odb::Header;
+
 use crate::{hex_to_id, odb::db};
 
 fn find_header(db: impl gix_odb::Header, hex_id: &str) -> gix_odb::find::Header {
@@ -11,6 +14,49 @@ fn loose_object() {
     find_header(db(), "37d4e6c5c48ba0d245164c4e10d5f41140cab980");
 }
 
+#[test]
+fn loose_object_without_full_decompression() -> crate::Result {
+    let tmp = gix_testtools::tempfile::tempdir()?;
+    let db = gix_odb::at(tmp.path())?;
+    let data = vec![b'x'; 64 * 1024];
+    let id = gix_object::Write::write_buf(&db, gix_object::Kind::Blob, &data)?;
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open(
+            tmp.path()
+                .join(id.to_hex_with_len(2).to_string())
+                .join(&id.to_hex().to_string()[2..]),
+        )?
+        .set_len(16)?;
+
+    assert_eq!(
+        db.try_header(&id)?.expect("present"),
+        gix_odb::find::Header::Loose {
+            kind: gix_object::Kind::Blob,
+            size: data.len() as u64,
+        },
+        "only the beginning of the object is needed to learn its size"
+    );
+    Ok(())
+}
+
+#[test]
+fn deltified_pack_object() -> crate::Result {
+    let db = db();
+    let id = hex_to_id("2c1e59ee54facb7d72c0061d06b9fe3889f357a9");
+    let header = find_header(&db, "2c1e59ee54facb7d72c0061d06b9fe3889f357a9"); // pack 11fd
+    let mut buf = Vec::new();
+    let object = db.find(&id, &mut buf)?;
+    assert_eq!(header.kind(), object.kind);
+    assert_eq!(
+        header.size(),
+        object.data.len() as u64,
+        "the size of the resolved object"
+    );
+    assert_eq!(header.num_deltas(), Some(1));
+    Ok(())
+}
+
 #[test]
 fn pack_object() {
     let db = db();
@@ -1003,3 +1003,119 @@ mod verify {
         );
     }
 }
+
+mod promisor {
+    use std::sync::{Arc, Mutex};
+
+    use gix_hash::ObjectId;
+    use gix_object::{Find, FindExt, Write};
+    use gix_odb::store::promisor;
+    use gix_testtools::fixture_path_standalone;
+
+    use crate::hex_to_id;
+
+    /// Copies the requested objects from a fully-populated object database into the partial one.
+    struct CopyFrom {
+        source: std::path::PathBuf,
+        destination: gix_odb::loose::Store,
+        requests: Mutex<Vec<Vec<ObjectId>>>,
+    }
+
+    impl promisor::Fetch for CopyFrom {
+        fn fetch(&self, ids: &[ObjectId]) -> Result<(), promisor::Error> {
+            self.requests.lock().expect("not poisoned").push(ids.to_vec());
+            let source = gix_odb::at(&self.source)?;
+            let mut buf = Vec::new();
+            for id in ids {
+                let object = source.find(id, &mut buf)?;
+                self.destination.write_buf(object.kind, object.data)?;
+            }
+            Ok(())
+        }
+    }
+
+    fn partial_db() -> crate::Result<(gix_odb::Handle, Arc<CopyFrom>, gix_testtools::tempfile::TempDir)> {
+        let dir = gix_testtools::tempfile::tempdir()?;
+        let mut handle = gix_odb::at(dir.path())?;
+        let promisor = Arc::new(CopyFrom {
+            source: fixture_path_standalone("objects"),
+            destination: gix_odb::loose::Store::at(dir.path(), gix_hash::Kind::Sha1),
+            requests: Default::default(),
+        });
+        handle.promisor = Some(promisor.clone());
+        Ok((handle, promisor, dir))
+    }
+
+    fn requests(promisor: &CopyFrom) -> Vec<Vec<ObjectId>> {
+        promisor.requests.lock().expect("not poisoned").clone()
+    }
+
+    #[test]
+    fn missing_objects_are_fetched_on_access() -> crate::Result {
+        let (handle, promisor, _tmp) = partial_db()?;
+        let id = hex_to_id("dd25c539efbb0ab018caa4cda2d133285634e9b5");
+        let mut buf = Vec::new();
+        assert_eq!(handle.find(&id, &mut buf)?.kind, gix_object::Kind::Blob);
+        assert_eq!(requests(&promisor), [vec![id]]);
+
+        handle.find(&id, &mut buf)?;
+        assert_eq!(requests(&promisor).len(), 1, "present objects aren't fetched again");
+        assert!(!handle.is_in_promisor_pack(&id), "it was written as loose object");
+
+        let missing = hex_to_id("0000000000000000000000000000000000000001");
+        assert!(
+            handle.try_find(&missing, &mut buf).is_err(),
+            "objects that the promisor doesn't have cause an error"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn headers_of_missing_objects_are_fetched_on_access() -> crate::Result {
+        let (handle, promisor, _tmp) = partial_db()?;
+        let id = hex_to_id("dd25c539efbb0ab018caa4cda2d133285634e9b5");
+        let header = gix_odb::Header::try_header(&handle, &id)?.expect("fetched");
+        assert_eq!(header.kind(), gix_object::Kind::Blob);
+        assert_eq!(requests(&promisor), [vec![id]]);
+
+        let mut buf = Vec::new();
+        let data = handle.find(&id, &mut buf)?.data.to_owned();
+        assert_eq!(
+            data,
+            gix_odb::at(fixture_path_standalone("objects"))?.find(&id, &mut buf)?.data,
+            "the object is read after it was fetched"
+        );
+        assert_eq!(requests(&promisor).len(), 1, "present objects aren't fetched again");
+        Ok(())
+    }
+
+    #[test]
+    fn prefetch_fetches_all_missing_objects_in_one_batch() -> crate::Result {
+        let (handle, promisor, _tmp) = partial_db()?;
+        let present = handle.write_buf(gix_object::Kind::Blob, b"hello world")?;
+        let a = hex_to_id("dd25c539efbb0ab018caa4cda2d133285634e9b5");
+        let b = hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0");
+        assert_eq!(handle.prefetch([b, present, a, b])?, 2);
+        assert_eq!(
+            requests(&promisor),
+            [vec![b, a]],
+            "sorted, deduplicated and only missing"
+        );
+
+        assert_eq!(handle.prefetch([a, b, present])?, 0, "all objects are present now");
+        assert_eq!(requests(&promisor).len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn handles_that_never_refresh_do_not_fetch() -> crate::Result {
+        let (mut handle, promisor, _tmp) = partial_db()?;
+        handle.refresh_never();
+        let mut buf = Vec::new();
+        assert!(handle
+            .try_find(&hex_to_id("dd25c539efbb0ab018caa4cda2d133285634e9b5"), &mut buf)?
+            .is_none());
+        assert!(requests(&promisor).is_empty());
+        Ok(())
+    }
+}
@@ -416,15 +416,50 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
         fn all() -> crate::Result {
             let db = ldb();
             let mut buf = Vec::new();
+            let mut inflate = gix_features::zlib::Inflate::default();
             for id in db.iter() {
                 let id = id?;
                 let expected = db.try_find(&id, &mut buf)?.expect("exists");
                 let (size, kind) = db.try_header(&id)?.expect("header exists");
                 assert_eq!(size, expected.data.len() as u64);
                 assert_eq!(kind, expected.kind);
+                assert_eq!(
+                    db.try_header_with_inflate(&id, &mut inflate)?,
+                    Some((size, kind)),
+                    "the decompressor can be reused"
+                );
             }
             Ok(())
         }
+
+        #[test]
+        fn only_the_header_is_read_and_decompressed() -> crate::Result {
+            let tmp = gix_testtools::tempfile::tempdir()?;
+            let db = gix_odb::loose::Store::at(tmp.path(), gix_hash::Kind::Sha1);
+            let data: Vec<u8> = (0..100_000_u32).flat_map(u32::to_le_bytes).collect();
+            let id = gix_object::Write::write_buf(&db, gix_object::Kind::Blob, &data)?;
+
+            let path = tmp
+                .path()
+                .join(id.to_hex_with_len(2).to_string())
+                .join(&id.to_hex().to_string()[2..]);
+            let file = std::fs::OpenOptions::new().write(true).open(&path)?;
+            file.set_len(32)?;
+
+            assert_eq!(
+                db.try_header(&id)?,
+                Some((data.len() as u64, gix_object::Kind::Blob)),
+                "the truncated object still has a complete header"
+            );
+            assert!(
+                db.try_find(&id, &mut Vec::new()).is_err(),
+                "reading the whole object fails"
+            );
+
+            file.set_len(2)?;
+            assert!(db.try_header(&id).is_err(), "incomplete headers are detected");
+            Ok(())
+        }
     }
 }
 
@@ -0,0 +1,341 @@
+use std::{
+    borrow::Cow,
+    ops::{Deref, Range},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+/// Determines how the bytes of pack data files and pack index files are accessed.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Memory-map the entire file, which is fastest but turns I/O errors into crashes, for instance when the file
+    /// is truncated while mapped, and needs enough address space to hold the whole file.
+    #[default]
+    MemoryMap,
+    /// Read the entire file into memory when opening it, so that all I/O errors are reported right away.
+    InMemory,
+    /// Read pack data files in windows of `window_size` bytes on demand, and keep at most as many bytes in windows
+    /// as the `budget` allows, similar to what `core.packedGitWindowSize` and `core.packedGitLimit` control in `git`.
+    ///
+    /// I/O errors are reported when reading a window. As pack index files are comparatively small and accessed randomly,
+    /// they are read into memory entirely instead.
+    Windowed {
+        /// The amount of bytes to read at once.
+        window_size: usize,
+        /// The limit for the bytes in windows of all packs that are opened with a clone of this mode.
+        budget: Budget,
+    },
+}
+
+/// The maximum amount of bytes to keep in windows of all pack data files that are opened with (clones of) it.
+///
+/// Once the limit is reached, the least recently used windows of any of these packs are dropped first.
+/// Windows larger than the limit are read, but not kept.
+///
+/// Note that unlike in `git`, where the limit applies to all packs of the process, it applies to all packs that share
+/// this instance, which typically are all packs of an object database.
+#[derive(Clone)]
+pub struct Budget(Arc<Mutex<Windows>>);
+
+impl Budget {
+    /// Create a new instance to keep at most `limit` bytes in windows.
+    pub fn new(limit: usize) -> Self {
+        Budget(Arc::new(Mutex::new(Windows {
+            limit,
+            ..Default::default()
+        })))
+    }
+
+    /// Return the maximum amount of bytes to keep in windows.
+    pub fn limit(&self) -> usize {
+        self.lock().limit
+    }
+
+    /// Return the amount of bytes that are currently kept in windows.
+    pub fn bytes_in_use(&self) -> usize {
+        self.lock().bytes
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Windows> {
+        self.0.lock().expect("no panic while holding the lock")
+    }
+}
+
+impl std::fmt::Debug for Budget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let windows = self.lock();
+        f.debug_struct("Budget")
+            .field("limit", &windows.limit)
+            .field("bytes_in_use", &windows.bytes)
+            .finish()
+    }
+}
+
+/// Instances are equal if they are shared.
+impl PartialEq for Budget {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Budget {}
+
+/// Bytes that are available in their entirety.
+pub(crate) enum Bytes {
+    Map(memmap2::Mmap),
+    Buffer(Vec<u8>),
+}
+
+impl Bytes {
+    /// Open the file at `path` according to `mode`, where [`Mode::Windowed`] is treated like [`Mode::InMemory`].
+    pub(crate) fn open(path: &Path, mode: Mode) -> std::io::Result<Self> {
+        Ok(match mode {
+            Mode::MemoryMap => Bytes::Map(crate::mmap::read_only(path)?),
+            Mode::InMemory | Mode::Windowed { .. } => Bytes::Buffer(std::fs::read(path)?),
+        })
+    }
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Bytes::Map(map) => map,
+            Bytes::Buffer(buf) => buf,
+        }
+    }
+}
+
+/// The bytes of a pack data file.
+pub(crate) enum Data {
+    Bytes(Bytes),
+    Windowed(Windowed),
+}
+
+/// A contiguous portion of [`Data`] that starts at a given offset.
+pub(crate) enum Chunk<'a> {
+    Borrowed(&'a [u8]),
+    Window { data: Arc<[u8]>, start: usize },
+}
+
+impl Deref for Chunk<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Chunk::Borrowed(data) => data,
+            Chunk::Window { data, start } => &data[*start..],
+        }
+    }
+}
+
+impl Data {
+    pub(crate) fn open(path: &Path, mode: Mode) -> std::io::Result<Self> {
+        Ok(match mode {
+            Mode::MemoryMap | Mode::InMemory => Data::Bytes(Bytes::open(path, mode)?),
+            Mode::Windowed { window_size, budget } => Data::Windowed(Windowed::open(path, window_size, budget)?),
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Data::Bytes(bytes) => bytes.len(),
+            Data::Windowed(windowed) => windowed.len,
+        }
+    }
+
+    /// Return all data if it is available in memory.
+    pub(crate) fn as_slice(&self) -> Option<&[u8]> {
+        match self {
+            Data::Bytes(bytes) => Some(bytes),
+            Data::Windowed(_) => None,
+        }
+    }
+
+    /// Return the bytes in `range`, or an error if they are out of bounds or couldn't be read.
+    pub(crate) fn read(&self, range: Range<usize>) -> std::io::Result<Cow<'_, [u8]>> {
+        match self {
+            Data::Bytes(bytes) => bytes.get(range).map(Cow::Borrowed).ok_or_else(out_of_bounds),
+            Data::Windowed(windowed) => windowed.read(range).map(Cow::Owned),
+        }
+    }
+
+    /// Return as many contiguous bytes as are cheaply available starting at `offset`, which may be fewer than
+    /// the remaining bytes of the file. The returned chunk is only empty if `offset` is at the end of the data.
+    pub(crate) fn chunk_at(&self, offset: usize) -> std::io::Result<Chunk<'_>> {
+        match self {
+            Data::Bytes(bytes) => bytes.get(offset..).map(Chunk::Borrowed).ok_or_else(out_of_bounds),
+            Data::Windowed(windowed) => windowed.chunk_at(offset),
+        }
+    }
+}
+
+fn out_of_bounds() -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "read beyond the end of the pack")
+}
+
+pub(crate) struct Windowed {
+    file: std::fs::File,
+    len: usize,
+    window_size: usize,
+    /// Identifies our windows among the ones of other packs in the `budget`.
+    id: usize,
+    budget: Budget,
+}
+
+#[derive(Default)]
+struct Windows {
+    /// Windows along with the id of their pack, their index and the time they were last used.
+    open: Vec<(usize, usize, Arc<[u8]>, u64)>,
+    bytes: usize,
+    limit: usize,
+    clock: u64,
+    next_id: usize,
+}
+
+impl Windows {
+    fn get(&mut self, id: usize, index: usize) -> Option<Arc<[u8]>> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.open
+            .iter_mut()
+            .find(|(window_id, window_index, _, _)| *window_id == id && *window_index == index)
+            .map(|(_, _, data, last_used)| {
+                *last_used = clock;
+                data.clone()
+            })
+    }
+
+    fn insert(&mut self, id: usize, index: usize, data: Arc<[u8]>) -> Arc<[u8]> {
+        if let Some(existing) = self.get(id, index) {
+            return existing;
+        }
+        if data.len() > self.limit {
+            return data;
+        }
+        while !self.open.is_empty() && self.bytes + data.len() > self.limit {
+            let lru = self
+                .open
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, (_, _, _, last_used))| *last_used)
+                .map(|(pos, _)| pos)
+                .expect("not empty");
+            let (_, _, evicted, _) = self.open.swap_remove(lru);
+            self.bytes -= evicted.len();
+        }
+        self.bytes += data.len();
+        self.open.push((id, index, data.clone(), self.clock));
+        data
+    }
+
+    fn remove_all(&mut self, id: usize) {
+        let bytes = &mut self.bytes;
+        self.open.retain(|(window_id, _, data, _)| {
+            let keep = *window_id != id;
+            if !keep {
+                *bytes -= data.len();
+            }
+            keep
+        });
+    }
+}
+
+impl Windowed {
+    fn open(path: &Path, window_size: usize, budget: Budget) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let len = file.metadata()?.len().try_into().map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "pack is too large to be addressed on this platform",
+            )
+        })?;
+        let id = {
+            let mut windows = budget.lock();
+            windows.next_id += 1;
+            windows.next_id
+        };
+        Ok(Windowed {
+            file,
+            len,
+            window_size: window_size.max(1),
+            id,
+            budget,
+        })
+    }
+
+    fn window(&self, index: usize) -> std::io::Result<Arc<[u8]>> {
+        if let Some(window) = self.budget.lock().get(self.id, index) {
+            return Ok(window);
+        }
+        let start = index * self.window_size;
+        let mut buf = vec![0; self.window_size.min(self.len - start)];
+        read_exact_at(&self.file, &mut buf, start as u64)?;
+        Ok(self.budget.lock().insert(self.id, index, buf.into()))
+    }
+
+    fn chunk_at(&self, offset: usize) -> std::io::Result<Chunk<'_>> {
+        if offset > self.len {
+            return Err(out_of_bounds());
+        }
+        if offset == self.len {
+            return Ok(Chunk::Borrowed(&[]));
+        }
+        Ok(Chunk::Window {
+            data: self.window(offset / self.window_size)?,
+            start: offset % self.window_size,
+        })
+    }
+
+    fn read(&self, range: Range<usize>) -> std::io::Result<Vec<u8>> {
+        if range.start > range.end || range.end > self.len {
+            return Err(out_of_bounds());
+        }
+        let mut out = Vec::with_capacity(range.len());
+        let mut offset = range.start;
+        while offset < range.end {
+            let chunk = self.chunk_at(offset)?;
+            let len = chunk.len().min(range.end - offset);
+            out.extend_from_slice(&chunk[..len]);
+            offset += len;
+        }
+        Ok(out)
+    }
+}
+
+impl Drop for Windowed {
+    fn drop(&mut self) {
+        self.budget.lock().remove_all(self.id);
+    }
+}
+
+#[cfg(unix)]
+fn read_exact_at(file: &std::fs::File, buf: &mut [u8], offset: u64) -> std::io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn read_exact_at(file: &std::fs::File, mut buf: &mut [u8], mut offset: u64) -> std::io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !buf.is_empty() {
+        match file.seek_read(buf, offset) {
+            Ok(0) => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => {
+                buf = &mut buf[n..];
+                offset += n as u64;
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn read_exact_at(_file: &std::fs::File, _buf: &mut [u8], _offset: u64) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "windowed pack access isn't supported on this platform",
+    ))
+}
@@ -1,4 +1,5 @@
 use std::{
+    borrow::Cow,
     io,
     io::Write,
     marker::PhantomData,
@@ -362,15 +363,17 @@ impl crate::Bundle {
     }
 }
 
-fn resolve_entry(range: data::EntryRange, mapped_file: &memmap2::Mmap) -> Option<&[u8]> {
-    mapped_file.get(range.start as usize..range.end as usize)
+fn resolve_entry(range: data::EntryRange, mapped_file: &memmap2::Mmap) -> Option<Cow<'_, [u8]>> {
+    mapped_file
+        .get(range.start as usize..range.end as usize)
+        .map(Cow::Borrowed)
 }
 
 #[allow(clippy::type_complexity)] // cannot typedef impl Fn
 fn new_pack_file_resolver(
     data_file: SharedTempFile,
 ) -> io::Result<(
-    impl Fn(data::EntryRange, &memmap2::Mmap) -> Option<&[u8]> + Send + Clone,
+    impl Fn(data::EntryRange, &memmap2::Mmap) -> Option<Cow<'_, [u8]>> + Send + Clone,
     memmap2::Mmap,
 )> {
     let mut guard = data_file.lock();
@@ -1,4 +1,7 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::{
+    borrow::Cow,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use gix_features::{
     parallel::in_parallel_with_slice,
@@ -116,7 +119,7 @@ where
         }: Options<'_, '_>,
     ) -> Result<Outcome<T>, Error>
     where
-        F: for<'r> Fn(EntryRange, &'r R) -> Option<&'r [u8]> + Send + Clone,
+        F: for<'r> Fn(EntryRange, &'r R) -> Option<Cow<'r, [u8]>> + Send + Clone,
         R: Send + Sync,
         MBFN: FnMut(&mut T, &dyn Progress, Context<'_>) -> Result<(), E> + Send + Clone,
         E: std::error::Error + Send + Sync + 'static,
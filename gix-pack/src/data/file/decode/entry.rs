@@ -8,6 +8,11 @@ use crate::{
     data::{delta, file::decode::Error, File},
 };
 
+/// The maximum size of an entry header without the hash of a base object, which is used by ref-deltas.
+/// It's made up of the type and size of the object as well as the distance to the base object of ofs-deltas,
+/// each encoded as variable-length integer of at most 10 bytes.
+const MAX_ENTRY_HEADER_SIZE: usize = 20;
+
 /// A return value of a resolve function, which given an [`ObjectId`][gix_hash::ObjectId] determines where an object can be found.
 #[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -97,18 +102,18 @@ impl File {
         );
 
         self.decompress_entry_from_data_offset(entry.data_offset, inflate, out)
-            .map_err(Into::into)
     }
 
     /// Obtain the [`Entry`][crate::data::Entry] at the given `offset` into the pack.
     ///
     /// The `offset` is typically obtained from the pack index file.
-    pub fn entry(&self, offset: data::Offset) -> Result<data::Entry, data::entry::decode::Error> {
+    pub fn entry(&self, offset: data::Offset) -> Result<data::Entry, Error> {
         let pack_offset: usize = offset.try_into().expect("offset representable by machine");
         assert!(pack_offset <= self.data.len(), "offset out of bounds");
 
-        let object_data = &self.data[pack_offset..];
-        data::Entry::from_bytes(object_data, offset, self.hash_len)
+        let header_end = (pack_offset + MAX_ENTRY_HEADER_SIZE + self.hash_len).min(self.data.len());
+        let object_data = self.data.read(pack_offset..header_end)?;
+        Ok(data::Entry::from_bytes(&object_data, offset, self.hash_len)?)
     }
 
     /// Decompress the object expected at the given data offset, sans pack header. This information is only
@@ -121,14 +126,9 @@ impl File {
         data_offset: data::Offset,
         inflate: &mut zlib::Inflate,
         out: &mut [u8],
-    ) -> Result<usize, zlib::inflate::Error> {
-        let offset: usize = data_offset.try_into().expect("offset representable by machine");
-        assert!(offset < self.data.len(), "entry offset out of bounds");
-
-        inflate.reset();
-        inflate
-            .once(&self.data[offset..], out)
-            .map(|(_status, consumed_in, _consumed_out)| consumed_in)
+    ) -> Result<usize, Error> {
+        self.decompress_entry_from_data_offset_2(data_offset, inflate, out)
+            .map(|(consumed_in, _consumed_out)| consumed_in)
     }
 
     /// Like `decompress_entry_from_data_offset`, but returns consumed input and output.
@@ -137,14 +137,23 @@ impl File {
         data_offset: data::Offset,
         inflate: &mut zlib::Inflate,
         out: &mut [u8],
-    ) -> Result<(usize, usize), zlib::inflate::Error> {
+    ) -> Result<(usize, usize), Error> {
         let offset: usize = data_offset.try_into().expect("offset representable by machine");
         assert!(offset < self.data.len(), "entry offset out of bounds");
 
         inflate.reset();
-        inflate
-            .once(&self.data[offset..], out)
-            .map(|(_status, consumed_in, consumed_out)| (consumed_in, consumed_out))
+        let (mut consumed_in, mut consumed_out) = (0, 0);
+        loop {
+            let chunk = self.data.chunk_at(offset + consumed_in)?;
+            let (status, chunk_in, chunk_out) = inflate.once(&chunk, &mut out[consumed_out..])?;
+            consumed_in += chunk_in;
+            consumed_out += chunk_out;
+            // Only windowed data can run out of input before the end of the pack is reached.
+            if status == zlib::Status::StreamEnd || chunk_in != chunk.len() || offset + consumed_in == self.data.len() {
+                break;
+            }
+        }
+        Ok((consumed_in, consumed_out))
     }
 
     /// Decode an entry, resolving delta's as needed, while growing the `out` vector if there is not enough
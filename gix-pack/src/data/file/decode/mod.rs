@@ -17,6 +17,8 @@ pub enum Error {
     DeltaBaseUnresolved(gix_hash::ObjectId),
     #[error(transparent)]
     EntryType(#[from] crate::data::entry::decode::Error),
+    #[error("Failed to read pack data")]
+    Io(#[from] std::io::Error),
     #[error("Entry too large to fit in memory")]
     OutOfMemory,
 }
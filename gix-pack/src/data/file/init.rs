@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use crate::data;
+use crate::{backing, data};
 
 /// Instantiation
 impl data::File {
@@ -9,25 +9,44 @@ impl data::File {
     /// The `object_hash` is a way to read (and write) the same file format with different hashes, as the hash kind
     /// isn't stored within the file format itself.
     pub fn at(path: impl AsRef<Path>, object_hash: gix_hash::Kind) -> Result<data::File, data::header::decode::Error> {
-        Self::at_inner(path.as_ref(), object_hash)
+        Self::at_inner(path.as_ref(), object_hash, backing::Mode::default())
     }
 
-    fn at_inner(path: &Path, object_hash: gix_hash::Kind) -> Result<data::File, data::header::decode::Error> {
+    /// Like [`at()`](Self::at()), but use `mode` to control how the pack data is accessed.
+    pub fn at_opts(
+        path: impl AsRef<Path>,
+        object_hash: gix_hash::Kind,
+        mode: backing::Mode,
+    ) -> Result<data::File, data::header::decode::Error> {
+        Self::at_inner(path.as_ref(), object_hash, mode)
+    }
+
+    fn at_inner(
+        path: &Path,
+        object_hash: gix_hash::Kind,
+        mode: backing::Mode,
+    ) -> Result<data::File, data::header::decode::Error> {
         use crate::data::header::N32_SIZE;
         let hash_len = object_hash.len_in_bytes();
 
-        let data = crate::mmap::read_only(path).map_err(|e| data::header::decode::Error::Io {
-            source: e,
+        let io_err = |source| data::header::decode::Error::Io {
+            source,
             path: path.to_owned(),
-        })?;
+        };
+        let data = backing::Data::open(path, mode).map_err(io_err)?;
         let pack_len = data.len();
         if pack_len < N32_SIZE * 3 + hash_len {
             return Err(data::header::decode::Error::Corrupt(format!(
                 "Pack data of size {pack_len} is too small for even an empty pack with shortest hash"
             )));
         }
-        let (kind, num_objects) =
-            data::header::decode(&data[..12].try_into().expect("enough data after previous check"))?;
+        let (kind, num_objects) = data::header::decode(
+            &data.read(0..12).map_err(io_err)?[..]
+                .try_into()
+                .expect("enough data after previous check"),
+        )?;
+        let checksum =
+            gix_hash::ObjectId::from_bytes_or_panic(&data.read(pack_len - hash_len..pack_len).map_err(io_err)?);
         Ok(data::File {
             data,
             path: path.to_owned(),
@@ -36,6 +55,7 @@ impl data::File {
             num_objects,
             hash_len,
             object_hash,
+            checksum,
         })
     }
 }
@@ -14,7 +14,7 @@ pub mod checksum {
 impl File {
     /// The checksum in the trailer of this pack data file
     pub fn checksum(&self) -> gix_hash::ObjectId {
-        gix_hash::ObjectId::from_bytes_or_panic(&self.data[self.data.len() - self.hash_len..])
+        self.checksum
     }
 
     /// Verifies that the checksum of the packfile over all bytes preceding it indeed matches the actual checksum,
@@ -30,9 +30,10 @@ impl File {
         progress: &mut dyn Progress,
         should_interrupt: &AtomicBool,
     ) -> Result<gix_hash::ObjectId, checksum::Error> {
-        crate::verify::checksum_on_disk_or_mmap(
+        crate::verify::checksum_on_disk_or_data(
             self.path(),
-            &self.data,
+            self.data.len(),
+            self.data.as_slice(),
             self.checksum(),
             self.object_hash,
             progress,
@@ -1,5 +1,5 @@
 //! a pack data file
-use std::path::Path;
+use std::{borrow::Cow, path::Path};
 
 /// The offset to an entry into the pack data file, relative to its beginning.
 pub type Offset = u64;
@@ -7,8 +7,6 @@ pub type Offset = u64;
 /// An identifier to uniquely identify all packs loaded within a known context or namespace.
 pub type Id = u32;
 
-use memmap2::Mmap;
-
 /// An representing an full- or delta-object within a pack
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -61,7 +59,7 @@ pub enum Version {
 
 /// A pack data file
 pub struct File {
-    data: Mmap,
+    data: crate::backing::Data,
     path: std::path::PathBuf,
     /// A value to represent this pack uniquely when used with cache lookup, or a way to identify this pack by its location on disk.
     /// The same location on disk should yield the same id.
@@ -77,6 +75,7 @@ pub struct File {
     /// based on their configuration.
     hash_len: usize,
     object_hash: gix_hash::Kind,
+    checksum: gix_hash::ObjectId,
 }
 
 /// Information about the pack data file itself
@@ -89,7 +88,7 @@ impl File {
     pub fn num_objects(&self) -> u32 {
         self.num_objects
     }
-    /// The length of all mapped data, including the pack header and the pack trailer
+    /// The length of all pack data, including the pack header and the pack trailer
     pub fn data_len(&self) -> usize {
         self.data.len()
     }
@@ -107,24 +106,36 @@ impl File {
         &self.path
     }
 
-    /// Returns the pack data at the given slice if its range is contained in the mapped pack data
-    pub fn entry_slice(&self, slice: EntryRange) -> Option<&[u8]> {
+    /// Returns the pack data at the given slice if its range is contained in the pack data and could be read.
+    ///
+    /// The data is only borrowed if the pack isn't accessed through [windows](crate::backing::Mode::Windowed).
+    pub fn entry_slice(&self, slice: EntryRange) -> Option<Cow<'_, [u8]>> {
         let entry_end: usize = slice.end.try_into().expect("end of pack fits into usize");
         let entry_start = slice.start as usize;
-        self.data.get(entry_start..entry_end)
+        self.data.read(entry_start..entry_end).ok()
     }
 
-    /// Returns the CRC32 of the pack data indicated by `pack_offset` and the `size` of the mapped data.
+    /// Returns the CRC32 of the pack data indicated by `pack_offset` and the `size` of the entry.
     ///
     /// _Note:_ finding the right size is only possible by decompressing
     /// the pack entry beforehand, or by using the (to be sorted) offsets stored in an index file.
     ///
-    /// # Panics
-    ///
-    /// If `pack_offset` or `size` are pointing to a range outside of the mapped pack data.
-    pub fn entry_crc32(&self, pack_offset: Offset, size: usize) -> u32 {
+    /// An error is returned if `pack_offset` or `size` are pointing to a range outside of the pack data, or if it couldn't be read.
+    pub fn entry_crc32(&self, pack_offset: Offset, size: usize) -> std::io::Result<u32> {
         let pack_offset: usize = pack_offset.try_into().expect("pack_size fits into usize");
-        gix_features::hash::crc32(&self.data[pack_offset..pack_offset + size])
+        let end = pack_offset + size;
+        let mut crc32 = 0;
+        let mut offset = pack_offset;
+        while offset < end {
+            let chunk = self.data.chunk_at(offset)?;
+            if chunk.is_empty() {
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+            let len = chunk.len().min(end - offset);
+            crc32 = gix_features::hash::crc32_update(crc32, &chunk[..len]);
+            offset += len;
+        }
+        Ok(crc32)
     }
 }
 
@@ -37,6 +37,31 @@ pub struct FromEntriesIter<I, W> {
     pack_offsets_and_validity: Vec<(u64, bool)>,
     /// If we are done, no additional writes will occur
     is_done: bool,
+    /// If `Some`, the id, pack offset and CRC32 of each written entry so that an index can be written without re-reading the pack.
+    index_entries: Option<Vec<IndexEntry>>,
+}
+
+/// Information about a written pack entry, as needed to write an index for it.
+#[cfg_attr(not(feature = "streaming-input"), allow(dead_code))]
+struct IndexEntry {
+    id: gix_hash::ObjectId,
+    pack_offset: crate::data::Offset,
+    crc32: u32,
+}
+
+#[cfg(feature = "streaming-input")]
+impl crate::index::encode::Entry for IndexEntry {
+    fn id(&self) -> &gix_hash::oid {
+        &self.id
+    }
+
+    fn crc32(&self) -> u32 {
+        self.crc32
+    }
+
+    fn pack_offset(&self) -> crate::data::Offset {
+        self.pack_offset
+    }
 }
 
 impl<I, W, E> FromEntriesIter<I, W>
@@ -75,9 +100,18 @@ where
             written: 0,
             header_info: Some((version, num_entries)),
             is_done: false,
+            index_entries: None,
         }
     }
 
+    /// Record the id, pack offset and CRC32 of each entry while it's written, which allows to call
+    /// [`write_index()`](Self::write_index()) once all entries were written without having to read the pack again.
+    #[cfg(feature = "streaming-input")]
+    pub fn with_index_entries(mut self) -> Self {
+        self.index_entries = Some(exact_vec(self.pack_offsets_and_validity.capacity()));
+        self
+    }
+
     /// Consume this instance and return the `output` implementation.
     ///
     /// _Note_ that the `input` iterator can be moved out of this instance beforehand.
@@ -91,6 +125,49 @@ where
         self.trailer
     }
 
+    /// Write an index of `version` for all written entries to `out`, along with a reverse index to `reverse_index_out` if set,
+    /// to make the pack usable without reading it again.
+    ///
+    /// # Panics
+    ///
+    /// If not all entries were written yet, or if [`with_index_entries()`](Self::with_index_entries()) wasn't called before writing.
+    #[cfg(feature = "streaming-input")]
+    pub fn write_index(
+        &mut self,
+        version: crate::index::Version,
+        out: &mut dyn std::io::Write,
+        reverse_index_out: Option<&mut dyn std::io::Write>,
+        progress: &mut dyn gix_features::progress::DynNestedProgress,
+    ) -> Result<crate::index::write::Outcome, crate::index::write::Error> {
+        use crate::index::write::Error;
+        if version != crate::index::Version::default() {
+            return Err(Error::Unsupported(version));
+        }
+        let pack_hash = self
+            .trailer
+            .expect("all entries must be written before writing the index");
+        let mut entries = self
+            .index_entries
+            .take()
+            .expect("with_index_entries() must be called before writing entries");
+        entries.sort_by_key(|entry| entry.id);
+        let num_objects = entries
+            .len()
+            .try_into()
+            .map_err(|_| Error::IteratorInvariantTooManyObjects(entries.len()))?;
+
+        let index_hash = crate::index::encode::write_to(out, &entries, &pack_hash, version, progress)?;
+        if let Some(out) = reverse_index_out {
+            crate::index::encode::write_reverse_to(out, &entries, &pack_hash, pack_hash.kind())?;
+        }
+        Ok(crate::index::write::Outcome {
+            index_version: version,
+            index_hash,
+            data_hash: pack_hash,
+            num_objects,
+        })
+    }
+
     fn next_inner(&mut self) -> Result<u64, Error<E>> {
         let previous_written = self.written;
         if let Some((version, num_entries)) = self.header_info.take() {
@@ -115,9 +192,30 @@ where
                         }
                         self.written - base_offset
                     });
-                    self.written += header
-                        .write_to(entry.decompressed_size as u64, &mut self.output)
-                        .map_err(gix_hash::io::Error::from)? as u64;
+                    let pack_offset = self.written;
+                    match self.index_entries.as_mut() {
+                        Some(index_entries) => {
+                            let mut header_buf = Vec::with_capacity(32);
+                            header
+                                .write_to(entry.decompressed_size as u64, &mut header_buf)
+                                .map_err(gix_hash::io::Error::from)?;
+                            self.output.write_all(&header_buf).map_err(gix_hash::io::Error::from)?;
+                            self.written += header_buf.len() as u64;
+                            index_entries.push(IndexEntry {
+                                id: entry.id,
+                                pack_offset,
+                                crc32: gix_features::hash::crc32_update(
+                                    gix_features::hash::crc32(&header_buf),
+                                    &entry.compressed_data,
+                                ),
+                            });
+                        }
+                        None => {
+                            self.written += header
+                                .write_to(entry.decompressed_size as u64, &mut self.output)
+                                .map_err(gix_hash::io::Error::from)? as u64;
+                        }
+                    }
                     self.written += std::io::copy(&mut &*entry.compressed_data, &mut self.output)
                         .map_err(gix_hash::io::Error::from)?;
                 }
@@ -32,14 +32,22 @@ pub(crate) fn fanout(iter: &mut dyn ExactSizeIterator<Item = u8>) -> [u32; 256]
     fan_out
 }
 
+/// An entry to be written into an index or reverse index.
+#[cfg(feature = "streaming-input")]
+pub(crate) trait Entry {
+    fn id(&self) -> &gix_hash::oid;
+    fn crc32(&self) -> u32;
+    fn pack_offset(&self) -> crate::data::Offset;
+}
+
 #[cfg(feature = "streaming-input")]
 mod function {
     use std::io;
 
     use gix_features::progress::{self, DynNestedProgress};
 
-    use super::{fanout, HIGH_BIT, LARGE_OFFSET_THRESHOLD};
-    use crate::index::V2_SIGNATURE;
+    use super::{fanout, Entry, HIGH_BIT, LARGE_OFFSET_THRESHOLD};
+    use crate::index::{REVERSE_SIGNATURE, V2_SIGNATURE};
 
     struct Count<W> {
         bytes: u64,
@@ -67,9 +75,9 @@ mod function {
         }
     }
 
-    pub(crate) fn write_to(
+    pub(crate) fn write_to<T: Entry>(
         out: &mut dyn io::Write,
-        entries_sorted_by_oid: Vec<crate::cache::delta::Item<crate::index::write::TreeEntry>>,
+        entries_sorted_by_oid: &[T],
         pack_hash: &gix_hash::ObjectId,
         kind: crate::index::Version,
         progress: &mut dyn DynNestedProgress,
@@ -92,7 +100,7 @@ mod function {
         progress.init(Some(4), progress::steps());
         let start = std::time::Instant::now();
         let _info = progress.add_child_with_id("writing fan-out table".into(), gix_features::progress::UNKNOWN);
-        let fan_out = fanout(&mut entries_sorted_by_oid.iter().map(|e| e.data.id.first_byte()));
+        let fan_out = fanout(&mut entries_sorted_by_oid.iter().map(|e| e.id().first_byte()));
 
         for value in fan_out.iter() {
             out.write_all(&value.to_be_bytes())?;
@@ -100,30 +108,31 @@ mod function {
 
         progress.inc();
         let _info = progress.add_child_with_id("writing ids".into(), gix_features::progress::UNKNOWN);
-        for entry in &entries_sorted_by_oid {
-            out.write_all(entry.data.id.as_slice())?;
+        for entry in entries_sorted_by_oid {
+            out.write_all(entry.id().as_bytes())?;
         }
 
         progress.inc();
         let _info = progress.add_child_with_id("writing crc32".into(), gix_features::progress::UNKNOWN);
-        for entry in &entries_sorted_by_oid {
-            out.write_all(&entry.data.crc32.to_be_bytes())?;
+        for entry in entries_sorted_by_oid {
+            out.write_all(&entry.crc32().to_be_bytes())?;
         }
 
         progress.inc();
         let _info = progress.add_child_with_id("writing offsets".into(), gix_features::progress::UNKNOWN);
         {
             let mut offsets64 = Vec::<u64>::new();
-            for entry in &entries_sorted_by_oid {
-                let offset: u32 = if entry.offset > LARGE_OFFSET_THRESHOLD {
+            for entry in entries_sorted_by_oid {
+                let pack_offset = entry.pack_offset();
+                let offset: u32 = if pack_offset > LARGE_OFFSET_THRESHOLD {
                     assert!(
                         offsets64.len() < LARGE_OFFSET_THRESHOLD as usize,
                         "Encoding breakdown - way too many 64bit offsets"
                     );
-                    offsets64.push(entry.offset);
+                    offsets64.push(pack_offset);
                     ((offsets64.len() - 1) as u32) | HIGH_BIT
                 } else {
-                    entry.offset as u32
+                    pack_offset as u32
                 };
                 out.write_all(&offset.to_be_bytes())?;
             }
@@ -150,6 +159,34 @@ mod function {
 
         Ok(index_hash)
     }
+
+    /// Write a reverse index for `entries_sorted_by_oid`, which maps positions in pack order to positions in the index,
+    /// to `out` and return its checksum.
+    pub(crate) fn write_reverse_to<T: Entry>(
+        out: &mut dyn io::Write,
+        entries_sorted_by_oid: &[T],
+        pack_hash: &gix_hash::ObjectId,
+        object_hash: gix_hash::Kind,
+    ) -> Result<gix_hash::ObjectId, gix_hash::io::Error> {
+        use io::Write;
+        let mut index_positions_in_pack_order: Vec<u32> = (0..entries_sorted_by_oid.len() as u32).collect();
+        index_positions_in_pack_order.sort_by_key(|idx| entries_sorted_by_oid[*idx as usize].pack_offset());
+
+        let mut out = std::io::BufWriter::with_capacity(8 * 4096, gix_hash::io::Write::new(out, object_hash));
+        out.write_all(REVERSE_SIGNATURE)?;
+        out.write_all(&1u32.to_be_bytes())?;
+        out.write_all(&(object_hash as u32).to_be_bytes())?;
+        for index_position in index_positions_in_pack_order {
+            out.write_all(&index_position.to_be_bytes())?;
+        }
+        out.write_all(pack_hash.as_slice())?;
+
+        let out = out.into_inner().map_err(io::Error::from)?;
+        let reverse_index_hash = out.hash.try_finalize()?;
+        out.inner.write_all(reverse_index_hash.as_slice())?;
+        out.inner.flush()?;
+        Ok(reverse_index_hash)
+    }
 }
 #[cfg(feature = "streaming-input")]
-pub(crate) use function::write_to;
+pub(crate) use function::{write_reverse_to, write_to};
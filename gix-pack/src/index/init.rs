@@ -1,8 +1,11 @@
 use std::{mem::size_of, path::Path};
 
-use crate::index::{self, Version, FAN_LEN, V2_SIGNATURE};
+use crate::{
+    backing,
+    index::{self, Version, FAN_LEN, V2_SIGNATURE},
+};
 
-/// Returned by [`index::File::at()`].
+/// Returned by [`index::File::at()`] and [`index::File::at_opts()`].
 #[derive(thiserror::Error, Debug)]
 #[allow(missing_docs)]
 pub enum Error {
@@ -26,11 +29,22 @@ impl index::File {
     /// The `object_hash` is a way to read (and write) the same file format with different hashes, as the hash kind
     /// isn't stored within the file format itself.
     pub fn at(path: impl AsRef<Path>, object_hash: gix_hash::Kind) -> Result<index::File, Error> {
-        Self::at_inner(path.as_ref(), object_hash)
+        Self::at_inner(path.as_ref(), object_hash, backing::Mode::default())
     }
 
-    fn at_inner(path: &Path, object_hash: gix_hash::Kind) -> Result<index::File, Error> {
-        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+    /// Like [`at()`](Self::at()), but use `mode` to control how the index data is accessed.
+    ///
+    /// Note that [windowed access](backing::Mode::Windowed) reads the whole index into memory.
+    pub fn at_opts(
+        path: impl AsRef<Path>,
+        object_hash: gix_hash::Kind,
+        mode: backing::Mode,
+    ) -> Result<index::File, Error> {
+        Self::at_inner(path.as_ref(), object_hash, mode)
+    }
+
+    fn at_inner(path: &Path, object_hash: gix_hash::Kind, mode: backing::Mode) -> Result<index::File, Error> {
+        let data = backing::Bytes::open(path, mode).map_err(|source| Error::Io {
             source,
             path: path.to_owned(),
         })?;
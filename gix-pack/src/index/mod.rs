@@ -76,8 +76,6 @@ macro_rules! izip {
     };
 }
 
-use memmap2::Mmap;
-
 /// The version of an index file
 #[derive(Default, PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -108,7 +106,7 @@ const FAN_LEN: usize = 256;
 
 /// A representation of a pack index file
 pub struct File {
-    data: Mmap,
+    data: crate::backing::Bytes,
     path: std::path::PathBuf,
     version: Version,
     num_objects: u32,
@@ -138,6 +136,8 @@ impl File {
 }
 
 const V2_SIGNATURE: &[u8] = b"\xfftOc";
+#[cfg(feature = "streaming-input")]
+const REVERSE_SIGNATURE: &[u8] = b"RIDX";
 ///
 pub mod init;
 
@@ -156,7 +156,13 @@ impl index::File {
         C: crate::cache::DecodeEntry,
         E: std::error::Error + Send + Sync + 'static,
     {
-        let pack_entry = pack.entry(index_entry.pack_offset)?;
+        let pack_entry = pack
+            .entry(index_entry.pack_offset)
+            .map_err(|source| Error::PackDecode {
+                source,
+                id: index_entry.oid,
+                offset: index_entry.pack_offset,
+            })?;
         let pack_entry_data_offset = pack_entry.data_offset;
         let entry_stats = pack
             .decode_entry(
@@ -185,7 +191,14 @@ impl index::File {
             object_kind,
             buf,
             index_entry,
-            || pack.entry_crc32(index_entry.pack_offset, entry_len),
+            || {
+                pack.entry_crc32(index_entry.pack_offset, entry_len)
+                    .map_err(|err| Error::PackDecode {
+                        source: err.into(),
+                        id: index_entry.oid,
+                        offset: index_entry.pack_offset,
+                    })
+            },
             progress,
             processor,
         )?;
@@ -199,7 +212,7 @@ fn process_entry<E>(
     object_kind: gix_object::Kind,
     decompressed: &[u8],
     index_entry: &index::Entry,
-    pack_entry_crc32: impl FnOnce() -> u32,
+    pack_entry_crc32: impl FnOnce() -> Result<u32, Error<E>>,
     progress: &dyn Progress,
     processor: &mut impl FnMut(gix_object::Kind, &[u8], &index::Entry, &dyn Progress) -> Result<(), E>,
 ) -> Result<(), Error<E>>
@@ -214,7 +227,7 @@ where
                 source,
             })?;
         if let Some(desired_crc32) = index_entry.crc32 {
-            let actual_crc32 = pack_entry_crc32();
+            let actual_crc32 = pack_entry_crc32()?;
             if actual_crc32 != desired_crc32 {
                 return Err(Error::Crc32Mismatch {
                     actual: actual_crc32,
@@ -139,10 +139,11 @@ impl index::File {
                                 // TODO: Fix this - we overwrite the header of 'data' which also changes the computed entry size,
                                 // causing index and pack to seemingly mismatch. This is surprising, and should be done differently.
                                 // debug_assert_eq!(&data.index_entry.pack_offset, &pack_entry.pack_offset());
-                                gix_features::hash::crc32(
-                                    pack.entry_slice(data.index_entry.pack_offset..entry_end)
+                                Ok(gix_features::hash::crc32(
+                                    &pack
+                                        .entry_slice(data.index_entry.pack_offset..entry_end)
                                         .expect("slice pointing into the pack (by now data is verified)"),
-                                )
+                                ))
                             },
                             progress,
                             &mut processor,
@@ -12,6 +12,20 @@ pub(crate) struct TreeEntry {
     pub crc32: u32,
 }
 
+impl crate::index::encode::Entry for crate::cache::delta::Item<TreeEntry> {
+    fn id(&self) -> &gix_hash::oid {
+        &self.data.id
+    }
+
+    fn crc32(&self) -> u32 {
+        self.data.crc32
+    }
+
+    fn pack_offset(&self) -> crate::data::Offset {
+        self.offset
+    }
+}
+
 /// Information gathered while executing [`write_data_iter_to_stream()`][crate::index::File::write_data_iter_to_stream]
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -96,7 +110,7 @@ impl crate::index::File {
     where
         F: FnOnce() -> io::Result<(F2, R)>,
         R: Send + Sync,
-        F2: for<'r> Fn(crate::data::EntryRange, &'r R) -> Option<&'r [u8]> + Send + Clone,
+        F2: for<'r> Fn(crate::data::EntryRange, &'r R) -> Option<std::borrow::Cow<'r, [u8]>> + Send + Clone,
     {
         if version != crate::index::Version::default() {
             return Err(Error::Unsupported(version));
@@ -230,7 +244,7 @@ impl crate::index::File {
         };
         let index_hash = crate::index::encode::write_to(
             out,
-            sorted_pack_offsets_by_oid,
+            &sorted_pack_offsets_by_oid,
             &pack_hash,
             version,
             &mut root_progress.add_child_with_id("writing index file".into(), ProgressId::IndexBytesWritten.into()),
@@ -14,6 +14,8 @@ pub mod checksum {
         Hasher(#[from] gix_hash::hasher::Error),
         #[error(transparent)]
         Verify(#[from] gix_hash::verify::Error),
+        #[error("Failed to read the data to hash")]
+        Io(#[source] std::io::Error),
     }
 }
 
@@ -35,7 +37,28 @@ pub fn checksum_on_disk_or_mmap(
     progress: &mut dyn Progress,
     should_interrupt: &AtomicBool,
 ) -> Result<gix_hash::ObjectId, checksum::Error> {
-    let data_len_without_trailer = data.len() - object_hash.len_in_bytes();
+    checksum_on_disk_or_data(
+        data_path,
+        data.len(),
+        Some(data),
+        expected,
+        object_hash,
+        progress,
+        should_interrupt,
+    )
+}
+
+/// Like [`checksum_on_disk_or_mmap()`], but only fall back to `data` of `data_len` bytes if it is available in memory.
+pub(crate) fn checksum_on_disk_or_data(
+    data_path: &Path,
+    data_len: usize,
+    data: Option<&[u8]>,
+    expected: gix_hash::ObjectId,
+    object_hash: gix_hash::Kind,
+    progress: &mut dyn Progress,
+    should_interrupt: &AtomicBool,
+) -> Result<gix_hash::ObjectId, checksum::Error> {
+    let data_len_without_trailer = data_len - object_hash.len_in_bytes();
     let actual = match gix_hash::bytes_of_file(
         data_path,
         data_len_without_trailer as u64,
@@ -47,7 +70,8 @@ pub fn checksum_on_disk_or_mmap(
         Err(gix_hash::io::Error::Io(err)) if err.kind() == std::io::ErrorKind::Interrupted => {
             return Err(checksum::Error::Interrupted);
         }
-        Err(gix_hash::io::Error::Io(_io_err)) => {
+        Err(gix_hash::io::Error::Io(io_err)) => {
+            let data = data.ok_or(checksum::Error::Io(io_err))?;
             let start = std::time::Instant::now();
             let mut hasher = gix_hash::hasher(object_hash);
             hasher.update(&data[..data_len_without_trailer]);
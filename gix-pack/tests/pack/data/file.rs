@@ -219,3 +219,134 @@ mod decompress_entry {
         buf
     }
 }
+
+mod backing {
+    use std::sync::atomic::AtomicBool;
+
+    use gix_features::progress;
+    use gix_object::bstr::ByteSlice;
+    use gix_pack::{
+        backing::{Budget, Mode},
+        cache,
+        data::decode::entry::ResolvedBase,
+    };
+
+    use crate::{fixture_path, pack::SMALL_PACK};
+
+    fn truncated_copy_of_small_pack(mode: Mode) -> crate::Result<(gix_pack::data::File, gix_testtools::tempfile::TempDir)> {
+        let dir = gix_testtools::tempfile::tempdir()?;
+        let path = dir.path().join("pack.pack");
+        std::fs::copy(fixture_path(SMALL_PACK), &path)?;
+        let pack = gix_pack::data::File::at_opts(&path, gix_hash::Kind::Sha1, mode)?;
+        std::fs::OpenOptions::new().write(true).open(&path)?.set_len(1000)?;
+        Ok((pack, dir))
+    }
+
+    fn decode_entry_at_offset(pack: &gix_pack::data::File, offset: u64) -> Result<Vec<u8>, gix_pack::data::decode::Error> {
+        #[allow(clippy::ptr_arg)]
+        fn resolve_with_panic(_oid: &gix_hash::oid, _out: &mut Vec<u8>) -> Option<ResolvedBase> {
+            panic!("should not want to resolve an id here")
+        }
+
+        let entry = pack.entry(offset)?;
+        let mut buf = Vec::new();
+        pack.decode_entry(
+            entry,
+            &mut buf,
+            &mut Default::default(),
+            &resolve_with_panic,
+            &mut cache::Never,
+        )?;
+        Ok(buf)
+    }
+
+    #[test]
+    fn windowed_access_reports_truncated_packs_as_error() -> crate::Result {
+        let (pack, _dir) = truncated_copy_of_small_pack(Mode::Windowed {
+            window_size: 64,
+            budget: Budget::new(128),
+        })?;
+        for offset in [1968, 3033, 3569] {
+            assert!(
+                matches!(
+                    decode_entry_at_offset(&pack, offset),
+                    Err(gix_pack::data::decode::Error::Io(err)) if err.kind() == std::io::ErrorKind::UnexpectedEof
+                ),
+                "entries beyond the end of the file can't be read anymore"
+            );
+        }
+        assert!(
+            pack.verify_checksum(&mut progress::Discard, &AtomicBool::new(false))
+                .is_err(),
+            "the checksum can't be computed without all data"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn windows_larger_than_the_limit_are_read_but_not_kept() -> crate::Result {
+        let expected = gix_pack::data::File::at(fixture_path(SMALL_PACK), gix_hash::Kind::Sha1)?;
+        let pack = gix_pack::data::File::at_opts(
+            fixture_path(SMALL_PACK),
+            gix_hash::Kind::Sha1,
+            Mode::Windowed {
+                window_size: 4096,
+                budget: Budget::new(64),
+            },
+        )?;
+        for offset in [1968, 3033, 3569] {
+            assert_eq!(
+                decode_entry_at_offset(&pack, offset)?,
+                decode_entry_at_offset(&expected, offset)?
+            );
+        }
+        assert!(pack
+            .verify_checksum(&mut progress::Discard, &AtomicBool::new(false))
+            .is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn the_budget_is_shared_by_all_packs() -> crate::Result {
+        let expected = gix_pack::data::File::at(fixture_path(SMALL_PACK), gix_hash::Kind::Sha1)?;
+        let budget = Budget::new(256);
+        let mode = Mode::Windowed {
+            window_size: 64,
+            budget: budget.clone(),
+        };
+        let packs = (0..4)
+            .map(|_| gix_pack::data::File::at_opts(fixture_path(SMALL_PACK), gix_hash::Kind::Sha1, mode.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        for pack in &packs {
+            for offset in [1968, 3033, 3569] {
+                assert_eq!(
+                    decode_entry_at_offset(pack, offset)?,
+                    decode_entry_at_offset(&expected, offset)?
+                );
+                assert!(
+                    budget.bytes_in_use() <= budget.limit(),
+                    "windows of all packs count towards the same limit"
+                );
+            }
+        }
+        assert_ne!(budget.bytes_in_use(), 0, "windows are kept");
+
+        drop(packs);
+        assert_eq!(budget.bytes_in_use(), 0, "the windows of dropped packs are released");
+        Ok(())
+    }
+
+    #[test]
+    fn in_memory_access_is_unaffected_by_truncation() -> crate::Result {
+        let (pack, _dir) = truncated_copy_of_small_pack(Mode::InMemory)?;
+        let buf = decode_entry_at_offset(&pack, 3569)?;
+        assert_eq!(
+            buf.as_bstr(),
+            crate::fixup(std::fs::read(fixture_path(
+                "objects/f139391424a8c623adadf2388caec73e5e90865b.txt"
+            ))?)
+            .as_bstr()
+        );
+        Ok(())
+    }
+}
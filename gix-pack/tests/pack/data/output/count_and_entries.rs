@@ -348,6 +348,7 @@ fn write_and_verify(
         .write(true)
         .create_new(true)
         .open(&pack_file_path)?;
+    let (mut index, mut reverse_index) = (Vec::new(), Vec::new());
     let (num_written_bytes, pack_hash) = {
         let num_entries = entries.len();
         let mut pack_writer = output::bytes::FromEntriesIter::new(
@@ -356,7 +357,8 @@ fn write_and_verify(
             num_entries as u32,
             pack::data::Version::V2,
             gix_hash::Kind::Sha1,
-        );
+        )
+        .with_index_entries();
         let mut n = pack_writer.next().expect("one entries bundle was written")?;
         n += pack_writer.next().expect("the trailer was written")?;
         assert!(
@@ -365,6 +367,14 @@ fn write_and_verify(
         );
         // verify we can still get the original parts back
         let hash = pack_writer.digest().expect("digest is available when iterator is done");
+        let outcome = pack_writer.write_index(
+            pack::index::Version::V2,
+            &mut index,
+            Some(&mut reverse_index),
+            &mut progress::Discard,
+        )?;
+        assert_eq!(outcome.data_hash, hash, "the index belongs to the written pack");
+        assert_eq!(outcome.num_objects as usize, num_entries);
         let _ = pack_writer.input;
         let _ = pack_writer.into_write();
         (n, hash)
@@ -387,19 +397,27 @@ fn write_and_verify(
 
     // Re-generate the index from the pack for validation.
     let object_hash = gix_hash::Kind::Sha1; // TODO: parameterize this
+    let outcome = pack::Bundle::write_to_directory(
+        &mut std::io::BufReader::new(std::fs::File::open(&pack_file_path)?),
+        Some(tmp_dir.path()),
+        &mut progress::Discard,
+        &should_interrupt,
+        Some(&db),
+        pack::bundle::write::Options::default(),
+    )?;
     let bundle = pack::Bundle::at(
-        pack::Bundle::write_to_directory(
-            &mut std::io::BufReader::new(std::fs::File::open(pack_file_path)?),
-            Some(tmp_dir.path()),
-            &mut progress::Discard,
-            &should_interrupt,
-            Some(&db),
-            pack::bundle::write::Options::default(),
-        )?
-        .data_path
-        .ok_or("pack data directory should be set")?,
+        outcome.data_path.as_ref().ok_or("pack data directory should be set")?,
         object_hash,
     )?;
+    let is_thin_pack = outcome.index.data_hash != pack_hash;
+    if !is_thin_pack {
+        assert_eq!(
+            index,
+            std::fs::read(outcome.index_path.as_ref().expect("set with data path"))?,
+            "the index written along with the pack is the same as the one created by reading the pack"
+        );
+        assert_reverse_index_is_in_pack_order(&reverse_index, &bundle.index, pack_hash);
+    }
     // TODO: figure out why these hashes change, also depending on the machine, even though they are indeed stable.
     // if let Some(thin_pack_checksum) = expected_thin_pack_hash {
     //     let actual_checksum = bundle.pack.verify_checksum(progress::Discard, &should_interrupt)?;
@@ -422,3 +440,33 @@ fn write_and_verify(
 
     Ok(())
 }
+
+fn assert_reverse_index_is_in_pack_order(
+    reverse_index: &[u8],
+    index: &pack::index::File,
+    pack_hash: gix_hash::ObjectId,
+) {
+    let hash_len = pack_hash.as_slice().len();
+    let (header, rest) = reverse_index.split_at(12);
+    assert_eq!(&header[..4], b"RIDX");
+    assert_eq!(header[4..8], 1u32.to_be_bytes(), "version");
+    assert_eq!(header[8..12], 1u32.to_be_bytes(), "SHA1");
+    let (positions, trailer) = rest.split_at(rest.len() - hash_len * 2);
+    assert_eq!(&trailer[..hash_len], pack_hash.as_slice());
+    let mut hasher = gix_hash::hasher(gix_hash::Kind::Sha1);
+    hasher.update(&reverse_index[..reverse_index.len() - hash_len]);
+    assert_eq!(
+        gix_hash::ObjectId::from_bytes_or_panic(&trailer[hash_len..]),
+        hasher.try_finalize().expect("no collision"),
+        "trailing checksum"
+    );
+    let pack_offsets: Vec<_> = positions
+        .chunks(4)
+        .map(|position| index.pack_offset_at_index(u32::from_be_bytes(position.try_into().expect("4 bytes"))))
+        .collect();
+    assert_eq!(pack_offsets.len(), index.num_objects() as usize);
+    assert!(
+        pack_offsets.windows(2).all(|w| w[0] < w[1]),
+        "index positions are sorted by pack offset"
+    );
+}
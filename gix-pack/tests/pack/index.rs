@@ -110,7 +110,7 @@ mod version {
 
     #[cfg(feature = "gix-features-parallel")]
     mod any {
-        use std::{fs, io, sync::atomic::AtomicBool};
+        use std::{borrow::Cow, fs, io, sync::atomic::AtomicBool};
 
         use gix_features::progress;
         use gix_odb::pack;
@@ -121,8 +121,8 @@ mod version {
             pack::{INDEX_V2, V2_PACKS_AND_INDICES},
         };
 
-        fn slice_map(entry: gix_pack::data::EntryRange, map: &memmap2::Mmap) -> Option<&[u8]> {
-            map.get(entry.start as usize..entry.end as usize)
+        fn slice_map(entry: gix_pack::data::EntryRange, map: &memmap2::Mmap) -> Option<Cow<'_, [u8]>> {
+            map.get(entry.start as usize..entry.end as usize).map(Cow::Borrowed)
         }
 
         #[test]
@@ -272,6 +272,17 @@ static ALGORITHMS: &[index::traverse::Algorithm] = &[
     index::traverse::Algorithm::DeltaTreeLookup,
 ];
 
+fn backings() -> [gix_pack::backing::Mode; 3] {
+    [
+        gix_pack::backing::Mode::MemoryMap,
+        gix_pack::backing::Mode::InMemory,
+        gix_pack::backing::Mode::Windowed {
+            window_size: 64,
+            budget: gix_pack::backing::Budget::new(256),
+        },
+    ]
+}
+
 static MODES: &[index::verify::Mode] = &[
     index::verify::Mode::HashCrc32,
     index::verify::Mode::HashCrc32Decode,
@@ -368,26 +379,30 @@ fn pack_lookup() -> Result<(), Box<dyn std::error::Error>> {
 
         assert_eq!(pack.version(), pack::data::Version::V2);
         assert_eq!(pack.num_objects(), idx.num_objects());
-        for algo in ALGORITHMS {
-            for mode in MODES {
-                assert_eq!(
-                    idx.verify_integrity(
-                        Some(gix_pack::index::verify::PackContext {
-                            data: &pack,
-                            options: gix_pack::index::verify::integrity::Options {
-                                verify_mode: *mode,
-                                traversal: *algo,
-                                make_pack_lookup_cache: || cache::Never,
-                                thread_limit: None
-                            }
-                        }),
-                        &mut progress::Discard,
-                        &AtomicBool::new(false)
-                    )
-                    .map(|o| (o.actual_index_checksum, o.pack_traverse_statistics))?,
-                    (idx.index_checksum(), Some(stats.to_owned())),
-                    "{algo:?} -> {mode:?}"
-                );
+        for backing in backings() {
+            let idx = index::File::at_opts(fixture_path(index_path), gix_hash::Kind::Sha1, backing.clone())?;
+            let pack = pack::data::File::at_opts(fixture_path(pack_path), gix_hash::Kind::Sha1, backing.clone())?;
+            for algo in ALGORITHMS {
+                for mode in MODES {
+                    assert_eq!(
+                        idx.verify_integrity(
+                            Some(gix_pack::index::verify::PackContext {
+                                data: &pack,
+                                options: gix_pack::index::verify::integrity::Options {
+                                    verify_mode: *mode,
+                                    traversal: *algo,
+                                    make_pack_lookup_cache: || cache::Never,
+                                    thread_limit: None
+                                }
+                            }),
+                            &mut progress::Discard,
+                            &AtomicBool::new(false)
+                        )
+                        .map(|o| (o.actual_index_checksum, o.pack_traverse_statistics))?,
+                        (idx.index_checksum(), Some(stats.to_owned())),
+                        "{backing:?}: {algo:?} -> {mode:?}"
+                    );
+                }
             }
         }
         let num_objects = stats
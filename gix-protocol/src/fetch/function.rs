@@ -51,6 +51,7 @@ pub async fn fetch<P, T, E>(
         shallow,
         tags,
         reject_shallow_remote,
+        filter,
     }: Options<'_>,
 ) -> Result<Option<Outcome>, Error>
 where
@@ -84,6 +85,13 @@ where
         }
         arguments.use_include_tag();
     }
+    if let Some(spec) = filter {
+        if arguments.can_use_filter() {
+            arguments.filter(spec);
+        } else {
+            gix_trace::warn!("filtering not recognized by server, ignoring");
+        }
+    }
     let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, shallow, &shallow_file)?;
 
     let negotiate_span = gix_trace::detail!(
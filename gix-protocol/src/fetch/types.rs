@@ -16,6 +16,10 @@ pub struct Options<'a> {
     /// If `true`, if we fetch from a remote that only offers shallow clones, the operation will fail with an error
     /// instead of writing the shallow boundary to the shallow file.
     pub reject_shallow_remote: bool,
+    /// If set, a filter specification like `blob:none` to ask the remote to omit objects, making this a partial fetch.
+    ///
+    /// It's ignored if the remote doesn't support filtering.
+    pub filter: Option<&'a str>,
 }
 
 /// For use in [`RefMap::new()`] and [`fetch`](crate::fetch()).
@@ -83,6 +83,70 @@ impl file::Store {
     }
 }
 
+///
+pub mod retain {
+    use std::{io::Write, path::PathBuf};
+
+    use gix_object::bstr::ByteSlice;
+
+    use crate::{
+        store_impl::{file, file::log::LineRef},
+        FullNameRef,
+    };
+
+    impl file::Store {
+        /// Rewrite the reflog of `name` to only contain the lines for which `keep(line)` returns `true`, and return the
+        /// amount of lines that were removed.
+        ///
+        /// The reflog is locked while it's rewritten, and it's left untouched if no line is removed or if it doesn't exist.
+        /// Lines that can't be parsed are retained.
+        pub fn reflog_retain(
+            &self,
+            name: &FullNameRef,
+            keep: &mut dyn FnMut(LineRef<'_>) -> bool,
+        ) -> Result<usize, Error> {
+            let path = self.reflog_path(name);
+            if !path.is_file() {
+                return Ok(0);
+            }
+            let mut lock =
+                gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+            let data = match std::fs::read(&path) {
+                Ok(data) => data,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+                Err(err) => return Err(Error::Io { source: err, path }),
+            };
+            let mut num_removed = 0;
+            let mut retained = Vec::with_capacity(data.len());
+            for line in data.lines_with_terminator() {
+                match LineRef::from_bytes(line.trim_end_with(|c| c == '\n' || c == '\r')) {
+                    Ok(parsed) if !keep(parsed) => num_removed += 1,
+                    _ => retained.extend_from_slice(line),
+                }
+            }
+            if num_removed == 0 {
+                return Ok(0);
+            }
+            lock.write_all(&retained)
+                .map_err(|err| Error::Io { source: err, path })?;
+            lock.commit()?;
+            Ok(num_removed)
+        }
+    }
+
+    /// The error returned by [`file::Store::reflog_retain()`].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        AcquireLock(#[from] gix_lock::acquire::Error),
+        #[error(transparent)]
+        CommitLock(#[from] gix_lock::commit::Error<gix_lock::File>),
+        #[error("Could not read or write reflog at {path:?}")]
+        Io { source: std::io::Error, path: PathBuf },
+    }
+}
+
 ///
 pub mod create_or_update {
     use std::{
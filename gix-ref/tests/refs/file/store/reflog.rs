@@ -67,3 +67,41 @@ mod iter_rev {
         Ok(())
     }
 }
+
+mod retain {
+    use gix_ref::file::log::LineRef;
+
+    #[test]
+    fn matching_lines_are_removed_and_others_are_kept_verbatim() -> crate::Result {
+        let dir = gix_testtools::scripted_fixture_writable_standalone("make_repo_for_reflog.sh")?;
+        let store = crate::file::Store::at(dir.path().join(".git"), Default::default());
+        let name = "refs/heads/main".try_into()?;
+        let before = std::fs::read(dir.path().join(".git/logs/refs/heads/main"))?;
+
+        let mut seen = 0;
+        let removed = store.reflog_retain(name, &mut |line: LineRef<'_>| {
+            seen += 1;
+            !line.message.starts_with(b"revert")
+        })?;
+        assert_eq!(seen, 5, "all lines are presented");
+        assert_eq!(removed, 2, "two reverts");
+
+        let mut buf = Vec::new();
+        let log = store.reflog_iter(name, &mut buf)?.expect("exists");
+        assert_eq!(log.filter_map(Result::ok).count(), 3);
+
+        let after = std::fs::read(dir.path().join(".git/logs/refs/heads/main"))?;
+        assert!(after.len() < before.len());
+        assert_eq!(
+            store.reflog_retain(name, &mut |_| true)?,
+            0,
+            "nothing is removed, nothing is written"
+        );
+        assert_eq!(
+            store.reflog_retain("refs/heads/does-not-exist".try_into()?, &mut |_| false)?,
+            0,
+            "non-existing reflogs are fine"
+        );
+        Ok(())
+    }
+}
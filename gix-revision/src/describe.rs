@@ -149,7 +149,7 @@ pub(crate) mod function {
 
     use super::{Error, Outcome};
     use crate::{
-        describe::{CommitTime, Flags, Options, MAX_CANDIDATES},
+        describe::{CommitTime, Flags, Options, COMMIT_TIME_MAX, MAX_CANDIDATES},
         Graph, PriorityQueue,
     };
 
@@ -200,7 +200,7 @@ pub(crate) mod function {
             };
         }
 
-        let mut queue = PriorityQueue::from_iter(Some((u32::MAX, commit.to_owned())));
+        let mut queue = PriorityQueue::from_iter(Some((COMMIT_TIME_MAX, commit.to_owned())));
         let mut candidates = Vec::new();
         let mut commits_seen = 0;
         let mut gave_up_on_commit = None;
@@ -283,7 +283,7 @@ pub(crate) mod function {
         });
 
         if let Some(commit_id) = gave_up_on_commit {
-            queue.insert(u32::MAX, commit_id);
+            queue.insert(COMMIT_TIME_MAX, commit_id);
             commits_seen -= 1;
         }
 
@@ -313,8 +313,11 @@ pub(crate) mod function {
         graph
             .insert_parents(
                 &commit,
-                &mut |parent_id, parent_commit_date| {
-                    queue.insert(parent_commit_date as u32, parent_id);
+                &mut |parent_id, parent_commit_date, parent_generation| {
+                    let corrected_commit_date = parent_generation
+                        .and_then(|generation| generation.corrected_commit_date(parent_commit_date))
+                        .unwrap_or(u64::MAX);
+                    queue.insert((corrected_commit_date, parent_commit_date), parent_id);
                     commit_flags
                 },
                 &mut |_parent_id, flags| *flags |= commit_flags,
@@ -361,5 +364,12 @@ pub(crate) mod function {
     }
 }
 
-/// The timestamp for the creation date of a commit in seconds since unix epoch.
-type CommitTime = u32;
+/// The corrected commit date of a commit, or the maximum value if it's unknown, followed by its creation date in seconds
+/// since unix epoch.
+///
+/// This is like ordering by creation date, but corrected commit dates keep commits with skewed dates in topological order
+/// and commits outside the commit-graph are younger than all commits inside of it.
+type CommitTime = (u64, gix_date::SecondsSinceUnixEpoch);
+
+/// The priority of the commit to describe, which is visited first.
+const COMMIT_TIME_MAX: CommitTime = (u64::MAX, gix_date::SecondsSinceUnixEpoch::MAX);
@@ -195,16 +195,20 @@ fn paint_down_to_common(
 // TODO(ST): Should this type be used for `describe` as well?
 #[derive(Debug, Clone, Copy)]
 struct GenThenTime {
-    /// Note that the special [`GENERATION_NUMBER_INFINITY`](gix_commitgraph::GENERATION_NUMBER_INFINITY) is used to indicate
-    /// that no commitgraph is available.
-    generation: gix_revwalk::graph::Generation,
+    /// The [effective generation](graph::Commit::effective_generation()), i.e. the corrected commit date if available.
+    ///
+    /// Note that the special [`GENERATION_NUMBER_V2_INFINITY`](gix_commitgraph::GENERATION_NUMBER_V2_INFINITY) is used
+    /// to indicate that no commitgraph is available.
+    generation: u64,
     time: gix_date::SecondsSinceUnixEpoch,
 }
 
 impl From<&graph::Commit<Flags>> for GenThenTime {
     fn from(commit: &graph::Commit<Flags>) -> Self {
         GenThenTime {
-            generation: commit.generation.unwrap_or(gix_commitgraph::GENERATION_NUMBER_INFINITY),
+            generation: commit
+                .effective_generation()
+                .unwrap_or(gix_commitgraph::GENERATION_NUMBER_V2_INFINITY),
             time: commit.commit_time,
         }
     }
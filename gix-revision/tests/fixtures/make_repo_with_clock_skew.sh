@@ -0,0 +1,46 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config commitGraph.generationVersion 2
+
+function commit_at() {
+  local message=${1:?first argument is the commit message}
+  local date=${2:?second argument is the commit date}
+  GIT_COMMITTER_DATE="$date" GIT_AUTHOR_DATE="$date" git commit -q --allow-empty -m "$message"
+}
+
+# The clock of the committer of s1 and s2 was behind, so they seem older than all other commits,
+# but corrected commit dates keep them in topological order.
+#
+#       merge
+#      /     \
+#   near     s2 (skewed)
+#     |       |
+#    n1      s1 (skewed)
+#     |       |
+#     |      far
+#      \     /
+#       base
+git checkout -q -b main
+commit_at base "@1000000000 +0000"
+git tag base
+commit_at n1 "@1000000100 +0000"
+git tag n1
+commit_at near "@1000000200 +0000"
+git tag near
+
+git checkout -q -b skewed base
+commit_at far "@1000000300 +0000"
+git tag far
+commit_at s1 "@900000000 +0000"
+git tag s1
+commit_at s2 "@900000100 +0000"
+git tag s2
+
+git checkout -q main
+GIT_COMMITTER_DATE="@1000000400 +0000" GIT_AUTHOR_DATE="@1000000400 +0000" git merge -q --no-ff -m merge skewed
+git tag merge
+
+git commit-graph write --no-progress --reachable
+git repack -adq
@@ -13,7 +13,7 @@ mod format;
 fn run_test(
     transform_odb: impl FnOnce(gix_odb::Handle) -> gix_odb::Handle,
     options: impl Fn(gix_hash::ObjectId) -> gix_revision::describe::Options<'static>,
-    run_assertions: impl Fn(Result<Option<Outcome<'static>>, Error>, gix_hash::ObjectId) -> crate::Result,
+    run_assertions: impl Fn(Result<Option<Outcome<'static>>, Error>, gix_hash::ObjectId, bool) -> crate::Result,
 ) -> crate::Result {
     let store = odb_at(".");
     let store = transform_odb(store);
@@ -26,6 +26,7 @@ fn run_test(
         run_assertions(
             gix_revision::describe(&commit_id, &mut graph, options(commit_id)),
             commit_id,
+            cache.is_some(),
         )?;
     }
     Ok(())
@@ -36,7 +37,7 @@ fn option_none_if_no_tag_found() -> crate::Result {
     run_test(
         std::convert::identity,
         |_| Default::default(),
-        |res, _id| {
+        |res, _id, _use_commitgraph| {
             assert!(res?.is_none(), "cannot find anything if there's no candidate");
             Ok(())
         },
@@ -51,7 +52,7 @@ fn fallback_if_configured_in_options_but_no_candidate_or_names() -> crate::Resul
             fallback_to_oid: true,
             ..Default::default()
         },
-        |res, _id| {
+        |res, _id, _use_commitgraph| {
             let res = res?.expect("fallback active");
             assert!(res.name.is_none(), "no name can be found");
             assert_eq!(res.depth, 0, "just a default, not relevant as there is no name");
@@ -74,7 +75,7 @@ fn fallback_if_configured_in_options_and_max_candidates_zero() -> crate::Result
             max_candidates: 0,
             ..Default::default()
         },
-        |res, _id| {
+        |res, _id, _use_commitgraph| {
             let res = res?.expect("fallback active");
             assert!(res.name.is_none(), "no name can be found");
             assert_eq!(res.depth, 0, "just a default, not relevant as there is no name");
@@ -103,11 +104,15 @@ fn not_enough_candidates() -> crate::Result {
             max_candidates: 1,
             ..Default::default()
         },
-        |res, id| {
+        |res, id, use_commitgraph| {
             let res = res?.expect("candidate found");
             assert_eq!(res.name, Some(name.clone()), "it finds the youngest/most-recent name");
             assert_eq!(res.id, id);
-            assert_eq!(res.commits_seen, 6, "it has to traverse commits");
+            assert_eq!(
+                res.commits_seen,
+                if use_commitgraph { 5 } else { 6 },
+                "it has to traverse commits, which are ordered topologically by their corrected commit date with a commit-graph"
+            );
             assert_eq!(
                 res.depth, 3,
                 "it calculates the final number of commits even though it aborted early"
@@ -127,7 +132,7 @@ fn typical_usecases() -> crate::Result {
             max_candidates: 0,
             ..Default::default()
         },
-        |res, id| {
+        |res, id, _use_commitgraph| {
             let res = res?.expect("candidate found");
             assert_eq!(
                 res.name,
@@ -156,7 +161,7 @@ fn typical_usecases() -> crate::Result {
             .collect(),
             ..Default::default()
         },
-        |res, id| {
+        |res, id, use_commitgraph| {
             let res = res?.expect("candidate found");
             assert_eq!(
                 res.name,
@@ -165,7 +170,11 @@ fn typical_usecases() -> crate::Result {
             );
             assert_eq!(res.id, id);
             assert_eq!(res.depth, 3);
-            assert_eq!(res.commits_seen, 6);
+            assert_eq!(
+                res.commits_seen,
+                if use_commitgraph { 5 } else { 6 },
+                "all commits have the same date, so only corrected commit dates can tell their order"
+            );
             Ok(())
         },
     )?;
@@ -185,7 +194,7 @@ fn typical_usecases() -> crate::Result {
             first_parent: true,
             ..Default::default()
         },
-        |res, id| {
+        |res, id, _use_commitgraph| {
             let res = res?.expect("candidate found");
             assert_eq!(res.name, Some(name.clone()));
             assert_eq!(res.id, id);
@@ -211,7 +220,7 @@ fn shallow_yields_no_result_if_provided_refs_are_in_truncated_part_of_history()
             first_parent: true,
             ..Default::default()
         },
-        |res, _id| {
+        |res, _id, _use_commitgraph| {
             let res = res?;
             assert!(
                 res.is_none(),
@@ -234,7 +243,7 @@ fn shallow_yields_result_if_refs_are_available() -> crate::Result {
             first_parent: true,
             ..Default::default()
         },
-        |res, id| {
+        |res, id, _use_commitgraph| {
             let res = res?.expect("found candidate");
             assert_eq!(res.name, Some(name.clone()));
             assert_eq!(res.id, id);
@@ -246,6 +255,35 @@ fn shallow_yields_result_if_refs_are_available() -> crate::Result {
     )
 }
 
+#[test]
+fn clock_skew_does_not_affect_the_depth() -> crate::Result {
+    let (odb, cache, tag) = crate::clock_skew_repo()?;
+    let near = Cow::Borrowed(b"near".as_bstr());
+    let far = Cow::Borrowed(b"far".as_bstr());
+    for cache in [None, Some(&cache)] {
+        let mut graph = gix_revision::Graph::new(&odb, cache);
+        let res = gix_revision::describe(
+            &tag("merge"),
+            &mut graph,
+            describe::Options {
+                name_by_oid: [(tag("near"), near.clone()), (tag("far"), far.clone())]
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            },
+        )?
+        .expect("candidate found");
+        assert_eq!(
+            res.name,
+            Some(near.clone()),
+            "it's closer than the tag below the skewed commits"
+        );
+        assert_eq!(res.depth, 4, "merge, far, s1 and s2 aren't reachable from near");
+        assert_eq!(res.commits_seen, 7);
+    }
+    Ok(())
+}
+
 fn odb_at(name: &str) -> gix_odb::Handle {
     gix_odb::at(fixture_path().join(name).join(".git/objects")).unwrap()
 }
@@ -9,3 +9,22 @@ pub use gix_testtools::Result;
 fn hex_to_id(hex: &str) -> gix_hash::ObjectId {
     gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("40 bytes hex")
 }
+
+/// Return the objects and commit-graph of a repository whose commits have skewed dates, along with a function
+/// to obtain the commit a tag points to.
+#[cfg(any(feature = "describe", feature = "merge_base"))]
+fn clock_skew_repo() -> Result<(
+    gix_odb::Handle,
+    gix_commitgraph::Graph,
+    impl Fn(&str) -> gix_hash::ObjectId,
+)> {
+    let root = gix_testtools::scripted_fixture_read_only("make_repo_with_clock_skew.sh")?;
+    let odb = gix_odb::at(root.join(".git/objects"))?;
+    let cache = gix_commitgraph::Graph::from_info_dir(&odb.store_ref().path().join("info"))?;
+    let tag = move |name: &str| {
+        use gix_object::bstr::ByteSlice;
+        let hex = std::fs::read(root.join(".git/refs/tags").join(name)).expect("loose tag exists");
+        gix_hash::ObjectId::from_hex(hex.trim()).expect("valid hex")
+    };
+    Ok((odb, cache, tag))
+}
@@ -38,6 +38,40 @@ fn validate() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn clock_skew_with_corrected_commit_dates() -> crate::Result {
+    let (odb, cache, tag) = crate::clock_skew_repo()?;
+    let mut graph = gix_revision::Graph::new(&odb, Some(&cache));
+    let generation = |graph: &mut gix_revision::Graph<'_, '_, ()>, name: &str| {
+        graph
+            .lookup(&tag(name))
+            .expect("present")
+            .to_owned(|| ())
+            .expect("valid")
+            .effective_generation()
+            .expect("in commit-graph")
+    };
+    assert_eq!(
+        generation(&mut graph, "s1"),
+        1_000_000_301,
+        "corrected commit dates are used, so the skewed commit is still younger than its parent"
+    );
+    assert_eq!(generation(&mut graph, "s2"), 1_000_000_302);
+
+    for cache in [None, Some(&cache)] {
+        let mut graph = gix_revision::Graph::new(&odb, cache);
+        for (first, other) in [("near", "s2"), ("s2", "near"), ("merge", "s1")] {
+            let expected = if first == "merge" { "s1" } else { "base" };
+            assert_eq!(
+                merge_base(tag(first), &[tag(other)], &mut graph)?,
+                Some(vec![tag(expected)]),
+                "{first} {other}"
+            );
+        }
+    }
+    Ok(())
+}
+
 mod octopus {
     use crate::hex_to_id;
 
@@ -25,11 +25,25 @@ impl<'graph, 'cache> LazyCommit<'graph, 'cache> {
         })
     }
 
+    /// Returns the corrected commit date of the commit if it is backed by a commit graph that stores them.
+    ///
+    /// Unlike the [committer timestamp](Self::committer_timestamp()), it's always larger than the one of all parents,
+    /// even if the clocks of committers were skewed.
+    pub fn corrected_commit_date(&self) -> Option<SecondsSinceUnixEpoch> {
+        match &self.backing {
+            Either::Left(_) => None,
+            Either::Right((cache, pos)) => cache
+                .commit_at(*pos)
+                .corrected_commit_date()
+                .map(|date| date.try_into().unwrap_or(SecondsSinceUnixEpoch::MAX)),
+        }
+    }
+
     /// Returns the generation of the commit if it is backed by a commit graph.
     pub fn generation(&self) -> Option<Generation> {
         match &self.backing {
             Either::Left(_) => None,
-            Either::Right((cache, pos)) => cache.commit_at(*pos).generation().into(),
+            Either::Right((cache, pos)) => Some(generation(&cache.commit_at(*pos))),
         }
     }
 
@@ -42,7 +56,7 @@ impl<'graph, 'cache> LazyCommit<'graph, 'cache> {
             Either::Right((cache, pos)) => {
                 let commit = cache.commit_at(*pos);
                 (
-                    commit.generation().into(),
+                    Some(generation(&commit)),
                     // a cast as we cannot represent the error and trying seems overkill
                     cache.commit_at(*pos).committer_timestamp() as SecondsSinceUnixEpoch,
                 )
@@ -97,7 +111,7 @@ impl<'graph, 'cache> LazyCommit<'graph, 'cache> {
                             actual: commit.committer_timestamp(),
                         }
                     })?,
-                    generation: Some(commit.generation()),
+                    generation: Some(generation(&commit)),
                     data,
                 }
             }
@@ -105,6 +119,13 @@ impl<'graph, 'cache> LazyCommit<'graph, 'cache> {
     }
 }
 
+fn generation(commit: &gix_commitgraph::file::Commit<'_>) -> Generation {
+    match commit.corrected_commit_date_offset() {
+        Some(offset) => Generation::CorrectedCommitDateOffset(offset),
+        None => Generation::Level(commit.generation()),
+    }
+}
+
 /// An iterator over the parents of a commit.
 pub struct Parents<'graph, 'cache> {
     backing: Either<
@@ -47,11 +47,37 @@ mod errors {
 pub use errors::{get_or_insert_default, insert_parents};
 use gix_date::SecondsSinceUnixEpoch;
 
-/// The generation away from the HEAD of graph, useful to limit algorithms by topological depth as well.
+/// The generation number of a commit, useful to limit algorithms by topological depth as well.
 ///
-/// 0 would mean the starting point of the hierarchy, and 1 their parents.
-/// This number is only available natively if there is a commit-graph.
-pub type Generation = u32;
+/// It's only available natively if there is a commit-graph, and all of its commits use the same kind of generation.
+/// It's kept compact as it's stored with each [`Commit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Generation {
+    /// The topological level of the commit, with 1 for commits without parents and one more than the largest level
+    /// of all parents otherwise.
+    Level(u32),
+    /// The offset of the corrected commit date to the commit time, which together form the corrected commit date
+    /// that is larger than the one of all parents even if the clocks of committers were skewed.
+    CorrectedCommitDateOffset(u32),
+}
+
+impl Generation {
+    /// Return the generation number for comparison with the ones of other commits of the same commit-graph,
+    /// using `commit_time` to compute the corrected commit date if needed.
+    pub fn to_comparable(self, commit_time: SecondsSinceUnixEpoch) -> u64 {
+        match self {
+            Generation::Level(level) => level.into(),
+            Generation::CorrectedCommitDateOffset(offset) => {
+                u64::try_from(commit_time).unwrap_or_default() + u64::from(offset)
+            }
+        }
+    }
+
+    /// Return the corrected commit date using `commit_time`, or `None` if this is a topological level.
+    pub fn corrected_commit_date(self, commit_time: SecondsSinceUnixEpoch) -> Option<u64> {
+        matches!(self, Generation::CorrectedCommitDateOffset(_)).then(|| self.to_comparable(commit_time))
+    }
+}
 
 impl<T: std::fmt::Debug> std::fmt::Debug for Graph<'_, '_, T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -126,13 +152,13 @@ impl<'cache, T> Graph<'_, 'cache, T> {
     }
 
     /// Insert the parents of commit named `id` to the graph and associate new parents with data
-    /// by calling `new_parent_data(parent_id, committer_timestamp)`, or update existing parents
+    /// by calling `new_parent_data(parent_id, committer_timestamp, generation)`, or update existing parents
     /// data with `update_existing(parent_id, &mut existing_data)`.
     /// If `first_parent` is `true`, only the first parent of commits will be looked at.
     pub fn insert_parents(
         &mut self,
         id: &gix_hash::oid,
-        new_parent_data: &mut dyn FnMut(gix_hash::ObjectId, SecondsSinceUnixEpoch) -> T,
+        new_parent_data: &mut dyn FnMut(gix_hash::ObjectId, SecondsSinceUnixEpoch, Option<Generation>) -> T,
         update_existing: &mut dyn FnMut(gix_hash::ObjectId, &mut T),
         first_parent: bool,
     ) -> Result<(), insert_parents::Error> {
@@ -148,7 +174,7 @@ impl<'cache, T> Graph<'_, 'cache, T> {
                     };
 
                     let parent_commit_date = parent.committer_timestamp().unwrap_or_default();
-                    entry.insert(new_parent_data(parent_id, parent_commit_date));
+                    entry.insert(new_parent_data(parent_id, parent_commit_date, parent.generation()));
                 }
                 gix_hashtable::hash_map::Entry::Occupied(mut entry) => {
                     update_existing(parent_id, entry.get_mut());
@@ -405,11 +431,20 @@ pub struct Commit<T> {
     /// The time at which the commit was created.
     pub commit_time: SecondsSinceUnixEpoch,
     /// The generation of the commit, if available.
-    pub generation: Option<u32>,
+    pub generation: Option<Generation>,
     /// Any kind of data to associate with this commit.
     pub data: T,
 }
 
+impl<T> Commit<T> {
+    /// Return the generation number of this commit for comparison with the ones of other commits of the same commit-graph,
+    /// or `None` if it's not in a commit-graph.
+    pub fn effective_generation(&self) -> Option<u64> {
+        self.generation
+            .map(|generation| generation.to_comparable(self.commit_time))
+    }
+}
+
 impl<T> std::fmt::Debug for Commit<T>
 where
     T: std::fmt::Debug,
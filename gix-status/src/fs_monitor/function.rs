@@ -0,0 +1,68 @@
+use bstr::{BString, ByteVec};
+use gix_index::{
+    entry::Flags,
+    extension::{fs_monitor::Token, FsMonitor},
+};
+
+use crate::fs_monitor::{Changes, Outcome, Query};
+
+/// Query `monitor` for all changes since the last query as recorded in the [filesystem monitor extension](gix_index::State::fs_monitor())
+/// of `index`, and mark all entries which aren't known to have changed with the
+/// [`FSMONITOR_VALID`](gix_index::entry::Flags::FSMONITOR_VALID) flag so they are skipped by
+/// [`index_as_worktree()`](crate::index_as_worktree()).
+///
+/// Entries that were dirty before remain dirty, along with all entries at or below the paths reported by `monitor`.
+/// Finally, the filesystem monitor extension of `index` is updated with the new token and the dirty entries.
+/// If there was no extension yet, all entries are considered dirty.
+///
+/// Note that dirty entries stay dirty until they are marked as valid after they were found unchanged, see
+/// [`FsMonitor::retain_dirty_entries()`].
+pub fn refresh<Q: Query>(index: &mut gix_index::State, monitor: &mut Q) -> Result<Outcome, Q::Error> {
+    let previous = index.fs_monitor().and_then(|fs_monitor| match fs_monitor.token() {
+        Token::V2 { token } => Some((token.clone(), fs_monitor.dirty_entries().to_owned())),
+        Token::V1 { .. } => None,
+    });
+    let Changes { token, paths } = monitor.query_changes(previous.as_ref().map(|(token, _)| token.as_ref()))?;
+
+    let num_entries = index.entries().len();
+    let mut is_dirty = vec![false; num_entries];
+    let mut out = Outcome {
+        is_trivial: paths.is_none(),
+        reported_paths: paths.as_ref().map_or(0, Vec::len),
+        dirty_entries: 0,
+    };
+    match (previous, paths) {
+        (Some((_, dirty_entries)), Some(paths)) => {
+            for idx in dirty_entries.into_iter().filter(|idx| *idx < num_entries) {
+                is_dirty[idx] = true;
+            }
+            let mut prefix = BString::default();
+            for path in paths {
+                let path = path.strip_suffix(b"/").unwrap_or(&path);
+                if let Some(range) = index.entry_range(path.into()) {
+                    is_dirty[range].fill(true);
+                }
+                // The path might also be a directory, in which case everything in it is considered changed.
+                prefix.clear();
+                prefix.push_str(path);
+                prefix.push(b'/');
+                if let Some(range) = index.prefixed_entries_range(prefix.as_ref()) {
+                    is_dirty[range].fill(true);
+                }
+            }
+        }
+        _ => is_dirty.fill(true),
+    }
+
+    for (entry, is_dirty) in index.entries_mut().iter_mut().zip(is_dirty.iter()) {
+        entry.flags.set(Flags::FSMONITOR_VALID, !is_dirty);
+    }
+    let dirty_entries: Vec<_> = is_dirty
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, is_dirty)| is_dirty.then_some(idx))
+        .collect();
+    out.dirty_entries = dirty_entries.len();
+    index.set_fs_monitor(Some(FsMonitor::new(Token::V2 { token }, dirty_entries)));
+    Ok(out)
+}
@@ -0,0 +1,98 @@
+use std::{
+    ffi::OsString,
+    path::PathBuf,
+    process::{ExitStatus, Stdio},
+};
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::fs_monitor::{Changes, Query};
+
+/// The error returned by [`Hook::query_changes()`](Query::query_changes()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not run the filesystem monitor hook {program:?}")]
+    Spawn { program: OsString, source: std::io::Error },
+    #[error("The filesystem monitor hook failed with {status}: {stderr}")]
+    Failed { status: ExitStatus, stderr: BString },
+    #[error("The filesystem monitor hook didn't return a token")]
+    MissingToken,
+}
+
+/// A filesystem monitor that is a program which speaks the second version of the hook protocol, as configured in `core.fsmonitor`.
+///
+/// It's invoked as `<program> 2 <token>` and outputs the new token followed by all changed paths, each terminated by a null byte.
+/// A changed path of `/` indicates that all paths should be considered changed.
+/// An example of such a hook is `fsmonitor-watchman.sample`, which uses [watchman](https://facebook.github.io/watchman/).
+///
+/// ### Deviation
+///
+/// The first version of the hook protocol isn't supported, and `core.fsmonitorHookVersion` is ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hook {
+    /// The program to run, which may also be a shell script.
+    pub program: OsString,
+    /// The directory to run the program in, which should be the root of the worktree.
+    pub worktree: PathBuf,
+}
+
+impl Query for Hook {
+    type Error = Error;
+
+    fn query_changes(&mut self, token: Option<&BStr>) -> Result<Changes, Self::Error> {
+        // Like `git`, the initial token is the current time in nanoseconds since the UNIX epoch.
+        let token = match token {
+            Some(token) => gix_path::from_bstr(token).into_owned().into_os_string(),
+            None => std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+                .to_string()
+                .into(),
+        };
+        let mut cmd: std::process::Command = gix_command::prepare(self.program.clone())
+            .command_may_be_shell_script()
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .arg("2")
+            .arg(token)
+            .into();
+        cmd.current_dir(&self.worktree);
+        gix_features::trace::debug!(cmd = ?cmd, "Querying filesystem monitor hook");
+        let output = cmd.output().map_err(|err| Error::Spawn {
+            program: self.program.clone(),
+            source: err,
+        })?;
+        if !output.status.success() {
+            return Err(Error::Failed {
+                status: output.status,
+                stderr: output.stderr.trim().into(),
+            });
+        }
+        parse_output(&output.stdout)
+    }
+}
+
+fn parse_output(stdout: &[u8]) -> Result<Changes, Error> {
+    let mut parts = stdout.split_str(b"\0");
+    let token = parts
+        .next()
+        .filter(|token| !token.is_empty())
+        .ok_or(Error::MissingToken)?;
+    let mut paths = Vec::new();
+    for path in parts.filter(|path| !path.is_empty()) {
+        if path == b"/" {
+            return Ok(Changes {
+                token: token.into(),
+                paths: None,
+            });
+        }
+        paths.push(path.into());
+    }
+    Ok(Changes {
+        token: token.into(),
+        paths: Some(paths),
+    })
+}
@@ -0,0 +1,50 @@
+//! Learn which index entries may have changed from a filesystem monitor, to avoid checking all of them for modifications.
+//!
+//! A filesystem monitor keeps track of changes to the worktree and hands out tokens which identify the point in time
+//! of a query. The next query with this token yields all paths that changed since then, which means that all other
+//! index entries that were unchanged before can be skipped by [`index_as_worktree()`](crate::index_as_worktree()).
+//! The token is persisted in the [filesystem monitor extension](gix_index::extension::FsMonitor) of the index.
+use bstr::{BStr, BString};
+
+mod function;
+pub use function::refresh;
+
+///
+pub mod hook;
+pub use hook::Hook;
+
+/// The response of a filesystem monitor to a [query](Query::query_changes()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Changes {
+    /// The token to pass with the next query, identifying the point in time at which this query was answered.
+    pub token: BString,
+    /// The worktree-relative paths of files or directories that changed since the token of the query,
+    /// or `None` if all paths have to be assumed changed.
+    ///
+    /// The latter is also called a *trivial response*, which happens if the filesystem monitor couldn't keep track of
+    /// changes, or if it doesn't know the token.
+    pub paths: Option<Vec<BString>>,
+}
+
+/// A filesystem monitor that can be asked for changes in the worktree since a previous query.
+pub trait Query {
+    /// The error returned if the query failed.
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Return all paths that changed since the query that returned `token`, along with a new token to identify this query.
+    ///
+    /// If `token` is `None`, there was no previous query and the returned paths don't matter as all entries will be
+    /// considered changed.
+    fn query_changes(&mut self, token: Option<&BStr>) -> Result<Changes, Self::Error>;
+}
+
+/// The outcome of [`refresh()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Outcome {
+    /// If `true`, the filesystem monitor didn't provide changed paths, and all entries are considered changed.
+    pub is_trivial: bool,
+    /// The amount of changed paths reported by the filesystem monitor.
+    pub reported_paths: usize,
+    /// The amount of index entries that are considered changed, and which have to be checked for modifications.
+    pub dirty_entries: usize,
+}
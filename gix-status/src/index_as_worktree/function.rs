@@ -75,16 +75,20 @@ where
     // (modified at or after the last index update) during the index update we then set those
     // entries size to 0 (see below) to ensure they keep showing up as racy and reset the timestamp.
     let timestamp = index.timestamp();
-    let (chunk_size, thread_limit, _) = gix_features::parallel::optimize_chunk_size_and_thread_limit(
-        500, // just like git
-        index.entries().len().into(),
-        options.thread_limit,
-        None,
-    );
-
     let range = index
         .prefixed_entries_range(pathspec.common_prefix())
         .unwrap_or(0..index.entries().len());
+    let num_threads = gix_features::parallel::num_threads_for_cost(
+        range.len(),
+        gix_index::refresh::THREAD_COST,
+        options.thread_limit,
+    );
+    let (chunk_size, thread_limit, _) = gix_features::parallel::optimize_chunk_size_and_thread_limit(
+        gix_index::refresh::THREAD_COST,
+        range.len().into(),
+        Some(num_threads),
+        None,
+    );
 
     let (entries, path_backing) = (index.entries(), index.path_backing());
     let mut num_entries = entries.len();
@@ -104,6 +108,16 @@ where
     progress.init(entries.len().into(), gix_features::progress::count("files"));
     let count = progress.counter();
 
+    // Submodules are costly to check as each one is a status of its own, so they are checked separately to be able
+    // to spread them across all threads instead of processing all of them that happen to be in one chunk one after another.
+    let submodule_entries: Vec<_> = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, entry)| {
+            (entry.mode.is_submodule() && entry.stage_raw() == 0).then_some(idx + entry_index_offset)
+        })
+        .collect();
+
     let new_state = {
         let options = &options;
         let (skipped_by_pathspec, skipped_by_entry_flags) = (&skipped_by_pathspec, &skipped_by_entry_flags);
@@ -140,7 +154,7 @@ where
         }
     };
     in_parallel_if(
-        || true, // TODO: heuristic: when is parallelization not worth it? Git says 500 items per thread, but to 20 threads, we can be more fine-grained though.
+        || num_threads > 1,
         gix_features::interrupt::Iter::new(
             OffsetIter {
                 inner: entries.chunks(chunk_size),
@@ -149,7 +163,7 @@ where
             should_interrupt,
         ),
         thread_limit,
-        new_state,
+        new_state.clone(),
         |(entry_offset, chunk_entries), (state, blobdiff, submdule, objects, pathspec)| {
             let all_entries = index.entries();
             let mut out = Vec::new();
@@ -173,6 +187,11 @@ where
                         continue;
                     }
                 }
+                if entry.mode.is_submodule() && entry.stage_raw() == 0 {
+                    // checked separately
+                    idx += 1;
+                    continue;
+                }
                 let res = state.process(
                     all_entries,
                     entry,
@@ -192,11 +211,41 @@ where
             out
         },
         ReduceChange {
-            collector,
+            collector: &mut *collector,
             entries: index.entries(),
         },
     )?;
 
+    if !submodule_entries.is_empty() {
+        let num_submodules = submodule_entries.len();
+        in_parallel_if(
+            || num_submodules > 1,
+            gix_features::interrupt::Iter::new(submodule_entries.into_iter(), should_interrupt),
+            options.thread_limit,
+            new_state,
+            |entry_index, (state, blobdiff, submodule, objects, pathspec)| {
+                let all_entries = index.entries();
+                let mut unused_outer_index = 0;
+                let res = state.process(
+                    all_entries,
+                    &all_entries[entry_index],
+                    entry_index,
+                    pathspec,
+                    blobdiff,
+                    submodule,
+                    objects,
+                    &mut unused_outer_index,
+                );
+                count.fetch_add(1, Ordering::Relaxed);
+                res.into_iter().collect()
+            },
+            ReduceChange {
+                collector,
+                entries: index.entries(),
+            },
+        )?;
+    }
+
     Ok(Outcome {
         entries_to_process: num_entries,
         entries_processed: count.load(Ordering::Relaxed),
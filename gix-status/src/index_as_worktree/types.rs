@@ -29,6 +29,9 @@ pub struct Options {
     /// If set, don't use more than this amount of threads.
     /// Otherwise, usually use as many threads as there are logical cores.
     /// A value of 0 is interpreted as no-limit
+    ///
+    /// Note that a thread is only used for each [`THREAD_COST`](gix_index::refresh::THREAD_COST) entries to check,
+    /// and that `Some(1)` should be used if `core.preloadIndex` is `false`.
     pub thread_limit: Option<usize>,
     /// Options that control how stat comparisons are made when checking if a file is fresh.
     pub stat: gix_index::entry::stat::Options,
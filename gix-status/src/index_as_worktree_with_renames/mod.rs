@@ -62,6 +62,8 @@ pub(super) mod function {
         E: std::error::Error + Send + Sync + 'static,
         Find: gix_object::Find + gix_object::FindHeader + Send + Clone,
     {
+        let mut untracked_cache = ctx.dirwalk.untracked_cache.take();
+        let options_stat = options.tracked_file_modifications.stat;
         gix_features::parallel::threads(|scope| -> Result<Outcome, Error> {
             let (tx, rx) = std::sync::mpsc::channel();
             let walk_outcome = options
@@ -89,8 +91,10 @@ pub(super) mod function {
                                 .any(|p| !p.attributes.is_empty())
                                 .then(|| ctx.resource_cache.attr_stack.clone());
                             let mut pathspec = ctx.pathspec.clone();
+                            let mut untracked_cache = untracked_cache.take();
+                            let stat = options_stat;
                             move || -> Result<_, Error> {
-                                gix_dir::walk(
+                                let (outcome, traversal_root) = gix_dir::walk(
                                     worktree,
                                     gix_dir::walk::Context {
                                         should_interrupt: Some(ctx.should_interrupt),
@@ -111,11 +115,15 @@ pub(super) mod function {
                                         excludes: excludes.as_mut(),
                                         objects: &objects,
                                         explicit_traversal_root: Some(worktree),
+                                        untracked_cache: untracked_cache
+                                            .as_mut()
+                                            .map(|cache| gix_dir::walk::UntrackedCacheContext { cache, stat }),
                                     },
                                     options,
                                     &mut collect,
                                 )
-                                .map_err(Error::DirWalk)
+                                .map_err(Error::DirWalk)?;
+                                Ok((outcome, traversal_root, untracked_cache))
                             }
                         })
                         .map_err(Error::SpawnThread)
@@ -331,10 +339,15 @@ pub(super) mod function {
                 .map(|handle| handle.join().expect("no panic"))
                 .transpose()?;
             let tracked_modifications_outcome = tracked_modifications_outcome.join().expect("no panic")?;
+            let dirwalk = walk_outcome.map(|(outcome, _traversal_root, cache)| {
+                untracked_cache = cache;
+                outcome
+            });
             Ok(Outcome {
-                dirwalk: walk_outcome.map(|t| t.0),
+                dirwalk,
                 tracked_file_modification: tracked_modifications_outcome,
                 rewrites: rewrite_outcome,
+                untracked_cache,
             })
         })
     }
@@ -46,6 +46,9 @@ pub struct Outcome {
     pub dirwalk: Option<gix_dir::walk::Outcome>,
     /// The result of the rewrite operation, if [rewrites were configured](Options::rewrites).
     pub rewrites: Option<gix_diff::rewrites::Outcome>,
+    /// The [untracked cache](DirwalkContext::untracked_cache) that was passed in, updated with the contents of
+    /// directories that had to be read during the directory walk.
+    pub untracked_cache: Option<gix_index::extension::UntrackedCache>,
 }
 
 /// Either an index entry for renames or another directory entry in case of copies.
@@ -360,6 +363,14 @@ pub struct DirwalkContext<'a> {
     ///
     /// [Read more in `gix-dir`](gix_dir::walk::Context::ignore_case_index_lookup).
     pub ignore_case_index_lookup: Option<&'a gix_index::AccelerateLookup<'a>>,
+    /// The untracked cache to use to avoid reading unchanged directories, typically obtained from the
+    /// [index](gix_index::State::untracked()). It will be updated and returned in [`Outcome::untracked_cache`].
+    ///
+    /// Its stat information is compared using the [stat options](crate::index_as_worktree::Options::stat) of the
+    /// tracked file modification check.
+    ///
+    /// [Read more in `gix-dir`](gix_dir::walk::Context::untracked_cache).
+    pub untracked_cache: Option<gix_index::extension::UntrackedCache>,
 }
 
 /// Observe the status of an entry by comparing an index entry to the worktree, along
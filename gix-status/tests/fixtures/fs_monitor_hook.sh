@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir dir
+echo -n "content" > dir/content
+echo -n "other content" > file
+
+git add -A
+git commit -q -m "Commit"
+
+# A fake filesystem monitor which reports changes depending on the token it receives.
+cat > .git/hooks/query-fsmonitor <<'HOOK'
+#!/bin/sh
+test "$1" = 2 || { echo "unsupported hook version $1" >&2; exit 1; }
+case "$2" in
+  token-1) printf 'token-2\0dir/content\0' ;;
+  token-2) printf 'token-3\0/\0' ;;
+  fail) echo "cannot handle token" >&2; exit 2 ;;
+  *) printf 'token-1\0' ;;
+esac
+HOOK
+chmod +x .git/hooks/query-fsmonitor
@@ -0,0 +1,179 @@
+use bstr::{BStr, BString};
+use gix_index::{
+    entry::Flags,
+    extension::{fs_monitor::Token, FsMonitor},
+};
+use gix_status::fs_monitor::{self, Changes, Query};
+
+use crate::fixture_path;
+
+/// A filesystem monitor that answers with `changes` and records the tokens it was queried with.
+pub(crate) struct Mock {
+    pub changes: Changes,
+    pub tokens: Vec<Option<BString>>,
+}
+
+impl Mock {
+    pub fn new(token: &str, paths: Option<&[&str]>) -> Self {
+        Mock {
+            changes: Changes {
+                token: token.into(),
+                paths: paths.map(|paths| paths.iter().map(|p| BString::from(*p)).collect()),
+            },
+            tokens: Vec::new(),
+        }
+    }
+}
+
+impl Query for Mock {
+    type Error = std::convert::Infallible;
+
+    fn query_changes(&mut self, token: Option<&BStr>) -> Result<Changes, Self::Error> {
+        self.tokens.push(token.map(ToOwned::to_owned));
+        Ok(self.changes.clone())
+    }
+}
+
+fn index() -> gix_index::File {
+    let git_dir = fixture_path("status_changed").join(".git");
+    gix_index::File::at(git_dir.join("index"), gix_hash::Kind::Sha1, false, Default::default()).unwrap()
+}
+
+fn valid_entries(index: &gix_index::State) -> Vec<&BStr> {
+    index
+        .entries()
+        .iter()
+        .filter(|e| e.flags.contains(Flags::FSMONITOR_VALID))
+        .map(|e| e.path(index))
+        .collect()
+}
+
+fn token(index: &gix_index::State) -> &Token {
+    index.fs_monitor().expect("always set after refresh").token()
+}
+
+#[test]
+fn without_extension_all_entries_are_dirty() {
+    let mut index = index();
+    assert!(index.fs_monitor().is_none());
+    let mut monitor = Mock::new("t1", Some(&[]));
+    let out = fs_monitor::refresh(&mut index, &mut monitor).unwrap();
+    assert_eq!(
+        out,
+        fs_monitor::Outcome {
+            is_trivial: false,
+            reported_paths: 0,
+            dirty_entries: 5,
+        }
+    );
+    assert_eq!(monitor.tokens, [None], "there is no token to pass yet");
+    assert_eq!(valid_entries(&index), Vec::<&BStr>::new());
+    assert_eq!(token(&index), &Token::V2 { token: "t1".into() });
+    assert_eq!(index.fs_monitor().unwrap().dirty_entries(), [0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn entries_at_and_below_reported_paths_become_dirty() {
+    let mut index = index();
+    index.set_fs_monitor(Some(FsMonitor::new(Token::V2 { token: "t1".into() }, vec![4])));
+    let mut monitor = Mock::new("t2", Some(&["dir/sub-dir/", "empty", "does-not-exist"]));
+    let out = fs_monitor::refresh(&mut index, &mut monitor).unwrap();
+    assert_eq!(
+        out,
+        fs_monitor::Outcome {
+            is_trivial: false,
+            reported_paths: 3,
+            dirty_entries: 3,
+        }
+    );
+    assert_eq!(monitor.tokens, [Some("t1".into())]);
+    assert_eq!(
+        valid_entries(&index),
+        ["dir/content", "dir/content2"],
+        "previously dirty entries stay dirty, all others are valid unless reported"
+    );
+    assert_eq!(token(&index), &Token::V2 { token: "t2".into() });
+    assert_eq!(index.fs_monitor().unwrap().dirty_entries(), [2, 3, 4]);
+
+    index
+        .fs_monitor_mut()
+        .expect("present")
+        .retain_dirty_entries(|idx| *idx == 3);
+    let mut monitor = Mock::new("t3", Some(&["dir"]));
+    let out = fs_monitor::refresh(&mut index, &mut monitor).unwrap();
+    assert_eq!(out.dirty_entries, 4, "entries can be marked valid after checking them");
+    assert_eq!(monitor.tokens, [Some("t2".into())]);
+    assert_eq!(valid_entries(&index), ["executable"]);
+}
+
+#[test]
+fn trivial_responses_and_unknown_tokens_make_all_entries_dirty() {
+    let mut index = index();
+    index.set_fs_monitor(Some(FsMonitor::new(Token::V2 { token: "t1".into() }, Vec::new())));
+    let out = fs_monitor::refresh(&mut index, &mut Mock::new("t2", None)).unwrap();
+    assert!(out.is_trivial);
+    assert_eq!(out.dirty_entries, 5);
+    assert_eq!(valid_entries(&index), Vec::<&BStr>::new());
+
+    index.set_fs_monitor(Some(FsMonitor::new(Token::V1 { nanos_since_1970: 42 }, Vec::new())));
+    let mut monitor = Mock::new("t3", Some(&[]));
+    let out = fs_monitor::refresh(&mut index, &mut monitor).unwrap();
+    assert_eq!(
+        monitor.tokens,
+        [None],
+        "tokens of the first version of the protocol aren't understood"
+    );
+    assert_eq!(out.dirty_entries, 5);
+}
+
+#[cfg(not(windows))]
+mod hook {
+    use gix_status::fs_monitor::{hook, Changes, Hook, Query};
+
+    use crate::fixture_path;
+
+    fn hook() -> Hook {
+        Hook {
+            program: ".git/hooks/query-fsmonitor".into(),
+            worktree: fixture_path("fs_monitor_hook"),
+        }
+    }
+
+    #[test]
+    fn tokens_and_changed_paths_are_parsed() -> crate::Result {
+        let mut hook = hook();
+        assert_eq!(
+            hook.query_changes(None)?,
+            Changes {
+                token: "token-1".into(),
+                paths: Some(Vec::new()),
+            },
+            "the initial token is a timestamp"
+        );
+        assert_eq!(
+            hook.query_changes(Some("token-1".into()))?,
+            Changes {
+                token: "token-2".into(),
+                paths: Some(vec!["dir/content".into()]),
+            }
+        );
+        assert_eq!(
+            hook.query_changes(Some("token-2".into()))?,
+            Changes {
+                token: "token-3".into(),
+                paths: None,
+            },
+            "'/' signals that everything changed"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn failures_are_reported() {
+        let err = hook().query_changes(Some("fail".into())).unwrap_err();
+        assert!(
+            matches!(&err, hook::Error::Failed { stderr, .. } if stderr == "cannot handle token"),
+            "{err:?}"
+        );
+    }
+}
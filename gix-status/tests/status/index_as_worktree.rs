@@ -671,6 +671,42 @@ fn refresh() {
     );
 }
 
+#[test]
+fn entries_marked_valid_by_fs_monitor_are_skipped() {
+    let actual_outcome = fixture_with_index(
+        "status_changed",
+        |index| {
+            index.set_fs_monitor(Some(gix_index::extension::FsMonitor::new(
+                gix_index::extension::fs_monitor::Token::V2 { token: "t1".into() },
+                Vec::new(),
+            )));
+            let mut monitor = crate::fs_monitor::Mock::new("t2", Some(&["dir/content2"]));
+            gix_status::fs_monitor::refresh(index, &mut monitor).expect("infallible");
+        },
+        &[(
+            BStr::new(b"dir/content2"),
+            1,
+            Change::Modification {
+                executable_bit_changed: false,
+                content_change: Some(()),
+                set_entry_stat_size_zero: false,
+            }
+            .into(),
+        )],
+    );
+    let expected_outcome = Outcome {
+        entries_to_process: 5,
+        entries_processed: 5,
+        entries_skipped_by_entry_flags: 4,
+        symlink_metadata_calls: 1,
+        ..Default::default()
+    };
+    assert_eq!(
+        actual_outcome, expected_outcome,
+        "only the path reported by the filesystem monitor is checked, other changes go unnoticed"
+    );
+}
+
 #[test]
 fn modified() {
     let expected_outcome = Outcome {
@@ -192,6 +192,7 @@ fn changed_and_untracked() {
             read_dir_calls: 3,
             returned_entries: 2,
             seen_entries: 8,
+            untracked_cache_hits: 0,
         }
     );
     assert_eq!(out.rewrites, None, "rewrites are still not configured");
@@ -277,6 +278,7 @@ fn fixture_filtered_detailed(
             git_dir_realpath: &git_dir_real,
             current_dir: &cwd,
             ignore_case_index_lookup: None,
+            untracked_cache: None,
         },
     };
     let options = Options {
@@ -1,5 +1,6 @@
 pub use gix_testtools::Result;
 
+mod fs_monitor;
 mod index_as_worktree;
 mod index_as_worktree_with_renames;
 
@@ -1,6 +1,6 @@
 use std::{borrow::Cow, collections::HashSet, path::Path};
 
-use bstr::BStr;
+use bstr::{BStr, BString};
 
 use crate::{
     config,
@@ -164,6 +164,26 @@ impl File {
         })
     }
 
+    /// Retrieve the `url` field of the submodule named `name` exactly as it is written in the `.gitmodules` file, ignoring
+    /// overrides from other configuration. It's an error if it doesn't exist or is empty.
+    ///
+    /// As opposed to [`url()`](Self::url()), the value isn't parsed as it may be [relative](crate::relative_url::is_relative())
+    /// to the url of the superproject.
+    pub fn url_from_modules_file(&self, name: &BStr) -> Result<BString, config::url::Error> {
+        let ours = self.config.meta();
+        let url = self
+            .config
+            .string_filter(
+                format!("submodule.{name}.url"),
+                &mut |meta: &gix_config::file::Metadata| std::ptr::eq(meta, ours),
+            )
+            .filter(|url| !url.is_empty())
+            .ok_or_else(|| config::url::Error::Missing {
+                submodule: name.to_owned(),
+            })?;
+        Ok(url.into_owned())
+    }
+
     /// Retrieve the `update` field of the submodule named `name`, if present.
     pub fn update(&self, name: &BStr) -> Result<Option<Update>, config::update::Error> {
         let value: Update = match self.config.string(format!("submodule.{name}.update")) {
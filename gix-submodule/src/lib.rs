@@ -22,6 +22,9 @@ pub mod config;
 ///
 pub mod is_active_platform;
 
+///
+pub mod relative_url;
+
 /// A platform to keep the state necessary to perform repeated active checks, created by [File::is_active_platform()].
 pub struct IsActivePlatform {
     pub(crate) search: Option<gix_pathspec::Search>,
@@ -0,0 +1,54 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// The error returned by [`resolve()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot strip one component off url '{base}' to resolve '{url}'")]
+    NoParent { base: BString, url: BString },
+}
+
+/// Return `true` if `url` is relative to the url of the superproject, i.e. starts with `./` or `../`.
+pub fn is_relative(url: &BStr) -> bool {
+    url.starts_with(b"./") || url.starts_with(b"../")
+}
+
+/// Resolve the [relative](is_relative()) `url` of a submodule against `base`, typically the url of the default remote of the
+/// superproject, or the path to its working tree if there is no remote.
+///
+/// Each leading `../` removes one path component from `base`, and `./` is ignored. If the last component is separated with
+/// a colon like in `host:repo`, the colon is used to join `url` back onto `base`, just like `git` does.
+/// `url` is returned unchanged if it isn't relative.
+pub fn resolve(base: &BStr, url: &BStr) -> Result<BString, Error> {
+    if !is_relative(url) {
+        return Ok(url.to_owned());
+    }
+    let mut remote = base.trim_end_with(|c| c == '/');
+    let mut separator = b'/';
+    let mut rest = url;
+    loop {
+        if let Some(tail) = rest.strip_prefix(b"./") {
+            rest = tail.as_bstr();
+        } else if let Some(tail) = rest.strip_prefix(b"../") {
+            rest = tail.as_bstr();
+            match remote.rfind_byteset(b"/:") {
+                Some(pos) => {
+                    separator = remote[pos];
+                    remote = &remote[..pos];
+                }
+                None => {
+                    return Err(Error::NoParent {
+                        base: base.to_owned(),
+                        url: url.to_owned(),
+                    })
+                }
+            }
+        } else {
+            break;
+        }
+    }
+    let mut out = BString::from(remote);
+    out.push_byte(separator);
+    out.push_str(rest);
+    Ok(out)
+}
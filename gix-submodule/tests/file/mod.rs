@@ -236,6 +236,30 @@ mod url {
 
         assert!(matches!(submodule_url("file://"), Error::Parse { .. }));
     }
+
+    #[test]
+    fn from_modules_file_ignores_overrides_and_keeps_relative_urls() -> crate::Result {
+        use std::str::FromStr;
+
+        let mut module = submodule("[submodule.a]\n url = ../relative");
+        assert_eq!(module.url_from_modules_file("a".into())?, "../relative");
+        module.append_submodule_overrides(&gix_config::File::from_str("[submodule.a]\n url = /absolute")?);
+        assert_eq!(
+            module.url("a".into())?.to_bstring(),
+            "/absolute",
+            "overrides are used by default"
+        );
+        assert_eq!(
+            module.url_from_modules_file("a".into())?,
+            "../relative",
+            "but not when reading the .gitmodules file"
+        );
+        assert!(matches!(
+            submodule("[submodule.a]\n url =").url_from_modules_file("a".into()),
+            Err(Error::Missing { .. })
+        ));
+        Ok(())
+    }
 }
 
 mod update {
@@ -0,0 +1,35 @@
+use gix_submodule::relative_url::{is_relative, resolve};
+
+#[test]
+fn only_urls_starting_with_dots_are_relative() {
+    for url in ["./a", "../a", "../../a"] {
+        assert!(is_relative(url.into()), "{url}");
+    }
+    for url in ["a", "/a", ".a", "..a", "https://host/a", "host:a"] {
+        assert!(!is_relative(url.into()), "{url}");
+    }
+}
+
+#[test]
+fn resolve_against_urls_and_paths() -> crate::Result {
+    for (base, url, expected) in [
+        ("https://host/org/super", "../sub", "https://host/org/sub"),
+        ("https://host/org/super/", "../sub", "https://host/org/sub"),
+        ("https://host/org/super.git", "./sub", "https://host/org/super.git/sub"),
+        ("https://host/org/super", "../../other/sub", "https://host/other/sub"),
+        ("https://host/org/super", "./../sub", "https://host/org/sub"),
+        ("/path/to/super", "../sub", "/path/to/sub"),
+        ("file:///path/to/super", "../sub", "file:///path/to/sub"),
+        ("host:super", "../sub", "host:sub"),
+        ("host:org/super", "../../sub", "host:sub"),
+        ("https://host/org/super", "absolute", "absolute"),
+    ] {
+        assert_eq!(resolve(base.into(), url.into())?, expected, "{base} + {url}");
+    }
+    Ok(())
+}
+
+#[test]
+fn resolve_fails_if_there_are_not_enough_components() {
+    assert!(resolve("super".into(), "../sub".into()).is_err());
+}
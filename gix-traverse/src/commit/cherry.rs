@@ -0,0 +1,47 @@
+//! Detect commits that are equivalent to commits on the other side of a range by their patch id,
+//! similar to `git cherry` and `git log --cherry-pick`.
+//!
+//! Computing patch ids requires diffing and is left to the caller, this module only performs the matching.
+use gix_hash::ObjectId;
+
+/// A commit along with its patch id and whether an equivalent commit exists on the other side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// The patch id of the commit, or `None` if it doesn't have one, as is the case for merge commits.
+    pub patch_id: Option<ObjectId>,
+    /// `true` if a commit with the same patch id exists on the other side.
+    ///
+    /// This is set by [`mark_equivalent()`].
+    pub equivalent: bool,
+}
+
+impl Entry {
+    /// Create a new instance for the commit `id` with `patch_id`, which isn't yet known to be equivalent.
+    pub fn new(id: ObjectId, patch_id: Option<ObjectId>) -> Self {
+        Entry {
+            id,
+            patch_id,
+            equivalent: false,
+        }
+    }
+}
+
+/// Set [`Entry::equivalent`] for all `left` and `right` entries whose patch id also appears on the other side.
+///
+/// Commits without patch id are never equivalent.
+///
+/// With `left` being the commits of `upstream..head` and `right` those of `head..upstream`, the `left` entries
+/// that aren't equivalent are the ones `git cherry upstream head` marks with `+`.
+/// Dropping all equivalent entries from both sides yields the commits `git log --cherry-pick upstream...head` shows.
+pub fn mark_equivalent(left: &mut [Entry], right: &mut [Entry]) {
+    let left_ids: gix_hashtable::HashSet = left.iter().filter_map(|entry| entry.patch_id).collect();
+    let right_ids: gix_hashtable::HashSet = right.iter().filter_map(|entry| entry.patch_id).collect();
+    for entry in left {
+        entry.equivalent = entry.patch_id.is_some_and(|id| right_ids.contains(&id));
+    }
+    for entry in right {
+        entry.equivalent = entry.patch_id.is_some_and(|id| left_ids.contains(&id));
+    }
+}
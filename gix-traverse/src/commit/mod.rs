@@ -33,12 +33,16 @@ pub struct Topo<Find, Predicate> {
     indegree_queue: PriorityQueue<topo::iter::GenAndCommitTime, ObjectId>,
     topo_queue: topo::iter::Queue,
     parents: Parents,
-    min_gen: u32,
+    min_gen: u64,
     buf: Vec<u8>,
 }
 
 pub mod topo;
 
+pub mod cherry;
+
+pub mod range;
+
 /// Specify how to handle commit parents during traversal.
 #[derive(Default, Copy, Clone)]
 pub enum Parents {
@@ -94,6 +98,20 @@ impl Either<'_, '_> {
             Self::CachedCommit(commit) => Ok(commit.committer_timestamp() as gix_date::SecondsSinceUnixEpoch),
         }
     }
+
+    /// Return `false` if the changed-path Bloom filter of a commit from a [`gix_commitgraph::Graph`] shows that the path
+    /// `keys` were computed for definitely didn't change compared to its first parent, or `true` if it may have changed.
+    ///
+    /// This allows to skip tree lookups and tree-diffs when limiting history to a path. Commits without
+    /// filter, or that aren't part of the commit-graph, may always have changed the path.
+    pub fn may_have_changed_path(self, keys: &gix_commitgraph::bloom::PathKeys) -> bool {
+        match self {
+            Self::CommitRefIter(_) => true,
+            Self::CachedCommit(commit) => commit
+                .bloom_filter()
+                .map_or(true, |filter| filter.may_contain_path(keys)),
+        }
+    }
 }
 
 /// Find information about a commit by either getting it from a [`gix_commitgraph::Graph`], if
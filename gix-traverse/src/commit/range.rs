@@ -0,0 +1,206 @@
+//! Find all commits that are reachable from some commits, but not from others, similar to `git rev-list ^hidden shown`.
+//!
+//! Unlike [`Simple`](super::Simple) and [`Topo`](super::Topo), hidden commits may be reached only after the commits
+//! they hide, which is why the result can only be known once the traversal is complete.
+use gix_date::SecondsSinceUnixEpoch;
+use gix_hash::{oid, ObjectId};
+use gix_hashtable::HashMap;
+use gix_object::FindExt;
+use gix_revwalk::PriorityQueue;
+
+use crate::commit::{Info, ParentIds};
+
+/// The error returned by [`commits()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Find(#[from] gix_object::find::existing_iter::Error),
+    #[error(transparent)]
+    ObjectDecode(#[from] gix_object::decode::Error),
+}
+
+/// Like in `git`, the amount of hidden commits to traverse once only hidden commits are queued,
+/// to not be fooled by clock skew if generation numbers aren't available.
+const SLOP: usize = 5;
+
+/// The generation and commit time, to traverse the newest commits first.
+type GenAndCommitTime = (u64, SecondsSinceUnixEpoch);
+
+/// Whether a commit is reachable from a shown commit, or from a hidden one, with the latter taking precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Shown,
+    Hidden,
+}
+
+struct State {
+    side: Side,
+    in_queue: bool,
+    info: Info,
+}
+
+/// Return all commits reachable from `shown`, but not from any of the `hidden` commits, newest first,
+/// like `git rev-list ^hidden shown` would.
+///
+/// Commits are looked up in `objects`, or in `cache` if it contains them, which also provides generation numbers to
+/// avoid traversing more hidden commits than needed. Without it, the commit time is used, and a few more hidden commits
+/// are traversed to not be fooled by clock skew, just like Git does.
+///
+/// Merge commits are contained as well, and can be identified by their [parent ids](Info::parent_ids).
+pub fn commits<Find>(
+    objects: Find,
+    cache: Option<&gix_commitgraph::Graph>,
+    shown: impl IntoIterator<Item = impl Into<ObjectId>>,
+    hidden: impl IntoIterator<Item = impl Into<ObjectId>>,
+) -> Result<Vec<Info>, Error>
+where
+    Find: gix_object::Find,
+{
+    let mut buf = Vec::new();
+    let mut states = HashMap::<ObjectId, State>::default();
+    let mut queue = PriorityQueue::<GenAndCommitTime, ObjectId>::new();
+    let mut shown_in_queue = 0;
+    let tips = shown
+        .into_iter()
+        .map(|id| (id.into(), Side::Shown))
+        .chain(hidden.into_iter().map(|id| (id.into(), Side::Hidden)));
+    for (id, side) in tips {
+        if states.contains_key(&id) {
+            if side == Side::Hidden {
+                hide(&mut states, id, &mut shown_in_queue);
+            }
+            continue;
+        }
+        let (key, info) = lookup(&objects, cache, &id, &mut buf)?;
+        states.insert(
+            id,
+            State {
+                side,
+                in_queue: true,
+                info,
+            },
+        );
+        shown_in_queue += usize::from(side == Side::Shown);
+        queue.insert(key, id);
+    }
+
+    // Traverse from newest to oldest so that hidden commits are usually known to be hidden before they are seen,
+    // and stop once only hidden commits were left for a while. Hiding propagates to all known ancestors.
+    let mut candidates = Vec::new();
+    let mut slop = SLOP;
+    while let Some((key, id)) = queue.pop() {
+        let state = states.get_mut(&id).expect("queued commits are known");
+        state.in_queue = false;
+        let side = state.side;
+        let parents = state.info.parent_ids.clone();
+        if side == Side::Shown {
+            shown_in_queue -= 1;
+            candidates.push(id);
+        }
+        for parent in parents {
+            if states.contains_key(&parent) {
+                if side == Side::Hidden {
+                    hide(&mut states, parent, &mut shown_in_queue);
+                }
+                continue;
+            }
+            let (parent_key, info) = lookup(&objects, cache, &parent, &mut buf)?;
+            states.insert(
+                parent,
+                State {
+                    side,
+                    in_queue: true,
+                    info,
+                },
+            );
+            shown_in_queue += usize::from(side == Side::Shown);
+            queue.insert(parent_key, parent);
+        }
+        if side == Side::Hidden {
+            slop = match queue.peek() {
+                None => 0,
+                Some((next_key, _)) if key <= *next_key || shown_in_queue != 0 => SLOP,
+                Some(_) => slop - 1,
+            };
+            if slop == 0 {
+                break;
+            }
+        }
+    }
+    Ok(candidates
+        .into_iter()
+        .filter_map(|id| {
+            let state = states.remove(&id).expect("candidates are known");
+            (state.side == Side::Shown).then_some(state.info)
+        })
+        .collect())
+}
+
+/// Mark `id` and all of its known ancestors as hidden.
+fn hide(states: &mut HashMap<ObjectId, State>, id: ObjectId, shown_in_queue: &mut usize) {
+    let mut stack = vec![id];
+    while let Some(id) = stack.pop() {
+        let Some(state) = states.get_mut(&id) else { continue };
+        if state.side == Side::Hidden {
+            continue;
+        }
+        state.side = Side::Hidden;
+        if state.in_queue {
+            *shown_in_queue -= 1;
+        }
+        stack.extend(state.info.parent_ids.iter().copied());
+    }
+}
+
+fn lookup<Find>(
+    objects: &Find,
+    cache: Option<&gix_commitgraph::Graph>,
+    id: &oid,
+    buf: &mut Vec<u8>,
+) -> Result<(GenAndCommitTime, Info), Error>
+where
+    Find: gix_object::Find,
+{
+    if let Some((cache, commit)) = cache.and_then(|cache| cache.commit_by_id(id).map(|commit| (cache, commit))) {
+        let parent_ids: Result<ParentIds, _> = commit
+            .iter_parents()
+            .map(|pos| pos.map(|pos| cache.commit_at(pos).id().to_owned()))
+            .collect();
+        // Use the object database if the commit-graph is corrupt.
+        if let Ok(parent_ids) = parent_ids {
+            let commit_time = commit.committer_timestamp() as SecondsSinceUnixEpoch;
+            return Ok((
+                (commit.effective_generation(), commit_time),
+                Info {
+                    id: id.to_owned(),
+                    parent_ids,
+                    commit_time: Some(commit_time),
+                },
+            ));
+        }
+    }
+
+    let mut parent_ids = ParentIds::new();
+    let mut commit_time = 0;
+    for token in objects.find_commit_iter(id, buf)? {
+        use gix_object::commit::ref_iter::Token as T;
+        match token? {
+            T::Tree { .. } | T::Author { .. } => continue,
+            T::Parent { id } => parent_ids.push(id),
+            T::Committer { signature } => {
+                commit_time = signature.seconds();
+                break;
+            }
+            _past_committer => break,
+        }
+    }
+    Ok((
+        (gix_commitgraph::GENERATION_NUMBER_V2_INFINITY, commit_time),
+        Info {
+            id: id.to_owned(),
+            parent_ids,
+            commit_time: Some(commit_time),
+        },
+    ))
+}
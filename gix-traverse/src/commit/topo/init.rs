@@ -121,7 +121,7 @@ where
             indegree_queue: PriorityQueue::new(),
             topo_queue: super::iter::Queue::new(self.sorting),
             parents: self.parents,
-            min_gen: gix_commitgraph::GENERATION_NUMBER_INFINITY,
+            min_gen: gix_commitgraph::GENERATION_NUMBER_V2_INFINITY,
             buf: vec![],
         };
 
@@ -8,7 +8,7 @@ use crate::commit::{
     Either, Info, Parents, Topo,
 };
 
-pub(in crate::commit) type GenAndCommitTime = (u32, i64);
+pub(in crate::commit) type GenAndCommitTime = (u64, i64);
 
 // Git's priority queue works as a LIFO stack if no compare function is set,
 // which is the case for `--topo-order.` However, even in that case the initial
@@ -53,7 +53,7 @@ impl<Find, Predicate> Topo<Find, Predicate>
 where
     Find: gix_object::Find,
 {
-    pub(super) fn compute_indegrees_to_depth(&mut self, gen_cutoff: u32) -> Result<(), Error> {
+    pub(super) fn compute_indegrees_to_depth(&mut self, gen_cutoff: u64) -> Result<(), Error> {
         while let Some(((gen, _), _)) = self.indegree_queue.peek() {
             if *gen >= gen_cutoff {
                 self.indegree_walk_step()?;
@@ -83,7 +83,7 @@ where
         Ok(())
     }
 
-    fn explore_to_depth(&mut self, gen_cutoff: u32) -> Result<(), Error> {
+    fn explore_to_depth(&mut self, gen_cutoff: u64) -> Result<(), Error> {
         while let Some(((gen, _), _)) = self.explore_queue.peek() {
             if *gen >= gen_cutoff {
                 self.explore_walk_step()?;
@@ -286,7 +286,10 @@ where
                     .commit_at(pos);
                 parents.push((
                     parent_commit.id().into(),
-                    (parent_commit.generation(), parent_commit.committer_timestamp() as i64),
+                    (
+                        parent_commit.effective_generation(),
+                        parent_commit.committer_timestamp() as i64,
+                    ),
                 ));
                 if first_only {
                     break;
@@ -315,8 +318,8 @@ pub(super) fn gen_and_commit_time(c: Either<'_, '_>) -> Result<GenAndCommitTime,
                     Err(err) => return Err(err.into()),
                 }
             }
-            Ok((gix_commitgraph::GENERATION_NUMBER_INFINITY, commit_time))
+            Ok((gix_commitgraph::GENERATION_NUMBER_V2_INFINITY, commit_time))
         }
-        Either::CachedCommit(c) => Ok((c.generation(), c.committer_timestamp() as i64)),
+        Either::CachedCommit(c) => Ok((c.effective_generation(), c.committer_timestamp() as i64)),
     }
 }
@@ -0,0 +1,53 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+function commit_at() {
+  local message=${1:?first argument is the commit message}
+  local date=${2:?second argument is the commit date}
+  GIT_COMMITTER_DATE="$date" GIT_AUTHOR_DATE="$date" git commit -q --allow-empty -m "$message"
+  git tag "$message"
+}
+
+function baseline() {
+  local name=${1:?first argument is the name of the baseline}
+  shift
+  git rev-list "$@" > "$name.baseline"
+}
+
+# The clock of the committer of s1 and s2 was behind, so they seem older than all other commits.
+#
+#       merge
+#      /     \
+#   near     s2 (skewed)
+#     |       |
+#    n1      s1 (skewed)
+#     |       |
+#     |      far
+#      \     /
+#       base
+git checkout -q -b main
+commit_at base "@1000000000 +0000"
+commit_at n1 "@1000000100 +0000"
+commit_at near "@1000000200 +0000"
+
+git checkout -q -b skewed base
+commit_at far "@1000000300 +0000"
+commit_at s1 "@900000000 +0000"
+commit_at s2 "@900000100 +0000"
+
+git checkout -q main
+GIT_COMMITTER_DATE="@1000000400 +0000" GIT_AUTHOR_DATE="@1000000400 +0000" git merge -q --no-ff -m merge skewed
+git tag merge
+
+git commit-graph write --no-progress --reachable
+git repack -adq
+
+baseline near-to-merge ^near merge
+baseline s2-to-merge ^s2 merge
+baseline s2-to-near ^s2 near
+baseline far-to-near ^far near
+baseline merge-to-near ^merge near
+baseline near-and-s1-to-merge ^near ^s1 merge
+baseline base-to-near-and-s2 ^base near s2
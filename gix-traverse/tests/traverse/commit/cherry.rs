@@ -0,0 +1,38 @@
+use gix_traverse::commit::cherry::{mark_equivalent, Entry};
+
+use crate::hex_to_id;
+
+fn entry(id: &str, patch_id: Option<&str>) -> Entry {
+    Entry::new(hex_to_id(id), patch_id.map(hex_to_id))
+}
+
+#[test]
+fn commits_with_the_same_patch_id_on_the_other_side_are_equivalent() {
+    let mut left = [
+        entry("1111111111111111111111111111111111111111", Some("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")),
+        entry("2222222222222222222222222222222222222222", Some("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb")),
+        entry("3333333333333333333333333333333333333333", None),
+    ];
+    let mut right = [
+        entry("4444444444444444444444444444444444444444", Some("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb")),
+        entry("5555555555555555555555555555555555555555", Some("cccccccccccccccccccccccccccccccccccccccc")),
+        entry("6666666666666666666666666666666666666666", None),
+    ];
+    mark_equivalent(&mut left, &mut right);
+    assert_eq!(
+        left.iter().map(|e| e.equivalent).collect::<Vec<_>>(),
+        [false, true, false],
+        "commits without patch id, like merges, are never equivalent"
+    );
+    assert_eq!(right.iter().map(|e| e.equivalent).collect::<Vec<_>>(), [true, false, false]);
+}
+
+#[test]
+fn equivalence_is_only_determined_across_sides() {
+    let mut left = [
+        entry("1111111111111111111111111111111111111111", Some("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")),
+        entry("2222222222222222222222222222222222222222", Some("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")),
+    ];
+    mark_equivalent(&mut left, &mut []);
+    assert!(left.iter().all(|e| !e.equivalent));
+}
@@ -1,2 +1,4 @@
+mod cherry;
+mod range;
 mod simple;
 mod topo;
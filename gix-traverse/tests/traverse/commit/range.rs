@@ -0,0 +1,69 @@
+use gix_hash::ObjectId;
+use gix_object::bstr::ByteSlice;
+use gix_traverse::commit::range;
+
+use crate::hex_to_id;
+
+/// Assert that the commits reachable from `shown` but not from `hidden` are the ones of the `baseline` created
+/// by `git rev-list`, with and without commit-graph.
+fn assert_baseline(baseline: &str, shown: &[&str], hidden: &[&str]) -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_read_only_standalone("make_repo_for_range.sh")?;
+    let git_dir = dir.join(".git");
+    let store = gix_odb::at(git_dir.join("objects"))?;
+    let tag = |name: &str| -> crate::Result<ObjectId> {
+        let hex = std::fs::read(git_dir.join("refs").join("tags").join(name))?;
+        Ok(hex_to_id(hex.trim().to_str()?))
+    };
+    let shown = shown.iter().map(|name| tag(name)).collect::<Result<Vec<_>, _>>()?;
+    let hidden = hidden.iter().map(|name| tag(name)).collect::<Result<Vec<_>, _>>()?;
+
+    let mut expected: Vec<_> = std::fs::read(dir.join(format!("{baseline}.baseline")))?
+        .lines()
+        .map(|hex| hex_to_id(hex.to_str().expect("ascii")))
+        .collect();
+    expected.sort();
+
+    for use_commitgraph in [false, true] {
+        let graph = use_commitgraph
+            .then(|| gix_commitgraph::at(git_dir.join("objects").join("info")))
+            .transpose()?;
+        let commits = range::commits(&store, graph.as_ref(), shown.iter().copied(), hidden.iter().copied())?;
+        let times: Vec<_> = commits.iter().map(|info| info.commit_time).collect();
+        assert!(
+            times.iter().all(Option::is_some),
+            "commit times are always known as they are used for sorting"
+        );
+        let mut actual: Vec<_> = commits.into_iter().map(|info| info.id).collect();
+        actual.sort();
+        assert_eq!(actual, expected, "{baseline}, commit-graph used: {use_commitgraph}");
+    }
+    Ok(())
+}
+
+#[test]
+fn hidden_ancestors_of_shown_commits() -> crate::Result {
+    assert_baseline("near-to-merge", &["merge"], &["near"])?;
+    assert_baseline("far-to-near", &["near"], &["far"])
+}
+
+#[test]
+fn hidden_commits_with_clock_skew() -> crate::Result {
+    assert_baseline("s2-to-merge", &["merge"], &["s2"])?;
+    assert_baseline("s2-to-near", &["near"], &["s2"])
+}
+
+#[test]
+fn everything_is_hidden_if_shown_commits_are_reachable_from_hidden_ones() -> crate::Result {
+    assert_baseline("merge-to-near", &["near"], &["merge"])
+}
+
+#[test]
+fn multiple_shown_and_hidden_commits() -> crate::Result {
+    assert_baseline("near-and-s1-to-merge", &["merge"], &["near", "s1"])?;
+    assert_baseline("base-to-near-and-s2", &["near", "s2"], &["base"])
+}
+
+#[test]
+fn commits_that_are_shown_and_hidden_are_hidden() -> crate::Result {
+    assert_baseline("merge-to-near", &["near", "merge"], &["merge"])
+}
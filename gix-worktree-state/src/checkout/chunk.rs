@@ -116,7 +116,7 @@ where
     let mut files_in_chunk = 0;
 
     for (entry, entry_path) in entries_with_paths {
-        // TODO: write test for that
+        // This also skips sparse directories, which always carry this flag.
         if entry.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE) {
             files.fetch_add(1, Ordering::Relaxed);
             files_in_chunk += 1;
@@ -4,3 +4,6 @@
 ///
 pub mod checkout;
 pub use checkout::function::checkout;
+
+///
+pub mod sparse;
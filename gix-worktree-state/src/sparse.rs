@@ -0,0 +1,340 @@
+use std::collections::BTreeSet;
+
+use bstr::{BStr, BString, ByteSlice};
+use gix_glob::{pattern::Case, search::pattern::List};
+use gix_ignore::search::Ignore;
+use gix_index::entry::Flags;
+
+/// The patterns of a `.git/info/sparse-checkout` file, which determine which entries of the index are present in the worktree.
+///
+/// In _cone mode_, patterns are directories whose files are included recursively, along with all files in their parent
+/// directories and in the root of the repository. This is what enables sparse indices.
+/// Otherwise patterns are matched like those in `.gitignore` files, but a match means a file is included.
+#[derive(Debug, Clone)]
+pub struct Patterns {
+    kind: Kind,
+    case: Case,
+}
+
+#[derive(Debug, Clone)]
+enum Kind {
+    Cone {
+        /// Directories whose files are all included, recursively.
+        recursive: BTreeSet<BString>,
+        /// Directories leading to recursive directories, whose immediate files are included.
+        parents: BTreeSet<BString>,
+    },
+    NonCone {
+        /// The lines as they were provided, to be able to write them back unchanged.
+        lines: Vec<BString>,
+        patterns: List<Ignore>,
+    },
+}
+
+/// The header of a sparse-checkout file in cone mode, which includes all files in the root.
+const CONE_HEADER: &[&[u8]] = &[b"/*", b"!/*/"];
+
+/// Initialization
+impl Patterns {
+    /// Parse `bytes` from a sparse-checkout file, in _cone mode_ if `cone` is `true`, or as `.gitignore`-like patterns otherwise.
+    /// Use `case` to determine how to match paths.
+    ///
+    /// Like `git`, if patterns aren't valid in cone mode, they are treated as non-cone patterns instead, which can
+    /// be checked with [`is_cone()`](Self::is_cone()).
+    pub fn from_bytes(bytes: &[u8], cone: bool, case: Case) -> Self {
+        let lines: Vec<BString> = bytes
+            .lines()
+            .map(|line| line.trim_end_with(|c| c == '\r').into())
+            .collect();
+        let kind = if cone {
+            parse_cone(&lines, case).unwrap_or_else(|| non_cone(lines))
+        } else {
+            non_cone(lines)
+        };
+        Patterns { kind, case }
+    }
+
+    /// Create new patterns from `input` like `git sparse-checkout set` would, so in _cone mode_ if `cone` is `true`
+    /// each item in `input` is a directory to include recursively, otherwise each item is a pattern.
+    /// Use `case` to determine how to match paths.
+    pub fn from_input(input: impl IntoIterator<Item = impl AsRef<BStr>>, cone: bool, case: Case) -> Self {
+        let kind = if cone {
+            Kind::Cone {
+                recursive: Default::default(),
+                parents: Default::default(),
+            }
+        } else {
+            non_cone(Vec::new())
+        };
+        let mut patterns = Patterns { kind, case };
+        patterns.add(input);
+        patterns
+    }
+}
+
+/// Mutation
+impl Patterns {
+    /// Add `input` like `git sparse-checkout add` would, so in _cone mode_ each item is a directory to include recursively,
+    /// otherwise each item is a pattern.
+    pub fn add(&mut self, input: impl IntoIterator<Item = impl AsRef<BStr>>) {
+        match &mut self.kind {
+            Kind::Cone { recursive, .. } => {
+                for directory in input {
+                    let directory = directory.as_ref().trim_start_with(|c| c == '/');
+                    let directory = directory.trim_end_with(|c| c == '/');
+                    if !directory.is_empty() {
+                        recursive.insert(normalize(directory.as_bstr(), self.case));
+                    }
+                }
+                self.normalize_cone();
+            }
+            Kind::NonCone { lines, .. } => {
+                lines.extend(input.into_iter().map(|pattern| pattern.as_ref().to_owned()));
+                let lines = std::mem::take(lines);
+                self.kind = non_cone(lines);
+            }
+        }
+    }
+
+    /// Remove all recursive directories that are already included by one of their parents,
+    /// and assure all parents of recursive directories are known.
+    fn normalize_cone(&mut self) {
+        let Kind::Cone { recursive, parents } = &mut self.kind else {
+            return;
+        };
+        let all_recursive = std::mem::take(recursive);
+        recursive.extend(
+            all_recursive
+                .iter()
+                .filter(|dir| !parent_directories(dir.as_bstr()).any(|parent| all_recursive.contains(parent)))
+                .cloned(),
+        );
+        parents.clear();
+        for dir in recursive.iter() {
+            parents.extend(parent_directories(dir.as_bstr()).map(ToOwned::to_owned));
+        }
+    }
+}
+
+/// Access
+impl Patterns {
+    /// Return `true` if these patterns are in _cone mode_.
+    pub fn is_cone(&self) -> bool {
+        matches!(self.kind, Kind::Cone { .. })
+    }
+
+    /// Return `true` if the file at the worktree-relative `path` should be present in the worktree.
+    pub fn is_included(&self, path: &BStr) -> bool {
+        match &self.kind {
+            Kind::Cone { recursive, parents } => {
+                let path = normalize(path, self.case);
+                let mut directories = parent_directories(path.as_bstr()).peekable();
+                let Some(directory) = directories.peek().copied() else {
+                    return true;
+                };
+                parents.contains(directory)
+                    || recursive.contains(directory)
+                    || directories.any(|dir| recursive.contains(dir))
+            }
+            Kind::NonCone { patterns, .. } => {
+                // Like `git`, if the file itself isn't matched, its parent directories decide.
+                let mut path = path;
+                let mut is_dir = false;
+                loop {
+                    let basename_pos = path.rfind_byte(b'/').map(|pos| pos + 1);
+                    if let Some(m) = gix_ignore::search::pattern_matching_relative_path(
+                        patterns,
+                        path,
+                        basename_pos,
+                        Some(is_dir),
+                        self.case,
+                    ) {
+                        return !m.pattern.is_negative();
+                    }
+                    match basename_pos {
+                        Some(pos) => {
+                            path = path[..pos - 1].as_bstr();
+                            is_dir = true;
+                        }
+                        None => return false,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return `true` if the worktree-relative `directory` and everything within it is excluded from the worktree, so
+    /// it can be represented by a single sparse directory entry in the index.
+    ///
+    /// This is only possible in _cone mode_, and `false` is always returned otherwise.
+    pub fn is_sparse_directory(&self, directory: &BStr) -> bool {
+        match &self.kind {
+            Kind::Cone { recursive, parents } => {
+                let directory = normalize(directory, self.case);
+                !parents.contains(&directory)
+                    && !recursive.contains(&directory)
+                    && !parent_directories(directory.as_bstr()).any(|dir| recursive.contains(dir))
+            }
+            Kind::NonCone { .. } => false,
+        }
+    }
+
+    /// Write these patterns in the format of a sparse-checkout file to `out`.
+    ///
+    /// In _cone mode_, the patterns are written just like `git` does, while otherwise the lines are written as they were provided.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        match &self.kind {
+            Kind::Cone { recursive, parents } => {
+                for line in CONE_HEADER {
+                    out.write_all(line)?;
+                    out.write_all(b"\n")?;
+                }
+                for dir in parents {
+                    let dir = escape(dir.as_bstr());
+                    writeln!(out, "/{dir}/\n!/{dir}/*/")?;
+                }
+                for dir in recursive {
+                    writeln!(out, "/{dir}/", dir = escape(dir.as_bstr()))?;
+                }
+            }
+            Kind::NonCone { lines, .. } => {
+                for line in lines {
+                    out.write_all(line)?;
+                    out.write_all(b"\n")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+///
+pub mod apply {
+    /// The outcome of [`Patterns::apply_to()`](super::Patterns::apply_to()).
+    #[derive(Debug, Default, Clone, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The indices of all entries which lost their [`SKIP_WORKTREE`](gix_index::entry::Flags::SKIP_WORKTREE) flag,
+        /// and which have to be checked out.
+        pub newly_included: Vec<usize>,
+        /// The indices of all entries which gained the [`SKIP_WORKTREE`](gix_index::entry::Flags::SKIP_WORKTREE) flag,
+        /// and which have to be removed from the worktree.
+        pub newly_excluded: Vec<usize>,
+    }
+}
+
+/// Index manipulation
+impl Patterns {
+    /// Set or clear the [`SKIP_WORKTREE`](Flags::SKIP_WORKTREE) flag of all entries in `index` depending on whether
+    /// they are [included](Self::is_included()), and return the indices of all entries that changed.
+    ///
+    /// Conflicting entries always remain in the worktree, and [sparse directories](gix_index::entry::Mode::DIR) are
+    /// left untouched, so the index should be [expanded](gix_index::State::expand_sparse_directories()) beforehand.
+    pub fn apply_to(&self, index: &mut gix_index::State) -> apply::Outcome {
+        let mut out = apply::Outcome::default();
+        for (idx, (entry, path)) in index.entries_mut_with_paths().enumerate() {
+            if entry.mode.is_sparse() {
+                continue;
+            }
+            let is_skipped = entry.flags.contains(Flags::SKIP_WORKTREE);
+            let should_skip = entry.stage_raw() == 0 && !self.is_included(path);
+            match (is_skipped, should_skip) {
+                (false, true) => {
+                    entry.flags.insert(Flags::SKIP_WORKTREE | Flags::EXTENDED);
+                    out.newly_excluded.push(idx);
+                }
+                (true, false) => {
+                    entry.flags.remove(Flags::SKIP_WORKTREE);
+                    if !entry.flags.contains(Flags::INTENT_TO_ADD) {
+                        entry.flags.remove(Flags::EXTENDED);
+                    }
+                    out.newly_included.push(idx);
+                }
+                _ => {}
+            }
+        }
+        out
+    }
+}
+
+/// Parse `lines` as patterns in cone mode, or return `None` if they are not valid in cone mode.
+fn parse_cone(lines: &[BString], case: Case) -> Option<Kind> {
+    let mut lines = lines.iter().filter(|line| !line.is_empty() && !line.starts_with(b"#"));
+    for expected in CONE_HEADER {
+        if lines.next()?.as_slice() != *expected {
+            return None;
+        }
+    }
+    let mut recursive = BTreeSet::new();
+    let mut parents = BTreeSet::new();
+    for line in lines {
+        if let Some(dir) = line.strip_prefix(b"!/").and_then(|dir| dir.strip_suffix(b"/*/")) {
+            let dir = normalize(unescape(dir)?.as_bstr(), case);
+            if !recursive.remove(&dir) {
+                return None;
+            }
+            parents.insert(dir);
+        } else {
+            let dir = line.strip_prefix(b"/")?.strip_suffix(b"/")?;
+            if dir.is_empty() || dir.starts_with(b"!") {
+                return None;
+            }
+            recursive.insert(normalize(unescape(dir)?.as_bstr(), case));
+        }
+    }
+    Some(Kind::Cone { recursive, parents })
+}
+
+fn non_cone(lines: Vec<BString>) -> Kind {
+    let mut buf = Vec::new();
+    for line in &lines {
+        buf.extend_from_slice(line);
+        buf.push(b'\n');
+    }
+    Kind::NonCone {
+        patterns: List::from_bytes(&buf, "sparse-checkout".into(), None),
+        lines,
+    }
+}
+
+/// Return all parent directories of `path`, starting with the closest one.
+fn parent_directories(path: &BStr) -> impl Iterator<Item = &BStr> {
+    let mut path = path;
+    std::iter::from_fn(move || {
+        let pos = path.rfind_byte(b'/')?;
+        path = path[..pos].as_bstr();
+        Some(path)
+    })
+}
+
+fn normalize(path: &BStr, case: Case) -> BString {
+    match case {
+        Case::Sensitive => path.to_owned(),
+        Case::Fold => path.to_ascii_lowercase().into(),
+    }
+}
+
+/// Escape characters that have a special meaning in patterns, like `git` does when writing directories in cone mode.
+fn escape(dir: &BStr) -> BString {
+    let mut out = BString::default();
+    for &b in dir.iter() {
+        if matches!(b, b'\\' | b'*' | b'?' | b'[' | b']') {
+            out.push(b'\\');
+        }
+        out.push(b);
+    }
+    out
+}
+
+/// Undo [`escape()`], or return `None` if `dir` contains unescaped wildcards and thus isn't a valid cone-mode directory.
+fn unescape(dir: &[u8]) -> Option<BString> {
+    let mut out = BString::default();
+    let mut bytes = dir.iter();
+    while let Some(&b) = bytes.next() {
+        match b {
+            b'\\' => out.push(*bytes.next()?),
+            b'*' | b'?' | b'[' => return None,
+            _ => out.push(b),
+        }
+    }
+    Some(out)
+}
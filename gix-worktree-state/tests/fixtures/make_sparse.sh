@@ -0,0 +1,35 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function make_repo() {
+  git init -q "$1"
+  (cd "$1"
+    touch a b
+    mkdir -p c1/c2 c1/c3 d/c4
+    (cd c1 && touch a b && touch c2/a c2/b && touch c3/a c3/b)
+    (cd d && touch a b && touch c4/a c4/b c4/c5)
+
+    git add .
+    git commit -q -m "init"
+  )
+}
+
+make_repo cone
+(cd cone
+  git sparse-checkout set c1/c2
+)
+
+make_repo cone-nested
+(cd cone-nested
+  git sparse-checkout set c1/c2 d c1/c2/sub
+)
+
+make_repo sparse-index
+(cd sparse-index
+  git sparse-checkout set c1/c2 --sparse-index
+)
+
+make_repo non-cone
+(cd non-cone
+  git sparse-checkout set --no-cone '/b' 'c1/c2/' 'a' '!d/a'
+)
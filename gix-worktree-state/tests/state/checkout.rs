@@ -270,6 +270,17 @@ fn overwriting_files_and_lone_directories_works() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn entries_outside_of_the_sparse_checkout_are_skipped() -> crate::Result {
+    for (subdir, is_sparse) in [("cone", false), ("non-cone", false), ("sparse-index", true)] {
+        let (source_tree, destination, index, _outcome) =
+            checkout_index_in_tmp_dir(opts_from_probe(), "make_sparse", Some(subdir))?;
+        assert_eq!(index.is_sparse(), is_sparse, "{subdir}");
+        assert_equality(&source_tree, &destination, true)?;
+    }
+    Ok(())
+}
+
 #[test]
 fn symlinks_become_files_if_disabled() -> crate::Result {
     let mut opts = opts_from_probe();
@@ -1,4 +1,5 @@
 mod checkout;
+mod sparse;
 
 use std::path::{Path, PathBuf};
 
@@ -0,0 +1,111 @@
+use gix_object::bstr::{BStr, ByteSlice};
+use gix_worktree_state::sparse::Patterns;
+
+use crate::fixture_path;
+
+fn repo_files(subdir: &str) -> crate::Result<(Vec<u8>, gix_index::File)> {
+    let git_dir = fixture_path("make_sparse").join(subdir).join(".git");
+    let patterns = std::fs::read(git_dir.join("info").join("sparse-checkout"))?;
+    let index = gix_index::File::at(git_dir.join("index"), gix_hash::Kind::Sha1, false, Default::default())?;
+    Ok((patterns, index))
+}
+
+fn skipped_paths(index: &gix_index::State) -> Vec<&BStr> {
+    index
+        .entries()
+        .iter()
+        .filter(|e| e.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE))
+        .map(|e| e.path(index))
+        .collect()
+}
+
+fn cone(patterns: &[u8]) -> Patterns {
+    Patterns::from_bytes(patterns, true, gix_glob::pattern::Case::Sensitive)
+}
+
+#[test]
+fn applying_patterns_matches_git() -> crate::Result {
+    for (subdir, is_cone) in [("cone", true), ("cone-nested", true), ("non-cone", false)] {
+        let (patterns, expected) = repo_files(subdir)?;
+        let patterns = Patterns::from_bytes(&patterns, is_cone, gix_glob::pattern::Case::Sensitive);
+        assert_eq!(patterns.is_cone(), is_cone, "{subdir}");
+
+        let mut index = expected.clone();
+        for entry in index.entries_mut() {
+            entry.flags.remove(gix_index::entry::Flags::SKIP_WORKTREE);
+        }
+        let outcome = patterns.apply_to(&mut index);
+        assert_eq!(skipped_paths(&index), skipped_paths(&expected), "{subdir}");
+        assert_eq!(outcome.newly_excluded.len(), skipped_paths(&expected).len(), "{subdir}");
+        assert!(outcome.newly_included.is_empty());
+
+        let outcome = patterns.apply_to(&mut index);
+        assert_eq!(outcome, Default::default(), "{subdir}: applying again changes nothing");
+    }
+    Ok(())
+}
+
+#[test]
+fn cone_patterns_are_written_like_git_does() -> crate::Result {
+    for (subdir, input) in [("cone", &["c1/c2"][..]), ("cone-nested", &["c1/c2", "d", "c1/c2/sub"])] {
+        let (expected, _) = repo_files(subdir)?;
+        let patterns = Patterns::from_input(
+            input.iter().map(|dir| dir.as_bytes().as_bstr()),
+            true,
+            Default::default(),
+        );
+        let mut actual = Vec::new();
+        patterns.write_to(&mut actual)?;
+        assert_eq!(actual.as_bstr(), expected.as_bstr(), "{subdir}");
+
+        let mut roundtrip = Vec::new();
+        cone(&expected).write_to(&mut roundtrip)?;
+        assert_eq!(roundtrip.as_bstr(), expected.as_bstr(), "{subdir}");
+    }
+    Ok(())
+}
+
+#[test]
+fn cone_mode_includes_parents_recursively_included_directories_and_the_root() {
+    let mut patterns = Patterns::from_input(
+        ["/c1/c2/"].map(|dir| dir.as_bytes().as_bstr()),
+        true,
+        Default::default(),
+    );
+    for (path, expected) in [
+        ("a", true),
+        ("c1/a", true),
+        ("c1/c2/a", true),
+        ("c1/c2/deep/a", true),
+        ("c1/c3/a", false),
+        ("d/a", false),
+    ] {
+        assert_eq!(patterns.is_included(path.into()), expected, "{path}");
+    }
+    for (dir, expected) in [
+        ("c1", false),
+        ("c1/c2", false),
+        ("c1/c2/deep", false),
+        ("c1/c3", true),
+        ("d", true),
+    ] {
+        assert_eq!(patterns.is_sparse_directory(dir.into()), expected, "{dir}");
+    }
+
+    patterns.add(["d"].map(|dir| dir.as_bytes().as_bstr()));
+    assert!(patterns.is_included("d/c4/a".into()));
+    assert!(!patterns.is_sparse_directory("d".into()));
+}
+
+#[test]
+fn invalid_cone_patterns_fall_back_to_non_cone_mode() {
+    for patterns in [&b""[..], b"/*\n!/*/\n*.txt\n", b"/*\n!/*/\n!/c1/*/\n", b"/c1/\n"] {
+        assert!(!cone(patterns).is_cone(), "{:?}", patterns.as_bstr());
+    }
+    assert!(cone(b"/*\n!/*/\n").is_cone(), "the header alone is valid");
+    assert!(cone(b"/*\n!/*/\n").is_sparse_directory("d".into()));
+    assert!(
+        !Patterns::from_bytes(b"/*\n!/*/\n", false, Default::default()).is_sparse_directory("d".into()),
+        "there are no sparse directories in non-cone mode"
+    );
+}
@@ -35,6 +35,16 @@ impl PrepareFetch {
         self
     }
 
+    /// Make this clone a partial one by asking the remote to omit objects according to the filter `spec`, like `blob:none`.
+    ///
+    /// The remote is configured as promisor so that missing objects are fetched on demand, which is also the case
+    /// when checking out the worktree.
+    /// Note that the filter is ignored if the remote doesn't support it, yielding a complete clone.
+    pub fn with_filter(mut self, spec: impl Into<BString>) -> Self {
+        self.filter = Some(spec.into());
+        self
+    }
+
     /// Apply the given configuration `values` right before readying the actual fetch from the remote.
     /// The configuration is marked with [source API](gix_config::Source::Api), and will not be written back, it's
     /// retained only in memory.
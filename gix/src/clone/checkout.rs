@@ -19,9 +19,13 @@ pub mod main_worktree {
             id: gix_hash::ObjectId,
             source: gix_index::init::from_tree::Error,
         },
+        #[error("Could not fetch the objects to check out from the promisor remote")]
+        Prefetch(#[source] gix_odb::store::promisor::Error),
         #[error("Couldn't obtain configuration for core.protect*")]
         BooleanConfig(#[from] crate::config::boolean::Error),
         #[error(transparent)]
+        IndexVersion(#[from] crate::config::index_version::Error),
+        #[error(transparent)]
         WriteIndex(#[from] gix_index::file::write::Error),
         #[error(transparent)]
         CheckoutOptions(#[from] crate::config::checkout_options::Error),
@@ -111,11 +115,24 @@ pub mod main_worktree {
                 }
             };
 
-            let index = gix_index::State::from_tree(&root_tree, &repo.objects, repo.config.protect_options()?)
+            let mut index = gix_index::State::from_tree(&root_tree, &repo.objects, repo.config.protect_options()?)
                 .map_err(|err| Error::IndexFromTree {
                     id: root_tree,
                     source: err,
                 })?;
+            index.set_version(repo.config.index_version()?);
+            // In partial clones, obtain all objects to check out at once instead of one by one.
+            repo.objects
+                .prefetch(
+                    index
+                        .entries()
+                        .iter()
+                        .filter(|entry| {
+                            !entry.mode.is_submodule() && !entry.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE)
+                        })
+                        .map(|entry| entry.id),
+                )
+                .map_err(Error::Prefetch)?;
             let mut index = gix_index::File::from_state(index, repo.index_path());
 
             let mut opts = repo.checkout_options(gix_worktree::stack::state::attributes::Source::IdMapping)?;
@@ -29,6 +29,8 @@ pub enum Error {
     LoadConfig(#[from] gix_config::file::init::from_paths::Error),
     #[error("Failed to store configured remote in memory")]
     SaveConfig(#[from] crate::remote::save::AsError),
+    #[error("Failed to configure the repository as partial clone")]
+    PartialCloneConfig(#[from] crate::config::set_value::Error),
     #[error("Failed to write repository configuration to disk")]
     SaveConfigIo(#[from] std::io::Error),
     #[error("The remote HEAD points to a reference named {head_ref_name:?} which is invalid.")]
@@ -197,10 +199,14 @@ impl PrepareFetch {
                 message: reflog_message.clone(),
             })
             .with_shallow(self.shallow.clone())
+            .with_filter(self.filter.clone())
             .receive(&mut progress, should_interrupt)
             .await?;
 
         util::append_config_to_repo_config(repo, config);
+        if let Some(filter) = &self.filter {
+            util::write_partial_clone_config(repo, remote_name.as_ref(), filter.as_ref())?;
+        }
         util::update_head(
             repo,
             &outcome.ref_map,
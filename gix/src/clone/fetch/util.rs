@@ -53,6 +53,40 @@ fn write_to_local_config(config: &gix_config::File<'static>, mode: WriteMode) ->
     config.write_to_filter(&mut local_config, |s| s.meta().source == gix_config::Source::Local)
 }
 
+/// Configure `remote_name` as promisor remote to lazily fetch the objects omitted by `filter` from, on disk and in memory,
+/// and let the object database of `repo` fetch missing objects from it.
+#[allow(clippy::result_large_err)]
+pub fn write_partial_clone_config(repo: &mut Repository, remote_name: &BStr, filter: &BStr) -> Result<(), Error> {
+    use crate::config::tree::{Core, Extensions, Key, Remote};
+
+    let path = local_config_meta(repo).path.expect("local config with path set");
+    let mut config = gix_config::File::from_path_no_includes(path.clone(), gix_config::Source::Local)?;
+    let values: [(&'static dyn Key, Option<&BStr>, &BStr); 4] = [
+        (&Core::REPOSITORY_FORMAT_VERSION, None, "1".into()),
+        (&Extensions::PARTIAL_CLONE, None, remote_name),
+        (&Remote::PROMISOR, Some(remote_name), "true".into()),
+        (&Remote::PARTIAL_CLONE_FILTER, Some(remote_name), filter),
+    ];
+    for (key, subsection, value) in values {
+        config
+            .set_raw_value_by(key.section().name(), subsection, key.name(), value)
+            .map_err(crate::config::set_value::Error::from)?;
+    }
+    std::fs::write(&path, config.to_bstring())?;
+
+    let mut snapshot = repo.config_snapshot_mut();
+    for (key, subsection, value) in values {
+        match subsection {
+            Some(subsection) => snapshot.set_subsection_value(key, subsection, value)?,
+            None => snapshot.set_value(key, value)?,
+        };
+    }
+    snapshot.commit()?;
+    #[cfg(feature = "blocking-network-client")]
+    crate::repository::init::setup_promisor(&mut repo.objects, repo.refs.git_dir(), &repo.config, &repo.options);
+    Ok(())
+}
+
 pub fn append_config_to_repo_config(repo: &mut Repository, config: gix_config::File<'static>) {
     let repo_config = gix_features::threading::OwnShared::make_mut(&mut repo.config.resolved);
     repo_config.append(config);
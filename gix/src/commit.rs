@@ -268,3 +268,130 @@ pub mod describe {
         }
     }
 }
+
+///
+#[cfg(feature = "blob-diff")]
+pub mod patch_id {
+    use gix_hash::ObjectId;
+
+    use crate::{
+        object::tree::diff::{patch, Action},
+        revision, Commit,
+    };
+
+    /// The error returned by [`Commit::patch_id()`] and the [`Iter`] returned by [`revision::Walk::patch_ids()`].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Walk(#[from] revision::walk::iter::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        ObjectKind(#[from] crate::object::try_into::Error),
+        #[error(transparent)]
+        CommitTree(#[from] crate::object::commit::Error),
+        #[error(transparent)]
+        DiffOptions(#[from] crate::diff::options::init::Error),
+        #[error(transparent)]
+        DiffResourceCache(#[from] crate::repository::diff_resource_cache::Error),
+        #[error(transparent)]
+        ForEachChange(#[from] crate::object::tree::diff::for_each::Error),
+        #[error(transparent)]
+        Patch(#[from] patch::file::Error),
+        #[error(transparent)]
+        Hash(#[from] gix_hash::hasher::Error),
+    }
+
+    /// An iterator over the commits of a [revision walk](revision::Walk) along with their patch id,
+    /// as returned by [`revision::Walk::patch_ids()`].
+    pub struct Iter<'repo> {
+        pub(crate) walk: revision::Walk<'repo>,
+        pub(crate) mode: gix_diff::patch_id::Mode,
+        pub(crate) resource_cache: Option<gix_diff::blob::Platform>,
+        pub(crate) buf: Vec<u8>,
+    }
+
+    impl<'repo> Iterator for Iter<'repo> {
+        type Item = Result<(revision::walk::Info<'repo>, Option<ObjectId>), Error>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let info = match self.walk.next()? {
+                Ok(info) => info,
+                Err(err) => return Some(Err(err.into())),
+            };
+            let mut compute = || {
+                let resource_cache = match self.resource_cache.as_mut() {
+                    Some(cache) => cache,
+                    None => self
+                        .resource_cache
+                        .insert(self.walk.repo.diff_resource_cache_for_tree_diff()?),
+                };
+                compute(&info.object()?, self.mode, resource_cache, &mut self.buf)
+            };
+            Some(compute().map(|patch_id| (info, patch_id)))
+        }
+    }
+
+    pub use gix_traverse::commit::cherry::Entry;
+
+    /// Compute the patch ids of all commits of `left` and `right`, typically the two sides of a symmetric range
+    /// like `upstream..head` and `head..upstream`, and mark all commits that have an equivalent on the other side.
+    ///
+    /// The entries are returned in traversal order. See [`gix_traverse::commit::cherry::mark_equivalent()`]
+    /// for how they relate to `git cherry` and `git log --cherry-pick`.
+    pub fn cherry(left: Iter<'_>, right: Iter<'_>) -> Result<(Vec<Entry>, Vec<Entry>), Error> {
+        let collect = |iter: Iter<'_>| {
+            iter.map(|res| res.map(|(info, patch_id)| Entry::new(info.id, patch_id)))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let (mut left, mut right) = (collect(left)?, collect(right)?);
+        gix_traverse::commit::cherry::mark_equivalent(&mut left, &mut right);
+        Ok((left, right))
+    }
+
+    /// Compute the patch id of `commit` in `mode`, using `resource_cache` for diffing and `buf` to hold the patch.
+    pub(crate) fn compute(
+        commit: &Commit<'_>,
+        mode: gix_diff::patch_id::Mode,
+        resource_cache: &mut gix_diff::blob::Platform,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<ObjectId>, Error> {
+        use crate::ext::TreeDiffChangeExt;
+
+        let repo = commit.repo;
+        let mut parents = commit.parent_ids();
+        let parent = parents.next();
+        if parents.next().is_some() {
+            return Ok(None);
+        }
+        let parent_tree = match parent {
+            Some(id) => id.object()?.try_into_commit()?.tree()?,
+            None => repo.empty_tree(),
+        };
+        let tree = commit.tree()?;
+
+        let mut changes = Vec::new();
+        parent_tree
+            .changes()?
+            .options(|opts| {
+                opts.track_rewrites(None);
+            })
+            .for_each_to_obtain_tree(&tree, |change| {
+                changes.push(change.detach());
+                Ok::<_, std::convert::Infallible>(Action::Continue)
+            })?;
+        changes.sort_by(|a, b| a.location().cmp(b.location()));
+
+        buf.clear();
+        let options = patch::Options {
+            full_index: true,
+            ..Default::default()
+        };
+        for change in &changes {
+            patch::write_change(change.attach(repo, repo), resource_cache, buf, options)?;
+            resource_cache.clear_resource_cache_keep_allocation();
+        }
+        Ok(gix_diff::patch_id::compute(buf, mode, repo.object_hash())?)
+    }
+}
@@ -38,6 +38,18 @@ impl Cache {
             .copied()
     }
 
+    #[cfg(feature = "status")]
+    pub(crate) fn diff_ignore_submodules(
+        &self,
+    ) -> Result<Option<gix_submodule::config::Ignore>, config::key::GenericErrorWithValue> {
+        use crate::config::{cache::util::ApplyLeniency, tree::Diff};
+        self.resolved
+            .string(Diff::IGNORE_SUBMODULES)
+            .map(|value| Diff::IGNORE_SUBMODULES.try_into_ignore(value))
+            .transpose()
+            .with_leniency(self.lenient_config)
+    }
+
     #[cfg(feature = "blob-diff")]
     pub(crate) fn diff_drivers(&self) -> Result<Vec<gix_diff::blob::Driver>, config::diff::drivers::Error> {
         use crate::config::cache::util::ApplyLeniencyDefault;
@@ -80,6 +92,12 @@ impl Cache {
             if let Some(textconv) = section.value(config::tree::Diff::DRIVER_TEXTCONV.name) {
                 driver.binary_to_text_command = textconv.into_owned().into();
             }
+            if let Some(patterns) = section
+                .value(config::tree::Diff::DRIVER_XFUNCNAME.name)
+                .or_else(|| section.value(config::tree::Diff::DRIVER_FUNCNAME.name))
+            {
+                driver.xfuncname = patterns.into_owned().into();
+            }
             if let Some(algorithm) = section.value("algorithm") {
                 driver.algorithm = config::tree::Diff::DRIVER_ALGORITHM
                     .try_into_algorithm(algorithm)
@@ -98,6 +116,15 @@ impl Cache {
                     .into();
             }
         }
+        // Built-in drivers are always available, and their patterns are used unless configured otherwise.
+        for name in gix_diff::blob::funcname::BUILTIN_DRIVER_NAMES {
+            if !out.iter().any(|d| d.name == *name) {
+                out.push(gix_diff::blob::Driver {
+                    name: (*name).into(),
+                    ..Default::default()
+                });
+            }
+        }
         Ok(out)
     }
 
@@ -223,6 +250,19 @@ impl Cache {
         })
     }
 
+    /// Return the thread limit to use when checking the stat information of index entries in parallel, which is `Some(1)`
+    /// if `core.preloadIndex` is `false`.
+    #[cfg(feature = "status")]
+    pub(crate) fn preload_index_thread_limit(&self) -> Result<Option<usize>, config::boolean::Error> {
+        const DEFAULT: bool = true;
+        let preload = self.resolved.boolean(Core::PRELOAD_INDEX).map_or(Ok(DEFAULT), |res| {
+            Core::PRELOAD_INDEX
+                .enrich_error(res)
+                .with_lenient_default_value(self.lenient_config, DEFAULT)
+        })?;
+        Ok((!preload).then_some(1))
+    }
+
     /// Returns (file-timeout, pack-refs timeout)
     pub(crate) fn lock_timeout(
         &self,
@@ -251,6 +291,16 @@ impl Cache {
             .into()
     }
 
+    /// Return the path to the filesystem monitor hook as configured in `core.fsmonitor`, or `None` if it's unset or a boolean,
+    /// which would enable or disable the builtin filesystem monitor daemon that we don't support.
+    pub(crate) fn fs_monitor_hook(&self) -> Option<Result<PathBuf, gix_config::path::interpolate::Error>> {
+        let value = self.resolved.string(Core::FSMONITOR)?;
+        if gix_config::Boolean::try_from(value.as_ref()).is_ok() {
+            return None;
+        }
+        self.trusted_file_path(Core::FSMONITOR)?.map(Cow::into_owned).into()
+    }
+
     /// A helper to obtain a file from trusted configuration at `section_name`, `subsection_name`, and `key`, which is interpolated
     /// if present.
     pub(crate) fn trusted_file_path(
@@ -296,6 +346,46 @@ impl Cache {
         })
     }
 
+    /// The version to use for newly created index files, as controlled by `index.version` and `feature.manyFiles`.
+    #[cfg(feature = "index")]
+    pub(crate) fn index_version(&self) -> Result<gix_index::Version, config::index_version::Error> {
+        use crate::config::tree::{Feature, Index};
+        if let Some(version) = self.apply_leniency(
+            self.resolved
+                .integer(Index::VERSION)
+                .map(|v| Index::VERSION.try_into_index_version(v)),
+        )? {
+            return Ok(version);
+        }
+        Ok(if boolean(self, "feature.manyFiles", &Feature::MANY_FILES, false)? {
+            gix_index::Version::V4
+        } else {
+            gix_index::Version::V2
+        })
+    }
+
+    /// The configuration of the sparse checkout and the sparse index, as controlled by `core.sparseCheckout`,
+    /// `core.sparseCheckoutCone` and `index.sparse`.
+    #[cfg(feature = "worktree-mutation")]
+    pub(crate) fn sparse_options(&self) -> Result<gix_index::sparse::Options, boolean::Error> {
+        use crate::config::tree::Index;
+        Ok(gix_index::sparse::Options {
+            sparse_checkout: boolean(self, "core.sparseCheckout", &Core::SPARSE_CHECKOUT, false)?,
+            directory_patterns_only: self.sparse_checkout_cone()?.unwrap_or(false),
+            write_sparse_index: boolean(self, "index.sparse", &Index::SPARSE, false)?,
+        })
+    }
+
+    /// The value of `core.sparseCheckoutCone`, if set.
+    #[cfg(feature = "worktree-mutation")]
+    pub(crate) fn sparse_checkout_cone(&self) -> Result<Option<bool>, boolean::Error> {
+        self.apply_leniency(
+            self.resolved
+                .boolean("core.sparseCheckoutCone")
+                .map(|v| Core::SPARSE_CHECKOUT_CONE.enrich_error(v)),
+        )
+    }
+
     #[cfg(any(feature = "index", feature = "tree-editor"))]
     pub(crate) fn protect_options(&self) -> Result<gix_validate::path::component::Options, config::boolean::Error> {
         const IS_WINDOWS: bool = cfg!(windows);
@@ -154,6 +154,7 @@ impl Cache {
             true,
             lenient_config,
         )?;
+        let pack_backing = util::parse_pack_backing(&config, lenient_config, filter_config_section)?;
         #[cfg(feature = "revision")]
         let object_kind_hint = util::disambiguate_hint(&config, lenient_config)?;
         let (static_pack_cache_limit_bytes, pack_cache_bytes, object_cache_bytes) =
@@ -162,6 +163,7 @@ impl Cache {
         Ok(Cache {
             resolved: config.into(),
             use_multi_pack_index,
+            pack_backing,
             object_hash,
             #[cfg(feature = "revision")]
             object_kind_hint,
@@ -103,6 +103,35 @@ pub(crate) fn parse_object_caches(
     Ok((static_pack_cache_limit, pack_cache_bytes, object_cache_bytes))
 }
 
+/// Use windowed pack access if `core.packedGitWindowSize` or `core.packedGitLimit` are set, with the defaults of `git` otherwise.
+pub(crate) fn parse_pack_backing(
+    config: &gix_config::File<'static>,
+    lenient: bool,
+    mut filter_config_section: fn(&gix_config::file::Metadata) -> bool,
+) -> Result<gix_pack::backing::Mode, Error> {
+    let window_size = config
+        .integer_filter("core.packedGitWindowSize", &mut filter_config_section)
+        .map(|res| Core::PACKED_GIT_WINDOW_SIZE.try_into_usize(res))
+        .transpose()
+        .with_leniency(lenient)?;
+    let limit = config
+        .integer_filter("core.packedGitLimit", &mut filter_config_section)
+        .map(|res| Core::PACKED_GIT_LIMIT.try_into_usize(res))
+        .transpose()
+        .with_leniency(lenient)?;
+    Ok(if window_size.is_none() && limit.is_none() {
+        gix_pack::backing::Mode::MemoryMap
+    } else {
+        // Git's defaults are meant for memory maps, but our windows are read into heap-allocated buffers,
+        // so much smaller defaults are used for whichever value isn't set.
+        let limit = limit.unwrap_or(64 * 1024 * 1024);
+        gix_pack::backing::Mode::Windowed {
+            window_size: window_size.unwrap_or(1024 * 1024).min(limit),
+            budget: gix_pack::backing::Budget::new(limit),
+        }
+    })
+}
+
 pub(crate) fn parse_core_abbrev(
     config: &gix_config::File<'static>,
     object_hash: gix_hash::Kind,
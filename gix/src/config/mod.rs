@@ -198,6 +198,20 @@ pub mod stat_options {
     }
 }
 
+///
+#[cfg(feature = "index")]
+pub mod index_version {
+    /// The error produced when obtaining the version of newly created index files.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigIndexVersion(#[from] super::key::GenericError),
+        #[error(transparent)]
+        ConfigBoolean(#[from] super::boolean::Error),
+    }
+}
+
 ///
 #[cfg(feature = "attributes")]
 pub mod checkout_options {
@@ -593,6 +607,8 @@ pub(crate) struct Cache {
     pub object_hash: gix_hash::Kind,
     /// If true, multi-pack indices, whether present or not, may be used by the object database.
     pub use_multi_pack_index: bool,
+    /// How to access pack data and index files, as controlled by `core.packedGitWindowSize` and `core.packedGitLimit`.
+    pub pack_backing: gix_pack::backing::Mode,
     /// The representation of `core.logallrefupdates`, or `None` if the variable wasn't set.
     pub reflog: Option<gix_ref::store::WriteReflog>,
     /// The representation of `gitoxide.core.refsNamespace`, or `None` if the variable wasn't set.
@@ -36,6 +36,18 @@ impl Core {
         keys::LockTimeout::new_lock_timeout("packedRefsTimeout", &config::Tree::CORE);
     /// The `core.multiPackIndex` key.
     pub const MULTIPACK_INDEX: keys::Boolean = keys::Boolean::new_boolean("multiPackIndex", &config::Tree::CORE);
+    /// The `core.packedGitLimit` key.
+    pub const PACKED_GIT_LIMIT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("packedGitLimit", &config::Tree::CORE).with_deviation(
+            "if this or core.packedGitWindowSize is set, packs are read into heap-allocated windows instead of being memory-mapped. \
+            The limit applies to all packs of the object database instead of all packs of the process, and defaults to 64MiB instead of 32TiB (or 256MiB on 32 bit systems)",
+        );
+    /// The `core.packedGitWindowSize` key.
+    pub const PACKED_GIT_WINDOW_SIZE: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("packedGitWindowSize", &config::Tree::CORE).with_deviation(
+            "if this or core.packedGitLimit is set, packs are read into heap-allocated windows instead of being memory-mapped. \
+            Defaults to 1MiB instead of 1GiB (or 32MiB on 32 bit systems), and is never larger than core.packedGitLimit",
+        );
     /// The `core.logAllRefUpdates` key.
     pub const LOG_ALL_REF_UPDATES: LogAllRefUpdates =
         LogAllRefUpdates::new_with_validate("logAllRefUpdates", &config::Tree::CORE, validate::LogAllRefUpdates);
@@ -63,6 +75,20 @@ impl Core {
     pub const ASKPASS: keys::Executable = keys::Executable::new_executable("askPass", &config::Tree::CORE)
         .with_environment_override("GIT_ASKPASS")
         .with_note("fallback is 'SSH_ASKPASS'");
+    /// The `core.untrackedCache` key.
+    pub const UNTRACKED_CACHE: UntrackedCache =
+        UntrackedCache::new_with_validate("untrackedCache", &config::Tree::CORE, validate::UntrackedCache);
+    /// The `core.fsmonitor` key.
+    pub const FSMONITOR: keys::Any = keys::Any::new("fsmonitor", &config::Tree::CORE).with_deviation(
+        "Only hooks are supported, the builtin filesystem monitor daemon is ignored if the value is a boolean",
+    );
+    /// The `core.preloadIndex` key.
+    pub const PRELOAD_INDEX: keys::Boolean = keys::Boolean::new_boolean("preloadIndex", &config::Tree::CORE);
+    /// The `core.sparseCheckout` key.
+    pub const SPARSE_CHECKOUT: keys::Boolean = keys::Boolean::new_boolean("sparseCheckout", &config::Tree::CORE);
+    /// The `core.sparseCheckoutCone` key.
+    pub const SPARSE_CHECKOUT_CONE: keys::Boolean =
+        keys::Boolean::new_boolean("sparseCheckoutCone", &config::Tree::CORE);
     /// The `core.excludesFile` key.
     pub const EXCLUDES_FILE: keys::Path = keys::Path::new_path("excludesFile", &config::Tree::CORE);
     /// The `core.attributesFile` key.
@@ -114,6 +140,8 @@ impl Section for Core {
             &Self::FILES_REF_LOCK_TIMEOUT,
             &Self::PACKED_REFS_TIMEOUT,
             &Self::MULTIPACK_INDEX,
+            &Self::PACKED_GIT_LIMIT,
+            &Self::PACKED_GIT_WINDOW_SIZE,
             &Self::LOG_ALL_REF_UPDATES,
             &Self::PRECOMPOSE_UNICODE,
             &Self::REPOSITORY_FORMAT_VERSION,
@@ -124,6 +152,11 @@ impl Section for Core {
             &Self::PROTECT_NTFS,
             &Self::ASKPASS,
             &Self::EXCLUDES_FILE,
+            &Self::UNTRACKED_CACHE,
+            &Self::FSMONITOR,
+            &Self::PRELOAD_INDEX,
+            &Self::SPARSE_CHECKOUT,
+            &Self::SPARSE_CHECKOUT_CONE,
             &Self::ATTRIBUTES_FILE,
             &Self::SSH_COMMAND,
             &Self::USE_REPLACE_REFS,
@@ -149,6 +182,9 @@ pub type Abbrev = keys::Any<validate::Abbrev>;
 /// The `core.logAllRefUpdates` key.
 pub type LogAllRefUpdates = keys::Any<validate::LogAllRefUpdates>;
 
+/// The `core.untrackedCache` key.
+pub type UntrackedCache = keys::Any<validate::UntrackedCache>;
+
 /// The `core.disambiguate` key.
 pub type Disambiguate = keys::Any<validate::Disambiguate>;
 
@@ -357,6 +393,29 @@ mod log_all_ref_updates {
     }
 }
 
+mod untracked_cache {
+    use crate::{config, config::tree::core::UntrackedCache};
+
+    impl UntrackedCache {
+        /// Returns `Some(true)` if the untracked cache should be created if it doesn't exist yet, `Some(false)` if it
+        /// shouldn't be used, or `None` if an existing cache should be used but not created, as parsed from `value`.
+        /// If `value` is not a boolean, we try to interpret the string value `keep` instead, which is the default.
+        pub fn try_into_untracked_cache(
+            &'static self,
+            value: Option<Result<bool, gix_config::value::Error>>,
+        ) -> Result<Option<bool>, config::key::GenericErrorWithValue> {
+            match value {
+                Some(Ok(bool)) => Ok(Some(bool)),
+                Some(Err(err)) => match err.input {
+                    val if val.eq_ignore_ascii_case(b"keep") => Ok(None),
+                    val => Err(config::key::GenericErrorWithValue::from_value(self, val)),
+                },
+                None => Ok(None),
+            }
+        }
+    }
+}
+
 mod check_stat {
     use std::borrow::Cow;
 
@@ -472,6 +531,15 @@ mod validate {
         }
     }
 
+    pub struct UntrackedCache;
+    impl keys::Validate for UntrackedCache {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Core::UNTRACKED_CACHE
+                .try_into_untracked_cache(Some(gix_config::Boolean::try_from(value).map(|b| b.0)))?;
+            Ok(())
+        }
+    }
+
     pub struct CheckStat;
     impl keys::Validate for CheckStat {
         fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
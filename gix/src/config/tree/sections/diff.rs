@@ -17,6 +17,11 @@ impl Diff {
     );
     /// The `diff.renames` key.
     pub const RENAMES: Renames = Renames::new_renames("renames", &config::Tree::DIFF);
+    /// The `diff.ignoreSubmodules` key.
+    #[cfg(feature = "attributes")]
+    pub const IGNORE_SUBMODULES: IgnoreSubmodules =
+        IgnoreSubmodules::new_with_validate("ignoreSubmodules", &config::Tree::DIFF, validate::IgnoreSubmodules)
+            .with_note("used by the status of submodules that have no `submodule.<name>.ignore` configuration");
 
     /// The `diff.<driver>.command` key.
     pub const DRIVER_COMMAND: keys::Program = keys::Program::new_program("command", &config::Tree::DIFF)
@@ -32,6 +37,14 @@ impl Diff {
     pub const DRIVER_BINARY: Binary = Binary::new_with_validate("binary", &config::Tree::DIFF, validate::Binary)
         .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")));
 
+    /// The `diff.<driver>.xfuncname` key.
+    pub const DRIVER_XFUNCNAME: keys::String = keys::String::new_string("xfuncname", &config::Tree::DIFF)
+        .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")));
+    /// The `diff.<driver>.funcname` key.
+    pub const DRIVER_FUNCNAME: keys::String = keys::String::new_string("funcname", &config::Tree::DIFF)
+        .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")))
+        .with_deviation("patterns are interpreted as extended regular expressions, just like `xfuncname`");
+
     /// The `diff.external` key.
     pub const EXTERNAL: keys::Program =
         keys::Program::new_program("external", &config::Tree::DIFF).with_environment_override("GIT_EXTERNAL_DIFF");
@@ -47,10 +60,14 @@ impl Section for Diff {
             &Self::ALGORITHM,
             &Self::RENAME_LIMIT,
             &Self::RENAMES,
+            #[cfg(feature = "attributes")]
+            &Self::IGNORE_SUBMODULES,
             &Self::DRIVER_COMMAND,
             &Self::DRIVER_TEXTCONV,
             &Self::DRIVER_ALGORITHM,
             &Self::DRIVER_BINARY,
+            &Self::DRIVER_XFUNCNAME,
+            &Self::DRIVER_FUNCNAME,
             &Self::EXTERNAL,
         ]
     }
@@ -65,6 +82,10 @@ pub type Renames = keys::Any<validate::Renames>;
 /// The `diff.<driver>.binary` key.
 pub type Binary = keys::Any<validate::Binary>;
 
+/// The `diff.ignoreSubmodules` key.
+#[cfg(feature = "attributes")]
+pub type IgnoreSubmodules = keys::Any<validate::IgnoreSubmodules>;
+
 mod algorithm {
     use std::borrow::Cow;
 
@@ -129,6 +150,24 @@ mod binary {
     }
 }
 
+#[cfg(feature = "attributes")]
+mod ignore_submodules {
+    use std::borrow::Cow;
+
+    use crate::{bstr::BStr, config::tree::sections::diff::IgnoreSubmodules};
+
+    impl IgnoreSubmodules {
+        /// Obtain the portion of the submodule status to ignore from `value`.
+        pub fn try_into_ignore(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_submodule::config::Ignore, crate::config::key::GenericErrorWithValue> {
+            gix_submodule::config::Ignore::try_from(value.as_ref())
+                .map_err(|()| crate::config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
+        }
+    }
+}
+
 mod renames {
     use crate::{
         bstr::ByteSlice,
@@ -195,4 +234,14 @@ pub(super) mod validate {
             Ok(())
         }
     }
+
+    pub struct IgnoreSubmodules;
+    impl keys::Validate for IgnoreSubmodules {
+        #[cfg_attr(not(feature = "attributes"), allow(unused_variables))]
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            #[cfg(feature = "attributes")]
+            Diff::IGNORE_SUBMODULES.try_into_ignore(value.into())?;
+            Ok(())
+        }
+    }
 }
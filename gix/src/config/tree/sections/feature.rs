@@ -0,0 +1,22 @@
+use crate::{
+    config,
+    config::tree::{keys, Feature, Key, Section},
+};
+
+impl Feature {
+    /// The `feature.manyFiles` key.
+    pub const MANY_FILES: keys::Boolean = keys::Boolean::new_boolean("manyFiles", &config::Tree::FEATURE)
+        .with_deviation(
+            "only affects the default of `index.version`, but not `index.skipHash` or `core.untrackedCache`",
+        );
+}
+
+impl Section for Feature {
+    fn name(&self) -> &str {
+        "feature"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::MANY_FILES]
+    }
+}
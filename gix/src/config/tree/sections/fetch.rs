@@ -14,6 +14,8 @@ impl Fetch {
     #[cfg(feature = "attributes")]
     pub const RECURSE_SUBMODULES: RecurseSubmodules =
         RecurseSubmodules::new_with_validate("recurseSubmodules", &config::Tree::FETCH, validate::RecurseSubmodules);
+    /// The `fetch.writeCommitGraph` key.
+    pub const WRITE_COMMIT_GRAPH: keys::Boolean = keys::Boolean::new_boolean("writeCommitGraph", &config::Tree::FETCH);
 }
 
 impl Section for Fetch {
@@ -26,6 +28,7 @@ impl Section for Fetch {
             &Self::NEGOTIATION_ALGORITHM,
             #[cfg(feature = "attributes")]
             &Self::RECURSE_SUBMODULES,
+            &Self::WRITE_COMMIT_GRAPH,
         ]
     }
 }
@@ -0,0 +1,94 @@
+use crate::{
+    config,
+    config::tree::{keys, Gc, Key, Section},
+};
+
+impl Gc {
+    /// The `gc.auto` key.
+    pub const AUTO: keys::UnsignedInteger = keys::UnsignedInteger::new_unsigned_integer("auto", &config::Tree::GC);
+    /// The `gc.autoPackLimit` key.
+    pub const AUTO_PACK_LIMIT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("autoPackLimit", &config::Tree::GC);
+    /// The `gc.pruneExpire` key.
+    pub const PRUNE_EXPIRE: Expiry = Expiry::new_with_validate("pruneExpire", &config::Tree::GC, validate::Expiry);
+    /// The `gc.reflogExpire` key.
+    pub const REFLOG_EXPIRE: Expiry = Expiry::new_with_validate("reflogExpire", &config::Tree::GC, validate::Expiry)
+        .with_note("per-reference patterns like `gc.<pattern>.reflogExpire` are not supported");
+    /// The `gc.reflogExpireUnreachable` key.
+    pub const REFLOG_EXPIRE_UNREACHABLE: Expiry =
+        Expiry::new_with_validate("reflogExpireUnreachable", &config::Tree::GC, validate::Expiry)
+            .with_note("per-reference patterns like `gc.<pattern>.reflogExpireUnreachable` are not supported");
+}
+
+impl Section for Gc {
+    fn name(&self) -> &str {
+        "gc"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::AUTO,
+            &Self::AUTO_PACK_LIMIT,
+            &Self::PRUNE_EXPIRE,
+            &Self::REFLOG_EXPIRE,
+            &Self::REFLOG_EXPIRE_UNREACHABLE,
+        ]
+    }
+}
+
+/// The `gc.pruneExpire` and `gc.reflogExpire*` keys.
+pub type Expiry = keys::Any<validate::Expiry>;
+
+mod expiry {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::sections::gc::Expiry,
+    };
+
+    impl Expiry {
+        /// Convert `value` into the time at or before which items are considered expired, with `now` as reference
+        /// for relative dates like `2.weeks.ago`.
+        ///
+        /// `never` and `false` are `None` as nothing ever expires, while `now` and `all` expire everything up to `now`.
+        pub fn try_into_expiry(
+            &'static self,
+            value: Cow<'_, BStr>,
+            now: std::time::SystemTime,
+        ) -> Result<Option<gix_date::Time>, config::key::GenericErrorWithValue<gix_date::parse::Error>> {
+            let to_error =
+                |err| config::key::GenericErrorWithValue::from_value(self, value.clone().into_owned()).with_source(err);
+            let input = value.to_str().map_err(|_| {
+                to_error(gix_date::parse::Error::InvalidDateString {
+                    input: value.to_string(),
+                })
+            })?;
+            Ok(match input {
+                "never" | "false" => None,
+                "now" | "all" => Some(gix_date::Time::new(
+                    now.duration_since(std::time::UNIX_EPOCH)
+                        .map_or(0, |elapsed| elapsed.as_secs() as gix_date::SecondsSinceUnixEpoch),
+                    0,
+                )),
+                dotted if dotted.strip_suffix(".ago").is_some() => {
+                    Some(gix_date::parse(&dotted.replace('.', " "), Some(now)).map_err(to_error)?)
+                }
+                other => Some(gix_date::parse(other, Some(now)).map_err(to_error)?),
+            })
+        }
+    }
+}
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct Expiry;
+    impl keys::Validate for Expiry {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Gc::PRUNE_EXPIRE.try_into_expiry(value.into(), std::time::SystemTime::now())?;
+            Ok(())
+        }
+    }
+}
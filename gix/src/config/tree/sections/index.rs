@@ -10,11 +10,39 @@ impl Index {
     /// The `index.skipHash` key.
     pub const SKIP_HASH: keys::Boolean = keys::Boolean::new_boolean("skipHash", &config::Tree::INDEX)
         .with_deviation("also used to skip the hash when reading, even if a hash exists in the index file");
+    /// The `index.version` key.
+    pub const VERSION: Version = Version::new_with_validate("version", &config::Tree::INDEX, validate::Version);
+    /// The `index.sparse` key.
+    pub const SPARSE: keys::Boolean = keys::Boolean::new_boolean("sparse", &config::Tree::INDEX);
 }
 
 /// The `index.threads` key.
 pub type IndexThreads = keys::Any<validate::IndexThreads>;
 
+/// The `index.version` key.
+pub type Version = keys::Any<validate::Version>;
+
+#[cfg(feature = "index")]
+mod version {
+    use crate::{config, config::tree::sections::index::Version};
+
+    impl Version {
+        /// Try to interpret an integer value as the version of newly created index files.
+        pub fn try_into_index_version(
+            &'static self,
+            value: Result<i64, gix_config::value::Error>,
+        ) -> Result<gix_index::Version, config::key::GenericError> {
+            let value = value.map_err(|err| config::key::GenericError::from(self).with_source(err))?;
+            Ok(match value {
+                2 => gix_index::Version::V2,
+                3 => gix_index::Version::V3,
+                4 => gix_index::Version::V4,
+                _ => return Err(config::key::GenericError::from(self)),
+            })
+        }
+    }
+}
+
 mod index_threads {
     use std::borrow::Cow;
 
@@ -50,7 +78,7 @@ impl Section for Index {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::THREADS, &Self::SKIP_HASH]
+        &[&Self::THREADS, &Self::SKIP_HASH, &Self::VERSION, &Self::SPARSE]
     }
 }
 
@@ -64,4 +92,17 @@ mod validate {
             Ok(())
         }
     }
+
+    pub struct Version;
+    impl keys::Validate for Version {
+        #[cfg_attr(not(feature = "index"), allow(unused_variables))]
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            #[cfg(feature = "index")]
+            super::Index::VERSION.try_into_index_version(gix_config::Integer::try_from(value).and_then(|int| {
+                int.to_decimal()
+                    .ok_or_else(|| gix_config::value::Error::new("integer out of range", value))
+            }))?;
+            Ok(())
+        }
+    }
 }
@@ -21,6 +21,11 @@ pub mod checkout;
 pub struct Clone;
 mod clone;
 
+/// The `commitGraph` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct CommitGraph;
+mod commit_graph;
+
 /// The `committer` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Committer;
@@ -48,11 +53,21 @@ pub mod diff;
 pub struct Extensions;
 pub mod extensions;
 
+/// The `feature` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Feature;
+mod feature;
+
 /// The `fetch` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Fetch;
 pub mod fetch;
 
+/// The `gc` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gc;
+pub mod gc;
+
 /// The `gitoxide` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Gitoxide;
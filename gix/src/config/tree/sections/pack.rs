@@ -12,6 +12,9 @@ impl Pack {
     /// The `pack.indexVersion` key.
     pub const INDEX_VERSION: IndexVersion =
         IndexVersion::new_with_validate("indexVersion", &config::Tree::PACK, validate::IndexVersion);
+
+    /// The `pack.writeReverseIndex` key.
+    pub const WRITE_REVERSE_INDEX: keys::Boolean = keys::Boolean::new_boolean("writeReverseIndex", &config::Tree::PACK);
 }
 
 /// The `pack.indexVersion` key.
@@ -42,7 +45,7 @@ impl Section for Pack {
     }
 
     fn keys(&self) -> &[&dyn Key] {
-        &[&Self::THREADS, &Self::INDEX_VERSION]
+        &[&Self::THREADS, &Self::INDEX_VERSION, &Self::WRITE_REVERSE_INDEX]
     }
 }
 
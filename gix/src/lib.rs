@@ -122,6 +122,8 @@ pub use gix_ignore as ignore;
 #[cfg(feature = "index")]
 pub use gix_index as index;
 pub use gix_lock as lock;
+#[cfg(feature = "mailbox")]
+pub use gix_mailbox as mailbox;
 #[cfg(feature = "credentials")]
 pub use gix_negotiate as negotiate;
 pub use gix_object as objs;
@@ -185,10 +187,16 @@ pub mod dirwalk;
 pub mod head;
 pub mod id;
 pub mod object;
+#[cfg(feature = "maintenance")]
+pub mod maintenance;
 #[cfg(feature = "attributes")]
 pub mod pathspec;
 pub mod reference;
 pub mod repository;
+#[cfg(feature = "worktree-mutation")]
+pub mod sparse_checkout;
+#[cfg(feature = "dirwalk")]
+pub mod staging;
 #[cfg(feature = "attributes")]
 pub mod submodule;
 pub mod tag;
@@ -0,0 +1,323 @@
+//! Keep the object database and references of a repository in shape, similar to `git gc`.
+//!
+//! The building blocks are available individually as well:
+//!
+//! * [`Repository::repack()`](crate::Repository::repack()) writes all reachable objects into a single new pack and removes all
+//!   other packs, similar to `git repack -a -d`.
+//! * [`Repository::prune()`](crate::Repository::prune()) removes unreachable loose objects, similar to `git prune`.
+//! * [`Repository::prune_packed()`](crate::Repository::prune_packed()) removes loose objects that are also packed,
+//!   similar to `git prune-packed`.
+//! * [`Repository::pack_refs()`](crate::Repository::pack_refs()) moves all loose references into the `packed-refs` file,
+//!   similar to `git pack-refs --all --prune`.
+//! * [`Repository::expire_reflogs()`](crate::Repository::expire_reflogs()) removes old reflog entries,
+//!   similar to `git reflog expire --all`.
+//!
+//! [`Repository::gc()`](crate::Repository::gc()) runs all of them with the settings of the `gc` configuration section,
+//! optionally only if [`gc.auto` thresholds](crate::Repository::needs_gc()) are exceeded.
+//!
+//! Times at which something expires are given as seconds since the Unix epoch, and everything with a modification time
+//! at or before it is considered expired. `None` means that nothing ever expires.
+
+/// The default value for `gc.auto`, the approximate amount of loose objects that trigger an automatic garbage collection.
+pub const DEFAULT_AUTO_LOOSE_OBJECTS_LIMIT: u64 = 6700;
+/// The default value for `gc.autoPackLimit`, the amount of packs that trigger an automatic garbage collection.
+pub const DEFAULT_AUTO_PACK_LIMIT: u64 = 50;
+
+///
+pub mod reachable {
+    /// The error returned when finding all objects that are reachable from references, reflogs and indices.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenReferences(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        IterReferences(#[from] crate::reference::iter::init::Error),
+        #[error("Could not read reference")]
+        ReadReference(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        ReadReflog(#[from] gix_ref::file::log::Error),
+        #[error(transparent)]
+        ListWorktrees(#[from] std::io::Error),
+        #[error(transparent)]
+        OpenWorktree(#[from] crate::open::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelObject(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        WalkCommits(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        TraverseCommits(#[from] crate::revision::walk::iter::Error),
+        #[error(transparent)]
+        CountObjects(#[from] gix_pack::data::output::count::objects::Error),
+        #[error("Partial clones are not supported as objects may be missing locally, and would be fetched one by one")]
+        PartialClone,
+        #[error("The operation was interrupted")]
+        Interrupted,
+    }
+}
+
+///
+pub mod repack {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::repack()`](crate::Repository::repack()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Reachable(#[from] super::reachable::Error),
+        #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+        OpenArcOdb(#[source] std::io::Error),
+        #[error(transparent)]
+        CreateEntries(#[from] gix_pack::data::output::entry::iter_from_counts::Error),
+        #[error(transparent)]
+        WritePackData(
+            #[from] gix_pack::data::output::bytes::Error<gix_pack::data::output::entry::iter_from_counts::Error>,
+        ),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_pack::index::write::Error),
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] gix_pack::index::init::Error),
+        #[error(transparent)]
+        FindObject(#[from] gix_object::find::existing::Error),
+        #[error(transparent)]
+        WriteLooseObject(#[from] gix_object::write::Error),
+        #[error(transparent)]
+        WriteMultiPackIndex(#[from] gix_pack::multi_index::write::Error),
+        #[error(transparent)]
+        AcquireLock(#[from] gix_lock::acquire::Error),
+        #[error(transparent)]
+        CommitLock(#[from] gix_lock::commit::Error<gix_lock::File>),
+        #[error(transparent)]
+        PrunePacked(#[from] super::prune_packed::Error),
+        #[error("Could not access '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("The operation was interrupted")]
+        Interrupted,
+    }
+
+    /// Options for use in [`Repository::repack()`](crate::Repository::repack()).
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct Options {
+        /// The amount of threads to use when creating pack entries and writing the index, or `None` to use all logical cores.
+        pub thread_limit: Option<usize>,
+        /// If `true`, write a multi-pack index over all packs after repacking, similar to `git repack --write-midx`.
+        ///
+        /// Note that a previously existing multi-pack index is removed either way as it would refer to removed packs.
+        pub write_multi_pack_index: bool,
+        /// Unreachable objects in removed packs are written as loose objects unless their pack was last modified at or before
+        /// this time, in which case they are dropped, similar to `git repack -A --unpack-unreachable=<when>`.
+        ///
+        /// If `None`, unreachable objects are never dropped. This should be set to the value of `gc.pruneExpire`.
+        pub unpack_unreachable_expire: Option<gix_date::SecondsSinceUnixEpoch>,
+    }
+
+    /// The outcome of [`Repository::repack()`](crate::Repository::repack()).
+    #[derive(Debug, Clone)]
+    pub struct Outcome {
+        /// The outcome of writing the new pack and its index, or `None` if there was no reachable object to write.
+        pub pack: Option<gix_pack::bundle::write::Outcome>,
+        /// The statistics of finding all reachable objects.
+        pub counts: gix_pack::data::output::count::objects::Outcome,
+        /// The amount of previously existing packs that were removed.
+        pub packs_removed: usize,
+        /// The amount of unreachable objects in removed packs that were written as loose objects.
+        pub objects_loosened: usize,
+        /// The amount of unreachable objects in removed packs that were dropped as they were expired.
+        pub objects_dropped: usize,
+        /// The path to the newly written multi-pack index, if one was requested and there were packs to write it for.
+        pub multi_pack_index_path: Option<PathBuf>,
+        /// The outcome of removing loose objects which are now packed.
+        pub prune_packed: super::prune_packed::Outcome,
+    }
+}
+
+///
+pub mod prune {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::prune()`](crate::Repository::prune()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Reachable(#[from] super::reachable::Error),
+        #[error(transparent)]
+        IterLooseObjects(#[from] gix_odb::loose::iter::Error),
+        #[error("Could not access '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("The operation was interrupted")]
+        Interrupted,
+    }
+
+    /// The outcome of [`Repository::prune()`](crate::Repository::prune()).
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The amount of unreachable loose objects that were removed.
+        pub objects_removed: usize,
+        /// The amount of unreachable loose objects that were kept as they didn't expire yet.
+        pub objects_kept: usize,
+    }
+}
+
+///
+pub mod prune_packed {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::prune_packed()`](crate::Repository::prune_packed()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenIndex(#[from] gix_pack::index::init::Error),
+        #[error(transparent)]
+        IterLooseObjects(#[from] gix_odb::loose::iter::Error),
+        #[error("Could not access '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+    }
+
+    /// The outcome of [`Repository::prune_packed()`](crate::Repository::prune_packed()).
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The amount of loose objects that were removed as they are also present in a pack.
+        pub objects_removed: usize,
+    }
+}
+
+///
+pub mod pack_refs {
+    /// The error returned by [`Repository::pack_refs()`](crate::Repository::pack_refs()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not iterate loose references")]
+        IterLooseReferences(#[from] std::io::Error),
+        #[error(transparent)]
+        LockTimeoutConfig(#[from] crate::config::lock_timeout::Error),
+        #[error(transparent)]
+        PrepareTransaction(#[from] gix_ref::file::transaction::prepare::Error),
+        #[error(transparent)]
+        CommitTransaction(#[from] gix_ref::file::transaction::commit::Error),
+    }
+
+    /// The outcome of [`Repository::pack_refs()`](crate::Repository::pack_refs()).
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The amount of loose references that were moved into the `packed-refs` file.
+        pub refs_packed: usize,
+    }
+}
+
+///
+pub mod expire_reflogs {
+    /// The error returned by [`Repository::expire_reflogs()`](crate::Repository::expire_reflogs()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenReferences(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        IterReferences(#[from] crate::reference::iter::init::Error),
+        #[error("Could not read reference")]
+        ReadReference(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        WalkCommits(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        TraverseCommits(#[from] crate::revision::walk::iter::Error),
+        #[error(transparent)]
+        ReadReflog(#[from] gix_ref::file::log::Error),
+        #[error(transparent)]
+        RewriteReflog(#[from] gix_ref::file::log::retain::Error),
+    }
+
+    /// Options for use in [`Repository::expire_reflogs()`](crate::Repository::expire_reflogs()).
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct Options {
+        /// Reflog entries created at or before this time are removed, like `gc.reflogExpire`.
+        pub expire: Option<gix_date::SecondsSinceUnixEpoch>,
+        /// Reflog entries created at or before this time are removed if the commit they point to isn't reachable
+        /// from the current tip of the reference anymore, like `gc.reflogExpireUnreachable`.
+        pub expire_unreachable: Option<gix_date::SecondsSinceUnixEpoch>,
+    }
+
+    /// The outcome of [`Repository::expire_reflogs()`](crate::Repository::expire_reflogs()).
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The amount of reflogs that were rewritten as they had expired entries.
+        pub reflogs_rewritten: usize,
+        /// The total amount of reflog entries that were removed.
+        pub entries_removed: usize,
+    }
+}
+
+///
+pub mod needs_gc {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::needs_gc()`](crate::Repository::needs_gc()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigUnsignedInteger(#[from] crate::config::unsigned_integer::Error),
+        #[error("Could not access '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+    }
+}
+
+///
+pub mod gc {
+    /// The error returned by [`Repository::gc()`](crate::Repository::gc()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        NeedsGc(#[from] super::needs_gc::Error),
+        #[error(transparent)]
+        ConfigExpiry(#[from] crate::config::key::GenericErrorWithValue<gix_date::parse::Error>),
+        #[error(transparent)]
+        PackRefs(#[from] super::pack_refs::Error),
+        #[error(transparent)]
+        ExpireReflogs(#[from] super::expire_reflogs::Error),
+        #[error(transparent)]
+        Reachable(#[from] super::reachable::Error),
+        #[error(transparent)]
+        Repack(#[from] super::repack::Error),
+        #[error(transparent)]
+        Prune(#[from] super::prune::Error),
+    }
+
+    /// Options for use in [`Repository::gc()`](crate::Repository::gc()).
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct Options {
+        /// If `true`, only collect garbage if [`Repository::needs_gc()`](crate::Repository::needs_gc()), similar to `git gc --auto`.
+        pub auto: bool,
+        /// The amount of threads to use when repacking, or `None` to use all logical cores.
+        pub thread_limit: Option<usize>,
+        /// If `true`, write a multi-pack index after repacking.
+        pub write_multi_pack_index: bool,
+    }
+
+    /// The outcome of [`Repository::gc()`](crate::Repository::gc()).
+    #[derive(Debug, Clone)]
+    pub struct Outcome {
+        /// The outcome of packing references.
+        pub pack_refs: super::pack_refs::Outcome,
+        /// The outcome of expiring reflog entries.
+        pub expire_reflogs: super::expire_reflogs::Outcome,
+        /// The outcome of repacking all reachable objects.
+        pub repack: super::repack::Outcome,
+        /// The outcome of pruning unreachable loose objects.
+        pub prune: super::prune::Outcome,
+    }
+}
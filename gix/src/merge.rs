@@ -144,12 +144,14 @@ pub mod tree {
         ///
         /// It's important that `index` is at the state of [`Self::tree`].
         /// Note that in practice, whenever there is a single [conflict](Conflict), this function will return `true`.
+        /// If a conflict lies inside a sparse directory of `index`, an error is returned and nothing is applied,
+        /// so sparse indices should be [expanded](gix_index::State::expand_sparse_directories()) beforehand.
         pub fn index_changed_after_applying_conflicts(
             &self,
             index: &mut gix_index::State,
             how: TreatAsUnresolved,
             removal_mode: apply_index_entries::RemovalMode,
-        ) -> bool {
+        ) -> Result<bool, apply_index_entries::Error> {
             apply_index_entries(&self.conflicts, how, index, removal_mode)
         }
     }
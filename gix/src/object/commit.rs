@@ -159,6 +159,23 @@ impl<'repo> Commit<'repo> {
         }
     }
 
+    /// Compute the patch id of the changes this commit introduces compared to its parent, or to the empty tree if it has none,
+    /// in the given `mode`, just like `git show <commit> | git patch-id` would.
+    ///
+    /// Commits with the same patch id introduce the same changes, which can be used to find commits that were cherry-picked.
+    /// Return `None` for merge commits and for commits that don't change anything.
+    ///
+    /// Rename tracking is disabled to match Git, and binary files are identified by their full object ids.
+    /// Use [`revision::Walk::patch_ids()`](crate::revision::Walk::patch_ids()) to efficiently compute the ids of many commits.
+    #[cfg(feature = "blob-diff")]
+    pub fn patch_id(
+        &self,
+        mode: gix_diff::patch_id::Mode,
+    ) -> Result<Option<gix_hash::ObjectId>, crate::commit::patch_id::Error> {
+        let mut resource_cache = self.repo.diff_resource_cache_for_tree_diff()?;
+        crate::commit::patch_id::compute(self, mode, &mut resource_cache, &mut Vec::new())
+    }
+
     /// Extracts the PGP signature and the data that was used to create the signature, or `None` if it wasn't signed.
     // TODO: make it possible to verify the signature, probably by wrapping `SignedData`. It's quite some work to do it properly.
     pub fn signature(
@@ -170,17 +170,10 @@ pub struct Stats {
 }
 
 ///
-pub mod stats {
-    /// The error returned by [`stats()`](super::Platform::stats()).
-    #[derive(Debug, thiserror::Error)]
-    #[allow(missing_docs)]
-    pub enum Error {
-        #[error(transparent)]
-        CreateResourceCache(#[from] crate::repository::diff_resource_cache::Error),
-        #[error(transparent)]
-        ForEachChange(#[from] crate::object::tree::diff::for_each::Error),
-    }
-}
+pub mod stats;
+
+///
+pub mod patch;
 
 /// Convenience
 impl Platform<'_, '_> {
@@ -219,6 +212,60 @@ impl Platform<'_, '_> {
             lines_removed,
         })
     }
+
+    /// Calculate statistics for each file that changed between our current and the `other` tree, to be able to
+    /// produce output similar to `git diff --stat`, `--numstat`, `--shortstat` and `--dirstat`.
+    ///
+    /// Files whose source was [rewritten](Change::Rewrite) are recorded with their source location.
+    /// Trees don't appear in the result, while submodules count as one line removed and one line added, just like in Git.
+    ///
+    /// ### Performance Notes
+    ///
+    /// Rename tracking is performed as configured, so it's recommended to disable it with
+    /// [`track_rewrites(None)`](crate::diff::Options::track_rewrites) if rewrites aren't relevant.
+    pub fn stats_per_file(&mut self, other: &Tree<'_>) -> Result<stats::PerFile, stats::Error> {
+        let mut resource_cache = self.lhs.repo.diff_resource_cache_for_tree_diff()?;
+        let mut files = Vec::new();
+        self.for_each_to_obtain_tree(other, |change| {
+            if let Some(file) = stats::File::from_change(change, &mut resource_cache)? {
+                files.push(file);
+            }
+            resource_cache.clear_resource_cache_keep_allocation();
+            Ok::<_, stats::file::Error>(Action::Continue)
+        })?;
+        files.sort_by(|a, b| a.location.cmp(&b.location));
+        Ok(stats::PerFile { files })
+    }
+
+    /// Write the changes between our current and the `other` tree as patch into `out`, just like `git diff` would,
+    /// with each file starting with `diff --git a/path b/path`.
+    ///
+    /// Files are written in the order of their location, and rewrites are shown as renames or copies if
+    /// [rename tracking](crate::diff::Options::track_rewrites) is enabled.
+    /// See [`patch::write_change()`] for details on the format of each file.
+    pub fn write_patch(
+        &mut self,
+        other: &Tree<'_>,
+        out: &mut dyn std::io::Write,
+        options: patch::Options,
+    ) -> Result<(), patch::Error> {
+        use crate::ext::TreeDiffChangeExt;
+
+        let repo = self.lhs.repo;
+        let mut resource_cache = repo.diff_resource_cache_for_tree_diff()?;
+        let mut changes = Vec::new();
+        self.for_each_to_obtain_tree(other, |change| {
+            changes.push(change.detach());
+            Ok::<_, std::convert::Infallible>(Action::Continue)
+        })?;
+        changes.sort_by(|a, b| a.location().cmp(b.location()));
+        for change in &changes {
+            patch::write_change(change.attach(repo, other.repo), &mut resource_cache, out, options)
+                .map_err(|err| for_each::Error::ForEach(err.into()))?;
+            resource_cache.clear_resource_cache_keep_allocation();
+        }
+        Ok(())
+    }
 }
 
 ///
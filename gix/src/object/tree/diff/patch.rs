@@ -0,0 +1,330 @@
+use std::io::Write;
+
+use gix_diff::blob::{
+    platform::{prepare_diff::Operation, resource::Data},
+    unified_diff::{ConsumeHunk, ContextSize, NewlineSeparator},
+    UnifiedDiff,
+};
+use gix_object::tree::EntryMode;
+
+use super::Change;
+
+/// The error returned by [`write_patch()`](super::Platform::write_patch()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    CreateResourceCache(#[from] crate::repository::diff_resource_cache::Error),
+    #[error(transparent)]
+    ForEachChange(#[from] crate::object::tree::diff::for_each::Error),
+}
+
+///
+pub mod file {
+    /// The error returned by [`write_change()`](super::write_change()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        SetResource(#[from] crate::object::blob::diff::init::Error),
+        #[error(transparent)]
+        PrepareDiff(#[from] gix_diff::blob::platform::prepare_diff::Error),
+        #[error(transparent)]
+        FunctionNames(#[from] gix_diff::blob::funcname::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+/// Options for use in [`write_patch()`](super::Platform::write_patch()) and [`write_change()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The amount of context lines and how to merge hunks, with 3 lines of context by default.
+    pub context: ContextSize,
+    /// If `true`, write full object ids into `index` lines, similar to `--full-index`.
+    /// Otherwise, they are abbreviated like configured.
+    pub full_index: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            context: ContextSize::symmetrical(3),
+            full_index: false,
+        }
+    }
+}
+
+/// Write `change` as a patch in the format used by `git diff`, starting with `diff --git a/path b/path`, into `out`.
+/// Use `resource_cache` to obtain diffable data and to perform the diff. Changes that involve trees only are ignored.
+///
+/// Hunk headers show function names as determined by the diff driver of each file, and lines without newline at the end
+/// are marked as such, so the patch can be applied again.
+///
+/// Note that paths aren't quoted even if they contain unusual characters, and that no binary patches are produced.
+/// Instead, binary files and files transformed by a text conversion driver are marked with `Binary files a/path and b/path differ`.
+pub fn write_change(
+    change: Change<'_, '_, '_>,
+    resource_cache: &mut gix_diff::blob::Platform,
+    out: &mut dyn Write,
+    options: Options,
+) -> Result<(), file::Error> {
+    let (source_location, previous, current, copy, diff) = match change {
+        Change::Addition { entry_mode, id, .. } => (None, None, Some((entry_mode, id)), false, None),
+        Change::Deletion { entry_mode, id, .. } => (None, Some((entry_mode, id)), None, false, None),
+        Change::Modification {
+            previous_entry_mode,
+            previous_id,
+            entry_mode,
+            id,
+            ..
+        } => (
+            None,
+            Some((previous_entry_mode, previous_id)),
+            Some((entry_mode, id)),
+            false,
+            None,
+        ),
+        Change::Rewrite {
+            source_location,
+            source_entry_mode,
+            source_id,
+            entry_mode,
+            id,
+            copy,
+            diff,
+            ..
+        } => (
+            Some(source_location),
+            Some((source_entry_mode, source_id)),
+            Some((entry_mode, id)),
+            copy,
+            diff,
+        ),
+    };
+    if previous.map_or(true, |(mode, _)| mode.is_tree()) && current.map_or(true, |(mode, _)| mode.is_tree()) {
+        return Ok(());
+    }
+
+    let location = change.location();
+    let old_location = source_location.unwrap_or(location);
+    writeln!(out, "diff --git a/{old_location} b/{location}")?;
+    match (previous, current) {
+        (None, Some((mode, _))) => writeln!(out, "new file mode {mode:o}")?,
+        (Some((mode, _)), None) => writeln!(out, "deleted file mode {mode:o}")?,
+        (Some((previous_mode, _)), Some((mode, _))) if previous_mode != mode => {
+            writeln!(out, "old mode {previous_mode:o}")?;
+            writeln!(out, "new mode {mode:o}")?;
+        }
+        _ => {}
+    }
+    if let Some(source_location) = source_location {
+        let similarity = diff.map_or(100, |diff| (diff.similarity * 100.0) as u32);
+        let kind = if copy { "copy" } else { "rename" };
+        writeln!(out, "similarity index {similarity}%")?;
+        writeln!(out, "{kind} from {source_location}")?;
+        writeln!(out, "{kind} to {location}")?;
+    }
+
+    let previous_id = previous.map(|(_, id)| id);
+    let id = current.map(|(_, id)| id);
+    if previous_id.is_some() && previous_id == id {
+        return Ok(());
+    }
+    let hex = |id: Option<crate::Id<'_>>| {
+        id.map(|id| {
+            if options.full_index {
+                id.to_string()
+            } else {
+                id.shorten_or_id().to_string()
+            }
+        })
+    };
+    let (old_hex, new_hex) = match (hex(previous_id), hex(id)) {
+        (Some(old), Some(new)) => (old, new),
+        (None, Some(new)) => ("0".repeat(new.len()), new),
+        (Some(old), None) => {
+            let null = "0".repeat(old.len());
+            (old, null)
+        }
+        (None, None) => unreachable!("trees were handled earlier"),
+    };
+    let unchanged_mode = match (previous, current) {
+        (Some((previous_mode, _)), Some((mode, _))) if previous_mode == mode => Some(mode),
+        _ => None,
+    };
+    match unchanged_mode {
+        Some(mode) => writeln!(out, "index {old_hex}..{new_hex} {mode:o}")?,
+        None => writeln!(out, "index {old_hex}..{new_hex}")?,
+    }
+
+    let old_path = previous.map(|_| format!("a/{old_location}"));
+    let new_path = current.map(|_| format!("b/{location}"));
+    let is_submodule = |entry: Option<(EntryMode, crate::Id<'_>)>| entry.is_some_and(|(mode, _)| mode.is_commit());
+    if is_submodule(previous) || is_submodule(current) {
+        let subproject = |entry: Option<(EntryMode, crate::Id<'_>)>| {
+            entry.map_or_else(String::new, |(_, id)| format!("Subproject commit {}\n", id.detach()))
+        };
+        let (old, new) = (subproject(previous), subproject(current));
+        let input = gix_diff::blob::intern::InternedInput::new(
+            gix_diff::blob::sources::byte_lines_with_terminator(old.as_bytes()),
+            gix_diff::blob::sources::byte_lines_with_terminator(new.as_bytes()),
+        );
+        write_paths(out, old_path.as_deref(), new_path.as_deref())?;
+        write_hunks(
+            out,
+            &input,
+            gix_diff::blob::Algorithm::Myers,
+            &Default::default(),
+            options.context,
+        )?;
+        return Ok(());
+    }
+
+    let platform = change.diff(resource_cache)?;
+    platform
+        .resource_cache
+        .options
+        .skip_internal_diff_if_external_is_configured = false;
+    let function_names = platform
+        .resource_cache
+        .resource(gix_diff::blob::ResourceKind::OldOrSource)
+        .and_then(|resource| resource.driver_index)
+        .map(|idx| platform.resource_cache.filter.drivers()[idx].function_name_matcher())
+        .transpose()?
+        .unwrap_or_default();
+    let prep = platform.resource_cache.prepare_diff()?;
+    let is_derived = |data: Data<'_>| matches!(data, Data::Buffer { is_derived: true, .. });
+    match prep.operation {
+        Operation::InternalDiff { algorithm } if !is_derived(prep.old.data) && !is_derived(prep.new.data) => {
+            let input = gix_diff::blob::intern::InternedInput::new(prep.old.intern_source(), prep.new.intern_source());
+            if input.before.is_empty() && input.after.is_empty() {
+                return Ok(());
+            }
+            write_paths(out, old_path.as_deref(), new_path.as_deref())?;
+            write_hunks(out, &input, algorithm, &function_names, options.context)?;
+        }
+        Operation::ExternalCommand { .. } => {
+            unreachable!("we disabled that")
+        }
+        Operation::InternalDiff { .. } | Operation::SourceOrDestinationIsBinary => {
+            writeln!(
+                out,
+                "Binary files {} and {} differ",
+                old_path.as_deref().unwrap_or("/dev/null"),
+                new_path.as_deref().unwrap_or("/dev/null")
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn write_paths(out: &mut dyn Write, old_path: Option<&str>, new_path: Option<&str>) -> std::io::Result<()> {
+    writeln!(out, "--- {}", old_path.unwrap_or("/dev/null"))?;
+    writeln!(out, "+++ {}", new_path.unwrap_or("/dev/null"))
+}
+
+fn write_hunks(
+    out: &mut dyn Write,
+    input: &gix_diff::blob::intern::InternedInput<&[u8]>,
+    algorithm: gix_diff::blob::Algorithm,
+    function_names: &gix_diff::blob::funcname::Matcher,
+    context: ContextSize,
+) -> std::io::Result<()> {
+    let unified_diff = UnifiedDiff::new(
+        input,
+        GitHunks { out },
+        NewlineSeparator::AfterHeaderAndWhenNeeded("\n"),
+        context,
+    )
+    .with_missing_newline_marker(true)
+    .with_function_names(function_names);
+    gix_diff::blob::diff(algorithm, input, unified_diff)
+}
+
+/// Write hunks with headers exactly like Git, which omits the length if it's 1, and the line number
+/// of empty hunks refers to the line before them.
+struct GitHunks<'a> {
+    out: &'a mut dyn Write,
+}
+
+impl ConsumeHunk for GitHunks<'_> {
+    type Out = ();
+
+    fn consume_hunk(
+        &mut self,
+        before_hunk_start: u32,
+        before_hunk_len: u32,
+        after_hunk_start: u32,
+        after_hunk_len: u32,
+        header: &str,
+        hunk: &[u8],
+    ) -> std::io::Result<()> {
+        let range = |start: u32, len: u32| {
+            let start = if len == 0 { start.saturating_sub(1) } else { start };
+            if len == 1 {
+                start.to_string()
+            } else {
+                format!("{start},{len}")
+            }
+        };
+        let function_name = header
+            .trim_end_matches('\n')
+            .get(2..)
+            .and_then(|header| header.split_once("@@"))
+            .map_or("", |(_, name)| name);
+        writeln!(
+            self.out,
+            "@@ -{} +{} @@{function_name}",
+            range(before_hunk_start, before_hunk_len),
+            range(after_hunk_start, after_hunk_len)
+        )?;
+        self.out.write_all(hunk)
+    }
+
+    fn finish(self) -> Self::Out {}
+}
+
+/// Write the summary of `change` into `out` like `git diff --summary` would, with lines like ` create mode 100644 path`,
+/// ` rename dir/{a => b} (90%)` or ` mode change 100644 => 100755 path`. Nothing is written for plain modifications
+/// and for changes that involve trees only.
+pub fn write_summary(change: Change<'_, '_, '_>, out: &mut dyn Write) -> std::io::Result<()> {
+    match change {
+        Change::Addition {
+            entry_mode, location, ..
+        } if !entry_mode.is_tree() => writeln!(out, " create mode {entry_mode:o} {location}"),
+        Change::Deletion {
+            entry_mode, location, ..
+        } if !entry_mode.is_tree() => writeln!(out, " delete mode {entry_mode:o} {location}"),
+        Change::Modification {
+            previous_entry_mode,
+            entry_mode,
+            location,
+            ..
+        } if !entry_mode.is_tree() && !previous_entry_mode.is_tree() && previous_entry_mode != entry_mode => {
+            writeln!(out, " mode change {previous_entry_mode:o} => {entry_mode:o} {location}")
+        }
+        Change::Rewrite {
+            source_location,
+            source_entry_mode,
+            entry_mode,
+            location,
+            diff,
+            copy,
+            ..
+        } => {
+            let similarity = diff.map_or(100, |diff| (diff.similarity * 100.0) as u32);
+            writeln!(
+                out,
+                " {} {} ({similarity}%)",
+                if copy { "copy" } else { "rename" },
+                super::stats::rename_display_name(source_location, location)
+            )?;
+            if source_entry_mode != entry_mode {
+                writeln!(out, " mode change {source_entry_mode:o} => {entry_mode:o}")?;
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
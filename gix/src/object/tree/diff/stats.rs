@@ -0,0 +1,603 @@
+use std::{borrow::Cow, io::Write};
+
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The error returned by [`stats()`](super::Platform::stats()) and [`stats_per_file()`](super::Platform::stats_per_file()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    CreateResourceCache(#[from] crate::repository::diff_resource_cache::Error),
+    #[error(transparent)]
+    ForEachChange(#[from] crate::object::tree::diff::for_each::Error),
+}
+
+/// Statistics about a single file that changed between two trees.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct File {
+    /// The location of the file after the change, or before the change if it was deleted.
+    pub location: BString,
+    /// The location of the file before the change if it was renamed or copied.
+    pub source_location: Option<BString>,
+    /// The amount of lines that were added, always 0 for binary files.
+    pub lines_added: u64,
+    /// The amount of lines that were removed, always 0 for binary files.
+    pub lines_removed: u64,
+    /// The amount of bytes in lines that were added, or the size of the new version for binary files.
+    pub bytes_added: u64,
+    /// The amount of bytes in lines that were removed, or the size of the old version for binary files.
+    pub bytes_removed: u64,
+    /// The size in bytes of the previous version, or 0 if it didn't exist.
+    pub size_before: u64,
+    /// The size in bytes of the new version, or 0 if it doesn't exist anymore.
+    pub size_after: u64,
+    /// If `true`, at least one side of the change is binary and no line-diff was performed.
+    pub is_binary: bool,
+    /// If `true`, the content of the file is the same, but its mode or location may have changed.
+    pub is_content_unchanged: bool,
+}
+
+/// The statistics of each file changed between two trees, as returned by [`Platform::stats_per_file()`](super::Platform::stats_per_file()).
+///
+/// Files are sorted by their [location](File::location) and are ready to be rendered like
+/// `git diff --stat`, `--numstat`, `--shortstat` and `--dirstat` would.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PerFile {
+    /// All changed files, sorted by location.
+    pub files: Vec<File>,
+}
+
+impl File {
+    /// Compute the statistics of `change`, using `resource_cache` to obtain diffable data and to perform the diff,
+    /// or return `None` if `change` involves trees only.
+    pub fn from_change(
+        change: super::Change<'_, '_, '_>,
+        resource_cache: &mut gix_diff::blob::Platform,
+    ) -> Result<Option<Self>, file::Error> {
+        use super::Change;
+        use gix_diff::blob::platform::{prepare_diff::Operation, resource::Data};
+
+        let (source_location, previous_entry_mode, previous_id, entry_mode, id) = match change {
+            Change::Addition { entry_mode, id, .. } => (None, None, None, Some(entry_mode), Some(id)),
+            Change::Deletion { entry_mode, id, .. } => (None, Some(entry_mode), Some(id), None, None),
+            Change::Modification {
+                previous_entry_mode,
+                previous_id,
+                entry_mode,
+                id,
+                ..
+            } => (
+                None,
+                Some(previous_entry_mode),
+                Some(previous_id),
+                Some(entry_mode),
+                Some(id),
+            ),
+            Change::Rewrite {
+                source_location,
+                source_entry_mode,
+                source_id,
+                entry_mode,
+                id,
+                ..
+            } => (
+                Some(source_location.to_owned()),
+                Some(source_entry_mode),
+                Some(source_id),
+                Some(entry_mode),
+                Some(id),
+            ),
+        };
+        if previous_entry_mode.map_or(true, |mode| mode.is_tree()) && entry_mode.map_or(true, |mode| mode.is_tree()) {
+            return Ok(None);
+        }
+
+        let mut file = File {
+            location: change.location().to_owned(),
+            source_location,
+            lines_added: 0,
+            lines_removed: 0,
+            bytes_added: 0,
+            bytes_removed: 0,
+            size_before: 0,
+            size_after: 0,
+            is_binary: false,
+            is_content_unchanged: previous_id.zip(id).is_some_and(|(a, b)| a == b),
+        };
+        if previous_entry_mode.is_some_and(|mode| mode.is_commit()) || entry_mode.is_some_and(|mode| mode.is_commit()) {
+            // Submodules are displayed as `Subproject commit <id>` line.
+            file.lines_removed = u64::from(previous_entry_mode.is_some_and(|mode| mode.is_commit()));
+            file.lines_added = u64::from(entry_mode.is_some_and(|mode| mode.is_commit()));
+            return Ok(Some(file));
+        }
+
+        let platform = change.diff(resource_cache)?;
+        platform
+            .resource_cache
+            .options
+            .skip_internal_diff_if_external_is_configured = false;
+        let prep = platform.resource_cache.prepare_diff()?;
+        let size = |data: Data<'_>| match data {
+            Data::Missing => 0,
+            Data::Buffer { buf, .. } => buf.len() as u64,
+            Data::Binary { size } => size,
+        };
+        file.size_before = size(prep.old.data);
+        file.size_after = size(prep.new.data);
+        match prep.operation {
+            Operation::InternalDiff { algorithm } => {
+                let input = prep.interned_input();
+                let bytes = |tokens: &[gix_diff::blob::intern::Token]| -> u64 {
+                    tokens.iter().map(|token| input.interner[*token].len() as u64).sum()
+                };
+                gix_diff::blob::diff(
+                    algorithm,
+                    &input,
+                    |before: std::ops::Range<u32>, after: std::ops::Range<u32>| {
+                        file.lines_removed += u64::from(before.end - before.start);
+                        file.lines_added += u64::from(after.end - after.start);
+                        file.bytes_removed += bytes(&input.before[before.start as usize..before.end as usize]);
+                        file.bytes_added += bytes(&input.after[after.start as usize..after.end as usize]);
+                    },
+                );
+            }
+            Operation::ExternalCommand { .. } => {
+                unreachable!("we disabled that")
+            }
+            Operation::SourceOrDestinationIsBinary => {
+                file.is_binary = true;
+                file.bytes_removed = file.size_before;
+                file.bytes_added = file.size_after;
+            }
+        }
+        Ok(Some(file))
+    }
+
+    /// The amount of lines that changed in total.
+    pub fn lines_changed(&self) -> u64 {
+        self.lines_added + self.lines_removed
+    }
+
+    /// Return the name of this file like `git` would display it, which is the location or `dir/{old => new}` for
+    /// rewrites.
+    ///
+    /// Note that the name isn't quoted even if it contains unusual characters.
+    pub fn display_name(&self) -> Cow<'_, BStr> {
+        match &self.source_location {
+            None => Cow::Borrowed(self.location.as_ref()),
+            Some(source) => Cow::Owned(rename_display_name(source.as_ref(), self.location.as_ref())),
+        }
+    }
+}
+
+/// Produce `a => b`, but factor out the common prefix and suffix at directory boundaries, like `dir/{a => b}/file`.
+pub(crate) fn rename_display_name(a: &BStr, b: &BStr) -> BString {
+    let mut prefix_len = 0;
+    for (idx, (ca, cb)) in a.iter().zip(b.iter()).enumerate() {
+        if ca != cb {
+            break;
+        }
+        if *ca == b'/' {
+            prefix_len = idx + 1;
+        }
+    }
+
+    // Compare backwards from the virtual end of both strings, allowing the suffix to share the slash that ends the prefix.
+    let mut suffix_len = 0;
+    let min_pos = prefix_len.saturating_sub(usize::from(prefix_len > 0));
+    let (mut ia, mut ib) = (a.len(), b.len());
+    while ia >= min_pos && ib >= min_pos {
+        let (ca, cb) = (a.get(ia), b.get(ib));
+        if ca != cb {
+            break;
+        }
+        if ca == Some(&b'/') {
+            suffix_len = a.len() - ia;
+        }
+        if ia == 0 || ib == 0 {
+            break;
+        }
+        ia -= 1;
+        ib -= 1;
+    }
+
+    let a_mid = a.len().saturating_sub(prefix_len + suffix_len);
+    let b_mid = b.len().saturating_sub(prefix_len + suffix_len);
+    let mut out = BString::default();
+    let has_common_parts = prefix_len + suffix_len > 0;
+    if has_common_parts {
+        out.extend_from_slice(&a[..prefix_len]);
+        out.push(b'{');
+    }
+    out.extend_from_slice(&a[prefix_len..][..a_mid]);
+    out.extend_from_slice(b" => ");
+    out.extend_from_slice(&b[prefix_len..][..b_mid]);
+    if has_common_parts {
+        out.push(b'}');
+        out.extend_from_slice(&a[a.len() - suffix_len..]);
+    }
+    out
+}
+
+/// Access
+impl PerFile {
+    /// Return the accumulated statistics over all files, which is what `git diff --shortstat` would display.
+    ///
+    /// Note that binary files are counted as changed, but don't contribute to the line counts.
+    pub fn totals(&self) -> super::Stats {
+        self.files.iter().fold(super::Stats::default(), |mut acc, file| {
+            acc.files_changed += 1;
+            acc.lines_added += file.lines_added;
+            acc.lines_removed += file.lines_removed;
+            acc
+        })
+    }
+}
+
+/// Text Rendering
+impl PerFile {
+    /// Write the summary line like `git diff --shortstat` into `out`, like ` 2 files changed, 3 insertions(+), 1 deletion(-)`.
+    pub fn write_shortstat(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        let totals = self.totals();
+        if totals.files_changed == 0 {
+            return writeln!(out, " 0 files changed");
+        }
+        write!(
+            out,
+            " {} file{} changed",
+            totals.files_changed,
+            plural(totals.files_changed)
+        )?;
+        if totals.lines_added != 0 || totals.lines_removed == 0 {
+            write!(
+                out,
+                ", {} insertion{}(+)",
+                totals.lines_added,
+                plural(totals.lines_added)
+            )?;
+        }
+        if totals.lines_removed != 0 || totals.lines_added == 0 {
+            write!(
+                out,
+                ", {} deletion{}(-)",
+                totals.lines_removed,
+                plural(totals.lines_removed)
+            )?;
+        }
+        writeln!(out)
+    }
+
+    /// Write a line for each file like `git diff --numstat` into `out`, like `3\t1\tpath`, or `-\t-\tpath` for binary files.
+    pub fn write_numstat(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        for file in &self.files {
+            if file.is_binary {
+                out.write_all(b"-\t-\t")?;
+            } else {
+                write!(out, "{}\t{}\t", file.lines_added, file.lines_removed)?;
+            }
+            out.write_all(&file.display_name())?;
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Write a line for each file like `git diff --stat` into `out`, followed by the [summary line](Self::write_shortstat()),
+    /// using `options` to control the layout.
+    ///
+    /// Lines look like ` path | 4 +++-`, with names and graphs scaled to fit into the configured width.
+    pub fn write_stat(&self, out: &mut dyn Write, options: stat::Options) -> std::io::Result<()> {
+        let count = options.count.unwrap_or(self.files.len()).min(self.files.len());
+        let files = &self.files[..count];
+        let names: Vec<_> = files
+            .iter()
+            .map(|f| f.display_name().to_str_lossy().into_owned())
+            .collect();
+
+        let (mut max_len, mut max_change, mut number_width, mut bin_width) = (0, 0, 0, 0);
+        for (file, name) in files.iter().zip(&names) {
+            max_len = max_len.max(name.chars().count());
+            if file.is_binary {
+                let width = 14 + decimal_width(file.size_after) + decimal_width(file.size_before);
+                bin_width = bin_width.max(width);
+                number_width = 3;
+                continue;
+            }
+            max_change = max_change.max(file.lines_changed());
+        }
+
+        let number_width = decimal_width(max_change).max(number_width);
+        let width = options.width.max(16 + 6 + number_width);
+        let max_change_width = usize::try_from(max_change).unwrap_or(usize::MAX);
+        let mut graph_width = if max_change_width.saturating_add(4) > bin_width {
+            max_change_width
+        } else {
+            bin_width - 4
+        };
+        if let Some(limit) = options.graph_width.filter(|w| *w > 0) {
+            graph_width = graph_width.min(limit);
+        }
+        let mut name_width = options.name_width.filter(|w| *w > 0 && *w < max_len).unwrap_or(max_len);
+
+        if name_width + number_width + 6 + graph_width > width {
+            let max_graph_width = (width * 3 / 8).saturating_sub(number_width + 6);
+            if graph_width > max_graph_width {
+                graph_width = max_graph_width.max(6);
+            }
+            if let Some(limit) = options.graph_width.filter(|w| *w > 0) {
+                graph_width = graph_width.min(limit);
+            }
+            let available = width.saturating_sub(number_width + 6 + graph_width);
+            if name_width > available {
+                name_width = available;
+            } else {
+                graph_width = width - number_width - 6 - name_width;
+            }
+        }
+
+        for (file, name) in files.iter().zip(&names) {
+            let (prefix, name) = scale_name(name, name_width);
+            let padding = name_width.saturating_sub(prefix.len() + name.chars().count());
+            write!(out, " {prefix}{name}{:padding$} | ", "")?;
+            if file.is_binary {
+                write!(out, "{:>number_width$}", "Bin")?;
+                if file.size_before != 0 || file.size_after != 0 {
+                    write!(out, " {} -> {} bytes", file.size_before, file.size_after)?;
+                }
+                writeln!(out)?;
+                continue;
+            }
+
+            let changed = file.lines_changed();
+            let (mut add, mut del) = (file.lines_added, file.lines_removed);
+            let graph_width = graph_width as u64;
+            if graph_width <= max_change {
+                let mut total = scale_linear(add + del, graph_width, max_change);
+                if total < 2 && add != 0 && del != 0 {
+                    total = 2;
+                }
+                if add < del {
+                    add = scale_linear(add, graph_width, max_change);
+                    del = total - add;
+                } else {
+                    del = scale_linear(del, graph_width, max_change);
+                    add = total - del;
+                }
+            }
+            write!(
+                out,
+                "{changed:>number_width$}{space}{plus}{minus}",
+                space = if changed != 0 { " " } else { "" },
+                plus = "+".repeat(add as usize),
+                minus = "-".repeat(del as usize),
+            )?;
+            writeln!(out)?;
+        }
+        if count < self.files.len() {
+            writeln!(out, " ...")?;
+        }
+        self.write_shortstat(out)
+    }
+
+    /// Compute the distribution of changes across directories like `git diff --dirstat` would, configured by `options`.
+    ///
+    /// Directories are returned in the order Git would print them, i.e. sorted by path with subdirectories before their parents.
+    pub fn dirstat(&self, options: dirstat::Options) -> Vec<dirstat::Directory> {
+        let mut files: Vec<(&BStr, u64)> = self
+            .files
+            .iter()
+            .map(|file| {
+                let damage = match options.mode {
+                    dirstat::Mode::Changes => {
+                        if file.is_content_unchanged {
+                            0
+                        } else if file.is_binary {
+                            // We can't know which bytes were retained, so count it as rewrite.
+                            file.size_before.max(file.size_after).max(1)
+                        } else {
+                            (file.bytes_added + file.bytes_removed).max(1)
+                        }
+                    }
+                    dirstat::Mode::Lines => {
+                        if file.is_binary {
+                            (file.size_before + file.size_after).div_ceil(64)
+                        } else {
+                            file.lines_changed()
+                        }
+                    }
+                    dirstat::Mode::Files => u64::from(!file.is_content_unchanged),
+                };
+                (file.location.as_ref(), damage)
+            })
+            .collect();
+        let total: u64 = files.iter().map(|(_, damage)| damage).sum();
+        let mut out = Vec::new();
+        if total == 0 {
+            return out;
+        }
+        files.sort_by(|a, b| a.0.cmp(b.0));
+        let mut files = files.as_slice();
+        gather_dirstat(&mut files, "".into(), total, &options, &mut out);
+        out
+    }
+
+    /// Write the result of [`dirstat()`](Self::dirstat()) like `git diff --dirstat` into `out`, like `  42.5% dir/`.
+    pub fn write_dirstat(&self, out: &mut dyn Write, options: dirstat::Options) -> std::io::Result<()> {
+        for dir in self.dirstat(options) {
+            write!(out, "{:>4}.{}% ", dir.permille / 10, dir.permille % 10)?;
+            out.write_all(&dir.path)?;
+            out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+fn gather_dirstat(
+    files: &mut &[(&BStr, u64)],
+    base: &BStr,
+    total: u64,
+    options: &dirstat::Options,
+    out: &mut Vec<dirstat::Directory>,
+) -> u64 {
+    let (mut sum, mut sources) = (0, 0);
+    while let Some((name, damage)) = files.first().copied() {
+        if !name.starts_with(base) {
+            break;
+        }
+        let changes = match name[base.len()..].find_byte(b'/') {
+            Some(pos) => {
+                sources += 1;
+                gather_dirstat(files, name[..base.len() + pos + 1].as_bstr(), total, options, out)
+            }
+            None => {
+                *files = &files[1..];
+                sources += 2;
+                damage
+            }
+        };
+        sum += changes;
+    }
+
+    // The top-level isn't reported, nor are directories whose changes all come from a single subdirectory.
+    if !base.is_empty() && sources != 1 && sum != 0 {
+        let permille = u32::try_from(sum * 1000 / total).expect("at most 1000");
+        if permille >= options.permille {
+            out.push(dirstat::Directory {
+                path: base.to_owned(),
+                permille,
+            });
+            if !options.cumulative {
+                return 0;
+            }
+        }
+    }
+    sum
+}
+
+fn plural(n: u64) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+fn decimal_width(mut n: u64) -> usize {
+    let mut width = 1;
+    while n >= 10 {
+        n /= 10;
+        width += 1;
+    }
+    width
+}
+
+/// Scale `it` so that `max_change` fits into `width`, but assure that anything that changed is at least 1.
+fn scale_linear(it: u64, width: u64, max_change: u64) -> u64 {
+    if it == 0 {
+        return 0;
+    }
+    1 + (it * (width - 1) / max_change)
+}
+
+/// Shorten `name` from the front so it fits into `width` characters, returning a `...` prefix if that was necessary.
+fn scale_name(name: &str, width: usize) -> (&'static str, &str) {
+    let len = name.chars().count();
+    if len <= width {
+        return ("", name);
+    }
+    let keep = width.saturating_sub(3);
+    let name = name.char_indices().nth(len - keep).map_or("", |(pos, _)| &name[pos..]);
+    let name = name.find('/').map_or(name, |pos| &name[pos..]);
+    ("...", name)
+}
+
+///
+pub mod file {
+    /// The error returned by [`File::from_change()`](super::File::from_change()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        SetResource(#[from] crate::object::blob::diff::init::Error),
+        #[error(transparent)]
+        PrepareDiff(#[from] gix_diff::blob::platform::prepare_diff::Error),
+    }
+}
+
+///
+pub mod stat {
+    /// Options for use in [`PerFile::write_stat()`](super::PerFile::write_stat()).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Options {
+        /// The total amount of columns to use, like `--stat-width`, defaulting to 80.
+        pub width: usize,
+        /// The maximum amount of columns to use for the name of each file, like `--stat-name-width`.
+        /// If `None`, names can take all the space they need unless they don't fit into `width`.
+        pub name_width: Option<usize>,
+        /// The maximum amount of columns to use for the graph of `+` and `-`, like `--stat-graph-width`.
+        pub graph_width: Option<usize>,
+        /// The maximum amount of files to show, like `--stat-count`. The summary line counts all files.
+        pub count: Option<usize>,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                width: 80,
+                name_width: None,
+                graph_width: None,
+                count: None,
+            }
+        }
+    }
+}
+
+///
+pub mod dirstat {
+    use crate::bstr::BString;
+
+    /// Determine how to count the damage done to each file.
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Mode {
+        /// Count the amount of bytes in lines that were added or removed, like `--dirstat=changes`.
+        ///
+        /// This is an approximation of what Git does, which counts the bytes that were removed from the source and added
+        /// to the destination while ignoring moves of lines within a file.
+        /// Modified binary files are counted as if they were rewritten entirely.
+        #[default]
+        Changes,
+        /// Count the lines that were added or removed, like `--dirstat=lines`.
+        Lines,
+        /// Count each changed file once, like `--dirstat=files`.
+        Files,
+    }
+
+    /// Options for use in [`PerFile::dirstat()`](super::PerFile::dirstat()).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Options {
+        /// The way to count changes.
+        pub mode: Mode,
+        /// If `true`, changes in subdirectories are also counted towards their parent directories, like `--dirstat=cumulative`.
+        pub cumulative: bool,
+        /// The minimal amount of changes in permille that a directory needs to be shown, defaulting to 30, i.e. 3%.
+        pub permille: u32,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                mode: Mode::default(),
+                cumulative: false,
+                permille: 30,
+            }
+        }
+    }
+
+    /// A directory along with the portion of changes it received.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct Directory {
+        /// The path of the directory relative to the root of the repository, with a trailing slash.
+        pub path: BString,
+        /// The amount of changes it received in relation to all changes, from 0 to 1000.
+        pub permille: u32,
+    }
+}
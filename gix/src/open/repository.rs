@@ -462,6 +462,7 @@ impl ThreadSafeRepository {
                     object_hash: config.object_hash,
                     use_multi_pack_index: config.use_multi_pack_index,
                     current_dir: current_dir.to_owned().into(),
+                    pack_backing: config.pack_backing.clone(),
                 },
             )?),
             common_dir,
@@ -1,6 +1,9 @@
 use super::Error;
 use crate::{
-    config::{cache::util::ApplyLeniency, tree::Pack},
+    config::{
+        cache::util::ApplyLeniency,
+        tree::{CommitGraph, Fetch, Pack},
+    },
     Repository,
 };
 
@@ -24,3 +27,27 @@ pub fn pack_index_version(repo: &Repository) -> Result<gix_pack::index::Version,
         .with_leniency(repo.options.lenient_config)?
         .unwrap_or(gix_pack::index::Version::V2))
 }
+
+pub fn write_commit_graph(repo: &Repository) -> Result<bool, Error> {
+    Ok(repo
+        .config
+        .resolved
+        .boolean_filter(Fetch::WRITE_COMMIT_GRAPH, &mut repo.filter_config_section())
+        .map(|value| Fetch::WRITE_COMMIT_GRAPH.enrich_error(value))
+        .transpose()
+        .with_leniency(repo.options.lenient_config)
+        .map_err(Error::WriteCommitGraphConfig)?
+        .unwrap_or(false))
+}
+
+pub fn write_corrected_commit_dates(repo: &Repository) -> Result<bool, Error> {
+    Ok(repo
+        .config
+        .resolved
+        .integer_filter(CommitGraph::GENERATION_VERSION, &mut repo.filter_config_section())
+        .map(|value| CommitGraph::GENERATION_VERSION.try_into_u64(value))
+        .transpose()
+        .with_leniency(repo.options.lenient_config)
+        .map_err(Error::CommitGraphGenerationVersionConfig)?
+        .map_or(true, |version| version == 2))
+}
@@ -36,6 +36,17 @@ pub enum Error {
     RejectShallowRemoteConfig(#[from] config::boolean::Error),
     #[error(transparent)]
     NegotiationAlgorithmConfig(#[from] config::key::GenericErrorWithValue),
+    #[error("Could not obtain configuration to learn if the remote is a promisor")]
+    PromisorConfig(#[source] config::boolean::Error),
+    #[error("Failed to write .promisor file at \"{}\"", path.display())]
+    WritePromisorFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not obtain configuration to learn if the commit-graph should be written")]
+    WriteCommitGraphConfig(#[source] config::boolean::Error),
+    #[error("Could not obtain configuration to learn which generation numbers to write into the commit-graph")]
+    CommitGraphGenerationVersionConfig(#[source] config::unsigned_integer::Error),
 }
 
 impl gix_protocol::transport::IsSpuriousError for Error {
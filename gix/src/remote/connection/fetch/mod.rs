@@ -150,6 +150,7 @@ where
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
             shallow: Default::default(),
+            filter: None,
         })
     }
 }
@@ -181,6 +182,7 @@ where
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
     shallow: remote::fetch::Shallow,
+    filter: Option<BString>,
 }
 
 /// Builder
@@ -222,4 +224,14 @@ where
         self.shallow = shallow;
         self
     }
+
+    /// Ask the remote to omit objects according to the filter `spec`, like `blob:none`, and mark the received pack
+    /// as promisor pack.
+    ///
+    /// If unset, the filter is read from `remote.<name>.partialCloneFilter` if the remote is configured as promisor
+    /// via `remote.<name>.promisor`.
+    pub fn with_filter(mut self, spec: Option<BString>) -> Self {
+        self.filter = spec;
+        self
+    }
 }
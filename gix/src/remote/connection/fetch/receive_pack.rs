@@ -1,4 +1,4 @@
-use std::{ops::DerefMut, path::PathBuf, sync::atomic::AtomicBool};
+use std::{borrow::Cow, ops::DerefMut, path::PathBuf, sync::atomic::AtomicBool};
 
 use gix_odb::store::RefreshMode;
 use gix_protocol::{
@@ -7,9 +7,10 @@ use gix_protocol::{
 };
 
 use crate::{
+    bstr::ByteSlice,
     config::{
         cache::util::ApplyLeniency,
-        tree::{Clone, Fetch},
+        tree::{Clone, Fetch, Remote},
     },
     remote,
     remote::{
@@ -97,6 +98,40 @@ where
             });
         }
 
+        let (filter, is_promisor) = match self.filter.take() {
+            Some(spec) => (Some(spec), true),
+            None => match con.remote.name().and_then(remote::Name::as_symbol) {
+                Some(name) => {
+                    let is_promisor = repo
+                        .config
+                        .resolved
+                        .boolean_filter_by(
+                            "remote",
+                            Some(name.into()),
+                            Remote::PROMISOR.name,
+                            &mut repo.filter_config_section(),
+                        )
+                        .map(|val| Remote::PROMISOR.enrich_error(val))
+                        .transpose()
+                        .map_err(Error::PromisorConfig)?
+                        .unwrap_or(false);
+                    let filter = is_promisor
+                        .then(|| {
+                            repo.config.resolved.string_filter_by(
+                                "remote",
+                                Some(name.into()),
+                                Remote::PARTIAL_CLONE_FILTER.name,
+                                &mut repo.filter_config_section(),
+                            )
+                        })
+                        .flatten()
+                        .map(Cow::into_owned);
+                    (filter, is_promisor)
+                }
+                None => (None, false),
+            },
+        };
+        let filter = filter.as_ref().map(|spec| spec.to_str_lossy());
         let fetch_options = gix_protocol::fetch::Options {
             shallow_file: repo.shallow_file(),
             shallow: &self.shallow,
@@ -108,6 +143,7 @@ where
                 .map(|val| Clone::REJECT_SHALLOW.enrich_error(val))
                 .transpose()?
                 .unwrap_or(false),
+            filter: filter.as_deref(),
         };
         let context = gix_protocol::fetch::Context {
             handshake: &mut handshake,
@@ -209,6 +245,16 @@ where
             self.write_packed_refs,
         )?;
 
+        if let Some(data_path) = write_pack_bundle
+            .as_ref()
+            .filter(|_| is_promisor)
+            .and_then(|bundle| bundle.data_path.as_ref())
+        {
+            // Objects reachable from promisor packs may be missing, they are fetched on demand instead.
+            let path = data_path.with_extension("promisor");
+            std::fs::write(&path, b"").map_err(|source| Error::WritePromisorFile { path, source })?;
+        }
+
         if let Some(bundle) = write_pack_bundle.as_mut() {
             if !update_refs.edits.is_empty() || bundle.index.num_objects == 0 {
                 if let Some(path) = bundle.keep_path.take() {
@@ -217,6 +263,17 @@ where
             }
         }
 
+        if write_pack_bundle.is_some() && !repo.is_shallow() && config::write_commit_graph(repo)? {
+            // Like `git`, don't fail the fetch as the refs were already updated, and the commit-graph is only a cache.
+            if let Err(_err) = repo.write_commit_graph(gix_commitgraph::write::info_dir::Options {
+                split: Some(gix_commitgraph::write::info_dir::Split::MergeLayers),
+                corrected_commit_dates: config::write_corrected_commit_dates(repo)?,
+                ..Default::default()
+            }) {
+                gix_trace::warn!("Could not write the commit-graph after fetching: {}", _err);
+            }
+        }
+
         let out = Outcome {
             handshake,
             ref_map: std::mem::take(&mut self.ref_map),
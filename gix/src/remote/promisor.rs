@@ -0,0 +1,71 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use gix_hash::ObjectId;
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    config::tree::{Extensions, Fetch},
+    remote,
+};
+
+/// A [promisor](gix_odb::store::promisor::Fetch) that lazily fetches missing objects from the remote configured in
+/// `extensions.partialClone`, similar to what `git` does in partial clones.
+///
+/// Each fetch opens the repository anew to fetch exactly the requested objects without updating any reference,
+/// using the `noop` negotiation algorithm and the filter configured in `remote.<name>.partialCloneFilter`.
+/// The received pack is marked with a `.promisor` file.
+#[derive(Clone)]
+pub struct Remote {
+    /// The repository to fetch into.
+    pub git_dir: PathBuf,
+    /// The options to use when opening the repository at `git_dir`.
+    pub options: crate::open::Options,
+    /// The name of the promisor remote to fetch from.
+    pub name: BString,
+}
+
+impl Remote {
+    /// Create an instance for `repo` if it is a partial clone, i.e. if `extensions.partialClone` names a remote.
+    pub fn from_repo(repo: &crate::Repository) -> Option<Self> {
+        Self::from_config(repo.git_dir().to_owned(), repo.options.clone(), &repo.config)
+    }
+
+    pub(crate) fn from_config(
+        git_dir: PathBuf,
+        options: crate::open::Options,
+        config: &crate::config::Cache,
+    ) -> Option<Self> {
+        let name = config.resolved.string(Extensions::PARTIAL_CLONE)?;
+        Some(Remote {
+            git_dir,
+            options,
+            name: name.into_owned(),
+        })
+    }
+}
+
+impl gix_odb::store::promisor::Fetch for Remote {
+    fn fetch(&self, ids: &[ObjectId]) -> Result<(), gix_odb::store::promisor::Error> {
+        let mut repo = crate::open_opts(&self.git_dir, self.options.clone())?;
+        // Objects that are still missing while receiving the pack must not cause a fetch on their own.
+        repo.objects.promisor = None;
+        {
+            let mut config = repo.config_snapshot_mut();
+            config.set_value(&Fetch::NEGOTIATION_ALGORITHM, "noop")?;
+            config.commit()?;
+        }
+
+        let mut remote = repo
+            .find_remote(self.name.as_bstr())?
+            .with_fetch_tags(remote::fetch::Tags::None);
+        remote.replace_refspecs(
+            ids.iter().map(|id| BString::from(id.to_string())),
+            remote::Direction::Fetch,
+        )?;
+        remote
+            .connect(remote::Direction::Fetch)?
+            .prepare_fetch(gix_features::progress::Discard, Default::default())?
+            .receive(gix_features::progress::Discard, &AtomicBool::default())?;
+        Ok(())
+    }
+}
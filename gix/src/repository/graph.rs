@@ -42,4 +42,114 @@ impl crate::Repository {
                 _ => Err(err),
             })?)
     }
+
+    /// Write all commits reachable from all references that aren't yet part of the commit-graph into the commit-graph files
+    /// of this repository according to `options`, similar to `git commit-graph write --reachable`.
+    ///
+    /// Note that `options.object_hash` is set to the object hash of this repository, and that commits which already are in the
+    /// commit-graph are retained even if they aren't reachable anymore.
+    /// Use [`Split::MergeLayers`](gix_commitgraph::write::info_dir::Split::MergeLayers) to cheaply add new commits to the
+    /// commit-graph chain, for instance after a fetch.
+    ///
+    /// Changed-path Bloom filters are computed for all new commits if `options.bloom_filter_settings` is set, or if the
+    /// existing commit-graph has such filters, in which case their settings are used.
+    pub fn write_commit_graph(
+        &self,
+        mut options: gix_commitgraph::write::info_dir::Options,
+    ) -> Result<gix_commitgraph::write::info_dir::Outcome, super::write_commit_graph::Error> {
+        use super::write_commit_graph::Error;
+
+        let graph = self.commit_graph().ok();
+        options.bloom_filter_settings = options
+            .bloom_filter_settings
+            .or_else(|| graph.as_ref().and_then(gix_commitgraph::Graph::bloom_filter_settings));
+        let mut queue = Vec::new();
+        for reference in self.references()?.all()? {
+            let mut reference = reference.map_err(Error::ReadReference)?;
+            queue.push(reference.peel_to_id_in_place()?.detach());
+        }
+
+        let mut seen = gix_hashtable::HashSet::default();
+        let mut commits = Vec::new();
+        let mut diff_state = gix_diff::tree::State::default();
+        while let Some(id) = queue.pop() {
+            if !seen.insert(id) || graph.as_ref().is_some_and(|graph| graph.lookup(id).is_some()) {
+                continue;
+            }
+            let object = self.find_object(id)?;
+            if object.kind != gix_object::Kind::Commit {
+                continue;
+            }
+            let commit = object.into_commit();
+            let parents: Vec<_> = commit.parent_ids().map(crate::Id::detach).collect();
+            queue.extend(parents.iter().copied());
+            let tree = commit.tree_id().map_err(crate::object::commit::Error::from)?.detach();
+            let bloom_filter = options
+                .bloom_filter_settings
+                .map(|settings| self.compute_bloom_filter(tree, parents.first().copied(), &settings, &mut diff_state))
+                .transpose()?;
+            commits.push(gix_commitgraph::write::Commit {
+                id,
+                tree,
+                parents,
+                committer_timestamp: commit.time()?.seconds.try_into().unwrap_or_default(),
+                bloom_filter,
+            });
+        }
+        drop(graph);
+
+        options.object_hash = self.object_hash();
+        Ok(gix_commitgraph::write::to_info_dir(
+            &self.objects.store_ref().path().join("info"),
+            commits,
+            options,
+        )?)
+    }
+
+    /// Compute the changed-path Bloom filter of the commit with `tree` and `first_parent` using `settings`.
+    fn compute_bloom_filter(
+        &self,
+        tree: gix_hash::ObjectId,
+        first_parent: Option<gix_hash::ObjectId>,
+        settings: &gix_commitgraph::bloom::Settings,
+        state: &mut gix_diff::tree::State,
+    ) -> Result<Vec<u8>, super::write_commit_graph::Error> {
+        use gix_object::FindExt;
+
+        fn find_tree<'a>(
+            objects: &impl gix_object::Find,
+            id: gix_hash::ObjectId,
+            buf: &'a mut Vec<u8>,
+        ) -> Result<gix_object::TreeRefIter<'a>, gix_object::find::existing_iter::Error> {
+            if id.is_empty_tree() {
+                Ok(gix_object::TreeRefIter::from_bytes(&[]))
+            } else {
+                objects.find_tree_iter(&id, buf)
+            }
+        }
+        let (mut lhs_buf, mut rhs_buf) = (Vec::new(), Vec::new());
+        let lhs = match first_parent {
+            Some(parent) => {
+                let parent_tree = self
+                    .find_object(parent)?
+                    .into_commit()
+                    .tree_id()
+                    .map_err(crate::object::commit::Error::from)?;
+                find_tree(&self.objects, parent_tree.detach(), &mut lhs_buf)?
+            }
+            None => gix_object::TreeRefIter::from_bytes(&[]),
+        };
+        let rhs = find_tree(&self.objects, tree, &mut rhs_buf)?;
+        let mut recorder = gix_diff::tree::Recorder::default();
+        gix_diff::tree(lhs, rhs, state, &self.objects, &mut recorder)?;
+        Ok(gix_commitgraph::bloom::compute_filter(
+            recorder.records.iter().map(|change| match change {
+                gix_diff::tree::recorder::Change::Addition { path, .. }
+                | gix_diff::tree::recorder::Change::Deletion { path, .. }
+                | gix_diff::tree::recorder::Change::Modification { path, .. } => path.as_ref(),
+            }),
+            settings,
+            gix_commitgraph::bloom::DEFAULT_MAX_CHANGED_PATHS,
+        ))
+    }
 }
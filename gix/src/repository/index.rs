@@ -66,13 +66,16 @@ impl crate::Repository {
     }
 
     /// Return the shared worktree index if present, or return a new empty one which has an association to the place where the index would be.
+    ///
+    /// The version of a new index is controlled by `index.version`.
     pub fn index_or_empty(&self) -> Result<worktree::Index, worktree::open_index::Error> {
-        Ok(self.try_index()?.unwrap_or_else(|| {
-            worktree::Index::new(gix_fs::FileSnapshot::new(gix_index::File::from_state(
-                gix_index::State::new(self.object_hash()),
+        Ok(match self.try_index()? {
+            Some(index) => index,
+            None => worktree::Index::new(gix_fs::FileSnapshot::new(gix_index::File::from_state(
+                self.empty_index_state()?,
                 self.index_path(),
-            )))
-        }))
+            ))),
+        })
     }
 
     /// Return a shared worktree index which is updated automatically if the in-memory snapshot has become stale as the underlying file
@@ -133,7 +136,7 @@ impl crate::Repository {
                     IndexPersistedOrInMemory::InMemory(self.index_from_tree(&head_tree_id)?)
                 }
                 None => IndexPersistedOrInMemory::InMemory(gix_index::File::from_state(
-                    gix_index::State::new(self.object_hash()),
+                    self.empty_index_state()?,
                     self.index_path(),
                 )),
             },
@@ -144,15 +147,21 @@ impl crate::Repository {
     ///
     /// Note that this is an expensive operation as it requires recursively traversing the entire tree to unpack it into the index.
     pub fn index_from_tree(&self, tree: &gix_hash::oid) -> Result<gix_index::File, super::index_from_tree::Error> {
-        Ok(gix_index::File::from_state(
-            gix_index::State::from_tree(tree, self, self.config.protect_options()?).map_err(|err| {
-                super::index_from_tree::Error::IndexFromTree {
-                    id: tree.into(),
-                    source: err,
-                }
-            })?,
-            self.git_dir().join("index"),
-        ))
+        let mut state = gix_index::State::from_tree(tree, self, self.config.protect_options()?).map_err(|err| {
+            super::index_from_tree::Error::IndexFromTree {
+                id: tree.into(),
+                source: err,
+            }
+        })?;
+        state.set_version(self.config.index_version()?);
+        Ok(gix_index::File::from_state(state, self.git_dir().join("index")))
+    }
+
+    /// Create a new empty index state with the version configured by `index.version`.
+    fn empty_index_state(&self) -> Result<gix_index::State, crate::config::index_version::Error> {
+        let mut state = gix_index::State::new(self.object_hash());
+        state.set_version(self.config.index_version()?);
+        Ok(state)
     }
 }
 
@@ -14,6 +14,8 @@ impl crate::Repository {
         #[cfg(feature = "attributes")] modules: crate::submodule::ModulesFileStorage,
     ) -> Self {
         setup_objects(&mut objects, &config);
+        #[cfg(feature = "blocking-network-client")]
+        setup_promisor(&mut objects, refs.git_dir(), &config, &linked_worktree_options);
         crate::Repository {
             bufs: Some(RefCell::new(Vec::with_capacity(4))),
             work_tree,
@@ -36,6 +38,18 @@ impl crate::Repository {
     }
 }
 
+/// Fetch missing objects lazily from the promisor remote in partial clones.
+#[cfg(feature = "blocking-network-client")]
+pub(crate) fn setup_promisor(
+    objects: &mut crate::OdbHandle,
+    git_dir: &std::path::Path,
+    config: &crate::config::Cache,
+    options: &crate::open::Options,
+) {
+    objects.promisor = crate::remote::promisor::Remote::from_config(git_dir.to_owned(), options.clone(), config)
+        .map(|remote| std::sync::Arc::new(remote) as _);
+}
+
 #[cfg_attr(not(feature = "max-performance-safe"), allow(unused_variables, unused_mut))]
 pub(crate) fn setup_objects(objects: &mut crate::OdbHandle, config: &crate::config::Cache) {
     #[cfg(feature = "max-performance-safe")]
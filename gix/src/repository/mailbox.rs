@@ -0,0 +1,376 @@
+use std::collections::BTreeMap;
+
+use gix_hash::ObjectId;
+use gix_object::tree::EntryKind;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    ext::TreeDiffChangeExt,
+    object::tree::diff::{patch, stats, Action},
+    repository::{apply_mailbox, format_patch},
+    Repository,
+};
+
+/// Patch emails
+impl Repository {
+    /// Render each commit in `commits`, which are expected to be in the order they should be applied in,
+    /// as patch email like `git format-patch` would, and return them along with the file name Git would use.
+    ///
+    /// Each commit is compared to its first parent, or to the empty tree if there is none. Rewrites are tracked as configured.
+    /// If a [cover letter](format_patch::Options::cover_letter) is requested, it's the first email with number 0,
+    /// showing a shortlog and statistics of all changes between the first parent of the first commit and the last commit.
+    pub fn format_patch(
+        &self,
+        commits: impl IntoIterator<Item = impl Into<ObjectId>>,
+        options: &format_patch::Options,
+    ) -> Result<Vec<format_patch::Email>, format_patch::Error> {
+        let commits = commits
+            .into_iter()
+            .map(|id| self.find_commit(id))
+            .collect::<Result<Vec<_>, _>>()?;
+        let total = commits.len();
+        let numbered = options.numbered.unwrap_or(total > 1) || options.cover_letter;
+
+        let mut emails = Vec::with_capacity(total + usize::from(options.cover_letter));
+        if options.cover_letter && total > 0 {
+            emails.push(self.format_cover_letter(&commits, options)?);
+        }
+        for (idx, commit) in commits.iter().enumerate() {
+            let commit_ref = commit.decode()?;
+            let base_tree = match commit_ref.parents().next() {
+                Some(parent) => self.find_commit(parent)?.tree()?,
+                None => self.empty_tree(),
+            };
+            let diff = self.format_patch_diff(&base_tree, &commit.tree()?, options, true)?;
+
+            let mut data = Vec::new();
+            let time = commit_ref.author.time()?;
+            gix_mailbox::write::Patch {
+                id: &commit.id,
+                author: commit_ref.author.trim().actor(),
+                time,
+                subject_prefix: &options.subject_prefix,
+                number: numbered.then_some((idx + 1, total)),
+                message: commit_ref.message,
+                diff: Some(&diff),
+                signature: options.signature.as_deref(),
+            }
+            .write_to(&mut data)?;
+            emails.push(format_patch::Email {
+                file_name: file_name(idx + 1, commit_ref.message),
+                data,
+            });
+        }
+        Ok(emails)
+    }
+
+    /// Produce the statistics and summary of the changes between `lhs` and `rhs`, optionally followed by the patch itself.
+    fn format_patch_diff(
+        &self,
+        lhs: &crate::Tree<'_>,
+        rhs: &crate::Tree<'_>,
+        options: &format_patch::Options,
+        with_patch: bool,
+    ) -> Result<Vec<u8>, format_patch::Error> {
+        let mut changes = Vec::new();
+        let mut platform = lhs.changes()?;
+        platform.for_each_to_obtain_tree(rhs, |change| {
+            changes.push(change.detach());
+            Ok::<_, std::convert::Infallible>(Action::Continue)
+        })?;
+        changes.sort_by(|a, b| a.location().cmp(b.location()));
+
+        let mut resource_cache = self.diff_resource_cache_for_tree_diff()?;
+        let mut stats = stats::PerFile::default();
+        for change in &changes {
+            if let Some(file) = stats::File::from_change(change.attach(self, self), &mut resource_cache)? {
+                stats.files.push(file);
+            }
+            resource_cache.clear_resource_cache_keep_allocation();
+        }
+
+        let mut out = Vec::new();
+        stats.write_stat(&mut out, options.stat)?;
+        for change in &changes {
+            patch::write_summary(change.attach(self, self), &mut out)?;
+        }
+        if with_patch {
+            out.push(b'\n');
+            for change in &changes {
+                patch::write_change(change.attach(self, self), &mut resource_cache, &mut out, options.patch)?;
+                resource_cache.clear_resource_cache_keep_allocation();
+            }
+        }
+        Ok(out)
+    }
+
+    fn format_cover_letter(
+        &self,
+        commits: &[crate::Commit<'_>],
+        options: &format_patch::Options,
+    ) -> Result<format_patch::Email, format_patch::Error> {
+        let mut shortlog = BTreeMap::<BString, Vec<BString>>::new();
+        for commit in commits {
+            let commit = commit.decode()?;
+            shortlog
+                .entry(commit.author.trim().name.to_owned())
+                .or_default()
+                .push(subject(commit.message));
+        }
+        let mut message = BString::from("*** SUBJECT HERE ***\n\n*** BLURB HERE ***\n\n");
+        for (author, subjects) in shortlog {
+            message.extend_from_slice(&author);
+            message.extend_from_slice(format!(" ({}):\n", subjects.len()).as_bytes());
+            for subject in subjects {
+                wrap(&mut message, subject.as_ref(), 72, 2, 4);
+            }
+            message.push(b'\n');
+        }
+
+        let first = commits.first().expect("non-empty").decode()?;
+        let base_tree = match first.parents().next() {
+            Some(parent) => self.find_commit(parent)?.tree()?,
+            None => self.empty_tree(),
+        };
+        let last_tree = commits.last().expect("non-empty").tree()?;
+        message.extend_from_slice(&self.format_patch_diff(&base_tree, &last_tree, options, false)?);
+
+        let committer = self
+            .committer()
+            .ok_or(format_patch::Error::CommitterMissing)??;
+        let mut data = Vec::new();
+        gix_mailbox::write::Patch {
+            id: &commits.last().expect("non-empty").id,
+            author: committer.actor(),
+            time: committer.time()?,
+            subject_prefix: &options.subject_prefix,
+            number: Some((0, commits.len())),
+            message: message.as_ref(),
+            diff: None,
+            signature: options.signature.as_deref(),
+        }
+        .write_to(&mut data)?;
+        Ok(format_patch::Email {
+            file_name: "0000-cover-letter.patch".into(),
+            data,
+        })
+    }
+
+    /// Apply each patch in `messages` on top of the commit `base` and create a commit for each of them, with the
+    /// author and message of the email, similar to what `git am` does.
+    ///
+    /// The committer is obtained from the configuration. If a patch doesn't apply and [three-way merges](apply_mailbox::Options::three_way)
+    /// are enabled, the blobs mentioned in the `index` lines of the patch are used to reconstruct the tree the patch was created for,
+    /// which is then merged with the current tree, just like `git am --3way` would.
+    ///
+    /// No reference, index or worktree is changed, so it's up to the caller to update these to the last commit in the returned list
+    /// of newly created commits.
+    pub fn apply_mailbox(
+        &self,
+        base: impl Into<ObjectId>,
+        messages: impl IntoIterator<Item = gix_mailbox::Message>,
+        options: apply_mailbox::Options,
+    ) -> Result<Vec<ObjectId>, apply_mailbox::Error> {
+        let committer = self
+            .committer()
+            .ok_or(apply_mailbox::Error::CommitterMissing)??
+            .to_owned()?;
+        let mut tip = base.into();
+        let mut commits = Vec::new();
+        for message in messages {
+            let files = gix_diff::patch::parse(&message.patch)?;
+            if files.is_empty() {
+                return Err(apply_mailbox::Error::EmptyPatch {
+                    subject: message.subject,
+                });
+            }
+            let our_tree = self.find_commit(tip)?.tree_id()?.detach();
+            let tree = match self.apply_patch_to_tree(our_tree, &files) {
+                Ok(tree) => tree,
+                Err(apply_mailbox::Error::Apply { .. } | apply_mailbox::Error::MissingPreimage { .. })
+                    if options.three_way =>
+                {
+                    self.apply_patch_three_way(our_tree, &files, message.subject.as_ref())?
+                }
+                Err(err) => return Err(err),
+            };
+
+            let commit = gix_object::Commit {
+                tree,
+                parents: [tip].into_iter().collect(),
+                author: message.author.clone(),
+                committer: committer.clone(),
+                encoding: None,
+                message: message.commit_message(),
+                extra_headers: Vec::new(),
+            };
+            tip = self.write_object(&commit)?.detach();
+            commits.push(tip);
+        }
+        Ok(commits)
+    }
+
+    /// Apply `files` to the tree with `id`, and return the id of the written tree.
+    fn apply_patch_to_tree(&self, id: ObjectId, files: &[gix_diff::patch::File]) -> Result<ObjectId, apply_mailbox::Error> {
+        let mut editor = self.edit_tree(id)?;
+        for file in files {
+            let old = match &file.old_path {
+                Some(path) => {
+                    let entry = editor
+                        .get(path.as_bstr())
+                        .filter(|entry| entry.mode().is_blob_or_symlink())
+                        .ok_or_else(|| apply_mailbox::Error::MissingPreimage { path: path.clone() })?;
+                    Some((entry.mode().kind(), entry.object_id()))
+                }
+                None => None,
+            };
+            let new_path = file.new_path.as_ref();
+            if let Some(path) = new_path.filter(|_| file.is_addition()) {
+                if editor.get(path.as_bstr()).is_some() {
+                    return Err(apply_mailbox::Error::Apply {
+                        path: path.clone(),
+                        source: None,
+                    });
+                }
+            }
+
+            let old_data = match old {
+                Some((_, id)) => self.find_blob(id)?.take_data(),
+                None => Vec::new(),
+            };
+            let new_data = file.apply(&old_data).map_err(|err| apply_mailbox::Error::Apply {
+                path: new_path.or(file.old_path.as_ref()).cloned().unwrap_or_default(),
+                source: Some(err),
+            })?;
+
+            if let Some(path) = file.old_path.as_ref().filter(|_| !file.is_copy) {
+                editor.remove(path.as_bstr())?;
+            }
+            if let Some(path) = new_path {
+                let kind = file
+                    .new_mode
+                    .and_then(|mode| mode.kind().into())
+                    .or(old.map(|(kind, _)| kind))
+                    .unwrap_or(EntryKind::Blob);
+                let id = self.write_blob(&new_data)?;
+                editor.upsert(path.as_bstr(), kind, id)?;
+            }
+        }
+        Ok(editor.write()?.detach())
+    }
+
+    /// Reconstruct the tree that `files` were created against from `our_tree` and the blobs in their `index` lines, apply
+    /// the patch to it and merge the result with `our_tree`.
+    fn apply_patch_three_way(
+        &self,
+        our_tree: ObjectId,
+        files: &[gix_diff::patch::File],
+        subject: &BStr,
+    ) -> Result<ObjectId, apply_mailbox::Error> {
+        let mut editor = self.edit_tree(our_tree)?;
+        for file in files {
+            if let Some(path) = file.new_path.as_ref().filter(|_| file.is_addition()) {
+                editor.remove(path.as_bstr())?;
+            }
+            let Some(path) = &file.old_path else { continue };
+            let id = file
+                .old_id
+                .and_then(|prefix| self.objects.lookup_prefix(prefix, None).ok().flatten())
+                .and_then(Result::ok)
+                .ok_or_else(|| apply_mailbox::Error::MissingPreimage { path: path.clone() })?;
+            let kind = file
+                .old_mode
+                .and_then(|mode| mode.kind().into())
+                .unwrap_or(EntryKind::Blob);
+            editor.upsert(path.as_bstr(), kind, id)?;
+        }
+        let ancestor_tree = editor.write()?.detach();
+        let their_tree = self.apply_patch_to_tree(ancestor_tree, files)?;
+
+        let labels = gix_merge::blob::builtin_driver::text::Labels {
+            ancestor: Some("ancestor".into()),
+            current: Some("ours".into()),
+            other: Some(subject),
+        };
+        let mut outcome = self.merge_trees(
+            ancestor_tree,
+            our_tree,
+            their_tree,
+            labels,
+            self.tree_merge_options()?,
+        )?;
+        if outcome.has_unresolved_conflicts(crate::merge::tree::TreatAsUnresolved::git()) {
+            return Err(apply_mailbox::Error::Conflict {
+                subject: subject.to_owned(),
+            });
+        }
+        Ok(outcome.tree.write()?.detach())
+    }
+}
+
+/// Return the subject of `message`, which is its first paragraph with all lines joined by spaces.
+fn subject(message: &BStr) -> BString {
+    let mut subject = BString::default();
+    for line in message.lines().skip_while(|line| line.trim().is_empty()) {
+        let line = line.trim();
+        if line.is_empty() {
+            break;
+        }
+        if !subject.is_empty() {
+            subject.push(b' ');
+        }
+        subject.extend_from_slice(line);
+    }
+    subject
+}
+
+/// Append `text` to `out`, wrapped at word boundaries so that lines don't exceed `width`, with the first line indented by
+/// `indent` and all following lines indented by `continuation_indent`.
+fn wrap(out: &mut BString, text: &BStr, width: usize, indent: usize, continuation_indent: usize) {
+    out.extend(std::iter::repeat(b' ').take(indent));
+    let mut line_len = indent;
+    for (idx, word) in text.split_str(" ").filter(|word| !word.is_empty()).enumerate() {
+        if idx > 0 {
+            if line_len + 1 + word.len() > width {
+                out.push(b'\n');
+                out.extend(std::iter::repeat(b' ').take(continuation_indent));
+                line_len = continuation_indent;
+            } else {
+                out.push(b' ');
+                line_len += 1;
+            }
+        }
+        out.extend_from_slice(word);
+        line_len += word.len();
+    }
+    out.push(b'\n');
+}
+
+/// Produce a file name like `0001-the-subject.patch`, just like Git does.
+fn file_name(number: usize, message: &BStr) -> String {
+    /// The maximum length of the file name, including the `.patch` suffix.
+    const MAX_LEN: usize = 64;
+    const SUFFIX: &str = ".patch";
+    let mut name = String::new();
+    let mut needs_separator = false;
+    let subject = subject(message);
+    let mut bytes = subject.iter().peekable();
+    while let Some(&byte) = bytes.next() {
+        if byte.is_ascii_alphanumeric() || byte == b'.' || byte == b'_' {
+            if needs_separator && !name.is_empty() {
+                name.push('-');
+            }
+            needs_separator = false;
+            name.push(byte as char);
+            if byte == b'.' {
+                while bytes.next_if_eq(&&b'.').is_some() {}
+            }
+        } else {
+            needs_separator = true;
+        }
+    }
+    let mut name = format!("{number:04}-{}", name.trim_end_matches(['.', '-']));
+    name.truncate(MAX_LEN - (SUFFIX.len() + 1));
+    name.push_str(SUFFIX);
+    name
+}
@@ -0,0 +1,748 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::SystemTime,
+};
+
+use gix_date::SecondsSinceUnixEpoch;
+use gix_features::progress::DynNestedProgress;
+use gix_hash::ObjectId;
+use gix_hashtable::HashSet;
+use gix_pack::data::output;
+
+use crate::{
+    config::{
+        cache::util::ApplyLeniency,
+        tree::{Extensions, Gc},
+    },
+    maintenance::{expire_reflogs, gc, needs_gc, pack_refs, prune, prune_packed, reachable, repack},
+};
+
+/// Maintenance of the object database and references, similar to `git gc`.
+impl crate::Repository {
+    /// Write all objects reachable from references, reflogs and the indices of all worktrees into a single new pack with
+    /// its index, and remove all other packs that aren't marked with a `.keep` file, similar to `git repack -a -d -A`.
+    ///
+    /// Unreachable objects in removed packs are loosened or dropped according to `options`, and loose objects that are now
+    /// packed are removed [like `prune_packed()` does](Self::prune_packed()).
+    /// Objects which are only available through alternates are copied into the new pack as well.
+    /// Partial clones are not supported yet and cause an error.
+    ///
+    /// `should_interrupt` can be set to abort the operation before any existing pack is removed.
+    pub fn repack(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: repack::Options,
+    ) -> Result<repack::Outcome, repack::Error> {
+        let (counts, stats) = self.count_reachable_objects(should_interrupt)?;
+        let reachable = counts.iter().map(|count| count.id).collect();
+        self.repack_counted(counts, stats, &reachable, progress, should_interrupt, options)
+    }
+
+    /// Remove all loose objects that aren't reachable from references, reflogs or the indices of all worktrees and
+    /// whose modification time is at or before `expire`, similar to `git prune --expire <expire>`.
+    ///
+    /// Nothing is done if `expire` is `None`.
+    pub fn prune(
+        &self,
+        expire: Option<SecondsSinceUnixEpoch>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<prune::Outcome, prune::Error> {
+        if expire.is_none() {
+            return Ok(Default::default());
+        }
+        let (counts, _stats) = self.count_reachable_objects(should_interrupt)?;
+        let reachable = counts.into_iter().map(|count| count.id).collect();
+        self.prune_unreachable(&reachable, expire, should_interrupt)
+    }
+
+    /// Remove all loose objects which are also present in one of the packs of this repository, similar to `git prune-packed`.
+    pub fn prune_packed(&self) -> Result<prune_packed::Outcome, prune_packed::Error> {
+        use prune_packed::Error;
+
+        let pack_dir = self.objects.store_ref().path().join("pack");
+        let indices = pack_index_paths(&pack_dir)
+            .map_err(|source| Error::Io {
+                source,
+                path: pack_dir.clone(),
+            })?
+            .into_iter()
+            .map(|path| gix_pack::index::File::at(path, self.object_hash()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut out = prune_packed::Outcome::default();
+        if indices.is_empty() {
+            return Ok(out);
+        }
+        let loose = self.loose_objects();
+        for id in loose.iter() {
+            let id = id?;
+            if indices.iter().any(|index| index.lookup(id).is_some()) {
+                let path = loose.object_path(&id);
+                std::fs::remove_file(&path).map_err(|source| Error::Io {
+                    source,
+                    path: path.clone(),
+                })?;
+                remove_empty_fanout_dir(&path);
+                out.objects_removed += 1;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Move all loose references that point to an object into the `packed-refs` file and remove them,
+    /// similar to `git pack-refs --all --prune`.
+    ///
+    /// Symbolic references and references outside of `refs/` remain loose, and reflogs aren't affected.
+    pub fn pack_refs(&self) -> Result<pack_refs::Outcome, pack_refs::Error> {
+        use gix_ref::{
+            file::transaction::PackedRefs,
+            transaction::{Change, LogChange, PreviousValue, RefEdit},
+            Target,
+        };
+
+        let edits: Vec<_> = self
+            .refs
+            .loose_iter()?
+            .filter_map(Result::ok)
+            .filter(|r| r.name.as_bstr().starts_with(b"refs/") && matches!(r.target, Target::Object(_)))
+            .map(|r| RefEdit {
+                change: Change::Update {
+                    log: LogChange::default(),
+                    expected: PreviousValue::MustExistAndMatch(r.target.clone()),
+                    new: r.target,
+                },
+                name: r.name,
+                deref: false,
+            })
+            .collect();
+        if edits.is_empty() {
+            return Ok(Default::default());
+        }
+
+        let (file_lock_fail, packed_refs_lock_fail) = self.config.lock_timeout()?;
+        // The reflog isn't touched as previous and new values are the same, so no committer is needed.
+        let edits = self
+            .refs
+            .transaction()
+            .packed_refs(PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(
+                Box::new(self.objects.clone()),
+            ))
+            .prepare(edits, file_lock_fail, packed_refs_lock_fail)?
+            .commit(None)?;
+        Ok(pack_refs::Outcome {
+            refs_packed: edits.len(),
+        })
+    }
+
+    /// Remove entries of the reflogs of `HEAD` and all references according to `options`, similar to `git reflog expire --all`.
+    ///
+    /// An entry is unreachable if its previous or new object isn't an ancestor of the commit the reference currently points to.
+    pub fn expire_reflogs(
+        &self,
+        options: expire_reflogs::Options,
+    ) -> Result<expire_reflogs::Outcome, expire_reflogs::Error> {
+        let mut out = expire_reflogs::Outcome::default();
+        let (expire, expire_unreachable) = match (options.expire, options.expire_unreachable) {
+            (None, None) => return Ok(out),
+            (expire, expire_unreachable) => (
+                expire.unwrap_or(SecondsSinceUnixEpoch::MIN),
+                expire_unreachable.unwrap_or(SecondsSinceUnixEpoch::MIN),
+            ),
+        };
+
+        let mut names_and_tips = vec![(
+            gix_ref::FullName::try_from("HEAD").expect("valid"),
+            self.head()?.id().map(crate::Id::detach),
+        )];
+        for reference in self.references()?.all()? {
+            let mut reference = reference.map_err(expire_reflogs::Error::ReadReference)?;
+            let tip = reference.peel_to_id_in_place().ok().map(crate::Id::detach);
+            names_and_tips.push((reference.inner.name, tip));
+        }
+
+        let mut buf = Vec::new();
+        for (name, tip) in names_and_tips {
+            let needs_ancestors = match self.refs.reflog_iter(name.as_ref(), &mut buf)? {
+                Some(log) => log.filter_map(Result::ok).any(|line| {
+                    let time = line.signature.seconds();
+                    time > expire && time <= expire_unreachable
+                }),
+                None => continue,
+            };
+            let ancestors = if needs_ancestors {
+                self.ancestors_of(tip)?
+            } else {
+                HashSet::default()
+            };
+            let is_unreachable = |id: ObjectId| !id.is_null() && !ancestors.contains(&id);
+            let removed = self.refs.reflog_retain(name.as_ref(), &mut |line| {
+                let time = line.signature.seconds();
+                !(time <= expire
+                    || (time <= expire_unreachable
+                        && (is_unreachable(line.previous_oid()) || is_unreachable(line.new_oid()))))
+            })?;
+            if removed != 0 {
+                out.reflogs_rewritten += 1;
+                out.entries_removed += removed;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Return `true` if there are enough loose objects or packs to warrant a garbage collection, as configured by `gc.auto`
+    /// and `gc.autoPackLimit`, similar to what `git gc --auto` does.
+    ///
+    /// Just like `git`, the amount of loose objects is estimated by looking at a single fan-out directory only,
+    /// and packs with a `.keep` file are not counted.
+    pub fn needs_gc(&self) -> Result<bool, needs_gc::Error> {
+        use crate::maintenance::{DEFAULT_AUTO_LOOSE_OBJECTS_LIMIT, DEFAULT_AUTO_PACK_LIMIT};
+        use needs_gc::Error;
+
+        let config = &self.config.resolved;
+        let lenient = self.options.lenient_config;
+        let auto = config
+            .integer(Gc::AUTO)
+            .map(|value| Gc::AUTO.try_into_u64(value))
+            .transpose()
+            .with_leniency(lenient)?
+            .unwrap_or(DEFAULT_AUTO_LOOSE_OBJECTS_LIMIT);
+        if auto == 0 {
+            return Ok(false);
+        }
+        let auto_pack_limit = config
+            .integer(Gc::AUTO_PACK_LIMIT)
+            .map(|value| Gc::AUTO_PACK_LIMIT.try_into_u64(value))
+            .transpose()
+            .with_leniency(lenient)?
+            .unwrap_or(DEFAULT_AUTO_PACK_LIMIT);
+
+        let objects_dir = self.objects.store_ref().path();
+        let fanout_dir = objects_dir.join("17");
+        let hex_len = self.object_hash().len_in_hex() - 2;
+        let num_loose_objects = match std::fs::read_dir(&fanout_dir) {
+            Ok(entries) => entries
+                .filter_map(Result::ok)
+                .filter(|entry| {
+                    let name = entry.file_name();
+                    name.len() == hex_len
+                        && name
+                            .to_str()
+                            .is_some_and(|name| name.bytes().all(|b| b.is_ascii_hexdigit()))
+                })
+                .count() as u64,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(source) => {
+                return Err(Error::Io {
+                    source,
+                    path: fanout_dir,
+                })
+            }
+        };
+        if num_loose_objects > auto.div_ceil(256) {
+            return Ok(true);
+        }
+
+        if auto_pack_limit == 0 {
+            return Ok(false);
+        }
+        let pack_dir = objects_dir.join("pack");
+        let num_packs = pack_index_paths(&pack_dir)
+            .map_err(|source| Error::Io { source, path: pack_dir })?
+            .into_iter()
+            .filter(|index| !index.with_extension("keep").is_file())
+            .count() as u64;
+        Ok(num_packs > auto_pack_limit)
+    }
+
+    /// Pack references, expire reflogs, repack all reachable objects and prune unreachable loose objects with the settings
+    /// from the `gc` configuration section, similar to `git gc`.
+    ///
+    /// If `options.auto` is `true`, nothing is done and `None` is returned unless [`needs_gc()`](Self::needs_gc()).
+    pub fn gc(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: gc::Options,
+    ) -> Result<Option<gc::Outcome>, gc::Error> {
+        if options.auto && !self.needs_gc()? {
+            return Ok(None);
+        }
+
+        let now = SystemTime::now();
+        let expiry = |key: &'static crate::config::tree::gc::Expiry, default: &str| -> Result<_, gc::Error> {
+            let configured = self
+                .config
+                .resolved
+                .string(key)
+                .map(|value| key.try_into_expiry(value, now))
+                .transpose()
+                .with_leniency(self.options.lenient_config)?;
+            let time = match configured {
+                Some(time) => time,
+                None => key.try_into_expiry(crate::bstr::BStr::new(default).into(), now)?,
+            };
+            Ok(time.map(|time| time.seconds))
+        };
+        let prune_expire = expiry(&Gc::PRUNE_EXPIRE, "2.weeks.ago")?;
+        let reflog_options = expire_reflogs::Options {
+            expire: expiry(&Gc::REFLOG_EXPIRE, "90.days.ago")?,
+            expire_unreachable: expiry(&Gc::REFLOG_EXPIRE_UNREACHABLE, "30.days.ago")?,
+        };
+
+        let pack_refs = self.pack_refs()?;
+        let expire_reflogs = self.expire_reflogs(reflog_options)?;
+        let (counts, stats) = self.count_reachable_objects(should_interrupt)?;
+        let reachable = counts.iter().map(|count| count.id).collect();
+        let repack = self.repack_counted(
+            counts,
+            stats,
+            &reachable,
+            progress,
+            should_interrupt,
+            repack::Options {
+                thread_limit: options.thread_limit,
+                write_multi_pack_index: options.write_multi_pack_index,
+                unpack_unreachable_expire: prune_expire,
+            },
+        )?;
+        let prune = self.prune_unreachable(&reachable, prune_expire, should_interrupt)?;
+        Ok(Some(gc::Outcome {
+            pack_refs,
+            expire_reflogs,
+            repack,
+            prune,
+        }))
+    }
+}
+
+/// Utilities
+impl crate::Repository {
+    fn loose_objects(&self) -> gix_odb::loose::Store {
+        gix_odb::loose::Store::at(self.objects.store_ref().path(), self.object_hash())
+    }
+
+    /// Return the object database handle to use when reading objects for writing them elsewhere, which must not apply
+    /// replacements and which must keep packs available even if they are deleted.
+    fn maintenance_objects(&self) -> gix_odb::Handle {
+        let mut objects = (*self.objects).clone();
+        objects.ignore_replacements = true;
+        objects.prevent_pack_unload();
+        objects
+    }
+
+    /// Return the ids of `tip` and all of its ancestors, if it's a commit.
+    fn ancestors_of(&self, tip: Option<ObjectId>) -> Result<HashSet<ObjectId>, expire_reflogs::Error> {
+        let mut out = HashSet::default();
+        let Some(tip) = tip else { return Ok(out) };
+        out.insert(tip);
+        if self
+            .find_header(tip)
+            .is_ok_and(|header| header.kind() == gix_object::Kind::Commit)
+        {
+            for info in self.ancestors_walk([tip])? {
+                out.insert(info?.id);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Walk `tips` and all of their ancestors, stopping at shallow boundaries.
+    ///
+    /// Like `git`, the commit-graph isn't used in shallow repositories as it may still contain parents that were
+    /// removed when the repository was made shallow.
+    fn ancestors_walk(
+        &self,
+        tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+    ) -> Result<crate::revision::Walk<'_>, crate::revision::walk::Error> {
+        self.rev_walk(tips).use_commit_graph(!self.is_shallow()).all()
+    }
+
+    /// Find all objects reachable from references, their reflogs, and the `HEAD` and index of all worktrees,
+    /// including the trees of their cache-tree extension.
+    fn count_reachable_objects(
+        &self,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(Vec<output::Count>, output::count::objects::Outcome), reachable::Error> {
+        if self.config.resolved.string(Extensions::PARTIAL_CLONE).is_some() {
+            return Err(reachable::Error::PartialClone);
+        }
+        let mut tips = Vec::new();
+        let mut buf = Vec::new();
+        let mut extend_with_reflog = |repo: &crate::Repository,
+                                      name: &gix_ref::FullNameRef,
+                                      tips: &mut Vec<ObjectId>|
+         -> Result<(), reachable::Error> {
+            if let Some(log) = repo.refs.reflog_iter(name, &mut buf)? {
+                for line in log.filter_map(Result::ok) {
+                    tips.extend(
+                        [line.previous_oid(), line.new_oid()]
+                            .into_iter()
+                            .filter(|id| !id.is_null()),
+                    );
+                }
+            }
+            Ok(())
+        };
+
+        for reference in self.references()?.all()? {
+            let reference = reference.map_err(reachable::Error::ReadReference)?;
+            if let Some(id) = reference.inner.target.try_id() {
+                tips.push(id.to_owned());
+            }
+            extend_with_reflog(self, reference.name(), &mut tips)?;
+        }
+        let worktree_repos = std::iter::once(self.main_repo())
+            .chain(
+                self.worktrees()?
+                    .into_iter()
+                    .map(crate::worktree::Proxy::into_repo_with_possibly_inaccessible_worktree),
+            )
+            .collect::<Result<Vec<_>, _>>()?;
+        for repo in &worktree_repos {
+            tips.extend(repo.head()?.id().map(crate::Id::detach));
+            extend_with_reflog(repo, "HEAD".try_into().expect("valid"), &mut tips)?;
+            let index = repo.index_or_empty()?;
+            tips.extend(
+                index
+                    .entries()
+                    .iter()
+                    .filter(|entry| !entry.mode.is_submodule())
+                    .map(|entry| entry.id),
+            );
+            if let Some(tree) = index.tree() {
+                extend_with_cache_tree(tree, &mut tips);
+            }
+        }
+
+        let mut input = Vec::with_capacity(tips.len());
+        let mut commits = Vec::new();
+        let mut seen = HashSet::default();
+        for tip in tips {
+            if !seen.insert(tip) || !self.has_object(tip) {
+                continue;
+            }
+            let object = self.find_object(tip)?.peel_tags_to_end()?;
+            if object.kind == gix_object::Kind::Commit {
+                commits.push(object.id);
+            }
+            input.push(tip);
+        }
+        if !commits.is_empty() {
+            for info in self.ancestors_walk(commits)? {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(reachable::Error::Interrupted);
+                }
+                input.push(info?.id);
+            }
+        }
+
+        let objects = self.maintenance_objects();
+        Ok(output::count::objects_unthreaded(
+            &objects,
+            &mut input.into_iter().map(Ok),
+            &gix_features::progress::Discard,
+            should_interrupt,
+            output::count::objects::ObjectExpansion::TreeContents,
+        )?)
+    }
+
+    fn repack_counted(
+        &self,
+        counts: Vec<output::Count>,
+        stats: output::count::objects::Outcome,
+        reachable: &HashSet<ObjectId>,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: repack::Options,
+    ) -> Result<repack::Outcome, repack::Error> {
+        use repack::Error;
+
+        let object_hash = self.object_hash();
+        let pack_dir = self.objects.store_ref().path().join("pack");
+        let io_err = |path: &Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { source, path }
+        };
+        std::fs::create_dir_all(&pack_dir).map_err(io_err(&pack_dir))?;
+        let write_reverse_index = self
+            .config
+            .resolved
+            .boolean(&crate::config::tree::Pack::WRITE_REVERSE_INDEX)
+            .map(|value| crate::config::tree::Pack::WRITE_REVERSE_INDEX.enrich_error(value))
+            .transpose()
+            .with_leniency(self.options.lenient_config)?
+            .unwrap_or(true);
+
+        let pack = if counts.is_empty() {
+            None
+        } else {
+            let num_objects = counts.len();
+            let mut objects = self.objects.clone().into_arc().map_err(Error::OpenArcOdb)?.into_inner();
+            objects.ignore_replacements = true;
+            objects.prevent_pack_unload();
+            let mut entries = gix_features::parallel::InOrderIter::from(output::entry::iter_from_counts(
+                counts,
+                objects,
+                Box::new(progress.add_child("creating entries".into())),
+                output::entry::iter_from_counts::Options {
+                    thread_limit: options.thread_limit,
+                    mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                    allow_thin_pack: false,
+                    chunk_size: 1000,
+                    version: Default::default(),
+                },
+            ));
+            let new_tempfile = || {
+                gix_tempfile::new(
+                    &pack_dir,
+                    gix_tempfile::ContainingDirectory::Exists,
+                    gix_tempfile::AutoRemove::Tempfile,
+                )
+                .map_err(io_err(&pack_dir))
+            };
+            let mut pack_data = new_tempfile()?;
+            let mut pack_writer = output::bytes::FromEntriesIter::new(
+                entries.by_ref(),
+                &mut pack_data,
+                num_objects as u32,
+                gix_pack::data::Version::V2,
+                object_hash,
+            )
+            .with_index_entries();
+            for written in pack_writer.by_ref() {
+                written?;
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+            }
+
+            let mut index = new_tempfile()?;
+            let mut reverse_index = write_reverse_index.then(new_tempfile).transpose()?;
+            let index_outcome = pack_writer.write_index(
+                Default::default(),
+                &mut index,
+                reverse_index.as_mut().map(|file| file as &mut dyn std::io::Write),
+                &mut progress.add_child("writing index".into()),
+            )?;
+            drop(pack_writer);
+            gix_features::parallel::reduce::Finalize::finalize(entries.inner)?;
+
+            let data_path = pack_dir.join(format!("pack-{}.pack", index_outcome.data_hash.to_hex()));
+            let index_path = data_path.with_extension("idx");
+            // The pack is only visible once its index is in place, so it's moved last.
+            // An existing pack with the same name has the same content and is kept as is.
+            if !index_path.is_file() {
+                let persist_err = |path: &Path| {
+                    let path = path.to_owned();
+                    move |err: gix_tempfile::handle::persist::Error<_>| Error::Io {
+                        source: err.error,
+                        path,
+                    }
+                };
+                pack_data.persist(&data_path).map_err(persist_err(&data_path))?;
+                if let Some(reverse_index) = reverse_index {
+                    let reverse_index_path = data_path.with_extension("rev");
+                    reverse_index
+                        .persist(&reverse_index_path)
+                        .map_err(persist_err(&reverse_index_path))?;
+                }
+                index.persist(&index_path).map_err(persist_err(&index_path))?;
+            }
+            Some(gix_pack::bundle::write::Outcome {
+                index: index_outcome,
+                pack_version: gix_pack::data::Version::V2,
+                object_hash,
+                index_path: Some(index_path),
+                data_path: Some(data_path),
+                keep_path: None,
+            })
+        };
+
+        let new_pack_path = pack.as_ref().and_then(|pack| pack.data_path.as_deref());
+        let mut packs_to_remove = Vec::new();
+        for index_path in pack_index_paths(&pack_dir).map_err(io_err(&pack_dir))? {
+            let data_path = index_path.with_extension("pack");
+            if Some(data_path.as_path()) != new_pack_path && !index_path.with_extension("keep").is_file() {
+                packs_to_remove.push((index_path, data_path));
+            }
+        }
+
+        let objects = self.maintenance_objects();
+        let loose = self.loose_objects();
+        let (mut objects_loosened, mut objects_dropped) = (0, 0);
+        let mut handled = HashSet::default();
+        let mut buf = Vec::new();
+        for (index_path, data_path) in &packs_to_remove {
+            let mtime = std::fs::metadata(data_path)
+                .and_then(|meta| meta.modified())
+                .map_err(io_err(data_path))?;
+            let is_expired = options
+                .unpack_unreachable_expire
+                .is_some_and(|expire| seconds_since_unix_epoch(mtime) <= expire);
+            let index = gix_pack::index::File::at(index_path, object_hash)?;
+            for entry in index.iter() {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                let id = entry.oid;
+                if reachable.contains(&id) || !handled.insert(id) || loose.contains(&id) {
+                    continue;
+                }
+                if is_expired {
+                    objects_dropped += 1;
+                    continue;
+                }
+                let object = gix_object::FindExt::find(&objects, &id, &mut buf)?;
+                let id = gix_object::Write::write_buf(&loose, object.kind, object.data)?;
+                // Keep the modification time of the pack so the object doesn't live longer than it would have in the pack.
+                let path = loose.object_path(&id);
+                std::fs::OpenOptions::new()
+                    .write(true)
+                    .open(&path)
+                    .and_then(|file| file.set_modified(mtime))
+                    .map_err(io_err(&path))?;
+                objects_loosened += 1;
+            }
+        }
+
+        for (index_path, data_path) in &packs_to_remove {
+            std::fs::remove_file(index_path).map_err(io_err(index_path))?;
+            std::fs::remove_file(data_path).map_err(io_err(data_path))?;
+            for extension in ["rev", "bitmap", "mtimes", "promisor"] {
+                remove_file_if_present(&index_path.with_extension(extension)).map_err(io_err(index_path))?;
+            }
+        }
+
+        let multi_pack_index_path = pack_dir.join("multi-pack-index");
+        remove_file_if_present(&multi_pack_index_path).map_err(io_err(&multi_pack_index_path))?;
+        for entry in std::fs::read_dir(&pack_dir).map_err(io_err(&pack_dir))? {
+            let path = entry.map_err(io_err(&pack_dir))?.path();
+            if path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("multi-pack-index-"))
+            {
+                remove_file_if_present(&path).map_err(io_err(&path))?;
+            }
+        }
+        let index_paths = pack_index_paths(&pack_dir).map_err(io_err(&pack_dir))?;
+        let multi_pack_index_path = if options.write_multi_pack_index && !index_paths.is_empty() {
+            let mut lock = gix_lock::File::acquire_to_update_resource(
+                &multi_pack_index_path,
+                gix_lock::acquire::Fail::Immediately,
+                None,
+            )?;
+            gix_pack::multi_index::File::write_from_index_paths(
+                index_paths,
+                &mut lock,
+                progress,
+                should_interrupt,
+                gix_pack::multi_index::write::Options { object_hash },
+            )?;
+            lock.commit()?;
+            Some(multi_pack_index_path)
+        } else {
+            None
+        };
+
+        let prune_packed = self.prune_packed()?;
+        Ok(repack::Outcome {
+            pack,
+            counts: stats,
+            packs_removed: packs_to_remove.len(),
+            objects_loosened,
+            objects_dropped,
+            multi_pack_index_path,
+            prune_packed,
+        })
+    }
+
+    fn prune_unreachable(
+        &self,
+        reachable: &HashSet<ObjectId>,
+        expire: Option<SecondsSinceUnixEpoch>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<prune::Outcome, prune::Error> {
+        use prune::Error;
+
+        let mut out = prune::Outcome::default();
+        let Some(expire) = expire else { return Ok(out) };
+        let loose = self.loose_objects();
+        for id in loose.iter() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let id = id?;
+            if reachable.contains(&id) {
+                continue;
+            }
+            let path = loose.object_path(&id);
+            let mtime = std::fs::metadata(&path)
+                .and_then(|meta| meta.modified())
+                .map_err(|source| Error::Io {
+                    source,
+                    path: path.clone(),
+                })?;
+            if seconds_since_unix_epoch(mtime) <= expire {
+                std::fs::remove_file(&path).map_err(|source| Error::Io {
+                    source,
+                    path: path.clone(),
+                })?;
+                remove_empty_fanout_dir(&path);
+                out.objects_removed += 1;
+            } else {
+                out.objects_kept += 1;
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Return the paths to all pack indices in `pack_dir`, or nothing if it doesn't exist.
+/// Add the ids of all valid trees in the cache-tree extension starting at `tree` to `out`, just like `git` does.
+fn extend_with_cache_tree(tree: &gix_index::extension::Tree, out: &mut Vec<ObjectId>) {
+    if tree.num_entries.is_some() {
+        out.push(tree.id);
+    }
+    for child in &tree.children {
+        extend_with_cache_tree(child, out);
+    }
+}
+
+fn pack_index_paths(pack_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let entries = match std::fs::read_dir(pack_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut out = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "idx") {
+            out.push(path);
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+fn remove_file_if_present(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        res => res,
+    }
+}
+
+/// Remove the fan-out directory of the loose object at `object_path` if it is empty, ignoring all errors.
+fn remove_empty_fanout_dir(object_path: &Path) {
+    if let Some(dir) = object_path.parent() {
+        std::fs::remove_dir(dir).ok();
+    }
+}
+
+fn seconds_since_unix_epoch(time: SystemTime) -> SecondsSinceUnixEpoch {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as SecondsSinceUnixEpoch)
+}
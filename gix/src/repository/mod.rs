@@ -43,18 +43,29 @@ mod index;
 pub(crate) mod init;
 mod kind;
 mod location;
+#[cfg(feature = "mailbox")]
+mod mailbox;
 #[cfg(feature = "mailmap")]
 mod mailmap;
+#[cfg(feature = "maintenance")]
+mod maintenance;
 ///
 #[cfg(feature = "merge")]
 mod merge;
 mod object;
 #[cfg(feature = "attributes")]
 mod pathspec;
+///
+#[cfg(feature = "blob-diff")]
+pub mod range_diff;
 mod reference;
 mod remote;
 mod revision;
 mod shallow;
+#[cfg(feature = "worktree-mutation")]
+mod sparse_checkout;
+#[cfg(feature = "dirwalk")]
+mod staging;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
@@ -314,6 +325,33 @@ pub mod commit_graph_if_enabled {
     }
 }
 
+///
+pub mod write_commit_graph {
+    /// The error returned by [Repository::write_commit_graph()](crate::Repository::write_commit_graph()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenReferences(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        IterReferences(#[from] crate::reference::iter::init::Error),
+        #[error("Could not read reference")]
+        ReadReference(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        PeelReference(#[from] crate::reference::peel::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] crate::object::commit::Error),
+        #[error(transparent)]
+        FindTree(#[from] gix_object::find::existing_iter::Error),
+        #[error(transparent)]
+        DiffTree(#[from] gix_diff::tree::Error),
+        #[error(transparent)]
+        WriteCommitGraph(#[from] gix_commitgraph::write::info_dir::Error),
+    }
+}
+
 ///
 #[cfg(feature = "index")]
 pub mod index_from_tree {
@@ -328,6 +366,8 @@ pub mod index_from_tree {
         },
         #[error("Couldn't obtain configuration for core.protect*")]
         BooleanConfig(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        IndexVersion(#[from] crate::config::index_version::Error),
     }
 }
 
@@ -431,6 +471,8 @@ pub mod index_or_load_from_head_or_empty {
         TraverseTree(#[from] crate::repository::index_from_tree::Error),
         #[error(transparent)]
         OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        IndexVersion(#[from] crate::config::index_version::Error),
     }
 }
 
@@ -465,3 +507,138 @@ pub mod worktree_archive {
     /// The error returned by [`Repository::worktree_archive()`](crate::Repository::worktree_archive()).
     pub type Error = gix_archive::Error;
 }
+
+///
+#[cfg(feature = "mailbox")]
+pub mod format_patch {
+    use crate::object::tree::diff::{patch, stats};
+
+    /// The error returned by [`Repository::format_patch()`](crate::Repository::format_patch()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        CommitTree(#[from] crate::object::commit::Error),
+        #[error(transparent)]
+        AuthorTime(#[from] gix_date::parse::Error),
+        #[error("Cannot create a cover letter without a committer")]
+        CommitterMissing,
+        #[error(transparent)]
+        CommitterTime(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        DiffOptions(#[from] crate::diff::options::init::Error),
+        #[error(transparent)]
+        DiffResourceCache(#[from] super::diff_resource_cache::Error),
+        #[error(transparent)]
+        ForEachChange(#[from] crate::object::tree::diff::for_each::Error),
+        #[error(transparent)]
+        Stats(#[from] stats::file::Error),
+        #[error(transparent)]
+        Patch(#[from] patch::file::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+
+    /// Options for use in [`Repository::format_patch()`](crate::Repository::format_patch()).
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Options {
+        /// The prefix to put in brackets before each subject, `PATCH` by default, like `--subject-prefix`.
+        pub subject_prefix: String,
+        /// If `Some(true)`, number patches like `[PATCH 1/2]` even if there is only one, or never number them if `Some(false)`.
+        /// If `None`, patches are numbered if there is more than one, which is the default.
+        /// A cover letter always implies numbered patches.
+        pub numbered: Option<bool>,
+        /// If `true`, create a cover letter with shortlog and statistics of all commits as first email, like `--cover-letter`.
+        pub cover_letter: bool,
+        /// The signature to write at the end of each email, or `None` to not write one, like `--signature`.
+        pub signature: Option<String>,
+        /// How to render the statistics of each patch, with a width of 72 columns by default.
+        pub stat: stats::stat::Options,
+        /// How to render the patch itself.
+        pub patch: patch::Options,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                subject_prefix: "PATCH".into(),
+                numbered: None,
+                cover_letter: false,
+                signature: Some(format!("gitoxide {}", env!("CARGO_PKG_VERSION"))),
+                stat: stats::stat::Options {
+                    width: 72,
+                    ..Default::default()
+                },
+                patch: Default::default(),
+            }
+        }
+    }
+
+    /// A single patch email as produced by [`Repository::format_patch()`](crate::Repository::format_patch()).
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Email {
+        /// The name of the file Git would write the email to, like `0001-the-subject.patch` or `0000-cover-letter.patch`.
+        pub file_name: String,
+        /// The email itself, which starts with a `From <id> Mon Sep 17 00:00:00 2001` line, so all emails can be concatenated
+        /// into a mailbox.
+        pub data: Vec<u8>,
+    }
+}
+
+///
+#[cfg(feature = "mailbox")]
+pub mod apply_mailbox {
+    use crate::bstr::BString;
+
+    /// The error returned by [`Repository::apply_mailbox()`](crate::Repository::apply_mailbox()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot create commits without a committer")]
+        CommitterMissing,
+        #[error(transparent)]
+        CommitterTime(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        CommitterDate(#[from] gix_date::parse::Error),
+        #[error(transparent)]
+        ParsePatch(#[from] gix_diff::patch::parse::Error),
+        #[error("The email with subject {subject:?} doesn't contain a patch")]
+        EmptyPatch { subject: BString },
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        EditTree(#[from] super::edit_tree::Error),
+        #[error(transparent)]
+        TreeEdit(#[from] gix_object::tree::editor::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        WriteObject(#[from] crate::object::write::Error),
+        #[error("The blob at {path:?} that the patch needs to apply to does not exist")]
+        MissingPreimage { path: BString },
+        #[error("The patch for {path:?} does not apply")]
+        Apply {
+            path: BString,
+            source: Option<gix_diff::patch::apply::Error>,
+        },
+        #[error(transparent)]
+        TreeMergeOptions(#[from] super::tree_merge_options::Error),
+        #[error(transparent)]
+        MergeTrees(#[from] super::merge_trees::Error),
+        #[error("The three-way merge of the patch with subject {subject:?} has conflicts")]
+        Conflict { subject: BString },
+    }
+
+    /// Options for use in [`Repository::apply_mailbox()`](crate::Repository::apply_mailbox()).
+    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct Options {
+        /// If `true`, fall back to a three-way merge if a patch doesn't apply cleanly, like `git am --3way`.
+        pub three_way: bool,
+    }
+}
@@ -0,0 +1,384 @@
+//! Compare two ranges of commits, like two versions of the same patch series, similar to `git range-diff`.
+use gix_diff::{
+    blob::unified_diff::ContextSize,
+    range_diff::{Pair, Patch},
+};
+use gix_hash::ObjectId;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    ext::TreeDiffChangeExt,
+    object::tree::diff::{patch, Action, Change},
+    Repository,
+};
+
+/// The error returned by [`Repository::range_diff()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    CommitTree(#[from] crate::object::commit::Error),
+    #[error(transparent)]
+    DiffOptions(#[from] crate::diff::options::init::Error),
+    #[error(transparent)]
+    DiffResourceCache(#[from] super::diff_resource_cache::Error),
+    #[error(transparent)]
+    ForEachChange(#[from] crate::object::tree::diff::for_each::Error),
+    #[error(transparent)]
+    Patch(#[from] patch::file::Error),
+    #[error(transparent)]
+    ShortId(#[from] crate::id::shorten::Error),
+    #[cfg(feature = "revision")]
+    #[error(transparent)]
+    RevParse(#[from] crate::revision::spec::parse::Error),
+    #[cfg(feature = "revision")]
+    #[error("The revision specification {spec:?} must be a range like 'from..to' or 'left...right'")]
+    NotARange { spec: BString },
+    #[cfg(feature = "revision")]
+    #[error("The revision specification {spec:?} must be a symmetric range like 'left...right'")]
+    NotASymmetricRange { spec: BString },
+    #[cfg(feature = "revision")]
+    #[error(transparent)]
+    OpenCommitGraph(#[from] super::commit_graph_if_enabled::Error),
+    #[cfg(feature = "revision")]
+    #[error(transparent)]
+    MergeBase(#[from] gix_revision::merge_base::Error),
+    #[cfg(feature = "revision")]
+    #[error(transparent)]
+    Traverse(#[from] gix_traverse::commit::range::Error),
+}
+
+/// Options for use in [`Repository::range_diff()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The cost of a commit that isn't paired in percent of the size of its changes, which allows more different commits
+    /// to be paired the higher it is. Defaults to [`DEFAULT_CREATION_FACTOR`](gix_diff::range_diff::DEFAULT_CREATION_FACTOR),
+    /// like `--creation-factor`.
+    pub creation_factor: u32,
+    /// The amount of context lines to show when writing the differences between paired commits, 3 by default.
+    ///
+    /// Note that the patch of each commit always uses 3 lines of context, just like Git does.
+    pub context: ContextSize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            creation_factor: gix_diff::range_diff::DEFAULT_CREATION_FACTOR,
+            context: ContextSize::symmetrical(3),
+        }
+    }
+}
+
+/// A commit of one of the ranges in an [`Outcome`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// The id of the commit, abbreviated as configured.
+    pub short_id: gix_hash::Prefix,
+    /// The first paragraph of the commit message.
+    pub subject: BString,
+    /// The author, message and changes of the commit rendered as text, which is used to pair commits and to show how they differ.
+    pub patch: Patch,
+}
+
+impl AsRef<Patch> for Commit {
+    fn as_ref(&self) -> &Patch {
+        &self.patch
+    }
+}
+
+/// The result of [`Repository::range_diff()`].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// All commits of the old range, oldest first.
+    pub old: Vec<Commit>,
+    /// All commits of the new range, oldest first.
+    pub new: Vec<Commit>,
+    /// Pairs of commits from `old` and `new`, in the order Git would show them.
+    pub pairs: Vec<Pair>,
+    /// The amount of context lines to use when writing the differences of paired commits.
+    pub context: ContextSize,
+}
+
+impl Outcome {
+    /// Write all pairs to `out` just like `git range-diff --no-color` would, with lines like
+    /// `1:  0123456 ! 1:  789abcd subject`, each followed by the differences between the patches of paired commits
+    /// that have changed.
+    pub fn write_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        let width = self.old.len().max(self.new.len()).to_string().len();
+        let dashes = self
+            .old
+            .first()
+            .or(self.new.first())
+            .map_or(String::new(), |commit| "-".repeat(commit.short_id.hex_len()));
+        for pair in &self.pairs {
+            let (old, new, status) = match *pair {
+                Pair::Removed { old } => (Some(old), None, '<'),
+                Pair::Added { new } => (None, Some(new), '>'),
+                Pair::Unchanged { old, new } => (Some(old), Some(new), '='),
+                Pair::Changed { old, new } => (Some(old), Some(new), '!'),
+            };
+            let old = old.map(|idx| (idx, &self.old[idx]));
+            let new = new.map(|idx| (idx, &self.new[idx]));
+            let column = |commit: Option<(usize, &Commit)>| match commit {
+                Some((idx, commit)) => format!("{:>width$}:  {}", idx + 1, commit.short_id),
+                None => format!("{:>width$}:  {dashes}", "-"),
+            };
+            let subject = &old.or(new).expect("at least one side is set").1.subject;
+            writeln!(out, "{} {status} {} {subject}", column(old), column(new))?;
+            if let (Some((_, old)), Some((_, new)), '!') = (old, new, status) {
+                gix_diff::range_diff::write_diff(&old.patch, &new.patch, out, self.context)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Range diffs
+impl Repository {
+    /// Pair the commits in `old` with the commits in `new`, both expected to be oldest first, to see how a series of commits
+    /// changed, similar to `git range-diff`. Use [`Outcome::write_to()`] to see the result.
+    ///
+    /// Each commit is compared to its first parent, and is rendered as text that contains its author, message and changes,
+    /// with rewrites being tracked as configured. Commits are paired if their changes are the same, or if they are similar enough
+    /// as determined by the [creation factor](Options::creation_factor).
+    ///
+    /// Note that unlike Git, the mailmap isn't applied to authors, and notes aren't shown.
+    pub fn range_diff(
+        &self,
+        old: impl IntoIterator<Item = impl Into<ObjectId>>,
+        new: impl IntoIterator<Item = impl Into<ObjectId>>,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let old = old
+            .into_iter()
+            .map(|id| self.range_diff_commit(id.into()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let new = new
+            .into_iter()
+            .map(|id| self.range_diff_commit(id.into()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let pairs = gix_diff::range_diff::pairs(&old, &new, options.creation_factor);
+        Ok(Outcome {
+            old,
+            new,
+            pairs,
+            context: options.context,
+        })
+    }
+
+    /// Like [`range_diff()`](Self::range_diff()), but obtain the commits of each range from a revision specification
+    /// like `main..feature` or `v1.0..@`, which contains all commits reachable from `feature`, but not from `main`.
+    /// Symmetric ranges like `main...feature` contain the commits of both sides that aren't reachable from their merge-bases.
+    /// Merge commits are skipped, just like Git does.
+    #[cfg(feature = "revision")]
+    pub fn range_diff_by_spec(
+        &self,
+        old: impl AsRef<BStr>,
+        new: impl AsRef<BStr>,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let old = self.commits_in_range(old.as_ref())?;
+        let new = self.commits_in_range(new.as_ref())?;
+        self.range_diff(old, new, options)
+    }
+
+    /// Like [`range_diff_by_spec()`](Self::range_diff_by_spec()), but obtain both ranges from a single symmetric
+    /// revision specification like `v1...v2`, similar to `git range-diff v1...v2`.
+    ///
+    /// The old range contains all commits reachable from `v1`, and the new range all commits reachable from `v2`,
+    /// but neither contains commits reachable from their merge-bases.
+    #[cfg(feature = "revision")]
+    pub fn range_diff_by_symmetric_spec(&self, spec: impl AsRef<BStr>, options: Options) -> Result<Outcome, Error> {
+        let spec = spec.as_ref();
+        let gix_revision::Spec::Merge { theirs, ours } = self.rev_parse(spec)?.detach() else {
+            return Err(Error::NotASymmetricRange { spec: spec.to_owned() });
+        };
+        let cache = self.commit_graph_if_enabled()?;
+        let bases = self.range_diff_merge_bases(cache.as_ref(), theirs, ours)?;
+        let old = self.range_diff_commits(cache.as_ref(), [theirs], bases.iter().copied())?;
+        let new = self.range_diff_commits(cache.as_ref(), [ours], bases)?;
+        self.range_diff(old, new, options)
+    }
+
+    /// Return all non-merge commits in the range `spec`, oldest first.
+    ///
+    /// The symmetric difference `a...b` contains the commits of both sides, without the ones reachable from their merge-bases.
+    #[cfg(feature = "revision")]
+    fn commits_in_range(&self, spec: &BStr) -> Result<Vec<ObjectId>, Error> {
+        let cache = self.commit_graph_if_enabled()?;
+        match self.rev_parse(spec)?.detach() {
+            gix_revision::Spec::Range { from, to } => self.range_diff_commits(cache.as_ref(), [to], [from]),
+            gix_revision::Spec::Merge { theirs, ours } => {
+                let bases = self.range_diff_merge_bases(cache.as_ref(), theirs, ours)?;
+                self.range_diff_commits(cache.as_ref(), [theirs, ours], bases)
+            }
+            _ => Err(Error::NotARange { spec: spec.to_owned() }),
+        }
+    }
+
+    #[cfg(feature = "revision")]
+    fn range_diff_merge_bases(
+        &self,
+        cache: Option<&gix_commitgraph::Graph>,
+        one: ObjectId,
+        two: ObjectId,
+    ) -> Result<Vec<ObjectId>, Error> {
+        let mut graph = self.revision_graph(cache);
+        Ok(self
+            .merge_bases_many_with_graph(one, &[two], &mut graph)?
+            .into_iter()
+            .map(crate::Id::detach)
+            .collect())
+    }
+
+    /// Return all non-merge commits reachable from `shown`, but not from `hidden`, oldest first.
+    #[cfg(feature = "revision")]
+    fn range_diff_commits(
+        &self,
+        cache: Option<&gix_commitgraph::Graph>,
+        shown: impl IntoIterator<Item = ObjectId>,
+        hidden: impl IntoIterator<Item = ObjectId>,
+    ) -> Result<Vec<ObjectId>, Error> {
+        let mut commits: Vec<_> = gix_traverse::commit::range::commits(&self.objects, cache, shown, hidden)?
+            .into_iter()
+            .filter(|info| info.parent_ids.len() < 2)
+            .map(|info| info.id)
+            .collect();
+        commits.reverse();
+        Ok(commits)
+    }
+
+    fn range_diff_commit(&self, id: ObjectId) -> Result<Commit, Error> {
+        let commit = self.find_commit(id)?;
+        let commit_ref = commit.decode()?;
+        let author = commit_ref.author();
+
+        let mut text = BString::from(" ## Metadata ##\nAuthor: ");
+        text.push_str(author.name);
+        text.push_str(" <");
+        text.push_str(author.email);
+        text.push_str(">\n\n ## Commit message ##\n");
+        for line in commit_ref.message.lines().skip_while(|line| line.trim().is_empty()) {
+            let line = line.trim_end();
+            if !line.is_empty() {
+                text.push_str("    ");
+                text.push_str(line);
+            }
+            text.push(b'\n');
+        }
+
+        let base_tree = match commit_ref.parents().next() {
+            Some(parent) => self.find_commit(parent)?.tree()?,
+            None => self.empty_tree(),
+        };
+        let tree = commit.tree()?;
+        let mut changes = Vec::new();
+        base_tree.changes()?.for_each_to_obtain_tree(&tree, |change| {
+            changes.push(change.detach());
+            Ok::<_, std::convert::Infallible>(Action::Continue)
+        })?;
+        changes.sort_by(|a, b| a.location().cmp(b.location()));
+
+        let mut diff_start = None;
+        let mut resource_cache = self.diff_resource_cache_for_tree_diff()?;
+        let mut buf = Vec::new();
+        for change in &changes {
+            let change = change.attach(self, self);
+            buf.clear();
+            patch::write_change(change, &mut resource_cache, &mut buf, patch::Options::default())?;
+            resource_cache.clear_resource_cache_keep_allocation();
+            if buf.is_empty() {
+                continue;
+            }
+            text.push(b'\n');
+            diff_start.get_or_insert(text.len());
+            write_section_header(&mut text, change);
+            convert_patch(&mut text, &buf, change.location());
+        }
+
+        Ok(Commit {
+            id,
+            short_id: commit.id().shorten()?,
+            subject: commit_ref.message().summary().into_owned(),
+            patch: Patch {
+                diff_start: diff_start.unwrap_or(text.len()),
+                text,
+            },
+        })
+    }
+}
+
+/// Write a line like ` ## path (new) ##` or ` ## old => new (mode change 100644 => 100755) ##` for `change`.
+fn write_section_header(out: &mut BString, change: Change<'_, '_, '_>) {
+    let (name, modes) = match change {
+        Change::Addition { location, .. } => (format!("{location} (new)"), None),
+        Change::Deletion { location, .. } => (format!("{location} (deleted)"), None),
+        Change::Modification {
+            location,
+            previous_entry_mode,
+            entry_mode,
+            ..
+        } => (location.to_string(), Some((previous_entry_mode, entry_mode))),
+        Change::Rewrite {
+            source_location,
+            location,
+            source_entry_mode,
+            entry_mode,
+            copy,
+            ..
+        } => (
+            if copy {
+                location.to_string()
+            } else {
+                format!("{source_location} => {location}")
+            },
+            Some((source_entry_mode, entry_mode)),
+        ),
+    };
+    out.push_str(" ## ");
+    out.push_str(name);
+    if let Some((previous_mode, mode)) = modes.filter(|(previous_mode, mode)| previous_mode != mode) {
+        out.push_str(format!(" (mode change {previous_mode:o} => {mode:o})"));
+    }
+    out.push_str(" ##\n");
+}
+
+/// Append the hunks of the git-style `patch` of the file at `location` to `out`, without any headers, and with hunk headers
+/// that show the file name and function name instead of line numbers, just like Git does for range diffs.
+fn convert_patch(out: &mut BString, patch: &[u8], location: &BStr) {
+    let mut in_header = true;
+    for line in patch.lines() {
+        if in_header {
+            if line.starts_with(b"Binary files ") {
+                out.push(b' ');
+                out.push_str(line);
+                out.push(b'\n');
+            }
+            in_header = !line.starts_with(b"+++ ");
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix(b"@@ ") {
+            let function_name = rest.find("@@").map_or(&[][..], |pos| &rest[pos + 2..]);
+            out.push_str("@@");
+            if !function_name.is_empty() {
+                out.push(b' ');
+                out.push_str(location);
+                out.push(b':');
+                out.push_str(function_name);
+            }
+        } else if line.starts_with(b"\\") {
+            out.push(b' ');
+            out.push_str(line);
+        } else {
+            out.push_str(line);
+        }
+        out.push(b'\n');
+    }
+}
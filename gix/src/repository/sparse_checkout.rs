@@ -0,0 +1,329 @@
+use std::{io::Read, path::Path, sync::atomic::AtomicBool};
+
+use gix_index::entry::{self, Flags};
+
+use crate::{
+    bstr::BStr,
+    config::tree::{Core, Extensions, Index},
+    sparse_checkout::{patterns, update, Options, Outcome, Patterns},
+    Repository,
+};
+
+impl Repository {
+    /// Return the path to the file with the sparse-checkout patterns, which is specific to the current worktree.
+    pub fn sparse_checkout_path(&self) -> std::path::PathBuf {
+        self.git_dir().join("info").join("sparse-checkout")
+    }
+
+    /// Return the sparse-checkout patterns if `core.sparseCheckout` is enabled and if the
+    /// [sparse-checkout file](Self::sparse_checkout_path()) exists, or `None` otherwise.
+    ///
+    /// They are in _cone mode_ if `core.sparseCheckoutCone` is set and if the patterns are valid in cone mode.
+    pub fn sparse_checkout_patterns(&self) -> Result<Option<Patterns>, patterns::Error> {
+        let options = self.config.sparse_options()?;
+        if !options.sparse_checkout {
+            return Ok(None);
+        }
+        let path = self.sparse_checkout_path();
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => return Err(patterns::Error::Io { source, path }),
+        };
+        Ok(Some(Patterns::from_bytes(
+            &bytes,
+            options.directory_patterns_only,
+            self.path_case()?,
+        )))
+    }
+
+    /// Enable the sparse checkout and replace its patterns with `input` like `git sparse-checkout set` would, configured
+    /// by `options`.
+    /// Then update the index and the worktree to match the new patterns, see [`sparse_checkout_reapply()`](Self::sparse_checkout_reapply()).
+    ///
+    /// In _cone mode_, each item in `input` is a directory to include recursively, otherwise it's a `.gitignore`-like pattern.
+    /// Just like `git`, `core.sparseCheckout`, `core.sparseCheckoutCone` and possibly `index.sparse` are written into the
+    /// worktree-specific configuration file, which is enabled with `extensions.worktreeConfig` if necessary.
+    pub fn sparse_checkout_set(
+        &mut self,
+        input: impl IntoIterator<Item = impl AsRef<BStr>>,
+        Options { cone, sparse_index }: Options,
+    ) -> Result<Outcome, update::Error> {
+        if self.workdir().is_none() {
+            return Err(update::Error::MissingWorkDir);
+        }
+        let cone = match cone {
+            Some(cone) => cone,
+            None => self.config.sparse_checkout_cone()?.unwrap_or(true),
+        };
+        let patterns = Patterns::from_input(input, cone, self.path_case()?);
+        self.write_sparse_checkout(&patterns, cone, sparse_index)?;
+        self.update_sparse_checkout(&patterns)
+    }
+
+    /// Add `input` to the patterns of the enabled sparse checkout like `git sparse-checkout add` would, and update the
+    /// index and the worktree to match, see [`sparse_checkout_reapply()`](Self::sparse_checkout_reapply()).
+    ///
+    /// In _cone mode_, each item in `input` is a directory to include recursively, otherwise it's a `.gitignore`-like pattern.
+    pub fn sparse_checkout_add(
+        &mut self,
+        input: impl IntoIterator<Item = impl AsRef<BStr>>,
+    ) -> Result<Outcome, update::Error> {
+        if self.workdir().is_none() {
+            return Err(update::Error::MissingWorkDir);
+        }
+        let mut patterns = self.sparse_checkout_patterns()?.ok_or(update::Error::NotEnabled)?;
+        patterns.add(input);
+        let cone = patterns.is_cone();
+        self.write_sparse_checkout(&patterns, cone, None)?;
+        self.update_sparse_checkout(&patterns)
+    }
+
+    /// Update the index and the worktree to match the patterns of the enabled sparse checkout, like
+    /// `git sparse-checkout reapply` would.
+    ///
+    /// Entries that are excluded are marked with [`SKIP_WORKTREE`](Flags::SKIP_WORKTREE) and their files are removed,
+    /// unless they are modified, and entries that are included are checked out.
+    /// If `index.sparse` is enabled and the patterns are in _cone mode_, directories that are entirely excluded are stored
+    /// as sparse directory entries, while a sparse index is always expanded otherwise.
+    pub fn sparse_checkout_reapply(&self) -> Result<Outcome, update::Error> {
+        if self.workdir().is_none() {
+            return Err(update::Error::MissingWorkDir);
+        }
+        let patterns = self.sparse_checkout_patterns()?.ok_or(update::Error::NotEnabled)?;
+        self.update_sparse_checkout(&patterns)
+    }
+}
+
+/// Utilities
+impl Repository {
+    fn path_case(&self) -> Result<gix_glob::pattern::Case, crate::config::boolean::Error> {
+        Ok(if self.filesystem_options()?.ignore_case {
+            gix_glob::pattern::Case::Fold
+        } else {
+            gix_glob::pattern::Case::Sensitive
+        })
+    }
+
+    /// Write `patterns` into the sparse-checkout file and enable the sparse checkout in the worktree configuration
+    /// with `cone` mode and `sparse_index` if set, just like `git` does.
+    fn write_sparse_checkout(
+        &mut self,
+        patterns: &Patterns,
+        cone: bool,
+        sparse_index: Option<bool>,
+    ) -> Result<(), update::Error> {
+        let path = self.sparse_checkout_path();
+        let parent = path.parent().expect("file in directory");
+        std::fs::create_dir_all(parent).map_err(|source| update::Error::Io {
+            source,
+            path: parent.to_owned(),
+        })?;
+        let mut lock = gix_lock::File::acquire_to_update_resource(&path, gix_lock::acquire::Fail::Immediately, None)?;
+        patterns
+            .write_to(&mut lock)
+            .map_err(|source| update::Error::Io { source, path })?;
+        lock.commit()?;
+
+        let cone = if cone { "true" } else { "false" };
+        let sparse_index = sparse_index.map(|value| if value { "true" } else { "false" });
+        if !self
+            .config
+            .resolved
+            .boolean(Extensions::WORKTREE_CONFIG)
+            .and_then(Result::ok)
+            .unwrap_or(false)
+        {
+            edit_config_file(&self.common_dir().join("config"), gix_config::Source::Local, |config| {
+                let version = config.integer(Core::REPOSITORY_FORMAT_VERSION).and_then(Result::ok);
+                if version.map_or(true, |version| version < 1) {
+                    config.set_raw_value(&Core::REPOSITORY_FORMAT_VERSION, "1")?;
+                }
+                config.set_raw_value(&Extensions::WORKTREE_CONFIG, "true")?;
+                Ok(())
+            })?;
+        }
+        edit_config_file(
+            &self.git_dir().join("config.worktree"),
+            gix_config::Source::Worktree,
+            |config| {
+                config.set_raw_value(&Core::SPARSE_CHECKOUT, "true")?;
+                config.set_raw_value(&Core::SPARSE_CHECKOUT_CONE, cone)?;
+                if let Some(sparse_index) = sparse_index {
+                    config.set_raw_value(&Index::SPARSE, sparse_index)?;
+                }
+                Ok(())
+            },
+        )?;
+
+        let mut config = self.config_snapshot_mut();
+        config.set_value(&Extensions::WORKTREE_CONFIG, "true")?;
+        config.set_value(&Core::SPARSE_CHECKOUT, "true")?;
+        config.set_value(&Core::SPARSE_CHECKOUT_CONE, cone)?;
+        if let Some(sparse_index) = sparse_index {
+            config.set_value(&Index::SPARSE, sparse_index)?;
+        }
+        config.commit()?;
+        Ok(())
+    }
+
+    /// Apply `patterns` to the index and change the worktree accordingly, then write the index.
+    fn update_sparse_checkout(&self, patterns: &Patterns) -> Result<Outcome, update::Error> {
+        let workdir = self.workdir().ok_or(update::Error::MissingWorkDir)?;
+        let options = self.config.sparse_options()?;
+        let mut index = self.open_index()?;
+        index.expand_sparse_directories(&self.objects, self.config.protect_options()?)?;
+        let changes = patterns.apply_to(&mut index);
+
+        let mut out = Outcome::default();
+        if !changes.newly_excluded.is_empty() {
+            let stat_options = self.stat_options()?;
+            let capabilities = self.filesystem_options()?;
+            let (mut pipeline, _) = self.filter_pipeline(None)?;
+            for idx in changes.newly_excluded {
+                let entry = &index.entries()[idx];
+                let rela_path = entry.path(&index);
+                let path = workdir.join(gix_path::from_bstr(rela_path));
+                let metadata = match gix_index::fs::Metadata::from_path_no_follow(&path) {
+                    Ok(metadata) => metadata,
+                    Err(err) if gix_fs::io_err::is_not_found(err.kind(), err.raw_os_error()) => continue,
+                    Err(source) => return Err(update::Error::Io { source, path }),
+                };
+                if is_modified(
+                    &index,
+                    entry,
+                    &path,
+                    &metadata,
+                    stat_options,
+                    capabilities,
+                    &mut pipeline,
+                )? {
+                    out.kept_modified.push(rela_path.to_owned());
+                    let entry = &mut index.entries_mut()[idx];
+                    entry.flags.remove(Flags::SKIP_WORKTREE);
+                    if !entry.flags.contains(Flags::INTENT_TO_ADD) {
+                        entry.flags.remove(Flags::EXTENDED);
+                    }
+                    continue;
+                }
+                std::fs::remove_file(&path).map_err(|source| update::Error::Io {
+                    source,
+                    path: path.clone(),
+                })?;
+                out.files_removed += 1;
+                // Remove directories that became empty, just like `git` does.
+                for dir in path.ancestors().skip(1).take_while(|dir| *dir != workdir) {
+                    if std::fs::remove_dir(dir).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+
+        if !changes.newly_included.is_empty() {
+            let mut included = gix_index::State::new(self.object_hash());
+            for idx in &changes.newly_included {
+                let entry = &index.entries()[*idx];
+                included.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, entry.path(&index));
+            }
+            let mut included = gix_index::File::from_state(included, self.index_path());
+            let checkout_options =
+                self.checkout_options(gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping)?;
+            out.checkout = gix_worktree_state::checkout(
+                &mut included,
+                workdir,
+                self.objects.clone().into_arc().map_err(update::Error::OpenArcOdb)?,
+                &gix_features::progress::Discard,
+                &gix_features::progress::Discard,
+                &AtomicBool::default(),
+                checkout_options,
+            )?;
+            out.files_checked_out = out.checkout.files_updated;
+            for (idx, checked_out) in changes.newly_included.iter().zip(included.entries()) {
+                index.entries_mut()[*idx].stat = checked_out.stat;
+            }
+        }
+
+        if matches!(
+            options.sparse_mode(),
+            gix_index::sparse::Mode::IncludeDirectoriesStoreIncludedEntriesAndExcludedDirs
+        ) && patterns.is_cone()
+            && index.entries().iter().all(|e| e.stage_raw() == 0)
+        {
+            index.write_tree(self)?;
+            index.collapse_to_sparse_directories(|dir| patterns.is_sparse_directory(dir));
+        }
+        index.write(Default::default())?;
+        Ok(out)
+    }
+}
+
+/// Return `true` if the file at `path` with `metadata` was changed compared to `entry` in `index`.
+fn is_modified(
+    index: &gix_index::State,
+    entry: &gix_index::Entry,
+    path: &Path,
+    metadata: &gix_index::fs::Metadata,
+    stat_options: entry::stat::Options,
+    capabilities: gix_fs::Capabilities,
+    pipeline: &mut crate::filter::Pipeline<'_>,
+) -> Result<bool, update::Error> {
+    if entry
+        .mode
+        .change_to_match_fs(metadata, capabilities.symlink, capabilities.executable_bit)
+        .is_some()
+    {
+        return Ok(true);
+    }
+    if entry.stat.matches(&entry::Stat::from_fs(metadata)?, stat_options)
+        && !entry.stat.is_racy(index.timestamp(), stat_options)
+    {
+        return Ok(false);
+    }
+    let io_err = |source| update::Error::Io {
+        source,
+        path: path.to_owned(),
+    };
+    let object_hash = index.object_hash();
+    let id = if metadata.is_symlink() {
+        let target = gix_path::into_bstr(std::fs::read_link(path).map_err(io_err)?);
+        gix_object::compute_hash(object_hash, gix_object::Kind::Blob, &target)?
+    } else if metadata.is_file() {
+        let file = std::fs::File::open(path).map_err(io_err)?;
+        let mut buf = Vec::new();
+        pipeline
+            .convert_to_git(file, gix_path::from_bstr(entry.path(index)).as_ref(), index)?
+            .read_to_end(&mut buf)
+            .map_err(io_err)?;
+        gix_object::compute_hash(object_hash, gix_object::Kind::Blob, &buf)?
+    } else {
+        return Ok(true);
+    };
+    Ok(id != entry.id)
+}
+
+/// Load the configuration file at `path` with `source`, or create it, and write it back after `edit` changed it.
+fn edit_config_file(
+    path: &Path,
+    source: gix_config::Source,
+    edit: impl FnOnce(&mut gix_config::File<'static>) -> Result<(), gix_config::file::set_raw_value::Error>,
+) -> Result<(), update::Error> {
+    let mut config = if path.is_file() {
+        gix_config::File::from_path_no_includes(path.to_owned(), source).map_err(|source| {
+            update::Error::LoadConfig {
+                source,
+                path: path.to_owned(),
+            }
+        })?
+    } else {
+        gix_config::File::new(gix_config::file::Metadata::from(source).at(path))
+    };
+    edit(&mut config).map_err(crate::config::set_value::Error::from)?;
+    let mut lock = gix_lock::File::acquire_to_update_resource(path, gix_lock::acquire::Fail::Immediately, None)?;
+    config.write_to(&mut lock).map_err(|source| update::Error::Io {
+        source,
+        path: path.to_owned(),
+    })?;
+    lock.commit()?;
+    Ok(())
+}
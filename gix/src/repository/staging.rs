@@ -0,0 +1,11 @@
+use crate::{staging, Repository};
+
+impl Repository {
+    /// Return a platform to change a copy of the index based on the worktree by adding, removing or moving entries,
+    /// similar to `git add`, `git rm` and `git mv`.
+    ///
+    /// Changes only become visible to others once the index is [written](staging::Platform::write()).
+    pub fn staging(&self) -> Result<staging::Platform<'_>, staging::init::Error> {
+        staging::Platform::new(self)
+    }
+}
@@ -143,3 +143,318 @@ impl crate::Repository {
         Ok(())
     }
 }
+
+/// Management of linked worktrees.
+impl crate::Repository {
+    /// Create a new linked worktree at `path` which checks out `head`, similar to `git worktree add`, and return it as
+    /// repository.
+    ///
+    /// `path` must not exist or be an empty directory. The id of the worktree is derived from the last component of `path`,
+    /// and made unique by appending a number if needed. Use `should_interrupt` to stop the checkout early.
+    ///
+    /// If anything fails, all files created so far are removed again.
+    #[cfg(feature = "worktree-mutation")]
+    #[allow(clippy::result_large_err)]
+    pub fn worktree_add(
+        &self,
+        path: impl Into<std::path::PathBuf>,
+        head: worktree::add::Head,
+        options: worktree::add::Options,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+    ) -> Result<crate::Repository, worktree::add::Error> {
+        use worktree::add::{Error, Head};
+        let path = path.into();
+        let io_err = |path: &std::path::Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { source, path }
+        };
+        let created_worktree_dir = match std::fs::read_dir(&path) {
+            Ok(mut entries) => {
+                if entries.next().is_some() {
+                    return Err(Error::LocationExists { path });
+                }
+                false
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => true,
+            Err(_) => return Err(Error::LocationExists { path }),
+        };
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| Error::InvalidLocation { path: path.clone() })?
+            .to_owned();
+
+        match &head {
+            Head::Branch(branch) => {
+                self.find_reference(branch.as_ref())?;
+                if let Some(path) = self.worktree_with_branch_checked_out(branch.as_ref()) {
+                    return Err(Error::BranchCheckedOut {
+                        name: branch.clone(),
+                        path,
+                    });
+                }
+            }
+            Head::NewBranch { name, start } => {
+                self.reference(
+                    name.clone(),
+                    *start,
+                    gix_ref::transaction::PreviousValue::MustNotExist,
+                    format!("branch: Created from {start}"),
+                )?;
+            }
+            Head::Detached(_) => {}
+        }
+
+        let worktrees_dir = self.common_dir().join("worktrees");
+        std::fs::create_dir_all(&worktrees_dir).map_err(io_err(&worktrees_dir))?;
+        let mut admin_dir = worktrees_dir.join(&name);
+        let mut counter = 1;
+        loop {
+            match std::fs::create_dir(&admin_dir) {
+                Ok(()) => break,
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                    admin_dir = worktrees_dir.join(format!("{name}{counter}"));
+                    counter += 1;
+                }
+                Err(err) => return Err(io_err(&admin_dir)(err)),
+            }
+        }
+
+        let res = (|| {
+            let lock_file = admin_dir.join("locked");
+            std::fs::write(&lock_file, "initializing").map_err(io_err(&lock_file))?;
+            std::fs::create_dir_all(&path).map_err(io_err(&path))?;
+            let workdir = gix_path::realpath(&path).map_err(|err| Error::Io {
+                source: std::io::Error::other(err),
+                path: path.clone(),
+            })?;
+            let admin_dir = gix_path::realpath(&admin_dir).map_err(|err| Error::Io {
+                source: std::io::Error::other(err),
+                path: admin_dir.clone(),
+            })?;
+            worktree::write_gitdir_file(&admin_dir, &workdir).map_err(io_err(&admin_dir.join("gitdir")))?;
+            let commondir = admin_dir.join("commondir");
+            std::fs::write(&commondir, "../..\n").map_err(io_err(&commondir))?;
+            let head_file = admin_dir.join("HEAD");
+            let head_content = match &head {
+                Head::Detached(id) => format!("{id}\n"),
+                Head::Branch(name) | Head::NewBranch { name, .. } => format!("ref: {}\n", name.as_bstr()),
+            };
+            std::fs::write(&head_file, head_content).map_err(io_err(&head_file))?;
+            worktree::write_dot_git_file(&workdir, &admin_dir).map_err(io_err(&workdir))?;
+
+            let repo: crate::Repository = crate::ThreadSafeRepository::open_from_paths(
+                admin_dir.clone(),
+                Some(workdir.clone()),
+                self.options.clone(),
+            )?
+            .into();
+            if options.checkout {
+                let root_tree = repo.head_tree_id()?.detach();
+                let mut index = gix_index::State::from_tree(&root_tree, &repo.objects, repo.config.protect_options()?)
+                    .map_err(|err| Error::IndexFromTree {
+                        id: root_tree,
+                        source: err,
+                    })?;
+                index.set_version(repo.config.index_version()?);
+                repo.objects
+                    .prefetch(
+                        index
+                            .entries()
+                            .iter()
+                            .filter(|entry| {
+                                !entry.mode.is_submodule()
+                                    && !entry.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE)
+                            })
+                            .map(|entry| entry.id),
+                    )
+                    .map_err(Error::Prefetch)?;
+                let mut index = gix_index::File::from_state(index, repo.index_path());
+                let mut opts = repo.checkout_options(gix_worktree::stack::state::attributes::Source::IdMapping)?;
+                opts.destination_is_initially_empty = true;
+                gix_worktree_state::checkout(
+                    &mut index,
+                    &workdir,
+                    repo.objects.clone().into_arc().map_err(Error::OpenArcOdb)?,
+                    &gix_features::progress::Discard,
+                    &gix_features::progress::Discard,
+                    should_interrupt,
+                    opts,
+                )?;
+                index.write(Default::default())?;
+            }
+            match &options.lock_reason {
+                Some(reason) => std::fs::write(&lock_file, reason).map_err(io_err(&lock_file))?,
+                None => std::fs::remove_file(&lock_file).map_err(io_err(&lock_file))?,
+            }
+            Ok(repo)
+        })();
+        if res.is_err() {
+            std::fs::remove_dir_all(&admin_dir).ok();
+            if created_worktree_dir {
+                std::fs::remove_dir_all(&path).ok();
+            } else if let Ok(entries) = std::fs::read_dir(&path) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.is_dir() && !path.is_symlink() {
+                        std::fs::remove_dir_all(path).ok();
+                    } else {
+                        std::fs::remove_file(path).ok();
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    /// Return the work tree that has `branch` checked out, if there is one. Bare repositories have no main worktree
+    /// which could have it checked out.
+    #[cfg(feature = "worktree-mutation")]
+    fn worktree_with_branch_checked_out(&self, branch: &gix_ref::FullNameRef) -> Option<std::path::PathBuf> {
+        use crate::bstr::ByteSlice;
+        let head_points_to_branch = |git_dir: &std::path::Path| {
+            std::fs::read(git_dir.join("HEAD")).ok().is_some_and(|content| {
+                content
+                    .trim()
+                    .strip_prefix(b"ref:")
+                    .is_some_and(|name| name.trim() == branch.as_bstr())
+            })
+        };
+        if !self.config.is_bare && head_points_to_branch(self.common_dir()) {
+            return Some(
+                self.main_repo()
+                    .ok()
+                    .and_then(|repo| repo.workdir().map(ToOwned::to_owned))
+                    .unwrap_or_else(|| self.common_dir().to_owned()),
+            );
+        }
+        self.worktrees()
+            .ok()?
+            .into_iter()
+            .find(|proxy| head_points_to_branch(proxy.git_dir()))
+            .map(|proxy| proxy.base().unwrap_or_else(|_| proxy.git_dir().to_owned()))
+    }
+
+    /// Remove the administrative files of all linked worktrees that aren't locked and whose checkout is gone or can't be located
+    /// anymore, similar to `git worktree prune`.
+    ///
+    /// If `expire` is set, worktrees whose checkout is missing are only pruned if they were last used at or before `expire`,
+    /// the amount of seconds since the unix epoch.
+    pub fn prune_worktrees(
+        &self,
+        expire: Option<gix_date::SecondsSinceUnixEpoch>,
+    ) -> Result<worktree::prune::Outcome, worktree::prune::Error> {
+        let mut out = worktree::prune::Outcome::default();
+        let worktrees_dir = self.common_dir().join("worktrees");
+        let io_err = |path: &std::path::Path| {
+            let path = path.to_owned();
+            move |source| worktree::prune::Error { source, path }
+        };
+        let entries = match std::fs::read_dir(&worktrees_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(out),
+            Err(err) => return Err(io_err(&worktrees_dir)(err)),
+        };
+        let mut entries = entries
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io_err(&worktrees_dir))?;
+        entries.sort();
+        for admin_dir in entries {
+            let Some(reason) = worktree::prune_reason(&admin_dir, expire) else {
+                continue;
+            };
+            if reason == worktree::prune::Reason::NotADirectory {
+                std::fs::remove_file(&admin_dir)
+            } else {
+                std::fs::remove_dir_all(&admin_dir)
+            }
+            .map_err(io_err(&admin_dir))?;
+            let id = gix_path::into_bstr(std::path::Path::new(
+                admin_dir.file_name().expect("entry in worktrees/"),
+            ))
+            .into_owned();
+            out.pruned.push((id, reason));
+        }
+        if std::fs::read_dir(&worktrees_dir)
+            .map_err(io_err(&worktrees_dir))?
+            .next()
+            .is_none()
+        {
+            std::fs::remove_dir(&worktrees_dir).map_err(io_err(&worktrees_dir))?;
+        }
+        Ok(out)
+    }
+
+    /// Repair the links between linked worktrees and their administrative files, similar to `git worktree repair`.
+    ///
+    /// The `.git` files of all linked worktrees are rewritten if they don't point to their administrative directory anymore.
+    /// Each of `paths` is expected to be the new location of a linked worktree that was moved without updating its
+    /// administrative files. Its `.git` file is used to find the worktree it belongs to, whose `gitdir` file
+    /// is then rewritten to point to it.
+    pub fn repair_worktrees(
+        &self,
+        paths: impl IntoIterator<Item = impl Into<std::path::PathBuf>>,
+    ) -> Result<worktree::repair::Outcome, worktree::repair::Error> {
+        use worktree::repair::Error;
+        let mut out = worktree::repair::Outcome::default();
+        let to_io_err = |path: &std::path::Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { source, path }
+        };
+        let realpath = |path: &std::path::Path| {
+            gix_path::realpath(path).map_err(|err| Error::Io {
+                source: std::io::Error::other(err),
+                path: path.to_owned(),
+            })
+        };
+        let worktrees = self.worktrees().map_err(Error::ListWorktrees)?;
+        for proxy in &worktrees {
+            let Some(base) = proxy.base().ok().filter(|base| base.is_dir()) else {
+                continue;
+            };
+            let admin_dir = realpath(proxy.git_dir())?;
+            let points_to_admin_dir = gix_discover::path::from_gitdir_file(&base.join(gix_discover::DOT_GIT_DIR))
+                .ok()
+                .and_then(|dir| gix_path::realpath(dir).ok())
+                .is_some_and(|dir| dir == admin_dir);
+            if !points_to_admin_dir {
+                out.dot_git_files
+                    .push(worktree::write_dot_git_file(&base, &admin_dir).map_err(to_io_err(&base))?);
+            }
+        }
+
+        for path in paths {
+            let path = realpath(&path.into())?;
+            let dot_git = path.join(gix_discover::DOT_GIT_DIR);
+            let target = gix_discover::path::from_gitdir_file(&dot_git)
+                .map_err(|_| Error::NotALinkedWorktree { path: path.clone() })?;
+            let proxy = match gix_path::realpath(&target).ok().and_then(|target| {
+                worktrees
+                    .iter()
+                    .find(|proxy| realpath(proxy.git_dir()).ok().as_ref() == Some(&target))
+            }) {
+                Some(proxy) => proxy,
+                None => {
+                    // The repository may have moved as well, so try to find the worktree by its id.
+                    let proxy = target
+                        .file_name()
+                        .and_then(|id| worktrees.iter().find(|proxy| proxy.git_dir().file_name() == Some(id)))
+                        .ok_or_else(|| Error::NotALinkedWorktree { path: path.clone() })?;
+                    let admin_dir = realpath(proxy.git_dir())?;
+                    out.dot_git_files
+                        .push(worktree::write_dot_git_file(&path, &admin_dir).map_err(to_io_err(&dot_git))?);
+                    proxy
+                }
+            };
+            if proxy.base().ok().as_ref() != Some(&path) {
+                out.gitdir_files.push(
+                    worktree::write_gitdir_file(proxy.git_dir(), &path)
+                        .map_err(to_io_err(&proxy.git_dir().join("gitdir")))?,
+                );
+            }
+        }
+        Ok(out)
+    }
+}
@@ -341,6 +341,23 @@ pub(crate) mod iter_impl {
         pub(crate) inner: Box<dyn Iterator<Item = Result<gix_traverse::commit::Info, super::iter::Error>> + 'repo>,
     }
 
+    impl<'repo> Walk<'repo> {
+        /// Turn this walk into an iterator that also yields the [patch id](crate::Commit::patch_id()) of each commit
+        /// computed in `mode`, reusing resources between commits.
+        ///
+        /// Collecting the patch ids of one range of commits allows to find commits in another range that were
+        /// cherry-picked from it, similar to `git cherry` or `git log --cherry-pick`.
+        #[cfg(feature = "blob-diff")]
+        pub fn patch_ids(self, mode: gix_diff::patch_id::Mode) -> crate::commit::patch_id::Iter<'repo> {
+            crate::commit::patch_id::Iter {
+                walk: self,
+                mode,
+                resource_cache: None,
+                buf: Vec::new(),
+            }
+        }
+    }
+
     impl<'repo> Iterator for Walk<'repo> {
         type Item = Result<super::Info<'repo>, super::iter::Error>;
 
@@ -0,0 +1,107 @@
+//! Change which files of the index are present in the worktree, similar to `git sparse-checkout`.
+//!
+//! The patterns are stored in the `info/sparse-checkout` file of the repository and are enabled with `core.sparseCheckout`.
+//! In _cone mode_, as configured with `core.sparseCheckoutCone`, entire directories are included, which also allows
+//! the index to be stored as sparse index if `index.sparse` is set.
+use crate::bstr::BString;
+pub use gix_worktree_state::sparse::Patterns;
+
+///
+pub mod patterns {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::sparse_checkout_patterns()`](crate::Repository::sparse_checkout_patterns()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+        #[error("Could not read the sparse-checkout patterns at '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+    }
+}
+
+///
+pub mod update {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::sparse_checkout_set()`](crate::Repository::sparse_checkout_set()) and related methods.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A working tree is required to change the sparse checkout")]
+        MissingWorkDir,
+        #[error("The sparse checkout isn't enabled, or there is no sparse-checkout file")]
+        NotEnabled,
+        #[error(transparent)]
+        Patterns(#[from] super::patterns::Error),
+        #[error(transparent)]
+        ConfigBoolean(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        StatOptions(#[from] crate::config::stat_options::Error),
+        #[error(transparent)]
+        SetConfig(#[from] crate::config::set_value::Error),
+        #[error(transparent)]
+        CommitConfig(#[from] crate::config::Error),
+        #[error("Could not read the configuration file at '{}'", path.display())]
+        LoadConfig {
+            source: gix_config::file::init::from_paths::Error,
+            path: PathBuf,
+        },
+        #[error(transparent)]
+        AcquireLock(#[from] gix_lock::acquire::Error),
+        #[error(transparent)]
+        CommitLock(#[from] gix_lock::commit::Error<gix_lock::File>),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        ExpandIndex(#[from] gix_index::sparse::expand::Error),
+        #[error(transparent)]
+        WriteTree(#[from] gix_index::write_tree::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+        #[error(transparent)]
+        ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+        #[error(transparent)]
+        Hash(#[from] gix_hash::hasher::Error),
+        #[error(transparent)]
+        Time(#[from] std::time::SystemTimeError),
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error(transparent)]
+        Checkout(#[from] gix_worktree_state::checkout::Error),
+        #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+        OpenArcOdb(std::io::Error),
+        #[error("Could not access '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+    }
+}
+
+/// Options for use in [`Repository::sparse_checkout_set()`](crate::Repository::sparse_checkout_set()).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// If `Some(true)`, the patterns are directories in _cone mode_, or `.gitignore`-like patterns if `Some(false)`.
+    /// If `None`, _cone mode_ is used unless `core.sparseCheckoutCone` is set to `false`, just like `git` does.
+    pub cone: Option<bool>,
+    /// If `Some(…)`, set `index.sparse` accordingly to control if the index should be stored as sparse index,
+    /// which is only possible in _cone mode_.
+    pub sparse_index: Option<bool>,
+}
+
+/// The outcome of changing the sparse checkout with [`Repository::sparse_checkout_set()`](crate::Repository::sparse_checkout_set())
+/// and related methods.
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// The amount of files that were checked out as they are now included.
+    pub files_checked_out: usize,
+    /// The amount of files that were removed from the worktree as they are now excluded.
+    pub files_removed: usize,
+    /// The paths of files that are excluded, but were kept in the worktree as they were modified.
+    ///
+    /// Just like in `git`, they are not marked as skipped either and remain visible in the worktree.
+    pub kept_modified: Vec<BString>,
+    /// The outcome of checking out newly included files, which contains possible collisions and errors.
+    pub checkout: gix_worktree_state::checkout::Outcome,
+}
@@ -0,0 +1,316 @@
+use std::{collections::HashSet, sync::atomic::AtomicBool};
+
+use gix_dir::{
+    entry::{Kind, Status},
+    walk::EmissionMode,
+};
+use gix_index::entry;
+
+use super::{first_unmatched_pattern, Platform, WorktreeFile};
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The error returned by [`Platform::add()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    Dirwalk(#[from] crate::dirwalk::Error),
+    #[error(transparent)]
+    FilesystemOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    StatOptions(#[from] crate::config::stat_options::Error),
+    #[error(transparent)]
+    File(#[from] super::file::Error),
+    #[error("Could not read the ignored directory at '{}'", path.display())]
+    ReadDir {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+    #[error("pathspec '{pattern}' did not match any files")]
+    PathspecDidNotMatch { pattern: BString },
+}
+
+/// Determine which files are considered by [`Platform::add()`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// Add untracked files, update modified tracked files and remove tracked files that were deleted, like `git add -A`,
+    /// which is also what `git add <pathspec>` does.
+    #[default]
+    All,
+    /// Only update modified tracked files and remove tracked files that were deleted, like `git add -u`.
+    Tracked,
+}
+
+/// Options for use in [`Platform::add()`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// Determine which files to consider.
+    pub mode: Mode,
+    /// If `true`, add ignored files as well, like `git add --force`.
+    pub force: bool,
+}
+
+/// The outcome of [`Platform::add()`], with all paths sorted.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// Paths of files that weren't tracked before and are now.
+    pub added: Vec<BString>,
+    /// Paths of tracked files whose content or mode changed, or that were conflicted before.
+    pub modified: Vec<BString>,
+    /// Paths of tracked files that were removed from the index as they don't exist in the worktree anymore.
+    pub removed: Vec<BString>,
+    /// Paths of ignored files or directories that were named explicitly, but weren't added as [force](Options::force) wasn't set.
+    pub ignored: Vec<BString>,
+}
+
+impl Platform<'_> {
+    /// Add worktree files matching `patterns` to the index after cleaning them with the filter pipeline, and configure
+    /// which files to consider with `options`, similar to `git add`. Empty `patterns` match all files.
+    ///
+    /// Ignored files are only added if [forced](Options::force), and nested repositories are added as submodule entries.
+    /// Tracked entries are only rehashed if their stat information changed, and entries marked as `skip-worktree` are
+    /// never touched.
+    ///
+    /// Like in `git`, it's an error if one of the patterns doesn't match a file, in which case the index remains unchanged.
+    pub fn add(
+        &mut self,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let repo = self.repo;
+        let workdir = repo.workdir().expect("checked on instantiation");
+        let patterns: Vec<BString> = patterns.into_iter().map(|p| p.as_ref().to_owned()).collect();
+        let stat_options = repo.stat_options()?;
+        let capabilities = repo.filesystem_options()?;
+        let mut pathspec = repo.pathspec(
+            false, /* empty patterns match prefix */
+            &patterns,
+            true, /* inherit ignore case */
+            &self.index,
+            crate::worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+        )?;
+
+        let mut matched_patterns = HashSet::new();
+        let mut matched_paths = Vec::<(BString, bool)>::new();
+        let mut tracked = Vec::new();
+        if let Some(entries) = self.index.prefixed_entries(pathspec.search().common_prefix()) {
+            for entry in entries {
+                let path = entry.path(&self.index);
+                if tracked.last().is_some_and(|(last, _): &(BString, _)| last == path) {
+                    continue;
+                }
+                let Some(m) = pathspec.pattern_matching_relative_path(path, Some(false)) else {
+                    continue;
+                };
+                if m.is_excluded() {
+                    continue;
+                }
+                matched_patterns.insert(m.pattern.to_bstring());
+                matched_paths.push((path.to_owned(), false));
+                if entry.flags.contains(entry::Flags::SKIP_WORKTREE) {
+                    continue;
+                }
+                tracked.push((path.to_owned(), entry.mode));
+            }
+        }
+
+        let mut untracked = Vec::new();
+        let mut out = Outcome::default();
+        if options.mode == Mode::All {
+            let walk_options = repo
+                .dirwalk_options()?
+                .emit_untracked(EmissionMode::Matching)
+                .emit_ignored(Some(EmissionMode::CollapseDirectory));
+            let mut collect = gix_dir::walk::delegate::Collect::default();
+            repo.dirwalk(
+                &self.index,
+                &patterns,
+                &AtomicBool::default(),
+                walk_options,
+                &mut collect,
+            )?;
+            for (entry, _dir_status) in collect.into_entries_by_path() {
+                let is_dir = entry.disk_kind.is_some_and(|kind| kind == Kind::Directory);
+                let Some(m) = pathspec.pattern_matching_relative_path(entry.rela_path.as_bstr(), Some(is_dir)) else {
+                    continue;
+                };
+                if m.is_excluded() {
+                    continue;
+                }
+                let is_named_explicitly = m.pattern.path() == entry.rela_path;
+                let pattern = m.pattern.to_bstring();
+                match entry.status {
+                    Status::Untracked => {}
+                    Status::Ignored(_) if options.force => {}
+                    Status::Ignored(_) => {
+                        matched_patterns.insert(pattern);
+                        matched_paths.push((entry.rela_path.clone(), is_dir));
+                        if is_named_explicitly {
+                            out.ignored.push(entry.rela_path);
+                        }
+                        continue;
+                    }
+                    Status::Pruned | Status::Tracked => continue,
+                }
+                match entry.disk_kind {
+                    Some(Kind::File | Kind::Symlink | Kind::Repository) => {
+                        matched_patterns.insert(pattern);
+                        matched_paths.push((entry.rela_path.clone(), is_dir));
+                        untracked.push(entry.rela_path);
+                    }
+                    Some(Kind::Directory) if options.force => {
+                        let mut files = Vec::new();
+                        collect_files_in_ignored_directory(workdir, entry.rela_path, &mut files)?;
+                        for path in files {
+                            if pathspec.is_included(path.as_bstr(), Some(false)) {
+                                matched_patterns.insert(pattern.clone());
+                                matched_paths.push((path.clone(), false));
+                                untracked.push(path);
+                            }
+                        }
+                    }
+                    Some(Kind::Directory | Kind::Untrackable) | None => {}
+                }
+            }
+        }
+
+        if let Some(pattern) = first_unmatched_pattern(
+            pathspec.search(),
+            &matched_patterns,
+            matched_paths.iter().map(|(path, is_dir)| (path.as_bstr(), *is_dir)),
+        ) {
+            return Err(Error::PathspecDidNotMatch { pattern });
+        }
+        drop(pathspec);
+
+        let mut updates = Vec::new();
+        for (path, mode) in tracked {
+            let is_conflicted = self
+                .index
+                .entry_range(path.as_bstr())
+                .is_some_and(|range| range.len() > 1 || self.index.entries()[range.start].stage_raw() != 0);
+            let entry = self
+                .index
+                .entry_by_path_and_stage(path.as_bstr(), entry::Stage::Unconflicted);
+            if let Some(entry) = entry.filter(|_| !is_conflicted) {
+                let worktree_path = workdir.join(gix_path::from_bstr(path.as_bstr()));
+                let metadata = match gix_index::fs::Metadata::from_path_no_follow(&worktree_path) {
+                    Ok(md) => Some(md),
+                    Err(err) if gix_fs::io_err::is_not_found(err.kind(), err.raw_os_error()) => None,
+                    Err(source) => {
+                        return Err(super::file::Error::Io {
+                            source,
+                            path: worktree_path,
+                        }
+                        .into())
+                    }
+                };
+                if let Some(metadata) = metadata {
+                    if !entry.flags.contains(entry::Flags::INTENT_TO_ADD)
+                        && self.is_unchanged(entry, &metadata, stat_options, capabilities)?
+                    {
+                        continue;
+                    }
+                }
+            }
+            let previous = entry.map(|e| (e.id, e.mode));
+            match self.worktree_file(path.as_bstr(), Some(mode), true)? {
+                Some(file) => {
+                    if is_conflicted || previous != Some((file.id, file.mode)) {
+                        out.modified.push(path.clone());
+                    }
+                    updates.push((path, Some(file)));
+                }
+                None => {
+                    out.removed.push(path.clone());
+                    updates.push((path, None));
+                }
+            }
+        }
+        for path in untracked {
+            if let Some(file) = self.worktree_file(path.as_bstr(), None, true)? {
+                out.added.push(path.clone());
+                updates.push((path, Some(file)));
+            }
+        }
+
+        let index = &mut self.index;
+        for (path, _) in updates.iter().filter(|(_, file)| file.is_none()) {
+            index.remove_entries_at_path(path.as_bstr());
+        }
+        for (path, file) in updates {
+            let Some(WorktreeFile { id, mode, stat }) = file else {
+                continue;
+            };
+            if index
+                .entry_range(path.as_bstr())
+                .is_some_and(|range| range.len() > 1 || index.entries()[range.start].stage_raw() != 0)
+            {
+                index.remove_entries_at_path(path.as_bstr());
+            }
+            remove_entries_conflicting_with(index, path.as_bstr());
+            index.upsert_entry(stat, id, entry::Flags::empty(), mode, path.as_bstr());
+        }
+
+        out.added.sort();
+        out.modified.sort();
+        out.removed.sort();
+        out.ignored.sort();
+        Ok(out)
+    }
+}
+
+/// Remove all entries that can't exist along with a file at `path`, i.e. files at leading directories
+/// of `path` and files within `path` if it was a directory.
+fn remove_entries_conflicting_with(index: &mut gix_index::State, path: &BStr) {
+    for pos in path.find_iter("/") {
+        index.remove_entries_at_path(path[..pos].as_bstr());
+    }
+    let mut dir = path.to_owned();
+    dir.push(b'/');
+    if let Some(range) = index.prefixed_entries_range(dir.as_bstr()) {
+        let paths: Vec<BString> = index.entries()[range]
+            .iter()
+            .map(|entry| entry.path(index).to_owned())
+            .collect();
+        for path in paths {
+            index.remove_entries_at_path(path.as_bstr());
+        }
+    }
+}
+
+/// Recursively collect all files and nested repositories in the ignored directory at `rela_path` into `out`.
+fn collect_files_in_ignored_directory(
+    workdir: &std::path::Path,
+    rela_path: BString,
+    out: &mut Vec<BString>,
+) -> Result<(), Error> {
+    let path = workdir.join(gix_path::from_bstr(rela_path.as_bstr()));
+    if path.join(gix_discover::DOT_GIT_DIR).exists() {
+        out.push(rela_path);
+        return Ok(());
+    }
+    let read_dir_error = |source| Error::ReadDir {
+        source,
+        path: path.clone(),
+    };
+    let mut entries = std::fs::read_dir(&path)
+        .map_err(read_dir_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(read_dir_error)?;
+    entries.sort_by_key(std::fs::DirEntry::file_name);
+    for entry in entries {
+        let mut child = rela_path.clone();
+        child.push(b'/');
+        child.extend_from_slice(&gix_path::into_bstr(std::path::PathBuf::from(entry.file_name())));
+        let file_type = entry.file_type().map_err(read_dir_error)?;
+        if file_type.is_dir() {
+            collect_files_in_ignored_directory(workdir, child, out)?;
+        } else if file_type.is_file() || file_type.is_symlink() {
+            out.push(child);
+        }
+    }
+    Ok(())
+}
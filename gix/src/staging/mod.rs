@@ -0,0 +1,241 @@
+//! Change the index by adding, removing and moving entries based on the worktree, similar to `git add`, `git rm` and `git mv`.
+//!
+//! All changes are applied to an in-memory copy of the index which is held by the [`Platform`], and
+//! they take effect once it is [written](Platform::write()).
+use std::collections::HashSet;
+
+use gix_index::entry;
+
+use crate::{
+    bstr::{BStr, BString},
+    filter, Repository,
+};
+
+///
+pub mod add;
+///
+pub mod remove;
+///
+pub mod rename;
+
+///
+pub mod init {
+    /// The error returned by [`Repository::staging()`](crate::Repository::staging()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("A working tree is required to change the index based on it")]
+        MissingWorkDir,
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+    }
+}
+
+///
+pub mod file {
+    use std::path::PathBuf;
+
+    /// The error returned when turning a worktree file into an object that can be added to the index.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+        #[error(transparent)]
+        WorktreeFileToObject(#[from] crate::filter::pipeline::worktree_file_to_object::Error),
+        #[error(transparent)]
+        ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+        #[error(transparent)]
+        FilesystemOptions(#[from] crate::config::boolean::Error),
+        #[error("Could not obtain the metadata or content of '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error(transparent)]
+        Time(#[from] std::time::SystemTimeError),
+        #[error(transparent)]
+        Hash(#[from] gix_hash::hasher::Error),
+    }
+}
+
+/// A way to change the index of a repository based on its worktree, obtained with [`Repository::staging()`].
+///
+/// Changes are made to a copy of the index which has to be [written](Self::write()) to become visible to others.
+pub struct Platform<'repo> {
+    pub(crate) repo: &'repo Repository,
+    pub(crate) index: gix_index::File,
+    pipeline: Option<filter::Pipeline<'repo>>,
+}
+
+/// The state of a worktree file as it would be stored in the index.
+pub(crate) struct WorktreeFile {
+    pub id: gix_hash::ObjectId,
+    pub mode: entry::Mode,
+    pub stat: entry::Stat,
+}
+
+/// Lifecycle
+impl<'repo> Platform<'repo> {
+    pub(crate) fn new(repo: &'repo Repository) -> Result<Self, init::Error> {
+        if repo.workdir().is_none() {
+            return Err(init::Error::MissingWorkDir);
+        }
+        let index = (**repo.index_or_empty()?).clone();
+        Ok(Platform {
+            repo,
+            index,
+            pipeline: None,
+        })
+    }
+
+    /// Write the changed index back to disk, replacing the index of the repository.
+    ///
+    /// The version of the index is retained, while new indices use the version configured with `index.version`.
+    pub fn write(&mut self) -> Result<(), gix_index::file::write::Error> {
+        self.index.write(Default::default())
+    }
+
+    /// Return the index with all changes applied so far.
+    pub fn into_index(self) -> gix_index::File {
+        self.index
+    }
+}
+
+/// Access
+impl Platform<'_> {
+    /// Return the index with all changes applied so far.
+    pub fn index(&self) -> &gix_index::File {
+        &self.index
+    }
+}
+
+/// Utilities
+impl Platform<'_> {
+    /// Return `true` if the stat information of `entry` still matches `metadata` of its worktree file, so its content
+    /// can be assumed unchanged.
+    fn is_unchanged(
+        &self,
+        entry: &gix_index::Entry,
+        metadata: &gix_index::fs::Metadata,
+        stat_options: entry::stat::Options,
+        capabilities: gix_fs::Capabilities,
+    ) -> Result<bool, file::Error> {
+        Ok(entry
+            .mode
+            .change_to_match_fs(metadata, capabilities.symlink, capabilities.executable_bit)
+            .is_none()
+            && entry.stat.matches(&entry::Stat::from_fs(metadata)?, stat_options)
+            && !entry.stat.is_racy(self.index.timestamp(), stat_options))
+    }
+
+    /// Turn the worktree file at `rela_path` into an object and return it, or `None` if it doesn't exist or can't be tracked.
+    /// If `write` is `false`, the object is only hashed, but not written to the object database.
+    /// `previous_mode` is the mode of the index entry at `rela_path`, if there is one, and used if the filesystem
+    /// can't represent the executable bit.
+    fn worktree_file(
+        &mut self,
+        rela_path: &BStr,
+        previous_mode: Option<entry::Mode>,
+        write: bool,
+    ) -> Result<Option<WorktreeFile>, file::Error> {
+        let repo = self.repo;
+        let capabilities = repo.filesystem_options()?;
+        let pipeline = match self.pipeline.as_mut() {
+            Some(pipeline) => pipeline,
+            None => self.pipeline.insert(repo.filter_pipeline(None)?.0),
+        };
+        let path = repo
+            .workdir()
+            .expect("checked on instantiation")
+            .join(gix_path::from_bstr(rela_path));
+        let metadata = match gix_index::fs::Metadata::from_path_no_follow(&path) {
+            Ok(md) => md,
+            Err(err) if gix_fs::io_err::is_not_found(err.kind(), err.raw_os_error()) => return Ok(None),
+            Err(source) => return Err(file::Error::Io { source, path }),
+        };
+
+        let (id, mode) = if write {
+            let Some((id, kind, _)) = pipeline.worktree_file_to_object(rela_path, &self.index)? else {
+                return Ok(None);
+            };
+            (id, entry::Mode::from(gix_object::tree::EntryMode::from(kind)))
+        } else if metadata.is_symlink() {
+            let target = std::fs::read_link(&path).map_err(|source| file::Error::Io { source, path })?;
+            let target = gix_path::into_bstr(target);
+            (
+                gix_object::compute_hash(repo.object_hash(), gix_object::Kind::Blob, &target)?,
+                entry::Mode::SYMLINK,
+            )
+        } else if metadata.is_file() {
+            use std::io::Read;
+
+            let file = std::fs::File::open(&path).map_err(|source| file::Error::Io {
+                source,
+                path: path.clone(),
+            })?;
+            let mut buf = Vec::new();
+            pipeline
+                .convert_to_git(file, gix_path::from_bstr(rela_path).as_ref(), &self.index)?
+                .read_to_end(&mut buf)
+                .map_err(|source| file::Error::Io { source, path })?;
+            let mode = if metadata.is_executable() {
+                entry::Mode::FILE_EXECUTABLE
+            } else {
+                entry::Mode::FILE
+            };
+            (
+                gix_object::compute_hash(repo.object_hash(), gix_object::Kind::Blob, &buf)?,
+                mode,
+            )
+        } else if metadata.is_dir() {
+            let Some(id) = crate::open_opts(&path, repo.open_options().clone())
+                .ok()
+                .and_then(|submodule| submodule.head_id().ok().map(crate::Id::detach))
+            else {
+                return Ok(None);
+            };
+            (id, entry::Mode::COMMIT)
+        } else {
+            return Ok(None);
+        };
+
+        let mode = match (mode, previous_mode) {
+            (entry::Mode::FILE | entry::Mode::FILE_EXECUTABLE, Some(previous @ entry::Mode::FILE_EXECUTABLE))
+                if !capabilities.executable_bit =>
+            {
+                previous
+            }
+            (entry::Mode::FILE_EXECUTABLE, _) if !capabilities.executable_bit => entry::Mode::FILE,
+            _ => mode,
+        };
+        Ok(Some(WorktreeFile {
+            id,
+            mode,
+            stat: entry::Stat::from_fs(&metadata)?,
+        }))
+    }
+}
+
+/// Return the path of the first positive pattern of `search` that doesn't match any of the `matched_paths`, given that patterns
+/// in `matched_patterns` are known to match. Patterns with attributes are assumed to match.
+fn first_unmatched_pattern<'a>(
+    search: &gix_pathspec::Search,
+    matched_patterns: &HashSet<BString>,
+    matched_paths: impl Iterator<Item = (&'a BStr, bool)> + Clone,
+) -> Option<BString> {
+    search
+        .patterns()
+        .filter(|pattern| !pattern.is_excluded() && !pattern.is_nil() && pattern.attributes.is_empty())
+        .filter(|pattern| !matched_patterns.contains(&pattern.to_bstring()))
+        .find(|pattern| {
+            let Ok(mut single) =
+                gix_pathspec::Search::from_specs(Some((*pattern).clone()), None, std::path::Path::new(""))
+            else {
+                return true;
+            };
+            !matched_paths.clone().any(|(path, is_dir)| {
+                single
+                    .pattern_matching_relative_path(path, Some(is_dir), &mut |_, _, _, _| false)
+                    .is_some()
+            })
+        })
+        .map(|pattern| pattern.path().to_owned())
+}
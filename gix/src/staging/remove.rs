@@ -0,0 +1,219 @@
+use std::collections::HashSet;
+
+use gix_index::entry;
+
+use super::{first_unmatched_pattern, Platform};
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// The error returned by [`Platform::remove()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Pathspec(#[from] crate::pathspec::init::Error),
+    #[error(transparent)]
+    FilesystemOptions(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    StatOptions(#[from] crate::config::stat_options::Error),
+    #[error(transparent)]
+    HeadTree(#[from] crate::reference::head_tree_id::Error),
+    #[error(transparent)]
+    FindTree(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    File(#[from] super::file::Error),
+    #[error("pathspec '{pattern}' did not match any files")]
+    PathspecDidNotMatch { pattern: BString },
+    #[error("not removing '{pattern}' recursively without recursion enabled")]
+    NotRecursive { pattern: BString },
+    #[error("'{path}' has staged content different from both the file and the HEAD")]
+    StagedContentDiffersFromFileAndHead { path: BString },
+    #[error("'{path}' has changes staged in the index")]
+    StagedChanges { path: BString },
+    #[error("'{path}' has local modifications")]
+    LocalModifications { path: BString },
+    #[error("Could not remove '{}' from the worktree", path.display())]
+    RemoveFile {
+        source: std::io::Error,
+        path: std::path::PathBuf,
+    },
+}
+
+/// Options for use in [`Platform::remove()`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// If `true`, only remove entries from the index, but keep the files in the worktree, like `git rm --cached`.
+    pub cached: bool,
+    /// If `true`, remove entries even if their files have local modifications or staged changes, like `git rm --force`.
+    pub force: bool,
+    /// If `true`, allow patterns to match a directory and remove all entries in it, like `git rm -r`.
+    pub recursive: bool,
+}
+
+/// The outcome of [`Platform::remove()`].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// Paths of all entries that were removed from the index, sorted.
+    pub removed: Vec<BString>,
+}
+
+impl Platform<'_> {
+    /// Remove all entries matching `patterns` from the index and delete their files in the worktree, configured by
+    /// `options`, similar to `git rm`.
+    ///
+    /// Unless [forced](Options::force), entries are only removed if no data could be lost, i.e. if their files are unchanged
+    /// compared to the index, and the index is unchanged compared to `HEAD`. When only removing from the index, it's
+    /// enough if either the file or the `HEAD` match the index.
+    /// Empty leading directories of removed files are removed, but the directories of submodules are kept if they aren't empty.
+    ///
+    /// Like in `git`, it's an error if one of the patterns doesn't match an entry, in which case the index remains unchanged.
+    pub fn remove(
+        &mut self,
+        patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let repo = self.repo;
+        let workdir = repo.workdir().expect("checked on instantiation");
+        let mut pathspec = repo.pathspec(
+            false, /* empty patterns match prefix */
+            patterns,
+            true, /* inherit ignore case */
+            &self.index,
+            crate::worktree::stack::state::attributes::Source::WorktreeThenIdMapping,
+        )?;
+
+        let mut matched_patterns = HashSet::new();
+        let mut paths = Vec::<BString>::new();
+        if let Some(entries) = self.index.prefixed_entries(pathspec.search().common_prefix()) {
+            for entry in entries {
+                let path = entry.path(&self.index);
+                if paths.last().is_some_and(|last| last == path) {
+                    continue;
+                }
+                let Some(m) = pathspec.pattern_matching_relative_path(path, Some(false)) else {
+                    continue;
+                };
+                if m.is_excluded() {
+                    continue;
+                }
+                if !options.recursive && m.kind == gix_pathspec::search::MatchKind::Prefix {
+                    return Err(Error::NotRecursive {
+                        pattern: m.pattern.path().to_owned(),
+                    });
+                }
+                matched_patterns.insert(m.pattern.to_bstring());
+                paths.push(path.to_owned());
+            }
+        }
+        if let Some(pattern) = first_unmatched_pattern(
+            pathspec.search(),
+            &matched_patterns,
+            paths.iter().map(|path| (path.as_bstr(), false)),
+        ) {
+            return Err(Error::PathspecDidNotMatch { pattern });
+        }
+        drop(pathspec);
+
+        if !options.force {
+            self.check_local_modifications(&paths, options.cached)?;
+        }
+
+        let mut removed_gitlinks = HashSet::new();
+        for path in &paths {
+            if self
+                .index
+                .entry_by_path(path.as_bstr())
+                .is_some_and(|entry| entry.mode == entry::Mode::COMMIT)
+            {
+                removed_gitlinks.insert(path.clone());
+            }
+            self.index.remove_entries_at_path(path.as_bstr());
+        }
+
+        if !options.cached {
+            for path in &paths {
+                let worktree_path = workdir.join(gix_path::from_bstr(path.as_bstr()));
+                let res = if removed_gitlinks.contains(path) {
+                    std::fs::remove_dir(&worktree_path)
+                } else {
+                    std::fs::remove_file(&worktree_path)
+                };
+                match res {
+                    Ok(()) => {}
+                    Err(err) if gix_fs::io_err::is_not_found(err.kind(), err.raw_os_error()) => {}
+                    Err(_) if removed_gitlinks.contains(path) => continue,
+                    Err(source) => {
+                        return Err(Error::RemoveFile {
+                            source,
+                            path: worktree_path,
+                        })
+                    }
+                }
+                let mut dir = worktree_path.parent();
+                while let Some(parent) = dir.filter(|dir| *dir != workdir) {
+                    if std::fs::remove_dir(parent).is_err() {
+                        break;
+                    }
+                    dir = parent.parent();
+                }
+            }
+        }
+
+        Ok(Outcome { removed: paths })
+    }
+
+    /// Fail if removing the entries at `paths` would lose data, like `git rm` does.
+    /// If `cached` is `true`, the files will remain in the worktree.
+    fn check_local_modifications(&mut self, paths: &[BString], cached: bool) -> Result<(), Error> {
+        let repo = self.repo;
+        let workdir = repo.workdir().expect("checked on instantiation");
+        let stat_options = repo.stat_options()?;
+        let capabilities = repo.filesystem_options()?;
+        let head_tree = repo.head_tree_id_or_empty()?.object()?.into_tree();
+        for path in paths {
+            let Some(entry) = self
+                .index
+                .entry_by_path_and_stage(path.as_bstr(), entry::Stage::Unconflicted)
+                .cloned()
+            else {
+                continue;
+            };
+            let worktree_path = workdir.join(gix_path::from_bstr(path.as_bstr()));
+            let metadata = match gix_index::fs::Metadata::from_path_no_follow(&worktree_path) {
+                Ok(md) => md,
+                Err(err) if gix_fs::io_err::is_not_found(err.kind(), err.raw_os_error()) => continue,
+                Err(source) => {
+                    return Err(super::file::Error::Io {
+                        source,
+                        path: worktree_path,
+                    }
+                    .into())
+                }
+            };
+            if metadata.is_dir() && entry.mode != entry::Mode::COMMIT {
+                continue;
+            }
+
+            let local_changes = !self.is_unchanged(&entry, &metadata, stat_options, capabilities)?
+                && self
+                    .worktree_file(path.as_bstr(), Some(entry.mode), false)?
+                    .map_or(true, |file| (file.id, file.mode) != (entry.id, entry.mode));
+            let staged_changes = head_tree.lookup_entry(path.split_str("/"))?.map_or(true, |head| {
+                (head.object_id(), entry::Mode::from(head.mode())) != (entry.id, entry.mode)
+            });
+
+            if local_changes && staged_changes {
+                if !cached || !entry.flags.contains(entry::Flags::INTENT_TO_ADD) {
+                    return Err(Error::StagedContentDiffersFromFileAndHead { path: path.clone() });
+                }
+            } else if !cached {
+                if staged_changes {
+                    return Err(Error::StagedChanges { path: path.clone() });
+                }
+                if local_changes {
+                    return Err(Error::LocalModifications { path: path.clone() });
+                }
+            }
+        }
+        Ok(())
+    }
+}
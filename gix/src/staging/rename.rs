@@ -0,0 +1,127 @@
+use gix_index::entry;
+
+use super::Platform;
+use crate::bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// The error returned by [`Platform::rename()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("'{path}' is not under version control")]
+    SourceNotTracked { path: BString },
+    #[error("'{path}' doesn't exist in the worktree")]
+    SourceMissing { path: BString },
+    #[error("'{path}' is conflicted")]
+    Conflicted { path: BString },
+    #[error("Cannot move '{source_path}' into itself at '{destination}'")]
+    IntoItself { source_path: BString, destination: BString },
+    #[error("Destination '{path}' exists")]
+    DestinationExists { path: BString },
+    #[error("The directory to contain the destination at '{path}' doesn't exist")]
+    DestinationDirectoryMissing { path: BString },
+    #[error("Could not move '{}' to '{}'", from.display(), to.display())]
+    Rename {
+        source: std::io::Error,
+        from: std::path::PathBuf,
+        to: std::path::PathBuf,
+    },
+}
+
+/// Options for use in [`Platform::rename()`].
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// If `true`, overwrite an existing file at the destination, like `git mv --force`.
+    pub force: bool,
+}
+
+/// The outcome of [`Platform::rename()`].
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The `(source, destination)` paths of all moved entries, sorted by source.
+    pub renamed: Vec<(BString, BString)>,
+}
+
+impl Platform<'_> {
+    /// Move the tracked file or directory at `source` to `destination` in the worktree and the index, similar to `git mv`.
+    /// Both paths are relative to the root of the worktree.
+    /// If `destination` is an existing directory, `source` is moved into it.
+    ///
+    /// Note that `.gitmodules` isn't adjusted when moving submodules.
+    pub fn rename(&mut self, source: &BStr, destination: &BStr, options: Options) -> Result<Outcome, Error> {
+        let workdir = self.repo.workdir().expect("checked on instantiation");
+        let to_path = |rela_path: &BStr| workdir.join(gix_path::from_bstr(rela_path));
+        let source = source.trim_end_with(|c| c == '/').as_bstr();
+        let mut destination = destination.trim_end_with(|c| c == '/').as_bstr().to_owned();
+
+        if to_path(destination.as_bstr()).is_dir() && !to_path(destination.as_bstr()).is_symlink() {
+            let name = source.rsplit_str("/").next().expect("at least one item");
+            if !destination.is_empty() {
+                destination.push_byte(b'/');
+            }
+            destination.push_str(name);
+        }
+        if destination == source || (destination.starts_with(source) && destination.get(source.len()) == Some(&b'/')) {
+            return Err(Error::IntoItself {
+                source_path: source.to_owned(),
+                destination,
+            });
+        }
+
+        let source_range = match self.index.entry_range(source) {
+            Some(range) => {
+                if range.len() > 1 || self.index.entries()[range.start].stage() != entry::Stage::Unconflicted {
+                    return Err(Error::Conflicted { path: source.into() });
+                }
+                range
+            }
+            None => {
+                let mut dir = source.to_owned();
+                dir.push_byte(b'/');
+                self.index
+                    .prefixed_entries_range(dir.as_bstr())
+                    .filter(|range| !range.is_empty())
+                    .ok_or_else(|| Error::SourceNotTracked { path: source.into() })?
+            }
+        };
+        if let Some(entry) = self.index.entries()[source_range.clone()]
+            .iter()
+            .find(|entry| entry.stage() != entry::Stage::Unconflicted)
+        {
+            return Err(Error::Conflicted {
+                path: entry.path(&self.index).to_owned(),
+            });
+        }
+        if to_path(source).symlink_metadata().is_err() {
+            return Err(Error::SourceMissing { path: source.into() });
+        }
+        if to_path(destination.as_bstr()).symlink_metadata().is_ok() && !options.force {
+            return Err(Error::DestinationExists { path: destination });
+        }
+        if let Some((parent, _)) = destination.rsplit_once_str("/") {
+            if !to_path(parent.as_bstr()).is_dir() {
+                return Err(Error::DestinationDirectoryMissing {
+                    path: parent.as_bstr().into(),
+                });
+            }
+        }
+
+        let (from, to) = (to_path(source), to_path(destination.as_bstr()));
+        std::fs::rename(&from, &to).map_err(|source| Error::Rename { source, from, to })?;
+
+        let entries: Vec<_> = self.index.entries()[source_range]
+            .iter()
+            .map(|entry| (entry.path(&self.index).to_owned(), entry.clone()))
+            .collect();
+        let mut out = Outcome::default();
+        self.index.remove_entries_at_path(destination.as_bstr());
+        for (path, entry) in entries {
+            self.index.remove_entries_at_path(path.as_bstr());
+            let mut new_path = destination.clone();
+            new_path.push_str(&path[source.len()..]);
+            self.index
+                .upsert_entry(entry.stat, entry.id, entry.flags, entry.mode, new_path.as_bstr());
+            out.renamed.push((path, new_path));
+        }
+        Ok(out)
+    }
+}
@@ -4,7 +4,9 @@ use gix_status::index_as_worktree::traits::{CompareBlobs, SubmoduleStatus};
 
 use crate::{
     bstr::{BStr, BString},
-    config, Repository,
+    config,
+    config::cache::util::ApplyLeniencyDefault,
+    Repository,
 };
 
 /// The error returned by [Repository::index_worktree_status()].
@@ -27,6 +29,18 @@ pub enum Error {
     StatOptions(#[from] config::stat_options::Error),
     #[error(transparent)]
     ResourceCache(#[from] crate::diff::resource_cache::Error),
+    #[error(transparent)]
+    UntrackedCacheConfig(#[from] config::key::GenericErrorWithValue),
+}
+
+/// Return the name of the system like `git` uses it to identify the location of an untracked cache.
+fn system_name() -> &'static str {
+    match std::env::consts::OS {
+        "linux" => "Linux",
+        "macos" => "Darwin",
+        "windows" => "Windows",
+        other => other,
+    }
 }
 
 /// Options for use with [Repository::index_worktree_status()].
@@ -56,7 +70,7 @@ pub struct Options {
     /// When rewrite tracking is enabled, there will be a delay for some entries as they partake in the rename-analysis.
     pub rewrites: Option<gix_diff::Rewrites>,
     /// If set, don't use more than this amount of threads for the tracked modification check.
-    /// Otherwise, usually use as many threads as there are logical cores.
+    /// Otherwise, usually use as many threads as there are logical cores, or a single thread if `core.preloadIndex` is `false`.
     /// A value of 0 is interpreted as no-limit
     pub thread_limit: Option<usize>,
 }
@@ -119,6 +133,10 @@ impl Repository {
 
         let cwd = self.current_dir();
         let git_dir_realpath = crate::path::realpath_opts(self.git_dir(), cwd, crate::path::realpath::MAX_SYMLINKS)?;
+        let untracked_cache = match options.dirwalk_options.as_ref() {
+            Some(dirwalk_options) => self.untracked_cache_for_dirwalk(index, workdir, dirwalk_options)?,
+            None => None,
+        };
         let fs_caps = self.filesystem_options()?;
         let accelerate_lookup = fs_caps.ignore_case.then(|| index.prepare_icase_backing());
         let resource_cache = crate::diff::resource_cache(
@@ -147,6 +165,7 @@ impl Repository {
                     git_dir_realpath: git_dir_realpath.as_path(),
                     current_dir: cwd,
                     ignore_case_index_lookup: accelerate_lookup.as_ref(),
+                    untracked_cache,
                 },
             },
             gix_status::index_as_worktree_with_renames::Options {
@@ -154,7 +173,10 @@ impl Repository {
                 object_hash: self.object_hash(),
                 tracked_file_modifications: gix_status::index_as_worktree::Options {
                     fs: fs_caps,
-                    thread_limit: options.thread_limit,
+                    thread_limit: match options.thread_limit {
+                        Some(limit) => Some(limit),
+                        None => self.config.preload_index_thread_limit()?,
+                    },
                     stat: self.stat_options()?,
                 },
                 dirwalk: options.dirwalk_options.map(Into::into),
@@ -164,6 +186,72 @@ impl Repository {
         Ok(out)
     }
 
+    /// Return the untracked cache of `index` to use with a dirwalk in `workdir` configured with `options`, as configured by
+    /// `core.untrackedCache`, after invalidating it if the global excludes changed since it was filled.
+    fn untracked_cache_for_dirwalk(
+        &self,
+        index: &gix_index::State,
+        workdir: &std::path::Path,
+        options: &crate::dirwalk::Options,
+    ) -> Result<Option<gix_index::extension::UntrackedCache>, Error> {
+        use gix_index::extension::{
+            untracked_cache::{OidStat, DIR_HIDE_EMPTY_DIRECTORIES, DIR_SHOW_OTHER_DIRECTORIES},
+            UntrackedCache,
+        };
+
+        let use_cache = config::tree::Core::UNTRACKED_CACHE
+            .try_into_untracked_cache(self.config.resolved.boolean(config::tree::Core::UNTRACKED_CACHE))
+            .with_lenient_default(self.config.lenient_config)?;
+        let realpath_workdir =
+            crate::path::realpath_opts(workdir, self.current_dir(), crate::path::realpath::MAX_SYMLINKS)?;
+        let location = format!("Location {}, system ", realpath_workdir.display());
+        let cache = match use_cache {
+            Some(false) => return Ok(None),
+            keep_or_create => match index
+                .untracked()
+                .filter(|cache| cache.identifier().starts_with(location.as_bytes()))
+            {
+                Some(cache) => cache.clone(),
+                None if keep_or_create == Some(true) => {
+                    let dir_flags = if gix_dir::walk::Options::from(*options).emit_untracked
+                        == gix_dir::walk::EmissionMode::CollapseDirectory
+                    {
+                        DIR_SHOW_OTHER_DIRECTORIES | DIR_HIDE_EMPTY_DIRECTORIES
+                    } else {
+                        0
+                    };
+                    let mut identifier = BString::from(format!("{location}{}", system_name()));
+                    identifier.push(0);
+                    UntrackedCache::new(identifier, ".gitignore", dir_flags)
+                }
+                None => return Ok(None),
+            },
+        };
+
+        let object_hash = index.object_hash();
+        let oid_stat = |path: &std::path::Path| -> Option<OidStat> {
+            let data = std::fs::read(path).ok()?;
+            let stat = gix_index::fs::Metadata::from_path_no_follow(path)
+                .ok()
+                .and_then(|meta| gix_index::entry::Stat::from_fs(&meta).ok())
+                .unwrap_or_default();
+            Some(OidStat {
+                stat,
+                id: gix_object::compute_hash(object_hash, gix_object::Kind::Blob, &data).ok()?,
+            })
+        };
+        let excludes_file = match self.config.excludes_file().and_then(Result::ok) {
+            Some(path) => Some(path),
+            None => self.config.xdg_config_path("ignore").ok().flatten(),
+        };
+        let mut cache = cache;
+        cache.set_global_excludes(
+            oid_stat(&self.common_dir().join("info").join("exclude")),
+            excludes_file.as_deref().and_then(oid_stat),
+        );
+        Ok(Some(cache))
+    }
+
     pub(super) fn index_worktree_status_pathspec<E>(
         &self,
         patterns: impl IntoIterator<Item = impl AsRef<BStr>>,
@@ -221,7 +309,11 @@ mod submodule_status {
             let local_repo = repo.to_thread_local();
             let submodule_paths = match local_repo.submodules() {
                 Ok(Some(sm)) => {
-                    let mut v: Vec<_> = sm.filter_map(|sm| sm.path().ok().map(Cow::into_owned)).collect();
+                    // Submodules whose activity can't be determined are kept to learn about errors later.
+                    let mut v: Vec<_> = sm
+                        .filter(|sm| sm.is_active().unwrap_or(true))
+                        .filter_map(|sm| sm.path().ok().map(Cow::into_owned))
+                        .collect();
                     v.sort();
                     v
                 }
@@ -254,6 +346,8 @@ mod submodule_status {
         SubmoduleStatus(#[from] crate::submodule::status::Error),
         #[error(transparent)]
         IgnoreConfig(#[from] crate::submodule::config::Error),
+        #[error(transparent)]
+        DiffIgnoreSubmodulesConfig(#[from] crate::config::key::GenericErrorWithValue),
     }
 
     impl gix_status::index_as_worktree::traits::SubmoduleStatus for BuiltinSubmoduleStatus {
@@ -282,7 +376,13 @@ mod submodule_status {
                 return Ok(None);
             };
             let (ignore, check_dirty) = match self.mode {
-                Submodule::AsConfigured { check_dirty } => (sm.ignore()?.unwrap_or_default(), check_dirty),
+                Submodule::AsConfigured { check_dirty } => (
+                    match sm.ignore()? {
+                        Some(ignore) => ignore,
+                        None => repo.config.diff_ignore_submodules()?.unwrap_or_default(),
+                    },
+                    check_dirty,
+                ),
                 Submodule::Given { ignore, check_dirty } => (ignore, check_dirty),
             };
             let status = sm.status(ignore, check_dirty)?;
@@ -298,8 +398,10 @@ mod submodule_status {
 ///
 /// ### Submodules
 ///
-/// Note that submodules can be set to 'inactive', which will not exclude them from the status operation, similar to
-/// how `git status` includes them.
+/// Note that submodules can be set to 'inactive', which excludes them from the status operation, unlike
+/// `git status` which includes them if they are checked out.
+/// Active submodules are checked in parallel if the `parallel` feature is enabled, and as their status includes the status
+/// of their own submodules, it is recursive.
 ///
 /// ### Index Changes
 ///
@@ -45,6 +45,10 @@ where
             None => IndexPersistedOrInMemory::Persisted(self.repo.index_or_empty()?),
             Some(index) => index,
         };
+        let (index, fs_monitor_changed_entries) = match refresh_fs_monitor(self.repo, &index)? {
+            Some(refreshed_index) => (IndexPersistedOrInMemory::InMemory(refreshed_index), Some(Vec::new())),
+            None => (index, None),
+        };
 
         let obtain_tree_id = || -> Result<Option<gix_hash::ObjectId>, crate::status::into_iter::Error> {
             Ok(match self.head_tree {
@@ -98,6 +102,10 @@ where
                                 Some(&mut pathspec),
                                 tree_index_renames,
                                 |change, _, _| {
+                                    // Conflicts are already reported as part of the index-worktree status.
+                                    if matches!(change, gix_diff::index::ChangeRef::Unmerged { .. }) {
+                                        return Ok(gix_diff::index::Action::Continue);
+                                    }
                                     let action = if tx.send(change.into_owned().into()).is_err()
                                         || should_interrupt.load(Ordering::Acquire)
                                     {
@@ -140,6 +148,7 @@ where
                             tree_index: None,
                             worktree_index: index,
                             changes: None,
+                            fs_monitor_changed_entries: None,
                             skip_hash,
                         })
                     }
@@ -150,6 +159,7 @@ where
                 rx_and_join: Some((rx, join_index_worktree, join_tree_index)),
                 should_interrupt,
                 index_changes: Vec::new(),
+                fs_monitor_changed_entries,
                 out: None,
             })
         }
@@ -175,7 +185,10 @@ where
                         Some(&mut pathspec),
                         self.tree_index_renames,
                         |change, _, _| {
-                            items.push(change.into_owned().into());
+                            // Conflicts are already reported as part of the index-worktree status.
+                            if !matches!(change, gix_diff::index::ChangeRef::Unmerged { .. }) {
+                                items.push(change.into_owned().into());
+                            }
                             let action = if should_interrupt.load(Ordering::Acquire) {
                                 gix_diff::index::Action::Cancel
                             } else {
@@ -201,6 +214,7 @@ where
             let mut iter = Iter {
                 items: Vec::new().into_iter(),
                 index_changes: Vec::new(),
+                fs_monitor_changed_entries,
                 out: None,
             };
             let mut out = Outcome {
@@ -208,6 +222,7 @@ where
                 worktree_index: index,
                 tree_index,
                 changes: None,
+                fs_monitor_changed_entries: None,
                 skip_hash,
             };
             items.extend(
@@ -217,6 +232,7 @@ where
                     .filter_map(|item| iter.maybe_keep_index_change(item)),
             );
             out.changes = (!iter.index_changes.is_empty()).then(|| std::mem::take(&mut iter.index_changes));
+            out.fs_monitor_changed_entries = iter.fs_monitor_changed_entries.take();
             iter.items = items.into_iter();
             iter.out = Some(out);
             Ok(iter)
@@ -224,6 +240,30 @@ where
     }
 }
 
+/// Query the filesystem monitor hook configured in `core.fsmonitor`, if there is one, to learn which entries of `index` changed,
+/// and return a copy of `index` with all other entries marked as valid so they aren't checked for modifications.
+fn refresh_fs_monitor(
+    repo: &crate::Repository,
+    index: &IndexPersistedOrInMemory,
+) -> Result<Option<gix_index::File>, crate::status::into_iter::Error> {
+    let Some(workdir) = repo.workdir() else {
+        return Ok(None);
+    };
+    let Some(program) = repo.config.apply_leniency(repo.config.fs_monitor_hook())? else {
+        return Ok(None);
+    };
+    let mut index = match index {
+        IndexPersistedOrInMemory::Persisted(index) => (***index).clone(),
+        IndexPersistedOrInMemory::InMemory(index) => index.clone(),
+    };
+    let mut hook = gix_status::fs_monitor::Hook {
+        program: program.into_os_string(),
+        worktree: workdir.to_owned(),
+    };
+    gix_status::fs_monitor::refresh(&mut index, &mut hook)?;
+    Ok(Some(index))
+}
+
 /// The error returned for each item returned by [`Iter`].
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -269,6 +309,7 @@ impl Iterator for Iter {
                     break match worktree_handle.join().expect("no panic") {
                         Ok(mut out) => {
                             out.changes = Some(std::mem::take(&mut self.index_changes));
+                            out.fs_monitor_changed_entries = self.fs_monitor_changed_entries.take();
                             out.tree_index = tree_index;
                             self.out = Some(out);
                             None
@@ -299,6 +340,26 @@ impl Iter {
 
 impl Iter {
     fn maybe_keep_index_change(&mut self, item: Item) -> Option<Item> {
+        if let Some(changed_entries) = self.fs_monitor_changed_entries.as_mut() {
+            match &item {
+                Item::IndexWorktree(index_worktree::Item::Modification {
+                    status: EntryStatus::NeedsUpdate(_),
+                    ..
+                }) => {}
+                Item::IndexWorktree(
+                    index_worktree::Item::Modification { entry_index, .. }
+                    | index_worktree::Item::Rewrite {
+                        source:
+                            index_worktree::RewriteSource::RewriteFromIndex {
+                                source_entry_index: entry_index,
+                                ..
+                            },
+                        ..
+                    },
+                ) => changed_entries.push(*entry_index),
+                _ => {}
+            }
+        }
         match item {
             Item::IndexWorktree(index_worktree::Item::Modification {
                 status: EntryStatus::NeedsUpdate(stat),
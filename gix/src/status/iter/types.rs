@@ -47,6 +47,8 @@ pub struct Iter {
     pub(in crate::status) out: Option<Outcome>,
     /// The set of `(entry_index, change)` we extracted in order to potentially write back the worktree index with the changes applied.
     pub(super) index_changes: Vec<(usize, ApplyChange)>,
+    /// The indices of entries that changed, if the filesystem monitor was used, to know which of its dirty entries remain dirty.
+    pub(super) fs_monitor_changed_entries: Option<Vec<usize>>,
 }
 
 /// The item produced by the [iterator](Iter).
@@ -57,6 +59,9 @@ pub enum Item {
     /// Note that untracked changes are also collected here.
     IndexWorktree(index_worktree::Item),
     /// A change between the three of `HEAD` and the index.
+    ///
+    /// Note that conflicts are only reported as part of [`Item::IndexWorktree`], so this is never
+    /// [`Unmerged`](gix_diff::index::ChangeRef::Unmerged).
     TreeIndex(gix_diff::index::Change),
 }
 
@@ -71,6 +76,7 @@ pub struct Outcome {
     pub worktree_index: IndexPersistedOrInMemory,
     pub(super) skip_hash: bool,
     pub(super) changes: Option<Vec<(usize, ApplyChange)>>,
+    pub(super) fs_monitor_changed_entries: Option<Vec<usize>>,
 }
 
 impl Outcome {
@@ -80,17 +86,46 @@ impl Outcome {
     /// additional work can be prevented by writing the changes back to the index.
     pub fn has_changes(&self) -> bool {
         self.changes.as_ref().is_some_and(|changes| !changes.is_empty())
+            || self.has_untracked_cache_changes()
+            || self.fs_monitor_changed_entries.is_some()
+    }
+
+    /// Returns `true` if the untracked cache was created or updated during the directory walk.
+    fn has_untracked_cache_changes(&self) -> bool {
+        self.index_worktree
+            .untracked_cache
+            .as_ref()
+            .is_some_and(|cache| self.worktree_index.untracked() != Some(cache))
     }
 
-    /// Write the changes if there are any back to the index file.
+    /// Write the changes if there are any back to the index file, along with the untracked cache if it was updated,
+    /// and the state of the filesystem monitor if it was used.
     /// This can only be done once as the changes are consumed in the process, if there were any.
     pub fn write_changes(&mut self) -> Option<Result<(), gix_index::file::write::Error>> {
         let _span = gix_features::trace::coarse!("gix::status::index_worktree::Outcome::write_changes()");
-        let changes = self.changes.take()?;
+        let write_untracked_cache = self.has_untracked_cache_changes();
+        let fs_monitor_changed_entries = self.fs_monitor_changed_entries.take();
+        let changes = match self.changes.take() {
+            Some(changes) => changes,
+            None if write_untracked_cache || fs_monitor_changed_entries.is_some() => Vec::new(),
+            None => return None,
+        };
         let mut index = match &self.worktree_index {
             IndexPersistedOrInMemory::Persisted(persisted) => (***persisted).clone(),
             IndexPersistedOrInMemory::InMemory(index) => index.clone(),
         };
+        if write_untracked_cache {
+            index.set_untracked(self.index_worktree.untracked_cache.take());
+        }
+        if let Some(changed_entries) = fs_monitor_changed_entries {
+            let checks = &self.index_worktree.tracked_file_modification;
+            let all_entries_were_checked = checks.entries_processed == checks.entries_to_process
+                && checks.entries_skipped_by_common_prefix == 0
+                && checks.entries_skipped_by_pathspec == 0;
+            if all_entries_were_checked {
+                keep_changed_fs_monitor_entries_dirty(&mut index, changed_entries);
+            }
+        }
 
         let entries = index.entries_mut();
         for (entry_index, change) in changes {
@@ -106,12 +141,41 @@ impl Outcome {
         }
 
         Some(index.write(crate::index::write::Options {
-            extensions: Default::default(),
             skip_hash: self.skip_hash,
+            ..Default::default()
         }))
     }
 }
 
+/// Mark all dirty entries of the filesystem monitor extension in `index` as valid, unless they are in `changed_entries`,
+/// as they were checked for modifications and found unchanged.
+/// Entries that are never checked, along with submodules whose worktree changes aren't tracked, remain dirty.
+fn keep_changed_fs_monitor_entries_dirty(index: &mut gix_index::File, mut changed_entries: Vec<usize>) {
+    use gix_index::entry::Flags;
+    changed_entries.sort_unstable();
+    let Some(fs_monitor) = index.fs_monitor() else {
+        return;
+    };
+    let entries = index.entries();
+    let still_dirty: Vec<_> = fs_monitor
+        .dirty_entries()
+        .iter()
+        .copied()
+        .filter(|idx| {
+            changed_entries.binary_search(idx).is_ok()
+                || entries.get(*idx).map_or(true, |entry| {
+                    entry.mode.is_submodule()
+                        || entry
+                            .flags
+                            .intersects(Flags::UPTODATE | Flags::SKIP_WORKTREE | Flags::ASSUME_VALID)
+                })
+        })
+        .collect();
+    if let Some(fs_monitor) = index.fs_monitor_mut() {
+        fs_monitor.retain_dirty_entries(|idx| still_dirty.binary_search(idx).is_ok());
+    }
+}
+
 pub(super) enum ApplyChange {
     SetSizeToZero,
     NewStat(crate::index::entry::Stat),
@@ -21,7 +21,9 @@ where
 #[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Submodule {
     /// Use the ['ignore' value](crate::Submodule::ignore) to determine which submodules
-    /// participate in the status query, and to which extent.
+    /// participate in the status query, and to which extent, falling back to `diff.ignoreSubmodules` if it is unset.
+    ///
+    /// Only [active](crate::Submodule::is_active()) submodules participate.
     AsConfigured {
         /// If `true`, default `false`, the computation will stop once the first in a ladder operations
         /// ordered from cheap to expensive shows that the submodule is dirty.
@@ -31,6 +33,8 @@ pub enum Submodule {
     /// Instead of the configuration, use the given ['ignore' value](crate::submodule::config::Ignore).
     /// This makes it possible to fine-tune the amount of work invested in this status, while allowing
     /// to turn off all submodule status information.
+    ///
+    /// Only [active](crate::Submodule::is_active()) submodules participate.
     Given {
         /// The portion of the submodule status to ignore.
         ignore: crate::submodule::config::Ignore,
@@ -228,6 +232,10 @@ pub mod into_iter {
         Pathspec(#[from] crate::pathspec::init::Error),
         #[error(transparent)]
         HeadTreeDiff(#[from] crate::status::tree_index::Error),
+        #[error("The path to the filesystem monitor hook could not be interpolated")]
+        FsMonitorHookPath(#[from] gix_config::path::interpolate::Error),
+        #[error(transparent)]
+        FsMonitor(#[from] gix_status::fs_monitor::hook::Error),
     }
 }
 
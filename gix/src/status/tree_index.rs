@@ -1,6 +1,8 @@
+use std::borrow::Cow;
+
 use crate::{config::tree, Repository};
 
-/// The error returned by [Repository::tree_index_status()].
+/// The error returned by [Repository::tree_index_status()] and [Repository::index_index_status()].
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
 pub enum Error {
@@ -12,9 +14,13 @@ pub enum Error {
     DiffResourceCache(#[from] crate::repository::diff_resource_cache::Error),
     #[error(transparent)]
     TreeIndexDiff(#[from] gix_diff::index::Error),
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error("Could not expand a sparse index for diffing")]
+    ExpandSparseIndex(#[from] gix_index::sparse::expand::Error),
 }
 
-/// Specify how to perform rewrite tracking [Repository::tree_index_status()].
+/// Specify how to perform rewrite tracking [Repository::tree_index_status()] and [Repository::index_index_status()].
 #[derive(Default, Debug, Copy, Clone)]
 pub enum TrackRenames {
     /// Check `status.renames` and then `diff.renames` if the former isn't set. Otherwise, default to performing rewrites if nothing
@@ -52,6 +58,8 @@ impl Repository {
     ///
     /// * This is a low-level method - prefer the [`Repository::status()`] platform instead for access to various iterators
     ///   over the same information.
+    /// * See [`index_index_status()`](Self::index_index_status()) for details on how unmerged entries and sparse indices
+    ///   are handled.
     pub fn tree_index_status<'repo, E>(
         &'repo self,
         tree_id: &gix_hash::oid,
@@ -69,6 +77,43 @@ impl Repository {
     {
         let _span = gix_trace::coarse!("gix::tree_index_status");
         let tree_index: gix_index::State = self.index_from_tree(tree_id)?.into();
+        let rewrite = self.index_index_status(&tree_index, worktree_index, pathspec, renames, &mut cb)?;
+        Ok(Outcome { rewrite, tree_index })
+    }
+
+    /// Produce the changes that would have to be applied to `lhs` to obtain `rhs`, like the portion of `git status` that
+    /// shows the difference between `HEAD^{tree}` and the index, and pass all changes to `cb(change, lhs, rhs)`
+    /// with full access to both indices that contributed to the change.
+    /// Set `pathspec` to `Some(_)` to further reduce the set of files to check, and use `renames` to control
+    /// how rewrites are tracked.
+    ///
+    /// Return the outcome of the rewrite tracking, which is `None` if it was disabled.
+    ///
+    /// ### Notes
+    ///
+    /// * Only `rhs` may contain unmerged entries, which are passed as [`Unmerged`](gix_diff::index::ChangeRef::Unmerged)
+    ///   once per path.
+    /// * Sparse indices are expanded for the purpose of diffing, so the indices passed to `cb` might not be `lhs` or `rhs`
+    ///   in that case.
+    pub fn index_index_status<'repo, E>(
+        &'repo self,
+        lhs: &gix_index::State,
+        rhs: &gix_index::State,
+        pathspec: Option<&mut crate::Pathspec<'repo>>,
+        renames: TrackRenames,
+        mut cb: impl FnMut(
+            gix_diff::index::ChangeRef<'_, '_>,
+            &gix_index::State,
+            &gix_index::State,
+        ) -> Result<gix_diff::index::Action, E>,
+    ) -> Result<Option<gix_diff::rewrites::Outcome>, Error>
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let _span = gix_trace::detail!("gix::index_index_status");
+        let lhs = self.expand_sparse_index(lhs)?;
+        let rhs = self.expand_sparse_index(rhs)?;
+        let (lhs, rhs) = (lhs.as_ref(), rhs.as_ref());
         let rewrites = match renames {
             TrackRenames::AsConfigured => {
                 let (mut rewrites, mut is_configured) = crate::diff::utils::new_rewrites_inner(
@@ -109,10 +154,10 @@ impl Repository {
 
         let pathspec =
             pathspec.unwrap_or_else(|| pathspec_storage.as_mut().expect("set if pathspec isn't set by user"));
-        let rewrite = gix_diff::index(
-            &tree_index,
-            worktree_index,
-            |change| cb(change, &tree_index, worktree_index),
+        Ok(gix_diff::index(
+            lhs,
+            rhs,
+            |change| cb(change, lhs, rhs),
             rewrites
                 .zip(resource_cache.as_mut())
                 .map(|(rewrites, resource_cache)| gix_diff::index::RewriteOptions {
@@ -132,8 +177,15 @@ impl Repository {
                     )
                     .is_ok_and(|platform| platform.matching_attributes(out))
             },
-        )?;
+        )?)
+    }
 
-        Ok(Outcome { rewrite, tree_index })
+    fn expand_sparse_index<'a>(&self, index: &'a gix_index::State) -> Result<Cow<'a, gix_index::State>, Error> {
+        if !index.is_sparse() {
+            return Ok(Cow::Borrowed(index));
+        }
+        let mut index = index.clone();
+        index.expand_sparse_directories(&self.objects, self.config.protect_options()?)?;
+        Ok(Cow::Owned(index))
     }
 }
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use crate::{submodule::util, Submodule};
+
+/// The error returned by [Submodule::absorb_git_dir()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    InvalidName(#[from] crate::submodule::validated_name::Error),
+    #[error(transparent)]
+    PathConfiguration(#[from] gix_submodule::config::path::Error),
+    #[error("Cannot move the repository of the submodule to '{}' as it already exists", path.display())]
+    DestinationExists { path: PathBuf },
+    #[error("Could not move the repository of the submodule from '{}'", path.display())]
+    Io { source: std::io::Error, path: PathBuf },
+    #[error(transparent)]
+    EditConfig(#[from] crate::submodule::edit_config::Error),
+}
+
+impl Submodule<'_> {
+    /// If the repository of this submodule is located in its working tree, move it into the `modules` directory of the
+    /// superproject and link both with a `.git` file, similar to `git submodule absorbgitdirs`.
+    ///
+    /// This allows the working tree to be removed without losing the repository. Return `true` if the repository was moved,
+    /// or `false` if it already was in the `modules` directory or if there is none.
+    pub fn absorb_git_dir(&self) -> Result<bool, Error> {
+        self.validated_name()?;
+        let workdir = self.work_dir()?;
+        let dot_git = workdir.join(gix_discover::DOT_GIT_DIR);
+        if !dot_git.is_dir() {
+            return Ok(false);
+        }
+        let git_dir = self.git_dir();
+        if git_dir.symlink_metadata().is_ok() {
+            return Err(Error::DestinationExists { path: git_dir });
+        }
+        let parent = git_dir.parent().expect("modules directory");
+        std::fs::create_dir_all(parent).map_err(|source| Error::Io {
+            source,
+            path: parent.to_owned(),
+        })?;
+        std::fs::rename(&dot_git, &git_dir).map_err(|source| Error::Io { source, path: dot_git })?;
+        util::link_git_dir(&workdir, &git_dir)?;
+        Ok(true)
+    }
+}
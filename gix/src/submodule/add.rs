@@ -0,0 +1,190 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    submodule::util,
+    Repository,
+};
+
+/// The error returned by [Repository::submodule_add()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A working tree is required to add a submodule")]
+    MissingWorkDir,
+    #[error("Submodule path '{path}' must be relative and stay within the working tree")]
+    InvalidPath { path: BString },
+    #[error(transparent)]
+    InvalidName(#[from] crate::submodule::validated_name::Error),
+    #[error("'{path}' already exists in the index")]
+    PathInIndex { path: BString },
+    #[error("A submodule named '{name}' is already configured in .gitmodules")]
+    NameInModulesFile { name: BString },
+    #[error("Submodule location '{}' already exists and is not an empty directory", path.display())]
+    LocationExists { path: PathBuf },
+    #[error("A repository for the submodule already exists at '{}'", path.display())]
+    GitDirExists { path: PathBuf },
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    ResolveUrl(#[from] gix_submodule::relative_url::Error),
+    #[error(transparent)]
+    ParseUrl(#[from] gix_url::parse::Error),
+    #[error(transparent)]
+    Clone(#[from] crate::submodule::clone::Error),
+    #[error(transparent)]
+    HeadTreeId(#[from] crate::reference::head_tree_id::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::submodule::checkout::Error),
+    #[error(transparent)]
+    EditConfig(#[from] crate::submodule::edit_config::Error),
+    #[error(transparent)]
+    Staging(#[from] crate::staging::init::Error),
+    #[error(transparent)]
+    StageChanges(#[from] crate::staging::add::Error),
+    #[error(transparent)]
+    WriteIndex(#[from] gix_index::file::write::Error),
+}
+
+/// Options for use in [Repository::submodule_add()].
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// The name of the submodule, which defaults to its path.
+    pub name: Option<BString>,
+    /// The name of the branch to check out instead of the `HEAD` of the remote, which is also recorded in the
+    /// `.gitmodules` file, similar to `git submodule add --branch`.
+    pub branch: Option<BString>,
+}
+
+impl Repository {
+    /// Clone the repository at `url` into `path` within the working tree and register it as submodule, similar to
+    /// `git submodule add`. Return the repository of the new submodule.
+    ///
+    /// The repository is placed in the `modules` directory of this repository and linked to its working tree at `path`,
+    /// which must not exist or be an empty directory. `url` may be [relative](gix_submodule::relative_url) to the url of
+    /// the default remote of this repository, and is written as is into the `.gitmodules` file, whereas the
+    /// resolved url is written to the configuration of this repository to initialize the submodule.
+    /// Finally, the `.gitmodules` file and the submodule are added to the index.
+    ///
+    /// If anything fails, all files created so far are removed again, but changes to the `.gitmodules` file and the
+    /// configuration remain.
+    /// Note that the configuration of this repository that is held in memory isn't updated.
+    pub fn submodule_add(
+        &self,
+        url: impl AsRef<BStr>,
+        path: impl AsRef<BStr>,
+        options: Options,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Repository, Error> {
+        let url = url.as_ref();
+        let workdir = self.workdir().ok_or(Error::MissingWorkDir)?;
+        let rela_path = path.as_ref().trim_end_with(|c| c == '/').as_bstr();
+        let is_valid_path = !rela_path.is_empty() && {
+            let path = gix_path::from_bstr(rela_path);
+            !path.is_absolute()
+                && path
+                    .components()
+                    .all(|component| matches!(component, std::path::Component::Normal(_)))
+        };
+        if !is_valid_path {
+            return Err(Error::InvalidPath {
+                path: rela_path.to_owned(),
+            });
+        }
+        let name = options.name.as_ref().map_or(rela_path, |name| name.as_bstr());
+        crate::submodule::validate_name(name)?;
+
+        {
+            let index = self.index_or_empty()?;
+            let mut dir_prefix = BString::from(rela_path);
+            dir_prefix.push(b'/');
+            if index.entry_by_path(rela_path).is_some()
+                || index
+                    .prefixed_entries(dir_prefix.as_ref())
+                    .is_some_and(|entries| !entries.is_empty())
+            {
+                return Err(Error::PathInIndex {
+                    path: rela_path.to_owned(),
+                });
+            }
+        }
+        if let Some(modules) = self.open_modules_file().ok().flatten() {
+            if modules.names().any(|existing| existing == name) {
+                return Err(Error::NameInModulesFile { name: name.to_owned() });
+            }
+        }
+
+        let destination = workdir.join(gix_path::from_bstr(rela_path));
+        let created_destination = match std::fs::read_dir(&destination) {
+            Ok(mut entries) => {
+                if entries.next().is_some() {
+                    return Err(Error::LocationExists { path: destination });
+                }
+                false
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => true,
+            Err(_) => return Err(Error::LocationExists { path: destination }),
+        };
+        let git_dir = self.common_dir().join("modules").join(gix_path::from_bstr(name));
+        if git_dir.symlink_metadata().is_ok() {
+            return Err(Error::GitDirExists { path: git_dir });
+        }
+
+        let resolved_url = util::resolve_url(self, url)?;
+        let res = (|| {
+            let submodule = util::clone(
+                self,
+                gix_url::parse(resolved_url.as_ref())?,
+                &git_dir,
+                &destination,
+                options.branch.as_ref().map(AsRef::as_ref),
+                should_interrupt,
+            )?;
+            let tree = submodule.head_tree_id()?.detach();
+            util::checkout_tree(&submodule, tree, None, should_interrupt)?;
+            Ok(submodule)
+        })();
+        let submodule = match res {
+            Ok(submodule) => submodule,
+            Err(err) => {
+                std::fs::remove_dir_all(&git_dir).ok();
+                if created_destination {
+                    std::fs::remove_dir_all(&destination).ok();
+                } else if let Ok(entries) = std::fs::read_dir(&destination) {
+                    for entry in entries.flatten() {
+                        let path = entry.path();
+                        if path.is_dir() && !path.is_symlink() {
+                            std::fs::remove_dir_all(path).ok();
+                        } else {
+                            std::fs::remove_file(path).ok();
+                        }
+                    }
+                }
+                return Err(err);
+            }
+        };
+
+        util::edit_config_file(
+            &workdir.join(crate::submodule::MODULES_FILE),
+            gix_config::Source::Worktree,
+            |config| {
+                config.set_raw_value_by("submodule", Some(name), "path", rela_path)?;
+                config.set_raw_value_by("submodule", Some(name), "url", url)?;
+                if let Some(branch) = &options.branch {
+                    config.set_raw_value_by("submodule", Some(name), "branch", branch.as_bstr())?;
+                }
+                Ok(())
+            },
+        )?;
+        util::edit_local_config(self, |config| {
+            config.set_raw_value_by("submodule", Some(name), "url", resolved_url.as_bstr())?;
+            config.set_raw_value_by("submodule", Some(name), "active", "true")?;
+            Ok(())
+        })?;
+
+        let mut staging = self.staging()?;
+        staging.add([crate::submodule::MODULES_FILE.into(), rela_path], Default::default())?;
+        staging.write()?;
+        Ok(submodule)
+    }
+}
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use crate::{submodule::util, Submodule};
+
+/// The error returned by [Submodule::deinit()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    InvalidName(#[from] crate::submodule::validated_name::Error),
+    #[error(transparent)]
+    PathConfiguration(#[from] gix_submodule::config::path::Error),
+    #[error(transparent)]
+    Status(#[from] crate::submodule::status::Error),
+    #[error("Submodule working tree at '{}' has modified or untracked files and can't be removed without force", path.display())]
+    Dirty { path: PathBuf },
+    #[error("Submodule working tree at '{}' contains its repository, which would be lost - absorb it first", path.display())]
+    RepositoryInWorktree { path: PathBuf },
+    #[error("Could not clear the submodule working tree at '{}'", path.display())]
+    Io { source: std::io::Error, path: PathBuf },
+    #[error(transparent)]
+    EditConfig(#[from] crate::submodule::edit_config::Error),
+}
+
+/// Options for use in [Submodule::deinit()].
+#[derive(Default, Debug, Copy, Clone)]
+pub struct Options {
+    /// If `true`, remove the working tree even if it has modified or untracked files, similar to `git submodule deinit --force`.
+    pub force: bool,
+}
+
+impl Submodule<'_> {
+    /// Remove the working tree of this submodule, leaving an empty directory, and remove its configuration from the
+    /// superproject so it isn't initialized anymore, similar to `git submodule deinit`.
+    ///
+    /// The repository in the `modules` directory of the superproject is kept so the submodule can be
+    /// [updated](Self::update_worktree()) again later.
+    /// Note that the configuration of the superproject that is held in memory isn't updated.
+    pub fn deinit(&self, options: Options) -> Result<(), Error> {
+        let name = self.validated_name()?;
+        let state = self.state()?;
+        let workdir = self.work_dir()?;
+        if state.is_old_form {
+            return Err(Error::RepositoryInWorktree { path: workdir });
+        }
+        if state.worktree_checkout {
+            if !options.force {
+                let status = self.status(gix_submodule::config::Ignore::None, true)?;
+                if status.has_modified_content() || status.has_untracked_content() {
+                    return Err(Error::Dirty { path: workdir });
+                }
+            }
+            std::fs::remove_dir_all(&workdir)
+                .and_then(|()| std::fs::create_dir(&workdir))
+                .map_err(|source| Error::Io { source, path: workdir })?;
+        }
+
+        util::edit_local_config(self.state.repo, |config| {
+            while config.remove_section("submodule", Some(name)).is_some() {}
+            Ok(())
+        })?;
+        Ok(())
+    }
+}
@@ -75,6 +75,21 @@ pub mod open {
     }
 }
 
+///
+pub mod validated_name {
+    use crate::bstr::BString;
+
+    /// The error returned by [Submodule::validated_name()](crate::Submodule::validated_name()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Validate(#[from] gix_validate::submodule::name::Error),
+        #[error("Submodule name '{name}' must be a relative path to be usable within the 'modules' directory")]
+        Absolute { name: BString },
+    }
+}
+
 ///
 pub mod index_id {
     /// The error returned by [Submodule::index_id()](crate::Submodule::index_id()).
@@ -104,3 +119,93 @@ pub mod head_id {
         PathConfiguration(#[from] gix_submodule::config::path::Error),
     }
 }
+
+///
+pub mod edit_config {
+    use std::path::PathBuf;
+
+    /// The error returned when changing the configuration of a superproject or a submodule on disk.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not read the configuration file at '{}'", path.display())]
+        LoadConfig {
+            source: gix_config::file::init::from_paths::Error,
+            path: PathBuf,
+        },
+        #[error(transparent)]
+        SetValue(#[from] gix_config::file::set_raw_value::Error),
+        #[error(transparent)]
+        AcquireLock(#[from] gix_lock::acquire::Error),
+        #[error(transparent)]
+        CommitLock(#[from] gix_lock::commit::Error<gix_lock::File>),
+        #[error("Could not write the configuration file at '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+    }
+}
+
+///
+#[cfg(all(
+    feature = "blocking-network-client",
+    feature = "worktree-mutation",
+    feature = "dirwalk"
+))]
+pub mod checkout {
+    /// The error returned when checking out a tree into the working tree of a submodule.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not create index from tree at {id}")]
+        IndexFromTree {
+            id: gix_hash::ObjectId,
+            source: gix_index::init::from_tree::Error,
+        },
+        #[error("Could not fetch the objects to check out from the promisor remote")]
+        Prefetch(#[source] gix_odb::store::promisor::Error),
+        #[error("Couldn't obtain configuration for core.protect*")]
+        BooleanConfig(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        IndexVersion(#[from] crate::config::index_version::Error),
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+        OpenArcOdb(#[source] std::io::Error),
+        #[error(transparent)]
+        Checkout(#[from] gix_worktree_state::checkout::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error("Could not remove '{}' which isn't tracked anymore", path.display())]
+        RemoveFile {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+    }
+}
+
+///
+#[cfg(all(
+    feature = "blocking-network-client",
+    feature = "worktree-mutation",
+    feature = "dirwalk"
+))]
+pub mod clone {
+    use std::path::PathBuf;
+
+    /// The error returned when cloning the repository of a submodule into the `modules` directory of the superproject.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Init(#[from] crate::clone::Error),
+        #[error(transparent)]
+        InvalidBranchName(#[from] gix_validate::reference::name::Error),
+        #[error(transparent)]
+        Fetch(#[from] crate::clone::fetch::Error),
+        #[error(transparent)]
+        EditConfig(#[from] super::edit_config::Error),
+        #[error(transparent)]
+        Open(#[from] crate::open::Error),
+        #[error("Could not access '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{
+    bstr::{BString, ByteSlice},
+    submodule::util,
+    Submodule,
+};
+
+/// The error returned by [Submodule::init()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    InvalidName(#[from] crate::submodule::validated_name::Error),
+    #[error(transparent)]
+    Url(#[from] gix_submodule::config::url::Error),
+    #[error(transparent)]
+    ResolveUrl(#[from] gix_submodule::relative_url::Error),
+    #[error(transparent)]
+    Update(#[from] gix_submodule::config::update::Error),
+    #[error(transparent)]
+    IsActive(#[from] crate::submodule::is_active::Error),
+    #[error(transparent)]
+    EditConfig(#[from] crate::submodule::edit_config::Error),
+}
+
+impl Submodule<'_> {
+    /// Register this submodule in the configuration of the superproject so it can be cloned and updated,
+    /// similar to `git submodule init`.
+    ///
+    /// The url in the `.gitmodules` file is written as `submodule.<name>.url`, after resolving it against the url of the
+    /// superproject if it is relative. The `update` strategy is copied as well, and the submodule is marked as `active`
+    /// unless it is active already.
+    ///
+    /// Return the url that was written, or `None` if the submodule was already initialized.
+    /// Note that the configuration of the superproject that is held in memory isn't updated.
+    pub fn init(&self) -> Result<Option<BString>, Error> {
+        let repo = self.state.repo;
+        let name = self.validated_name()?;
+        let url_key = format!("submodule.{name}.url");
+        if repo.config.resolved.string(url_key.as_str()).is_some() {
+            return Ok(None);
+        }
+        let url = util::resolve_url(repo, self.state.modules.url_from_modules_file(name)?.as_ref())?;
+        let update = self.update()?.and_then(|update| match update {
+            gix_submodule::config::Update::Checkout => Some("checkout"),
+            gix_submodule::config::Update::Rebase => Some("rebase"),
+            gix_submodule::config::Update::Merge => Some("merge"),
+            gix_submodule::config::Update::None => Some("none"),
+            gix_submodule::config::Update::Command(_) => None,
+        });
+        let is_active = self.is_active()?;
+        let mut newly_initialized = false;
+        util::edit_local_config(repo, |config| {
+            if config.string(url_key.as_str()).is_some() {
+                return Ok(());
+            }
+            newly_initialized = true;
+            let subsection = Some(name);
+            if !is_active {
+                config.set_raw_value_by("submodule", subsection, "active", "true")?;
+            }
+            config.set_raw_value_by("submodule", subsection, "url", url.as_bstr())?;
+            if let Some(update) = update {
+                if repo
+                    .config
+                    .resolved
+                    .string(format!("submodule.{name}.update"))
+                    .is_none()
+                {
+                    config.set_raw_value_by("submodule", subsection, "update", update)?;
+                }
+            }
+            Ok(())
+        })?;
+        Ok(newly_initialized.then_some(url))
+    }
+}
@@ -21,6 +21,32 @@ pub(crate) const MODULES_FILE: &str = ".gitmodules";
 mod errors;
 pub use errors::*;
 
+///
+pub mod absorb_git_dir;
+///
+#[cfg(all(
+    feature = "blocking-network-client",
+    feature = "worktree-mutation",
+    feature = "dirwalk"
+))]
+pub mod add;
+///
+#[cfg(feature = "status")]
+pub mod deinit;
+///
+pub mod init;
+///
+pub mod sync;
+///
+#[cfg(all(
+    feature = "blocking-network-client",
+    feature = "worktree-mutation",
+    feature = "status"
+))]
+pub mod update;
+
+mod util;
+
 /// A platform maintaining state needed to interact with submodules, created by [`Repository::submodules()].
 pub(crate) struct SharedState<'repo> {
     pub repo: &'repo Repository,
@@ -77,6 +103,16 @@ impl<'repo> SharedState<'repo> {
     }
 }
 
+/// Validate `name` for use as directory within the `modules` directory.
+pub(crate) fn validate_name(name: &BStr) -> Result<&BStr, validated_name::Error> {
+    let name = gix_validate::submodule::name(name)?;
+    let path = gix_path::from_bstr(name);
+    if path.has_root() || path.is_absolute() {
+        return Err(validated_name::Error::Absolute { name: name.to_owned() });
+    }
+    Ok(name)
+}
+
 struct IsActiveState {
     platform: IsActivePlatform,
     attributes: gix_worktree::Stack,
@@ -88,6 +124,15 @@ impl Submodule<'_> {
     pub fn name(&self) -> &BStr {
         self.name.as_ref()
     }
+    /// Return the submodule's name if it's safe to use as directory within the `modules` directory of the superproject,
+    /// i.e. if it's not empty, not absolute and doesn't contain `..` components.
+    ///
+    /// As the name is read from the `.gitmodules` file, which is controlled by whoever authored the superproject,
+    /// this must be used before the name is used to create or alter anything on disk.
+    pub fn validated_name(&self) -> Result<&BStr, validated_name::Error> {
+        validate_name(self.name())
+    }
+
     /// Return the path at which the submodule can be found, relative to the repository.
     ///
     /// For details, see [gix_submodule::File::path()].
@@ -195,6 +240,8 @@ impl Submodule<'_> {
     /// Return the path at which the repository of the submodule should be located.
     ///
     /// The directory might not exist yet.
+    /// Note that the name isn't validated, and that the path may thus point outside of the `modules` directory -
+    /// use [`validated_name()`](Self::validated_name()) before writing to it.
     pub fn git_dir(&self) -> PathBuf {
         self.state
             .repo
@@ -421,6 +468,54 @@ pub mod status {
                 self.checked_out_head_id != self.index_id || self.changes.as_ref().is_some_and(|c| !c.is_empty());
             Some(is_dirty)
         }
+
+        /// Return `true` if the submodule is checked out at a commit other than the one recorded in the index of the
+        /// superproject, which `git status` shows as *new commits*.
+        pub fn is_head_changed(&self) -> bool {
+            self.checked_out_head_id.is_some() && self.checked_out_head_id != self.index_id
+        }
+
+        /// Return `true` if tracked files or the index of the submodule are modified, or if one of its own submodules
+        /// [has changes](Self::is_dirty()) other than untracked files, which `git status` shows as *modified content*.
+        ///
+        /// Note that this is only known if [`changes`](Self::changes) were computed.
+        pub fn has_modified_content(&self) -> bool {
+            use crate::status::{index_worktree, Item};
+            use gix_status::index_as_worktree::{Change, EntryStatus};
+
+            self.changes.as_ref().is_some_and(|changes| {
+                changes.iter().any(|change| match change {
+                    Item::IndexWorktree(index_worktree::Item::DirectoryContents { .. }) => false,
+                    Item::IndexWorktree(index_worktree::Item::Modification {
+                        status: EntryStatus::Change(Change::SubmoduleModification(status)),
+                        ..
+                    }) => status.is_head_changed() || status.has_modified_content(),
+                    _ => true,
+                })
+            })
+        }
+
+        /// Return `true` if there are untracked files in the submodule, or in one of its own submodules,
+        /// which `git status` shows as *untracked content*.
+        ///
+        /// Note that this is only known if [`changes`](Self::changes) were computed.
+        pub fn has_untracked_content(&self) -> bool {
+            use crate::status::{index_worktree, Item};
+            use gix_status::index_as_worktree::{Change, EntryStatus};
+
+            self.changes.as_ref().is_some_and(|changes| {
+                changes.iter().any(|change| match change {
+                    Item::IndexWorktree(index_worktree::Item::DirectoryContents { entry, .. }) => {
+                        entry.status == gix_dir::entry::Status::Untracked
+                    }
+                    Item::IndexWorktree(index_worktree::Item::Modification {
+                        status: EntryStatus::Change(Change::SubmoduleModification(status)),
+                        ..
+                    }) => status.has_untracked_content(),
+                    _ => false,
+                })
+            })
+        }
     }
 
     pub(super) mod types {
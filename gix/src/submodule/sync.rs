@@ -0,0 +1,75 @@
+use crate::{
+    bstr::{BString, ByteSlice},
+    submodule::util,
+    Submodule,
+};
+
+/// The error returned by [Submodule::sync()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    InvalidName(#[from] crate::submodule::validated_name::Error),
+    #[error(transparent)]
+    Url(#[from] gix_submodule::config::url::Error),
+    #[error(transparent)]
+    ResolveUrl(#[from] gix_submodule::relative_url::Error),
+    #[error(transparent)]
+    OpenRepository(#[from] crate::submodule::open::Error),
+    #[error(transparent)]
+    EditConfig(#[from] crate::submodule::edit_config::Error),
+}
+
+/// The outcome of [Submodule::sync()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The url of the submodule, resolved against the url of the superproject if it was relative.
+    pub url: BString,
+    /// If `true`, the submodule is initialized and its url was updated in the configuration of the superproject.
+    pub superproject_config: bool,
+    /// The name of the remote in the submodule repository whose url was updated, or `None` if the submodule wasn't cloned yet.
+    pub remote_name: Option<BString>,
+}
+
+impl Submodule<'_> {
+    /// Update the url of this submodule in the configuration of the superproject if it is initialized, and the url of
+    /// the default remote of the submodule repository if it was cloned, to match the url in the `.gitmodules` file,
+    /// similar to `git submodule sync`.
+    ///
+    /// This is useful after the url of a submodule changed upstream.
+    /// Note that the configuration of the superproject that is held in memory isn't updated.
+    pub fn sync(&self) -> Result<Outcome, Error> {
+        let repo = self.state.repo;
+        let name = self.validated_name()?;
+        let url = util::resolve_url(repo, self.state.modules.url_from_modules_file(name)?.as_ref())?;
+
+        let url_key = format!("submodule.{name}.url");
+        let mut superproject_config = repo.config.resolved.string(url_key.as_str()).is_some();
+        util::edit_local_config(repo, |config| {
+            superproject_config |= config.string(url_key.as_str()).is_some();
+            if superproject_config {
+                config.set_raw_value_by("submodule", Some(name), "url", url.as_bstr())?;
+            }
+            Ok(())
+        })?;
+
+        let remote_name = match self.open()? {
+            Some(submodule) => {
+                let remote_name: BString = submodule
+                    .remote_default_name(crate::remote::Direction::Fetch)
+                    .map_or_else(|| "origin".into(), std::borrow::Cow::into_owned);
+                util::edit_local_config(&submodule, |config| {
+                    config.set_raw_value_by("remote", Some(remote_name.as_ref()), "url", url.as_bstr())?;
+                    Ok(())
+                })?;
+                Some(remote_name)
+            }
+            None => None,
+        };
+        Ok(Outcome {
+            url,
+            superproject_config,
+            remote_name,
+        })
+    }
+}
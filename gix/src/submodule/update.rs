@@ -0,0 +1,436 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_hash::ObjectId;
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+use gix_submodule::config::Update;
+
+use crate::{bstr::BString, submodule::util, Repository, Submodule};
+
+/// The error returned by [Submodule::update_worktree()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    InvalidName(#[from] crate::submodule::validated_name::Error),
+    #[error(transparent)]
+    PathConfiguration(#[from] gix_submodule::config::path::Error),
+    #[error(transparent)]
+    UpdateConfiguration(#[from] gix_submodule::config::update::Error),
+    #[error(transparent)]
+    UrlConfiguration(#[from] gix_submodule::config::url::Error),
+    #[error("Update strategy '!{command}' of submodule '{name}' runs a command, which isn't supported")]
+    CommandUnsupported { name: BString, command: BString },
+    #[error("Update strategy {strategy:?} of submodule '{name}' needs the `merge` feature")]
+    StrategyUnsupported { name: BString, strategy: Update },
+    #[error(transparent)]
+    IndexId(#[from] crate::submodule::index_id::Error),
+    #[error(transparent)]
+    IsActive(#[from] crate::submodule::is_active::Error),
+    #[error(transparent)]
+    Init(#[from] crate::submodule::init::Error),
+    #[error(transparent)]
+    OpenRepository(#[from] crate::submodule::open::Error),
+    #[error(transparent)]
+    Clone(#[from] crate::submodule::clone::Error),
+    #[error(transparent)]
+    EditConfig(#[from] crate::submodule::edit_config::Error),
+    #[error("The submodule repository at '{}' has no remote to fetch from", git_dir.display())]
+    MissingRemote { git_dir: std::path::PathBuf },
+    #[error(transparent)]
+    FindRemote(#[from] crate::remote::find::existing::Error),
+    #[error(transparent)]
+    Connect(#[from] crate::remote::connect::Error),
+    #[error(transparent)]
+    PrepareFetch(#[from] crate::remote::fetch::prepare::Error),
+    #[error(transparent)]
+    Fetch(#[from] crate::remote::fetch::Error),
+    #[error("Commit {id} that submodule '{name}' should be at isn't available in its repository")]
+    MissingCommit { name: BString, id: ObjectId },
+    #[error(transparent)]
+    IsDirty(#[from] crate::status::is_dirty::Error),
+    #[error("Submodule working tree at '{}' has modified files that would be overwritten, use force to discard them", path.display())]
+    Dirty { path: std::path::PathBuf },
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    HeadId(#[from] crate::reference::head_id::Error),
+    #[error(transparent)]
+    FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+    #[error(transparent)]
+    CommitTree(#[from] crate::object::commit::Error),
+    #[cfg(feature = "merge")]
+    #[error(transparent)]
+    MergeBase(#[from] crate::repository::merge_base::Error),
+    #[cfg(feature = "merge")]
+    #[error(transparent)]
+    MergeOptions(#[from] crate::repository::tree_merge_options::Error),
+    #[cfg(feature = "merge")]
+    #[error(transparent)]
+    MergeCommits(#[from] crate::repository::merge_commits::Error),
+    #[cfg(feature = "merge")]
+    #[error(transparent)]
+    MergeTrees(#[from] crate::repository::merge_trees::Error),
+    #[cfg(feature = "merge")]
+    #[error(transparent)]
+    WriteTree(#[from] crate::object::tree::editor::write::Error),
+    #[cfg(feature = "merge")]
+    #[error("Merging {their_id} into the submodule at '{}' caused conflicts", path.display())]
+    MergeConflict {
+        their_id: ObjectId,
+        path: std::path::PathBuf,
+    },
+    #[cfg(feature = "merge")]
+    #[error("Rebasing commit {id} of the submodule at '{}' onto its new base caused conflicts", path.display())]
+    RebaseConflict { id: ObjectId, path: std::path::PathBuf },
+    #[cfg(feature = "merge")]
+    #[error(transparent)]
+    Signature(#[from] crate::config::time::Error),
+    #[cfg(feature = "merge")]
+    #[error("The committer of the submodule repository isn't configured")]
+    CommitterMissing,
+    #[cfg(feature = "merge")]
+    #[error(transparent)]
+    WriteObject(#[from] crate::object::write::Error),
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::submodule::checkout::Error),
+    #[error(transparent)]
+    EditReference(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    OpenSubmoduleRepository(#[from] crate::open::Error),
+    #[error(transparent)]
+    ListSubmodules(#[from] crate::submodule::modules::Error),
+}
+
+/// Options for use in [Submodule::update_worktree()].
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The strategy to use instead of the one configured for the submodule, which defaults to [checking out](Update::Checkout)
+    /// the commit recorded in the superproject.
+    pub strategy: Option<Update>,
+    /// If `true`, [initialize](Submodule::init()) the submodule if it isn't initialized yet, similar to `git submodule update --init`.
+    /// Otherwise, uninitialized submodules are skipped.
+    pub init: bool,
+    /// If `true`, fetch from the default remote of the submodule if the commit recorded in the superproject isn't available,
+    /// which is the default.
+    pub fetch: bool,
+    /// If `true`, discard local modifications of tracked files in the working tree of the submodule, similar to
+    /// `git submodule update --force`. Otherwise, the update fails if there are any.
+    pub force: bool,
+    /// If `true`, update the submodules of the submodule with the same options, similar to `git submodule update --recursive`.
+    pub recursive: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            strategy: None,
+            init: false,
+            fetch: true,
+            force: false,
+            recursive: false,
+        }
+    }
+}
+
+/// The outcome of [Submodule::update_worktree()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The update strategy that was used.
+    pub strategy: Update,
+    /// If `true`, the submodule repository was cloned.
+    pub cloned: bool,
+    /// The commit `HEAD` of the submodule pointed to before the update, or `None` if it was just cloned or its working tree
+    /// had to be checked out from scratch.
+    pub previous_head_id: Option<ObjectId>,
+    /// The commit `HEAD` of the submodule points to after the update.
+    pub head_id: ObjectId,
+    /// The names and outcomes of the updated submodules of the submodule, if the update was [recursive](Options::recursive).
+    pub submodules: Vec<(BString, Outcome)>,
+}
+
+impl Submodule<'_> {
+    /// Bring the working tree of this submodule to the commit recorded in the index of the superproject, cloning its
+    /// repository first if needed, similar to `git submodule update`.
+    ///
+    /// The commit is applied with the [configured strategy](Self::update()) unless overridden in `options`:
+    ///
+    /// * [`Update::Checkout`] checks out the commit on a detached `HEAD`.
+    /// * [`Update::Merge`] merges the commit into the current branch, or fast-forwards it.
+    /// * [`Update::Rebase`] rebases the commits between the merge-base and `HEAD`, following only the first parent and
+    ///   skipping merge commits, onto the commit, or fast-forwards the current branch.
+    /// * [`Update::None`] skips the submodule.
+    ///
+    /// Freshly cloned submodules, or those without a working tree, are always checked out.
+    /// Merging and rebasing need the `merge` feature, and commands aren't supported.
+    ///
+    /// Return `None` if the submodule was skipped as it isn't initialized, isn't active, isn't in the index of the superproject,
+    /// or is configured to not be updated.
+    pub fn update_worktree(&self, options: Options, should_interrupt: &AtomicBool) -> Result<Option<Outcome>, Error> {
+        let name = self.validated_name()?;
+        let mut strategy = match options.strategy.clone() {
+            Some(strategy) => strategy,
+            None => self.update()?.unwrap_or_default(),
+        };
+        match &strategy {
+            Update::None => return Ok(None),
+            Update::Command(command) => {
+                return Err(Error::CommandUnsupported {
+                    name: name.to_owned(),
+                    command: command.clone(),
+                })
+            }
+            Update::Checkout | Update::Rebase | Update::Merge => {}
+        }
+        let Some(target) = self.index_id()? else {
+            return Ok(None);
+        };
+
+        let state = self.state()?;
+        let url = if state.superproject_configuration {
+            if !self.is_active()? {
+                return Ok(None);
+            }
+            None
+        } else if options.init {
+            self.init()?
+        } else {
+            return Ok(None);
+        };
+
+        let workdir = self.work_dir()?;
+        let (submodule, cloned) = match self.open()? {
+            Some(submodule) => (submodule, false),
+            None => {
+                let url = match url {
+                    Some(url) => {
+                        gix_url::parse(url.as_ref()).map_err(|source| gix_submodule::config::url::Error::Parse {
+                            submodule: name.to_owned(),
+                            source,
+                        })?
+                    }
+                    None => self.url()?,
+                };
+                let submodule = util::clone(self.state.repo, url, &self.git_dir(), &workdir, None, should_interrupt)?;
+                (submodule, true)
+            }
+        };
+        let fresh_checkout = cloned || !state.worktree_checkout;
+        if fresh_checkout {
+            std::fs::create_dir_all(&workdir).map_err(|source| crate::submodule::edit_config::Error::Io {
+                source,
+                path: workdir.clone(),
+            })?;
+            util::link_git_dir(&workdir, submodule.git_dir())?;
+            strategy = Update::Checkout;
+        }
+        let submodule = if fresh_checkout && !cloned {
+            crate::open_opts(&workdir, self.state.repo.options.clone())?
+        } else {
+            submodule
+        };
+
+        if !submodule.has_object(target) {
+            if options.fetch && !cloned {
+                let remote = submodule
+                    .find_default_remote(crate::remote::Direction::Fetch)
+                    .ok_or_else(|| Error::MissingRemote {
+                        git_dir: submodule.git_dir().to_owned(),
+                    })??;
+                remote
+                    .connect(crate::remote::Direction::Fetch)?
+                    .prepare_fetch(gix_features::progress::Discard, Default::default())?
+                    .receive(gix_features::progress::Discard, should_interrupt)?;
+            }
+            if !submodule.has_object(target) {
+                return Err(Error::MissingCommit {
+                    name: name.to_owned(),
+                    id: target,
+                });
+            }
+        }
+
+        let previous_head_id = if fresh_checkout {
+            None
+        } else {
+            submodule.head_id().ok().map(crate::Id::detach)
+        };
+        if !fresh_checkout && !options.force && submodule.is_dirty()? {
+            return Err(Error::Dirty { path: workdir });
+        }
+
+        if previous_head_id != Some(target) || fresh_checkout || options.force {
+            let (head_id, deref) = match (&strategy, previous_head_id) {
+                (Update::Merge, Some(head)) => (merge(&submodule, head, target)?, true),
+                (Update::Rebase, Some(head)) => (rebase(&submodule, head, target)?, true),
+                _ => (target, false),
+            };
+            if previous_head_id != Some(head_id) || fresh_checkout || options.force {
+                let previous_index = if fresh_checkout {
+                    None
+                } else {
+                    Some(submodule.index_or_empty()?)
+                };
+                let tree = submodule.find_commit(head_id)?.tree_id()?.detach();
+                util::checkout_tree(
+                    &submodule,
+                    tree,
+                    previous_index.as_deref().map(|index| &***index),
+                    should_interrupt,
+                )?;
+                let message = match previous_head_id {
+                    Some(previous) => format!("submodule update: moving from {previous} to {head_id}"),
+                    None => format!("submodule update: checkout {head_id}"),
+                };
+                set_head(&submodule, head_id, deref, message)?;
+            }
+        }
+
+        let mut outcome = Outcome {
+            strategy,
+            cloned,
+            previous_head_id,
+            head_id: submodule.head_id()?.detach(),
+            submodules: Vec::new(),
+        };
+        if options.recursive {
+            if let Some(submodules) = submodule.submodules()? {
+                for nested in submodules {
+                    if let Some(nested_outcome) = nested.update_worktree(options.clone(), should_interrupt)? {
+                        outcome.submodules.push((nested.name().to_owned(), nested_outcome));
+                    }
+                }
+            }
+        }
+        Ok(Some(outcome))
+    }
+}
+
+/// Point `HEAD` of `repo` to `id`, and also the branch it points to if `deref` is `true`.
+fn set_head(
+    repo: &Repository,
+    id: ObjectId,
+    deref: bool,
+    message: String,
+) -> Result<(), crate::reference::edit::Error> {
+    repo.edit_reference(RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: false,
+                message: message.into(),
+            },
+            expected: PreviousValue::Any,
+            new: gix_ref::Target::Object(id),
+        },
+        name: "HEAD".try_into().expect("valid"),
+        deref,
+    })?;
+    Ok(())
+}
+
+/// Merge `target` into `head` and return the commit `HEAD` should point to.
+#[cfg(feature = "merge")]
+fn merge(repo: &Repository, head: ObjectId, target: ObjectId) -> Result<ObjectId, Error> {
+    let base = repo.merge_base(head, target)?.detach();
+    if base == target {
+        return Ok(head);
+    }
+    if base == head {
+        return Ok(target);
+    }
+    let options = repo.tree_merge_options()?;
+    let mut outcome = repo.merge_commits(head, target, Default::default(), options.into())?;
+    if outcome
+        .tree_merge
+        .has_unresolved_conflicts(crate::merge::tree::TreatAsUnresolved::default())
+    {
+        return Err(Error::MergeConflict {
+            their_id: target,
+            path: repo.workdir().unwrap_or(repo.git_dir()).to_owned(),
+        });
+    }
+    let tree = outcome.tree_merge.tree.write()?.detach();
+    let committer = repo.committer().ok_or(Error::CommitterMissing)??;
+    let author = repo.author().ok_or(Error::CommitterMissing)??;
+    let commit = gix_object::Commit {
+        message: format!("Merge commit '{target}'\n").into(),
+        tree,
+        author: author.into(),
+        committer: committer.into(),
+        encoding: None,
+        parents: [head, target].into_iter().collect(),
+        extra_headers: Default::default(),
+    };
+    Ok(repo.write_object(&commit)?.detach())
+}
+
+/// Rebase the commits between the merge-base of `head` and `target` and `head` onto `target` and return the commit
+/// `HEAD` should point to.
+#[cfg(feature = "merge")]
+fn rebase(repo: &Repository, head: ObjectId, target: ObjectId) -> Result<ObjectId, Error> {
+    let base = repo.merge_base(head, target)?.detach();
+    if base == target {
+        return Ok(head);
+    }
+    let mut commits = Vec::new();
+    let mut current = head;
+    while current != base {
+        let commit = repo.find_commit(current)?;
+        let mut parents = commit.parent_ids();
+        let Some(first_parent) = parents.next() else { break };
+        if parents.next().is_none() {
+            commits.push(current);
+        }
+        current = first_parent.detach();
+    }
+
+    let committer = repo.committer().ok_or(Error::CommitterMissing)??;
+    let mut onto = target;
+    for id in commits.into_iter().rev() {
+        let commit = repo.find_commit(id)?;
+        let parent_tree = repo
+            .find_commit(commit.parent_ids().next().expect("only commits with parent"))?
+            .tree_id()?;
+        let onto_tree = repo.find_commit(onto)?.tree_id()?;
+        let mut outcome = repo.merge_trees(
+            parent_tree,
+            onto_tree,
+            commit.tree_id()?,
+            Default::default(),
+            repo.tree_merge_options()?,
+        )?;
+        if outcome.has_unresolved_conflicts(crate::merge::tree::TreatAsUnresolved::default()) {
+            return Err(Error::RebaseConflict {
+                id,
+                path: repo.workdir().unwrap_or(repo.git_dir()).to_owned(),
+            });
+        }
+        let tree = outcome.tree.write()?.detach();
+        let mut rebased = commit.decode()?.to_owned();
+        rebased.tree = tree;
+        rebased.parents = Some(onto).into_iter().collect();
+        rebased.committer = committer.into();
+        onto = repo.write_object(&rebased)?.detach();
+    }
+    Ok(onto)
+}
+
+#[cfg(not(feature = "merge"))]
+fn merge(repo: &Repository, _head: ObjectId, _target: ObjectId) -> Result<ObjectId, Error> {
+    Err(unsupported(repo, Update::Merge))
+}
+
+#[cfg(not(feature = "merge"))]
+fn rebase(repo: &Repository, _head: ObjectId, _target: ObjectId) -> Result<ObjectId, Error> {
+    Err(unsupported(repo, Update::Rebase))
+}
+
+#[cfg(not(feature = "merge"))]
+fn unsupported(repo: &Repository, strategy: Update) -> Error {
+    Error::StrategyUnsupported {
+        name: gix_path::into_bstr(repo.workdir().unwrap_or(repo.git_dir())).into_owned(),
+        strategy,
+    }
+}
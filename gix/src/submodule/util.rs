@@ -0,0 +1,232 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    submodule::edit_config,
+    Repository,
+};
+
+/// Load the configuration file at `path` with `source`, or create it, and write it back after `edit` changed it.
+pub(crate) fn edit_config_file(
+    path: &Path,
+    source: gix_config::Source,
+    edit: impl FnOnce(&mut gix_config::File<'static>) -> Result<(), gix_config::file::set_raw_value::Error>,
+) -> Result<(), edit_config::Error> {
+    let mut config = if path.is_file() {
+        gix_config::File::from_path_no_includes(path.to_owned(), source).map_err(|source| {
+            edit_config::Error::LoadConfig {
+                source,
+                path: path.to_owned(),
+            }
+        })?
+    } else {
+        gix_config::File::new(gix_config::file::Metadata::from(source).at(path))
+    };
+    edit(&mut config)?;
+    let mut lock = gix_lock::File::acquire_to_update_resource(path, gix_lock::acquire::Fail::Immediately, None)?;
+    config.write_to(&mut lock).map_err(|source| edit_config::Error::Io {
+        source,
+        path: path.to_owned(),
+    })?;
+    lock.commit()?;
+    Ok(())
+}
+
+/// Change the repository-local configuration of `repo` on disk with `edit`.
+pub(crate) fn edit_local_config(
+    repo: &Repository,
+    edit: impl FnOnce(&mut gix_config::File<'static>) -> Result<(), gix_config::file::set_raw_value::Error>,
+) -> Result<(), edit_config::Error> {
+    edit_config_file(&repo.common_dir().join("config"), gix_config::Source::Local, edit)
+}
+
+/// Return the url that relative submodule urls of `repo` are resolved against, which is the url of its default remote,
+/// or the path to its working tree if there is none.
+pub(crate) fn superproject_url(repo: &Repository) -> BString {
+    repo.find_default_remote(crate::remote::Direction::Fetch)
+        .and_then(Result::ok)
+        .and_then(|remote| {
+            remote
+                .url(crate::remote::Direction::Fetch)
+                .map(gix_url::Url::to_bstring)
+        })
+        .unwrap_or_else(|| gix_path::into_bstr(repo.workdir().unwrap_or(repo.git_dir())).into_owned())
+}
+
+/// Resolve `url` against the [url of the superproject](superproject_url()) if it is relative.
+pub(crate) fn resolve_url(repo: &Repository, url: &BStr) -> Result<BString, gix_submodule::relative_url::Error> {
+    if gix_submodule::relative_url::is_relative(url) {
+        gix_submodule::relative_url::resolve(superproject_url(repo).as_ref(), url)
+    } else {
+        Ok(url.to_owned())
+    }
+}
+
+/// Return the path to `to` relative to the directory `from`, with both paths being absolute and without relative components.
+pub(crate) fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<_> = from.components().collect();
+    let to: Vec<_> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut out: PathBuf = std::iter::repeat("..").take(from.len() - common).collect();
+    out.extend(&to[common..]);
+    out
+}
+
+/// Return `path` with all symlinks resolved, even if its last components don't exist yet.
+pub(crate) fn realpath_of_possibly_missing(path: &Path) -> std::io::Result<PathBuf> {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    while !existing.exists() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => break,
+        }
+    }
+    let mut out = gix_path::realpath(existing).map_err(std::io::Error::other)?;
+    out.extend(missing.into_iter().rev());
+    Ok(out)
+}
+
+/// Link the working tree at `workdir` and the repository at `git_dir` by writing a `.git` file into the former and
+/// setting `core.worktree` in the latter, using relative paths just like `git` does.
+pub(crate) fn link_git_dir(workdir: &Path, git_dir: &Path) -> Result<(), edit_config::Error> {
+    let io_err = |path: &Path| {
+        let path = path.to_owned();
+        move |source| edit_config::Error::Io { source, path }
+    };
+    let workdir = realpath_of_possibly_missing(workdir).map_err(io_err(workdir))?;
+    let git_dir = realpath_of_possibly_missing(git_dir).map_err(io_err(git_dir))?;
+
+    let dot_git = workdir.join(gix_discover::DOT_GIT_DIR);
+    let mut content = BString::from("gitdir: ");
+    content.extend_from_slice(&gix_path::to_unix_separators_on_windows(gix_path::into_bstr(
+        relative_path(&workdir, &git_dir),
+    )));
+    content.push(b'\n');
+    std::fs::write(&dot_git, content).map_err(io_err(&dot_git))?;
+
+    let worktree =
+        gix_path::to_unix_separators_on_windows(gix_path::into_bstr(relative_path(&git_dir, &workdir))).into_owned();
+    edit_config_file(&git_dir.join("config"), gix_config::Source::Local, |config| {
+        config.set_raw_value(&crate::config::tree::Core::BARE, "false")?;
+        config.set_raw_value(&crate::config::tree::Core::WORKTREE, worktree.as_bstr())?;
+        Ok(())
+    })
+}
+
+/// Check out `tree` into the working tree of `repo` and write its index.
+///
+/// If `previous` is set, it's the index matching the current working tree, whose files are overwritten or removed
+/// if they aren't part of `tree`. Otherwise the working tree is expected to be empty.
+#[cfg(all(
+    feature = "blocking-network-client",
+    feature = "worktree-mutation",
+    feature = "dirwalk"
+))]
+pub(crate) fn checkout_tree(
+    repo: &Repository,
+    tree: gix_hash::ObjectId,
+    previous: Option<&gix_index::State>,
+    should_interrupt: &std::sync::atomic::AtomicBool,
+) -> Result<(), crate::submodule::checkout::Error> {
+    use crate::submodule::checkout::Error;
+
+    let workdir = repo.workdir().expect("submodules have a working tree");
+    let mut index = gix_index::State::from_tree(&tree, &repo.objects, repo.config.protect_options()?)
+        .map_err(|err| Error::IndexFromTree { id: tree, source: err })?;
+    index.set_version(repo.config.index_version()?);
+    repo.objects
+        .prefetch(
+            index
+                .entries()
+                .iter()
+                .filter(|entry| {
+                    !entry.mode.is_submodule() && !entry.flags.contains(gix_index::entry::Flags::SKIP_WORKTREE)
+                })
+                .map(|entry| entry.id),
+        )
+        .map_err(Error::Prefetch)?;
+
+    if let Some(previous) = previous {
+        for entry in previous.entries() {
+            let rela_path = entry.path(previous);
+            if entry.mode.is_submodule() || index.entry_by_path(rela_path).is_some() {
+                continue;
+            }
+            let path = workdir.join(gix_path::from_bstr(rela_path));
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(source) => return Err(Error::RemoveFile { source, path }),
+            }
+            let mut dir = path.parent();
+            while let Some(parent) = dir.filter(|dir| *dir != workdir) {
+                if std::fs::remove_dir(parent).is_err() {
+                    break;
+                }
+                dir = parent.parent();
+            }
+        }
+    }
+
+    let mut index = gix_index::File::from_state(index, repo.index_path());
+    let mut opts = repo.checkout_options(gix_worktree::stack::state::attributes::Source::IdMapping)?;
+    opts.destination_is_initially_empty = previous.is_none();
+    opts.overwrite_existing = previous.is_some();
+    gix_worktree_state::checkout(
+        &mut index,
+        workdir,
+        repo.objects.clone().into_arc().map_err(Error::OpenArcOdb)?,
+        &gix_features::progress::Discard,
+        &gix_features::progress::Discard,
+        should_interrupt,
+        opts,
+    )?;
+    index.write(Default::default())?;
+    Ok(())
+}
+
+/// Clone the repository at `url` into `git_dir`, typically in the `modules` directory of `superproject`, and link
+/// it to the working tree at `workdir`, which is created if needed but not checked out.
+/// If `branch` is set, it's used instead of the `HEAD` of the remote.
+#[cfg(all(
+    feature = "blocking-network-client",
+    feature = "worktree-mutation",
+    feature = "dirwalk"
+))]
+pub(crate) fn clone(
+    superproject: &Repository,
+    url: gix_url::Url,
+    git_dir: &Path,
+    workdir: &Path,
+    branch: Option<&BStr>,
+    should_interrupt: &std::sync::atomic::AtomicBool,
+) -> Result<Repository, crate::submodule::clone::Error> {
+    use crate::submodule::clone::Error;
+
+    if let Some(parent) = git_dir.parent() {
+        std::fs::create_dir_all(parent).map_err(|source| Error::Io {
+            source,
+            path: parent.to_owned(),
+        })?;
+    }
+    let mut prepare = crate::clone::PrepareFetch::new(
+        url,
+        git_dir,
+        crate::create::Kind::Bare,
+        Default::default(),
+        superproject.options.clone(),
+    )?
+    .with_ref_name(branch)?;
+    prepare.fetch_only(gix_features::progress::Discard, should_interrupt)?;
+
+    std::fs::create_dir_all(workdir).map_err(|source| Error::Io {
+        source,
+        path: workdir.to_owned(),
+    })?;
+    link_git_dir(workdir, git_dir)?;
+    Ok(crate::open_opts(workdir, superproject.options.clone())?)
+}
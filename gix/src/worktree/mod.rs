@@ -124,6 +124,8 @@ pub mod open_index {
         #[error(transparent)]
         ConfigSkipHash(#[from] crate::config::boolean::Error),
         #[error(transparent)]
+        ConfigIndexVersion(#[from] crate::config::index_version::Error),
+        #[error(transparent)]
         IndexFile(#[from] gix_index::file::init::Error),
         #[error(transparent)]
         IndexCorrupt(#[from] gix_index::file::verify::Error),
@@ -282,3 +284,222 @@ pub mod pathspec {
         }
     }
 }
+
+///
+#[cfg(feature = "worktree-mutation")]
+pub mod add {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// The error returned by [`Repository::worktree_add()`](crate::Repository::worktree_add()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree location '{}' already exists and is not an empty directory", path.display())]
+        LocationExists { path: PathBuf },
+        #[error("Worktree location '{}' has no file name that could be used as worktree id", path.display())]
+        InvalidLocation { path: PathBuf },
+        #[error("Branch '{name}' is already checked out at '{}'", path.display())]
+        BranchCheckedOut { name: gix_ref::FullName, path: PathBuf },
+        #[error(transparent)]
+        FindBranch(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        CreateBranch(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        OpenMainRepository(#[from] crate::open::Error),
+        #[error("Could not access '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error(transparent)]
+        HeadTreeId(#[from] crate::reference::head_tree_id::Error),
+        #[error("Could not create index from tree at {id}")]
+        IndexFromTree {
+            id: gix_hash::ObjectId,
+            source: gix_index::init::from_tree::Error,
+        },
+        #[error("Could not fetch the objects to check out from the promisor remote")]
+        Prefetch(#[source] gix_odb::store::promisor::Error),
+        #[error("Couldn't obtain configuration for core.protect*")]
+        BooleanConfig(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        IndexVersion(#[from] crate::config::index_version::Error),
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+        OpenArcOdb(#[source] std::io::Error),
+        #[error(transparent)]
+        Checkout(#[from] gix_worktree_state::checkout::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+    }
+
+    /// What to check out in a new linked worktree.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Head {
+        /// Detach `HEAD` at the given commit.
+        Detached(gix_hash::ObjectId),
+        /// Check out an existing branch, which must not be checked out in any other worktree.
+        Branch(gix_ref::FullName),
+        /// Create a new branch `name` pointing to the commit `start` and check it out. It's an error if it already exists.
+        NewBranch {
+            /// The name of the branch to create, like `refs/heads/topic`.
+            name: gix_ref::FullName,
+            /// The commit the new branch should point to.
+            start: gix_hash::ObjectId,
+        },
+    }
+
+    /// Options for use in [`Repository::worktree_add()`](crate::Repository::worktree_add()).
+    #[derive(Debug, Clone)]
+    pub struct Options {
+        /// If `true`, check out the tree of `HEAD` into the worktree and write its index, similar to `git worktree add`.
+        /// Otherwise, the worktree remains empty and has no index, similar to `git worktree add --no-checkout`.
+        pub checkout: bool,
+        /// If set, the new worktree will be locked with the given reason, which may be empty, similar to `git worktree add --lock`.
+        pub lock_reason: Option<BString>,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                checkout: true,
+                lock_reason: None,
+            }
+        }
+    }
+}
+
+///
+pub mod prune {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// The error returned by [`Repository::prune_worktrees()`](crate::Repository::prune_worktrees()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    #[error("Could not access '{}'", path.display())]
+    pub struct Error {
+        pub source: std::io::Error,
+        pub path: PathBuf,
+    }
+
+    /// The reason for a linked worktree to be prunable, as determined by [`Proxy::prunable()`](crate::worktree::Proxy::prunable()).
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub enum Reason {
+        /// The entry in `worktrees/` isn't a directory.
+        NotADirectory,
+        /// The `gitdir` file that points to the worktree doesn't exist.
+        GitDirFileMissing,
+        /// The `gitdir` file that points to the worktree couldn't be read.
+        GitDirFileUnreadable,
+        /// The `gitdir` file that points to the worktree is empty.
+        GitDirFileInvalid,
+        /// The `gitdir` file points to a location that doesn't exist anymore.
+        LocationMissing,
+    }
+
+    /// The outcome of [`Repository::prune_worktrees()`](crate::Repository::prune_worktrees()).
+    #[derive(Default, Debug, Clone)]
+    pub struct Outcome {
+        /// The ids of the worktrees whose administrative directories were removed, along with the reason for it.
+        pub pruned: Vec<(BString, Reason)>,
+    }
+}
+
+///
+pub mod repair {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Repository::repair_worktrees()`](crate::Repository::repair_worktrees()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ListWorktrees(std::io::Error),
+        #[error("Could not access '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+        #[error("'{}' is not a linked worktree of this repository", path.display())]
+        NotALinkedWorktree { path: PathBuf },
+    }
+
+    /// The outcome of [`Repository::repair_worktrees()`](crate::Repository::repair_worktrees()).
+    #[derive(Default, Debug, Clone)]
+    pub struct Outcome {
+        /// The `.git` files in worktrees that were rewritten to point to their administrative directory again.
+        pub dot_git_files: Vec<PathBuf>,
+        /// The `gitdir` files in administrative directories that were rewritten to point to their moved worktree again.
+        pub gitdir_files: Vec<PathBuf>,
+    }
+}
+
+/// Write `<worktree>/.git` so it points to the administrative directory `admin_dir` of the worktree.
+pub(crate) fn write_dot_git_file(
+    worktree: &std::path::Path,
+    admin_dir: &std::path::Path,
+) -> std::io::Result<std::path::PathBuf> {
+    let path = worktree.join(gix_discover::DOT_GIT_DIR);
+    let mut content = BString::from("gitdir: ");
+    content.extend_from_slice(&gix_path::to_unix_separators_on_windows(gix_path::into_bstr(admin_dir)));
+    content.push(b'\n');
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// Write the `gitdir` file in the administrative directory `admin_dir` so it points to the `.git` file in `worktree`.
+pub(crate) fn write_gitdir_file(
+    admin_dir: &std::path::Path,
+    worktree: &std::path::Path,
+) -> std::io::Result<std::path::PathBuf> {
+    let path = admin_dir.join("gitdir");
+    let mut content: BString =
+        gix_path::to_unix_separators_on_windows(gix_path::into_bstr(worktree.join(gix_discover::DOT_GIT_DIR)))
+            .into_owned();
+    content.push(b'\n');
+    std::fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// Return the reason for the worktree with the administrative directory at `admin_dir` to be prunable, or `None` if it is locked
+/// or still valid.
+/// If `expire` is set, worktrees whose location doesn't exist anymore are only prunable if their index was last modified
+/// at or before `expire`.
+pub(crate) fn prune_reason(
+    admin_dir: &std::path::Path,
+    expire: Option<gix_date::SecondsSinceUnixEpoch>,
+) -> Option<prune::Reason> {
+    use crate::bstr::ByteSlice;
+    use prune::Reason;
+    if !admin_dir.is_dir() {
+        return Some(Reason::NotADirectory);
+    }
+    if admin_dir.join("locked").exists() {
+        return None;
+    }
+    let gitdir = admin_dir.join("gitdir");
+    if !gitdir.exists() {
+        return Some(Reason::GitDirFileMissing);
+    }
+    let location = match std::fs::read(&gitdir) {
+        Ok(content) => content,
+        Err(_) => return Some(Reason::GitDirFileUnreadable),
+    };
+    let location = location.trim();
+    if location.is_empty() {
+        return Some(Reason::GitDirFileInvalid);
+    }
+    let location = gix_path::from_bstr(location.as_bstr());
+    if location.exists() {
+        return None;
+    }
+    let is_expired = expire.map_or(true, |expire| {
+        std::fs::metadata(admin_dir.join("index"))
+            .and_then(|md| md.modified())
+            .ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(true, |modified| {
+                modified.as_secs() as gix_date::SecondsSinceUnixEpoch <= expire
+            })
+    });
+    is_expired.then_some(Reason::LocationMissing)
+}
@@ -24,6 +24,77 @@ pub mod into_repo {
     }
 }
 
+///
+#[cfg(feature = "status")]
+pub mod remove {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Proxy::remove()`][super::Proxy::remove()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is locked and can't be removed")]
+        Locked { id: crate::bstr::BString },
+        #[error("Worktree at '{}' has modified or untracked files and can't be removed without force", base.display())]
+        Dirty { base: PathBuf },
+        #[error(transparent)]
+        OpenWorktree(#[from] crate::open::Error),
+        #[error(transparent)]
+        StatusPlatform(#[from] crate::status::Error),
+        #[error(transparent)]
+        CreateStatusIterator(#[from] crate::status::into_iter::Error),
+        #[error(transparent)]
+        Status(#[from] crate::status::iter::Error),
+        #[error("Could not access '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+    }
+
+    /// Options for use in [`Proxy::remove()`][super::Proxy::remove()].
+    #[derive(Default, Debug, Copy, Clone)]
+    pub struct Options {
+        /// If `true`, remove the worktree even if it has modified or untracked files, similar to `git worktree remove --force`.
+        ///
+        /// Locked worktrees are never removed.
+        pub force: bool,
+    }
+}
+
+///
+pub mod move_to {
+    use std::path::PathBuf;
+
+    /// The error returned by [`Proxy::move_to()`][super::Proxy::move_to()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is locked and can't be moved")]
+        Locked { id: crate::bstr::BString },
+        #[error("Worktree at '{}' is inaccessible", .base.display())]
+        MissingWorktree { base: PathBuf },
+        #[error("Destination '{}' already exists", path.display())]
+        DestinationExists { path: PathBuf },
+        #[error("Cannot move worktree from '{}' to '{}' as they are on different filesystems", base.display(), destination.display())]
+        CrossDevice { base: PathBuf, destination: PathBuf },
+        #[error("Could not access '{}'", path.display())]
+        Io { source: std::io::Error, path: PathBuf },
+    }
+}
+
+///
+pub mod lock {
+    /// The error returned by [`Proxy::lock()`][super::Proxy::lock()] and [`Proxy::unlock()`][super::Proxy::unlock()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Worktree '{id}' is already locked")]
+        AlreadyLocked { id: crate::bstr::BString },
+        #[error("Worktree '{id}' is not locked")]
+        NotLocked { id: crate::bstr::BString },
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
 impl<'repo> Proxy<'repo> {
     pub(crate) fn new(parent: &'repo Repository, git_dir: impl Into<PathBuf>) -> Self {
         Proxy {
@@ -98,4 +169,110 @@ impl Proxy<'_> {
         let repo = ThreadSafeRepository::open_from_paths(self.git_dir, base.into(), self.parent.options.clone())?;
         Ok(repo.into())
     }
+
+    /// Return the reason for this worktree to be pruned by [`Repository::prune_worktrees()`], or `None` if it is valid
+    /// or locked.
+    pub fn prunable(&self) -> Option<crate::worktree::prune::Reason> {
+        crate::worktree::prune_reason(&self.git_dir, None)
+    }
+
+    /// Lock this worktree so it can't be pruned, moved or removed, and store `reason` along with it, similar to `git worktree lock`.
+    pub fn lock(&self, reason: Option<&BStr>) -> Result<(), lock::Error> {
+        if self.is_locked() {
+            return Err(lock::Error::AlreadyLocked {
+                id: self.id().to_owned(),
+            });
+        }
+        std::fs::write(self.git_dir.join("locked"), reason.unwrap_or_default())?;
+        Ok(())
+    }
+
+    /// Unlock this worktree, similar to `git worktree unlock`.
+    pub fn unlock(&self) -> Result<(), lock::Error> {
+        if !self.is_locked() {
+            return Err(lock::Error::NotLocked {
+                id: self.id().to_owned(),
+            });
+        }
+        std::fs::remove_file(self.git_dir.join("locked"))?;
+        Ok(())
+    }
+
+    /// Move the checkout of this worktree to `destination`, which must not exist yet, and update the administrative
+    /// files accordingly, similar to `git worktree move`.
+    ///
+    /// Like `git`, this fails with [`move_to::Error::CrossDevice`] if `destination` is on a different filesystem.
+    pub fn move_to(&self, destination: impl Into<PathBuf>) -> Result<(), move_to::Error> {
+        let destination = destination.into();
+        if self.is_locked() {
+            return Err(move_to::Error::Locked {
+                id: self.id().to_owned(),
+            });
+        }
+        let base = self.base().map_err(|source| move_to::Error::Io {
+            source,
+            path: self.git_dir.join("gitdir"),
+        })?;
+        if !base.is_dir() {
+            return Err(move_to::Error::MissingWorktree { base });
+        }
+        if destination.symlink_metadata().is_ok() {
+            return Err(move_to::Error::DestinationExists { path: destination });
+        }
+        std::fs::rename(&base, &destination).map_err(|source| {
+            if gix_fs::io_err::is_cross_device(source.raw_os_error()) {
+                move_to::Error::CrossDevice {
+                    base: base.clone(),
+                    destination: destination.clone(),
+                }
+            } else {
+                move_to::Error::Io {
+                    source,
+                    path: destination.clone(),
+                }
+            }
+        })?;
+        let destination = gix_path::realpath(&destination).map_err(|err| move_to::Error::Io {
+            source: std::io::Error::other(err),
+            path: destination.clone(),
+        })?;
+        crate::worktree::write_gitdir_file(&self.git_dir, &destination).map_err(|source| move_to::Error::Io {
+            source,
+            path: self.git_dir.join("gitdir"),
+        })?;
+        Ok(())
+    }
+
+    /// Delete the checkout of this worktree along with its administrative files, similar to `git worktree remove`.
+    ///
+    /// Unless `options.force` is set, this fails if the worktree has modified or untracked files. If the checkout
+    /// doesn't exist anymore, only the administrative files are removed.
+    #[cfg(feature = "status")]
+    pub fn remove(self, options: remove::Options) -> Result<(), remove::Error> {
+        if self.is_locked() {
+            return Err(remove::Error::Locked {
+                id: self.id().to_owned(),
+            });
+        }
+        if let Some(base) = self.base().ok().filter(|base| base.is_dir()) {
+            if !options.force {
+                let repo: Repository = ThreadSafeRepository::open_from_paths(
+                    self.git_dir.clone(),
+                    base.clone().into(),
+                    self.parent.options.clone(),
+                )?
+                .into();
+                if let Some(change) = repo.status(gix_features::progress::Discard)?.into_iter(None)?.next() {
+                    change?;
+                    return Err(remove::Error::Dirty { base });
+                }
+            }
+            std::fs::remove_dir_all(&base).map_err(|source| remove::Error::Io { source, path: base })?;
+        }
+        std::fs::remove_dir_all(&self.git_dir).map_err(|source| remove::Error::Io {
+            source,
+            path: self.git_dir,
+        })?;
+        Ok(())
+    }
 }
@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+mkdir -p dir/sub other
+seq 10 > a
+seq 100 > dir/big
+seq 5 > dir/sub/c
+seq 20 > other/to-be-renamed
+printf '\0\1\2' > bin
+touch empty
+git add . && git commit -q -m "c1"
+
+seq 12 > a
+seq 2 100 > dir/big
+echo 6 >> dir/sub/c
+git mv other/to-be-renamed other/renamed
+echo 21 >> other/renamed
+printf '\0\1\2\3\4' > bin
+git rm -q empty
+seq 3 > new
+chmod +x dir/sub/c
+git add . && git commit -q -m "c2"
@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+seq 10 > a
+seq 20 > to-be-renamed
+printf 'no newline' > b
+git add . && git commit -q -m "base"
+git tag base
+
+seq 12 > a
+git mv to-be-renamed renamed
+echo 21 >> renamed
+git add . && git commit -q -m "modify a and rename a file
+
+This is the body,
+with two lines."
+
+printf 'no newline, still' > b
+echo new > new
+chmod +x a
+git add . && git commit -q -m "a rather long subject line with [special] characters/like slashes, which is going to be folded"
+
+git format-patch -q --cover-letter --signature=gitoxide -o patches base..main
+git format-patch -q --signature=gitoxide -o single main~1..main
+
+git checkout -q -b diverged base
+{ echo 0; seq 10 | sed "s/^9$/nine/"; } > a
+git commit -q -am "change the context of the first patch"
@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q cache-tree
+(cd cache-tree
+  echo 1 >file && git add file && git commit -q -m c1
+  # trees that are only reachable through the cache-tree extension of the index
+  mkdir dir && echo 2 >dir/file && git add dir && git write-tree >/dev/null
+)
+
+git init -q base
+(cd base
+  for n in 1 2 3; do
+    echo $n >file && git add file && git commit -q -m c$n
+  done
+)
+
+git clone -q --no-local base shallow
+(cd shallow
+  # the commit-graph still knows the parents that are removed after making the repository shallow
+  git commit-graph write --reachable
+  git fetch -q --depth 1
+  git remote remove origin
+  git -c gc.pruneExpire=now -c gc.writeCommitGraph=false gc -q
+  echo unreachable-loose | git hash-object -w --stdin >/dev/null
+)
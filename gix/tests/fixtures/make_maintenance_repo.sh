@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+echo 1 >file && git add file && git commit -q -m c1
+echo 2 >file && git commit -q -am c2
+git tag -a -m "annotated" v1
+git repack -q -a -d
+
+git checkout -q -b side
+echo side >side && git add side && git commit -q -m side
+git checkout -q main
+echo 3 >file && git commit -q -am c3
+git repack -q -d
+
+# a commit that is only reachable through the reflog
+echo 4 >file && git commit -q -am c4
+git reset -q --hard HEAD~1
+
+# a blob that is only reachable through the index
+echo staged >staged && git add staged
+
+# an unreachable object that is only present in its own pack
+echo unreachable-in-pack | git hash-object -w --stdin | git pack-objects -q .git/objects/pack/pack >/dev/null
+git prune-packed
+
+# an unreachable loose object
+echo unreachable-loose | git hash-object -w --stdin >/dev/null
@@ -0,0 +1,47 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+seq 20 > numbers
+printf 'no newline' > unterminated
+echo "to be deleted" > deleted
+mkdir dir
+echo "a" > dir/file
+echo "b" > dir.file
+git add . && git commit -q -m "base"
+git tag base
+
+git checkout -q -b upstream
+sed -i.bak 's/^5$/five/; s/^15$/fifteen/' numbers && rm numbers.bak
+git commit -q -am "change numbers"
+printf 'still no newline' > unterminated
+git commit -q -am "change unterminated"
+printf '\0binary\n' > binary
+git add binary && git commit -q -m "add binary"
+chmod +x numbers
+git commit -q -am "make numbers executable"
+git rm -q deleted
+echo "c" >> dir/file
+echo "d" >> dir.file
+echo "new" > new
+git add . && git commit -q -m "multiple files"
+echo "only upstream" >> numbers
+git commit -q -am "only upstream"
+
+git checkout -q -b topic base
+echo "  only  topic" > topic-only
+git add topic-only && git commit -q -m "only topic"
+git cherry-pick upstream~4 >/dev/null
+# The same change with different whitespace has the same patch id, unless whitespace is considered.
+sed -i.bak 's/^5$/  five/; s/^15$/fifteen/' numbers && rm numbers.bak
+git commit -q -am "change numbers with different whitespace"
+git cherry-pick upstream~1 >/dev/null
+
+for commit in $(git rev-list base upstream topic); do
+  for mode in stable unstable verbatim; do
+    git show --full-index $commit | git patch-id --$mode >> patch-ids.$mode
+  done
+done
+git cherry upstream topic > cherry
@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function commit_at() {
+  local date="$1" name="$2"
+  echo "$name" > "$name"
+  git add "$name"
+  GIT_AUTHOR_DATE="@$date +0000" GIT_COMMITTER_DATE="@$date +0000" git commit -q -m "$name"
+}
+
+git init -q
+git checkout -q -b main
+commit_at 1000000100 base
+commit_at 1000000200 shared
+
+# the clock of whoever committed this was far behind, so it looks older than its parent
+git checkout -q -b hidden
+commit_at 1000000050 skewed
+
+git checkout -q -b shown main
+commit_at 1000000300 first
+commit_at 1000000400 second
+
+git rev-list --reverse --no-merges hidden..shown > expected
@@ -0,0 +1,53 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+
+cat > a.c <<'EOC'
+int main() {
+    return 0;
+}
+
+int helper() {
+    return 1;
+}
+EOC
+seq 10 > b
+echo x > c
+git add . && git commit -q -m "base"
+git tag base
+
+git checkout -q -b v1
+sed -i.bak 's/return 0/return 2/' a.c && rm a.c.bak
+git commit -q -am "change main"
+echo 11 >> b
+git commit -q -am "extend b"
+echo x > new
+git add new && git commit -q -m "add new"
+git mv a.c moved.c
+echo "int z;" >> moved.c
+git rm -q c
+chmod +x b
+git add . && git commit -q -m "various"
+
+git checkout -q -b v2 base
+sed -i.bak 's/return 0/return 3/' a.c && rm a.c.bak
+git commit -q -am "change main" -m "with a body"
+echo 11 >> b
+git commit -q -am "extend b"
+echo y > other
+git add other && git commit -q -m "add other"
+chmod +x b
+echo 12 >> b
+git mv a.c moved.c
+echo "int y;" >> moved.c
+echo changed > c
+git add . && git commit -q -m "various"
+printf 'no newline' > d
+git add d && git commit -q -m "add d"
+
+git range-diff --no-color base..v1 base..v2 > range-diff.default
+git range-diff --no-color --creation-factor=300 base..v1 base..v2 > range-diff.creation-factor-300
+git range-diff --no-color --creation-factor=300 -U1 base..v1 base..v2 > range-diff.context-1
+git range-diff --no-color --creation-factor=300 v1...v2 > range-diff.symmetric
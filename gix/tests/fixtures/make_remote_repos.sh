@@ -381,3 +381,8 @@ git init empty-core-askpass
 (cd empty-core-askpass
   echo "    askpass =" >> .git/config
 )
+
+git clone --bare base partial-clone-remote
+(cd partial-clone-remote
+  git config uploadpack.allowFilter true
+)
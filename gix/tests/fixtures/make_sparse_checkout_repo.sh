@@ -0,0 +1,36 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  mkdir -p c1/c2 c1/c3 d/c4
+  for file in a b c1/a c1/b c1/c2/a c1/c2/b c1/c3/a c1/c3/b d/a d/b d/c4/a d/c4/b d/c4/c5; do
+    echo "$file" > "$file"
+  done
+  git add . && git commit -q -m "init"
+)
+
+function expect() {
+  local name=${1:?first argument is the scenario name}
+  shift
+  cp -R repo "$name"
+  (cd "$name"
+    git update-index --refresh >/dev/null
+    "$@" >/dev/null 2>&1
+    git ls-files -t --sparse > "../$name.index"
+    find . -type f -not -path './.git/*' | sort > "../$name.files"
+    cp .git/info/sparse-checkout "../$name.patterns"
+  )
+  rm -Rf "$name"
+}
+
+function modify_then_set() {
+  echo "modified" > d/a
+  git sparse-checkout set "$@"
+}
+
+expect set-cone git sparse-checkout set c1/c2
+expect set-cone-add sh -c 'git sparse-checkout set c1/c2 && git sparse-checkout add d/c4'
+expect set-sparse-index git sparse-checkout set --sparse-index c1/c2
+expect set-non-cone git sparse-checkout set --no-cone '/b' 'c1/c2/' 'a' '!d/a'
+expect set-modified modify_then_set c1/c2
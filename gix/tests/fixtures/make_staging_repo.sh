@@ -0,0 +1,43 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  printf '*.o\nignored-dir/\n' > .gitignore
+  echo "a" > a
+  mkdir -p dir/sub
+  echo "b" > dir/b
+  echo "c" > dir/sub/c
+  echo "exe" > exe && chmod +x exe
+  echo "to be deleted" > deleted
+  echo "staged" > staged
+  git add . && git commit -q -m "base"
+
+  echo "staged change" >> staged
+  git add staged
+  echo "a change" >> a
+  rm deleted
+  chmod -x exe
+  echo "new" > new
+  echo "new in dir" > dir/new
+  echo "object" > x.o
+  mkdir ignored-dir
+  echo "ignored" > ignored-dir/file
+)
+
+function expect() {
+  local name=${1:?first argument is the scenario name}
+  shift
+  cp -R repo "$name"
+  (cd "$name" && "$@" && git ls-files -s > "../$name.index")
+  rm -Rf "$name"
+}
+
+expect add-all git add -A
+expect add-tracked git add -u
+expect add-dir git add dir
+expect add-force git add -f x.o ignored-dir
+expect rm-cached git rm -q --cached a
+expect rm-recursive git rm -q -r dir
+expect mv-dir git mv dir moved
+expect mv-into-dir git mv exe dir
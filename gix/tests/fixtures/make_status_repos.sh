@@ -41,3 +41,36 @@ git init untracked-added
   echo content >added
   git add added
 )
+
+git init conflict
+(cd conflict
+  echo base >conflicting
+  echo content >to-rename
+  echo content >mode-change
+  git add . && git commit -m "base"
+
+  git checkout -b other
+  echo theirs >conflicting
+  git commit -am "theirs"
+
+  git checkout -
+  echo ours >conflicting
+  git commit -am "ours"
+  git merge other || :
+
+  git mv to-rename renamed
+  git update-index --chmod=+x mode-change
+)
+
+git init sparse-index
+(cd sparse-index
+  mkdir -p included excluded/sub
+  echo a >included/a
+  echo b >excluded/b
+  echo c >excluded/sub/c
+  echo root >root
+  git add . && git commit -m "init"
+
+  git sparse-checkout set --sparse-index included
+  echo changed >included/a && git add included/a
+)
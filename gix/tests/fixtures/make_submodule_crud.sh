@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q module1
+(cd module1
+  echo one > file
+  mkdir dir
+  echo two > dir/nested
+  git add .
+  git commit -q -m c1
+  echo three >> file
+  git commit -q -am c2
+)
+
+git init -q super
+(cd super
+  git submodule add ../module1 m1
+  (cd m1 && git checkout -q @~1)
+  git add m1
+  git commit -q -m "add submodule at c1"
+)
+
+git clone -q super super-clone
+(cd super-clone
+  git remote remove origin
+)
@@ -144,3 +144,42 @@ git clone with-submodules not-a-submodule
   mv modules.bak .gitmodules
   git add m1 && git commit -m "no submodule in index and commit, but in configuration"
 )
+
+git init module-with-submodule
+(cd module-with-submodule
+  git submodule add ../module1 m1
+  git commit -m "add submodule"
+)
+
+git init recursive-modified
+(cd recursive-modified
+  git submodule add ../module-with-submodule sm
+  git commit -m "add submodule"
+  git submodule update --init --recursive
+
+  echo change >> sm/m1/this
+)
+
+git init recursive-untracked
+(cd recursive-untracked
+  git submodule add ../module-with-submodule sm
+  git commit -m "add submodule"
+  git submodule update --init --recursive
+
+  touch sm/m1/new
+)
+
+cp -R modified-and-untracked modified-and-untracked-inactive
+(cd modified-and-untracked-inactive
+  git config submodule.m1.active false
+)
+
+cp -R modified-and-untracked modified-and-untracked-diff-ignore-all
+(cd modified-and-untracked-diff-ignore-all
+  git config diff.ignoreSubmodules all
+)
+
+cp -R modified-and-untracked-diff-ignore-all modified-and-untracked-diff-ignore-all-submodule-ignore-none
+(cd modified-and-untracked-diff-ignore-all-submodule-ignore-none
+  git config submodule.m1.ignore none
+)
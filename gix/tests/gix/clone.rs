@@ -695,6 +695,87 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    fn partial_clone_fetches_missing_objects_on_demand() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, out) = gix::prepare_clone_bare(remote::repo("partial-clone-remote").path(), tmp.path())?
+            .with_filter("blob:none")
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let config = repo.config_snapshot();
+        assert_eq!(config.integer("core.repositoryFormatVersion"), Some(1));
+        assert_eq!(
+            config.string("extensions.partialClone").expect("set").as_ref(),
+            "origin"
+        );
+        assert_eq!(config.boolean("remote.origin.promisor"), Some(true));
+        assert_eq!(
+            config.string("remote.origin.partialCloneFilter").expect("set").as_ref(),
+            "blob:none"
+        );
+        let data_path = match out.status {
+            gix::remote::fetch::Status::Change { write_pack_bundle, .. } => {
+                write_pack_bundle.data_path.expect("pack was written")
+            }
+            _ => unreachable!("a clone always carries a change"),
+        };
+        assert!(
+            data_path.with_extension("promisor").is_file(),
+            "packs from promisor remotes are marked as such"
+        );
+
+        let num_refs = repo.refs.iter()?.all()?.count();
+        let head_blob = hex_to_id("fe27474251f7f8368742f01fbd3bd5666b630a82");
+        assert!(!repo.has_object(head_blob), "blobs were omitted");
+        assert_eq!(
+            repo.find_object(head_blob)?.data.as_bstr(),
+            "g\nh\ni\nj\nd\ne\nf\nb\nc\na\n",
+            "but are fetched on access"
+        );
+        assert!(repo.has_object(head_blob));
+        assert!(repo.objects.is_in_promisor_pack(&head_blob));
+
+        let repo = gix::open_opts(repo.git_dir(), restricted())?;
+        let other_blob = hex_to_id("01058d844a98d293a3b03a8615a34700e4ed2be3");
+        assert!(!repo.has_object(other_blob));
+        assert_eq!(
+            repo.find_object(other_blob)?.data.as_bstr(),
+            "g\n",
+            "lazy fetching works after reopening the repository as well"
+        );
+        assert_eq!(
+            repo.refs.iter()?.all()?.count(),
+            num_refs,
+            "fetching missing objects doesn't create references"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn partial_clone_and_checkout() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let mut prepare = gix::clone::PrepareFetch::new(
+            remote::repo("partial-clone-remote").path(),
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted(),
+        )?
+        .with_filter("blob:none");
+        let (mut checkout, _out) =
+            prepare.fetch_then_checkout(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let index = repo.index()?;
+        assure_index_entries_on_disk(&index, repo.workdir().expect("non-bare"));
+        assert_eq!(
+            std::fs::read(repo.workdir().expect("non-bare").join("file"))?,
+            b"g\nh\ni\nj\nd\ne\nf\nb\nc\na\n",
+            "the blobs to check out were fetched"
+        );
+        Ok(())
+    }
+
     #[test]
     fn fetch_only_without_configuration() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
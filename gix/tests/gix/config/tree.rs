@@ -306,6 +306,30 @@ mod diff {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "attributes")]
+    fn ignore_submodules() -> crate::Result {
+        use gix::submodule::config::Ignore;
+        for (value, expected) in [
+            ("all", Ignore::All),
+            ("dirty", Ignore::Dirty),
+            ("untracked", Ignore::Untracked),
+            ("none", Ignore::None),
+        ] {
+            assert_eq!(Diff::IGNORE_SUBMODULES.try_into_ignore(bcow(value))?, expected);
+            assert!(Diff::IGNORE_SUBMODULES.validate(value.into()).is_ok());
+        }
+        assert_eq!(
+            Diff::IGNORE_SUBMODULES
+                .try_into_ignore(bcow("foo"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"diff.ignoreSubmodules=foo\" was invalid"
+        );
+        assert!(Diff::IGNORE_SUBMODULES.validate("foo".into()).is_err());
+        Ok(())
+    }
+
     #[test]
     fn driver_binary() -> crate::Result {
         assert_eq!(
@@ -494,6 +518,37 @@ mod core {
         Ok(())
     }
 
+    #[test]
+    fn untracked_cache() -> crate::Result {
+        assert_eq!(
+            Core::UNTRACKED_CACHE.try_into_untracked_cache(Some(Ok(true)))?,
+            Some(true)
+        );
+        assert!(Core::UNTRACKED_CACHE.validate("true".into()).is_ok());
+        assert_eq!(
+            Core::UNTRACKED_CACHE.try_into_untracked_cache(Some(Ok(false)))?,
+            Some(false)
+        );
+        assert!(Core::UNTRACKED_CACHE.validate("0".into()).is_ok());
+        let boolean = |value| {
+            gix_config::Boolean::try_from(bcow(value))
+                .map(|b| Some(b.0))
+                .transpose()
+        };
+        assert_eq!(Core::UNTRACKED_CACHE.try_into_untracked_cache(boolean("keep"))?, None);
+        assert!(Core::UNTRACKED_CACHE.validate("keep".into()).is_ok());
+        assert_eq!(Core::UNTRACKED_CACHE.try_into_untracked_cache(None)?, None);
+        assert_eq!(
+            Core::UNTRACKED_CACHE
+                .try_into_untracked_cache(boolean("invalid"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"core.untrackedCache=invalid\" was invalid"
+        );
+        assert!(Core::UNTRACKED_CACHE.validate("invalid".into()).is_err());
+        Ok(())
+    }
+
     #[test]
     fn abbrev() -> crate::Result {
         let object_hash = gix_hash::Kind::Sha1;
@@ -677,6 +732,26 @@ mod index {
             "The key \"index.threads=nothing\" was invalid"
         );
     }
+
+    #[test]
+    #[cfg(feature = "index")]
+    fn version() -> crate::Result {
+        for (value, expected) in [
+            (2, gix::index::Version::V2),
+            (3, gix::index::Version::V3),
+            (4, gix::index::Version::V4),
+        ] {
+            assert_eq!(Index::VERSION.try_into_index_version(Ok(value))?, expected);
+            assert!(Index::VERSION.validate(value.to_string().as_str().into()).is_ok());
+        }
+        assert_eq!(
+            Index::VERSION.try_into_index_version(Ok(5)).unwrap_err().to_string(),
+            "The value of key \"index.version\" was invalid"
+        );
+        assert!(Index::VERSION.validate("1".into()).is_err());
+        assert!(Index::VERSION.validate("four".into()).is_err());
+        Ok(())
+    }
 }
 
 mod extensions {
@@ -728,6 +803,47 @@ mod checkout {
     }
 }
 
+mod gc {
+    use gix::config::tree::{Gc, Key};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn expiry() -> crate::Result {
+        let now = std::time::UNIX_EPOCH + std::time::Duration::from_secs(20 * 24 * 60 * 60);
+        for never in ["never", "false"] {
+            assert_eq!(Gc::PRUNE_EXPIRE.try_into_expiry(bcow(never), now)?, None);
+            assert!(Gc::PRUNE_EXPIRE.validate(never.into()).is_ok());
+        }
+        for everything in ["now", "all"] {
+            assert_eq!(
+                Gc::REFLOG_EXPIRE.try_into_expiry(bcow(everything), now)?,
+                Some(gix_date::Time::new(20 * 24 * 60 * 60, 0))
+            );
+        }
+        for two_weeks_ago in ["2.weeks.ago", "2 weeks ago"] {
+            assert_eq!(
+                Gc::REFLOG_EXPIRE_UNREACHABLE.try_into_expiry(bcow(two_weeks_ago), now)?,
+                Some(gix_date::Time::new(6 * 24 * 60 * 60, 0))
+            );
+            assert!(Gc::REFLOG_EXPIRE_UNREACHABLE.validate(two_weeks_ago.into()).is_ok());
+        }
+        assert_eq!(
+            Gc::PRUNE_EXPIRE.try_into_expiry(bcow("1700000000"), now)?,
+            Some(gix_date::Time::new(1700000000, 0))
+        );
+        assert_eq!(
+            Gc::PRUNE_EXPIRE
+                .try_into_expiry(bcow("foo"), now)
+                .unwrap_err()
+                .to_string(),
+            "The key \"gc.pruneExpire=foo\" was invalid"
+        );
+        assert!(Gc::PRUNE_EXPIRE.validate("foo".into()).is_err());
+        Ok(())
+    }
+}
+
 mod pack {
     use gix::config::tree::{Key, Pack};
 
@@ -13,15 +13,22 @@ fn resource_cache() -> crate::Result {
             .detach(),
         Default::default(),
     )?;
+    let (builtin, configured): (Vec<_>, Vec<_>) = cache
+        .filter
+        .drivers()
+        .iter()
+        .cloned()
+        .partition(|d| gix_diff::blob::funcname::BUILTIN_DRIVER_NAMES.contains(&d.name.to_string().as_str()));
     assert_eq!(
-        cache.filter.drivers(),
+        configured,
         &[
             Driver {
                 name: "all-but-binary".into(),
                 command: Some("command".into()),
                 algorithm: Some(Algorithm::Histogram),
                 binary_to_text_command: Some("textconv".into()),
-                is_binary: None
+                is_binary: None,
+                xfuncname: None,
             },
             Driver {
                 name: "binary-false".into(),
@@ -35,6 +42,11 @@ fn resource_cache() -> crate::Result {
             }
         ]
     );
+    assert_eq!(
+        builtin.len(),
+        gix_diff::blob::funcname::BUILTIN_DRIVER_NAMES.len(),
+        "built-in drivers are always present so their function-name patterns can be used"
+    );
     assert_eq!(cache.options.algorithm, Some(Algorithm::Histogram));
     assert!(
         !cache.options.skip_internal_diff_if_external_is_configured,
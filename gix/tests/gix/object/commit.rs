@@ -43,3 +43,110 @@ fn decode() -> crate::Result {
     assert_eq!(commit.decode()?.message, "c2\n");
     Ok(())
 }
+
+#[cfg(feature = "blob-diff")]
+mod patch_id {
+    use std::collections::HashMap;
+
+    use gix::diff::patch_id::Mode;
+    use gix_hash::ObjectId;
+
+    use crate::util::named_repo;
+
+    /// Read the patch ids Git produced for each commit, keyed by commit.
+    fn expected(repo: &gix::Repository, mode: &str) -> crate::Result<HashMap<ObjectId, ObjectId>> {
+        let ids = std::fs::read_to_string(repo.workdir().expect("non-bare").join(format!("patch-ids.{mode}")))?;
+        ids.lines()
+            .map(|line| {
+                let (patch_id, commit) = line.split_once(' ').expect("two ids per line");
+                Ok((
+                    ObjectId::from_hex(commit.as_bytes())?,
+                    ObjectId::from_hex(patch_id.as_bytes())?,
+                ))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_git_in_all_modes() -> crate::Result {
+        let repo = named_repo("make_patch_id_repo.sh")?;
+        for (mode, name) in [
+            (Mode::Stable, "stable"),
+            (Mode::Unstable, "unstable"),
+            (Mode::Verbatim, "verbatim"),
+        ] {
+            let expected = expected(&repo, name)?;
+            assert_eq!(expected.len(), 11, "all commits of all branches have a patch id");
+            for (commit, patch_id) in &expected {
+                let commit = repo.find_commit(*commit)?;
+                assert_eq!(
+                    commit.patch_id(mode)?,
+                    Some(*patch_id),
+                    "{name}: {}",
+                    commit.message()?.summary()
+                );
+            }
+
+            let mut seen = 0;
+            for res in repo
+                .rev_walk([repo.rev_parse_single("upstream")?])
+                .all()?
+                .patch_ids(mode)
+            {
+                let (info, patch_id) = res?;
+                assert_eq!(
+                    patch_id.as_ref(),
+                    expected.get(&info.id),
+                    "{name}: the bulk mode is the same"
+                );
+                seen += 1;
+            }
+            assert_eq!(seen, 7, "upstream has all commits");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn whitespace_only_matters_in_verbatim_mode() -> crate::Result {
+        let repo = named_repo("make_patch_id_repo.sh")?;
+        let original = repo.rev_parse_single("upstream~5")?.object()?.into_commit();
+        let reformatted = repo.rev_parse_single("topic~1")?.object()?.into_commit();
+        for mode in [Mode::Stable, Mode::Unstable] {
+            assert_eq!(original.patch_id(mode)?, reformatted.patch_id(mode)?);
+        }
+        assert_ne!(
+            original.patch_id(Mode::Verbatim)?,
+            reformatted.patch_id(Mode::Verbatim)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cherry_picked_commits_can_be_found() -> crate::Result {
+        let repo = named_repo("make_patch_id_repo.sh")?;
+        let base = repo.rev_parse_single("base")?.detach();
+        let not_base = move |id: &gix_hash::oid| id != base;
+
+        let (mut topic, _upstream) = gix::commit::patch_id::cherry(
+            repo.rev_walk([repo.rev_parse_single("topic")?])
+                .selected(not_base)?
+                .patch_ids(Mode::Stable),
+            repo.rev_walk([repo.rev_parse_single("upstream")?])
+                .selected(not_base)?
+                .patch_ids(Mode::Stable),
+        )?;
+        topic.reverse();
+        let cherry: Vec<_> = topic
+            .iter()
+            .map(|entry| format!("{} {}\n", if entry.equivalent { '-' } else { '+' }, entry.id))
+            .collect();
+
+        let expected = std::fs::read_to_string(repo.workdir().expect("non-bare").join("cherry"))?;
+        assert_eq!(
+            cherry.concat(),
+            expected,
+            "the output matches `git cherry upstream topic`"
+        );
+        Ok(())
+    }
+}
@@ -120,6 +120,151 @@ fn changes_against_tree_modified() -> crate::Result {
     Ok(())
 }
 
+mod stats_per_file {
+    use gix::object::tree::diff::stats::{dirstat, stat, PerFile};
+    use gix_object::bstr::ByteSlice;
+
+    use crate::{named_repo, object::tree::diff::tree_named};
+
+    fn per_file() -> crate::Result<PerFile> {
+        let repo = named_repo("make_diff_stat_repo.sh")?;
+        let from = tree_named(&repo, "@~1");
+        let to = tree_named(&repo, "@");
+        Ok(from.changes()?.stats_per_file(&to)?)
+    }
+
+    fn render(f: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> crate::Result<String> {
+        let mut buf = Vec::new();
+        f(&mut buf)?;
+        Ok(buf.to_str()?.to_owned())
+    }
+
+    #[test]
+    fn stat_numstat_and_shortstat_match_git() -> crate::Result {
+        let stats = per_file()?;
+        assert_eq!(
+            stats.totals(),
+            gix::object::tree::diff::Stats {
+                lines_added: 7,
+                lines_removed: 1,
+                files_changed: 7,
+            }
+        );
+
+        insta::assert_snapshot!(render(|out| stats.write_stat(out, Default::default()))?, @r"
+         a                                |   2 ++
+         bin                              | Bin 3 -> 5 bytes
+         dir/big                          |   1 -
+         dir/sub/c                        |   1 +
+         empty                            |   0
+         new                              |   3 +++
+         other/{to-be-renamed => renamed} |   1 +
+         7 files changed, 7 insertions(+), 1 deletion(-)
+        ");
+        insta::assert_snapshot!(render(|out| stats.write_stat(out, stat::Options { width: 40, ..Default::default() }))?, @r"
+         a                         |   2 ++
+         bin                       | Bin 3 -> 5 bytes
+         dir/big                   |   1 -
+         dir/sub/c                 |   1 +
+         empty                     |   0
+         new                       |   3 +++
+         ...be-renamed => renamed} |   1 +
+         7 files changed, 7 insertions(+), 1 deletion(-)
+        ");
+        insta::assert_snapshot!(render(|out| stats.write_stat(
+            out,
+            stat::Options {
+                width: 60,
+                name_width: Some(10),
+                graph_width: Some(5),
+                count: Some(3),
+            }
+        ))?, @r"
+         a       |   2 ++
+         bin     | Bin 3 -> 5 bytes
+         dir/big |   1 -
+         ...
+         7 files changed, 7 insertions(+), 1 deletion(-)
+        ");
+
+        insta::assert_snapshot!(render(|out| stats.write_numstat(out))?, @r"
+        2	0	a
+        -	-	bin
+        0	1	dir/big
+        1	0	dir/sub/c
+        0	0	empty
+        3	0	new
+        1	0	other/{to-be-renamed => renamed}
+        ");
+        insta::assert_snapshot!(render(|out| stats.write_shortstat(out))?, @" 7 files changed, 7 insertions(+), 1 deletion(-)");
+        Ok(())
+    }
+
+    #[test]
+    fn dirstat() -> crate::Result {
+        let stats = per_file()?;
+        insta::assert_snapshot!(render(|out| stats.write_dirstat(
+            out,
+            dirstat::Options {
+                mode: dirstat::Mode::Lines,
+                cumulative: true,
+                ..Default::default()
+            }
+        ))?, @r"
+          11.1% dir/sub/
+          22.2% dir/
+          11.1% other/
+        ");
+        insta::assert_snapshot!(render(|out| stats.write_dirstat(
+            out,
+            dirstat::Options {
+                mode: dirstat::Mode::Files,
+                permille: 0,
+                ..Default::default()
+            }
+        ))?, @r"
+          14.2% dir/sub/
+          14.2% dir/
+          14.2% other/
+        ");
+
+        let dirs = stats.dirstat(Default::default());
+        assert_eq!(
+            dirs.iter().map(|d| d.path.to_str().expect("valid")).collect::<Vec<_>>(),
+            ["dir/sub/", "dir/", "other/"],
+            "byte-based changes only differ in the way binary files are counted"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rename_names_factor_out_common_parts() {
+        let file = |source: &str, location: &str| gix::object::tree::diff::stats::File {
+            location: location.into(),
+            source_location: Some(source.into()),
+            lines_added: 0,
+            lines_removed: 0,
+            bytes_added: 0,
+            bytes_removed: 0,
+            size_before: 0,
+            size_after: 0,
+            is_binary: false,
+            is_content_unchanged: true,
+        };
+        for (source, location, expected) in [
+            ("a", "b", "a => b"),
+            ("dir/a", "dir/b", "dir/{a => b}"),
+            ("a/file", "b/file", "{a => b}/file"),
+            ("dir/a/file", "dir/b/file", "dir/{a => b}/file"),
+            ("dir/file", "dir/sub/file", "dir/{ => sub}/file"),
+            ("dir/sub/file", "dir/file", "dir/{sub => }/file"),
+            ("file", "dir/file", "file => dir/file"),
+        ] {
+            assert_eq!(file(source, location).display_name().as_ref(), expected);
+        }
+    }
+}
+
 mod track_rewrites {
     use std::{collections::HashMap, convert::Infallible};
 
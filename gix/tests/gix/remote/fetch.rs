@@ -268,6 +268,73 @@ mod blocking_and_async_io {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_writes_commit_graph_if_configured() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, out) = gix::clone::PrepareFetch::new(
+            remote::repo("base").path(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            gix::open::Options::isolated().config_overrides(["fetch.writeCommitGraph=true"]),
+        )?
+        .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+        assert!(matches!(out.status, Status::Change { .. }), "a pack was received");
+
+        let graph = repo.commit_graph()?;
+        let num_commits = repo
+            .rev_walk(
+                repo.references()?
+                    .all()?
+                    .filter_map(|r| r.ok()?.peel_to_id_in_place().ok()),
+            )
+            .all()?
+            .count();
+        assert_eq!(
+            graph.num_commits() as usize,
+            num_commits,
+            "all reachable commits are in the graph"
+        );
+        assert!(
+            repo.objects
+                .store_ref()
+                .path()
+                .join("info/commit-graphs/commit-graph-chain")
+                .is_file(),
+            "just like git, a split commit-graph is written"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "blocking-network-client")]
+    fn fetch_succeeds_even_if_commit_graph_cannot_be_written() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, out) = gix::clone::PrepareFetch::new(
+            remote::repo("base").path(),
+            tmp.path(),
+            gix::create::Kind::Bare,
+            Default::default(),
+            gix::open::Options::isolated().config_overrides(["fetch.writeCommitGraph=true"]),
+        )?
+        .configure_remote(|remote| {
+            // A file where the commit-graph directory should be makes writing it fail.
+            let info_dir = remote.repo().objects.store_ref().path().join("info");
+            std::fs::create_dir_all(&info_dir)?;
+            std::fs::write(info_dir.join("commit-graphs"), b"")?;
+            Ok(remote)
+        })
+        .fetch_only(gix::progress::Discard, &AtomicBool::default())?;
+        assert!(matches!(out.status, Status::Change { .. }), "a pack was received");
+        assert!(
+            repo.find_reference("refs/remotes/origin/main").is_ok(),
+            "refs were updated as the failure to write the commit-graph is not fatal"
+        );
+        assert!(repo.commit_graph().is_err(), "no commit-graph could be written");
+        Ok(())
+    }
+
     #[maybe_async::test(
         feature = "blocking-network-client",
         async(feature = "async-network-client-async-std", async_std::test)
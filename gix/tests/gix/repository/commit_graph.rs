@@ -0,0 +1,122 @@
+use gix::commitgraph::{
+    bloom::PathKeys,
+    write::info_dir::{Options, Split},
+};
+
+use crate::util::repo_rw;
+
+#[test]
+fn write_commit_graph_adds_reachable_commits_as_new_layer() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repo_with_fork_and_dates.sh")?;
+    assert_eq!(
+        repo.commit_graph()?.num_commits(),
+        3,
+        "the merge commit isn't in the graph yet"
+    );
+
+    let options = Options {
+        split: Some(Split::NoMerge),
+        ..Default::default()
+    };
+    let outcome = repo.write_commit_graph(options)?;
+    assert_eq!(outcome.num_commits, 1, "only the merge commit is new");
+    assert_eq!(
+        outcome.num_layers, 2,
+        "the existing graph becomes the base of the chain"
+    );
+
+    let graph = repo.commit_graph()?;
+    assert_eq!(graph.num_commits(), 4);
+    graph.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+    let head = repo.head_id()?;
+    let merge = graph.commit_by_id(head).expect("HEAD is in the graph");
+    assert_eq!(merge.generation(), 3);
+    assert_eq!(
+        graph.id_at(merge.parent1()?.expect("first parent")),
+        head.object()?
+            .into_commit()
+            .parent_ids()
+            .next()
+            .expect("first parent")
+            .as_ref()
+    );
+
+    let outcome = repo.write_commit_graph(options)?;
+    assert_eq!(outcome.path, None, "nothing to do as all commits are in the graph");
+
+    let outcome = repo.write_commit_graph(Options::default())?;
+    assert_eq!(outcome.num_commits, 4);
+    assert_eq!(
+        outcome.num_layers, 0,
+        "the chain is merged into a single file by default"
+    );
+    let graph = repo.commit_graph()?;
+    assert!(
+        graph.iter_commits().all(|c| c.corrected_commit_date().is_some()),
+        "corrected commit dates are written by default"
+    );
+    graph.verify_integrity(|_| Ok::<_, std::convert::Infallible>(()))?;
+    Ok(())
+}
+
+#[test]
+fn write_commit_graph_with_bloom_filters() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_repo_with_fork_and_dates.sh")?;
+    let outcome = repo.write_commit_graph(Options {
+        split: Some(Split::Replace),
+        bloom_filter_settings: Some(Default::default()),
+        ..Default::default()
+    })?;
+    assert_eq!(outcome.num_commits, 4);
+
+    let graph = repo.commit_graph()?;
+    let file_keys = PathKeys::new("dir/file".into());
+    let head = graph.commit_by_id(repo.head_id()?).expect("HEAD is in the graph");
+    let filter = head.bloom_filter().expect("new commits have filters");
+    assert_eq!(
+        filter.data(),
+        [0],
+        "the merge didn't change anything compared to its first parent"
+    );
+    assert!(!filter.may_contain_path(&file_keys));
+    assert_eq!(
+        graph
+            .iter_commits()
+            .filter(|commit| commit.bloom_filter().is_none())
+            .count(),
+        3,
+        "filters aren't computed for commits that were already in the graph"
+    );
+
+    let blob = repo.write_blob("content")?.detach();
+    let dir = repo.write_object(gix::objs::Tree {
+        entries: vec![gix::objs::tree::Entry {
+            mode: gix::objs::tree::EntryKind::Blob.into(),
+            filename: "file".into(),
+            oid: blob,
+        }],
+    })?;
+    let tree = repo.write_object(gix::objs::Tree {
+        entries: vec![gix::objs::tree::Entry {
+            mode: gix::objs::tree::EntryKind::Tree.into(),
+            filename: "dir".into(),
+            oid: dir.detach(),
+        }],
+    })?;
+    let commit = repo.commit("HEAD", "add file", tree, [repo.head_id()?])?;
+
+    let outcome = repo.write_commit_graph(Options {
+        split: Some(Split::NoMerge),
+        ..Default::default()
+    })?;
+    assert_eq!(outcome.num_commits, 1);
+    let graph = repo.commit_graph()?;
+    let filter = graph
+        .commit_by_id(commit)
+        .expect("new commit is in the graph")
+        .bloom_filter()
+        .expect("filters are written if the graph has them already");
+    assert!(filter.may_contain_path(&file_keys));
+    assert!(filter.may_contain_path(&PathKeys::new("dir".into())));
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use gix::{bstr::ByteSlice, repository::format_patch};
+
+fn repo_opts() -> gix::open::Options {
+    gix::open::Options::isolated().config_overrides([
+        "user.name=committer",
+        "user.email=committer@example.com",
+        "gitoxide.commit.committerDate=2000-01-02 00:00:00 +0000",
+    ])
+}
+
+fn read_patches(repo: &gix::Repository, dir: &str) -> crate::Result<Vec<(String, Vec<u8>)>> {
+    let mut patches = std::fs::read_dir(repo.workdir().expect("non-bare").join(dir))?
+        .map(|entry| {
+            let path = entry?.path();
+            Ok((
+                path.file_name().expect("file").to_str().expect("valid UTF-8").to_owned(),
+                std::fs::read(&path)?,
+            ))
+        })
+        .collect::<std::io::Result<Vec<_>>>()?;
+    patches.sort();
+    Ok(patches)
+}
+
+fn commits(repo: &gix::Repository, revs: &[&str]) -> crate::Result<Vec<gix::ObjectId>> {
+    Ok(revs
+        .iter()
+        .map(|rev| repo.rev_parse_single(*rev).map(gix::Id::detach))
+        .collect::<Result<_, _>>()?)
+}
+
+#[test]
+fn format_patch_matches_git() -> crate::Result {
+    let repo = crate::named_subrepo_opts("make_format_patch_repo.sh", "", repo_opts())?;
+    let options = format_patch::Options {
+        signature: Some("gitoxide".into()),
+        ..Default::default()
+    };
+
+    for (revs, dir, cover_letter) in [
+        (&["main~1", "main"][..], "patches", true),
+        (&["main"][..], "single", false),
+    ] {
+        let emails = repo.format_patch(
+            commits(&repo, revs)?,
+            &format_patch::Options {
+                cover_letter,
+                ..options.clone()
+            },
+        )?;
+        let expected = read_patches(&repo, dir)?;
+        assert_eq!(emails.len(), expected.len());
+        for (email, (file_name, data)) in emails.iter().zip(expected) {
+            assert_eq!(email.file_name, file_name);
+            assert_eq!(email.data.as_bstr(), data.as_bstr(), "{file_name} should match");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn apply_mailbox_recreates_the_original_commits() -> crate::Result {
+    let (repo, _tmp) = crate::util::repo_rw_opts("make_format_patch_repo.sh", repo_opts())?;
+    let mbox: Vec<u8> = read_patches(&repo, "patches")?
+        .into_iter()
+        .skip(1)
+        .flat_map(|(_, data)| data)
+        .collect();
+    let messages = gix::mailbox::split::mbox(&mbox)
+        .map(gix::mailbox::Message::from_bytes)
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(messages.len(), 2);
+
+    let base = repo.rev_parse_single("base")?.detach();
+    let commits = repo.apply_mailbox(base, messages, Default::default())?;
+    assert_eq!(commits.len(), 2);
+
+    for (actual, expected) in commits.iter().zip(["main~1", "main"]) {
+        let actual = repo.find_commit(*actual)?;
+        let expected = repo.rev_parse_single(expected)?.object()?.into_commit();
+        assert_eq!(actual.tree_id()?, expected.tree_id()?, "the trees are the same");
+        assert_eq!(actual.message_raw()?, expected.message_raw()?);
+        assert_eq!(actual.author()?, expected.author()?);
+    }
+    assert_eq!(
+        repo.find_commit(commits[1])?.parent_ids().next().map(gix::Id::detach),
+        Some(commits[0]),
+        "commits are chained"
+    );
+    Ok(())
+}
+
+#[test]
+fn apply_mailbox_with_three_way_merge() -> crate::Result {
+    let (repo, _tmp) = crate::util::repo_rw_opts("make_format_patch_repo.sh", repo_opts())?;
+    let (_, data) = read_patches(&repo, "patches")?.swap_remove(1);
+    let message = gix::mailbox::Message::from_bytes(&data)?;
+    assert_eq!(message.subject, "modify a and rename a file");
+
+    let diverged = repo.rev_parse_single("diverged")?.detach();
+    let err = repo
+        .apply_mailbox(diverged, Some(message.clone()), Default::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, gix::repository::apply_mailbox::Error::Apply { ref path, .. } if path == "a"),
+        "the context of the hunk changed: {err:?}"
+    );
+
+    let commits = repo.apply_mailbox(
+        diverged,
+        Some(message),
+        gix::repository::apply_mailbox::Options { three_way: true },
+    )?;
+    let tree = repo.find_commit(commits[0])?.tree()?;
+    let a = tree.find_entry("a").expect("present").object()?.detach().data;
+    assert_eq!(a.as_bstr(), "0\n1\n2\n3\n4\n5\n6\n7\n8\nnine\n10\n11\n12\n");
+    assert!(tree.find_entry("renamed").is_some(), "the rename was applied as well");
+    assert!(tree.find_entry("to-be-renamed").is_none());
+    Ok(())
+}
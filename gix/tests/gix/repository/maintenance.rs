@@ -0,0 +1,366 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix::{
+    config::tree::{Extensions, Gc},
+    maintenance::{expire_reflogs, gc, reachable, repack},
+};
+
+use crate::util::{hex_to_id, repo_rw};
+
+const UNREACHABLE_IN_PACK: &str = "6fe506003f1ccdfa2528ec9be66825539701af80";
+const UNREACHABLE_LOOSE: &str = "79cdf4f1f0a0c0b2610a56b07f4bc46d1d4e7128";
+const STAGED: &str = "19d9cc8584ac2c7dcf57d2680375e80f099dc481";
+
+fn packs(repo: &gix::Repository) -> std::io::Result<Vec<String>> {
+    let mut out: Vec<_> = std::fs::read_dir(repo.objects.store_ref().path().join("pack"))?
+        .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().into_owned()))
+        .collect::<Result<_, _>>()?;
+    out.retain(|name| Path::new(name).extension().is_some_and(|ext| ext == "pack"));
+    Ok(out)
+}
+
+fn loose_objects(repo: &gix::Repository) -> Vec<gix::ObjectId> {
+    let mut out: Vec<_> = gix_odb::loose::Store::at(repo.objects.store_ref().path(), repo.object_hash())
+        .iter()
+        .map(Result::unwrap)
+        .collect();
+    out.sort();
+    out
+}
+
+fn reopen(path: &Path) -> crate::Result<gix::Repository> {
+    Ok(gix::open_opts(path, crate::restricted())?)
+}
+
+#[test]
+fn repack_writes_all_reachable_objects_into_a_single_pack() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_maintenance_repo.sh")?;
+    assert_eq!(packs(&repo)?.len(), 3);
+
+    let outcome = repo.repack(
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        repack::Options {
+            write_multi_pack_index: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.packs_removed, 3);
+    assert_eq!(
+        outcome.pack.as_ref().expect("written").index.num_objects,
+        17,
+        "all commits, trees and blobs, the annotated tag, the commit in the reflog and the staged blob"
+    );
+    assert_eq!(outcome.objects_loosened, 1, "the unreachable object in its pack");
+    assert_eq!(outcome.objects_dropped, 0, "nothing expires by default");
+    assert_eq!(
+        outcome.prune_packed.objects_removed, 4,
+        "the reflog commit with its tree and blob, and the staged blob were loose"
+    );
+    let data_path = outcome
+        .pack
+        .as_ref()
+        .and_then(|pack| pack.data_path.clone())
+        .expect("written");
+    assert!(
+        data_path.with_extension("rev").is_file(),
+        "reverse indices are written by default"
+    );
+    let multi_pack_index_path = outcome.multi_pack_index_path.expect("requested");
+    assert!(multi_pack_index_path.is_file());
+
+    let repo = reopen(repo.git_dir())?;
+    assert_eq!(packs(&repo)?.len(), 1);
+    assert_eq!(
+        loose_objects(&repo),
+        [hex_to_id(UNREACHABLE_IN_PACK), hex_to_id(UNREACHABLE_LOOSE)],
+        "unreachable objects are loose now"
+    );
+    let reflog_commit = repo.rev_parse_single("main@{1}")?;
+    for id in [reflog_commit.detach(), hex_to_id(STAGED)] {
+        assert!(repo.has_object(id), "reflog and index objects are retained");
+    }
+    let multi_index = gix_pack::multi_index::File::at(&multi_pack_index_path)?;
+    assert_eq!(multi_index.num_indices(), 1);
+    assert_eq!(multi_index.num_objects(), 17);
+
+    let outcome = repo.repack(
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        repack::Options::default(),
+    )?;
+    assert_eq!(outcome.packs_removed, 0, "the pack is identical, and thus kept");
+    assert_eq!(outcome.prune_packed.objects_removed, 0);
+    assert_eq!(outcome.multi_pack_index_path, None);
+    assert!(!multi_pack_index_path.exists(), "the previous one is removed");
+    Ok(())
+}
+
+#[test]
+fn repack_refuses_to_work_on_partial_clones() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_maintenance_repo.sh")?;
+    repo.config_snapshot_mut()
+        .set_value(&Extensions::PARTIAL_CLONE, "origin")?;
+    let err = repo
+        .repack(
+            &mut gix::progress::Discard,
+            &AtomicBool::default(),
+            repack::Options::default(),
+        )
+        .unwrap_err();
+    assert!(
+        matches!(err, repack::Error::Reachable(reachable::Error::PartialClone)),
+        "missing objects would otherwise be fetched one by one"
+    );
+    assert_eq!(packs(&repo)?.len(), 3, "nothing was changed");
+    Ok(())
+}
+
+#[test]
+fn repack_drops_expired_unreachable_objects() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_maintenance_repo.sh")?;
+    let outcome = repo.repack(
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        repack::Options {
+            unpack_unreachable_expire: Some(gix_date::SecondsSinceUnixEpoch::MAX),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.objects_loosened, 0);
+    assert_eq!(outcome.objects_dropped, 1);
+
+    let repo = reopen(repo.git_dir())?;
+    assert!(!repo.has_object(hex_to_id(UNREACHABLE_IN_PACK)));
+    assert_eq!(loose_objects(&repo), [hex_to_id(UNREACHABLE_LOOSE)]);
+    Ok(())
+}
+
+#[test]
+fn prune_removes_expired_unreachable_loose_objects() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_maintenance_repo.sh")?;
+    let should_interrupt = AtomicBool::default();
+    assert_eq!(
+        repo.prune(None, &should_interrupt)?,
+        Default::default(),
+        "nothing expires, so nothing is done"
+    );
+
+    let outcome = repo.prune(Some(0), &should_interrupt)?;
+    assert_eq!(outcome.objects_removed, 0);
+    assert_eq!(outcome.objects_kept, 1, "the unreachable loose object is too new");
+
+    let outcome = repo.prune(Some(gix_date::SecondsSinceUnixEpoch::MAX), &should_interrupt)?;
+    assert_eq!(outcome.objects_removed, 1);
+    assert_eq!(outcome.objects_kept, 0);
+    assert_eq!(
+        loose_objects(&repo).len(),
+        4,
+        "the reflog commit with its tree and blob, and the staged blob, are reachable"
+    );
+    assert!(
+        repo.has_object(hex_to_id(UNREACHABLE_IN_PACK)),
+        "packed objects are not affected"
+    );
+    Ok(())
+}
+
+#[test]
+fn prune_packed_removes_loose_objects_that_are_packed() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_maintenance_repo.sh")?;
+    assert_eq!(
+        repo.prune_packed()?.objects_removed,
+        0,
+        "the fixture has no such objects"
+    );
+
+    let packed_path = repo
+        .objects
+        .store_ref()
+        .path()
+        .join(&UNREACHABLE_IN_PACK[..2])
+        .join(&UNREACHABLE_IN_PACK[2..]);
+    let loose = gix_odb::loose::Store::at(repo.objects.store_ref().path(), repo.object_hash());
+    let object = repo.find_object(hex_to_id(UNREACHABLE_IN_PACK))?;
+    let id = gix_object::Write::write_buf(&loose, object.kind, &object.data)?;
+    assert_eq!(id, hex_to_id(UNREACHABLE_IN_PACK));
+    assert!(packed_path.is_file());
+
+    assert_eq!(repo.prune_packed()?.objects_removed, 1);
+    assert!(!packed_path.exists());
+    assert!(
+        !packed_path.parent().expect("fan-out dir").exists(),
+        "empty fan-out directories are removed"
+    );
+    Ok(())
+}
+
+#[test]
+fn pack_refs_moves_loose_references_into_packed_refs() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_maintenance_repo.sh")?;
+    let before: Vec<_> = repo
+        .references()?
+        .all()?
+        .map(|r| r.map(|r| (r.name().to_owned(), r.target().into_owned())))
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(repo.pack_refs()?.refs_packed, 3, "two branches and a tag");
+    assert_eq!(repo.refs.loose_iter()?.count(), 0, "all loose references were removed");
+    let after: Vec<_> = repo
+        .references()?
+        .all()?
+        .map(|r| r.map(|r| (r.name().to_owned(), r.target().into_owned())))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(after, before, "references are unchanged");
+    assert_eq!(
+        repo.head_name()?.expect("not detached").as_bstr(),
+        "refs/heads/main",
+        "HEAD remains symbolic"
+    );
+    assert_eq!(
+        repo.find_reference("v1")?.peel_to_id_in_place()?,
+        repo.rev_parse_single("main~1")?,
+        "annotated tags can still be peeled"
+    );
+
+    assert_eq!(repo.pack_refs()?.refs_packed, 0, "there is nothing left to pack");
+    Ok(())
+}
+
+#[test]
+fn expire_reflogs_removes_old_or_unreachable_entries() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_maintenance_repo.sh")?;
+    assert_eq!(
+        repo.expire_reflogs(Default::default())?,
+        Default::default(),
+        "nothing expires by default"
+    );
+
+    let outcome = repo.expire_reflogs(expire_reflogs::Options {
+        expire: None,
+        expire_unreachable: Some(gix_date::SecondsSinceUnixEpoch::MAX),
+    })?;
+    assert_eq!(outcome.reflogs_rewritten, 2, "HEAD and main");
+    assert_eq!(
+        outcome.entries_removed, 6,
+        "HEAD loses the side-branch commit and the reflog commit with their follow-ups, main loses the reflog commit and the reset"
+    );
+    let messages = |name: &str| -> crate::Result<Vec<String>> {
+        let mut buf = Vec::new();
+        Ok(repo
+            .refs
+            .reflog_iter(name, &mut buf)?
+            .expect("present")
+            .map(|line| line.map(|line| line.message.to_string()))
+            .collect::<Result<_, _>>()?)
+    };
+    assert_eq!(
+        messages("refs/heads/main")?,
+        ["commit (initial): c1", "commit: c2", "commit: c3"]
+    );
+    assert_eq!(messages("refs/heads/side")?.len(), 2, "everything is reachable");
+
+    let outcome = repo.expire_reflogs(expire_reflogs::Options {
+        expire: Some(gix_date::SecondsSinceUnixEpoch::MAX),
+        expire_unreachable: None,
+    })?;
+    assert_eq!(outcome.reflogs_rewritten, 3);
+    assert_eq!(outcome.entries_removed, 4 + 3 + 2);
+    assert_eq!(messages("HEAD")?.len(), 0);
+    Ok(())
+}
+
+#[test]
+fn needs_gc_respects_auto_limits() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_maintenance_repo.sh")?;
+    assert!(!repo.needs_gc()?, "there are just a few loose objects and packs");
+
+    repo.config_snapshot_mut().set_value(&Gc::AUTO_PACK_LIMIT, "2")?;
+    assert!(repo.needs_gc()?, "there are 3 packs");
+
+    repo.config_snapshot_mut().set_value(&Gc::AUTO, "0")?;
+    assert!(!repo.needs_gc()?, "automatic garbage collection is disabled");
+    Ok(())
+}
+
+#[test]
+fn gc_packs_references_expires_reflogs_and_repacks() -> crate::Result {
+    let (mut repo, _tmp) = repo_rw("make_maintenance_repo.sh")?;
+    let should_interrupt = AtomicBool::default();
+    let auto = gc::Options {
+        auto: true,
+        ..Default::default()
+    };
+    assert!(
+        repo.gc(&mut gix::progress::Discard, &should_interrupt, auto)?.is_none(),
+        "nothing to do"
+    );
+
+    repo.config_snapshot_mut().set_value(&Gc::AUTO_PACK_LIMIT, "2")?;
+    let outcome = repo
+        .gc(&mut gix::progress::Discard, &should_interrupt, auto)?
+        .expect("there are too many packs");
+    assert_eq!(outcome.pack_refs.refs_packed, 3);
+    assert_eq!(
+        outcome.expire_reflogs.entries_removed,
+        8 + 5 + 2,
+        "all reflog entries are older than 90 days"
+    );
+    assert_eq!(outcome.repack.packs_removed, 3);
+    assert_eq!(
+        outcome.repack.pack.as_ref().expect("written").index.num_objects,
+        14,
+        "the reflog commit, its tree and blob aren't reachable anymore"
+    );
+    assert_eq!(outcome.repack.objects_loosened, 1, "the unreachable pack is too new");
+    assert_eq!(
+        outcome.prune.objects_kept, 5,
+        "unreachable loose objects are too new to be pruned, including the reflog commit with its tree and blob"
+    );
+
+    let repo = reopen(repo.git_dir())?;
+    assert!(!repo.needs_gc()?);
+    assert_eq!(packs(&repo)?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn prune_keeps_trees_that_are_only_referenced_by_the_cache_tree() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_maintenance_index_and_shallow_repos.sh")?;
+    let repo = reopen(&tmp.path().join("cache-tree"))?;
+    let index = repo.index()?;
+    let root = index.tree().expect("written by `git write-tree`");
+    let trees: Vec<_> = std::iter::once(root.id)
+        .chain(root.children.iter().map(|child| child.id))
+        .collect();
+    assert_eq!(trees.len(), 2, "the root tree and 'dir'");
+
+    let outcome = repo.prune(Some(gix_date::SecondsSinceUnixEpoch::MAX), &AtomicBool::default())?;
+    assert_eq!(outcome.objects_removed, 0, "all loose objects are reachable");
+    for id in trees {
+        assert!(repo.has_object(id), "like git, trees of the cache-tree are kept");
+    }
+    Ok(())
+}
+
+#[test]
+fn prune_and_repack_stop_at_shallow_boundaries() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_maintenance_index_and_shallow_repos.sh")?;
+    let repo = reopen(&tmp.path().join("shallow"))?;
+    assert!(repo.is_shallow());
+
+    let outcome = repo.prune(Some(gix_date::SecondsSinceUnixEpoch::MAX), &AtomicBool::default())?;
+    assert_eq!(outcome.objects_removed, 1, "only the unreachable object is removed");
+
+    let outcome = repo.repack(
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        repack::Options::default(),
+    )?;
+    assert_eq!(
+        outcome.pack.as_ref().expect("written").index.num_objects,
+        3,
+        "the shallow commit with its tree and blob, without the missing parents"
+    );
+    Ok(())
+}
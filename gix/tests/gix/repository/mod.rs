@@ -1,19 +1,30 @@
 use gix::Repository;
 
+mod commit_graph;
 mod config;
 #[cfg(feature = "excludes")]
 mod excludes;
 #[cfg(feature = "attributes")]
 mod filter;
+#[cfg(feature = "mailbox")]
+mod mailbox;
+#[cfg(feature = "maintenance")]
+mod maintenance;
 #[cfg(feature = "merge")]
 mod merge;
 mod object;
 mod open;
 #[cfg(feature = "attributes")]
 mod pathspec;
+#[cfg(feature = "blob-diff")]
+mod range_diff;
 mod reference;
 mod remote;
 mod shallow;
+#[cfg(feature = "worktree-mutation")]
+mod sparse_checkout;
+#[cfg(feature = "dirwalk")]
+mod staging;
 mod state;
 #[cfg(feature = "attributes")]
 mod submodule;
@@ -469,6 +469,28 @@ mod find {
         Ok(())
     }
 
+    #[test]
+    fn packs_can_be_read_in_windows_if_configured() -> crate::Result {
+        let repo = crate::named_repo("make_packed_and_loose.sh")?;
+        let windowed_repo = crate::repo_opts(
+            "make_packed_and_loose.sh",
+            crate::restricted().config_overrides(["core.packedGitWindowSize=64", "core.packedGitLimit=128"]),
+        )?
+        .to_thread_local();
+
+        let mut num_objects = 0;
+        for id in repo.objects.iter()? {
+            let id = id?;
+            let expected = repo.find_object(id)?;
+            let actual = windowed_repo.find_object(id)?;
+            assert_eq!(actual.kind, expected.kind);
+            assert_eq!(actual.data, expected.data, "{id}: windowed access yields the same data");
+            num_objects += 1;
+        }
+        assert!(num_objects > 0, "the fixture has objects to read");
+        Ok(())
+    }
+
     #[test]
     fn empty_tree_can_always_be_found() -> crate::Result {
         let repo = basic_repo()?;
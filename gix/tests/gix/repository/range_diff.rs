@@ -0,0 +1,143 @@
+use gix::{
+    bstr::ByteSlice,
+    diff::blob::unified_diff::ContextSize,
+    repository::range_diff::{Options, Outcome},
+};
+use gix_diff::range_diff::Pair;
+
+use crate::util::named_repo;
+
+fn render(outcome: &Outcome) -> crate::Result<String> {
+    let mut buf = Vec::new();
+    outcome.write_to(&mut buf)?;
+    Ok(String::from_utf8(buf)?)
+}
+
+fn expected(repo: &gix::Repository, name: &str) -> crate::Result<String> {
+    Ok(std::fs::read_to_string(
+        repo.workdir().expect("non-bare").join(format!("range-diff.{name}")),
+    )?)
+}
+
+#[test]
+fn output_matches_git() -> crate::Result {
+    let repo = named_repo("make_range_diff_repo.sh")?;
+    for (name, options) in [
+        ("default", Options::default()),
+        (
+            "creation-factor-300",
+            Options {
+                creation_factor: 300,
+                ..Default::default()
+            },
+        ),
+        (
+            "context-1",
+            Options {
+                creation_factor: 300,
+                context: ContextSize::symmetrical(1),
+            },
+        ),
+    ] {
+        let outcome = repo.range_diff_by_spec("base..v1", "base..v2", options)?;
+        assert_eq!(render(&outcome)?, expected(&repo, name)?, "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn symmetric_ranges_use_the_merge_base() -> crate::Result {
+    let repo = named_repo("make_range_diff_repo.sh")?;
+    let options = Options {
+        creation_factor: 300,
+        ..Default::default()
+    };
+    let outcome = repo.range_diff_by_symmetric_spec("v1...v2", options)?;
+    assert_eq!(render(&outcome)?, expected(&repo, "symmetric")?);
+
+    let by_spec = repo.range_diff_by_spec("v2..v1", "v1..v2", options)?;
+    assert_eq!(render(&by_spec)?, render(&outcome)?, "this is the same as two ranges");
+
+    let both_sides = repo.range_diff_by_spec("v1...v2", "v1...v2", options)?;
+    assert_eq!(
+        both_sides.old.len(),
+        outcome.old.len() + outcome.new.len(),
+        "used as a single range, the commits of both sides are contained"
+    );
+    Ok(())
+}
+
+#[test]
+fn pairs_and_commits() -> crate::Result {
+    let repo = named_repo("make_range_diff_repo.sh")?;
+    let outcome = repo.range_diff_by_spec(
+        "base..v1",
+        "base..v2",
+        Options {
+            creation_factor: 300,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.old.len(), 4);
+    assert_eq!(outcome.new.len(), 5);
+    assert_eq!(
+        outcome.pairs,
+        [
+            Pair::Changed { old: 0, new: 0 },
+            Pair::Unchanged { old: 1, new: 1 },
+            Pair::Added { new: 2 },
+            Pair::Changed { old: 3, new: 3 },
+            Pair::Changed { old: 2, new: 4 },
+        ]
+    );
+
+    let various = &outcome.new[3];
+    assert_eq!(various.subject, "various");
+    assert_eq!(
+        various.patch.diff().lines().next().map(ByteSlice::as_bstr),
+        Some(" ## b (mode change 100644 => 100755) ##".into()),
+        "the diff starts with the first file"
+    );
+
+    let ids: Vec<_> = outcome.new.iter().map(|c| c.id).collect();
+    let same = repo.range_diff(ids.clone(), ids, Options::default())?;
+    assert!(
+        same.pairs
+            .iter()
+            .all(|pair| matches!(pair, Pair::Unchanged { old, new } if old == new)),
+        "identical ranges pair all commits with themselves"
+    );
+    Ok(())
+}
+
+#[test]
+fn only_ranges_are_supported() -> crate::Result {
+    let repo = named_repo("make_range_diff_repo.sh")?;
+    let err = repo
+        .range_diff_by_spec("v1", "base..v2", Options::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, gix::repository::range_diff::Error::NotARange { .. }),
+        "{err:?}"
+    );
+
+    let err = repo
+        .range_diff_by_symmetric_spec("base..v2", Options::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, gix::repository::range_diff::Error::NotASymmetricRange { .. }),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn commits_reachable_from_the_hidden_side_are_excluded_despite_clock_skew() -> crate::Result {
+    let repo = named_repo("make_range_diff_clock_skew_repo.sh")?;
+    let outcome = repo.range_diff_by_spec("hidden..shown", "hidden..shown", Options::default())?;
+    let actual: Vec<_> = outcome.new.iter().map(|c| c.id.to_string()).collect();
+    let expected = std::fs::read_to_string(repo.workdir().expect("non-bare").join("expected"))?;
+    assert_eq!(actual, expected.lines().collect::<Vec<_>>());
+    assert_eq!(actual.len(), 2, "only 'first' and 'second' are in the range");
+    Ok(())
+}
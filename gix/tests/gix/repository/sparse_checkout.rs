@@ -0,0 +1,187 @@
+use gix::{bstr::ByteSlice, sparse_checkout::Options};
+use gix_testtools::tempfile::TempDir;
+
+fn writable_repo() -> crate::Result<(gix::Repository, TempDir)> {
+    let tmp = gix_testtools::scripted_fixture_writable("make_sparse_checkout_repo.sh")?;
+    let repo = gix::open_opts(tmp.path().join("repo"), crate::restricted())?;
+    Ok((repo, tmp))
+}
+
+/// Return the index of `repo` like `git ls-files -t --sparse` would.
+fn ls_files(repo: &gix::Repository) -> crate::Result<String> {
+    use std::fmt::Write;
+
+    let index = repo.open_index()?;
+    let mut out = String::new();
+    for entry in index.entries() {
+        let tag = if entry.flags.contains(gix::index::entry::Flags::SKIP_WORKTREE) {
+            'S'
+        } else {
+            'H'
+        };
+        writeln!(out, "{tag} {}", entry.path(&index))?;
+    }
+    Ok(out)
+}
+
+/// Return all files in the worktree of `repo` like `find . -type f -not -path './.git/*' | sort` would.
+fn worktree_files(repo: &gix::Repository) -> crate::Result<String> {
+    let workdir = repo.workdir().expect("non-bare");
+    let mut files = Vec::new();
+    for entry in walkdir::WalkDir::new(workdir)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".git")
+    {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            let rela_path = gix::path::to_unix_separators_on_windows(gix::path::into_bstr(
+                entry.path().strip_prefix(workdir)?.to_owned(),
+            ))
+            .into_owned();
+            files.push(format!("./{rela_path}\n"));
+        }
+    }
+    files.sort();
+    Ok(files.concat())
+}
+
+fn expected(tmp: &TempDir, scenario: &str, kind: &str) -> crate::Result<String> {
+    Ok(std::fs::read_to_string(tmp.path().join(format!("{scenario}.{kind}")))?)
+}
+
+fn assert_baseline(repo: &gix::Repository, tmp: &TempDir, scenario: &str) -> crate::Result {
+    assert_eq!(ls_files(repo)?, expected(tmp, scenario, "index")?, "{scenario}: index");
+    assert_eq!(
+        worktree_files(repo)?,
+        expected(tmp, scenario, "files")?,
+        "{scenario}: worktree"
+    );
+    assert_eq!(
+        std::fs::read_to_string(repo.sparse_checkout_path())?,
+        expected(tmp, scenario, "patterns")?,
+        "{scenario}: patterns"
+    );
+    Ok(())
+}
+
+#[test]
+fn patterns_are_none_if_sparse_checkout_is_disabled() -> crate::Result {
+    let (repo, _tmp) = writable_repo()?;
+    assert!(repo.sparse_checkout_patterns()?.is_none());
+    assert!(
+        matches!(
+            repo.sparse_checkout_reapply(),
+            Err(gix::sparse_checkout::update::Error::NotEnabled)
+        ),
+        "reapplying needs the sparse checkout to be enabled"
+    );
+    Ok(())
+}
+
+#[test]
+fn set_in_cone_mode() -> crate::Result {
+    let (mut repo, tmp) = writable_repo()?;
+    let out = repo.sparse_checkout_set(["c1/c2"], Options::default())?;
+    assert_eq!(out.files_removed, 7);
+    assert_eq!(out.files_checked_out, 0);
+    assert!(out.kept_modified.is_empty());
+    assert_baseline(&repo, &tmp, "set-cone")?;
+
+    assert!(
+        !repo.workdir_path("d").expect("non-bare").exists(),
+        "directories that are now empty are removed as well"
+    );
+    let config = repo.config_snapshot();
+    assert_eq!(config.boolean("core.sparseCheckout"), Some(true));
+    assert_eq!(config.boolean("core.sparseCheckoutCone"), Some(true));
+    assert_eq!(config.boolean("extensions.worktreeConfig"), Some(true));
+
+    let repo = gix::open_opts(repo.workdir().expect("non-bare"), crate::restricted())?;
+    let patterns = repo.sparse_checkout_patterns()?.expect("enabled");
+    assert!(patterns.is_cone(), "the configuration was persisted");
+    assert!(patterns.is_included("c1/c2/a".into()));
+    assert!(!patterns.is_included("d/a".into()));
+    Ok(())
+}
+
+#[test]
+fn add_in_cone_mode() -> crate::Result {
+    let (mut repo, tmp) = writable_repo()?;
+    repo.sparse_checkout_set(["c1/c2"], Options::default())?;
+    let out = repo.sparse_checkout_add(["d/c4"])?;
+    assert_eq!(
+        out.files_checked_out, 5,
+        "d/a and d/b are included as d is a parent now"
+    );
+    assert_eq!(out.files_removed, 0);
+    assert_baseline(&repo, &tmp, "set-cone-add")?;
+    assert_eq!(
+        std::fs::read(repo.workdir_path("d/c4/c5").expect("non-bare"))?.as_bstr(),
+        "d/c4/c5\n",
+        "files are checked out with their content"
+    );
+    Ok(())
+}
+
+#[test]
+fn set_keeps_modified_files() -> crate::Result {
+    let (mut repo, tmp) = writable_repo()?;
+    std::fs::write(repo.workdir_path("d/a").expect("non-bare"), "modified\n")?;
+    let out = repo.sparse_checkout_set(["c1/c2"], Options::default())?;
+    assert_eq!(out.kept_modified, ["d/a"]);
+    assert_eq!(out.files_removed, 6);
+    assert_baseline(&repo, &tmp, "set-modified")?;
+
+    std::fs::remove_file(repo.workdir_path("d/a").expect("non-bare"))?;
+    let out = repo.sparse_checkout_reapply()?;
+    assert_eq!(out.files_removed, 0, "there was nothing left to remove");
+    assert!(out.kept_modified.is_empty());
+    assert_baseline(&repo, &tmp, "set-cone")?;
+    Ok(())
+}
+
+#[test]
+fn set_with_sparse_index() -> crate::Result {
+    let (mut repo, tmp) = writable_repo()?;
+    repo.sparse_checkout_set(
+        ["c1/c2"],
+        Options {
+            sparse_index: Some(true),
+            ..Default::default()
+        },
+    )?;
+    assert_baseline(&repo, &tmp, "set-sparse-index")?;
+    assert!(repo.open_index()?.is_sparse());
+    assert_eq!(repo.config_snapshot().boolean("index.sparse"), Some(true));
+
+    let out = repo.sparse_checkout_add(["d/c4"])?;
+    assert_eq!(out.files_checked_out, 5, "sparse directories are expanded as needed");
+    let index = repo.open_index()?;
+    assert!(index.is_sparse(), "c1/c3 remains a sparse directory");
+    assert_eq!(
+        index
+            .entries()
+            .iter()
+            .filter(|e| e.mode.is_sparse())
+            .map(|e| e.path(&index).to_owned())
+            .collect::<Vec<_>>(),
+        ["c1/c3/"]
+    );
+    Ok(())
+}
+
+#[test]
+fn set_in_non_cone_mode() -> crate::Result {
+    let (mut repo, tmp) = writable_repo()?;
+    let out = repo.sparse_checkout_set(
+        ["/b", "c1/c2/", "a", "!d/a"],
+        Options {
+            cone: Some(false),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(out.files_removed, 6);
+    assert_baseline(&repo, &tmp, "set-non-cone")?;
+    assert_eq!(repo.config_snapshot().boolean("core.sparseCheckoutCone"), Some(false));
+    Ok(())
+}
@@ -0,0 +1,387 @@
+use gix::bstr::{BString, ByteSlice};
+use gix_testtools::tempfile::TempDir;
+
+fn writable_repo() -> crate::Result<(gix::Repository, TempDir)> {
+    let tmp = gix_testtools::scripted_fixture_writable("make_staging_repo.sh")?;
+    let repo = gix::open_opts(tmp.path().join("repo"), crate::restricted())?;
+    Ok((repo, tmp))
+}
+
+/// Return the index of `staging` like `git ls-files -s` would.
+fn ls_files(staging: &gix::staging::Platform<'_>) -> String {
+    use std::fmt::Write;
+
+    let index = staging.index();
+    let mut out = String::new();
+    for entry in index.entries() {
+        writeln!(
+            out,
+            "{:06o} {} {}\t{}",
+            entry.mode.bits(),
+            entry.id,
+            entry.stage_raw(),
+            entry.path(index)
+        )
+        .expect("writing to a string works");
+    }
+    out
+}
+
+fn expected(tmp: &TempDir, scenario: &str) -> crate::Result<String> {
+    Ok(std::fs::read_to_string(tmp.path().join(format!("{scenario}.index")))?)
+}
+
+fn paths(paths: &[&str]) -> Vec<BString> {
+    paths.iter().map(|p| (*p).into()).collect()
+}
+
+mod add {
+    use gix::staging::add;
+
+    use super::{expected, ls_files, paths, writable_repo};
+
+    #[test]
+    fn all() -> crate::Result {
+        let (repo, tmp) = writable_repo()?;
+        let mut staging = repo.staging()?;
+        let out = staging.add(None::<&str>, add::Options::default())?;
+        assert_eq!(ls_files(&staging), expected(&tmp, "add-all")?);
+        assert_eq!(
+            out,
+            add::Outcome {
+                added: paths(&["dir/new", "new"]),
+                modified: paths(&["a", "exe"]),
+                removed: paths(&["deleted"]),
+                ignored: Vec::new(),
+            }
+        );
+
+        staging.write()?;
+        let index = repo.open_index()?;
+        assert_eq!(index.entries().len(), 8, "the index can be written and read back");
+        assert!(index.entry_by_path("dir/new".into()).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn tracked_only() -> crate::Result {
+        let (repo, tmp) = writable_repo()?;
+        let mut staging = repo.staging()?;
+        let out = staging.add(
+            None::<&str>,
+            add::Options {
+                mode: add::Mode::Tracked,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(ls_files(&staging), expected(&tmp, "add-tracked")?);
+        assert!(out.added.is_empty());
+        assert_eq!(out.removed, paths(&["deleted"]));
+        Ok(())
+    }
+
+    #[test]
+    fn directory() -> crate::Result {
+        let (repo, tmp) = writable_repo()?;
+        let mut staging = repo.staging()?;
+        let out = staging.add(Some("dir"), add::Options::default())?;
+        assert_eq!(ls_files(&staging), expected(&tmp, "add-dir")?);
+        assert_eq!(out.added, paths(&["dir/new"]));
+        assert!(out.modified.is_empty() && out.removed.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn ignored_files_need_force() -> crate::Result {
+        let (repo, tmp) = writable_repo()?;
+        let mut staging = repo.staging()?;
+        let out = staging.add(["x.o", "ignored-dir"], add::Options::default())?;
+        assert_eq!(
+            out.ignored,
+            paths(&["ignored-dir", "x.o"]),
+            "explicitly named ignored paths are reported"
+        );
+        assert!(out.added.is_empty());
+
+        let out = staging.add(
+            ["x.o", "ignored-dir"],
+            add::Options {
+                force: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(ls_files(&staging), expected(&tmp, "add-force")?);
+        assert_eq!(out.added, paths(&["ignored-dir/file", "x.o"]));
+        assert!(out.ignored.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn unmatched_pathspecs_are_an_error() -> crate::Result {
+        let (repo, _tmp) = writable_repo()?;
+        let mut staging = repo.staging()?;
+        let before = ls_files(&staging);
+        let err = staging
+            .add(["new", "does-not-exist"], add::Options::default())
+            .unwrap_err();
+        assert_eq!(err.to_string(), "pathspec 'does-not-exist' did not match any files");
+        assert_eq!(ls_files(&staging), before, "nothing was changed");
+        Ok(())
+    }
+
+    #[test]
+    fn unchanged_files_are_not_reported() -> crate::Result {
+        let (repo, _tmp) = writable_repo()?;
+        let mut staging = repo.staging()?;
+        staging.add(None::<&str>, add::Options::default())?;
+        let out = staging.add(None::<&str>, add::Options::default())?;
+        assert_eq!(out, add::Outcome::default());
+        Ok(())
+    }
+}
+
+mod remove {
+    use gix::staging::remove;
+
+    use super::{expected, ls_files, paths, writable_repo, ByteSlice};
+
+    #[test]
+    fn cached() -> crate::Result {
+        let (repo, tmp) = writable_repo()?;
+        let mut staging = repo.staging()?;
+        let out = staging.remove(
+            Some("a"),
+            remove::Options {
+                cached: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(out.removed, paths(&["a"]));
+        assert_eq!(ls_files(&staging), expected(&tmp, "rm-cached")?);
+        assert!(
+            repo.workdir().expect("non-bare").join("a").is_file(),
+            "the file is kept"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn recursive() -> crate::Result {
+        let (repo, tmp) = writable_repo()?;
+        let mut staging = repo.staging()?;
+        let err = staging.remove(Some("dir"), remove::Options::default()).unwrap_err();
+        assert!(matches!(err, remove::Error::NotRecursive { pattern } if pattern == "dir"));
+
+        let out = staging.remove(
+            Some("dir"),
+            remove::Options {
+                recursive: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(out.removed, paths(&["dir/b", "dir/sub/c"]));
+        assert_eq!(ls_files(&staging), expected(&tmp, "rm-recursive")?);
+        let workdir = repo.workdir().expect("non-bare");
+        assert!(!workdir.join("dir/sub").exists(), "empty directories are removed");
+        assert!(
+            workdir.join("dir/new").is_file(),
+            "untracked files are kept, along with their directory"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn changes_are_protected_unless_forced() -> crate::Result {
+        let (repo, _tmp) = writable_repo()?;
+        let mut staging = repo.staging()?;
+        let err = staging.remove(Some("a"), remove::Options::default()).unwrap_err();
+        assert!(matches!(err, remove::Error::LocalModifications { path } if path == "a"));
+        let err = staging.remove(Some("staged"), remove::Options::default()).unwrap_err();
+        assert!(matches!(err, remove::Error::StagedChanges { path } if path == "staged"));
+
+        std::fs::write(repo.workdir().expect("non-bare").join("staged"), "changed again")?;
+        for cached in [false, true] {
+            let err = staging
+                .remove(
+                    Some("staged"),
+                    remove::Options {
+                        cached,
+                        ..Default::default()
+                    },
+                )
+                .unwrap_err();
+            assert!(
+                matches!(err, remove::Error::StagedContentDiffersFromFileAndHead { ref path } if path == "staged"),
+                "{err:?}"
+            );
+        }
+
+        let out = staging.remove(
+            ["a", "staged"],
+            remove::Options {
+                force: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(out.removed, paths(&["a", "staged"]));
+        assert!(staging.index().entry_by_path("a".as_bytes().as_bstr()).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn unmatched_pathspecs_are_an_error() -> crate::Result {
+        let (repo, _tmp) = writable_repo()?;
+        let mut staging = repo.staging()?;
+        let err = staging.remove(Some("new"), remove::Options::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "pathspec 'new' did not match any files",
+            "untracked files can't be removed"
+        );
+        Ok(())
+    }
+}
+
+mod rename {
+    use gix::staging::rename;
+
+    use super::{expected, ls_files, writable_repo};
+
+    #[test]
+    fn directory() -> crate::Result {
+        let (repo, tmp) = writable_repo()?;
+        let mut staging = repo.staging()?;
+        let out = staging.rename("dir".into(), "moved".into(), rename::Options::default())?;
+        assert_eq!(
+            out.renamed,
+            [("dir/b", "moved/b"), ("dir/sub/c", "moved/sub/c")]
+                .into_iter()
+                .map(|(from, to)| (from.into(), to.into()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(ls_files(&staging), expected(&tmp, "mv-dir")?);
+        let workdir = repo.workdir().expect("non-bare");
+        assert!(workdir.join("moved/sub/c").is_file());
+        assert!(
+            workdir.join("moved/new").is_file(),
+            "untracked files are moved along with their directory"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn into_existing_directory() -> crate::Result {
+        let (repo, tmp) = writable_repo()?;
+        let mut staging = repo.staging()?;
+        staging.rename("exe".into(), "dir".into(), rename::Options::default())?;
+        assert_eq!(ls_files(&staging), expected(&tmp, "mv-into-dir")?);
+        assert!(repo.workdir().expect("non-bare").join("dir/exe").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn errors() -> crate::Result {
+        let (repo, _tmp) = writable_repo()?;
+        let mut staging = repo.staging()?;
+        let err = staging
+            .rename("new".into(), "renamed".into(), rename::Options::default())
+            .unwrap_err();
+        assert!(matches!(err, rename::Error::SourceNotTracked { .. }));
+
+        let err = staging
+            .rename("a".into(), "dir/b".into(), rename::Options::default())
+            .unwrap_err();
+        assert!(matches!(err, rename::Error::DestinationExists { .. }));
+
+        let err = staging
+            .rename("dir".into(), "dir/sub".into(), rename::Options::default())
+            .unwrap_err();
+        assert!(matches!(err, rename::Error::IntoItself { .. }));
+
+        let err = staging
+            .rename("a".into(), "missing/a".into(), rename::Options::default())
+            .unwrap_err();
+        assert!(matches!(err, rename::Error::DestinationDirectoryMissing { .. }));
+
+        let out = staging.rename("a".into(), "dir/b".into(), rename::Options { force: true })?;
+        assert_eq!(out.renamed, [("a".into(), "dir/b".into())]);
+        let index = staging.index();
+        assert!(index.entry_by_path("a".into()).is_none());
+        assert_eq!(
+            index.entries().len(),
+            6,
+            "the destination entry was replaced, instead of having been added"
+        );
+        Ok(())
+    }
+}
+
+mod write {
+    use gix::config::tree::{Feature, Index, Key};
+    use gix::staging::add;
+    use gix_testtools::tempfile::TempDir;
+
+    fn writable_repo_with_config(overrides: &[String]) -> crate::Result<(gix::Repository, TempDir)> {
+        let tmp = gix_testtools::scripted_fixture_writable("make_staging_repo.sh")?;
+        let repo = gix::open_opts(
+            tmp.path().join("repo"),
+            crate::restricted().config_overrides(overrides.iter().cloned()),
+        )?;
+        Ok((repo, tmp))
+    }
+
+    #[test]
+    fn existing_indices_retain_their_version() -> crate::Result {
+        let (repo, _tmp) = writable_repo_with_config(&[Index::VERSION.validated_assignment_fmt(&2)?.to_string()])?;
+        let mut index = repo.open_index()?;
+        index.write(gix::index::write::Options {
+            version: Some(gix::index::Version::V4),
+            ..Default::default()
+        })?;
+
+        let mut staging = repo.staging()?;
+        staging.add(None::<&str>, add::Options::default())?;
+        staging.write()?;
+        let index = repo.open_index()?;
+        assert_eq!(
+            index.version(),
+            gix::index::Version::V4,
+            "like in Git, `index.version` only affects new indices"
+        );
+        assert_eq!(index.entries().len(), 8);
+        Ok(())
+    }
+
+    #[test]
+    fn new_indices_use_the_configured_version() -> crate::Result {
+        for (overrides, expected) in [
+            (Vec::new(), gix::index::Version::V2),
+            (
+                vec![Feature::MANY_FILES.validated_assignment_fmt(&true)?.to_string()],
+                gix::index::Version::V4,
+            ),
+            (
+                vec![
+                    Feature::MANY_FILES.validated_assignment_fmt(&true)?.to_string(),
+                    Index::VERSION.validated_assignment_fmt(&3)?.to_string(),
+                ],
+                gix::index::Version::V2,
+            ),
+        ] {
+            let (repo, _tmp) = writable_repo_with_config(&overrides)?;
+            std::fs::remove_file(repo.index_path())?;
+
+            let mut staging = repo.staging()?;
+            staging.add(None::<&str>, add::Options::default())?;
+            staging.write()?;
+            let index = repo.open_index()?;
+            assert_eq!(
+                index.version(),
+                expected,
+                "{overrides:?}: V3 is only used if needed, and `index.version` has precedence"
+            );
+            assert_eq!(index.entries().len(), 8, "{overrides:?}");
+        }
+        Ok(())
+    }
+}
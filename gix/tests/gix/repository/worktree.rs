@@ -343,3 +343,209 @@ fn run_assertions(main_repo: gix::Repository, should_be_bare: bool) {
         );
     }
 }
+
+#[cfg(all(feature = "worktree-mutation", feature = "status"))]
+mod management {
+    use std::{path::Path, process::Command, sync::atomic::AtomicBool};
+
+    use gix::{
+        bstr::ByteSlice,
+        worktree::{add, prune},
+    };
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .env_remove("GIT_DIR")
+            .output()
+            .expect("git can be executed");
+        assert!(
+            output.status.success(),
+            "git {args:?} succeeds: {:?}",
+            output.stderr.as_bstr()
+        );
+        String::from_utf8(output.stdout).expect("valid UTF-8")
+    }
+
+    fn listed_worktrees(repo: &gix::Repository) -> Vec<String> {
+        git(repo.workdir().expect("non-bare"), &["worktree", "list", "--porcelain"])
+            .lines()
+            .filter_map(|line| line.strip_prefix("worktree "))
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+
+    fn add(repo: &gix::Repository, path: &Path, head: add::Head) -> crate::Result<gix::Repository> {
+        Ok(repo.worktree_add(path, head, Default::default(), &AtomicBool::default())?)
+    }
+
+    #[test]
+    fn add_with_new_branch_and_detached_head() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_packed_and_loose.sh")?;
+        let dir = gix_testtools::tempfile::TempDir::new()?;
+        let head_id = repo.head_id()?.detach();
+
+        let path = dir.path().join("topic");
+        let wt = add(
+            &repo,
+            &path,
+            add::Head::NewBranch {
+                name: "refs/heads/topic".try_into()?,
+                start: head_id,
+            },
+        )?;
+        assert_eq!(wt.head_name()?.expect("not detached").as_bstr(), "refs/heads/topic");
+        assert_eq!(wt.head_id()?, head_id);
+        assert_eq!(std::fs::read(path.join("that"))?, b"hello\n", "files are checked out");
+        assert_eq!(
+            git(&path, &["status", "--porcelain"]),
+            "",
+            "git agrees that nothing changed"
+        );
+
+        let nested = dir.path().join("other").join("topic");
+        let wt = add(&repo, &nested, add::Head::Detached(head_id))?;
+        assert_eq!(
+            wt.git_dir().file_name().expect("present"),
+            "topic1",
+            "ids are made unique"
+        );
+        assert!(wt.head()?.is_detached());
+        assert_eq!(git(&nested, &["status", "--porcelain"]), "");
+
+        let worktrees = repo.worktrees()?;
+        assert_eq!(worktrees.len(), 2);
+        let mut listed = listed_worktrees(&repo).split_off(1);
+        listed.sort();
+        assert_eq!(
+            listed,
+            [
+                gix_path::realpath(&nested)?.to_str().expect("valid UTF-8"),
+                gix_path::realpath(&path)?.to_str().expect("valid UTF-8")
+            ],
+            "git sees both worktrees"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn add_refuses_branches_that_are_checked_out_and_non_empty_locations() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_packed_and_loose.sh")?;
+        let dir = gix_testtools::tempfile::TempDir::new()?;
+
+        let err = add(
+            &repo,
+            &dir.path().join("a"),
+            add::Head::Branch("refs/heads/main".try_into()?),
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Branch 'refs/heads/main' is already checked out at"),
+            "{err}"
+        );
+
+        std::fs::write(dir.path().join("file"), b"content")?;
+        let err = add(&repo, dir.path(), add::Head::Detached(repo.head_id()?.detach())).unwrap_err();
+        assert!(err.to_string().contains("is not an empty directory"), "{err}");
+        assert_eq!(repo.worktrees()?.len(), 0, "nothing was left behind");
+        assert!(!repo.common_dir().join("worktrees").join("a").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn lock_move_and_remove() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_packed_and_loose.sh")?;
+        let dir = gix_testtools::tempfile::TempDir::new()?;
+        let path = dir.path().join("wt");
+        add(&repo, &path, add::Head::Detached(repo.head_id()?.detach()))?;
+
+        let proxy = repo.worktrees()?.pop().expect("one worktree");
+        proxy.lock(Some("on a USB stick".into()))?;
+        assert!(proxy.lock(None).is_err(), "can't lock twice");
+        assert_eq!(proxy.lock_reason().expect("locked"), "on a USB stick");
+
+        let new_path = dir.path().join("moved");
+        assert!(proxy.move_to(&new_path).is_err(), "locked worktrees can't be moved");
+        proxy.unlock()?;
+        assert!(proxy.unlock().is_err(), "can't unlock twice");
+
+        assert!(
+            proxy.move_to(&path).is_err(),
+            "the destination must not exist, even if it's the worktree itself"
+        );
+        proxy.move_to(&new_path)?;
+        assert!(!path.exists());
+        assert_eq!(proxy.base()?, gix_path::realpath(&new_path)?);
+        assert_eq!(git(&new_path, &["status", "--porcelain"]), "", "git can still use it");
+
+        std::fs::write(new_path.join("untracked"), b"content")?;
+        let err = proxy.remove(Default::default()).unwrap_err();
+        assert!(err.to_string().contains("has modified or untracked files"), "{err}");
+
+        let proxy = repo.worktrees()?.pop().expect("still present");
+        proxy.remove(gix::worktree::proxy::remove::Options { force: true })?;
+        assert!(!new_path.exists());
+        assert_eq!(repo.worktrees()?.len(), 0);
+        assert_eq!(listed_worktrees(&repo).len(), 1, "only the main worktree is left");
+        Ok(())
+    }
+
+    #[test]
+    fn prune_and_repair() -> crate::Result {
+        let (repo, _tmp) = crate::repo_rw("make_packed_and_loose.sh")?;
+        let dir = gix_testtools::tempfile::TempDir::new()?;
+        let head_id = repo.head_id()?.detach();
+        for name in ["deleted", "locked", "moved"] {
+            add(&repo, &dir.path().join(name), add::Head::Detached(head_id))?;
+        }
+        for proxy in repo.worktrees()? {
+            assert_eq!(proxy.prunable(), None, "all worktrees are valid");
+            if proxy.id() == "locked" {
+                proxy.lock(None)?;
+            }
+        }
+        std::fs::remove_dir_all(dir.path().join("deleted"))?;
+        std::fs::remove_dir_all(dir.path().join("locked"))?;
+        std::fs::rename(dir.path().join("moved"), dir.path().join("moved-away"))?;
+
+        let prunable: Vec<_> = repo.worktrees()?.iter().map(gix::worktree::Proxy::prunable).collect();
+        assert_eq!(
+            prunable,
+            [
+                Some(prune::Reason::LocationMissing),
+                None,
+                Some(prune::Reason::LocationMissing)
+            ],
+            "locked worktrees are never prunable"
+        );
+
+        let out = repo.repair_worktrees([dir.path().join("moved-away")])?;
+        assert_eq!(
+            out.dot_git_files.len(),
+            0,
+            "the .git file still points to the right place"
+        );
+        assert_eq!(out.gitdir_files.len(), 1, "the worktree is found again");
+        assert_eq!(git(&dir.path().join("moved-away"), &["status", "--porcelain"]), "");
+
+        let out = repo.prune_worktrees(Some(0))?;
+        assert_eq!(out.pruned.len(), 0, "the worktree was used after the expiry date");
+        let out = repo.prune_worktrees(None)?;
+        assert_eq!(out.pruned, [("deleted".into(), prune::Reason::LocationMissing)]);
+        assert_eq!(
+            repo.worktrees()?
+                .iter()
+                .map(|proxy| proxy.id().to_owned())
+                .collect::<Vec<_>>(),
+            ["locked", "moved"]
+        );
+        assert_eq!(
+            git(repo.workdir().expect("non-bare"), &["worktree", "prune", "--dry-run"]),
+            ""
+        );
+        Ok(())
+    }
+}
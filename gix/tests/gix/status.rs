@@ -167,9 +167,124 @@ mod into_iter {
     }
 }
 
+mod tree_index {
+    use gix::status::tree_index::TrackRenames;
+    use gix_diff::index::Change;
+
+    use crate::status::repo;
+
+    /// Return all changes between `HEAD^{tree}` and the index of `repo` like `git diff-index --cached -M HEAD` would.
+    fn diff_index_head(repo: &gix::Repository) -> crate::Result<Vec<String>> {
+        let mut out = Vec::new();
+        repo.tree_index_status(
+            &repo.head_tree_id()?,
+            &*repo.index()?,
+            None,
+            TrackRenames::Given(Default::default()),
+            |change, _, _| {
+                out.push(change.into_owned());
+                Ok::<_, std::convert::Infallible>(gix_diff::index::Action::Continue)
+            },
+        )?;
+        let mut out: Vec<_> = out.iter().map(summarize).collect();
+        out.sort();
+        Ok(out)
+    }
+
+    fn summarize(change: &Change) -> String {
+        match change {
+            Change::Addition { location, .. } => format!("A {location}"),
+            Change::Deletion { location, .. } => format!("D {location}"),
+            Change::Modification {
+                location,
+                previous_entry_mode,
+                entry_mode,
+                ..
+            } => format!(
+                "M {location} {:o} -> {:o}",
+                previous_entry_mode.bits(),
+                entry_mode.bits()
+            ),
+            Change::Rewrite {
+                source_location,
+                location,
+                copy,
+                ..
+            } => format!("{} {source_location} -> {location}", if *copy { "C" } else { "R" }),
+            Change::Unmerged { location, .. } => format!("U {location}"),
+        }
+    }
+
+    #[test]
+    fn conflicts_renames_and_mode_changes() -> crate::Result {
+        let repo = repo("conflict")?;
+        insta::assert_debug_snapshot!(diff_index_head(&repo)?, @r#"
+        [
+            "M mode-change 100644 -> 100755",
+            "R to-rename -> renamed",
+            "U conflicting",
+        ]
+        "#);
+
+        let mut status = repo.status(gix::progress::Discard)?.into_iter(None)?;
+        let items: Vec<_> = status.by_ref().filter_map(Result::ok).collect();
+        assert!(
+            items
+                .iter()
+                .all(|item| !matches!(item, gix::status::Item::TreeIndex(Change::Unmerged { .. }))),
+            "conflicts are only reported by the index-worktree part of the status"
+        );
+        assert_eq!(
+            items.iter().filter(|item| item.location() == "conflicting").count(),
+            1,
+            "the conflict is reported exactly once"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn sparse_index_is_expanded() -> crate::Result {
+        let repo = repo("sparse-index")?;
+        assert!(repo.index()?.is_sparse());
+        insta::assert_debug_snapshot!(diff_index_head(&repo)?, @r#"
+        [
+            "M included/a 100644 -> 100644",
+        ]
+        "#);
+        Ok(())
+    }
+
+    #[test]
+    fn index_to_index() -> crate::Result {
+        let repo = repo("conflict")?;
+        let head_index = repo.index_from_tree(&repo.head_tree_id()?)?;
+        let previous_index = repo.index_from_tree(&repo.rev_parse_single("@~1^{tree}")?)?;
+
+        let mut changes = Vec::new();
+        let rewrites = repo.index_index_status(
+            &previous_index,
+            &head_index,
+            None,
+            TrackRenames::Disabled,
+            |change, lhs, rhs| {
+                assert!(
+                    std::ptr::eq(lhs, &*previous_index) && std::ptr::eq(rhs, &*head_index),
+                    "indices that aren't sparse are passed as is"
+                );
+                changes.push(summarize(&change.into_owned()));
+                Ok::<_, std::convert::Infallible>(gix_diff::index::Action::Continue)
+            },
+        )?;
+        assert!(rewrites.is_none(), "rename tracking was disabled");
+        assert_eq!(changes, ["M conflicting 100644 -> 100644"]);
+        Ok(())
+    }
+}
+
 mod index_worktree {
     mod iter {
         use gix::status::index_worktree::Item;
+        use gix::status::plumbing::index_as_worktree::{Change, EntryStatus};
         use gix_testtools::size_ok;
         use pretty_assertions::assert_eq;
 
@@ -200,6 +315,109 @@ mod index_worktree {
             Ok(())
         }
 
+        #[test]
+        fn submodule_modification_without_preload_index() -> crate::Result {
+            let mut repo = submodule_repo("modified-untracked-and-submodule-head-changed-and-modified")?;
+            repo.config_snapshot_mut()
+                .set_value(&gix::config::tree::Core::PRELOAD_INDEX, "false")?;
+            let mut status = repo
+                .status(gix::progress::Discard)?
+                .index_worktree_options_mut(|opts| {
+                    opts.sorting =
+                        Some(gix::status::plumbing::index_as_worktree_with_renames::Sorting::ByPathCaseSensitive);
+                })
+                .into_index_worktree_iter(None)?;
+            let items: Vec<_> = status.by_ref().filter_map(Result::ok).collect();
+            assert_eq!(
+                items.len(),
+                3,
+                "the result is the same when checking entries on a single thread"
+            );
+            Ok(())
+        }
+
+        fn submodule_changes(name: &str) -> crate::Result<Vec<(String, gix::submodule::Status)>> {
+            let repo = submodule_repo(name)?;
+            let status = repo
+                .status(gix::progress::Discard)?
+                .index_worktree_options_mut(|opts| {
+                    opts.sorting =
+                        Some(gix::status::plumbing::index_as_worktree_with_renames::Sorting::ByPathCaseSensitive);
+                })
+                .into_index_worktree_iter(None)?;
+            let mut out = Vec::new();
+            for item in status {
+                if let Item::Modification {
+                    rela_path,
+                    status: EntryStatus::Change(Change::SubmoduleModification(status)),
+                    ..
+                } = item?
+                {
+                    out.push((rela_path.to_string(), status));
+                }
+            }
+            Ok(out)
+        }
+
+        #[test]
+        fn recursive_submodule_modification() -> crate::Result {
+            let changes = submodule_changes("recursive-modified")?;
+            assert_eq!(changes.len(), 1);
+            let (path, status) = &changes[0];
+            assert_eq!(path, "sm");
+            assert!(!status.is_head_changed());
+            assert!(
+                status.has_modified_content(),
+                "a modification in a nested submodule is modified content of its parent, like `git status` sees it"
+            );
+            assert!(!status.has_untracked_content());
+            Ok(())
+        }
+
+        #[test]
+        fn recursive_submodule_untracked() -> crate::Result {
+            let changes = submodule_changes("recursive-untracked")?;
+            assert_eq!(changes.len(), 1);
+            let (path, status) = &changes[0];
+            assert_eq!(path, "sm");
+            assert!(!status.is_head_changed());
+            assert!(!status.has_modified_content());
+            assert!(
+                status.has_untracked_content(),
+                "untracked files in nested submodules are propagated"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn inactive_submodules_are_ignored() -> crate::Result {
+            let changes = submodule_changes("modified-and-untracked-inactive")?;
+            assert_eq!(
+                changes.len(),
+                0,
+                "unlike git, we don't look at inactive submodules at all"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn diff_ignore_submodules_is_used_as_fallback() -> crate::Result {
+            let changes = submodule_changes("modified-and-untracked-diff-ignore-all")?;
+            assert_eq!(
+                changes.len(),
+                0,
+                "`diff.ignoreSubmodules=all` applies to all submodules"
+            );
+
+            let changes = submodule_changes("modified-and-untracked-diff-ignore-all-submodule-ignore-none")?;
+            assert_eq!(changes.len(), 1, "`submodule.m1.ignore` has precedence");
+            let (path, status) = &changes[0];
+            assert_eq!(path, "m1");
+            assert!(status.has_modified_content());
+            assert!(status.has_untracked_content());
+            Ok(())
+        }
+
         #[test]
         fn untracked_files_collapse_by_default() -> crate::Result {
             let repo = repo("untracked-only")?;
@@ -266,6 +484,146 @@ mod index_worktree {
             Ok(())
         }
 
+        #[test]
+        fn untracked_cache_is_created_written_and_used() -> crate::Result {
+            let tmp = gix_testtools::scripted_fixture_writable("make_status_repos.sh")?;
+            let open_repo = || -> crate::Result<gix::Repository> {
+                let mut repo = gix::open_opts(tmp.path().join("untracked-only"), gix::open::Options::isolated())?;
+                let mut config = repo.config_snapshot_mut();
+                config.set_value(&gix::config::tree::Core::UNTRACKED_CACHE, "true")?;
+                config.set_raw_value(&"gitoxide.core.useNsec", "true")?;
+                config.commit()?;
+                Ok(repo)
+            };
+            let run = |repo: &gix::Repository| -> crate::Result<(usize, gix::status::Outcome)> {
+                let mut status = repo.status(gix::progress::Discard)?.into_index_worktree_iter(None)?;
+                let num_items = status.by_ref().filter_map(Result::ok).count();
+                let outcome = status.into_outcome().expect("iteration done");
+                Ok((num_items, outcome))
+            };
+
+            let repo = open_repo()?;
+            assert!(
+                repo.index()?.untracked().is_none(),
+                "there is no untracked cache initially"
+            );
+            let (num_items, mut outcome) = run(&repo)?;
+            assert_eq!(num_items, 2, "'new/' and 'subdir/untracked'");
+            assert!(
+                outcome.index_worktree.untracked_cache.is_some(),
+                "the cache was created as configured"
+            );
+            assert!(outcome.has_changes(), "the new untracked cache wants to be written");
+            outcome.write_changes().expect("changes present")?;
+
+            let repo = open_repo()?;
+            let index = repo.index()?;
+            let cache = index.untracked().expect("cache was written");
+            assert!(cache.identifier().starts_with(b"Location "));
+            assert!(!cache.directories().is_empty());
+
+            let (second_num_items, outcome) = run(&repo)?;
+            assert_eq!(second_num_items, num_items, "the result is the same with the cache");
+            let dirwalk = outcome.index_worktree.dirwalk.expect("dirwalk was performed");
+            assert!(
+                dirwalk.untracked_cache_hits > 0,
+                "unchanged directories are served from the cache"
+            );
+            Ok(())
+        }
+
+        #[test]
+        #[cfg(unix)]
+        fn fs_monitor_hook_limits_modification_checks() -> crate::Result {
+            use std::os::unix::fs::PermissionsExt;
+
+            use gix::status::index_worktree::Item;
+
+            let tmp = gix_testtools::scripted_fixture_writable("make_status_repos.sh")?;
+            let workdir = tmp.path().join("untracked-only");
+            let hook = workdir.join(".git").join("fsmonitor-hook");
+            std::fs::write(
+                &hook,
+                "#!/bin/sh\ncase \"$2\" in\n  t1) printf 't2\\0this\\0' ;;\n  *) printf 't1\\0' ;;\nesac\n",
+            )?;
+            std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755))?;
+            let open_repo = || -> crate::Result<gix::Repository> {
+                let mut repo = gix::open_opts(&workdir, gix::open::Options::isolated())?;
+                repo.config_snapshot_mut()
+                    .set_value(&gix::config::tree::Core::FSMONITOR, hook.to_str().expect("valid UTF-8"))?;
+                Ok(repo)
+            };
+            let run = |repo: &gix::Repository| -> crate::Result<(Vec<Item>, gix::status::Outcome)> {
+                let mut status = repo
+                    .status(gix::progress::Discard)?
+                    .untracked_files(gix::status::UntrackedFiles::None)
+                    .into_index_worktree_iter(None)?;
+                let items = status.by_ref().filter_map(Result::ok).collect();
+                Ok((items, status.into_outcome().expect("iteration done")))
+            };
+            let fs_monitor_token = |repo: &gix::Repository| -> crate::Result<Option<gix::bstr::BString>> {
+                Ok(repo.index()?.fs_monitor().map(|fs_monitor| match fs_monitor.token() {
+                    gix::index::extension::fs_monitor::Token::V2 { token } => token.clone(),
+                    gix::index::extension::fs_monitor::Token::V1 { .. } => unreachable!("we only write V2"),
+                }))
+            };
+
+            let repo = open_repo()?;
+            assert_eq!(fs_monitor_token(&repo)?, None, "the extension doesn't exist initially");
+            let (items, mut outcome) = run(&repo)?;
+            assert_eq!(items, [], "nothing changed");
+            assert_eq!(
+                outcome
+                    .index_worktree
+                    .tracked_file_modification
+                    .entries_skipped_by_entry_flags,
+                0,
+                "without previous token, all entries are checked"
+            );
+            assert!(outcome.has_changes(), "the new token has to be written");
+            outcome.write_changes().expect("changes present")?;
+
+            let repo = open_repo()?;
+            assert_eq!(
+                fs_monitor_token(&repo)?.as_ref().map(|t| t.as_slice()),
+                Some(&b"t1"[..])
+            );
+            assert_eq!(
+                repo.index()?.fs_monitor().expect("present").dirty_entries(),
+                [] as [usize; 0],
+                "all entries were checked and found unchanged"
+            );
+
+            std::fs::write(
+                workdir.join("subdir").join("that"),
+                "changed without the monitor noticing",
+            )?;
+            std::fs::write(workdir.join("this"), "changed")?;
+            let (items, mut outcome) = run(&repo)?;
+            let paths: Vec<_> = items.iter().map(Item::rela_path).collect();
+            assert_eq!(paths, ["this"], "only the path reported by the hook is checked");
+            assert_eq!(
+                outcome
+                    .index_worktree
+                    .tracked_file_modification
+                    .entries_skipped_by_entry_flags,
+                1
+            );
+            outcome.write_changes().expect("changes present")?;
+
+            let repo = open_repo()?;
+            assert_eq!(
+                fs_monitor_token(&repo)?.as_ref().map(|t| t.as_slice()),
+                Some(&b"t2"[..])
+            );
+            assert_eq!(
+                repo.index()?.fs_monitor().expect("present").dirty_entries().len(),
+                1,
+                "the changed entry remains dirty"
+            );
+            Ok(())
+        }
+
         #[test]
         fn early_drop_for_is_dirty_emulation() -> crate::Result {
             let repo = submodule_repo("modified-untracked-and-submodule-head-changed-and-modified")?;
@@ -418,3 +418,394 @@ mod open {
         Ok(())
     }
 }
+
+mod management {
+    use std::{path::Path, process::Command};
+
+    use gix::bstr::ByteSlice;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .env_remove("GIT_DIR")
+            .output()
+            .expect("git can be executed");
+        assert!(
+            output.status.success(),
+            "git {args:?} succeeds: {:?}",
+            output.stderr.as_bstr()
+        );
+        String::from_utf8(output.stdout).expect("valid UTF-8")
+    }
+
+    fn writable(fixture: &str, name: &str) -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+        let tmp = gix_testtools::scripted_fixture_writable(fixture)?;
+        let repo = gix::open_opts(tmp.path().join(name), crate::restricted())?;
+        Ok((repo, tmp))
+    }
+
+    fn only_submodule(repo: &gix::Repository) -> crate::Result<gix::Submodule<'_>> {
+        Ok(repo
+            .submodules()?
+            .expect("modules present")
+            .next()
+            .expect("one submodule"))
+    }
+
+    fn workdir(repo: &gix::Repository) -> &Path {
+        repo.workdir().expect("non-bare")
+    }
+
+    #[test]
+    fn init_writes_resolved_url_once() -> crate::Result {
+        let (repo, tmp) = writable("make_submodule_crud.sh", "super-clone")?;
+        let sm = only_submodule(&repo)?;
+        assert!(!sm.state()?.superproject_configuration, "not yet initialized");
+
+        let url = sm.init()?.expect("newly initialized");
+        assert_eq!(
+            gix_path::realpath(gix_path::from_bstr(url.as_bstr()))?,
+            gix_path::realpath(tmp.path().join("module1"))?,
+            "relative urls are resolved against the working tree without default remote"
+        );
+        assert_eq!(git(workdir(&repo), &["config", "submodule.m1.url"]).trim_end(), url);
+        assert_eq!(
+            git(workdir(&repo), &["config", "submodule.m1.active"]).trim_end(),
+            "true"
+        );
+        assert_eq!(sm.init()?, None, "the configuration on disk is checked as well");
+
+        let repo = gix::open_opts(workdir(&repo), crate::restricted())?;
+        let sm = only_submodule(&repo)?;
+        assert!(sm.state()?.superproject_configuration);
+        assert!(sm.is_active()?);
+        assert_eq!(sm.init()?, None, "already initialized");
+
+        let outcome = sm.sync()?;
+        assert_eq!(outcome.url, url);
+        assert!(outcome.superproject_config);
+        assert_eq!(outcome.remote_name, None, "there is no submodule repository yet");
+        Ok(())
+    }
+
+    #[test]
+    fn malicious_names_are_rejected_before_touching_the_disk() -> crate::Result {
+        for name in ["../../hooks", "a/../../x", "/tmp/absolute"] {
+            let (repo, _tmp) = writable("make_submodule_crud.sh", "super-clone")?;
+            std::fs::write(
+                workdir(&repo).join(".gitmodules"),
+                format!("[submodule \"{name}\"]\n\tpath = m1\n\turl = ../module1\n"),
+            )?;
+            let config_before = std::fs::read(repo.git_dir().join("config"))?;
+            let repo = gix::open_opts(workdir(&repo), crate::restricted())?;
+            let sm = only_submodule(&repo)?;
+            assert_eq!(sm.name(), name);
+            assert!(sm.validated_name().is_err(), "{name}");
+
+            assert!(matches!(sm.init(), Err(gix::submodule::init::Error::InvalidName(_))));
+            assert!(matches!(sm.sync(), Err(gix::submodule::sync::Error::InvalidName(_))));
+            assert!(matches!(
+                sm.absorb_git_dir(),
+                Err(gix::submodule::absorb_git_dir::Error::InvalidName(_))
+            ));
+            #[cfg(feature = "status")]
+            assert!(matches!(
+                sm.deinit(Default::default()),
+                Err(gix::submodule::deinit::Error::InvalidName(_))
+            ));
+            #[cfg(all(
+                feature = "blocking-network-client",
+                feature = "worktree-mutation",
+                feature = "status"
+            ))]
+            assert!(matches!(
+                sm.update_worktree(
+                    gix::submodule::update::Options {
+                        init: true,
+                        ..Default::default()
+                    },
+                    &std::sync::atomic::AtomicBool::default()
+                ),
+                Err(gix::submodule::update::Error::InvalidName(_))
+            ));
+
+            assert_eq!(
+                std::fs::read(repo.git_dir().join("config"))?,
+                config_before,
+                "the configuration wasn't touched"
+            );
+            assert!(!repo.git_dir().join("modules").exists(), "nothing was cloned");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn absorb_git_dir_of_old_form() -> crate::Result {
+        let (repo, _tmp) = writable("make_submodules.sh", "old-form")?;
+        let sm = only_submodule(&repo)?;
+        assert!(sm.state()?.is_old_form);
+
+        assert!(sm.absorb_git_dir()?, "the repository was moved");
+        let state = sm.state()?;
+        assert!(!state.is_old_form);
+        assert!(state.repository_exists);
+        assert!(state.worktree_checkout, "a `.git` file links the worktree");
+        assert!(sm.work_dir()?.join(".git").is_file());
+
+        let sm_repo = sm.open()?.expect("still present");
+        assert_eq!(sm_repo.git_dir(), sm.git_dir());
+        assert_eq!(
+            sm_repo.workdir().map(gix_path::realpath).transpose()?,
+            Some(gix_path::realpath(sm.work_dir()?)?)
+        );
+        assert_eq!(git(&sm.work_dir()?, &["status", "--porcelain"]), "");
+        assert_eq!(
+            git(workdir(&repo), &["status", "--porcelain"]),
+            "A  .gitmodules\nA  dir/old-form\n",
+            "the submodule was only added, and git sees no changes to it"
+        );
+
+        assert!(!sm.absorb_git_dir()?, "nothing to do the second time");
+        Ok(())
+    }
+
+    #[cfg(all(
+        feature = "blocking-network-client",
+        feature = "worktree-mutation",
+        feature = "status"
+    ))]
+    mod update {
+        use std::sync::atomic::AtomicBool;
+
+        use gix::{
+            bstr::ByteSlice,
+            submodule::{deinit, update},
+        };
+
+        use super::{git, only_submodule, workdir, writable};
+
+        fn update(sm: &gix::Submodule<'_>, options: update::Options) -> crate::Result<Option<update::Outcome>> {
+            Ok(sm.update_worktree(options, &AtomicBool::default())?)
+        }
+
+        fn with_init() -> update::Options {
+            update::Options {
+                init: true,
+                ..Default::default()
+            }
+        }
+
+        fn reopen(repo: &gix::Repository) -> crate::Result<gix::Repository> {
+            Ok(gix::open_opts(workdir(repo), crate::restricted())?)
+        }
+
+        fn set_gitlink(repo: &gix::Repository, id: &str) -> crate::Result<gix::Repository> {
+            git(
+                workdir(repo),
+                &["update-index", "--cacheinfo", &format!("160000,{id},m1")],
+            );
+            reopen(repo)
+        }
+
+        fn commit_in(dir: &std::path::Path, file: &str, content: &str) -> String {
+            std::fs::write(dir.join(file), content).expect("can write");
+            git(dir, &["add", file]);
+            git(
+                dir,
+                &["-c", "user.name=a", "-c", "user.email=a@b", "commit", "-q", "-m", file],
+            );
+            git(dir, &["rev-parse", "HEAD"]).trim_end().to_owned()
+        }
+
+        #[test]
+        fn clone_checkout_fetch_deinit_and_checkout_again() -> crate::Result {
+            let (repo, tmp) = writable("make_submodule_crud.sh", "super-clone")?;
+            let sm = only_submodule(&repo)?;
+            assert_eq!(
+                update(&sm, Default::default())?,
+                None,
+                "uninitialized submodules are skipped"
+            );
+
+            let outcome = update(&sm, with_init())?.expect("updated");
+            let index_id = sm.index_id()?.expect("in index");
+            assert!(outcome.cloned);
+            assert_eq!(outcome.previous_head_id, None);
+            assert_eq!(outcome.head_id, index_id, "the superproject commit is checked out");
+            let sm_dir = sm.work_dir()?;
+            assert_eq!(std::fs::read(sm_dir.join("file"))?, b"one\n");
+            assert_eq!(std::fs::read(sm_dir.join("dir/nested"))?, b"two\n");
+            assert_eq!(git(&sm_dir, &["status", "--porcelain"]), "");
+            assert_eq!(git(workdir(&repo), &["status", "--porcelain"]), "");
+            assert!(
+                git(workdir(&repo), &["submodule", "status"]).starts_with(' '),
+                "git considers the submodule initialized and up to date"
+            );
+
+            let repo = reopen(&repo)?;
+            let sm = only_submodule(&repo)?;
+            let outcome = update(&sm, Default::default())?.expect("initialized now");
+            assert!(!outcome.cloned);
+            assert_eq!(outcome.previous_head_id, Some(index_id), "nothing to do");
+
+            let sync = sm.sync()?;
+            assert_eq!(
+                sync.remote_name.as_ref().map(|name| name.as_bstr()),
+                Some("origin".into())
+            );
+            assert_eq!(git(&sm_dir, &["config", "remote.origin.url"]).trim_end(), sync.url);
+
+            let new_id = commit_in(&tmp.path().join("module1"), "new", "new\n");
+            let repo = set_gitlink(&repo, &new_id)?;
+            let sm = only_submodule(&repo)?;
+            std::fs::write(sm_dir.join("file"), "changed\n")?;
+            assert!(
+                matches!(update(&sm, Default::default()), Err(err) if err.to_string().contains("modified files")),
+                "modifications are protected"
+            );
+            let outcome = update(
+                &sm,
+                update::Options {
+                    force: true,
+                    ..Default::default()
+                },
+            )?
+            .expect("updated");
+            assert_eq!(outcome.previous_head_id, Some(index_id));
+            assert_eq!(outcome.head_id.to_string(), new_id, "missing commits are fetched");
+            assert_eq!(std::fs::read(sm_dir.join("file"))?, b"one\nthree\n");
+            assert_eq!(std::fs::read(sm_dir.join("new"))?, b"new\n");
+            assert_eq!(git(&sm_dir, &["status", "--porcelain"]), "");
+
+            std::fs::write(sm_dir.join("file"), "changed\n")?;
+            assert!(
+                sm.deinit(Default::default()).is_err(),
+                "modifications prevent deinit unless forced"
+            );
+            sm.deinit(deinit::Options { force: true })?;
+            assert_eq!(
+                std::fs::read_dir(&sm_dir)?.count(),
+                0,
+                "the directory is kept, but empty"
+            );
+            let repo = reopen(&repo)?;
+            let sm = only_submodule(&repo)?;
+            let state = sm.state()?;
+            assert!(state.repository_exists);
+            assert!(!state.worktree_checkout);
+            assert!(!state.superproject_configuration);
+            assert_eq!(
+                git(workdir(&repo), &["status", "--porcelain"]),
+                "M  m1\n",
+                "deinitialized submodules are unchanged, only the staged gitlink is visible"
+            );
+
+            let outcome = update(&sm, with_init())?.expect("initialized again");
+            assert!(!outcome.cloned, "the repository is reused");
+            assert_eq!(
+                outcome.previous_head_id, None,
+                "the worktree was checked out from scratch"
+            );
+            assert_eq!(std::fs::read(sm_dir.join("new"))?, b"new\n");
+            assert_eq!(git(&sm_dir, &["status", "--porcelain"]), "");
+            assert_eq!(git(workdir(&repo), &["status", "--porcelain"]), "M  m1\n");
+            Ok(())
+        }
+
+        #[test]
+        #[cfg(feature = "merge")]
+        fn rebase_and_merge_local_commits() -> crate::Result {
+            use gix::submodule::config::Update;
+            for strategy in [Update::Rebase, Update::Merge] {
+                let (repo, _tmp) = writable("make_submodule_crud.sh", "super-clone")?;
+                let sm = only_submodule(&repo)?;
+                let c1 = update(&sm, with_init())?.expect("updated").head_id;
+                let sm_dir = sm.work_dir()?;
+                git(&sm_dir, &["checkout", "-q", "-b", "local"]);
+                let local = commit_in(&sm_dir, "local", "local\n");
+                let c2 = git(&sm_dir, &["rev-parse", "origin/main"]).trim_end().to_owned();
+
+                let repo = set_gitlink(&repo, &c2)?;
+                let sm = only_submodule(&repo)?;
+                let outcome = update(
+                    &sm,
+                    update::Options {
+                        strategy: Some(strategy.clone()),
+                        ..Default::default()
+                    },
+                )?
+                .expect("updated");
+                assert_eq!(outcome.strategy, strategy);
+                assert_eq!(outcome.previous_head_id.map(|id| id.to_string()), Some(local.clone()));
+                assert_eq!(
+                    git(&sm_dir, &["symbolic-ref", "HEAD"]).trim_end(),
+                    "refs/heads/local",
+                    "the branch is updated"
+                );
+                assert_eq!(
+                    git(&sm_dir, &["rev-parse", "local"]).trim_end(),
+                    outcome.head_id.to_string()
+                );
+                let parents = git(&sm_dir, &["log", "-1", "--format=%P"]);
+                match strategy {
+                    Update::Rebase => assert_eq!(parents.trim_end(), c2, "local commit was rebased onto c2"),
+                    _ => assert_eq!(
+                        parents.trim_end(),
+                        format!("{local} {c2}"),
+                        "a merge commit was created"
+                    ),
+                }
+                assert_ne!(outcome.head_id, c1);
+                assert_eq!(std::fs::read(sm_dir.join("file"))?, b"one\nthree\n");
+                assert_eq!(std::fs::read(sm_dir.join("local"))?, b"local\n");
+                assert_eq!(git(&sm_dir, &["status", "--porcelain"]), "");
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "blocking-network-client",
+        feature = "worktree-mutation",
+        feature = "dirwalk"
+    ))]
+    fn add() -> crate::Result {
+        let (repo, _tmp) = writable("make_submodule_crud.sh", "super-clone")?;
+        let sm_repo = repo.submodule_add(
+            "../module1",
+            "added",
+            Default::default(),
+            &std::sync::atomic::AtomicBool::default(),
+        )?;
+        let sm_dir = workdir(&repo).join("added");
+        assert_eq!(sm_repo.git_dir(), repo.git_dir().join("modules").join("added"));
+        assert_eq!(std::fs::read(sm_dir.join("file"))?, b"one\nthree\n");
+        assert_eq!(git(&sm_dir, &["status", "--porcelain"]), "");
+        assert_eq!(
+            git(workdir(&repo), &["config", "-f", ".gitmodules", "submodule.added.url"]).trim_end(),
+            "../module1",
+            "the url is written as given"
+        );
+        assert_eq!(
+            git(workdir(&repo), &["status", "--porcelain"]),
+            "M  .gitmodules\nA  added\n",
+            "both are staged"
+        );
+        assert!(git(workdir(&repo), &["submodule", "status", "added"]).starts_with(' '));
+
+        assert!(
+            repo.submodule_add(
+                "../module1",
+                "m1",
+                Default::default(),
+                &std::sync::atomic::AtomicBool::default()
+            )
+            .is_err(),
+            "existing submodules can't be added again"
+        );
+        Ok(())
+    }
+}